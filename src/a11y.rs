@@ -0,0 +1,95 @@
+//! Keyboard operation and control names for assistive technology.
+//!
+//! gpui 0.2 has no accessibility tree (no AccessKit bridge), so roles and
+//! names can't be handed to a screen reader directly. What the app can do,
+//! and what this module centralizes:
+//!
+//! - **Tab order.** Each area of the window is a gpui tab group with an
+//!   explicit index (`tab_order`), so Tab walks URL bar → method → Send →
+//!   editor tabs → editor rows → response → history → request tabs, whatever
+//!   the visual layout.
+//! - **Activation.** gpui turns Enter / Space on a focused element into a
+//!   click, so every control made a tab stop (`focusable`) works from the
+//!   keyboard without extra key handlers.
+//! - **Names.** Icon-only controls get a descriptive name ("Close tab GET
+//!   zen", "Delete header row 3") from the helpers below. It is shown as the
+//!   control's tooltip today and is the string to expose once gpui grows an
+//!   accessibility API.
+//!
+//! # Manual test script
+//!
+//! 1. Launch with a fresh profile, click the URL bar, then press Tab
+//!    repeatedly: focus visits the method selector, Send, the Send ×N /
+//!    route / code buttons, the Headers / Auth / Params / Body pills, the
+//!    rows of the open panel, the response Body / Headers / Request pills,
+//!    each History row and finally each request tab; Shift-Tab walks back.
+//! 2. On a Headers pill press Enter, on the Params pill press Space: each
+//!    opens its panel.
+//! 3. Add a custom header, Tab to its "×": the tooltip reads "Delete header
+//!    row N"; Enter deletes the row.
+//! 4. Open two tabs, Tab to a tab and press Enter: it is activated. Hover
+//!    its "×": "Close tab <METHOD> <title>" ("WS" for a WebSocket tab).
+//! 5. Tab to a History row and press Space: it opens in the editor.
+//! 6. Switch to split layout and repeat 1 — the order is unchanged.
+
+use gpui::{Div, InteractiveElement as _, Stateful, Styled as _};
+use gpui_component::Theme;
+
+/// Tab-group index of each window area; lower comes first.
+pub mod tab_order {
+    pub const URL_BAR: isize = 1;
+    pub const METHOD: isize = 2;
+    pub const SEND: isize = 3;
+    /// Route selector, code snippet and Send ×N, after the main trio.
+    pub const URL_BAR_EXTRAS: isize = 4;
+    pub const EDITOR_TABS: isize = 5;
+    pub const EDITOR: isize = 6;
+    pub const RESPONSE: isize = 7;
+    pub const HISTORY: isize = 8;
+    pub const TAB_BAR: isize = 9;
+}
+
+/// Make `element` the tab group at `index`. Only for plain (id-less) `Div`s:
+/// those take part in ordering without becoming a focus target themselves.
+pub fn tab_group(element: Div, index: isize) -> Div {
+    element.tab_index(index).tab_stop(false).tab_group()
+}
+
+/// Make a clickable element a tab stop with a visible focus state.
+pub fn focusable(element: Stateful<Div>, theme: &Theme) -> Stateful<Div> {
+    let ring = theme.ring;
+    element.tab_index(0).focus(move |s| s.bg(ring.opacity(0.15)))
+}
+
+/// `badge` is what the strip shows before the title (`RequestTab::badge`).
+pub fn close_tab(badge: &str, title: &str) -> String {
+    format!("Close tab {badge} {title}")
+}
+
+/// `kind` is "header" or "param"; `index` is 0-based.
+pub fn delete_row(kind: &str, index: usize) -> String {
+    format!("Delete {kind} row {}", index + 1)
+}
+
+pub fn row_actions(kind: &str, index: usize) -> String {
+    format!("More actions for {kind} row {}", index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_read_as_sentences_with_one_based_rows() {
+        assert_eq!(close_tab("GET", "zen"), "Close tab GET zen");
+        assert_eq!(delete_row("header", 2), "Delete header row 3");
+        assert_eq!(row_actions("param", 0), "More actions for param row 1");
+    }
+
+    #[test]
+    fn areas_are_ordered_url_method_send_first() {
+        use tab_order::*;
+        let order = [URL_BAR, METHOD, SEND, URL_BAR_EXTRAS, EDITOR_TABS, EDITOR, RESPONSE, HISTORY, TAB_BAR];
+        assert!(order.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -0,0 +1,223 @@
+//! Optional "activity webhook": after each completed send, POST a small JSON
+//! event to a URL the user configured (Edit → Activity Webhook…), so poopman
+//! activity can feed external dashboards.
+//!
+//! Delivery is fire-and-forget on the shared `HttpClient`; a failed delivery
+//! is logged and never surfaces in the UI.
+//!
+//! # Payload, version 1
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "event": "request.completed",
+//!   "request_id": 42,
+//!   "method": "GET",
+//!   "host": "api.example.com:8443",
+//!   "path": "/users/7",
+//!   "status": 200,
+//!   "duration_ms": 118
+//! }
+//! ```
+//!
+//! - `request_id`: the History row the send was recorded as; `null` when
+//!   writing History failed.
+//! - `host`: host and, when not the scheme default, port.
+//! - `status`: `null` when no response arrived (network error).
+//!
+//! Only these fields are sent. The query string, userinfo, fragment, headers
+//! and bodies are never included — they are where tokens and passwords live.
+//! Adding a field is backwards compatible; removing or changing one bumps
+//! `SCHEMA_VERSION`.
+
+use serde::Serialize;
+
+use crate::http_client::HttpClient;
+use crate::types::{BodyType, HttpMethod, RawSubtype, RequestData, ResponseData};
+
+/// Version of the payload documented above.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActivityEvent {
+    pub version: u32,
+    pub event: &'static str,
+    pub request_id: Option<i64>,
+    pub method: String,
+    pub host: String,
+    pub path: String,
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+}
+
+impl ActivityEvent {
+    pub fn completed(request_id: Option<i64>, request: &RequestData, response: &ResponseData) -> Self {
+        let (host, path) = match url::Url::parse(&request.url) {
+            Ok(url) => {
+                let host = match (url.host_str(), url.port()) {
+                    (Some(host), Some(port)) => format!("{host}:{port}"),
+                    (Some(host), None) => host.to_string(),
+                    (None, _) => String::new(),
+                };
+                (host, url.path().to_string())
+            }
+            Err(_) => (String::new(), String::new()),
+        };
+        Self {
+            version: SCHEMA_VERSION,
+            event: "request.completed",
+            request_id,
+            method: request.method.to_string(),
+            host,
+            path,
+            status: response.status,
+            duration_ms: response.duration_ms,
+        }
+    }
+}
+
+/// Whether `url` can be used as the webhook target: an absolute http(s) URL.
+pub fn is_usable_webhook_url(url: &str) -> bool {
+    url::Url::parse(url)
+        .is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.host_str().is_some())
+}
+
+/// POST `event` to `url` and log the outcome. Meant to be spawned and
+/// detached; it never reports back.
+pub async fn deliver(url: String, event: ActivityEvent) {
+    let body = match serde_json::to_string(&event) {
+        Ok(body) => body,
+        Err(e) => {
+            log::warn!("Activity webhook: failed to serialize event: {}", e);
+            return;
+        }
+    };
+    let inflight = HttpClient::new().start_send(
+        HttpMethod::POST,
+        url.clone(),
+        vec![("Content-Type".to_string(), "application/json".to_string())],
+        BodyType::Raw { content: body, subtype: RawSubtype::Json },
+    );
+    match inflight.wait().await {
+        Ok(response) if (200..300).contains(&response.status) => {}
+        Ok(response) => log::warn!("Activity webhook {} answered {}", url, response.status),
+        Err(e) => log::warn!("Activity webhook {} failed: {}", url, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SendRoute;
+    use std::io::{Read as _, Write as _};
+
+    fn request(url: &str) -> RequestData {
+        RequestData {
+            headers: vec![("Authorization".to_string(), "Bearer s3cret".to_string())],
+            body: BodyType::Raw { content: "{\"password\":\"hunter2\"}".to_string(), subtype: RawSubtype::Json },
+            ..RequestData::new(HttpMethod::PUT, url.to_string())
+        }
+    }
+
+    fn response(status: Option<u16>) -> ResponseData {
+        ResponseData {
+            status,
+            duration_ms: 37,
+            headers: vec![],
+            body: b"token=abc".to_vec(),
+            is_text: true,
+            route: SendRoute::default(),
+            raw_header_values: vec![],
+            network_profile: Default::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
+        }
+    }
+
+    #[test]
+    fn event_keeps_host_and_path_only() {
+        let event = ActivityEvent::completed(
+            Some(9),
+            &request("https://user:pw@api.test:8443/v1/items?api_key=s3cret#frag"),
+            &response(Some(201)),
+        );
+        assert_eq!(event.host, "api.test:8443");
+        assert_eq!(event.path, "/v1/items");
+        assert_eq!(event.status, Some(201));
+
+        let failed = ActivityEvent::completed(None, &request("{{base}}/x"), &response(None));
+        assert_eq!((failed.host.as_str(), failed.path.as_str(), failed.status), ("", "", None));
+    }
+
+    #[test]
+    fn only_absolute_http_urls_are_usable() {
+        assert!(is_usable_webhook_url("http://127.0.0.1:9000/hook"));
+        assert!(is_usable_webhook_url("https://dash.local/in"));
+        assert!(!is_usable_webhook_url("127.0.0.1:9000"));
+        assert!(!is_usable_webhook_url("ftp://host/x"));
+    }
+
+    #[test]
+    fn delivers_the_versioned_payload_to_a_local_listener() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let received = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut data = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Read until the whole body announced by Content-Length is in.
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                data.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&data).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if body.len() >= length || n == 0 {
+                        stream
+                            .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                            .unwrap();
+                        return (head.lines().next().unwrap_or_default().to_string(), body.to_string());
+                    }
+                }
+            }
+        });
+
+        let event = ActivityEvent::completed(
+            Some(42),
+            &request("https://api.test/v1/items?token=s3cret"),
+            &response(Some(200)),
+        );
+        futures::executor::block_on(deliver(url, event));
+
+        let (request_line, body) = received.join().unwrap();
+        assert_eq!(request_line, "POST /hook HTTP/1.1");
+        let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "version": 1,
+                "event": "request.completed",
+                "request_id": 42,
+                "method": "PUT",
+                "host": "api.test",
+                "path": "/v1/items",
+                "status": 200,
+                "duration_ms": 37,
+            })
+        );
+        for secret in ["s3cret", "hunter2", "token", "Bearer"] {
+            assert!(!body.contains(secret), "payload leaked {secret}: {body}");
+        }
+    }
+}
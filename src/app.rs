@@ -1,24 +1,68 @@
+use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::{
-    h_flex, v_flex, ActiveTheme as _, Root, TitleBar, WindowExt,
+    button::{Button, ButtonVariant, ButtonVariants as _},
+    checkbox::Checkbox,
+    dialog::DialogButtonProps,
+    input::{Input, InputState},
+    select::{Select, SelectState},
+    h_flex, v_flex, ActiveTheme as _, Disableable as _, IndexPath, Root, Sizable as _, TitleBar, WindowExt,
     resizable::{h_resizable, resizable_panel, v_resizable},
 };
 use gpui::px;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::code_snippet_panel::CodeSnippetPanel;
+use crate::collections_panel::{
+    CollectionDeleteRequested, CollectionExportRequested, CollectionRunRequested, CollectionsPanel, NewCollectionRequested,
+    SavedRequestClicked,
+};
 use crate::db::Database;
+use crate::env_overrides::EnvOverrides;
 use crate::environment_manager::{EnvironmentManager, EnvironmentsChanged};
-use crate::history_panel::{HistoryItemClicked, HistoryPanel};
-use crate::request_editor::{OpenCodeSnippet, RequestCancelled, RequestCompleted, RequestEditor};
+use crate::history_panel::{
+    HistoryDiffRequested, HistoryDuplicateRequested, HistoryExportRequested, HistoryFileExportRequested,
+    HistoryItemClicked, HistoryPanel, HistoryPinChanged, HistoryResent,
+};
+use crate::request_editor::{
+    ConfigureHostOverrides, ConfigureNetworkProfile, ConfigureProxy, ConfigureUserAgent, EditPreset, EnvOverrideEdited, EnvVarEdited, OpenCodeSnippet, RepeatCompleted,
+    DefaultHeadersChanged, RequestCancelled, RequestCompleted, RequestEditor, SaveRequested, SplitLayoutChanged, UrlEdited,
+};
+use crate::request_export::ExportFormat;
 use crate::request_tab::RequestTab;
-use crate::response_viewer::ResponseViewer;
-use crate::tab_bar::{NewTabClicked, TabBar, TabClicked, TabCloseClicked};
+use crate::response_viewer::{FollowPageLink, ResponseViewer, RetryCommand};
+use crate::retry_after::{retry_delay, PendingRetry, MAX_AUTO_RETRIES};
+use crate::runner_panel::RunnerPanel;
+use crate::start_panel::{remember_closed, StartItemPicked, StartPanel, StartPick};
+use crate::tab_bar::{
+    NewTabClicked, NewWebSocketTabClicked, TabBar, TabClicked, TabCloseClicked, TabColorPicked, TabCompareClicked, TabDuplicateClicked,
+    TabMoved, TabPinToggled, TabRenamed, TabsCloseOthers, TabsCloseRight,
+};
+use crate::workspace_bundle::{ImportMode, SecretPolicy, WorkspaceBundle};
+use crate::ws_panel::WsPanel;
 use crate::theme::{
     REQUEST_INITIAL_HEIGHT, REQUEST_MAX, REQUEST_MIN, SIDEBAR_MAX, SIDEBAR_MIN, SIDEBAR_WIDTH,
 };
 
-actions!(poopman, [SendRequest, NewTab, CloseTab, NextTab, PrevTab, FocusUrl, Quit]);
+actions!(
+    poopman,
+    [
+        SendRequest, SaveRequest, NewTab, CloseTab, NextTab, PrevTab, MoveTabLeft, MoveTabRight, FocusUrl,
+        OpenMethodMenu, NextMethod, PrevMethod, UndoBodyAction, Quit
+    ]
+);
+
+/// Ctrl+1..9: make the tab at `index` active.
+#[derive(Clone, Action, PartialEq, Eq, serde::Deserialize)]
+#[action(namespace = poopman, no_json)]
+pub struct ActivateTab {
+    pub index: usize,
+}
+
+/// How long edits must pause before the active tab's dirty dot is re-checked.
+const DIRTY_CHECK_DELAY: std::time::Duration = std::time::Duration::from_millis(300);
 
 /// Main application view
 pub struct PoopmanApp {
@@ -37,6 +81,9 @@ pub struct PoopmanApp {
     focus_handle: FocusHandle,
     db: Arc<Database>,
     history_panel: Entity<HistoryPanel>,
+    collections_panel: Entity<CollectionsPanel>,
+    /// Which of the two panels the sidebar shows.
+    sidebar: Sidebar,
     request_editor: Entity<RequestEditor>,
     response_viewer: Entity<ResponseViewer>,
     tab_bar: Entity<TabBar>,
@@ -47,29 +94,132 @@ pub struct PoopmanApp {
     active_environment_id: Option<i64>,
     env_manager: Entity<EnvironmentManager>,
     code_panel: Entity<CodeSnippetPanel>,
+    /// Running `Retry-After` countdowns by tab id; dropping one cancels it.
+    retry_timers: HashMap<usize, Task<()>>,
+    /// Pending re-check of the active tab's dirty dot; replacing it cancels
+    /// the previous one.
+    dirty_check: Option<Task<()>>,
+    /// Activity webhook target; completed sends are reported only when set.
+    activity_webhook_url: Option<String>,
+    /// Runtime log level (see `request_log`).
+    log_level: log::LevelFilter,
+    /// Extra CA certificate file, mirrored into `http_client`.
+    ca_cert_path: Option<String>,
+    /// Response body size limit, mirrored into `http_client`.
+    body_limit: u64,
+    /// Bytes of each response body stored with history (0 = whole body).
+    history_body_cap: u64,
+    /// Whether request diffs ignore `request_diff::VOLATILE_HEADERS`.
+    diff_hide_volatile: bool,
+    /// Temporary variable overrides, resolved above the active environment.
+    env_overrides: EnvOverrides,
+    /// Whether `env_overrides` are saved for the next start.
+    keep_env_overrides: bool,
+    /// Shown instead of the response viewer while the active tab is blank.
+    start_panel: Entity<StartPanel>,
+    /// Closed tabs, newest first, for the start panel's "Recently closed".
+    recently_closed: Vec<RequestTab>,
+    /// Whether the URL bar is empty, as last reported by `UrlEdited`.
+    url_empty: bool,
+    /// The panels of WebSocket tabs by tab id, made when a tab is first
+    /// shown; each holds its tab's connection.
+    ws_panels: HashMap<usize, Entity<WsPanel>>,
     _subscriptions: Vec<Subscription>,
 }
 
-impl PoopmanApp {
-    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
-        // Initialize database
-        let db = Arc::new(Database::new().expect("Failed to initialize database"));
+/// The left sidebar's two views.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sidebar {
+    History,
+    Collections,
+}
+
+/// Settings and environments read from the database at startup. Loaded on a
+/// background thread (the database may still be opening) and applied when
+/// ready; until then the app runs on the defaults.
+#[derive(Debug, Default)]
+struct StartupState {
+    environments: Vec<crate::types::Environment>,
+    active_environment_id: Option<i64>,
+    globals: Vec<crate::types::EnvVar>,
+    proxy_url: Option<String>,
+    proxy_options: crate::http_client::ProxyOptions,
+    split_editor: bool,
+    /// `None` until picked: all of them.
+    default_headers: Option<Vec<crate::types::PredefinedHeader>>,
+    /// `None` until set: `types::DEFAULT_USER_AGENT`.
+    default_user_agent: Option<String>,
+    cookie_jar_enabled: bool,
+    activity_webhook_url: Option<String>,
+    log_level: Option<log::LevelFilter>,
+    ca_cert_path: Option<String>,
+    host_overrides: Vec<crate::types::HostOverride>,
+    body_limit: Option<u64>,
+    history_body_cap: Option<u64>,
+    /// Overrides kept from the last session, if the user chose to keep them.
+    kept_env_overrides: Option<EnvOverrides>,
+    /// The tabs open at the last exit (see `session`).
+    session: Option<String>,
+    presets: Vec<crate::types::Preset>,
+}
+
+impl StartupState {
+    fn load(db: &Database) -> Self {
+        Self {
+            environments: db.load_environments().unwrap_or_default(),
+            active_environment_id: db.get_active_environment_id().unwrap_or(None),
+            globals: db.load_globals().unwrap_or_default(),
+            proxy_url: db.get_proxy_url().unwrap_or(None),
+            proxy_options: db.get_proxy_options().unwrap_or_default(),
+            split_editor: db.get_split_editor().unwrap_or(false),
+            default_headers: db.get_default_headers().unwrap_or(None),
+            default_user_agent: db.get_default_user_agent().unwrap_or(None),
+            cookie_jar_enabled: db.get_cookie_jar_enabled().unwrap_or(false),
+            activity_webhook_url: db.get_activity_webhook_url().unwrap_or(None),
+            log_level: db.get_log_level().unwrap_or(None).as_deref().and_then(crate::request_log::parse_level),
+            ca_cert_path: db.get_ca_cert_path().unwrap_or(None),
+            host_overrides: db.get_host_overrides().unwrap_or_default(),
+            body_limit: db.get_body_limit().unwrap_or(None),
+            history_body_cap: db.get_history_body_cap().unwrap_or(None),
+            kept_env_overrides: db.get_kept_env_overrides().unwrap_or(None),
+            session: db.get_session().unwrap_or(None),
+            presets: db.load_presets().unwrap_or_default(),
+        }
+    }
+}
 
-        // Load environments + active selection
-        let environments = db.load_environments().unwrap_or_default();
-        let active_environment_id = db.get_active_environment_id().unwrap_or(None);
+impl PoopmanApp {
+    /// `db` may still be opening (see `Database::open_in_background`): nothing
+    /// here waits on it. Environments and settings arrive through
+    /// `apply_startup_state`, history through the panel's own first load.
+    pub fn new(db: Arc<Database>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let started = std::time::Instant::now();
+        let environments = vec![];
+        let active_environment_id = None;
 
         // Create components
         let request_editor = cx.new(|cx| RequestEditor::new(window, cx));
         let response_viewer = cx.new(|cx| ResponseViewer::new(window, cx));
         let history_panel = cx.new(|cx| HistoryPanel::new(db.clone(), window, cx));
+        let collections_panel = cx.new(|cx| CollectionsPanel::new(db.clone(), cx));
         let tab_bar = cx.new(|cx| TabBar::new(window, cx));
         let env_manager = cx.new(|cx| EnvironmentManager::new(db.clone(), window, cx));
         let code_panel = cx.new(|cx| CodeSnippetPanel::new(window, cx));
-
-        // Push the active environment's variables into the request editor.
-        let initial_env_vars = Self::active_env_vars(&environments, active_environment_id);
-        request_editor.update(cx, |editor, _| editor.set_env_vars(initial_env_vars));
+        let start_panel = cx.new(|_| StartPanel::new());
+        start_panel.update(cx, |panel, cx| panel.refresh(db.clone(), cx));
+
+        let loader = db.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let state = cx
+                .background_executor()
+                .spawn(async move { StartupState::load(&loader) })
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                this.apply_startup_state(state, window, cx);
+                log::info!("Startup: settings loaded {}ms after app init", started.elapsed().as_millis());
+            })
+        })
+        .detach();
 
         // Initialize with one empty tab
         let request_tabs = vec![RequestTab::new_empty(0)];
@@ -77,8 +227,6 @@ impl PoopmanApp {
         let next_tab_id = 1;
 
         // Subscribe to request completion events
-        let db_clone = db.clone();
-        let history_panel_clone = history_panel.clone();
         let response_viewer_clone = response_viewer.clone();
         let request_sub = cx.subscribe_in(
             &request_editor,
@@ -87,28 +235,101 @@ impl PoopmanApp {
                 // Postman behavior: every send is logged to History, including a
                 // re-send of a request opened from history (so edits like added
                 // auth are captured as a new entry).
-                if let Err(e) = Self::persist_send(&db_clone, &event.request) {
-                    log::error!("Failed to save history: {}", e);
+                if let Some(id) = this.record_send(&event.request, &event.response, window, cx) {
+                    this.record_tab_color(id);
                 }
-                history_panel_clone.update(cx, |panel, cx| {
-                    panel.reload(window, cx);
-                });
 
                 // Update response viewer (always)
+                let extracted = this.run_extract_rules(&event.sent, &event.response, cx);
                 response_viewer_clone.update(cx, |viewer, cx| {
-                    viewer.set_response(event.response.clone(), window, cx);
+                    viewer.set_response(event.response.clone(), Some(event.sent.clone()), window, cx);
+                    viewer.set_extracted(extracted, cx);
                 });
 
                 // Update current tab data with the completed request and response (always)
+                let current = this.request_editor.read(cx).get_current_request_data(cx);
+                if let Some(tab) = this.request_tabs.get_mut(this.active_tab_index) {
+                    // What was sent is the tab's new unedited state.
+                    if tab.baseline.is_some() {
+                        tab.set_baseline(current);
+                    }
+                    tab.request = event.request.clone();
+                    tab.record_response(event.response.clone());
+                    tab.sent_request = Some(event.sent.clone());
+                    tab.update_title();
+                    this.update_tab_bar(cx);
+                }
+                this.handle_rate_limit(&event.response, window, cx);
+            },
+        );
+
+        let retry_sub = cx.subscribe_in(
+            &response_viewer,
+            window,
+            |this, _, command: &RetryCommand, window, cx| {
+                let Some(tab_id) = this.request_tabs.get(this.active_tab_index).map(|t| t.id) else {
+                    return;
+                };
+                match command {
+                    RetryCommand::Start => this.start_retry(tab_id, window, cx),
+                    RetryCommand::Now => this.fire_retry(tab_id, window, cx),
+                    RetryCommand::Cancel => this.cancel_retry(tab_id, cx),
+                }
+            },
+        );
+
+        // A "Send ×N" batch is recorded as ONE history row carrying the
+        // serialized attempt details, not one row per attempt.
+        let repeat_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            move |this, _, event: &RepeatCompleted, window, cx| {
+                match Self::persist_send(&this.db, &event.request) {
+                    Ok(id) => {
+                        this.record_tab_color(id);
+                        let report = serde_json::to_string(event.report.as_ref()).unwrap_or_default();
+                        if let Err(e) = this.db.set_history_run_report(id, &report) {
+                            log::error!("Failed to save run report: {}", e);
+                        }
+                    }
+                    Err(e) => log::error!("Failed to save history: {}", e),
+                }
+                this.history_panel.update(cx, |panel, cx| panel.reload(window, cx));
+
+                this.response_viewer.update(cx, |viewer, cx| {
+                    viewer.set_repeat_report(event.report.clone(), window, cx);
+                });
                 if let Some(tab) = this.request_tabs.get_mut(this.active_tab_index) {
                     tab.request = event.request.clone();
-                    tab.response = Some(event.response.clone());
+                    tab.response = None;
+                    tab.sent_request = None;
+                    tab.repeat_report = Some(event.report.clone());
                     tab.update_title();
                     this.update_tab_bar(cx);
                 }
             },
         );
 
+        // Pagination buttons: resolve against the URL that was actually sent
+        // (variables substituted) and load it into the current tab.
+        let page_link_sub = cx.subscribe_in(
+            &response_viewer,
+            window,
+            |this, _, event: &FollowPageLink, window, cx| {
+                let base = this.request_editor.read(cx).resolved_request_data(cx).url;
+                let Some(url) = crate::pagination::resolve_target(&base, &event.target) else {
+                    log::warn!("Cannot resolve page link {:?} against {}", event.target, base);
+                    return;
+                };
+                this.request_editor.update(cx, |editor, cx| {
+                    editor.set_url(&url, window, cx);
+                    if event.send {
+                        editor.send_current(window, cx);
+                    }
+                });
+            },
+        );
+
         // Subscribe to history item click events - open in new tab
         let history_sub = cx.subscribe_in(
             &history_panel,
@@ -135,6 +356,14 @@ impl PoopmanApp {
             },
         );
 
+        let new_ws_tab_sub = cx.subscribe_in(
+            &tab_bar,
+            window,
+            move |this, _, _event: &NewWebSocketTabClicked, _window, cx| {
+                this.create_new_ws_tab(cx);
+            },
+        );
+
         let close_tab_sub = cx.subscribe_in(
             &tab_bar,
             window,
@@ -143,6 +372,201 @@ impl PoopmanApp {
             },
         );
 
+        let compare_tab_sub = cx.subscribe_in(
+            &tab_bar,
+            window,
+            move |this, _, event: &TabCompareClicked, window, cx| {
+                this.open_compare_dialog(event.tab_index, window, cx);
+            },
+        );
+
+        let history_diff_sub = cx.subscribe_in(
+            &history_panel,
+            window,
+            move |this, _, event: &HistoryDiffRequested, window, cx| {
+                this.open_history_diff_dialog(&event.item, window, cx);
+            },
+        );
+
+        let history_export_sub = cx.subscribe_in(
+            &history_panel,
+            window,
+            move |this, _, event: &HistoryExportRequested, window, cx| {
+                this.export_history_to_postman(&event.items, window, cx);
+            },
+        );
+
+        let history_duplicate_sub = cx.subscribe_in(
+            &history_panel,
+            window,
+            move |this, _, event: &HistoryDuplicateRequested, window, cx| {
+                let item = this.with_stored_response(&event.item);
+                this.open_in_new_tab(|id| RequestTab::from_history(id, &item).duplicate(id), window, cx);
+            },
+        );
+
+        let history_resent_sub = cx.subscribe_in(
+            &history_panel,
+            window,
+            move |this, _, event: &HistoryResent, window, cx| {
+                this.record_send(&event.request, &event.response, window, cx);
+            },
+        );
+
+        let history_file_export_sub = cx.subscribe_in(
+            &history_panel,
+            window,
+            move |this, _, event: &HistoryFileExportRequested, window, cx| {
+                this.export_history_item(&event.item, window, cx);
+            },
+        );
+
+        let saved_click_sub = cx.subscribe_in(
+            &collections_panel,
+            window,
+            move |this, _, event: &SavedRequestClicked, window, cx| {
+                this.open_saved_in_new_tab(&event.saved, window, cx);
+            },
+        );
+
+        let collection_delete_sub = cx.subscribe_in(
+            &collections_panel,
+            window,
+            move |this, _, event: &CollectionDeleteRequested, window, cx| {
+                this.confirm_delete_collection(event.collection.clone(), window, cx);
+            },
+        );
+
+        let new_collection_sub = cx.subscribe_in(
+            &collections_panel,
+            window,
+            move |this, _, _e: &NewCollectionRequested, window, cx| {
+                this.open_new_collection_dialog(window, cx);
+            },
+        );
+
+        let collection_run_sub = cx.subscribe_in(
+            &collections_panel,
+            window,
+            move |this, _, event: &CollectionRunRequested, window, cx| {
+                this.open_collection_runner(&event.folder, window, cx);
+            },
+        );
+
+        let collection_export_sub = cx.subscribe_in(
+            &collections_panel,
+            window,
+            move |this, _, event: &CollectionExportRequested, window, cx| {
+                this.export_to_postman(&event.folder, window, cx);
+            },
+        );
+
+        let save_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            move |this, _, _e: &SaveRequested, window, cx| {
+                this.save_request(window, cx);
+            },
+        );
+
+        // Pinning changes what the start panel lists.
+        let history_pin_sub = cx.subscribe_in(
+            &history_panel,
+            window,
+            move |this, _, _e: &HistoryPinChanged, _window, cx| {
+                this.refresh_start_panel(cx);
+            },
+        );
+
+        let start_pick_sub = cx.subscribe_in(
+            &start_panel,
+            window,
+            move |this, _, event: &StartItemPicked, window, cx| match &event.pick {
+                StartPick::History(item) => this.open_history_in_new_tab(item, window, cx),
+                StartPick::Closed(index) => this.reopen_closed_tab(*index, window, cx),
+            },
+        );
+
+        let url_edited_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            move |this, _, e: &UrlEdited, _window, cx| {
+                if this.url_empty != e.empty {
+                    this.url_empty = e.empty;
+                    cx.notify();
+                }
+            },
+        );
+
+        let tab_color_sub = cx.subscribe_in(
+            &tab_bar,
+            window,
+            move |this, _, event: &TabColorPicked, _window, cx| {
+                if let Some(tab) = this.request_tabs.get_mut(event.tab_index) {
+                    tab.color = event.color;
+                    this.update_tab_bar(cx);
+                }
+            },
+        );
+
+        let close_others_sub = cx.subscribe_in(
+            &tab_bar,
+            window,
+            move |this, _, event: &TabsCloseOthers, window, cx| {
+                this.close_other_tabs(event.keep_index, window, cx);
+            },
+        );
+
+        let close_right_sub = cx.subscribe_in(
+            &tab_bar,
+            window,
+            move |this, _, event: &TabsCloseRight, window, cx| {
+                this.close_tabs_to_the_right(event.from_index, window, cx);
+            },
+        );
+
+        let tab_pin_sub = cx.subscribe_in(
+            &tab_bar,
+            window,
+            move |this, _, event: &TabPinToggled, _window, cx| {
+                this.set_tab_pinned(event.tab_index, event.pinned, cx);
+            },
+        );
+
+        let tab_renamed_sub = cx.subscribe_in(
+            &tab_bar,
+            window,
+            move |this, _, event: &TabRenamed, window, cx| {
+                if let Some(tab) = this.request_tabs.get_mut(event.tab_index) {
+                    tab.custom_title = event.title.clone();
+                    tab.update_title();
+                    this.update_tab_bar(cx);
+                    this.save_session();
+                }
+                // Enter leaves the dropped title input's focus nowhere; take it
+                // back so shortcuts keep working (see `focus_handle`).
+                if window.focused(cx).is_none() {
+                    window.focus(&this.focus_handle);
+                }
+            },
+        );
+
+        let tab_moved_sub = cx.subscribe_in(
+            &tab_bar,
+            window,
+            move |this, _, event: &TabMoved, _window, cx| {
+                this.move_tab(event.from, event.to, cx);
+            },
+        );
+
+        let tab_duplicate_sub = cx.subscribe_in(
+            &tab_bar,
+            window,
+            move |this, _, event: &TabDuplicateClicked, window, cx| {
+                this.duplicate_tab(event.tab_index, window, cx);
+            },
+        );
+
         // Reload environments + refresh editor vars whenever the manager changes them.
         let env_changed_sub = cx.subscribe_in(
             &env_manager,
@@ -178,6 +602,84 @@ impl PoopmanApp {
             },
         );
 
+        // Inline `{{var}}` edits from the URL bar write through to the active
+        // environment; the environment manager is refreshed to match.
+        let env_var_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            move |this, _, e: &EnvVarEdited, _window, cx| {
+                this.update_active_env_var(&e.key, &e.value, cx);
+            },
+        );
+
+        let env_override_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            move |this, _, e: &EnvOverrideEdited, _window, cx| {
+                this.set_env_override(&e.key, &e.value, cx);
+            },
+        );
+
+        let proxy_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            |this, _, _e: &ConfigureProxy, window, cx| {
+                this.open_proxy_dialog(window, cx);
+            },
+        );
+
+        let preset_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            |this, _, e: &EditPreset, window, cx| {
+                this.open_preset_dialog(e.id, window, cx);
+            },
+        );
+
+        let network_profile_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            |this, _, e: &ConfigureNetworkProfile, window, cx| {
+                this.open_network_profile_dialog(e.current, window, cx);
+            },
+        );
+
+        let host_overrides_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            |this, _, e: &ConfigureHostOverrides, window, cx| {
+                this.open_host_overrides_dialog(Some(e.current.clone()), window, cx);
+            },
+        );
+
+        let layout_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            |this, _, e: &SplitLayoutChanged, _window, _cx| {
+                if let Err(e) = this.db.set_split_editor(e.split) {
+                    log::error!("Failed to save editor layout: {}", e);
+                }
+            },
+        );
+
+        let default_headers_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            |this, _, e: &DefaultHeadersChanged, _window, _cx| {
+                if let Err(e) = this.db.set_default_headers(&e.headers) {
+                    log::error!("Failed to save the default headers: {}", e);
+                }
+            },
+        );
+
+        let user_agent_sub = cx.subscribe_in(
+            &request_editor,
+            window,
+            |this, _, e: &ConfigureUserAgent, window, cx| {
+                this.open_user_agent_dialog(e.current.clone(), window, cx);
+            },
+        );
+
         // Show the canceled notice when the user aborts an in-flight request.
         // Canceled requests are never written to history (same as Postman).
         let response_viewer_for_cancel = response_viewer.clone();
@@ -191,6 +693,21 @@ impl PoopmanApp {
             },
         );
 
+        // Keep the open tabs for the next start. Closing the window and
+        // quitting (Cmd+Q) are separate paths, so both save.
+        let app = cx.weak_entity();
+        window.on_window_should_close(cx, move |_, cx| {
+            app.update(cx, |this, cx| this.save_current_tab_state(cx)).ok();
+            true
+        });
+        let quit_sub = cx.on_app_quit(|this, cx| {
+            this.save_current_tab_state(cx);
+            async {}
+        });
+
+        // Keep the active tab's dirty dot in step with edits.
+        let dirty_sub = cx.observe(&request_editor, |this, _, cx| this.schedule_dirty_check(cx));
+
         // Push the initial tab into the tab bar so the first request shows as a
         // tab immediately (the TabBar entity starts empty; without this the bar
         // would show only the "+" until the first tab action).
@@ -203,11 +720,14 @@ impl PoopmanApp {
         // click something focusable.
         let focus_handle = cx.focus_handle();
         window.focus(&focus_handle);
+        log::info!("Startup: app state built in {}ms", started.elapsed().as_millis());
 
         Self {
             focus_handle,
             db,
             history_panel,
+            collections_panel,
+            sidebar: Sidebar::History,
             request_editor,
             response_viewer,
             tab_bar,
@@ -218,14 +738,65 @@ impl PoopmanApp {
             active_environment_id,
             env_manager,
             code_panel,
+            retry_timers: HashMap::new(),
+            dirty_check: None,
+            activity_webhook_url: None,
+            log_level: crate::request_log::DEFAULT_LOG_LEVEL,
+            ca_cert_path: None,
+            body_limit: crate::http_client::DEFAULT_BODY_LIMIT,
+            history_body_cap: crate::db::DEFAULT_HISTORY_BODY_CAP,
+            diff_hide_volatile: true,
+            env_overrides: EnvOverrides::default(),
+            keep_env_overrides: false,
+            start_panel,
+            recently_closed: Vec::new(),
+            url_empty: true,
+            ws_panels: HashMap::new(),
             _subscriptions: vec![
+                quit_sub,
+                dirty_sub,
                 request_sub,
+                retry_sub,
+                repeat_sub,
+                page_link_sub,
                 history_sub,
                 tab_clicked_sub,
                 new_tab_sub,
+                new_ws_tab_sub,
                 close_tab_sub,
+                compare_tab_sub,
+                history_diff_sub,
+                history_pin_sub,
+                history_export_sub,
+                history_file_export_sub,
+                history_duplicate_sub,
+                history_resent_sub,
+                saved_click_sub,
+                collection_delete_sub,
+                new_collection_sub,
+                collection_run_sub,
+                collection_export_sub,
+                save_sub,
+                start_pick_sub,
+                url_edited_sub,
+                tab_color_sub,
+                tab_duplicate_sub,
+                tab_moved_sub,
+                close_others_sub,
+                close_right_sub,
+                tab_renamed_sub,
+                tab_pin_sub,
                 env_changed_sub,
                 open_code_sub,
+                env_var_sub,
+                env_override_sub,
+                proxy_sub,
+                preset_sub,
+                network_profile_sub,
+                host_overrides_sub,
+                layout_sub,
+                default_headers_sub,
+                user_agent_sub,
                 cancel_sub,
             ],
         }
@@ -236,71 +807,1947 @@ impl PoopmanApp {
         environments: &[crate::types::Environment],
         active_id: Option<i64>,
     ) -> std::collections::HashMap<String, String> {
-        let mut map = std::collections::HashMap::new();
-        if let Some(id) = active_id
-            && let Some(env) = environments.iter().find(|e| e.id == id)
-        {
-            for v in &env.variables {
-                if v.enabled && !v.key.is_empty() {
-                    map.insert(v.key.clone(), v.value.clone());
-                }
-            }
+        active_id
+            .and_then(|id| environments.iter().find(|e| e.id == id))
+            .map(|env| crate::variables::enabled_map(&env.variables))
+            .unwrap_or_default()
+    }
+
+    /// Name of the active environment, if any.
+    fn active_env_name(
+        environments: &[crate::types::Environment],
+        active_id: Option<i64>,
+    ) -> Option<String> {
+        let id = active_id?;
+        environments.iter().find(|e| e.id == id).map(|e| e.name.clone())
+    }
+
+    /// Set one variable of the active environment and persist it. Every tab
+    /// resolves against the same map at send time, so they all see the change.
+    fn update_active_env_var(&mut self, key: &str, value: &str, cx: &mut Context<Self>) {
+        self.update_active_env_vars(&[(key, value)], cx);
+    }
+
+    /// `update_active_env_var` for several variables, saved together.
+    fn update_active_env_vars(&mut self, updates: &[(&str, &str)], cx: &mut Context<Self>) {
+        let Some(env) = self
+            .active_environment_id
+            .and_then(|id| self.environments.iter().find(|e| e.id == id))
+        else {
+            return;
+        };
+        let mut vars = env.variables.clone();
+        for (key, value) in updates {
+            crate::variables::upsert_var(&mut vars, key, value);
+        }
+        if let Err(e) = self.db.replace_variables(env.id, &vars) {
+            log::error!("Failed to save variables: {}", e);
+            return;
         }
-        map
+        self.reload_environments(cx);
+        self.env_manager.update(cx, |mgr, cx| {
+            mgr.reload();
+            cx.notify();
+        });
     }
 
-    /// Append a completed send to History and return the new row id.
+    /// Append a completed send's request to History and return the new row
+    /// id: everything it was sent with, as `Database::insert_history` lists.
+    /// The caller adds what came back to the row: the response, its body
+    /// cut to the history body cap (`record_send`), or a Send ×N batch's
+    /// report.
     ///
     /// Postman behavior: EVERY send is logged, including a re-send of a request
     /// opened from history. (Previously gated on `!is_from_history`, which
     /// silently dropped edits — e.g. added auth — made to a restored request.)
-    /// Only the request is stored; response bodies are not.
+    /// An OAuth 2.0 token is stored redacted.
     fn persist_send(db: &Database, request: &crate::types::RequestData) -> anyhow::Result<i64> {
-        let request_headers = serde_json::to_string(&request.headers).unwrap_or_default();
-        db.insert_history(
-            request.method.as_str(),
-            &request.url,
-            &request_headers,
-            &request.body,
-            &request.auth,
-        )
+        db.insert_history(&crate::oauth2::redacted(request))
     }
 
-    /// Reload environments + active selection from the DB and push the active
-    /// variable map to the request editor.
-    fn reload_environments(&mut self, cx: &mut Context<Self>) {
-        self.environments = self.db.load_environments().unwrap_or_default();
-        self.active_environment_id = self.db.get_active_environment_id().unwrap_or(None);
-        let vars = Self::active_env_vars(&self.environments, self.active_environment_id);
-        self.request_editor.update(cx, |editor, _| editor.set_env_vars(vars));
-        cx.notify();
+    /// Append a completed send to History with its response, tell the
+    /// activity webhook, and refresh the panel. Returns the new row id.
+    ///
+    /// History records `request` as resolved before the wire (see
+    /// `request_builder::PreparedRequest`): `{{vars}}` substituted, but the
+    /// URL still a `:name` template beside its path variable values, the
+    /// presets referred to by id rather than merged in, and the auth config
+    /// apart from the headers.
+    fn record_send(
+        &mut self,
+        request: &crate::types::RequestData,
+        response: &crate::types::ResponseData,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<i64> {
+        let history_id = match Self::persist_send(&self.db, request) {
+            Ok(id) => {
+                if let Err(e) = self.db.set_history_response(id, response, self.history_body_cap) {
+                    log::error!("Failed to save response to history: {}", e);
+                }
+                Some(id)
+            }
+            Err(e) => {
+                log::error!("Failed to save history: {}", e);
+                None
+            }
+        };
+        self.report_activity(history_id, request, response, cx);
+        self.history_panel.update(cx, |panel, cx| {
+            panel.reload(window, cx);
+        });
+        history_id
+    }
+
+    /// Tint a just-written history row with the active tab's color label.
+    fn record_tab_color(&self, history_id: i64) {
+        let color = self.request_tabs.get(self.active_tab_index).and_then(|t| t.color);
+        if color.is_some()
+            && let Err(e) = self.db.set_history_color(history_id, color)
+        {
+            log::error!("Failed to save tab color: {}", e);
+        }
+    }
+
+    /// Take over what `StartupState::load` read: environments (into the
+    /// editor and the environment dialog), proxy, CA certificate, layout
+    /// and webhook.
+    fn apply_startup_state(&mut self, state: StartupState, window: &mut Window, cx: &mut Context<Self>) {
+        let vars = Self::active_env_vars(&state.environments, state.active_environment_id);
+        let name = Self::active_env_name(&state.environments, state.active_environment_id);
+        let globals = crate::variables::enabled_map(&state.globals);
+        self.request_editor.update(cx, |editor, cx| {
+            editor.set_env_vars(vars, globals, name);
+            editor.set_proxy_url(state.proxy_url, cx);
+            editor.set_split_layout(state.split_editor, cx);
+            editor.set_presets(state.presets, cx);
+            if let Some(headers) = state.default_headers {
+                editor.set_default_headers(headers, cx);
+            }
+            if let Some(user_agent) = state.default_user_agent {
+                editor.set_default_user_agent(user_agent, cx);
+            }
+        });
+        self.sync_history_send_context(cx);
+        // The startup tab was filled before the settings arrived; an
+        // untouched one is redone with the configured default headers.
+        if self.request_tabs.len() == 1
+            && self.request_tabs[0].is_blank()
+            && self.request_editor.read(cx).get_current_request_data(cx).url.trim().is_empty()
+        {
+            let request = self.request_tabs[0].request.clone();
+            self.request_editor.update(cx, |editor, cx| editor.load_request(&request, window, cx));
+        }
+        self.env_manager.update(cx, |manager, cx| {
+            manager.set_environments(
+                state.environments.clone(),
+                state.active_environment_id,
+                state.globals,
+                window,
+                cx,
+            )
+        });
+        self.environments = state.environments;
+        self.active_environment_id = state.active_environment_id;
+        self.activity_webhook_url = state.activity_webhook_url;
+        if let Some(level) = state.log_level {
+            self.log_level = level;
+            crate::request_log::set_level(level);
+        }
+        crate::http_client::set_proxy_options(state.proxy_options);
+        crate::http_client::set_cookie_jar_enabled(state.cookie_jar_enabled);
+        crate::http_client::set_ca_cert_path(state.ca_cert_path.as_ref().map(Into::into));
+        self.ca_cert_path = state.ca_cert_path;
+        crate::http_client::set_host_overrides(state.host_overrides);
+        if let Some(limit) = state.body_limit {
+            self.body_limit = limit;
+            crate::http_client::set_body_limit(limit);
+        }
+        if let Some(cap) = state.history_body_cap {
+            self.history_body_cap = cap;
+        }
+        if let Some(overrides) = state.kept_env_overrides {
+            self.env_overrides = overrides;
+            self.keep_env_overrides = true;
+            self.env_overrides_changed(cx);
+        }
+        if let Some(json) = state.session {
+            self.restore_session(&json, window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Reopen the tabs saved by `save_session`. Skipped once the startup tab
+    /// has been used, so nothing typed before the database answered is lost;
+    /// an unreadable session leaves the single empty tab in place.
+    fn restore_session(&mut self, json: &str, window: &mut Window, cx: &mut Context<Self>) {
+        if self.request_tabs.len() != 1 || !self.request_tabs[0].is_blank() {
+            return;
+        }
+        let (tabs, active) = match crate::session::restore(json) {
+            Ok(restored) => restored,
+            Err(e) => {
+                log::warn!("Ignoring the saved tabs: {}", e);
+                return;
+            }
+        };
+        self.next_tab_id = tabs.len();
+        self.request_tabs = tabs;
+        self.active_tab_index = active;
+
+        let tab = self.request_tabs[active].clone();
+        self.request_editor.update(cx, |editor, cx| {
+            editor.load_request(&tab.request, window, cx);
+            if let Some(params_state) = &tab.params_state
+                && !params_state.is_empty()
+            {
+                editor.load_params_state(params_state, window, cx);
+            }
+            if let Some(headers_state) = &tab.headers_state
+                && !headers_state.is_empty()
+            {
+                editor.load_headers_state(headers_state, window, cx);
+            }
+            editor.set_auto_retry(tab.auto_retry, cx);
+        });
+        self.show_tab_response(&tab, window, cx);
+        self.update_tab_bar(cx);
+        log::info!("Restored {} tab(s) from the last session", self.request_tabs.len());
+    }
+
+    /// Write the open tabs for the next start.
+    fn save_session(&self) {
+        let saved = crate::session::to_json(&self.request_tabs, self.active_tab_index)
+            .and_then(|json| self.db.set_session(&json));
+        if let Err(e) = saved {
+            log::error!("Failed to save the open tabs: {}", e);
+        }
+    }
+
+    /// Add or change a temporary override.
+    fn set_env_override(&mut self, key: &str, value: &str, cx: &mut Context<Self>) {
+        self.env_overrides.set(key, value);
+        self.env_overrides_changed(cx);
+    }
+
+    fn remove_env_override(&mut self, key: &str, cx: &mut Context<Self>) {
+        if self.env_overrides.remove(key).is_some() {
+            self.env_overrides_changed(cx);
+        }
+    }
+
+    fn clear_env_overrides(&mut self, cx: &mut Context<Self>) {
+        self.env_overrides.clear();
+        self.env_overrides_changed(cx);
+    }
+
+    /// "Promote to environment": write the override's value into the active
+    /// environment and drop the override.
+    fn promote_env_override(&mut self, key: &str, cx: &mut Context<Self>) {
+        if self.active_environment_id.is_none() {
+            return;
+        }
+        if let Some(value) = self.env_overrides.remove(key) {
+            self.update_active_env_var(key, &value, cx);
+            self.env_overrides_changed(cx);
+        }
+    }
+
+    fn set_keep_env_overrides(&mut self, keep: bool, cx: &mut Context<Self>) {
+        self.keep_env_overrides = keep;
+        self.env_overrides_changed(cx);
+    }
+
+    /// Run the sent request's extract rules on its response and write the
+    /// values out: into the active environment, or the session overrides.
+    /// Environment rules go to the session when no environment is active;
+    /// the returned results name the scope each value actually went to.
+    fn run_extract_rules(
+        &mut self,
+        sent: &crate::types::RequestData,
+        response: &crate::types::ResponseData,
+        cx: &mut Context<Self>,
+    ) -> Vec<crate::extract::ExtractResult> {
+        use crate::extract::Scope;
+        let mut results = crate::extract::run(&sent.extract, response);
+        let has_env = self.active_environment_id.is_some_and(|id| self.environments.iter().any(|e| e.id == id));
+        let mut to_env = Vec::new();
+        for result in &mut results {
+            let Ok(value) = &result.value else {
+                continue;
+            };
+            if result.rule.scope == Scope::Environment && has_env {
+                to_env.push((result.rule.variable.clone(), value.clone()));
+            } else {
+                result.rule.scope = Scope::Session;
+                self.env_overrides.set(&result.rule.variable, value);
+            }
+        }
+        if !to_env.is_empty() {
+            let updates: Vec<(&str, &str)> = to_env.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+            self.update_active_env_vars(&updates, cx);
+        }
+        if results.iter().any(|r| r.value.is_ok() && r.rule.scope == Scope::Session) {
+            self.env_overrides_changed(cx);
+        }
+        results
+    }
+
+    /// Push the overrides to the editor and, when they are kept, save them.
+    fn env_overrides_changed(&mut self, cx: &mut Context<Self>) {
+        let kept = self.keep_env_overrides.then_some(&self.env_overrides);
+        if let Err(e) = self.db.set_kept_env_overrides(kept) {
+            log::error!("Failed to save variable overrides: {}", e);
+        }
+        let overrides = self.env_overrides.to_map();
+        self.request_editor.update(cx, |editor, cx| editor.set_env_overrides(overrides, cx));
+        self.sync_history_send_context(cx);
+        cx.notify();
+    }
+
+    /// "Temporary Overrides…": variables that win over the active environment
+    /// for this session only.
+    pub(crate) fn open_env_overrides_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let key_input = cx.new(|cx| InputState::new(window, cx).placeholder("base_url"));
+        let value_input = cx.new(|cx| InputState::new(window, cx).placeholder("http://localhost:3000"));
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let this = app.read(cx);
+            let env_name = this
+                .active_environment_id
+                .and_then(|id| this.environments.iter().find(|e| e.id == id))
+                .map(|e| e.name.clone());
+            let keep = this.keep_env_overrides;
+            let any = !this.env_overrides.is_empty();
+            let rows: Vec<(String, String)> =
+                this.env_overrides.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+
+            let add = {
+                let (app, key_input, value_input) = (app.clone(), key_input.clone(), value_input.clone());
+                move |window: &mut Window, cx: &mut App| {
+                    let key = key_input.read(cx).value().trim().to_string();
+                    if key.is_empty() {
+                        return;
+                    }
+                    let value = value_input.read(cx).value().to_string();
+                    app.update(cx, |app, cx| app.set_env_override(&key, &value, cx));
+                    key_input.update(cx, |input, cx| input.set_value("", window, cx));
+                    value_input.update(cx, |input, cx| input.set_value("", window, cx));
+                    window.refresh();
+                }
+            };
+
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Temporary Overrides"),
+                )
+                .w(px(560.))
+                .child(
+                    v_flex()
+                        .gap_3()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .justify_between()
+                                .child(div().text_xs().text_color(theme.muted_foreground).child(
+                                    "Overrides win over the active environment until the app closes. \
+                                     The saved environment is not changed.",
+                                ))
+                                .when(any, |this| {
+                                    let app = app.clone();
+                                    this.child(
+                                        Button::new("override-clear").xsmall().ghost().label("Clear all").on_click(
+                                            move |_, window, cx| {
+                                                app.update(cx, |app, cx| app.clear_env_overrides(cx));
+                                                window.refresh();
+                                            },
+                                        ),
+                                    )
+                                }),
+                        )
+                        .when(!any, |this| {
+                            this.child(div().text_sm().text_color(theme.muted_foreground).child("No overrides"))
+                        })
+                        .children(rows.into_iter().enumerate().map(|(i, (key, value))| {
+                            let (promote_app, remove_app) = (app.clone(), app.clone());
+                            let (promote_key, remove_key) = (key.clone(), key.clone());
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .min_w_0()
+                                        .overflow_hidden()
+                                        .text_ellipsis()
+                                        .whitespace_nowrap()
+                                        .text_sm()
+                                        .child(format!("{{{{{key}}}}} = {value}")),
+                                )
+                                .child(
+                                    Button::new(("override-promote", i))
+                                        .xsmall()
+                                        .ghost()
+                                        .label("Promote to environment")
+                                        .disabled(env_name.is_none())
+                                        .tooltip(match &env_name {
+                                            Some(env) => format!("Save into \"{env}\" and remove the override"),
+                                            None => "No active environment".to_string(),
+                                        })
+                                        .on_click(move |_, window, cx| {
+                                            promote_app.update(cx, |app, cx| app.promote_env_override(&promote_key, cx));
+                                            window.refresh();
+                                        }),
+                                )
+                                .child(
+                                    Button::new(("override-remove", i))
+                                        .xsmall()
+                                        .ghost()
+                                        .label("×")
+                                        .tooltip(format!("Remove override {{{{{key}}}}}"))
+                                        .on_click(move |_, window, cx| {
+                                            remove_app.update(cx, |app, cx| app.remove_env_override(&remove_key, cx));
+                                            window.refresh();
+                                        }),
+                                )
+                        }))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(div().w(px(160.)).child(Input::new(&key_input).small()))
+                                .child(div().flex_1().child(Input::new(&value_input).small()))
+                                .child(
+                                    Button::new("override-add")
+                                        .small()
+                                        .label("Add")
+                                        .on_click(move |_, window, cx| add(window, cx)),
+                                ),
+                        )
+                        .child({
+                            let app = app.clone();
+                            Checkbox::new("keep-overrides")
+                                .label("Keep overrides after closing the app")
+                                .checked(keep)
+                                .on_click(move |checked: &bool, window, cx| {
+                                    let keep = *checked;
+                                    app.update(cx, |app, cx| app.set_keep_env_overrides(keep, cx));
+                                    window.refresh();
+                                })
+                        }),
+                )
+        });
+    }
+
+    /// Reload environments, globals and the active selection from the DB and
+    /// push the variable maps to the request editor.
+    fn reload_environments(&mut self, cx: &mut Context<Self>) {
+        self.environments = self.db.load_environments().unwrap_or_default();
+        self.active_environment_id = self.db.get_active_environment_id().unwrap_or(None);
+        let vars = Self::active_env_vars(&self.environments, self.active_environment_id);
+        let name = Self::active_env_name(&self.environments, self.active_environment_id);
+        let globals = crate::variables::enabled_map(&self.db.load_globals().unwrap_or_default());
+        self.request_editor.update(cx, |editor, cx| {
+            editor.set_env_vars(vars, globals, name);
+            cx.notify();
+        });
+        self.sync_history_send_context(cx);
+        cx.notify();
+    }
+
+    /// Hand History's resend the presets and `{{vars}}` the request editor
+    /// now sends with, so a recorded request goes out as Send would.
+    fn sync_history_send_context(&mut self, cx: &mut Context<Self>) {
+        let editor = self.request_editor.read(cx);
+        let (presets, vars) = (editor.presets().to_vec(), editor.resolved_vars());
+        self.history_panel.update(cx, |panel, _| panel.set_send_context(presets, vars));
+    }
+
+    /// The cookie jar: the on/off switch and the cookies it holds, by domain,
+    /// each deletable.
+    pub(crate) fn open_cookies_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let app = cx.entity();
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let enabled = crate::http_client::cookie_jar_enabled();
+            let mut cookies = crate::http_client::cookie_jar().cookies().to_vec();
+            cookies.sort_by(|a, b| a.domain.cmp(&b.domain));
+            let any = !cookies.is_empty();
+
+            let mut list = v_flex().gap_1();
+            let mut last_domain = None;
+            for (i, cookie) in cookies.into_iter().enumerate() {
+                if last_domain.as_ref() != Some(&cookie.domain) {
+                    list = list.child(
+                        div()
+                            .pt_1()
+                            .text_xs()
+                            .font_weight(gpui::FontWeight::SEMIBOLD)
+                            .text_color(theme.muted_foreground)
+                            .child(if cookie.host_only { cookie.domain.clone() } else { format!(".{}", cookie.domain) }),
+                    );
+                    last_domain = Some(cookie.domain.clone());
+                }
+                let expiry = match cookie.expires_at {
+                    Some(at) => at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string(),
+                    None => "Session".to_string(),
+                };
+                let flags = [(cookie.secure, "Secure"), (cookie.http_only, "HttpOnly")]
+                    .iter()
+                    .filter(|(on, _)| *on)
+                    .map(|(_, name)| *name)
+                    .collect::<Vec<_>>();
+                let app = app.clone();
+                list = list.child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .whitespace_nowrap()
+                                .text_sm()
+                                .child(format!("{}={}", cookie.name, cookie.value)),
+                        )
+                        .child(
+                            div()
+                                .flex_shrink_0()
+                                .text_xs()
+                                .text_color(theme.muted_foreground)
+                                .child(
+                                    std::iter::once(cookie.path.clone())
+                                        .chain(std::iter::once(expiry))
+                                        .chain(flags.into_iter().map(str::to_string))
+                                        .collect::<Vec<_>>()
+                                        .join(" · "),
+                                ),
+                        )
+                        .child(
+                            Button::new(("cookie-remove", i))
+                                .xsmall()
+                                .ghost()
+                                .label("×")
+                                .tooltip(format!("Delete {}", cookie.name))
+                                .on_click(move |_, window, cx| {
+                                    crate::http_client::cookie_jar().remove(&cookie.domain, &cookie.path, &cookie.name);
+                                    app.update(cx, |app, cx| app.cookies_changed(cx));
+                                    window.refresh();
+                                }),
+                        ),
+                );
+            }
+
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Cookies"),
+                )
+                .w(px(560.))
+                .child(
+                    v_flex()
+                        .gap_3()
+                        .child({
+                            let app = app.clone();
+                            Checkbox::new("cookie-jar-enabled")
+                                .label("Keep cookies from responses and send them on later requests")
+                                .checked(enabled)
+                                .on_click(move |checked: &bool, window, cx| {
+                                    let enabled = *checked;
+                                    app.update(cx, |app, cx| app.set_cookie_jar_enabled(enabled, cx));
+                                    window.refresh();
+                                })
+                        })
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .justify_between()
+                                .child(div().text_xs().text_color(theme.muted_foreground).child(
+                                    "Cookies are kept until the app closes. A request with its own Cookie \
+                                     header is sent with that header only.",
+                                ))
+                                .when(any, |this| {
+                                    let app = app.clone();
+                                    this.child(
+                                        Button::new("cookie-clear").xsmall().ghost().label("Clear all").on_click(
+                                            move |_, window, cx| {
+                                                crate::http_client::cookie_jar().clear();
+                                                app.update(cx, |app, cx| app.cookies_changed(cx));
+                                                window.refresh();
+                                            },
+                                        ),
+                                    )
+                                }),
+                        )
+                        .when(!any, |this| {
+                            this.child(div().text_sm().text_color(theme.muted_foreground).child("No cookies"))
+                        })
+                        .child(div().id("cookie-list").max_h(px(360.)).overflow_y_scroll().child(list)),
+                )
+        });
+    }
+
+    /// Turn the cookie jar on or off; off stops cookies going out from the
+    /// next request on.
+    fn set_cookie_jar_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        crate::http_client::set_cookie_jar_enabled(enabled);
+        if let Err(e) = self.db.set_cookie_jar_enabled(enabled) {
+            log::error!("Failed to save cookie setting: {}", e);
+        }
+        self.cookies_changed(cx);
+    }
+
+    /// The editor shows which jar cookies a send would carry; redraw it.
+    fn cookies_changed(&mut self, cx: &mut Context<Self>) {
+        self.request_editor.update(cx, |_, cx| cx.notify());
+    }
+
+    /// Open the environment management dialog.
+    pub(crate) fn open_env_manager(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let manager = self.env_manager.clone();
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            dialog
+                .title(
+                    v_flex()
+                        .gap_0p5()
+                        .child(
+                            div()
+                                .text_lg()
+                                .font_weight(gpui::FontWeight::BOLD)
+                                .text_color(theme.foreground)
+                                .child("Environments"),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.muted_foreground)
+                                .child("Define variables like {{base_url}} per environment"),
+                        ),
+                )
+                .w(px(680.))
+                .child(manager.clone())
+        });
+    }
+
+    /// Diff the active tab's request (as currently edited) against another
+    /// tab's and show the report in a dialog.
+    fn open_compare_dialog(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(other) = self.request_tabs.get(index) else {
+            return;
+        };
+        let current = self.request_editor.read(cx).get_current_request_data(cx);
+        let diff = crate::request_diff::diff_requests(&current, &other.request);
+        let right_title = other.title.clone();
+        self.show_request_diff(diff, right_title, window, cx);
+    }
+
+    /// Diff the active tab's request against a History entry — "did this
+    /// request change since it last worked?".
+    fn open_history_diff_dialog(&mut self, item: &crate::types::HistoryItem, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.request_editor.read(cx).get_current_request_data(cx);
+        let diff = crate::request_diff::diff_requests(&current, &item.request);
+        let sent = crate::format::format_relative_time(&item.timestamp, chrono::Utc::now());
+        self.show_request_diff(diff, format!("History · {sent}"), window, cx);
+    }
+
+    /// Open the diff report with the active tab on the left.
+    fn show_request_diff(
+        &mut self,
+        diff: crate::request_diff::RequestDiff,
+        right_title: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let left_title = self
+            .request_tabs
+            .get(self.active_tab_index)
+            .map(|tab| tab.title.clone())
+            .unwrap_or_default();
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let hide_volatile = app.read(cx).diff_hide_volatile;
+            let app = app.clone();
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Compare requests"),
+                )
+                .w(px(820.))
+                .child(crate::compare_view::render_request_diff(
+                    &diff,
+                    &left_title,
+                    &right_title,
+                    hide_volatile,
+                    move |checked, _window, cx| {
+                        app.update(cx, |app, cx| {
+                            app.diff_hide_volatile = *checked;
+                            cx.notify();
+                        })
+                    },
+                    cx,
+                ))
+        });
+    }
+
+    /// Write the workspace bundle (environments, globals, presets,
+    /// collections and the portable settings) to a file chosen via the OS
+    /// save dialog.
+    pub(crate) fn export_workspace(&mut self, policy: SecretPolicy, window: &mut Window, cx: &mut Context<Self>) {
+        let workspace = match self.db.load_workspace() {
+            Ok(workspace) => workspace,
+            Err(e) => {
+                log::error!("Failed to read workspace for export: {}", e);
+                return;
+            }
+        };
+        let bundle = crate::workspace_bundle::build_bundle(&workspace, policy, chrono::Utc::now().to_rfc3339());
+        let json = match serde_json::to_string_pretty(&bundle) {
+            Ok(json) => json,
+            Err(e) => {
+                log::error!("Failed to serialize workspace: {}", e);
+                return;
+            }
+        };
+        let dir = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| std::path::PathBuf::from("."));
+        let rx = cx.prompt_for_new_path(&dir, Some("workspace.poopman.json"));
+        cx.spawn_in(window, async move |_this, _cx| {
+            if let Ok(Ok(Some(path))) = rx.await
+                && let Err(e) = std::fs::write(&path, json)
+            {
+                log::error!("Failed to export workspace to {:?}: {}", path, e);
+            }
+        })
+        .detach();
+    }
+
+    /// Save `items` as a Postman v2.1 collection, one request per row, named
+    /// by method and URL.
+    fn export_history_to_postman(&mut self, items: &[crate::types::HistoryItem], window: &mut Window, cx: &mut Context<Self>) {
+        let folder = crate::postman_import::CollectionFolder {
+            name: "Poopman history".to_string(),
+            folders: Vec::new(),
+            requests: items
+                .iter()
+                .map(|item| crate::postman_import::CollectionRequest {
+                    name: crate::postman_export::default_name(&item.request),
+                    request: item.request.clone(),
+                })
+                .collect(),
+        };
+        self.export_to_postman(&folder, window, cx);
+    }
+
+    /// Open the collection runner on `folder`, starting from the active
+    /// environment. Closing the dialog stops a run still going.
+    fn open_collection_runner(
+        &mut self,
+        folder: &crate::postman_import::CollectionFolder,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let globals = crate::variables::enabled_map(&self.db.load_globals().unwrap_or_default());
+        let presets = self.db.load_presets().unwrap_or_default();
+        let (environments, active_id) = (self.environments.clone(), self.active_environment_id);
+        let runner =
+            cx.new(|cx| RunnerPanel::new(folder, environments, active_id, globals, presets, window, cx));
+        let title = format!("Run {}", folder.name);
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child(title.clone()),
+                )
+                .w(px(880.))
+                .child(runner.clone())
+        });
+    }
+
+    /// Ask where to save `folder` as a Postman v2.1 collection, then write it.
+    fn export_to_postman(
+        &mut self,
+        folder: &crate::postman_import::CollectionFolder,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let json = crate::postman_export::export_collection(folder);
+        let dir = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| std::path::PathBuf::from("."));
+        let stem: String = folder
+            .name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '-' })
+            .collect();
+        let rx = cx.prompt_for_new_path(&dir, Some(&format!("{stem}.postman_collection.json")));
+        cx.spawn_in(window, async move |_this, _cx| {
+            if let Ok(Ok(Some(path))) = rx.await
+                && let Err(e) = std::fs::write(&path, json)
+            {
+                log::error!("Failed to export Postman collection to {:?}: {}", path, e);
+            }
+        })
+        .detach();
+    }
+
+    /// Export a history row, with its stored response, to a file.
+    fn export_history_item(&mut self, item: &crate::types::HistoryItem, window: &mut Window, cx: &mut Context<Self>) {
+        let item = self.with_stored_response(item);
+        self.open_export_request_dialog(item.request, item.response, window, cx);
+    }
+
+    /// Export the active tab's request, with its response, to a file.
+    pub(crate) fn export_current_request(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.save_current_tab_state(cx);
+        let Some(tab) = self.request_tabs.get(self.active_tab_index) else {
+            return;
+        };
+        self.open_export_request_dialog(tab.request.clone(), tab.response.clone(), window, cx);
+    }
+
+    /// "Export request": pick the file format, then where to save it.
+    fn open_export_request_dialog(
+        &mut self,
+        request: crate::types::RequestData,
+        response: Option<Arc<crate::types::ResponseData>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let labels: Vec<String> = ExportFormat::ALL.iter().map(|format| format.label().to_string()).collect();
+        let format = cx.new(|cx| SelectState::new(labels, Some(IndexPath::default()), window, cx));
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let (app, format) = (app.clone(), format.clone());
+            let (request, response) = (request.clone(), response.clone());
+
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Export request"),
+                )
+                .w(px(420.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(
+                            div()
+                                .text_xs()
+                                .font_weight(gpui::FontWeight::BOLD)
+                                .text_color(theme.muted_foreground)
+                                .child("FORMAT"),
+                        )
+                        .child(Select::new(&format))
+                        .when(response.is_none(), |this| {
+                            this.child(
+                                div()
+                                    .text_sm()
+                                    .text_color(theme.muted_foreground)
+                                    .child("Not sent yet: only the request is exported."),
+                            )
+                        }),
+                )
+                .confirm()
+                .on_ok(move |_, window, cx| {
+                    let format = format
+                        .read(cx)
+                        .selected_index(cx)
+                        .and_then(|ix| ExportFormat::ALL.get(ix.row).copied())
+                        .unwrap_or(ExportFormat::Json);
+                    app.update(cx, |app, cx| {
+                        app.write_request_export(format, &request, response.as_deref(), window, cx);
+                    });
+                    true
+                })
+        });
+    }
+
+    /// Ask where to save `request` (and `response`) in `format`, then write it.
+    fn write_request_export(
+        &mut self,
+        format: ExportFormat,
+        request: &crate::types::RequestData,
+        response: Option<&crate::types::ResponseData>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let contents = match crate::request_export::export(format, request, response) {
+            Ok(contents) => contents,
+            Err(e) => {
+                log::error!("Failed to serialize request: {}", e);
+                return;
+            }
+        };
+        let dir = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| std::path::PathBuf::from("."));
+        let rx = cx.prompt_for_new_path(&dir, Some(&crate::request_export::file_name(request, format)));
+        cx.spawn_in(window, async move |_this, _cx| {
+            if let Ok(Ok(Some(path))) = rx.await
+                && let Err(e) = std::fs::write(&path, contents)
+            {
+                log::error!("Failed to export request to {:?}: {}", path, e);
+            }
+        })
+        .detach();
+    }
+
+    /// Save (Ctrl+S): overwrite the saved request the tab came from, or ask
+    /// for a name and a collection when there is none.
+    fn save_request(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.save_current_tab_state(cx);
+        // A WebSocket tab is saved with the session, as just done.
+        let Some(tab) = self.request_tabs.get(self.active_tab_index).filter(|tab| tab.ws.is_none()) else {
+            return;
+        };
+        if let Some(id) = tab.saved_request_id {
+            match self.db.update_saved_request(id, &tab.request) {
+                Ok(true) => {
+                    let request = tab.request.clone();
+                    if let Some(tab) = self.request_tabs.get_mut(self.active_tab_index) {
+                        tab.set_baseline(request);
+                    }
+                    self.collections_panel.update(cx, |panel, cx| panel.reload(cx));
+                    self.update_tab_bar(cx);
+                    return;
+                }
+                // Deleted since it was opened: save it afresh.
+                Ok(false) => {}
+                Err(e) => {
+                    log::error!("Failed to save request: {}", e);
+                    return;
+                }
+            }
+        }
+        self.open_save_dialog(window, cx);
+    }
+
+    /// "Save request": a name, and an existing collection or a new one.
+    fn open_save_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tab) = self.request_tabs.get(self.active_tab_index) else {
+            return;
+        };
+        // The title: a saved request's name, with " (copy)" on a duplicate.
+        let default_name = tab.title.clone();
+        let (ids, paths): (Vec<i64>, Vec<String>) =
+            self.collections_panel.read(cx).collection_paths().into_iter().unzip();
+        let name = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Request name")
+                .default_value(default_name)
+        });
+        let new_collection = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(if ids.is_empty() {
+                "Collection name"
+            } else {
+                "Or a new collection"
+            })
+        });
+        let has_collections = !ids.is_empty();
+        let target = cx.new(|cx| SelectState::new(paths, has_collections.then(IndexPath::default), window, cx));
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let (app, ids) = (app.clone(), ids.clone());
+            let (name, new_collection, target) = (name.clone(), new_collection.clone(), target.clone());
+
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Save request"),
+                )
+                .w(px(420.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(Input::new(&name))
+                        .when(has_collections, |this| {
+                            this.child(
+                                div()
+                                    .text_xs()
+                                    .font_weight(gpui::FontWeight::BOLD)
+                                    .text_color(theme.muted_foreground)
+                                    .child("SAVE TO"),
+                            )
+                            .child(Select::new(&target))
+                        })
+                        .child(Input::new(&new_collection)),
+                )
+                .confirm()
+                .on_ok(move |_, _window, cx| {
+                    let name = name.read(cx).value().trim().to_string();
+                    let new_collection = new_collection.read(cx).value().trim().to_string();
+                    let existing = target
+                        .read(cx)
+                        .selected_index(cx)
+                        .and_then(|ix| ids.get(ix.row).copied());
+                    if name.is_empty() || (new_collection.is_empty() && existing.is_none()) {
+                        return false;
+                    }
+                    app.update(cx, |app, cx| app.save_request_as(&name, existing, &new_collection, cx))
+                })
+        });
+    }
+
+    /// Save the active tab's request as `name` into `new_collection` (created
+    /// here) when given, else into the collection `existing`.
+    fn save_request_as(
+        &mut self,
+        name: &str,
+        existing: Option<i64>,
+        new_collection: &str,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let collection_id = if !new_collection.is_empty() {
+            match self.db.create_collection(new_collection, None) {
+                Ok(id) => id,
+                Err(e) => {
+                    log::error!("Failed to create collection: {}", e);
+                    return false;
+                }
+            }
+        } else if let Some(id) = existing {
+            id
+        } else {
+            return false;
+        };
+        let Some(tab) = self.request_tabs.get_mut(self.active_tab_index) else {
+            return false;
+        };
+        match self.db.save_request(collection_id, name, &tab.request) {
+            Ok(id) => {
+                tab.saved_request_id = Some(id);
+                tab.saved_name = Some(name.to_string());
+                tab.copy = false;
+                tab.update_title();
+                tab.set_baseline(tab.request.clone());
+            }
+            Err(e) => {
+                log::error!("Failed to save request: {}", e);
+                return false;
+            }
+        }
+        self.collections_panel.update(cx, |panel, cx| {
+            panel.reload(cx);
+            panel.reveal(collection_id, cx);
+        });
+        self.sidebar = Sidebar::Collections;
+        self.update_tab_bar(cx);
+        cx.notify();
+        true
+    }
+
+    /// The Collections header's "New": name an empty collection.
+    fn open_new_collection_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Collection name"));
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let (app, input_for_ok) = (app.clone(), input.clone());
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("New collection"),
+                )
+                .w(px(420.))
+                .child(Input::new(&input))
+                .confirm()
+                .on_ok(move |_, _window, cx| {
+                    let name = input_for_ok.read(cx).value().trim().to_string();
+                    if name.is_empty() {
+                        return false;
+                    }
+                    app.update(cx, |app, cx| match app.db.create_collection(&name, None) {
+                        Ok(_) => app.collections_panel.update(cx, |panel, cx| panel.reload(cx)),
+                        Err(e) => log::error!("Failed to create collection: {}", e),
+                    });
+                    true
+                })
+        });
+    }
+
+    /// Deleting a collection takes its folders and saved requests with it, so
+    /// it is confirmed first.
+    fn confirm_delete_collection(
+        &mut self,
+        collection: crate::types::Collection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let kind = if collection.parent_id.is_some() { "folder" } else { "collection" };
+        let message = format!(
+            "Delete the {} \u{201c}{}\u{201d} and every request saved in it? This cannot be undone.",
+            kind, collection.name
+        );
+        let id = collection.id;
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let app = app.clone();
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child(format!("Delete {kind}")),
+                )
+                .w(px(420.))
+                .child(div().text_sm().child(message.clone()))
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("Delete").ok_variant(ButtonVariant::Danger))
+                .on_ok(move |_, _window, cx| {
+                    app.update(cx, |app, cx| {
+                        if let Err(e) = app.db.delete_collection(id) {
+                            log::error!("Failed to delete collection: {}", e);
+                        }
+                        app.collections_panel.update(cx, |panel, cx| panel.reload(cx));
+                    });
+                    true
+                })
+        });
+    }
+
+    /// New or edit a request preset: a name, headers as `Name: value` lines
+    /// and params as `key=value` lines. Requests that apply the preset pick
+    /// up the change on their next send.
+    fn open_preset_dialog(&mut self, id: Option<i64>, window: &mut Window, cx: &mut Context<Self>) {
+        let presets = self.db.load_presets().unwrap_or_default();
+        let preset = id.and_then(|id| presets.into_iter().find(|p| p.id == id)).unwrap_or(crate::types::Preset {
+            id: 0,
+            name: String::new(),
+            headers: Vec::new(),
+            params: Vec::new(),
+        });
+        let name = cx.new(|cx| InputState::new(window, cx).placeholder("Name").default_value(preset.name.clone()));
+        let headers = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .rows(5)
+                .placeholder("X-Tenant-Id: acme\nX-Trace-Id: {{trace_id}}")
+                .default_value(crate::presets::headers_to_text(&preset.headers))
+        });
+        let params = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .rows(3)
+                .placeholder("api-version=2024-01-01")
+                .default_value(crate::presets::params_to_text(&preset.params))
+        });
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let label = |text: &'static str| div().text_xs().text_color(theme.muted_foreground).child(text);
+            let (app_for_ok, fields) = (app.clone(), (name.clone(), headers.clone(), params.clone()));
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child(if preset.id == 0 { "New preset" } else { "Edit preset" }),
+                )
+                .w(px(520.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(Input::new(&name))
+                        .child(label("Headers, one Name: value per line"))
+                        .child(Input::new(&headers))
+                        .child(label("Query params, one key=value per line"))
+                        .child(Input::new(&params))
+                        .when(preset.id != 0, |this| {
+                            let (app, id) = (app.clone(), preset.id);
+                            this.child(
+                                h_flex().justify_end().child(
+                                    Button::new("delete-preset").xsmall().ghost().label("Delete preset").on_click(
+                                        move |_, window, cx| {
+                                            app.update(cx, |app, cx| {
+                                                if let Err(e) = app.db.delete_preset(id) {
+                                                    log::error!("Failed to delete preset: {}", e);
+                                                }
+                                                app.reload_presets(cx);
+                                            });
+                                            window.close_dialog(cx);
+                                        },
+                                    ),
+                                ),
+                            )
+                        }),
+                )
+                .confirm()
+                .on_ok({
+                    let id = preset.id;
+                    move |_, _window, cx| {
+                        let (name, headers, params) = &fields;
+                        let name = name.read(cx).value().trim().to_string();
+                        if name.is_empty() {
+                            return false;
+                        }
+                        let preset = crate::types::Preset {
+                            id,
+                            name,
+                            headers: crate::presets::parse_lines(&headers.read(cx).value(), ':'),
+                            params: crate::presets::parse_lines(&params.read(cx).value(), '='),
+                        };
+                        app_for_ok.update(cx, |app, cx| {
+                            if let Err(e) = app.db.save_preset(&preset) {
+                                log::error!("Failed to save preset: {}", e);
+                            }
+                            app.reload_presets(cx);
+                        });
+                        true
+                    }
+                })
+        });
+    }
+
+    fn reload_presets(&mut self, cx: &mut Context<Self>) {
+        let presets = self.db.load_presets().unwrap_or_default();
+        self.request_editor.update(cx, |editor, cx| editor.set_presets(presets, cx));
+        self.sync_history_send_context(cx);
+    }
+
+    /// "Configure Proxy…": edit the proxy URL the send-through selector
+    /// offers. Saving an empty value removes it.
+    fn open_proxy_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.db.get_proxy_url().unwrap_or(None).unwrap_or_default();
+        let options = self.db.get_proxy_options().unwrap_or_default();
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("http://127.0.0.1:8888")
+                .default_value(current)
+        });
+        let username = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Username (optional)")
+                .default_value(options.username)
+        });
+        let password = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Password")
+                .masked(true)
+                .default_value(options.password)
+        });
+        let no_proxy = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("No proxy for: localhost, .corp.local")
+                .default_value(options.no_proxy)
+        });
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let value = input.read(cx).value().trim().to_string();
+            let error = (!value.is_empty()).then(|| crate::http_client::proxy_url_error(&value)).flatten();
+            let (app, input_for_ok) = (app.clone(), input.clone());
+            let fields = (username.clone(), password.clone(), no_proxy.clone());
+
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Proxy"),
+                )
+                .w(px(460.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(Input::new(&input))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(if error.is_none() { theme.muted_foreground } else { theme.danger })
+                                .child(error.unwrap_or_else(|| {
+                                    "HTTP(S) or SOCKS5, used by requests whose send-through selector is set to it. \
+                                     Leave empty to remove."
+                                        .to_string()
+                                })),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(Input::new(&username))
+                                .child(Input::new(&password).mask_toggle()),
+                        )
+                        .child(Input::new(&no_proxy))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.muted_foreground)
+                                .child("Comma-separated hosts reached directly; a domain covers its subdomains."),
+                        ),
+                )
+                .confirm()
+                .on_ok(move |_, _window, cx| {
+                    let value = input_for_ok.read(cx).value().trim().to_string();
+                    if !value.is_empty() && !crate::http_client::is_usable_proxy_url(&value) {
+                        return false;
+                    }
+                    let (username, password, no_proxy) = &fields;
+                    let options = crate::http_client::ProxyOptions {
+                        username: username.read(cx).value().trim().to_string(),
+                        password: password.read(cx).value().to_string(),
+                        no_proxy: no_proxy.read(cx).value().trim().to_string(),
+                    };
+                    app.update(cx, |app, cx| {
+                        app.set_proxy_options(options);
+                        app.set_proxy_url((!value.is_empty()).then_some(value), cx)
+                    });
+                    true
+                })
+        });
+    }
+
+    /// "Custom…" network profile: a rate (kbps, both directions) and added
+    /// latency for the active request.
+    fn open_network_profile_dialog(
+        &mut self,
+        current: crate::types::NetworkProfile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (kbps, latency_ms) = match current {
+            crate::types::NetworkProfile::Custom { kbps, latency_ms } => (kbps.to_string(), latency_ms.to_string()),
+            _ => (String::new(), String::new()),
+        };
+        let kbps_input = cx.new(|cx| InputState::new(window, cx).placeholder("e.g. 256").default_value(kbps));
+        let latency_input = cx.new(|cx| InputState::new(window, cx).placeholder("e.g. 300").default_value(latency_ms));
+        let editor = self.request_editor.clone();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let parsed = crate::throttle::parse_custom(
+                &kbps_input.read(cx).value(),
+                &latency_input.read(cx).value(),
+            );
+            let (editor, kbps_for_ok, latency_for_ok) = (editor.clone(), kbps_input.clone(), latency_input.clone());
+            let field = |label: &'static str, input: &Entity<InputState>| {
+                h_flex()
+                    .gap_2()
+                    .child(div().w(px(140.)).text_sm().text_color(theme.foreground).child(label))
+                    .child(div().flex_1().child(Input::new(input)))
+            };
+
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Network Profile"),
+                )
+                .w(px(460.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(field("Bandwidth (kbps)", &kbps_input))
+                        .child(field("Added latency (ms)", &latency_input))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(if parsed.is_some() { theme.muted_foreground } else { theme.danger })
+                                .child(if parsed.is_some() {
+                                    "Applies to uploads and downloads of this request. Leave a field empty for no limit."
+                                } else {
+                                    "Enter whole numbers."
+                                }),
+                        ),
+                )
+                .confirm()
+                .on_ok(move |_, _window, cx| {
+                    let Some(profile) = crate::throttle::parse_custom(
+                        &kbps_for_ok.read(cx).value(),
+                        &latency_for_ok.read(cx).value(),
+                    ) else {
+                        return false;
+                    };
+                    editor.update(cx, |editor, cx| editor.set_network_profile(profile, cx));
+                    true
+                })
+        });
+    }
+
+    /// "Default User-Agent…": the User-Agent row's value in new tabs.
+    /// Saving an empty value goes back to `types::DEFAULT_USER_AGENT`.
+    fn open_user_agent_dialog(&mut self, current: String, window: &mut Window, cx: &mut Context<Self>) {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(crate::types::DEFAULT_USER_AGENT)
+                .default_value(current)
+        });
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let (app, input_for_ok) = (app.clone(), input.clone());
+
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Default User-Agent"),
+                )
+                .w(px(460.))
+                .child(
+                    v_flex().gap_2().child(Input::new(&input)).child(
+                        div().text_xs().text_color(theme.muted_foreground).child(format!(
+                            "New tabs start with this User-Agent; leave empty for {}. \
+                             To send none, disable or clear the request's row.",
+                            crate::types::DEFAULT_USER_AGENT
+                        )),
+                    ),
+                )
+                .confirm()
+                .on_ok(move |_, _window, cx| {
+                    let value = input_for_ok.read(cx).value().trim().to_string();
+                    app.update(cx, |app, cx| app.set_default_user_agent((!value.is_empty()).then_some(value), cx));
+                    true
+                })
+        });
+    }
+
+    fn set_default_user_agent(&mut self, user_agent: Option<String>, cx: &mut Context<Self>) {
+        if let Err(e) = self.db.set_default_user_agent(user_agent.as_deref()) {
+            log::error!("Failed to save the default User-Agent: {}", e);
+        }
+        let user_agent = user_agent.unwrap_or_else(|| crate::types::DEFAULT_USER_AGENT.to_string());
+        self.request_editor.update(cx, |editor, cx| editor.set_default_user_agent(user_agent, cx));
+    }
+
+    /// "Resolve Hosts…": edit the host overrides of every request (`None`)
+    /// or, from the send-through selector, of the active one.
+    pub(crate) fn open_host_overrides_dialog(
+        &mut self,
+        request: Option<Vec<crate::types::HostOverride>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let for_request = request.is_some();
+        let current = request.unwrap_or_else(crate::http_client::host_overrides);
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .rows(5)
+                .placeholder("api.example.com 10.0.0.5\nstaging.example.com 10.0.0.6:8443")
+                .default_value(crate::host_overrides::format(&current))
+        });
+        let (app, editor) = (cx.entity(), self.request_editor.clone());
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let parsed = crate::host_overrides::parse(&input.read(cx).value());
+            let (app, editor, input_for_ok) = (app.clone(), editor.clone(), input.clone());
+            let hint = if for_request {
+                "One host and IP[:port] per line, for this request; over the global ones for the same host. \
+                 TLS and the Host header still use the host name."
+            } else {
+                "One host and IP[:port] per line, for every request. TLS and the Host header still use the host name."
+            };
+
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child(if for_request { "Resolve Hosts for This Request" } else { "Resolve Hosts" }),
+                )
+                .w(px(460.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(Input::new(&input))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(if parsed.is_ok() { theme.muted_foreground } else { theme.danger })
+                                .child(parsed.err().unwrap_or_else(|| hint.to_string())),
+                        ),
+                )
+                .confirm()
+                .on_ok(move |_, _window, cx| {
+                    let Ok(overrides) = crate::host_overrides::parse(&input_for_ok.read(cx).value()) else {
+                        return false;
+                    };
+                    if for_request {
+                        editor.update(cx, |editor, cx| editor.set_host_overrides(overrides, cx));
+                    } else {
+                        app.update(cx, |app, cx| app.set_host_overrides(overrides, cx));
+                    }
+                    true
+                })
+        });
+    }
+
+    fn set_host_overrides(&mut self, overrides: Vec<crate::types::HostOverride>, cx: &mut Context<Self>) {
+        if let Err(e) = self.db.set_host_overrides(&overrides) {
+            log::error!("Failed to save host overrides: {}", e);
+        }
+        crate::http_client::set_host_overrides(overrides);
+        cx.notify();
+    }
+
+    /// "Activity Webhook…": set the URL completed sends are reported to.
+    /// Saving an empty value turns the webhook off.
+    pub(crate) fn open_activity_webhook_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.activity_webhook_url.clone().unwrap_or_default();
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("http://127.0.0.1:9000/poopman")
+                .default_value(current)
+        });
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let value = input.read(cx).value().trim().to_string();
+            let valid = value.is_empty() || crate::activity_webhook::is_usable_webhook_url(&value);
+            let (app, input_for_ok) = (app.clone(), input.clone());
+
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Activity Webhook"),
+                )
+                .w(px(460.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(Input::new(&input))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(if valid { theme.muted_foreground } else { theme.danger })
+                                .child(if valid {
+                                    "Each completed request is POSTed here as JSON: method, host, path, status \
+                                     and duration — never headers, query strings or bodies. Leave empty to turn off."
+                                } else {
+                                    "Enter an absolute http:// or https:// URL."
+                                }),
+                        ),
+                )
+                .confirm()
+                .on_ok(move |_, _window, cx| {
+                    let value = input_for_ok.read(cx).value().trim().to_string();
+                    if !value.is_empty() && !crate::activity_webhook::is_usable_webhook_url(&value) {
+                        return false;
+                    }
+                    app.update(cx, |app, _| app.set_activity_webhook_url((!value.is_empty()).then_some(value)));
+                    true
+                })
+        });
+    }
+
+    fn set_activity_webhook_url(&mut self, url: Option<String>) {
+        if let Err(e) = self.db.set_activity_webhook_url(url.as_deref()) {
+            log::error!("Failed to save activity webhook URL: {}", e);
+            return;
+        }
+        self.activity_webhook_url = url;
+    }
+
+    /// Edit → Log Level: takes effect at once and is kept for the next start.
+    pub(crate) fn set_log_level(&mut self, level: log::LevelFilter, cx: &mut Context<Self>) {
+        crate::request_log::set_level(level);
+        self.log_level = level;
+        log::info!("Log level set to {}", level);
+        if let Err(e) = self.db.set_log_level(level.as_str()) {
+            log::error!("Failed to save log level: {}", e);
+        }
+        cx.notify();
+    }
+
+    /// Report a completed send to the activity webhook, if one is set.
+    /// Fire-and-forget: the outcome is only logged.
+    fn report_activity(
+        &self,
+        history_id: Option<i64>,
+        request: &crate::types::RequestData,
+        response: &crate::types::ResponseData,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(url) = self.activity_webhook_url.clone() else {
+            return;
+        };
+        let event = crate::activity_webhook::ActivityEvent::completed(history_id, request, response);
+        cx.background_executor()
+            .spawn(crate::activity_webhook::deliver(url, event))
+            .detach();
+    }
+
+    fn set_proxy_options(&mut self, options: crate::http_client::ProxyOptions) {
+        if let Err(e) = self.db.set_proxy_options(&options) {
+            log::error!("Failed to save proxy options: {}", e);
+            return;
+        }
+        crate::http_client::set_proxy_options(options);
+    }
+
+    fn set_proxy_url(&mut self, url: Option<String>, cx: &mut Context<Self>) {
+        if let Err(e) = self.db.set_proxy_url(url.as_deref()) {
+            log::error!("Failed to save proxy URL: {}", e);
+            return;
+        }
+        self.request_editor.update(cx, |editor, cx| editor.set_proxy_url(url, cx));
+    }
+
+    /// Cap response bodies read into memory at `bytes` (0 = no limit).
+    pub(crate) fn set_body_limit(&mut self, bytes: u64, cx: &mut Context<Self>) {
+        crate::http_client::set_body_limit(bytes);
+        self.body_limit = bytes;
+        if let Err(e) = self.db.set_body_limit(bytes) {
+            log::error!("Failed to save response size limit: {}", e);
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn set_history_body_cap(&mut self, bytes: u64, cx: &mut Context<Self>) {
+        self.history_body_cap = bytes;
+        if let Err(e) = self.db.set_history_body_cap(bytes) {
+            log::error!("Failed to save history response size: {}", e);
+        }
+        cx.notify();
+    }
+
+    /// Pick the PEM file of a CA certificate to trust from now on.
+    pub(crate) fn choose_ca_cert(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let rx = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Select a CA certificate (PEM)".into()),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            if let Ok(Ok(Some(paths))) = rx.await
+                && let Some(path) = paths.first()
+            {
+                let path = path.to_string_lossy().to_string();
+                this.update(cx, |this, cx| this.set_ca_cert_path(Some(path), cx))?;
+            }
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Trust the CA certificate at `path`, or with `None` the system roots
+    /// only. An unusable file is reported by the requests sent with it.
+    pub(crate) fn set_ca_cert_path(&mut self, path: Option<String>, cx: &mut Context<Self>) {
+        if let Err(e) = self.db.set_ca_cert_path(path.as_deref()) {
+            log::error!("Failed to save CA certificate path: {}", e);
+            return;
+        }
+        crate::http_client::set_ca_cert_path(path.as_ref().map(Into::into));
+        log::info!("CA certificate set to {}", path.as_deref().unwrap_or("none"));
+        self.ca_cert_path = path;
+        cx.notify();
+    }
+
+    /// Pick a bundle file, then show what importing it would change.
+    pub(crate) fn import_workspace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let rx = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import workspace".into()),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = rx.await else {
+                return Ok(());
+            };
+            let Some(path) = paths.first() else {
+                return Ok(());
+            };
+            let bundle = std::fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| crate::workspace_bundle::parse_bundle(&json));
+            match bundle {
+                Ok(bundle) => this.update_in(cx, |this, window, cx| {
+                    this.open_import_preview(bundle, window, cx);
+                })?,
+                Err(e) => log::error!("Failed to import workspace from {:?}: {}", path, e),
+            }
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Pick a Postman v2.1 collection file and add its requests to History.
+    pub(crate) fn import_postman_collection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let rx = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import Postman collection".into()),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = rx.await else {
+                return Ok(());
+            };
+            let Some(path) = paths.first() else {
+                return Ok(());
+            };
+            let collection = std::fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| crate::postman_import::parse_collection(&json));
+            match collection {
+                Ok(collection) => this.update_in(cx, |this, window, cx| {
+                    this.apply_postman_import(collection, window, cx);
+                })?,
+                Err(e) => log::error!("Failed to import Postman collection from {:?}: {}", path, e),
+            }
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Pick a file written by "Export to File" (JSON) and open it in a tab.
+    pub(crate) fn import_request(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let rx = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import request".into()),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = rx.await else {
+                return Ok(());
+            };
+            let Some(path) = paths.first() else {
+                return Ok(());
+            };
+            let exported = std::fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| crate::request_export::parse_export(&json));
+            match exported {
+                Ok(exported) => this.update_in(cx, |this, window, cx| {
+                    this.open_in_new_tab(|id| RequestTab::from_export(id, exported), window, cx);
+                })?,
+                Err(e) => log::error!("Failed to import request from {:?}: {}", path, e),
+            }
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Store `collection` as a new collection, folders included, then show
+    /// what was imported and what was skipped.
+    fn apply_postman_import(
+        &mut self,
+        collection: crate::postman_import::ImportedCollection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let imported = collection.root.flatten().len();
+        let id = match self.db.import_collection(&collection.root) {
+            Ok(id) => id,
+            Err(e) => {
+                log::error!("Failed to import Postman collection {:?}: {}", collection.name, e);
+                return;
+            }
+        };
+        log::info!("Imported {} requests from Postman collection {:?}", imported, collection.name);
+        self.collections_panel.update(cx, |panel, cx| {
+            panel.reload(cx);
+            panel.reveal(id, cx);
+        });
+        self.sidebar = Sidebar::Collections;
+        cx.notify();
+
+        let skipped = collection.skipped;
+        let summary = format!(
+            "{} request{} from \u{201c}{}\u{201d} added to Collections.",
+            imported,
+            if imported == 1 { "" } else { "s" },
+            collection.name
+        );
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Postman collection imported"),
+                )
+                .w(px(520.))
+                .alert()
+                .child(
+                    v_flex()
+                        .gap_3()
+                        .child(div().text_sm().child(summary.clone()))
+                        .child(
+                            v_flex()
+                                .gap_0p5()
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .font_weight(gpui::FontWeight::BOLD)
+                                        .text_color(theme.muted_foreground)
+                                        .child("NOT IMPORTED"),
+                                )
+                                .child(
+                                    v_flex()
+                                        .id("postman-skipped")
+                                        .max_h(px(240.))
+                                        .overflow_y_scroll()
+                                        .children(if skipped.is_empty() {
+                                            vec![div().text_sm().child("Nothing — everything was supported")]
+                                        } else {
+                                            skipped.iter().map(|line| div().text_sm().child(line.clone())).collect()
+                                        }),
+                                ),
+                        ),
+                )
+        });
     }
 
-    /// Open the environment management dialog.
-    pub(crate) fn open_env_manager(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let manager = self.env_manager.clone();
+    /// Preview dialog for an import: new vs same-named environments, presets
+    /// and collections, with the choice to merge into or replace the current
+    /// workspace.
+    fn open_import_preview(&mut self, bundle: WorkspaceBundle, window: &mut Window, cx: &mut Context<Self>) {
+        let workspace = self.db.load_workspace().unwrap_or_default();
+        let preview = crate::workspace_bundle::preview_import(&bundle, &workspace);
+        let app = cx.entity();
+        let bundle = Arc::new(bundle);
+
         window.open_dialog(cx, move |dialog, _window, cx| {
             let theme = cx.theme();
+            let section = |title: &str, diff: &crate::workspace_bundle::NameDiff| {
+                let line = |label: &str, names: &[String]| {
+                    let names = if names.is_empty() { "none".to_string() } else { names.join(", ") };
+                    div().text_sm().child(format!("{label}: {names}"))
+                };
+                v_flex()
+                    .gap_0p5()
+                    .child(
+                        div()
+                            .text_xs()
+                            .font_weight(gpui::FontWeight::BOLD)
+                            .text_color(theme.muted_foreground)
+                            .child(title.to_uppercase()),
+                    )
+                    .child(line("New", &diff.added))
+                    .child(line("Same name as an existing one", &diff.conflicts))
+                    .child(line("Only here (removed by Replace all)", &diff.local_only))
+            };
+            let (app, bundle) = (app.clone(), bundle.clone());
+
             dialog
                 .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Import workspace"),
+                )
+                .w(px(520.))
+                .child(
                     v_flex()
-                        .gap_0p5()
-                        .child(
-                            div()
-                                .text_lg()
-                                .font_weight(gpui::FontWeight::BOLD)
-                                .text_color(theme.foreground)
-                                .child("Environments"),
-                        )
-                        .child(
-                            div()
-                                .text_xs()
-                                .text_color(theme.muted_foreground)
-                                .child("Define variables like {{base_url}} per environment"),
-                        ),
+                        .gap_3()
+                        .child(section("Environments", &preview.environments))
+                        .child(section("Presets", &preview.presets))
+                        .child(section("Collections", &preview.collections)),
                 )
-                .w(px(680.))
-                .child(manager.clone())
+                .footer(move |_ok, cancel, window, cx| {
+                    let import_button = |id: &'static str, label: &'static str, mode: ImportMode| {
+                        let (app, bundle) = (app.clone(), bundle.clone());
+                        Button::new(id)
+                            .label(label)
+                            .on_click(move |_, window, cx| {
+                                app.update(cx, |app, cx| app.apply_import(&bundle, mode, cx));
+                                window.close_dialog(cx);
+                            })
+                    };
+                    vec![
+                        cancel(window, cx),
+                        import_button("import-replace", "Replace all", ImportMode::Replace)
+                            .danger()
+                            .into_any_element(),
+                        import_button("import-merge", "Merge", ImportMode::Merge)
+                            .primary()
+                            .into_any_element(),
+                    ]
+                })
+        });
+    }
+
+    fn apply_import(&mut self, bundle: &WorkspaceBundle, mode: ImportMode, cx: &mut Context<Self>) {
+        let workspace = match self.db.load_workspace() {
+            Ok(workspace) => workspace,
+            Err(e) => {
+                log::error!("Failed to read workspace for import: {}", e);
+                return;
+            }
+        };
+        let plan = crate::workspace_bundle::plan_import(bundle, &workspace, mode);
+        if let Err(e) = self.db.apply_workspace_import(plan) {
+            log::error!("Failed to import workspace: {}", e);
+        }
+        // The settings the bundle carried take effect as if picked here.
+        if let Some(headers) = &bundle.settings.default_headers {
+            let headers = headers.clone();
+            self.request_editor.update(cx, |editor, cx| editor.set_default_headers(headers, cx));
+        }
+        if let Some(user_agent) = &bundle.settings.default_user_agent {
+            let user_agent = user_agent.clone();
+            self.request_editor.update(cx, |editor, cx| editor.set_default_user_agent(user_agent, cx));
+        }
+        if let Some(limit) = bundle.settings.body_limit {
+            self.body_limit = limit;
+            crate::http_client::set_body_limit(limit);
+        }
+        self.reload_presets(cx);
+        self.collections_panel.update(cx, |panel, cx| panel.reload(cx));
+        self.reload_environments(cx);
+        self.env_manager.update(cx, |mgr, cx| {
+            mgr.reload();
+            cx.notify();
         });
     }
 
@@ -320,18 +2767,35 @@ impl PoopmanApp {
 
     /// Save current editor state to active tab
     fn save_current_tab_state(&mut self, cx: &mut Context<Self>) {
-        if let Some(tab) = self.request_tabs.get_mut(self.active_tab_index) {
+        // WebSocket tabs log frames in the background too, so all of them
+        // are brought up to date; the editor holds none of their state.
+        for tab in &mut self.request_tabs {
+            if let Some(panel) = self.ws_panels.get(&tab.id) {
+                tab.ws = Some(panel.read(cx).state(cx));
+                tab.update_title();
+            }
+        }
+        if let Some(tab) = self.request_tabs.get_mut(self.active_tab_index)
+            && tab.ws.is_none()
+        {
             let request_data = self.request_editor.read(cx).get_current_request_data(cx);
             let params_state = self.request_editor.read(cx).get_params_state(cx);
             let headers_state = self.request_editor.read(cx).get_headers_state(cx);
             let response = self.response_viewer.read(cx).get_response();
+            let repeat_report = self.response_viewer.read(cx).get_repeat_report();
+            let body_view = self.response_viewer.read(cx).body_view();
 
+            tab.dirty = tab.differs_from_baseline(&request_data);
             tab.request = request_data;
             tab.response = response;
+            tab.repeat_report = repeat_report;
+            tab.body_view = body_view;
+            tab.auto_retry = self.request_editor.read(cx).auto_retry();
             tab.params_state = Some(params_state);
             tab.headers_state = Some(headers_state);
             tab.update_title();
         }
+        self.save_session();
     }
 
     /// Switch to a different tab
@@ -364,16 +2828,11 @@ impl PoopmanApp {
                 {
                     editor.load_headers_state(headers_state, window, cx);
                 }
+                editor.set_auto_retry(tab.auto_retry, cx);
             });
 
-            // Load response data
-            self.response_viewer.update(cx, |viewer, cx| {
-                if let Some(response) = &tab.response {
-                    viewer.set_response(response.clone(), window, cx);
-                } else {
-                    viewer.clear_response(window, cx);
-                }
-            });
+            self.show_tab_response(&tab, window, cx);
+            self.resume_due_retry(&tab, window, cx);
         }
 
         self.update_tab_bar(cx);
@@ -394,6 +2853,7 @@ impl PoopmanApp {
         // Load new tab into editor
         self.request_editor.update(cx, |editor, cx| {
             editor.load_request(&new_tab.request, window, cx);
+            editor.set_auto_retry(new_tab.auto_retry, cx);
         });
 
         // Clear response for new tab
@@ -401,26 +2861,154 @@ impl PoopmanApp {
             viewer.clear_response(window, cx);
         });
 
+        self.refresh_start_panel(cx);
+        self.update_tab_bar(cx);
+        cx.notify();
+    }
+
+    /// Open an empty WebSocket tab.
+    fn create_new_ws_tab(&mut self, cx: &mut Context<Self>) {
+        self.save_current_tab_state(cx);
+        self.request_tabs.push(RequestTab::new_websocket(self.next_tab_id));
+        self.next_tab_id += 1;
+        self.active_tab_index = self.request_tabs.len() - 1;
+        self.update_tab_bar(cx);
+        cx.notify();
+    }
+
+    /// The active tab's WebSocket panel, made on first use; `None` on an
+    /// HTTP tab.
+    fn active_ws_panel(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Option<Entity<WsPanel>> {
+        let tab = self.request_tabs.get(self.active_tab_index)?;
+        let state = tab.ws.as_ref()?;
+        let panel = self
+            .ws_panels
+            .entry(tab.id)
+            .or_insert_with(|| cx.new(|cx| WsPanel::new(state, window, cx)));
+        Some(panel.clone())
+    }
+
+    fn refresh_start_panel(&mut self, cx: &mut Context<Self>) {
+        let db = self.db.clone();
+        self.start_panel.update(cx, |panel, cx| panel.refresh(db, cx));
+    }
+
+    /// Put a closed tab on the start panel's "Recently closed" list.
+    fn remember_closed_tab(&mut self, tab: RequestTab, cx: &mut Context<Self>) {
+        remember_closed(&mut self.recently_closed, tab);
+        let closed = self.recently_closed.clone();
+        self.start_panel.update(cx, |panel, cx| panel.set_closed(&closed, cx));
+    }
+
+    /// Reopen the `index`-th recently closed tab, in place of the active tab
+    /// when that one is blank (it is whenever the start panel is showing).
+    fn reopen_closed_tab(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if index >= self.recently_closed.len() {
+            return;
+        }
+        let mut tab = self.recently_closed.remove(index);
+        self.save_current_tab_state(cx);
+
+        if self
+            .request_tabs
+            .get(self.active_tab_index)
+            .is_some_and(RequestTab::is_blank)
+        {
+            tab.id = self.request_tabs[self.active_tab_index].id;
+            self.request_tabs[self.active_tab_index] = tab.clone();
+        } else {
+            tab.id = self.next_tab_id;
+            self.next_tab_id += 1;
+            self.request_tabs.push(tab.clone());
+            self.active_tab_index = self.request_tabs.len() - 1;
+        }
+
+        self.request_editor.update(cx, |editor, cx| {
+            editor.load_request(&tab.request, window, cx);
+            if let Some(params_state) = &tab.params_state
+                && !params_state.is_empty()
+            {
+                editor.load_params_state(params_state, window, cx);
+            }
+            if let Some(headers_state) = &tab.headers_state
+                && !headers_state.is_empty()
+            {
+                editor.load_headers_state(headers_state, window, cx);
+            }
+            editor.set_auto_retry(tab.auto_retry, cx);
+        });
+        self.show_tab_response(&tab, window, cx);
+
+        let closed = self.recently_closed.clone();
+        self.start_panel.update(cx, |panel, cx| panel.set_closed(&closed, cx));
         self.update_tab_bar(cx);
         cx.notify();
     }
 
-    /// Close a tab
+    /// Close a tab, asking first when it is pinned.
     fn close_tab(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tab) = self.request_tabs.get(index) else {
+            return;
+        };
+        if !tab.pinned {
+            self.close_tab_now(index, window, cx);
+            return;
+        }
+        let message = format!("\u{201c}{}\u{201d} is pinned. Close it anyway?", tab.title);
+        let id = tab.id;
+        let app = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let theme = cx.theme();
+            let app = app.clone();
+            dialog
+                .title(
+                    div()
+                        .text_lg()
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(theme.foreground)
+                        .child("Close pinned tab"),
+                )
+                .w(px(420.))
+                .child(div().text_sm().child(message.clone()))
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("Close"))
+                .on_ok(move |_, window, cx| {
+                    app.update(cx, |app, cx| {
+                        // Looked up again: the tab may have moved meanwhile.
+                        if let Some(index) = app.request_tabs.iter().position(|t| t.id == id) {
+                            app.close_tab_now(index, window, cx);
+                        }
+                    });
+                    true
+                })
+        });
+    }
+
+    fn close_tab_now(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        // The editor may hold edits the tab hasn't seen yet; the closed copy
+        // should reopen with them.
+        if index == self.active_tab_index {
+            self.save_current_tab_state(cx);
+        }
         if self.request_tabs.len() <= 1 {
             // Don't close the last tab, just reset it to empty
-            self.request_tabs[0] = RequestTab::new_empty(self.next_tab_id);
+            let closed = std::mem::replace(&mut self.request_tabs[0], RequestTab::new_empty(self.next_tab_id));
+            self.remember_closed_tab(closed, cx);
             self.next_tab_id += 1;
             self.active_tab_index = 0;
 
+            self.retry_timers.clear();
             self.request_editor.update(cx, |editor, cx| {
                 editor.load_request(&self.request_tabs[0].request, window, cx);
+                editor.set_auto_retry(false, cx);
             });
 
             // Clear response for reset tab
             self.response_viewer.update(cx, |viewer, cx| {
                 viewer.clear_response(window, cx);
             });
+            self.refresh_start_panel(cx);
 
             self.update_tab_bar(cx);
             cx.notify();
@@ -428,7 +3016,9 @@ impl PoopmanApp {
         }
 
         // Remove the tab
-        self.request_tabs.remove(index);
+        let closed = self.request_tabs.remove(index);
+        self.retry_timers.remove(&closed.id);
+        self.remember_closed_tab(closed, cx);
 
         // Adjust active tab index
         if index < self.active_tab_index {
@@ -443,16 +3033,11 @@ impl PoopmanApp {
             if let Some(tab) = self.request_tabs.get(self.active_tab_index).cloned() {
                 self.request_editor.update(cx, |editor, cx| {
                     editor.load_request(&tab.request, window, cx);
+                    editor.set_auto_retry(tab.auto_retry, cx);
                 });
 
-                // Load response for the new active tab
-                self.response_viewer.update(cx, |viewer, cx| {
-                    if let Some(response) = &tab.response {
-                        viewer.set_response(response.clone(), window, cx);
-                    } else {
-                        viewer.clear_response(window, cx);
-                    }
-                });
+                self.show_tab_response(&tab, window, cx);
+                self.resume_due_retry(&tab, window, cx);
             }
         }
 
@@ -460,6 +3045,48 @@ impl PoopmanApp {
         cx.notify();
     }
 
+    /// Close every tab but the one at `keep` and the pinned ones; `keep`
+    /// becomes the active tab.
+    fn close_other_tabs(&mut self, keep: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if keep >= self.request_tabs.len() {
+            return;
+        }
+        // Switch first, so every close below is of an inactive tab.
+        self.switch_to_tab(keep, window, cx);
+        for index in (0..self.request_tabs.len()).rev().filter(|&index| index != keep) {
+            if !self.request_tabs[index].pinned {
+                self.close_tab_now(index, window, cx);
+            }
+        }
+    }
+
+    /// Close every unpinned tab after the one at `from`; if the active tab is
+    /// among them, the tab at `from` becomes active.
+    fn close_tabs_to_the_right(&mut self, from: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if from >= self.request_tabs.len() {
+            return;
+        }
+        if self.active_tab_index > from && !self.request_tabs[self.active_tab_index].pinned {
+            self.switch_to_tab(from, window, cx);
+        }
+        for index in (from + 1..self.request_tabs.len()).rev() {
+            if !self.request_tabs[index].pinned {
+                self.close_tab_now(index, window, cx);
+            }
+        }
+    }
+
+    /// Pin the tab at `index` (it joins the pinned tabs at the front of the
+    /// strip) or unpin it.
+    fn set_tab_pinned(&mut self, index: usize, pinned: bool, cx: &mut Context<Self>) {
+        if let Some(tab) = self.request_tabs.get_mut(index) {
+            tab.pinned = pinned;
+            self.update_tab_bar(cx);
+            self.save_session();
+            cx.notify();
+        }
+    }
+
     /// Open history item in a new tab (or switch to existing tab if already open)
     fn open_history_in_new_tab(
         &mut self,
@@ -478,6 +3105,54 @@ impl PoopmanApp {
             return;
         }
 
+        let item = self.with_stored_response(item);
+        self.open_in_new_tab(|id| RequestTab::from_history(id, &item), window, cx);
+    }
+
+    /// `item` with its stored response: the list carries only the status.
+    fn with_stored_response(&self, item: &crate::types::HistoryItem) -> crate::types::HistoryItem {
+        let mut item = item.clone();
+        if item.response.is_none() {
+            match self.db.load_history_response(item.id) {
+                Ok(response) => item.response = response.map(Arc::new),
+                Err(e) => log::error!("Failed to load stored response: {}", e),
+            }
+        }
+        item
+    }
+
+    /// Open a copy of the tab at `index` next to it, detached from the
+    /// history row or saved request the original came from.
+    fn duplicate_tab(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.save_current_tab_state(cx);
+        let Some(tab) = self.request_tabs.get(index).cloned() else {
+            return;
+        };
+        self.open_in_new_tab(|id| tab.duplicate(id), window, cx);
+    }
+
+    /// Open a saved request in a new tab (or switch to the tab it is already
+    /// open in).
+    fn open_saved_in_new_tab(&mut self, saved: &crate::types::SavedRequest, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(existing_index) = self
+            .request_tabs
+            .iter()
+            .position(|tab| tab.saved_request_id == Some(saved.id))
+        {
+            self.switch_to_tab(existing_index, window, cx);
+            return;
+        }
+        self.open_in_new_tab(|id| RequestTab::from_saved(id, saved), window, cx);
+    }
+
+    /// Put the tab `make` builds (given its id) in front, filling the active
+    /// tab in place when that one is blank.
+    fn open_in_new_tab(
+        &mut self,
+        make: impl FnOnce(usize) -> RequestTab,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         // Save current tab state
         self.save_current_tab_state(cx);
 
@@ -489,11 +3164,11 @@ impl PoopmanApp {
             .is_some_and(RequestTab::is_blank)
         {
             let id = self.request_tabs[self.active_tab_index].id;
-            let tab = RequestTab::from_history(id, item);
+            let tab = make(id);
             self.request_tabs[self.active_tab_index] = tab.clone();
             tab
         } else {
-            let tab = RequestTab::from_history(self.next_tab_id, item);
+            let tab = make(self.next_tab_id);
             self.next_tab_id += 1;
             self.request_tabs.push(tab.clone());
             self.active_tab_index = self.request_tabs.len() - 1;
@@ -503,54 +3178,275 @@ impl PoopmanApp {
         // Load into editor
         self.request_editor.update(cx, |editor, cx| {
             editor.load_request(&new_tab.request, window, cx);
+            editor.set_auto_retry(new_tab.auto_retry, cx);
         });
+        // A tab with a source starts out clean. The baseline is read back from
+        // the editor, which fills in its predefined header rows on load.
+        if new_tab.history_id.is_some() || new_tab.saved_request_id.is_some() {
+            let loaded = self.request_editor.read(cx).get_current_request_data(cx);
+            self.request_tabs[self.active_tab_index].set_baseline(loaded);
+        }
+
+        self.show_tab_response(&new_tab, window, cx);
+
+        self.update_tab_bar(cx);
+        cx.notify();
+    }
 
-        // Load response from history
+    /// Put a tab's response (or its "Send ×N" report) into the viewer, or
+    /// clear the viewer when the tab has neither.
+    fn show_tab_response(&self, tab: &RequestTab, window: &mut Window, cx: &mut Context<Self>) {
         self.response_viewer.update(cx, |viewer, cx| {
-            if let Some(response) = &new_tab.response {
-                viewer.set_response(response.clone(), window, cx);
+            viewer.restore_body_view(tab.body_view);
+            if let Some(response) = &tab.response {
+                viewer.set_response(response.clone(), tab.sent_request.clone(), window, cx);
+                viewer.set_retry(tab.retry, cx);
+            } else if let Some(report) = &tab.repeat_report {
+                viewer.set_repeat_report(report.clone(), window, cx);
             } else {
                 viewer.clear_response(window, cx);
             }
         });
+    }
+
+    /// After a send on the active tab completes: a 429 with `Retry-After`
+    /// starts the countdown (auto-retry on and fewer than `MAX_AUTO_RETRIES`
+    /// 429s in a row) or only offers it; any other response ends the wait.
+    fn handle_rate_limit(&mut self, response: &crate::types::ResponseData, window: &mut Window, cx: &mut Context<Self>) {
+        let auto = self.request_editor.read(cx).auto_retry();
+        let Some(tab) = self.request_tabs.get_mut(self.active_tab_index) else {
+            return;
+        };
+        self.retry_timers.remove(&tab.id);
+        tab.retry = retry_delay(response, chrono::Utc::now()).map(|delay| PendingRetry { delay, deadline: None });
+        let Some(retry) = tab.retry else {
+            return;
+        };
+        if auto && tab.rate_limit_streak() <= MAX_AUTO_RETRIES as usize {
+            log::info!(
+                "Rate limited (tab {}): retrying in {}s, attempt {}/{}",
+                tab.id,
+                retry.delay.as_secs(),
+                tab.rate_limit_streak(),
+                MAX_AUTO_RETRIES
+            );
+            let tab_id = tab.id;
+            self.start_retry(tab_id, window, cx);
+        } else {
+            log::info!("Rate limited (tab {}): retry offered in {}s", tab.id, retry.delay.as_secs());
+            self.response_viewer.update(cx, |viewer, cx| viewer.set_retry(Some(retry), cx));
+        }
+    }
+
+    /// Start counting down a tab's pending retry.
+    fn start_retry(&mut self, tab_id: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(index) = self.request_tabs.iter().position(|t| t.id == tab_id) else {
+            return;
+        };
+        let Some(retry) = self.request_tabs[index].retry.as_mut() else {
+            return;
+        };
+        retry.deadline = Some(Instant::now() + retry.delay);
+        let retry = *retry;
+
+        let timer = cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(retry.delay).await;
+            this.update_in(cx, |this, window, cx| this.fire_retry(tab_id, window, cx)).ok();
+        });
+        self.retry_timers.insert(tab_id, timer);
+        if index == self.active_tab_index {
+            self.response_viewer.update(cx, |viewer, cx| viewer.set_retry(Some(retry), cx));
+        }
+    }
+
+    /// Re-send a tab's request now. Only the active tab can send (the editor
+    /// holds its request); a background tab keeps the due retry and sends it
+    /// when it is shown again.
+    fn fire_retry(&mut self, tab_id: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.retry_timers.remove(&tab_id);
+        let Some(tab) = self.request_tabs.get_mut(self.active_tab_index) else {
+            return;
+        };
+        if tab.id != tab_id || tab.retry.take().is_none() {
+            return;
+        }
+        log::info!("Retrying request (tab {})", tab_id);
+        self.response_viewer.update(cx, |viewer, cx| viewer.set_retry(None, cx));
+        self.request_editor.update(cx, |editor, cx| editor.send_current(window, cx));
+    }
+
+    fn cancel_retry(&mut self, tab_id: usize, cx: &mut Context<Self>) {
+        if self.retry_timers.remove(&tab_id).is_some() {
+            log::info!("Retry canceled (tab {})", tab_id);
+        }
+        if let Some(tab) = self.request_tabs.iter_mut().find(|t| t.id == tab_id) {
+            tab.retry = None;
+        }
+        self.response_viewer.update(cx, |viewer, cx| viewer.set_retry(None, cx));
+    }
+
+    /// A retry that came due while its tab was in the background.
+    fn resume_due_retry(&mut self, tab: &RequestTab, window: &mut Window, cx: &mut Context<Self>) {
+        if tab.retry.is_some_and(|r| r.is_due(Instant::now())) {
+            self.fire_retry(tab.id, window, cx);
+        }
+    }
+
+    /// History / Collections pills at the top of the sidebar card.
+    fn render_sidebar_switch(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let pill = |view: Sidebar, id: &'static str, label: &'static str| {
+            crate::ui::segment_pill(theme, self.sidebar == view)
+                .id(id)
+                .flex_1()
+                .text_center()
+                .child(label)
+                .on_click(cx.listener(move |this, _, _window, cx| {
+                    this.sidebar = view;
+                    cx.notify();
+                }))
+        };
+        div().px_2().pt_2().child(
+            crate::ui::segmented_bar(theme)
+                .child(pill(Sidebar::History, "sidebar-history", "History"))
+                .child(pill(Sidebar::Collections, "sidebar-collections", "Collections")),
+        )
+    }
+
+    /// Re-check the active tab against its baseline once edits pause.
+    fn schedule_dirty_check(&mut self, cx: &mut Context<Self>) {
+        if self.request_tabs.get(self.active_tab_index).is_none_or(|tab| tab.baseline.is_none()) {
+            return;
+        }
+        self.dirty_check = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(DIRTY_CHECK_DELAY).await;
+            let _ = this.update(cx, |this, cx| {
+                let request = this.request_editor.read(cx).get_current_request_data(cx);
+                if let Some(tab) = this.request_tabs.get_mut(this.active_tab_index) {
+                    let dirty = tab.differs_from_baseline(&request);
+                    if tab.dirty != dirty {
+                        tab.dirty = dirty;
+                        this.update_tab_bar(cx);
+                    }
+                }
+            });
+        }));
+    }
 
+    /// Move the tab at `from` to `to`, shifting the tabs in between; the
+    /// active tab stays active wherever it ends up.
+    fn move_tab(&mut self, from: usize, to: usize, cx: &mut Context<Self>) {
+        let len = self.request_tabs.len();
+        if from == to || from >= len || to >= len {
+            return;
+        }
+        let tab = self.request_tabs.remove(from);
+        self.request_tabs.insert(to, tab);
+        self.active_tab_index = index_after_move(self.active_tab_index, from, to);
         self.update_tab_bar(cx);
         cx.notify();
     }
 
-    /// Update tab bar with current tabs
+    /// Whether a dialog or sheet is up. Every app shortcut stays off then,
+    /// so Ctrl+W cannot close the tab a dialog is about.
+    fn shortcuts_blocked(window: &mut Window, cx: &mut App) -> bool {
+        window.has_active_dialog(cx) || window.has_active_sheet(cx)
+    }
+
+    /// Update tab bar with current tabs, pinned ones first. Every change to
+    /// the tab set comes through here, so this is where the order is kept.
     fn update_tab_bar(&mut self, cx: &mut Context<Self>) {
+        self.active_tab_index = pinned_first(&mut self.request_tabs, self.active_tab_index);
         self.tab_bar.update(cx, |tab_bar, cx| {
             tab_bar.update_tabs(self.request_tabs.clone(), self.active_tab_index, cx);
         });
+        // Closing a WebSocket tab drops its panel, and so its connection.
+        let tabs = &self.request_tabs;
+        self.ws_panels.retain(|id, _| tabs.iter().any(|tab| tab.id == *id));
     }
 }
 
 impl Render for PoopmanApp {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // A blank tab has nothing to show in the response area; offer the
+        // start panel's shortcuts there until a URL is typed.
+        let show_start_panel = self.url_empty && {
+            let viewer = self.response_viewer.read(cx);
+            viewer.get_response().is_none() && viewer.get_repeat_report().is_none()
+        };
+        let ws_panel = self.active_ws_panel(window, cx);
         let theme = cx.theme();
 
         v_flex()
             .key_context("Poopman")
             .on_action(cx.listener(|this, _: &SendRequest, window, cx| {
-                this.request_editor.update(cx, |editor, cx| editor.send(window, cx));
+                if Self::shortcuts_blocked(window, cx) {
+                    return;
+                }
+                match this.active_ws_panel(window, cx) {
+                    Some(panel) => panel.update(cx, |panel, cx| panel.send_message(cx)),
+                    None => this.request_editor.update(cx, |editor, cx| editor.send_current(window, cx)),
+                }
+            }))
+            .on_action(cx.listener(|this, _: &SaveRequest, window, cx| {
+                if Self::shortcuts_blocked(window, cx) {
+                    return;
+                }
+                this.save_request(window, cx);
             }))
             .on_action(cx.listener(|this, _: &NewTab, window, cx| {
+                if Self::shortcuts_blocked(window, cx) {
+                    return;
+                }
                 this.create_new_tab(window, cx);
             }))
             .on_action(cx.listener(|this, _: &CloseTab, window, cx| {
+                if Self::shortcuts_blocked(window, cx) {
+                    return;
+                }
                 let index = this.active_tab_index;
                 this.close_tab(index, window, cx);
             }))
             .on_action(cx.listener(|this, _: &NextTab, window, cx| {
+                if Self::shortcuts_blocked(window, cx) {
+                    return;
+                }
                 let next = cycle_index(this.active_tab_index, this.request_tabs.len(), true);
                 this.switch_to_tab(next, window, cx);
             }))
             .on_action(cx.listener(|this, _: &PrevTab, window, cx| {
+                if Self::shortcuts_blocked(window, cx) {
+                    return;
+                }
                 let prev = cycle_index(this.active_tab_index, this.request_tabs.len(), false);
                 this.switch_to_tab(prev, window, cx);
             }))
+            .on_action(cx.listener(|this, action: &ActivateTab, window, cx| {
+                if Self::shortcuts_blocked(window, cx) {
+                    return;
+                }
+                this.switch_to_tab(action.index, window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &MoveTabLeft, window, cx| {
+                if Self::shortcuts_blocked(window, cx) {
+                    return;
+                }
+                let index = this.active_tab_index;
+                if index > 0 {
+                    this.move_tab(index, index - 1, cx);
+                }
+            }))
+            .on_action(cx.listener(|this, _: &MoveTabRight, window, cx| {
+                if Self::shortcuts_blocked(window, cx) {
+                    return;
+                }
+                let index = this.active_tab_index;
+                this.move_tab(index, index + 1, cx);
+            }))
             .on_action(cx.listener(|this, _: &FocusUrl, window, cx| {
+                if Self::shortcuts_blocked(window, cx) {
+                    return;
+                }
                 this.request_editor.update(cx, |editor, cx| editor.focus_url(window, cx));
             }))
             .size_full()
@@ -575,6 +3471,10 @@ impl Render for PoopmanApp {
                             cx.entity(),
                             self.environments.clone(),
                             self.active_environment_id,
+                            self.env_overrides.len(),
+                            self.log_level,
+                            self.ca_cert_path.clone(),
+                            (self.body_limit, self.history_body_cap),
                         )),
                 ),
             )
@@ -603,15 +3503,21 @@ impl Render for PoopmanApp {
                     .child(
                         h_resizable("history-main-splitter")
                             .child(
-                                // Left: History panel with resizable width
+                                // Left: History / Collections with resizable width
                                 resizable_panel()
                                     .size(px(SIDEBAR_WIDTH))
                                     .size_range(px(SIDEBAR_MIN)..px(SIDEBAR_MAX))
                                     .child(
                                         crate::ui::card_panel(theme)
                                             .size_full()
+                                            .flex()
+                                            .flex_col()
                                             .on_scroll_wheel(|_, _, cx| cx.stop_propagation())
-                                            .child(self.history_panel.clone()),
+                                            .child(self.render_sidebar_switch(cx))
+                                            .child(div().flex_1().min_h_0().child(match self.sidebar {
+                                                Sidebar::History => self.history_panel.clone().into_any_element(),
+                                                Sidebar::Collections => self.collections_panel.clone().into_any_element(),
+                                            })),
                                     ),
                             )
                             .child(
@@ -636,7 +3542,11 @@ impl Render for PoopmanApp {
                                                 .child(div().flex_1().min_w_0().child(self.tab_bar.clone())),
                                         ),
                                     )
-                                    .child(
+                                    .when_some(ws_panel.clone(), |this, panel| {
+                                        // A WebSocket tab's panel takes the whole area
+                                        this.child(crate::ui::card_panel(theme).flex_1().w_full().min_h_0().child(panel))
+                                    })
+                                    .when(ws_panel.is_none(), |this| this.child(
                                         // Request editor and response viewer with resizable splitter
                                         // w_full keeps this host's width definite, so the
                                         // width:100% that ResizablePanelGroup and
@@ -670,11 +3580,17 @@ impl Render for PoopmanApp {
                                                         .flex_1()
                                                         .min_h(px(200.))
                                                         .mt(px(10.))
-                                                        .child(self.response_viewer.clone())
+                                                        .map(|this| {
+                                                            if show_start_panel {
+                                                                this.child(self.start_panel.clone())
+                                                            } else {
+                                                                this.child(self.response_viewer.clone())
+                                                            }
+                                                        })
                                                         .into_any_element(),
                                                 ),
                                         ),
-                                    )
+                                    ))
                                     .into_any_element(),
                             ),
                     ),
@@ -700,11 +3616,34 @@ fn cycle_index(current: usize, len: usize, forward: bool) -> usize {
     }
 }
 
+/// Move pinned tabs in front of the others, keeping the order within each
+/// group, and return where the tab at `active` ended up.
+fn pinned_first(tabs: &mut [RequestTab], active: usize) -> usize {
+    let active_id = tabs.get(active).map(|tab| tab.id);
+    tabs.sort_by_key(|tab| !tab.pinned);
+    active_id
+        .and_then(|id| tabs.iter().position(|tab| tab.id == id))
+        .unwrap_or(active)
+}
+
+/// Where the tab at `index` ends up after the tab at `from` moves to `to`.
+fn index_after_move(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < index && index <= to {
+        index - 1
+    } else if to <= index && index < from {
+        index + 1
+    } else {
+        index
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // NOT `use super::*`: that would pull in `gpui::*`, whose `test` attribute
     // macro shadows the standard `#[test]`.
-    use super::cycle_index;
+    use super::{cycle_index, index_after_move, pinned_first, RequestTab, StartupState};
 
     #[test]
     fn steps_forward_through_the_middle_of_the_list() {
@@ -734,6 +3673,30 @@ mod tests {
         assert_eq!(cycle_index(0, 1, false), 0);
     }
 
+    #[test]
+    fn moving_a_tab_shifts_the_ones_it_passes() {
+        // [a, b, c, d]: b moves to the end -> [a, c, d, b].
+        assert_eq!(index_after_move(1, 1, 3), 3);
+        assert_eq!(index_after_move(0, 1, 3), 0);
+        assert_eq!(index_after_move(2, 1, 3), 1);
+        assert_eq!(index_after_move(3, 1, 3), 2);
+        // [a, b, c, d]: d moves to the front -> [d, a, b, c].
+        assert_eq!(index_after_move(3, 3, 0), 0);
+        assert_eq!(index_after_move(0, 3, 0), 1);
+        assert_eq!(index_after_move(2, 3, 0), 3);
+    }
+
+    #[test]
+    fn pinned_tabs_go_first_and_the_active_tab_is_followed() {
+        let mut tabs: Vec<RequestTab> = (0..4).map(RequestTab::new_empty).collect();
+        tabs[2].pinned = true;
+        tabs[3].pinned = true;
+        let active = pinned_first(&mut tabs, 1);
+        assert_eq!(tabs.iter().map(|t| t.id).collect::<Vec<_>>(), vec![2, 3, 0, 1]);
+        assert_eq!(active, 3);
+        assert_eq!(pinned_first(&mut tabs, 3), 3, "already in order");
+    }
+
     #[test]
     fn empty_list_returns_current_without_panicking() {
         assert_eq!(cycle_index(0, 0, true), 0);
@@ -743,6 +3706,44 @@ mod tests {
     // Postman behavior: EVERY send is logged to History, including a re-send of a
     // request opened from history. A previous `!is_from_history` gate silently
     // dropped edits (e.g. added auth) made to a restored request.
+    #[test]
+    fn startup_state_defaults_until_the_database_answers() {
+        // What the app runs on before `apply_startup_state`.
+        let state = StartupState::default();
+        assert!(state.environments.is_empty());
+        assert_eq!((state.proxy_url, state.split_editor), (None, false));
+        assert_eq!(state.default_headers, None);
+
+        let db = crate::db::Database::new_in_memory();
+        db.set_proxy_url(Some("http://127.0.0.1:8888")).unwrap();
+        db.set_split_editor(true).unwrap();
+        db.set_default_headers(&[crate::types::PredefinedHeader::Accept]).unwrap();
+        db.set_default_user_agent(Some("acme-monitor/2")).unwrap();
+        db.set_ca_cert_path(Some("/etc/corp/root-ca.pem")).unwrap();
+        let overrides = crate::host_overrides::parse("api.example.com 10.0.0.5").unwrap();
+        db.set_host_overrides(&overrides).unwrap();
+        db.create_environment("dev").unwrap();
+        let state = StartupState::load(&db);
+        assert_eq!(state.proxy_url.as_deref(), Some("http://127.0.0.1:8888"));
+        assert_eq!(state.ca_cert_path.as_deref(), Some("/etc/corp/root-ca.pem"));
+        assert_eq!(state.host_overrides, overrides);
+        assert!(state.split_editor);
+        assert_eq!(state.default_headers, Some(vec![crate::types::PredefinedHeader::Accept]));
+        assert_eq!(state.default_user_agent.as_deref(), Some("acme-monitor/2"));
+        assert_eq!(state.environments.len(), 1);
+    }
+
+    #[test]
+    fn startup_state_brings_back_only_kept_overrides() {
+        let db = crate::db::Database::new_in_memory();
+        assert_eq!(StartupState::load(&db).kept_env_overrides, None);
+
+        let mut overrides = crate::env_overrides::EnvOverrides::default();
+        overrides.set("base_url", "http://localhost:3000");
+        db.set_kept_env_overrides(Some(&overrides)).unwrap();
+        assert_eq!(StartupState::load(&db).kept_env_overrides, Some(overrides));
+    }
+
     #[test]
     fn every_send_appends_history_including_a_resend() {
         use super::PoopmanApp;
@@ -764,7 +3765,7 @@ mod tests {
         };
         PoopmanApp::persist_send(&db, &edited).unwrap();
 
-        let items = db.load_recent_history(10).unwrap();
+        let items = db.load_recent_history(10, 0).unwrap();
         assert_eq!(items.len(), 2, "each send must append its own history row");
         // Newest first: the edited re-send carries the Bearer auth...
         assert_eq!(items[0].request.auth.auth_type, AuthType::Bearer);
@@ -772,4 +3773,82 @@ mod tests {
         // ...and the original row is untouched.
         assert_eq!(items[1].request.auth.auth_type, AuthType::None);
     }
+
+    #[test]
+    fn history_keeps_the_path_template_and_its_values() {
+        use super::PoopmanApp;
+        use crate::db::Database;
+        use crate::types::{HttpMethod, RequestData};
+
+        let db = Database::new_in_memory();
+        let mut request = RequestData::new(HttpMethod::GET, "https://api.test/users/:id".to_string());
+        request.path_variables = vec![("id".to_string(), "7".to_string())];
+        PoopmanApp::persist_send(&db, &request).unwrap();
+
+        let recorded = &db.load_recent_history(10, 0).unwrap()[0].request;
+        assert_eq!(recorded.url, "https://api.test/users/:id");
+        assert_eq!(recorded.path_variables, request.path_variables);
+        let vars = std::collections::HashMap::new();
+        let opts = crate::request_builder::SendOptions { default_headers: vec![], presets: &[], vars: &vars };
+        assert_eq!(crate::request_builder::wire(recorded, &opts).unwrap().url, "https://api.test/users/7");
+    }
+
+    #[test]
+    fn history_keeps_a_get_body_and_whether_it_was_sent_anyway() {
+        use super::PoopmanApp;
+        use crate::db::Database;
+        use crate::types::{BodyType, HttpMethod, RawSubtype, RequestData};
+
+        let db = Database::new_in_memory();
+        let mut search = RequestData::new(HttpMethod::GET, "https://es.test/_search".to_string());
+        search.body = BodyType::Raw { content: r#"{"query":{}}"#.into(), subtype: RawSubtype::Json };
+        PoopmanApp::persist_send(&db, &search).unwrap();
+        search.send_body_anyway = true;
+        PoopmanApp::persist_send(&db, &search).unwrap();
+
+        let vars = std::collections::HashMap::new();
+        let opts = crate::request_builder::SendOptions { default_headers: vec![], presets: &[], vars: &vars };
+        let items = db.load_recent_history(10, 0).unwrap();
+        // Both keep the body; only the one sent anyway sends it again.
+        assert!(items.iter().all(|item| item.request.body == search.body));
+        assert!(items[0].request.send_body_anyway);
+        assert_eq!(crate::request_builder::wire(&items[0].request, &opts).unwrap().body, search.body);
+        assert!(!items[1].request.send_body_anyway);
+        assert_eq!(crate::request_builder::wire(&items[1].request, &opts).unwrap().body, BodyType::None);
+    }
+
+    #[test]
+    fn history_refers_to_presets_instead_of_keeping_their_headers() {
+        use super::PoopmanApp;
+        use crate::db::Database;
+        use crate::request_builder::{build, wire, SendOptions};
+        use crate::types::{HttpMethod, Preset, RequestData};
+
+        let db = Database::new_in_memory();
+        let hs = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        let mut preset =
+            Preset { id: 3, name: "Tenant".into(), headers: hs(&[("X-Tenant", "{{tenant}}")]), params: hs(&[("v", "1")]) };
+        let vars = std::collections::HashMap::from([("tenant".to_string(), "acme".to_string())]);
+        let mut request = RequestData::new(HttpMethod::GET, "https://api.test/items".to_string());
+        request.headers = hs(&[("X-Trace", "1")]);
+        request.presets = vec![3];
+        let opts = SendOptions { default_headers: vec![], presets: std::slice::from_ref(&preset), vars: &vars };
+        let built = build(&request, &opts).unwrap();
+        assert_eq!(built.sent.url, "https://api.test/items?v=1");
+        assert_eq!(built.sent.headers, hs(&[("X-Tenant", "acme"), ("X-Trace", "1")]));
+        PoopmanApp::persist_send(&db, &built.request).unwrap();
+
+        // Only the user's own header and the preset's id are recorded...
+        let recorded = &db.load_recent_history(10, 0).unwrap()[0].request;
+        assert_eq!(recorded.url, "https://api.test/items");
+        assert_eq!(recorded.headers, hs(&[("X-Trace", "1")]));
+        assert_eq!(recorded.presets, vec![3]);
+
+        // ...so sending it again applies the preset as it is now.
+        preset.headers = hs(&[("X-Tenant", "globex")]);
+        let opts = SendOptions { default_headers: vec![], presets: std::slice::from_ref(&preset), vars: &vars };
+        assert_eq!(wire(recorded, &opts).unwrap().headers, hs(&[("X-Tenant", "globex"), ("X-Trace", "1")]));
+    }
 }
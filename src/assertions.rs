@@ -0,0 +1,295 @@
+//! The Tests tab: checks declared on a request, without scripting, and run
+//! against each response it gets. Saved with the request (tabs, history,
+//! collections); evaluated by the response viewer. Pure.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::ResponseData;
+
+/// What an assertion looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    #[default]
+    StatusEquals,
+    /// `200-299`, or `2xx`.
+    StatusInRange,
+    HeaderEquals,
+    HeaderContains,
+    BodyContains,
+    /// The value the path selects; several matches compare as an array.
+    JsonPathEquals,
+    TimeUnder,
+}
+
+impl Check {
+    pub const ALL: [Check; 7] = [
+        Check::StatusEquals,
+        Check::StatusInRange,
+        Check::HeaderEquals,
+        Check::HeaderContains,
+        Check::BodyContains,
+        Check::JsonPathEquals,
+        Check::TimeUnder,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Check::StatusEquals => "Status equals",
+            Check::StatusInRange => "Status in range",
+            Check::HeaderEquals => "Header equals",
+            Check::HeaderContains => "Header contains",
+            Check::BodyContains => "Body contains",
+            Check::JsonPathEquals => "JSONPath equals",
+            Check::TimeUnder => "Time under (ms)",
+        }
+    }
+
+    /// Placeholder of the target field; `None` when the check has none.
+    pub fn target_hint(self) -> Option<&'static str> {
+        match self {
+            Check::HeaderEquals | Check::HeaderContains => Some("Header name"),
+            Check::JsonPathEquals => Some("$.items[0].id"),
+            _ => None,
+        }
+    }
+
+    pub fn expected_hint(self) -> &'static str {
+        match self {
+            Check::StatusEquals => "200",
+            Check::StatusInRange => "200-299 or 2xx",
+            Check::HeaderEquals | Check::HeaderContains | Check::BodyContains => "Text",
+            Check::JsonPathEquals => "JSON value, e.g. 42 or \"ok\"",
+            Check::TimeUnder => "500",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Assertion {
+    pub check: Check,
+    /// Header name or JSONPath; empty for the other checks.
+    #[serde(default)]
+    pub target: String,
+    pub expected: String,
+}
+
+impl Assertion {
+    #[cfg(test)]
+    pub fn new(check: Check, target: &str, expected: &str) -> Self {
+        Self { check, target: target.to_string(), expected: expected.to_string() }
+    }
+
+    /// One line for the results list: "Header Content-Type contains json".
+    pub fn describe(&self) -> String {
+        let (target, expected) = (self.target.trim(), self.expected.trim());
+        match self.check {
+            Check::StatusEquals => format!("Status is {expected}"),
+            Check::StatusInRange => format!("Status in {expected}"),
+            Check::HeaderEquals => format!("Header {target} is {expected}"),
+            Check::HeaderContains => format!("Header {target} contains {expected}"),
+            Check::BodyContains => format!("Body contains {expected}"),
+            Check::JsonPathEquals => format!("{target} equals {expected}"),
+            Check::TimeUnder => format!("Time under {expected} ms"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AssertionResult {
+    pub assertion: Assertion,
+    pub passed: bool,
+    /// What the response had, or why the check could not be made.
+    pub actual: String,
+}
+
+/// Run every assertion against `response`, in order.
+pub fn evaluate(assertions: &[Assertion], response: &ResponseData) -> Vec<AssertionResult> {
+    assertions
+        .iter()
+        .map(|assertion| {
+            let (passed, actual) = check(assertion, response);
+            AssertionResult { assertion: assertion.clone(), passed, actual }
+        })
+        .collect()
+}
+
+fn check(assertion: &Assertion, response: &ResponseData) -> (bool, String) {
+    let expected = assertion.expected.trim();
+    match assertion.check {
+        Check::StatusEquals => {
+            let Ok(want) = expected.parse::<u16>() else {
+                return (false, format!("\"{expected}\" is not a status code"));
+            };
+            match response.status {
+                Some(status) => (status == want, status.to_string()),
+                None => (false, "no response".to_string()),
+            }
+        }
+        Check::StatusInRange => {
+            let Some((low, high)) = status_range(expected) else {
+                return (false, format!("\"{expected}\" is not a range like 200-299 or 2xx"));
+            };
+            match response.status {
+                Some(status) => ((low..=high).contains(&status), status.to_string()),
+                None => (false, "no response".to_string()),
+            }
+        }
+        Check::HeaderEquals | Check::HeaderContains => {
+            let name = assertion.target.trim();
+            let values: Vec<&str> = response
+                .headers
+                .iter()
+                .filter(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+                .collect();
+            if values.is_empty() {
+                return (false, "header missing".to_string());
+            }
+            let passed = if assertion.check == Check::HeaderEquals {
+                values.iter().any(|v| v.trim() == expected)
+            } else {
+                values.iter().any(|v| v.contains(expected))
+            };
+            (passed, values.join(", "))
+        }
+        Check::BodyContains => {
+            if response.body_text().contains(expected) {
+                (true, "found in the body".to_string())
+            } else {
+                (false, "not in the body".to_string())
+            }
+        }
+        Check::JsonPathEquals => json_path_equals(assertion.target.trim(), expected, response),
+        Check::TimeUnder => {
+            let Ok(limit) = expected.parse::<u64>() else {
+                return (false, format!("\"{expected}\" is not a number of ms"));
+            };
+            (response.duration_ms < limit, format!("{} ms", response.duration_ms))
+        }
+    }
+}
+
+/// `200-299` or `2xx`, both ends included.
+fn status_range(text: &str) -> Option<(u16, u16)> {
+    if let Some((low, high)) = text.split_once('-') {
+        let (low, high) = (low.trim().parse().ok()?, high.trim().parse().ok()?);
+        return (low <= high).then_some((low, high));
+    }
+    let class = text.strip_suffix("xx").or_else(|| text.strip_suffix("XX"))?;
+    let class: u16 = class.parse().ok().filter(|c| (1..=5).contains(c))?;
+    Some((class * 100, class * 100 + 99))
+}
+
+fn json_path_equals(path: &str, expected: &str, response: &ResponseData) -> (bool, String) {
+    let Ok(root) = serde_json::from_str::<Value>(&response.body_text()) else {
+        return (false, "body is not JSON".to_string());
+    };
+    let matches = match crate::json_filter::evaluate(path, &root) {
+        Ok(matches) => matches,
+        Err(e) => return (false, e.to_string()),
+    };
+    let actual = match <[Value; 1]>::try_from(matches) {
+        Ok([single]) => single,
+        Err(matches) if matches.is_empty() => return (false, "no match".to_string()),
+        Err(matches) => Value::Array(matches),
+    };
+    // A bare word is taken as a string, so `ok` works as well as `"ok"`.
+    let want = serde_json::from_str::<Value>(expected).unwrap_or_else(|_| Value::String(expected.to_string()));
+    (actual == want, actual.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: Option<u16>, headers: &[(&str, &str)], body: &str) -> ResponseData {
+        ResponseData {
+            status,
+            duration_ms: 120,
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: body.as_bytes().to_vec(),
+            is_text: true,
+            route: Default::default(),
+            raw_header_values: vec![],
+            network_profile: Default::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
+        }
+    }
+
+    fn outcome(assertion: Assertion, response: &ResponseData) -> (bool, String) {
+        let result = evaluate(&[assertion], response).remove(0);
+        (result.passed, result.actual)
+    }
+
+    #[test]
+    fn status_checks() {
+        let ok = response(Some(201), &[], "");
+        assert_eq!(outcome(Assertion::new(Check::StatusEquals, "", "201"), &ok), (true, "201".into()));
+        assert!(!outcome(Assertion::new(Check::StatusEquals, "", "200"), &ok).0);
+        assert!(outcome(Assertion::new(Check::StatusInRange, "", "200-299"), &ok).0);
+        assert!(outcome(Assertion::new(Check::StatusInRange, "", "2xx"), &ok).0);
+        assert!(!outcome(Assertion::new(Check::StatusInRange, "", "4xx"), &ok).0);
+        let (passed, actual) = outcome(Assertion::new(Check::StatusEquals, "", "abc"), &ok);
+        assert!(!passed);
+        assert!(actual.contains("not a status code"));
+        let failed = response(None, &[], "connection refused");
+        assert_eq!(outcome(Assertion::new(Check::StatusInRange, "", "2xx"), &failed), (false, "no response".into()));
+    }
+
+    #[test]
+    fn header_checks_ignore_name_case() {
+        let r = response(Some(200), &[("Content-Type", "application/json; charset=utf-8")], "");
+        assert!(outcome(Assertion::new(Check::HeaderContains, "content-type", "json"), &r).0);
+        assert!(!outcome(Assertion::new(Check::HeaderEquals, "Content-Type", "application/json"), &r).0);
+        assert_eq!(
+            outcome(Assertion::new(Check::HeaderEquals, "ETag", "x"), &r),
+            (false, "header missing".into())
+        );
+    }
+
+    #[test]
+    fn body_and_time_checks() {
+        let r = response(Some(200), &[], "{\"message\":\"created\"}");
+        assert!(outcome(Assertion::new(Check::BodyContains, "", "created"), &r).0);
+        assert!(!outcome(Assertion::new(Check::BodyContains, "", "deleted"), &r).0);
+        assert_eq!(outcome(Assertion::new(Check::TimeUnder, "", "500"), &r), (true, "120 ms".into()));
+        assert!(!outcome(Assertion::new(Check::TimeUnder, "", "100"), &r).0);
+    }
+
+    #[test]
+    fn json_path_compares_json_values() {
+        let r = response(Some(200), &[], r#"{"id":7,"name":"ada","tags":["a","b"],"ok":true}"#);
+        assert!(outcome(Assertion::new(Check::JsonPathEquals, "$.id", "7"), &r).0);
+        assert!(!outcome(Assertion::new(Check::JsonPathEquals, "$.id", "\"7\""), &r).0);
+        assert!(outcome(Assertion::new(Check::JsonPathEquals, "$.name", "\"ada\""), &r).0);
+        assert!(outcome(Assertion::new(Check::JsonPathEquals, "$.name", "ada"), &r).0);
+        assert!(outcome(Assertion::new(Check::JsonPathEquals, "$.tags[*]", "[\"a\",\"b\"]"), &r).0);
+        assert!(outcome(Assertion::new(Check::JsonPathEquals, "ok", "true"), &r).0);
+        assert_eq!(
+            outcome(Assertion::new(Check::JsonPathEquals, "$.missing", "1"), &r),
+            (false, "no match".into())
+        );
+        let text = response(Some(200), &[], "plain");
+        assert_eq!(
+            outcome(Assertion::new(Check::JsonPathEquals, "$.id", "1"), &text),
+            (false, "body is not JSON".into())
+        );
+    }
+
+    #[test]
+    fn assertions_read_back_from_older_json() {
+        let parsed: Assertion = serde_json::from_str(r#"{"check":"body_contains","expected":"x"}"#).unwrap();
+        assert_eq!(parsed, Assertion::new(Check::BodyContains, "", "x"));
+        assert_eq!(parsed.describe(), "Body contains x");
+    }
+}
@@ -2,25 +2,62 @@ use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui::px;
 use gpui_component::{
+    button::{Button, ButtonVariants as _},
     input::{Input, InputState},
-    v_flex, h_flex, ActiveTheme as _,
+    v_flex, h_flex, ActiveTheme as _, Sizable as _,
 };
 
-use crate::types::{AuthConfig, AuthType};
+use crate::oauth2::Token;
+use crate::types::{ApiKeyPlacement, AuthConfig, AuthType, OAuth2ClientAuth, OAuth2Config, OAuth2Grant};
+
+/// Emitted by the OAuth 2.0 token row; the request editor, which knows the
+/// variables the config refers to, does the work.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenCommand {
+    /// Fetch a token now, signing in in the browser for the authorization code.
+    Get,
+    /// Stop a fetch or sign-in under way.
+    Cancel,
+    /// Drop the held token.
+    Forget,
+}
+
+/// What the token row shows, set by the request editor.
+#[derive(Clone, Debug, Default)]
+pub enum TokenState {
+    #[default]
+    None,
+    Fetching,
+    Held(Token),
+    Failed(String),
+}
 
 /// Auth sub-tab editor. A flat set of input fields (one per auth field) plus a
 /// type selector; only the active type's fields render. Values persist across
 /// type switches because each field is its own always-alive `InputState`.
 pub struct AuthEditor {
-    /// 0 = None, 1 = Bearer, 2 = Basic, 3 = ApiKey.
+    /// 0 = None, 1 = Bearer, 2 = Basic, 3 = ApiKey, 4 = OAuth2.
     auth_type_index: usize,
     bearer_token: Entity<InputState>,
     basic_username: Entity<InputState>,
     basic_password: Entity<InputState>,
     api_key_name: Entity<InputState>,
     api_key_value: Entity<InputState>,
+    api_key_in: ApiKeyPlacement,
+    oauth2_grant: OAuth2Grant,
+    oauth2_token_url: Entity<InputState>,
+    oauth2_auth_url: Entity<InputState>,
+    oauth2_client_id: Entity<InputState>,
+    oauth2_client_secret: Entity<InputState>,
+    oauth2_scopes: Entity<InputState>,
+    oauth2_audience: Entity<InputState>,
+    oauth2_redirect_uri: Entity<InputState>,
+    oauth2_client_auth: OAuth2ClientAuth,
+    token: TokenState,
 }
 
+impl EventEmitter<TokenCommand> for AuthEditor {}
+
 impl AuthEditor {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
         Self {
@@ -30,9 +67,25 @@ impl AuthEditor {
             basic_password: cx.new(|cx| InputState::new(window, cx).placeholder("Password")),
             api_key_name: cx.new(|cx| InputState::new(window, cx).placeholder("Key (e.g. X-API-Key)")),
             api_key_value: cx.new(|cx| InputState::new(window, cx).placeholder("Value")),
+            api_key_in: ApiKeyPlacement::Header,
+            oauth2_grant: OAuth2Grant::default(),
+            oauth2_token_url: cx.new(|cx| InputState::new(window, cx).placeholder("https://auth.example.com/oauth/token")),
+            oauth2_auth_url: cx.new(|cx| InputState::new(window, cx).placeholder("https://auth.example.com/authorize")),
+            oauth2_client_id: cx.new(|cx| InputState::new(window, cx).placeholder("Client ID")),
+            oauth2_client_secret: cx.new(|cx| InputState::new(window, cx).placeholder("Client secret").masked(true)),
+            oauth2_scopes: cx.new(|cx| InputState::new(window, cx).placeholder("Space-separated (optional)")),
+            oauth2_audience: cx.new(|cx| InputState::new(window, cx).placeholder("Optional")),
+            oauth2_redirect_uri: cx.new(|cx| InputState::new(window, cx).placeholder(crate::types::DEFAULT_OAUTH2_REDIRECT_URI)),
+            oauth2_client_auth: OAuth2ClientAuth::default(),
+            token: TokenState::None,
         }
     }
 
+    pub fn set_token_state(&mut self, token: TokenState, cx: &mut Context<Self>) {
+        self.token = token;
+        cx.notify();
+    }
+
     /// Read the current auth configuration from the UI fields.
     pub fn get_auth(&self, cx: &App) -> AuthConfig {
         AuthConfig {
@@ -40,6 +93,7 @@ impl AuthEditor {
                 1 => AuthType::Bearer,
                 2 => AuthType::Basic,
                 3 => AuthType::ApiKey,
+                4 => AuthType::OAuth2,
                 _ => AuthType::None,
             },
             bearer_token: self.bearer_token.read(cx).value().to_string(),
@@ -47,6 +101,18 @@ impl AuthEditor {
             basic_password: self.basic_password.read(cx).value().to_string(),
             api_key_name: self.api_key_name.read(cx).value().to_string(),
             api_key_value: self.api_key_value.read(cx).value().to_string(),
+            api_key_in: self.api_key_in,
+            oauth2: OAuth2Config {
+                grant: self.oauth2_grant,
+                token_url: self.oauth2_token_url.read(cx).value().to_string(),
+                auth_url: self.oauth2_auth_url.read(cx).value().to_string(),
+                client_id: self.oauth2_client_id.read(cx).value().to_string(),
+                client_secret: self.oauth2_client_secret.read(cx).value().to_string(),
+                scopes: self.oauth2_scopes.read(cx).value().to_string(),
+                audience: self.oauth2_audience.read(cx).value().to_string(),
+                redirect_uri: self.oauth2_redirect_uri.read(cx).value().to_string(),
+                client_auth: self.oauth2_client_auth,
+            },
         }
     }
 
@@ -57,15 +123,53 @@ impl AuthEditor {
             AuthType::Bearer => 1,
             AuthType::Basic => 2,
             AuthType::ApiKey => 3,
+            AuthType::OAuth2 => 4,
         };
         self.bearer_token.update(cx, |i, cx| i.set_value(&auth.bearer_token, window, cx));
         self.basic_username.update(cx, |i, cx| i.set_value(&auth.basic_username, window, cx));
         self.basic_password.update(cx, |i, cx| i.set_value(&auth.basic_password, window, cx));
         self.api_key_name.update(cx, |i, cx| i.set_value(&auth.api_key_name, window, cx));
         self.api_key_value.update(cx, |i, cx| i.set_value(&auth.api_key_value, window, cx));
+        self.api_key_in = auth.api_key_in;
+        let oauth2 = &auth.oauth2;
+        self.oauth2_grant = oauth2.grant;
+        self.oauth2_token_url.update(cx, |i, cx| i.set_value(&oauth2.token_url, window, cx));
+        self.oauth2_auth_url.update(cx, |i, cx| i.set_value(&oauth2.auth_url, window, cx));
+        self.oauth2_client_id.update(cx, |i, cx| i.set_value(&oauth2.client_id, window, cx));
+        self.oauth2_client_secret.update(cx, |i, cx| i.set_value(&oauth2.client_secret, window, cx));
+        self.oauth2_scopes.update(cx, |i, cx| i.set_value(&oauth2.scopes, window, cx));
+        self.oauth2_audience.update(cx, |i, cx| i.set_value(&oauth2.audience, window, cx));
+        self.oauth2_redirect_uri.update(cx, |i, cx| i.set_value(&oauth2.redirect_uri, window, cx));
+        self.oauth2_client_auth = oauth2.client_auth;
         cx.notify();
     }
 
+    /// A muted radio option, matching BodyEditor's body-type row.
+    fn radio(id: impl Into<ElementId>, label: &'static str, selected: bool, theme: &gpui_component::Theme) -> Stateful<Div> {
+        h_flex()
+            .id(id)
+            .gap_1p5()
+            .items_center()
+            .cursor_pointer()
+            .child(
+                div()
+                    .size(px(14.))
+                    .rounded_full()
+                    .border_1()
+                    .border_color(if selected { theme.primary } else { theme.border })
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .when(selected, |d| d.child(div().size(px(6.)).rounded_full().bg(theme.primary))),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(if selected { theme.foreground } else { theme.muted_foreground })
+                    .child(label),
+            )
+    }
+
     /// A labelled input row (label on the left, field filling the rest).
     fn field_row(label: &'static str, input: &Entity<InputState>, theme: &gpui_component::Theme) -> impl IntoElement {
         h_flex()
@@ -82,6 +186,110 @@ impl AuthEditor {
             )
             .child(div().flex_1().child(Input::new(input)))
     }
+
+    /// A label in the field column, for rows of radios or buttons.
+    fn row_label(label: &'static str, theme: &gpui_component::Theme) -> Div {
+        div().w(px(120.)).flex_shrink_0().text_sm().text_color(theme.muted_foreground).child(label)
+    }
+
+    fn render_oauth2(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let code = self.oauth2_grant == OAuth2Grant::AuthorizationCode;
+        let grants = [(OAuth2Grant::ClientCredentials, "Client credentials"), (OAuth2Grant::AuthorizationCode, "Authorization code")];
+        let client_auths = [(OAuth2ClientAuth::BasicHeader, "Basic auth header"), (OAuth2ClientAuth::Body, "Request body")];
+        let status = match &self.token {
+            TokenState::None if code => "Not signed in".to_string(),
+            TokenState::None => "None yet; fetched when the request is sent".to_string(),
+            TokenState::Fetching if code => "Waiting for the sign-in in your browser…".to_string(),
+            TokenState::Fetching => "Fetching…".to_string(),
+            TokenState::Held(token) => match token.expires_at {
+                Some(at) if !token.is_fresh(chrono::Local::now()) => {
+                    format!("{} · expired {}, renewed on send", token.masked(), at.format("%H:%M:%S"))
+                }
+                Some(at) => format!("{} · expires {}", token.masked(), at.format("%H:%M:%S")),
+                None => format!("{} · no expiry given", token.masked()),
+            },
+            TokenState::Failed(message) => message.clone(),
+        };
+
+        v_flex()
+            .gap_3()
+            .child(
+                h_flex().gap_3().items_center().child(Self::row_label("Grant", theme)).children(
+                    grants.into_iter().enumerate().map(|(i, (grant, label))| {
+                        Self::radio(("oauth2-grant", i), label, self.oauth2_grant == grant, theme).on_click(cx.listener(
+                            move |this, _, _window, cx| {
+                                this.oauth2_grant = grant;
+                                cx.notify();
+                            },
+                        ))
+                    }),
+                ),
+            )
+            .when(code, |this| this.child(Self::field_row("Authorization URL", &self.oauth2_auth_url, theme)))
+            .child(Self::field_row("Token URL", &self.oauth2_token_url, theme))
+            .child(Self::field_row("Client ID", &self.oauth2_client_id, theme))
+            .child(Self::field_row("Client secret", &self.oauth2_client_secret, theme))
+            .child(Self::field_row("Scopes", &self.oauth2_scopes, theme))
+            .child(Self::field_row("Audience", &self.oauth2_audience, theme))
+            .when(code, |this| this.child(Self::field_row("Redirect URI", &self.oauth2_redirect_uri, theme)))
+            .child(
+                h_flex().gap_3().items_center().child(Self::row_label("Send client as", theme)).children(
+                    client_auths.into_iter().enumerate().map(|(i, (client_auth, label))| {
+                        Self::radio(("oauth2-client-auth", i), label, self.oauth2_client_auth == client_auth, theme)
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                this.oauth2_client_auth = client_auth;
+                                cx.notify();
+                            }))
+                    }),
+                ),
+            )
+            .child(
+                h_flex()
+                    .gap_3()
+                    .items_center()
+                    .child(Self::row_label("Token", theme))
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .truncate()
+                            .text_sm()
+                            .text_color(match self.token {
+                                TokenState::Failed(_) => theme.danger,
+                                TokenState::Held(_) => theme.foreground,
+                                _ => theme.muted_foreground,
+                            })
+                            .child(status),
+                    )
+                    .map(|row| match self.token {
+                        TokenState::Fetching => row.child(
+                            Button::new("oauth2-cancel")
+                                .outline()
+                                .small()
+                                .label("Cancel")
+                                .on_click(cx.listener(|_, _, _window, cx| cx.emit(TokenCommand::Cancel))),
+                        ),
+                        _ => row
+                            .when(matches!(self.token, TokenState::Held(_)), |row| {
+                                row.child(
+                                    Button::new("oauth2-forget")
+                                        .ghost()
+                                        .small()
+                                        .label("Clear")
+                                        .on_click(cx.listener(|_, _, _window, cx| cx.emit(TokenCommand::Forget))),
+                                )
+                            })
+                            .child(
+                                Button::new("oauth2-get-token")
+                                    .outline()
+                                    .small()
+                                    .label("Get token")
+                                    .on_click(cx.listener(|_, _, _window, cx| cx.emit(TokenCommand::Get))),
+                            ),
+                    }),
+            )
+    }
 }
 
 impl Render for AuthEditor {
@@ -96,46 +304,26 @@ impl Render for AuthEditor {
             // Type selector — muted radios, matching BodyEditor's body-type row.
             .child(
                 h_flex().gap_4().items_center().children(
-                    ["None", "Bearer", "Basic", "API Key"].into_iter().enumerate().map(|(i, label)| {
-                        let selected = self.auth_type_index == i;
-                        h_flex()
-                            .id(("auth-type", i))
-                            .gap_1p5()
-                            .items_center()
-                            .cursor_pointer()
-                            .on_click(cx.listener(move |this, _, _window, cx| {
+                    ["None", "Bearer", "Basic", "API Key", "OAuth 2.0"].into_iter().enumerate().map(|(i, label)| {
+                        Self::radio(("auth-type", i), label, self.auth_type_index == i, theme).on_click(cx.listener(
+                            move |this, _, _window, cx| {
                                 this.auth_type_index = i;
                                 cx.notify();
-                            }))
-                            .child(
-                                div()
-                                    .size(px(14.))
-                                    .rounded_full()
-                                    .border_1()
-                                    .border_color(if selected { theme.primary } else { theme.border })
-                                    .flex()
-                                    .items_center()
-                                    .justify_center()
-                                    .when(selected, |d| {
-                                        d.child(div().size(px(6.)).rounded_full().bg(theme.primary))
-                                    }),
-                            )
-                            .child(
-                                div()
-                                    .text_sm()
-                                    .text_color(if selected { theme.foreground } else { theme.muted_foreground })
-                                    .child(label),
-                            )
+                            },
+                        ))
                     }),
                 ),
             )
             // A one-line note: auth is injected at send time and wins over a manual header.
-            .child(
-                div()
-                    .text_xs()
-                    .text_color(theme.muted_foreground)
-                    .child("The auth header is added when the request is sent and overrides a manually-typed header of the same name."),
-            )
+            .child(div().text_xs().text_color(theme.muted_foreground).child(
+                if self.auth_type_index == 3 && self.api_key_in == ApiKeyPlacement::Query {
+                    "The key is appended to the URL's query string when the request is sent; it is not added to Params."
+                } else if self.auth_type_index == 4 {
+                    "A Bearer token is added when the request is sent, fetched again once it expires. Tokens are kept in memory only."
+                } else {
+                    "The auth header is added when the request is sent and overrides a manually-typed header of the same name."
+                },
+            ))
             // Active type's fields.
             .when(self.auth_type_index == 0, |this| {
                 this.child(
@@ -158,6 +346,25 @@ impl Render for AuthEditor {
             .when(self.auth_type_index == 3, |this| {
                 this.child(Self::field_row("Key", &self.api_key_name, theme))
                     .child(Self::field_row("Value", &self.api_key_value, theme))
+                    .child(
+                        h_flex()
+                            .gap_3()
+                            .items_center()
+                            .child(div().w(px(120.)).flex_shrink_0().text_sm().text_color(theme.muted_foreground).child("Add to"))
+                            .children(
+                                [(ApiKeyPlacement::Header, "Header"), (ApiKeyPlacement::Query, "Query Params")]
+                                    .into_iter()
+                                    .enumerate()
+                                    .map(|(i, (placement, label))| {
+                                        Self::radio(("api-key-in", i), label, self.api_key_in == placement, theme)
+                                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                                this.api_key_in = placement;
+                                                cx.notify();
+                                            }))
+                                    }),
+                            ),
+                    )
             })
+            .when(self.auth_type_index == 4, |this| this.child(self.render_oauth2(cx)))
     }
 }
@@ -7,6 +7,8 @@ use gpui_component::{
     select::*, v_flex, ActiveTheme as _, IndexPath, Sizable as _,
 };
 
+use crate::body_history::{BodyAction, BodyUndoStack};
+use crate::code_formatter::JsonDiagnostic;
 use crate::types::{BodyType, FormDataRow, FormDataValue, RawSubtype};
 
 use gpui::Subscription;
@@ -39,6 +41,13 @@ pub struct BodyEditor {
     formdata_input_states: Vec<FormDataRowInputs>,
     formdata_scroll_handle: ScrollHandle,
     _subscriptions: Vec<Subscription>,
+    /// Re-lints the raw body on edits. Kept apart from `_subscriptions`,
+    /// which `set_body` rebuilds for form-data rows.
+    _lint_subscription: Subscription,
+    /// Findings of `lint_json` for a raw JSON body, listed under the editor.
+    json_diagnostics: Vec<JsonDiagnostic>,
+    /// Snapshots of Beautify / JSON-fix rewrites, for Ctrl+Alt+Z.
+    body_undo: BodyUndoStack,
     // Format/validation state
     validation_message: Option<String>,
     validation_error: bool,
@@ -84,14 +93,7 @@ impl BodyEditor {
 
         // Create single editor for all raw types (default to JSON)
         let current_raw_subtype = RawSubtype::Json;
-        let raw_body_editor = cx.new(|cx| {
-            InputState::new(window, cx)
-                .code_editor(current_raw_subtype.as_str())
-                .line_number(true)
-                .indent_guides(true)
-                .tab_size(TabSize { tab_size: 4, hard_tabs: false })
-                .placeholder(get_placeholder_for_subtype(current_raw_subtype))
-        });
+        let (raw_body_editor, lint_subscription) = Self::new_raw_editor(current_raw_subtype, window, cx);
 
         log::info!("Created single body editor with default language: 'json'");
 
@@ -104,6 +106,9 @@ impl BodyEditor {
             formdata_input_states: vec![],
             formdata_scroll_handle: ScrollHandle::new(),
             _subscriptions: vec![],
+            _lint_subscription: lint_subscription,
+            json_diagnostics: vec![],
+            body_undo: BodyUndoStack::default(),
             validation_message: None,
             validation_error: false,
         };
@@ -124,6 +129,28 @@ impl BodyEditor {
         editor
     }
 
+    /// The raw body input, with the subscription that re-lints it on edits.
+    fn new_raw_editor(
+        subtype: RawSubtype,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> (Entity<InputState>, Subscription) {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .code_editor(subtype.as_str())
+                .line_number(true)
+                .indent_guides(true)
+                .tab_size(TabSize { tab_size: 4, hard_tabs: false })
+                .placeholder(get_placeholder_for_subtype(subtype))
+        });
+        let subscription = cx.subscribe(&input, |this: &mut BodyEditor, _, event: &InputChangeEvent, cx| {
+            if let InputChangeEvent::Change = event {
+                this.refresh_json_diagnostics(cx);
+            }
+        });
+        (input, subscription)
+    }
+
     /// Handle raw subtype change - switch syntax highlighting and placeholder
     fn handle_subtype_change(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let subtype_index = self.raw_subtype_select
@@ -149,6 +176,7 @@ impl BodyEditor {
                 content_type: Some(new_subtype.content_type().to_string()),
             });
 
+            self.refresh_json_diagnostics(cx);
             cx.notify();
         }
     }
@@ -191,6 +219,7 @@ impl BodyEditor {
 
     /// Set body from loaded request
     pub fn set_body(&mut self, body: &BodyType, window: &mut Window, cx: &mut Context<Self>) {
+        self.body_undo.clear();
         match body {
             BodyType::None => {
                 self.body_type_index = 0;
@@ -203,12 +232,15 @@ impl BodyEditor {
                 });
                 // Update current subtype and syntax highlighting
                 self.current_raw_subtype = *subtype;
-                self.raw_body_editor.update(cx, |input, cx| {
-                    input.set_value(content, window, cx);
-                    input.set_highlighter(subtype.as_str(), cx);
-                    // Also update placeholder when loading
-                    input.set_placeholder(get_placeholder_for_subtype(*subtype), window, cx);
-                });
+                // A fresh input rather than `set_value` on the old one:
+                // `set_value` bypasses the input's undo history, whose
+                // entries then point into text that is gone, and Ctrl+Z
+                // after a load would splice them into the new body.
+                let (input, subscription) = Self::new_raw_editor(*subtype, window, cx);
+                input.update(cx, |input, cx| input.set_value(content, window, cx));
+                self.raw_body_editor = input;
+                self._lint_subscription = subscription;
+                self.refresh_json_diagnostics(cx);
             }
             BodyType::FormData(rows) => {
                 self.body_type_index = 2;
@@ -299,12 +331,17 @@ impl BodyEditor {
         let content_type = match body {
             BodyType::None => None,
             BodyType::Raw { subtype, .. } => Some(subtype.content_type().to_string()),
-            BodyType::FormData(_) => Some("multipart/form-data; boundary=<auto>".to_string()),
+            BodyType::FormData(_) => Some(crate::content_type::MULTIPART_AUTO.to_string()),
         };
 
         cx.emit(BodyTypeChanged { content_type });
     }
 
+    /// Whether a body type other than None is picked.
+    pub fn has_body(&self) -> bool {
+        self.body_type_index != 0
+    }
+
     /// Calculate body content length
     pub fn calculate_length(&self, cx: &App) -> usize {
         match self.body_type_index {
@@ -490,9 +527,7 @@ impl BodyEditor {
 
         match result {
             Ok(formatted) => {
-                self.raw_body_editor.update(cx, |input, cx| {
-                    input.set_value(&formatted, window, cx);
-                });
+                self.rewrite_raw_body(BodyAction::Format, formatted, window, cx);
                 self.validation_message = Some(format!("{} formatted successfully", self.current_raw_subtype.as_str().to_uppercase()));
                 self.validation_error = false;
             }
@@ -505,6 +540,158 @@ impl BodyEditor {
     }
 
 
+    /// Replace the whole raw body as one undoable edit: the input records
+    /// it (so Ctrl+Z works) and `body_undo` keeps the text from before.
+    fn rewrite_raw_body(&mut self, action: BodyAction, text: String, window: &mut Window, cx: &mut Context<Self>) {
+        let before = self.raw_body_editor.read(cx).value().to_string();
+        if before == text {
+            return;
+        }
+        self.replace_raw_text(&before, &text, window, cx);
+        self.body_undo.push(action, before, text);
+    }
+
+    /// Swap `old` (the input's current text) for `new` through the input's
+    /// own edit path, which unlike `set_value` records an undo step.
+    fn replace_raw_text(&mut self, old: &str, new: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let whole = 0..old.encode_utf16().count();
+        self.raw_body_editor.update(cx, |input, cx| {
+            input.replace_text_in_range(Some(whole), new, window, cx);
+            input.set_cursor_position(gpui_component::input::Position::new(0, 0), window, cx);
+        });
+    }
+
+    /// Ctrl+Alt+Z / "Revert format": put back the text from before the last
+    /// Beautify or JSON fix.
+    fn undo_body_action(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.raw_body_editor.read(cx).value().to_string();
+        match self.body_undo.undo(&current) {
+            Some(snapshot) => {
+                self.replace_raw_text(&current, &snapshot.before, window, cx);
+                self.validation_message = Some(format!("Reverted {}", snapshot.action.label()));
+                self.validation_error = false;
+            }
+            None => {
+                self.validation_message = Some("Nothing to revert: the body was edited since".to_string());
+                self.validation_error = false;
+            }
+        }
+        self.refresh_json_diagnostics(cx);
+    }
+
+    fn refresh_json_diagnostics(&mut self, cx: &mut Context<Self>) {
+        self.json_diagnostics = if self.current_raw_subtype == RawSubtype::Json {
+            crate::code_formatter::lint_json(&self.raw_body_editor.read(cx).value())
+        } else {
+            vec![]
+        };
+        cx.notify();
+    }
+
+    /// Apply one diagnostic's fix, or all of them (`None`).
+    fn apply_json_fix(&mut self, index: Option<usize>, window: &mut Window, cx: &mut Context<Self>) {
+        let content = self.raw_body_editor.read(cx).value().to_string();
+        let (action, fixed) = match index {
+            Some(index) => match self.json_diagnostics.get(index) {
+                Some(diagnostic) => {
+                    (BodyAction::Fix, crate::code_formatter::apply_json_fixes(&content, [&diagnostic.fix]))
+                }
+                None => return,
+            },
+            None => (BodyAction::FixAll, crate::code_formatter::fix_all_json(&content)),
+        };
+        self.rewrite_raw_body(action, fixed, window, cx);
+        self.refresh_json_diagnostics(cx);
+    }
+
+    /// Put the cursor on a diagnostic's position in the editor.
+    fn reveal_json_diagnostic(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(diagnostic) = self.json_diagnostics.get(index) {
+            let position = gpui_component::input::Position::new(
+                diagnostic.line.saturating_sub(1) as u32,
+                diagnostic.column.saturating_sub(1) as u32,
+            );
+            self.raw_body_editor.update(cx, |input, cx| {
+                input.set_cursor_position(position, window, cx);
+            });
+        }
+    }
+
+    /// Diagnostics list under the raw JSON editor, with per-item and
+    /// "Fix all" rewrites. Items scroll once there are more than a few.
+    fn render_json_diagnostics(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let count = self.json_diagnostics.len();
+        v_flex()
+            .gap_1()
+            .w_full()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .items_center()
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.warning)
+                            .child(format!("{count} JSON problem{}", if count == 1 { "" } else { "s" })),
+                    )
+                    .child(
+                        Button::new("json-fix-all")
+                            .xsmall()
+                            .ghost()
+                            .label("Fix all")
+                            .on_click(cx.listener(|this, _, window, cx| this.apply_json_fix(None, window, cx))),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("json-diagnostics")
+                    .max_h(px(120.))
+                    .overflow_y_scroll()
+                    .children(self.json_diagnostics.iter().enumerate().map(|(index, diagnostic)| {
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                h_flex()
+                                    .id(("json-diagnostic", index))
+                                    .gap_2()
+                                    .min_w_0()
+                                    .text_xs()
+                                    .cursor_pointer()
+                                    .hover(|s| s.bg(theme.list_hover))
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.reveal_json_diagnostic(index, window, cx);
+                                    }))
+                                    .child(
+                                        div()
+                                            .flex_shrink_0()
+                                            .font_family(theme.mono_font_family.clone())
+                                            .text_color(theme.muted_foreground)
+                                            .child(format!("{}:{}", diagnostic.line, diagnostic.column)),
+                                    )
+                                    .child(
+                                        div()
+                                            .overflow_hidden()
+                                            .whitespace_nowrap()
+                                            .text_ellipsis()
+                                            .child(diagnostic.message.clone()),
+                                    ),
+                            )
+                            .child(
+                                Button::new(("json-fix", index))
+                                    .xsmall()
+                                    .ghost()
+                                    .label(diagnostic.fix.label.clone())
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.apply_json_fix(Some(index), window, cx);
+                                    })),
+                            )
+                    })),
+            )
+    }
+
     fn select_file_for_row(&mut self, index: usize, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         let path = cx.prompt_for_paths(PathPromptOptions {
             files: true,
@@ -534,9 +721,16 @@ impl BodyEditor {
 
 impl Render for BodyEditor {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let can_revert_format = self.body_type_index == 1
+            && self.body_undo.last_action(&self.raw_body_editor.read(cx).value()) == Some(BodyAction::Format);
         let theme = cx.theme();
 
         v_flex()
+            .on_action(cx.listener(|this, _: &crate::app::UndoBodyAction, window, cx| {
+                if this.body_type_index == 1 {
+                    this.undo_body_action(window, cx);
+                }
+            }))
             .gap_3()
             .w_full()
             .flex_1()
@@ -561,7 +755,7 @@ impl Render for BodyEditor {
                                         let content_type = match i {
                                             0 => None,
                                             1 => Some(this.current_raw_subtype.content_type().to_string()),
-                                            2 => Some("multipart/form-data; boundary=<auto>".to_string()),
+                                            2 => Some(crate::content_type::MULTIPART_AUTO.to_string()),
                                             _ => None,
                                         };
                                         cx.emit(BodyTypeChanged { content_type });
@@ -607,8 +801,20 @@ impl Render for BodyEditor {
                     )
                     .child(
                         // Right-aligned action, like Postman's Beautify
-                        h_flex().items_center().when(self.body_type_index == 1, |this| {
-                            this.child(
+                        h_flex().items_center().gap_1().when(self.body_type_index == 1, |this| {
+                            this.when(can_revert_format, |this| {
+                                this.child(
+                                    Button::new("revert-format-button")
+                                        .small()
+                                        .ghost()
+                                        .label("Revert format")
+                                        .tooltip("Back to the body before Beautify (Ctrl+Alt+Z)")
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.undo_body_action(window, cx);
+                                        })),
+                                )
+                            })
+                            .child(
                                 Button::new("beautify-button")
                                     .small()
                                     .ghost()
@@ -652,6 +858,7 @@ impl Render for BodyEditor {
                                 .h_full()
                         )
                 )
+                .when(!self.json_diagnostics.is_empty(), |this| this.child(self.render_json_diagnostics(cx)))
             })
             .when(self.body_type_index == 2, |this| {
                 // Form-data - show table (like headers layout)
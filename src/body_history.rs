@@ -0,0 +1,125 @@
+//! Undo for the body editor's own rewrites (Beautify, JSON fixes).
+//!
+//! These replace the whole text in one go. The input's Ctrl+Z records them,
+//! but it groups changes made within a second of each other, so a Beautify
+//! right after typing undoes together with the last keystrokes. This stack
+//! keeps a snapshot per rewrite instead: Ctrl+Alt+Z (and "Revert format")
+//! goes back to exactly the text before it.
+//!
+//! A snapshot only applies while the editor still shows what the rewrite
+//! produced. Once the user has typed over it, restoring would throw the
+//! typing away, so the stale entries are dropped instead.
+
+/// Cap on remembered rewrites; the oldest go first.
+pub const MAX_BODY_UNDO: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyAction {
+    Format,
+    Fix,
+    FixAll,
+}
+
+impl BodyAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BodyAction::Format => "Beautify",
+            BodyAction::Fix => "JSON fix",
+            BodyAction::FixAll => "Fix all",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BodySnapshot {
+    pub action: BodyAction,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Default)]
+pub struct BodyUndoStack {
+    entries: Vec<BodySnapshot>,
+}
+
+impl BodyUndoStack {
+    /// Remember that `action` turned `before` into `after`. A rewrite that
+    /// changed nothing is not an undo step.
+    pub fn push(&mut self, action: BodyAction, before: String, after: String) {
+        if before == after {
+            return;
+        }
+        if self.entries.len() == MAX_BODY_UNDO {
+            self.entries.remove(0);
+        }
+        self.entries.push(BodySnapshot { action, before, after });
+    }
+
+    /// Take the latest rewrite if the editor still shows its result
+    /// (`current`); otherwise forget every snapshot and return `None`.
+    pub fn undo(&mut self, current: &str) -> Option<BodySnapshot> {
+        match self.entries.last() {
+            Some(top) if top.after == current => self.entries.pop(),
+            _ => {
+                self.entries.clear();
+                None
+            }
+        }
+    }
+
+    /// The rewrite that produced `current`, if it was the last thing done.
+    pub fn last_action(&self, current: &str) -> Option<BodyAction> {
+        self.entries.last().filter(|top| top.after == current).map(|top| top.action)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_steps_back_through_rewrites() {
+        let mut stack = BodyUndoStack::default();
+        stack.push(BodyAction::Fix, "{'a':1,}".into(), "{\"a\":1,}".into());
+        stack.push(BodyAction::Format, "{\"a\":1,}".into(), "{\n  \"a\": 1\n}".into());
+        assert_eq!(stack.last_action("{\n  \"a\": 1\n}"), Some(BodyAction::Format));
+
+        let format = stack.undo("{\n  \"a\": 1\n}").unwrap();
+        assert_eq!(format.before, "{\"a\":1,}");
+        assert_eq!(stack.undo("{\"a\":1,}").unwrap().before, "{'a':1,}");
+        assert_eq!(stack.undo("{'a':1,}"), None);
+    }
+
+    #[test]
+    fn typing_after_a_rewrite_makes_it_stale() {
+        let mut stack = BodyUndoStack::default();
+        stack.push(BodyAction::Format, "{}".into(), "{ }".into());
+        stack.push(BodyAction::Format, "{ }".into(), "{\n}".into());
+        assert_eq!(stack.last_action("{\n} x"), None);
+        assert_eq!(stack.undo("{\n} x"), None);
+        // Everything is dropped, not just the top.
+        assert_eq!(stack.undo("{ }"), None);
+    }
+
+    #[test]
+    fn no_op_rewrites_are_skipped_and_the_stack_is_capped() {
+        let mut stack = BodyUndoStack::default();
+        stack.push(BodyAction::Format, "same".into(), "same".into());
+        assert_eq!(stack.last_action("same"), None);
+
+        for i in 0..MAX_BODY_UNDO + 5 {
+            stack.push(BodyAction::FixAll, i.to_string(), (i + 1).to_string());
+        }
+        let mut current = (MAX_BODY_UNDO + 5).to_string();
+        let mut steps = 0;
+        while let Some(snapshot) = stack.undo(&current) {
+            current = snapshot.before;
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_BODY_UNDO);
+    }
+}
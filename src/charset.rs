@@ -0,0 +1,129 @@
+//! The text of a response body in the charset its `Content-Type` declares
+//! (`charset=iso-8859-1`, `shift_jis`, ...), decoded with `encoding_rs`.
+//! Without a charset, or with one `encoding_rs` doesn't know, the body is
+//! taken as UTF-8, invalid bytes replaced. The stored body is never
+//! touched, so Save body writes the bytes as they arrived. Pure.
+
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, UTF_8};
+
+/// How a body was turned into text, for the viewer's status bar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoding {
+    /// Valid UTF-8, declared or not.
+    Utf8,
+    /// In the declared charset (or the one a byte order mark names), by its
+    /// WHATWG name: `iso-8859-1` is decoded as `windows-1252`.
+    Charset { name: &'static str, malformed: bool },
+    /// Not valid UTF-8 and no charset to go by (or `label`, an unknown one).
+    LossyUtf8 { label: Option<String> },
+}
+
+impl Decoding {
+    /// What the status bar says; nothing for plain UTF-8.
+    pub fn note(&self) -> Option<String> {
+        match self {
+            Decoding::Utf8 => None,
+            Decoding::Charset { name, malformed: false } => Some(format!("Decoded as {name}")),
+            Decoding::Charset { name, malformed: true } => {
+                Some(format!("Decoded as {name}, invalid bytes replaced"))
+            }
+            Decoding::LossyUtf8 { label: None } => Some("Not valid UTF-8, invalid bytes replaced".to_string()),
+            Decoding::LossyUtf8 { label: Some(label) } => {
+                Some(format!("Unknown charset \"{label}\", shown as UTF-8 with invalid bytes replaced"))
+            }
+        }
+    }
+}
+
+/// The `charset` parameter of the `Content-Type` header, unquoted.
+pub fn declared(headers: &[(String, String)]) -> Option<String> {
+    let content_type = headers.iter().find(|(k, _)| k.eq_ignore_ascii_case("content-type"))?;
+    content_type.1.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"').to_string())
+            .filter(|value| !value.is_empty())
+    })
+}
+
+/// `body` as text, and how it was decoded. Borrowed when the bytes are
+/// valid UTF-8 and UTF-8 is what they were read as.
+pub fn decode<'a>(headers: &[(String, String)], body: &'a [u8]) -> (Cow<'a, str>, Decoding) {
+    let label = declared(headers);
+    let encoding = label.as_deref().and_then(|label| Encoding::for_label(label.as_bytes()));
+    match encoding {
+        Some(encoding) if encoding != UTF_8 => {
+            let (text, used, malformed) = encoding.decode(body);
+            (text, Decoding::Charset { name: used.name(), malformed })
+        }
+        _ => {
+            let text = String::from_utf8_lossy(body);
+            let decoding = match (&text, encoding) {
+                (Cow::Borrowed(_), _) => Decoding::Utf8,
+                (Cow::Owned(_), Some(_)) => Decoding::Charset { name: UTF_8.name(), malformed: true },
+                (Cow::Owned(_), None) => Decoding::LossyUtf8 { label },
+            };
+            (text, decoding)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn h(content_type: &str) -> Vec<(String, String)> {
+        vec![("Content-Type".to_string(), content_type.to_string())]
+    }
+
+    /// "Grüße, café" in ISO-8859-1.
+    const LATIN1: &[u8] = b"Gr\xfc\xdfe, caf\xe9";
+    /// "こんにちは、世界" in Shift_JIS.
+    const SHIFT_JIS: &[u8] = b"\x82\xb1\x82\xf1\x82\xc9\x82\xbf\x82\xcd\x81\x41\x90\xa2\x8a\x45";
+
+    #[test]
+    fn latin1_is_decoded_in_the_declared_charset() {
+        let (text, decoding) = decode(&h("text/plain; charset=iso-8859-1"), LATIN1);
+        assert_eq!(text, "Grüße, café");
+        assert_eq!(decoding, Decoding::Charset { name: "windows-1252", malformed: false });
+        assert_eq!(decoding.note().unwrap(), "Decoded as windows-1252");
+    }
+
+    #[test]
+    fn shift_jis_is_decoded_in_the_declared_charset() {
+        let (text, decoding) = decode(&h("text/html; Charset=\"Shift_JIS\""), SHIFT_JIS);
+        assert_eq!(text, "こんにちは、世界");
+        assert_eq!(decoding, Decoding::Charset { name: "Shift_JIS", malformed: false });
+    }
+
+    #[test]
+    fn utf8_stays_borrowed_and_unnoted() {
+        let body = "Grüße".as_bytes();
+        for headers in [h("text/plain; charset=utf-8"), h("application/json"), vec![]] {
+            let (text, decoding) = decode(&headers, body);
+            assert!(matches!(text, Cow::Borrowed("Grüße")));
+            assert_eq!(decoding.note(), None);
+        }
+    }
+
+    #[test]
+    fn without_a_usable_charset_invalid_bytes_are_replaced() {
+        let (text, decoding) = decode(&h("text/plain"), LATIN1);
+        assert_eq!(text, "Gr\u{fffd}\u{fffd}e, caf\u{fffd}");
+        assert_eq!(decoding, Decoding::LossyUtf8 { label: None });
+        let (_, decoding) = decode(&h("text/plain; charset=x-klingon"), LATIN1);
+        assert!(decoding.note().unwrap().contains("\"x-klingon\""));
+        let (_, decoding) = decode(&h("text/plain; charset=utf-8"), SHIFT_JIS);
+        assert_eq!(decoding, Decoding::Charset { name: "UTF-8", malformed: true });
+    }
+
+    #[test]
+    fn the_charset_parameter_is_found_among_others() {
+        assert_eq!(declared(&h("text/csv; header=present; charset=\"windows-1251\"")).as_deref(), Some("windows-1251"));
+        assert_eq!(declared(&h("text/csv; charset=")), None);
+        assert_eq!(declared(&[]), None);
+    }
+}
@@ -60,6 +60,248 @@ pub fn validate_json(input: &str) -> Result<(), String> {
         .map_err(|e| format!("Invalid JSON: {}", e))
 }
 
+/// What a [`JsonDiagnostic`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonIssue {
+    TrailingComma,
+    Comment,
+    SingleQuotes,
+    DuplicateKey,
+}
+
+/// A rewrite fixing one diagnostic: replace the bytes `start..end` of the
+/// input with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonFix {
+    pub label: String,
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+/// A problem found by [`lint_json`]. `line` and `column` are 1-based, the
+/// column counted in characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonDiagnostic {
+    pub issue: JsonIssue,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    pub fix: JsonFix,
+}
+
+/// Container the scanner is inside of. Objects remember their keys (as
+/// double-quoted JSON, with their offsets) to spot duplicates.
+enum Frame {
+    Object { members: Vec<(String, usize)>, expect_key: bool },
+    Array,
+}
+
+/// Tolerant pre-pass over a JSON body for the mistakes serde reports
+/// cryptically or not at all: trailing commas, comments, single-quoted
+/// strings and duplicate object keys (serde keeps the last one silently).
+///
+/// Not a validator — anything else is left to [`validate_json`]. Strings are
+/// skipped as a whole, so commas, slashes or braces inside them never count.
+pub fn lint_json(input: &str) -> Vec<JsonDiagnostic> {
+    let bytes = input.as_bytes();
+    // (issue, offset it is reported at, message, fix)
+    let mut found: Vec<(JsonIssue, usize, String, JsonFix)> = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    // A comma that is, so far, the last significant token.
+    let mut pending_comma: Option<usize> = None;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if matches!(bytes.get(i + 1), Some(b'/') | Some(b'*')) => {
+                let end = if bytes[i + 1] == b'/' {
+                    input[i..].find('\n').map_or(input.len(), |n| i + n)
+                } else {
+                    input[i + 2..].find("*/").map_or(input.len(), |n| i + 2 + n + 2)
+                };
+                found.push((
+                    JsonIssue::Comment,
+                    i,
+                    "Comments are not allowed in JSON".to_string(),
+                    JsonFix { label: "Remove comment".to_string(), start: i, end, replacement: String::new() },
+                ));
+                i = end;
+                continue;
+            }
+            quote @ (b'"' | b'\'') => {
+                let (content_end, end, terminated) = string_end(bytes, i);
+                let content = &input[i + 1..content_end];
+                let single = quote == b'\'';
+                if single && terminated {
+                    found.push((
+                        JsonIssue::SingleQuotes,
+                        i,
+                        "Strings must use double quotes".to_string(),
+                        JsonFix {
+                            label: "Use double quotes".to_string(),
+                            start: i,
+                            end,
+                            replacement: to_double_quoted(content),
+                        },
+                    ));
+                }
+                if let Some(Frame::Object { members, expect_key }) = stack.last_mut()
+                    && *expect_key
+                {
+                    *expect_key = false;
+                    let name = if single { to_double_quoted(content) } else { input[i..end].to_string() };
+                    if let Some(prev) = members.iter().rposition(|(n, _)| *n == name) {
+                        let prev_start = members[prev].1;
+                        let next_start = members.get(prev + 1).map_or(i, |m| m.1);
+                        found.push((
+                            JsonIssue::DuplicateKey,
+                            i,
+                            format!(
+                                "Duplicate key {name} (also on line {}); only the last one is kept",
+                                line_column(input, prev_start).0
+                            ),
+                            JsonFix {
+                                label: format!("Remove the earlier {name}"),
+                                start: prev_start,
+                                end: next_start,
+                                replacement: String::new(),
+                            },
+                        ));
+                    }
+                    members.push((name, i));
+                }
+                pending_comma = None;
+                i = end;
+                continue;
+            }
+            b'{' => {
+                stack.push(Frame::Object { members: Vec::new(), expect_key: true });
+                pending_comma = None;
+            }
+            b'[' => {
+                stack.push(Frame::Array);
+                pending_comma = None;
+            }
+            b'}' | b']' => {
+                if let Some(comma) = pending_comma.take() {
+                    found.push((
+                        JsonIssue::TrailingComma,
+                        comma,
+                        format!("Trailing comma before '{}'", bytes[i] as char),
+                        JsonFix {
+                            label: "Remove trailing comma".to_string(),
+                            start: comma,
+                            end: comma + 1,
+                            replacement: String::new(),
+                        },
+                    ));
+                }
+                stack.pop();
+            }
+            b',' => {
+                if let Some(Frame::Object { expect_key, .. }) = stack.last_mut() {
+                    *expect_key = true;
+                }
+                pending_comma = Some(i);
+            }
+            c if c.is_ascii_whitespace() => {}
+            _ => pending_comma = None,
+        }
+        i += 1;
+    }
+
+    found.sort_by_key(|(_, at, _, _)| *at);
+    found
+        .into_iter()
+        .map(|(issue, at, message, fix)| {
+            let (line, column) = line_column(input, at);
+            JsonDiagnostic { issue, line, column, message, fix }
+        })
+        .collect()
+}
+
+/// Apply `fixes` to `input`. Overlapping fixes after the first are skipped;
+/// [`fix_all_json`] re-lints to pick them up.
+pub fn apply_json_fixes<'a>(input: &str, fixes: impl IntoIterator<Item = &'a JsonFix>) -> String {
+    let mut fixes: Vec<&JsonFix> = fixes.into_iter().collect();
+    fixes.sort_by_key(|f| f.start);
+    let mut out = String::with_capacity(input.len());
+    let mut copied = 0;
+    for fix in fixes {
+        if fix.start < copied {
+            continue;
+        }
+        out.push_str(&input[copied..fix.start]);
+        out.push_str(&fix.replacement);
+        copied = fix.end;
+    }
+    out.push_str(&input[copied..]);
+    out
+}
+
+/// Apply every suggested fix, re-linting until nothing is left to fix.
+pub fn fix_all_json(input: &str) -> String {
+    let mut text = input.to_string();
+    // Each pass fixes at least one issue; the bound only guards against a
+    // rewrite that keeps reintroducing what it removed.
+    for _ in 0..16 {
+        let diagnostics = lint_json(&text);
+        if diagnostics.is_empty() {
+            break;
+        }
+        text = apply_json_fixes(&text, diagnostics.iter().map(|d| &d.fix));
+    }
+    text
+}
+
+/// For the string starting at `start`: where its content ends, the offset
+/// after the closing quote, and whether it was closed at all.
+fn string_end(bytes: &[u8], start: usize) -> (usize, usize, bool) {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            c if c == quote => return (i, i + 1, true),
+            _ => i += 1,
+        }
+    }
+    (bytes.len(), bytes.len(), false)
+}
+
+/// Rewrite the body of a single-quoted string as a double-quoted JSON
+/// string: `\'` needs no escape any more, a bare `"` now does.
+fn to_double_quoted(content: &str) -> String {
+    let mut out = String::with_capacity(content.len() + 2);
+    out.push('"');
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\'') => out.push('\''),
+                Some(next) => {
+                    out.push('\\');
+                    out.push(next);
+                }
+                None => out.push_str("\\\\"),
+            },
+            '"' => out.push_str("\\\""),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// 1-based line and character column of byte `offset`.
+fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |n| n + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
 /// Format XML string with indentation.
 ///
 /// # Arguments
@@ -103,6 +345,30 @@ pub fn format_xml(input: &str) -> Result<String, String> {
     String::from_utf8(result).map_err(|e| format!("UTF-8 conversion error: {}", e))
 }
 
+/// Whether a response body should be run through `format_xml`: an XML
+/// Content-Type (`application/xml`, `text/xml`, `application/soap+xml`, any
+/// `+xml`), or no telling Content-Type and a body that starts with `<`.
+/// HTML is left alone either way — it is rarely well-formed XML.
+pub fn looks_like_xml(content_type: Option<&str>, body: &str) -> bool {
+    let mime = content_type
+        .and_then(|ct| ct.split(';').next())
+        .map(|m| m.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if mime.contains("html") {
+        return false;
+    }
+    if mime == "application/xml" || mime == "text/xml" || mime.ends_with("+xml") {
+        return true;
+    }
+    let generic = mime.is_empty() || mime == "text/plain" || mime == "application/octet-stream";
+    let head = body.trim_start();
+    let html = head
+        .get(..14)
+        .is_some_and(|h| h.eq_ignore_ascii_case("<!doctype html"))
+        || head.get(..5).is_some_and(|h| h.eq_ignore_ascii_case("<html"));
+    generic && head.starts_with('<') && !html
+}
+
 /// Validate XML syntax without formatting.
 ///
 /// # Arguments
@@ -209,6 +475,101 @@ mod tests {
         assert!(validate_json("   ").is_ok());
     }
 
+    // ============ JSON Lint Tests ============
+
+    fn issues(input: &str) -> Vec<(JsonIssue, usize, usize)> {
+        lint_json(input).into_iter().map(|d| (d.issue, d.line, d.column)).collect()
+    }
+
+    #[test]
+    fn test_lint_json_clean_input_has_no_diagnostics() {
+        assert!(lint_json(r#"{"a": [1, {"b": "c"}], "d": null}"#).is_empty());
+        assert!(lint_json("").is_empty());
+    }
+
+    #[test]
+    fn test_lint_json_trailing_commas_ignore_commas_in_strings() {
+        let input = r#"{"a": "x,}", "b": [1, 2,], "c": "[,]",}"#;
+        assert_eq!(
+            issues(input),
+            vec![(JsonIssue::TrailingComma, 1, 24), (JsonIssue::TrailingComma, 1, 38)]
+        );
+        let fixed = fix_all_json(input);
+        assert_eq!(fixed, r#"{"a": "x,}", "b": [1, 2], "c": "[,]"}"#);
+        assert!(validate_json(&fixed).is_ok());
+    }
+
+    #[test]
+    fn test_lint_json_trailing_comma_with_comment_before_closer() {
+        let input = "[\n  1,\n  2, // last\n]";
+        assert_eq!(
+            issues(input),
+            vec![(JsonIssue::TrailingComma, 3, 4), (JsonIssue::Comment, 3, 6)]
+        );
+        assert_eq!(serde_json::from_str::<Vec<u8>>(&fix_all_json(input)).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_lint_json_comments_but_not_slashes_in_strings() {
+        let input = "{\"url\": \"http://x/*y*/\", // note\n  \"n\": 1 /* one */}";
+        assert_eq!(issues(input), vec![(JsonIssue::Comment, 1, 26), (JsonIssue::Comment, 2, 10)]);
+        let value: serde_json::Value = serde_json::from_str(&fix_all_json(input)).unwrap();
+        assert_eq!(value, serde_json::json!({"url": "http://x/*y*/", "n": 1}));
+    }
+
+    #[test]
+    fn test_lint_json_single_quotes_are_requoted_with_escapes() {
+        let input = r#"{'it\'s': 'say "hi", ok', "plain": "don't"}"#;
+        assert_eq!(
+            issues(input),
+            vec![(JsonIssue::SingleQuotes, 1, 2), (JsonIssue::SingleQuotes, 1, 11)]
+        );
+        let value: serde_json::Value = serde_json::from_str(&fix_all_json(input)).unwrap();
+        assert_eq!(value, serde_json::json!({"it's": "say \"hi\", ok", "plain": "don't"}));
+    }
+
+    #[test]
+    fn test_lint_json_duplicate_keys_only_within_one_object() {
+        let input = "{\n  \"a\": {\"id\": 1, \"x\": 2},\n  \"id\": 0,\n  \"b\": [{\"id\": 1}, {\"id\": 2}],\n  \"id\": 3\n}";
+        let diagnostics = lint_json(input);
+        assert_eq!(diagnostics.len(), 1);
+        let d = &diagnostics[0];
+        assert_eq!((d.issue, d.line, d.column), (JsonIssue::DuplicateKey, 5, 3));
+        assert!(d.message.contains("line 3"), "{}", d.message);
+        assert_eq!(d.fix.label, "Remove the earlier \"id\"");
+
+        // Removing the earlier member keeps what serde would have sent.
+        let fixed = fix_all_json(input);
+        let original: serde_json::Value = serde_json::from_str(input).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&fixed).unwrap(), original);
+        assert!(!fixed.contains("\"id\": 0"));
+    }
+
+    #[test]
+    fn test_lint_json_single_and_double_quoted_keys_collide() {
+        assert_eq!(issues(r#"{'k': 1, "k": 2}"#)[1], (JsonIssue::DuplicateKey, 1, 10));
+    }
+
+    #[test]
+    fn test_lint_json_columns_count_characters() {
+        assert_eq!(issues("{\n  \"ü\": 1,\n}"), vec![(JsonIssue::TrailingComma, 2, 9)]);
+    }
+
+    #[test]
+    fn test_fix_all_json_resolves_overlapping_fixes() {
+        // The duplicate's removal swallows the trailing comma inside it.
+        let fixed = fix_all_json(r#"{"a": [1,], 'a': 2,}"#);
+        assert_eq!(fixed, r#"{"a": 2}"#);
+        assert!(lint_json(&fixed).is_empty());
+    }
+
+    #[test]
+    fn test_apply_json_fixes_single_fix() {
+        let input = r#"[1, 2,]"#;
+        let diagnostics = lint_json(input);
+        assert_eq!(apply_json_fixes(input, [&diagnostics[0].fix]), "[1, 2]");
+    }
+
     // ============ XML Format Tests ============
 
     #[test]
@@ -228,6 +589,46 @@ mod tests {
         assert!(result.contains("<child>"));
     }
 
+    #[test]
+    fn test_format_xml_keeps_attribute_order() {
+        let input = r#"<root z="1" a="2" m="3"><child b="x" a="y"/></root>"#;
+        let result = format_xml(input).unwrap();
+        assert!(result.contains(r#"<root z="1" a="2" m="3">"#), "{result}");
+        assert!(result.contains(r#"<child b="x" a="y"/>"#), "{result}");
+    }
+
+    #[test]
+    fn test_format_xml_preserves_cdata() {
+        let input = "<root><script><![CDATA[ if (a < b && c) {\n  x(); } ]]></script></root>";
+        let result = format_xml(input).unwrap();
+        assert!(result.contains("<![CDATA[ if (a < b && c) {\n  x(); } ]]>"), "{result}");
+    }
+
+    #[test]
+    fn test_format_xml_soap_envelope() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?><soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body><m:GetPriceResponse xmlns:m="https://x.test/prices"><m:Price>1.90</m:Price></m:GetPriceResponse></soap:Body></soap:Envelope>"#;
+        let result = format_xml(input).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines[0], r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        assert!(lines.contains(&"  <soap:Body>"), "{result}");
+        assert!(lines.contains(&"      <m:Price>1.90</m:Price>"), "{result}");
+    }
+
+    #[test]
+    fn test_looks_like_xml() {
+        assert!(looks_like_xml(Some("application/xml"), "x"));
+        assert!(looks_like_xml(Some("text/xml; charset=utf-8"), ""));
+        assert!(looks_like_xml(Some("application/soap+xml"), ""));
+        assert!(looks_like_xml(Some("application/atom+xml"), ""));
+        assert!(looks_like_xml(None, "  <root/>"));
+        assert!(looks_like_xml(Some("text/plain"), "<?xml version=\"1.0\"?><a/>"));
+        assert!(!looks_like_xml(Some("text/html"), "<html></html>"));
+        assert!(!looks_like_xml(None, "<!DOCTYPE html><html></html>"));
+        assert!(!looks_like_xml(Some("application/xhtml+xml"), "<html/>"));
+        assert!(!looks_like_xml(Some("application/json"), "<a/>"));
+        assert!(!looks_like_xml(None, "plain text"));
+    }
+
     #[test]
     fn test_format_xml_invalid() {
         let input = r#"<root><child>value</root>"#; // Mismatched tags
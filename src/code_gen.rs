@@ -6,7 +6,7 @@
 //! Form-data exports skip the UI-pinned Content-Type header — each target's
 //! HTTP library generates its own multipart boundary.
 
-use crate::types::{BodyType, FormDataRow, FormDataValue, RequestData};
+use crate::types::{BodyType, FormDataRow, FormDataValue, HttpMethod, RequestData};
 
 /// A language/library target for code generation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -170,10 +170,12 @@ fn file_basename(path: &str) -> &str {
 /// Top-level dispatch: generate source code for `target` from `req`.
 pub fn generate(target: CodeTarget, req: &RequestData) -> String {
     // Fold the computed auth header into the header list so every target emits it
-    // as a normal header. `effective_wire_headers` is the single source of truth,
-    // shared with the send path. When auth is None this is a no-op copy.
+    // as a normal header (or, for API-Key-in-query, a URL parameter).
+    // `effective_wire_headers` / `effective_wire_url` are the single source of
+    // truth, shared with the send path. When auth is None this is a no-op copy.
     let mut merged = req.clone();
     merged.headers = crate::types::effective_wire_headers(&req.headers, &req.auth);
+    merged.url = crate::types::effective_wire_url(&req.url, &req.auth, req.param_encoding);
     let req = &merged;
 
     match target {
@@ -188,9 +190,16 @@ pub fn generate(target: CodeTarget, req: &RequestData) -> String {
 
 fn gen_curl(req: &RequestData) -> String {
     let mut lines: Vec<String> = Vec::new();
+    // A custom method may hold shell characters ('&', '|', …).
+    let method = req.method.as_str();
+    let method = if method.bytes().all(|b| b.is_ascii_alphanumeric() || b"-_.".contains(&b)) {
+        method.to_string()
+    } else {
+        format!("'{}'", shell_single(method))
+    };
     lines.push(format!(
         "curl --location --request {} '{}'",
-        req.method.as_str(),
+        method,
         shell_single(&req.url)
     ));
     for (k, v) in export_headers(req) {
@@ -255,9 +264,14 @@ fn gen_rust(req: &RequestData) -> String {
         s.push_str(&chain.join("\n"));
         s.push_str(";\n\n");
     }
+    // reqwest names only the standard methods.
+    let method = match &req.method {
+        HttpMethod::Custom(name) => format!("reqwest::Method::from_bytes(b\"{}\")?", dq(name)),
+        method => format!("reqwest::Method::{}", method.as_str()),
+    };
     s.push_str(&format!(
-        "    let response = client\n        .request(reqwest::Method::{}, \"{}\")\n        .headers(headers)\n",
-        req.method.as_str(),
+        "    let response = client\n        .request({}, \"{}\")\n        .headers(headers)\n",
+        method,
         dq(&req.url)
     ));
     if !form.is_empty() {
@@ -544,24 +558,20 @@ mod tests {
 
     fn get_req() -> RequestData {
         RequestData {
-            method: HttpMethod::GET,
-            url: "https://api.example.com/users".to_string(),
             headers: vec![("Accept".to_string(), "application/json".to_string())],
             body: BodyType::None,
-            auth: crate::types::AuthConfig::default(),
+            ..RequestData::new(HttpMethod::GET, "https://api.example.com/users".to_string())
         }
     }
 
     fn post_json_req() -> RequestData {
         RequestData {
-            method: HttpMethod::POST,
-            url: "https://api.example.com/users".to_string(),
             headers: vec![("Content-Type".to_string(), "application/json".to_string())],
             body: BodyType::Raw {
                 content: "{\"name\": \"ada\"}".to_string(),
                 subtype: RawSubtype::Json,
             },
-            auth: crate::types::AuthConfig::default(),
+            ..RequestData::new(HttpMethod::POST, "https://api.example.com/users".to_string())
         }
     }
 
@@ -569,8 +579,6 @@ mod tests {
     /// UI-pinned multipart Content-Type header that must NOT be exported.
     fn form_req() -> RequestData {
         RequestData {
-            method: HttpMethod::POST,
-            url: "https://api.example.com/upload".to_string(),
             headers: vec![
                 ("Accept".to_string(), "application/json".to_string()),
                 (
@@ -595,7 +603,7 @@ mod tests {
                     value: FormDataValue::Text("nope".to_string()),
                 },
             ]),
-            auth: crate::types::AuthConfig::default(),
+            ..RequestData::new(HttpMethod::POST, "https://api.example.com/upload".to_string())
         }
     }
 
@@ -630,6 +638,22 @@ mod tests {
         assert!(generate(CodeTarget::Curl, &req).contains("--header 'X-API-Key: secret'"));
     }
 
+    #[test]
+    fn code_gen_api_key_in_query_is_part_of_the_url() {
+        use crate::types::{ApiKeyPlacement, AuthConfig, AuthType};
+        let mut req = get_req();
+        req.auth = AuthConfig {
+            auth_type: AuthType::ApiKey,
+            api_key_name: "key".into(),
+            api_key_value: "secret".into(),
+            api_key_in: ApiKeyPlacement::Query,
+            ..Default::default()
+        };
+        let out = generate(CodeTarget::Curl, &req);
+        assert!(out.contains("'https://api.example.com/users?key=secret'"));
+        assert!(!out.contains("--header 'key: secret'"));
+    }
+
     #[test]
     fn code_gen_auth_overrides_manual_same_name_header() {
         use crate::types::{AuthConfig, AuthType};
@@ -686,6 +710,17 @@ mod tests {
         assert!(out.contains(".body(r#\"{\"name\": \"ada\"}\"#)"));
     }
 
+    #[test]
+    fn custom_methods_are_spelled_out() {
+        let mut req = get_req();
+        req.method = HttpMethod::Custom("PROPFIND".into());
+        assert!(generate(CodeTarget::Curl, &req).contains("--request PROPFIND 'https://api.example.com/users'"));
+        assert!(generate(CodeTarget::RustReqwest, &req).contains(".request(reqwest::Method::from_bytes(b\"PROPFIND\")?, "));
+        assert!(generate(CodeTarget::PythonRequests, &req).contains("requests.request(\"PROPFIND\", url"));
+        req.method = HttpMethod::Custom("A&B".into());
+        assert!(generate(CodeTarget::Curl, &req).contains("--request 'A&B' "));
+    }
+
     #[test]
     fn python_omits_payload_when_no_body() {
         let out = generate(CodeTarget::PythonRequests, &get_req());
@@ -962,4 +997,145 @@ mod tests {
         let out = generate(CodeTarget::Curl, &post_json_req());
         assert!(out.contains("--header 'Content-Type: application/json'"));
     }
+
+    /// The JSON POST with a query string, as the snippet panel shows it.
+    fn golden_req() -> RequestData {
+        RequestData {
+            url: "https://api.example.com/users?page=2&q=a%20b".to_string(),
+            ..post_json_req()
+        }
+    }
+
+    #[test]
+    fn python_golden_snippet() {
+        let expected = r#"import requests
+
+url = "https://api.example.com/users?page=2&q=a%20b"
+
+headers = {
+    "Content-Type": "application/json",
+}
+payload = """{"name": "ada"}"""
+
+response = requests.request("POST", url, headers=headers, data=payload)
+print(response.text)
+"#;
+        assert_eq!(generate(CodeTarget::PythonRequests, &golden_req()), expected);
+    }
+
+    #[test]
+    fn fetch_golden_snippet() {
+        let expected = r#"const myHeaders = new Headers();
+myHeaders.append("Content-Type", "application/json");
+
+const requestOptions = {
+  method: "POST",
+  headers: myHeaders,
+  body: `{"name": "ada"}`,
+  redirect: "follow",
+};
+
+fetch("https://api.example.com/users?page=2&q=a%20b", requestOptions)
+  .then((response) => response.text())
+  .then((result) => console.log(result))
+  .catch((error) => console.error(error));
+"#;
+        assert_eq!(generate(CodeTarget::JavaScriptFetch, &golden_req()), expected);
+    }
+
+    #[test]
+    fn go_golden_snippet() {
+        let expected = "package main
+
+import (
+\t\"fmt\"
+\t\"io\"
+\t\"net/http\"
+\t\"strings\"
+)
+
+func main() {
+\turl := \"https://api.example.com/users?page=2&q=a%20b\"
+\tmethod := \"POST\"
+
+\tpayload := strings.NewReader(`{\"name\": \"ada\"}`)
+
+\tclient := &http.Client{}
+\treq, err := http.NewRequest(method, url, payload)
+\tif err != nil {
+\t\tfmt.Println(err)
+\t\treturn
+\t}
+\treq.Header.Add(\"Content-Type\", \"application/json\")
+
+\tres, err := client.Do(req)
+\tif err != nil {
+\t\tfmt.Println(err)
+\t\treturn
+\t}
+\tdefer res.Body.Close()
+
+\tbody, err := io.ReadAll(res.Body)
+\tif err != nil {
+\t\tfmt.Println(err)
+\t\treturn
+\t}
+\tfmt.Println(string(body))
+}
+";
+        assert_eq!(generate(CodeTarget::GoNetHttp, &golden_req()), expected);
+    }
+
+    #[test]
+    fn rust_golden_snippet() {
+        let expected = r##"use reqwest::blocking::Client;
+use reqwest::header::{HeaderMap, HeaderValue};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = Client::new();
+
+    let mut headers = HeaderMap::new();
+    headers.insert("Content-Type", HeaderValue::from_str("application/json")?);
+
+    let response = client
+        .request(reqwest::Method::POST, "https://api.example.com/users?page=2&q=a%20b")
+        .headers(headers)
+        .body(r#"{"name": "ada"}"#)
+        .send()?;
+
+    println!("{}", response.text()?);
+    Ok(())
+}
+"##;
+        assert_eq!(generate(CodeTarget::RustReqwest, &golden_req()), expected);
+    }
+
+    #[test]
+    fn rust_multipart_golden_snippet() {
+        let expected = r#"use reqwest::blocking::Client;
+use reqwest::blocking::multipart;
+use reqwest::header::{HeaderMap, HeaderValue};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = Client::new();
+
+    let mut headers = HeaderMap::new();
+    headers.insert("Accept", HeaderValue::from_str("application/json")?);
+
+    let form = multipart::Form::new()
+        .text("note", "hello world")
+        .file("avatar", "C:\\pics\\me.png")?;
+
+    let response = client
+        .request(reqwest::Method::POST, "https://api.example.com/upload")
+        .headers(headers)
+        .multipart(form)
+        .send()?;
+
+    println!("{}", response.text()?);
+    Ok(())
+}
+"#;
+        assert_eq!(generate(CodeTarget::RustReqwest, &form_req()), expected);
+    }
 }
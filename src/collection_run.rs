@@ -0,0 +1,272 @@
+//! The collection runner: every request of a collection (or folder) sent in
+//! order, for a number of iterations, with each one's status, time and
+//! Tests recorded. The sending lives in `runner_panel`; this module resolves
+//! saved requests for the wire and keeps the results, so it is pure.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::assertions::{self, AssertionResult};
+use crate::postman_import::CollectionFolder;
+use crate::types::{Preset, RequestData, ResponseData};
+
+/// Upper bound of the iterations field.
+pub const MAX_ITERATIONS: usize = 1000;
+
+/// Response body kept per result for the details row and the export.
+const BODY_PREVIEW_CHARS: usize = 2000;
+
+/// One request of the run, in collection order.
+#[derive(Debug, Clone)]
+pub struct RunStep {
+    pub name: String,
+    /// "Folder / Subfolder" under the collection; empty at its top level.
+    pub folder: String,
+    pub request: RequestData,
+}
+
+/// The requests of `folder` in the order they run: a folder's own requests,
+/// then each subfolder's (as the tree lists them).
+pub fn steps(folder: &CollectionFolder) -> Vec<RunStep> {
+    folder
+        .flatten()
+        .into_iter()
+        .map(|(path, saved)| RunStep { name: saved.name.clone(), folder: path.join(" / "), request: saved.request.clone() })
+        .collect()
+}
+
+/// A saved request as it goes on the wire (see `request_builder::build`).
+/// Its headers are all taken as ones the user added, so they take
+/// precedence over the presets' as in the editor.
+pub fn prepare(request: &RequestData, presets: &[Preset], vars: &HashMap<String, String>) -> Result<RequestData, String> {
+    let opts = crate::request_builder::SendOptions { default_headers: Vec::new(), presets, vars };
+    crate::request_builder::build(request, &opts)
+        .map(|built| built.sent)
+        .map_err(|e| format!("Request not sent: {e}"))
+}
+
+/// What one send of the run got.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunEntry {
+    /// 1-based.
+    pub iteration: usize,
+    pub name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub folder: String,
+    pub method: String,
+    pub url: String,
+    /// `None` when there was no response; `error` says why.
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub tests: Vec<AssertionResult>,
+    pub response_headers: Vec<(String, String)>,
+    /// The start of the response body.
+    pub body: String,
+}
+
+impl RunEntry {
+    /// The entry for `sent` (the wire request) and the response it got.
+    pub fn new(iteration: usize, step: &RunStep, sent: &RequestData, response: &ResponseData) -> Self {
+        let body: String = response.body_text().chars().take(BODY_PREVIEW_CHARS).collect();
+        Self {
+            iteration,
+            name: step.name.clone(),
+            folder: step.folder.clone(),
+            method: sent.method.as_str().to_string(),
+            url: sent.url.clone(),
+            status: response.status,
+            duration_ms: response.duration_ms,
+            error: response.status.is_none().then(|| body.clone()),
+            tests: assertions::evaluate(&sent.assertions, response),
+            response_headers: response.headers.clone(),
+            body: if response.status.is_some() { body } else { String::new() },
+        }
+    }
+
+    /// The entry for a request that could not be sent (`prepare` failed).
+    pub fn not_sent(iteration: usize, step: &RunStep, error: String) -> Self {
+        Self {
+            iteration,
+            name: step.name.clone(),
+            folder: step.folder.clone(),
+            method: step.request.method.as_str().to_string(),
+            url: step.request.url.clone(),
+            status: None,
+            duration_ms: 0,
+            error: Some(error),
+            tests: Vec::new(),
+            response_headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    /// Passed: a response came back and every test passed. A request
+    /// without tests passes on any status below 400.
+    pub fn passed(&self) -> bool {
+        match self.status {
+            None => false,
+            Some(_) if !self.tests.is_empty() => self.tests.iter().all(|t| t.passed),
+            Some(status) => status < 400,
+        }
+    }
+}
+
+/// A whole run; also what "Export JSON" writes.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunReport {
+    pub collection: String,
+    pub environment: Option<String>,
+    pub iterations: usize,
+    pub delay_ms: u64,
+    pub total_ms: u64,
+    /// Stopped by the user.
+    pub canceled: bool,
+    /// Stopped at the first failure ("Stop on failure").
+    pub stopped_on_failure: bool,
+    pub entries: Vec<RunEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub tests_passed: usize,
+    pub tests_failed: usize,
+}
+
+impl RunReport {
+    pub fn summary(&self) -> RunSummary {
+        let passed = self.entries.iter().filter(|e| e.passed()).count();
+        let tests = self.entries.iter().flat_map(|e| &e.tests);
+        let tests_passed = tests.clone().filter(|t| t.passed).count();
+        RunSummary {
+            passed,
+            failed: self.entries.len() - passed,
+            tests_passed,
+            tests_failed: tests.count() - tests_passed,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assertions::{Assertion, Check};
+    use crate::postman_import::CollectionRequest;
+    use crate::types::{AuthConfig, AuthType, HttpMethod};
+
+    fn request(url: &str) -> RequestData {
+        RequestData::new(HttpMethod::GET, url.to_string())
+    }
+
+    fn response(status: Option<u16>, body: &str) -> ResponseData {
+        ResponseData {
+            status,
+            duration_ms: 42,
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: body.as_bytes().to_vec(),
+            is_text: true,
+            route: Default::default(),
+            raw_header_values: vec![],
+            network_profile: Default::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
+        }
+    }
+
+    fn step(request: RequestData) -> RunStep {
+        RunStep { name: "Get".into(), folder: String::new(), request }
+    }
+
+    #[test]
+    fn steps_follow_the_tree_order() {
+        let named = |name: &str| CollectionRequest { name: name.into(), request: request("x") };
+        let folder = CollectionFolder {
+            name: "API".into(),
+            folders: vec![CollectionFolder { name: "Users".into(), folders: vec![], requests: vec![named("List users")] }],
+            requests: vec![named("Login")],
+        };
+        let steps: Vec<(String, String)> = steps(&folder).into_iter().map(|s| (s.name, s.folder)).collect();
+        assert_eq!(steps, [("Login".into(), String::new()), ("List users".into(), "Users".into())]);
+    }
+
+    #[test]
+    fn prepare_resolves_vars_presets_and_auth() {
+        let mut saved = request("{{host}}/items");
+        saved.headers = vec![("X-Tenant".into(), "{{tenant}}".into())];
+        saved.auth = AuthConfig { auth_type: AuthType::Bearer, bearer_token: "{{token}}".into(), ..Default::default() };
+        saved.presets = vec![1];
+        let preset = Preset {
+            id: 1,
+            name: "p".into(),
+            headers: vec![("X-Tenant".into(), "preset".into()), ("X-Trace".into(), "on".into())],
+            params: vec![("v".into(), "2".into())],
+        };
+        let vars: HashMap<String, String> =
+            [("host", "api.test"), ("tenant", "acme"), ("token", "t0k")].map(|(k, v)| (k.into(), v.into())).into();
+        let sent = prepare(&saved, &[preset], &vars).unwrap();
+        assert_eq!(sent.url, "https://api.test/items?v=2");
+        assert!(sent.headers.contains(&("X-Tenant".into(), "acme".into())));
+        assert!(sent.headers.contains(&("X-Trace".into(), "on".into())));
+        assert!(sent.headers.contains(&("Authorization".into(), "Bearer t0k".into())));
+        assert!(prepare(&request("  "), &[], &vars).unwrap_err().contains("URL is empty"));
+    }
+
+    #[test]
+    fn prepare_fills_in_path_variables() {
+        let mut saved = request("https://api.test/users/:id");
+        let vars: HashMap<String, String> = [("user", "7".to_string())].map(|(k, v)| (k.into(), v)).into();
+        assert!(prepare(&saved, &[], &vars).unwrap_err().contains(":id has no value"));
+        saved.path_variables = vec![("id".into(), "{{user}}".into())];
+        assert_eq!(prepare(&saved, &[], &vars).unwrap().url, "https://api.test/users/7");
+    }
+
+    #[test]
+    fn prepare_leaves_the_body_off_a_get() {
+        let mut saved = request("https://api.test/search");
+        saved.headers = vec![("Content-Type".into(), "application/json".into())];
+        saved.body = crate::types::BodyType::Raw { content: "{}".into(), subtype: crate::types::RawSubtype::Json };
+        let sent = prepare(&saved, &[], &HashMap::new()).unwrap();
+        assert_eq!(sent.body, crate::types::BodyType::None);
+        assert!(sent.headers.is_empty());
+        saved.send_body_anyway = true;
+        let sent = prepare(&saved, &[], &HashMap::new()).unwrap();
+        assert_eq!(sent.body, saved.body);
+        assert_eq!(sent.headers, saved.headers);
+    }
+
+    #[test]
+    fn entries_pass_on_their_tests_or_else_the_status() {
+        let mut sent = request("https://x.test/");
+        sent.method = HttpMethod::POST;
+        let plain = RunEntry::new(1, &step(sent.clone()), &sent, &response(Some(404), "{}"));
+        assert!(!plain.passed());
+        assert_eq!(plain.method, "POST");
+        sent.assertions = vec![Assertion::new(Check::StatusEquals, "", "404")];
+        let tested = RunEntry::new(1, &step(sent.clone()), &sent, &response(Some(404), "{}"));
+        assert!(tested.passed());
+        let failed = RunEntry::new(2, &step(sent.clone()), &sent, &response(None, "connection refused"));
+        assert!(!failed.passed());
+        assert_eq!(failed.error.as_deref(), Some("connection refused"));
+
+        let report = RunReport { entries: vec![plain, tested, failed], ..Default::default() };
+        assert_eq!(report.summary(), RunSummary { passed: 1, failed: 2, tests_passed: 1, tests_failed: 1 });
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["entries"][2]["status"], serde_json::Value::Null);
+        assert_eq!(json["entries"][1]["tests"][0]["passed"], true);
+    }
+}
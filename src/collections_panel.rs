@@ -0,0 +1,449 @@
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{
+    button::*, h_flex,
+    menu::{ContextMenuExt as _, PopupMenuItem},
+    scroll::ScrollableElement as _,
+    v_flex, ActiveTheme as _, Sizable as _,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::a11y::{self, tab_order};
+use crate::db::Database;
+use crate::postman_import::CollectionFolder;
+use crate::types::{Collection, SavedRequest};
+
+/// Indent per tree level, in pixels.
+const INDENT: f32 = 14.;
+
+/// Event emitted when a saved request is clicked
+#[derive(Clone)]
+pub struct SavedRequestClicked {
+    pub saved: SavedRequest,
+}
+
+/// Event emitted when "Delete…" is picked on a collection or folder; the app
+/// asks for confirmation before anything is removed.
+#[derive(Clone)]
+pub struct CollectionDeleteRequested {
+    pub collection: Collection,
+}
+
+/// Event emitted by the header's "New" button.
+#[derive(Clone)]
+pub struct NewCollectionRequested;
+
+/// Event emitted to export a collection (or folder) as a Postman collection.
+#[derive(Clone)]
+pub struct CollectionExportRequested {
+    pub folder: CollectionFolder,
+}
+
+/// Event emitted to run a collection (or folder) with the collection runner.
+#[derive(Clone)]
+pub struct CollectionRunRequested {
+    pub folder: CollectionFolder,
+}
+
+/// A saved request being dragged in the tree; also draws itself as the drag
+/// preview.
+#[derive(Clone)]
+struct DraggedSavedRequest {
+    id: i64,
+    name: SharedString,
+}
+
+impl Render for DraggedSavedRequest {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        div()
+            .px_2()
+            .py_1()
+            .rounded(theme.radius)
+            .bg(theme.background)
+            .border_1()
+            .border_color(theme.border)
+            .shadow_sm()
+            .text_sm()
+            .text_color(theme.foreground)
+            .child(self.name.clone())
+    }
+}
+
+/// Collections sidebar: collections and their folders as an expandable tree,
+/// saved requests as its leaves.
+pub struct CollectionsPanel {
+    db: Arc<Database>,
+    collections: Vec<Collection>,
+    saved: Vec<SavedRequest>,
+    /// Collections and folders currently expanded.
+    expanded: HashSet<i64>,
+    selected_id: Option<i64>,
+    list_scroll_handle: ScrollHandle,
+}
+
+impl CollectionsPanel {
+    pub fn new(db: Arc<Database>, cx: &mut Context<Self>) -> Self {
+        // Same as History: the first load must not wait on the database
+        // opening during startup.
+        let loader = db.clone();
+        cx.spawn(async move |this, cx| {
+            let loaded = cx
+                .background_executor()
+                .spawn(async move { loader.load_collections().and_then(|c| Ok((c, loader.load_saved_requests()?))) })
+                .await;
+            this.update(cx, |this, cx| match loaded {
+                Ok((collections, saved)) => {
+                    this.collections = collections;
+                    this.saved = saved;
+                    cx.notify();
+                }
+                Err(e) => log::error!("Failed to load collections: {}", e),
+            })
+        })
+        .detach();
+
+        Self {
+            db,
+            collections: vec![],
+            saved: vec![],
+            expanded: HashSet::new(),
+            selected_id: None,
+            list_scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    /// Re-read collections and saved requests from the database.
+    pub fn reload(&mut self, cx: &mut Context<Self>) {
+        match self.db.load_collections() {
+            Ok(collections) => self.collections = collections,
+            Err(e) => log::error!("Failed to load collections: {}", e),
+        }
+        match self.db.load_saved_requests() {
+            Ok(saved) => self.saved = saved,
+            Err(e) => log::error!("Failed to load saved requests: {}", e),
+        }
+        cx.notify();
+    }
+
+    /// Every collection and folder as `(id, "Collection / Folder")`, in tree
+    /// order — the choices for "Save to".
+    pub fn collection_paths(&self) -> Vec<(i64, String)> {
+        let mut paths = Vec::new();
+        self.walk(None, "", &mut paths);
+        paths
+    }
+
+    fn walk(&self, parent_id: Option<i64>, prefix: &str, out: &mut Vec<(i64, String)>) {
+        for collection in self.collections.iter().filter(|c| c.parent_id == parent_id) {
+            let path = format!("{}{}", prefix, collection.name);
+            out.push((collection.id, path.clone()));
+            self.walk(Some(collection.id), &format!("{path} / "), out);
+        }
+    }
+
+    /// Expand a collection and all its parents, e.g. after saving into it.
+    pub fn reveal(&mut self, collection_id: i64, cx: &mut Context<Self>) {
+        let mut next = Some(collection_id);
+        while let Some(id) = next {
+            self.expanded.insert(id);
+            next = self.collections.iter().find(|c| c.id == id).and_then(|c| c.parent_id);
+        }
+        cx.notify();
+    }
+
+    /// A collection's subtree in the shape the Postman exporter takes.
+    fn folder(&self, collection: &Collection) -> CollectionFolder {
+        CollectionFolder::from_saved(collection, &self.collections, &self.saved)
+    }
+
+    fn toggle(&mut self, id: i64, cx: &mut Context<Self>) {
+        if !self.expanded.remove(&id) {
+            self.expanded.insert(id);
+        }
+        cx.notify();
+    }
+
+    fn on_saved_click(&mut self, saved: &SavedRequest, cx: &mut Context<Self>) {
+        self.selected_id = Some(saved.id);
+        cx.emit(SavedRequestClicked { saved: saved.clone() });
+        cx.notify();
+    }
+
+    /// Move a saved request to `index` among `collection_id`'s requests and
+    /// re-read the tree.
+    fn move_saved(&mut self, id: i64, collection_id: i64, index: usize, cx: &mut Context<Self>) {
+        if let Err(e) = self.db.move_saved_request(id, collection_id, index) {
+            log::error!("Failed to move saved request: {}", e);
+            return;
+        }
+        self.expanded.insert(collection_id);
+        self.reload(cx);
+    }
+
+    /// Context-menu fallback for dragging: one place up (`-1`) or down (`1`)
+    /// within the request's own collection.
+    fn nudge_saved(&mut self, id: i64, step: isize, cx: &mut Context<Self>) {
+        let Some(saved) = self.saved.iter().find(|s| s.id == id) else {
+            return;
+        };
+        let collection_id = saved.collection_id;
+        let position = self.position_in_collection(id);
+        let Some(index) = position.checked_add_signed(step) else {
+            return;
+        };
+        self.move_saved(id, collection_id, index, cx);
+    }
+
+    /// Index of a saved request among its collection's requests.
+    fn position_in_collection(&self, id: i64) -> usize {
+        let Some(collection_id) = self.saved.iter().find(|s| s.id == id).map(|s| s.collection_id) else {
+            return 0;
+        };
+        self.saved
+            .iter()
+            .filter(|s| s.collection_id == collection_id)
+            .position(|s| s.id == id)
+            .unwrap_or(0)
+    }
+
+    fn delete_saved(&mut self, id: i64, cx: &mut Context<Self>) {
+        if let Err(e) = self.db.delete_saved_request(id) {
+            log::error!("Failed to delete saved request: {}", e);
+            return;
+        }
+        self.saved.retain(|s| s.id != id);
+        cx.notify();
+    }
+
+    /// Rows for `parent_id`'s children: sub-collections first, then saved
+    /// requests; expanded collections are followed by their own rows.
+    fn render_children(&self, parent_id: Option<i64>, depth: usize, cx: &Context<Self>) -> Vec<AnyElement> {
+        let mut rows = Vec::new();
+        for collection in self.collections.iter().filter(|c| c.parent_id == parent_id) {
+            let open = self.expanded.contains(&collection.id);
+            rows.push(self.render_collection(collection, open, depth, cx).into_any_element());
+            if open {
+                rows.extend(self.render_children(Some(collection.id), depth + 1, cx));
+            }
+        }
+        if let Some(collection_id) = parent_id {
+            for saved in self.saved.iter().filter(|s| s.collection_id == collection_id) {
+                rows.push(self.render_saved(saved, depth, cx).into_any_element());
+            }
+        }
+        rows
+    }
+
+    fn render_collection(&self, collection: &Collection, open: bool, depth: usize, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let id = collection.id;
+        let panel = cx.entity();
+        let menu_collection = collection.clone();
+        let folder = self.folder(collection);
+
+        a11y::focusable(h_flex().id(("collection", id as u64)), theme)
+            .gap_1p5()
+            .w_full()
+            .pl(px(8. + depth as f32 * INDENT))
+            .pr_2()
+            .py_1p5()
+            .rounded(theme.radius_lg)
+            .cursor_pointer()
+            .hover(|s| s.bg(theme.list_hover))
+            .on_click(cx.listener(move |this, _event: &gpui::ClickEvent, _window, cx| this.toggle(id, cx)))
+            // Dropping a request on a collection or folder appends it there.
+            .drag_over::<DraggedSavedRequest>(|style, _, _, cx| style.bg(cx.theme().drop_target))
+            .on_drop(cx.listener(move |this, dragged: &DraggedSavedRequest, _window, cx| {
+                this.move_saved(dragged.id, id, usize::MAX, cx);
+            }))
+            .child(
+                div()
+                    .flex_shrink_0()
+                    .w(px(10.))
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .child(if open { "\u{25be}" } else { "\u{25b8}" }),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .text_sm()
+                    .when(depth == 0, |this| this.font_weight(FontWeight::SEMIBOLD))
+                    .text_color(theme.foreground)
+                    .overflow_x_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .child(collection.name.clone()),
+            )
+            .context_menu(move |menu, _window, _cx| {
+                let (run_panel, run_folder) = (panel.clone(), folder.clone());
+                let (export_panel, folder) = (panel.clone(), folder.clone());
+                let (delete_panel, collection) = (panel.clone(), menu_collection.clone());
+                menu.item(PopupMenuItem::new("Run\u{2026}").on_click(move |_, _window, cx| {
+                    let folder = run_folder.clone();
+                    run_panel.update(cx, |_, cx| cx.emit(CollectionRunRequested { folder }));
+                }))
+                .item(PopupMenuItem::new("Export to Postman\u{2026}").on_click(move |_, _window, cx| {
+                    let folder = folder.clone();
+                    export_panel.update(cx, |_, cx| cx.emit(CollectionExportRequested { folder }));
+                }))
+                .item(PopupMenuItem::new("Delete\u{2026}").on_click(move |_, _window, cx| {
+                    let collection = collection.clone();
+                    delete_panel.update(cx, |_, cx| cx.emit(CollectionDeleteRequested { collection }));
+                }))
+            })
+    }
+
+    fn render_saved(&self, saved: &SavedRequest, depth: usize, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let id = saved.id;
+        let is_selected = self.selected_id == Some(id);
+        let method = saved.request.method.clone();
+        let saved_clone = saved.clone();
+        let panel = cx.entity();
+        let collection_id = saved.collection_id;
+        let position = self.position_in_collection(id);
+        let siblings = self.saved.iter().filter(|s| s.collection_id == collection_id).count();
+        let dragged = DraggedSavedRequest { id, name: saved.name.clone().into() };
+
+        a11y::focusable(h_flex().id(("saved-request", id as u64)), theme)
+            .gap_2()
+            .w_full()
+            .pl(px(8. + depth as f32 * INDENT))
+            .pr_2()
+            .py_1p5()
+            .rounded(theme.radius_lg)
+            .border_1()
+            .border_color(if is_selected { theme.list_active_border } else { gpui::transparent_black() })
+            .bg(if is_selected { theme.list_active } else { gpui::transparent_black() })
+            .cursor_pointer()
+            .hover(|s| s.bg(if is_selected { theme.list_active } else { theme.list_hover }))
+            .on_click(cx.listener(move |this, _event: &gpui::ClickEvent, _window, cx| {
+                this.on_saved_click(&saved_clone, cx);
+            }))
+            .on_drag(dragged, |dragged, _, _, cx| {
+                cx.stop_propagation();
+                cx.new(|_| dragged.clone())
+            })
+            // Dropping on a request puts the dragged one in its place.
+            .drag_over::<DraggedSavedRequest>(|style, _, _, cx| style.border_color(cx.theme().drag_border))
+            .on_drop(cx.listener(move |this, dragged: &DraggedSavedRequest, _window, cx| {
+                if dragged.id != id {
+                    this.move_saved(dragged.id, collection_id, position, cx);
+                }
+            }))
+            .child(
+                div()
+                    .flex_shrink_0()
+                    .w(px(34.))
+                    .text_right()
+                    .text_xs()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(crate::theme::method_color(&method, theme))
+                    .child(crate::theme::method_badge(&method)),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .text_sm()
+                    .text_color(theme.foreground)
+                    .overflow_x_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .child(saved.name.clone()),
+            )
+            .context_menu(move |menu, _window, _cx| {
+                let (up_panel, down_panel, panel) = (panel.clone(), panel.clone(), panel.clone());
+                menu.item(PopupMenuItem::new("Move up").disabled(position == 0).on_click(move |_, _window, cx| {
+                    up_panel.update(cx, |panel, cx| panel.nudge_saved(id, -1, cx));
+                }))
+                .item(PopupMenuItem::new("Move down").disabled(position + 1 >= siblings).on_click(
+                    move |_, _window, cx| {
+                        down_panel.update(cx, |panel, cx| panel.nudge_saved(id, 1, cx));
+                    },
+                ))
+                .separator()
+                .item(PopupMenuItem::new("Delete").on_click(move |_, _window, cx| {
+                    panel.update(cx, |panel, cx| panel.delete_saved(id, cx));
+                }))
+            })
+    }
+}
+
+impl EventEmitter<SavedRequestClicked> for CollectionsPanel {}
+impl EventEmitter<CollectionDeleteRequested> for CollectionsPanel {}
+impl EventEmitter<NewCollectionRequested> for CollectionsPanel {}
+impl EventEmitter<CollectionExportRequested> for CollectionsPanel {}
+impl EventEmitter<CollectionRunRequested> for CollectionsPanel {}
+
+impl Render for CollectionsPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        // Shares History's tab slot: only one of the two is shown at a time.
+        a11y::tab_group(v_flex(), tab_order::HISTORY)
+            .size_full()
+            .child(
+                // Header
+                h_flex()
+                    .items_center()
+                    .justify_between()
+                    .p_3()
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(theme.foreground)
+                            .child("Collections"),
+                    )
+                    .child(
+                        Button::new("new-collection-btn")
+                            .xsmall()
+                            .ghost()
+                            .label("New")
+                            .tooltip("Create an empty collection")
+                            .on_click(cx.listener(|_, _, _window, cx| cx.emit(NewCollectionRequested))),
+                    ),
+            )
+            .when(self.collections.is_empty(), |this| {
+                this.child(
+                    div()
+                        .flex_1()
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .text_center()
+                        .text_color(theme.muted_foreground)
+                        .text_sm()
+                        .child("No collections yet\n\nSave a request to start one"),
+                )
+            })
+            .when(!self.collections.is_empty(), |this| {
+                this.child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .flex_1()
+                        .min_h_0()
+                        .w_full()
+                        .overflow_hidden()
+                        .child(
+                            v_flex()
+                                .id("collections-list-scroll")
+                                .flex_1()
+                                .w_full()
+                                .min_h_0()
+                                .track_scroll(&self.list_scroll_handle)
+                                .overflow_scroll()
+                                .child(v_flex().gap_0p5().px_2().py_1().children(self.render_children(None, 0, cx))),
+                        )
+                        .vertical_scrollbar(&self.list_scroll_handle),
+                )
+            })
+    }
+}
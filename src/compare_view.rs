@@ -0,0 +1,169 @@
+//! Body of the "Compare with tab…" / "Diff with current tab" dialogs: renders
+//! a `RequestDiff` as side-by-side rows per section, with changed rows
+//! highlighted and the body shown as a +/- line diff. Volatile headers
+//! (`request_diff::VOLATILE_HEADERS`) can be folded into one summary line.
+
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{checkbox::Checkbox, h_flex, v_flex, ActiveTheme as _, Theme};
+
+use crate::request_diff::{is_volatile_header, FieldDiff, RequestDiff, VOLATILE_HEADERS};
+use crate::text_diff::DiffLine;
+
+/// Tallest the report grows before scrolling inside the dialog.
+const REPORT_MAX_HEIGHT: f32 = 520.;
+
+fn section_title(theme: &Theme, title: &str) -> Div {
+    div()
+        .pt_2()
+        .text_xs()
+        .font_weight(FontWeight::BOLD)
+        .text_color(theme.muted_foreground)
+        .child(title.to_uppercase())
+}
+
+fn value_cell(theme: &Theme, value: Option<&str>) -> Div {
+    let cell = div().flex_1().min_w_0().text_sm().font_family("monospace");
+    match value {
+        Some(v) => cell.child(v.to_string()),
+        None => cell.text_color(theme.muted_foreground).italic().child("(missing)"),
+    }
+}
+
+fn field_row(theme: &Theme, field: &FieldDiff) -> Div {
+    let changed = field.is_changed();
+    h_flex()
+        .gap_3()
+        .px_2()
+        .py_0p5()
+        .rounded(theme.radius)
+        .when(changed, |this| this.bg(theme.warning.opacity(0.12)))
+        .child(
+            div()
+                .w(px(140.))
+                .flex_shrink_0()
+                .text_sm()
+                .when(changed, |this| this.font_weight(FontWeight::BOLD))
+                .child(field.name.clone()),
+        )
+        .child(value_cell(theme, field.left.as_deref()))
+        .child(value_cell(theme, field.right.as_deref()))
+}
+
+fn body_line(theme: &Theme, line: &DiffLine) -> Div {
+    let (marker, text, color) = match line {
+        DiffLine::Same(t) => (" ", t, None),
+        DiffLine::Removed(t) => ("-", t, Some(theme.danger)),
+        DiffLine::Added(t) => ("+", t, Some(theme.success)),
+    };
+    h_flex()
+        .px_2()
+        .text_sm()
+        .font_family("monospace")
+        .whitespace_nowrap()
+        .when_some(color, |this, c| this.bg(c.opacity(0.12)).text_color(c))
+        .child(div().w(px(16.)).flex_shrink_0().child(marker))
+        .child(text.clone())
+}
+
+/// The dialog content. `left` / `right` are the two side titles. With
+/// `hide_volatile`, volatile header rows are left out of the list and the
+/// count; `on_toggle_volatile` flips it from the checkbox in the header.
+pub fn render_request_diff(
+    diff: &RequestDiff,
+    left: &str,
+    right: &str,
+    hide_volatile: bool,
+    on_toggle_volatile: impl Fn(&bool, &mut Window, &mut App) + 'static,
+    cx: &App,
+) -> impl IntoElement {
+    let theme = cx.theme();
+    let changes = diff.change_count(hide_volatile);
+    let (hidden, headers): (Vec<FieldDiff>, Vec<FieldDiff>) = diff
+        .headers
+        .iter()
+        .cloned()
+        .partition(|h| hide_volatile && is_volatile_header(&h.name));
+    let fields = |items: &[FieldDiff]| -> Vec<Div> {
+        if items.is_empty() {
+            vec![div().px_2().text_sm().text_color(theme.muted_foreground).child("None on either side")]
+        } else {
+            items.iter().map(|f| field_row(theme, f)).collect()
+        }
+    };
+    let body_changed = diff.body.iter().any(DiffLine::is_change);
+
+    v_flex()
+        .gap_1()
+        .child(
+            h_flex()
+                .gap_3()
+                .px_2()
+                .pb_1()
+                .border_b_1()
+                .border_color(theme.border)
+                .text_xs()
+                .text_color(theme.muted_foreground)
+                .child(div().w(px(140.)).flex_shrink_0().child(match changes {
+                    0 => "Identical".to_string(),
+                    1 => "1 difference".to_string(),
+                    n => format!("{n} differences"),
+                }))
+                .child(div().flex_1().min_w_0().truncate().child(left.to_string()))
+                .child(div().flex_1().min_w_0().truncate().child(right.to_string())),
+        )
+        .child(
+            Checkbox::new("diff-hide-volatile")
+                .label(format!("Ignore {}", VOLATILE_HEADERS.join(", ")))
+                .checked(hide_volatile)
+                .on_click(on_toggle_volatile),
+        )
+        .child(
+            v_flex()
+                .id("request-diff-scroll")
+                .max_h(px(REPORT_MAX_HEIGHT))
+                .overflow_y_scroll()
+                .gap_1()
+                .child(section_title(theme, "Request"))
+                .child(field_row(theme, &diff.method))
+                .child(field_row(theme, &diff.base_url))
+                .child(section_title(theme, "Params"))
+                .children(fields(&diff.params))
+                .child(section_title(theme, "Headers"))
+                // All hidden: the summary line below stands in for the list.
+                .when(!headers.is_empty() || hidden.is_empty(), |this| this.children(fields(&headers)))
+                .when(!hidden.is_empty(), |this| {
+                    let changed = hidden.iter().filter(|h| h.is_changed()).count();
+                    let names: Vec<&str> = hidden.iter().map(|h| h.name.as_str()).collect();
+                    this.child(
+                        div()
+                            .px_2()
+                            .text_xs()
+                            .italic()
+                            .text_color(theme.muted_foreground)
+                            .child(format!("{} ignored ({changed} differing)", names.join(", "))),
+                    )
+                })
+                .child(section_title(theme, "Body"))
+                .child(field_row(theme, &diff.body_kind))
+                .when(!body_changed, |this| {
+                    this.child(
+                        div()
+                            .px_2()
+                            .text_sm()
+                            .text_color(theme.muted_foreground)
+                            .child(if diff.body.is_empty() { "Both empty" } else { "Same content" }),
+                    )
+                })
+                .when(body_changed, |this| {
+                    this.child(
+                        v_flex()
+                            .py_1()
+                            .rounded(theme.radius)
+                            .border_1()
+                            .border_color(theme.border)
+                            .children(diff.body.iter().map(|line| body_line(theme, line))),
+                    )
+                }),
+        )
+}
@@ -0,0 +1,135 @@
+//! DNS and connect times for `ResponseTiming`, which reqwest does not
+//! report. Every client `http_client` builds resolves through
+//! `TimedResolver` and connects through `TimedConnectLayer`; both add what
+//! they took to the request being measured, found through a task-local set
+//! by `measure`. A request sent on a kept-alive connection does neither, so
+//! both stay 0.
+
+use std::future::Future;
+use std::net::ToSocketAddrs as _;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+#[derive(Debug, Default)]
+struct Phases {
+    dns_us: AtomicU64,
+    /// The whole connector call, DNS included.
+    connect_us: AtomicU64,
+}
+
+tokio::task_local! {
+    static PHASES: Arc<Phases>;
+}
+
+/// What a request spent before it could be written, across every hop.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Measured {
+    pub dns_ms: u64,
+    /// TCP connect plus TLS handshake, DNS excluded.
+    pub connect_ms: u64,
+}
+
+/// Run `request`, timing the lookups and connections it makes.
+pub async fn measure<F: Future>(request: F) -> (F::Output, Measured) {
+    let phases = Arc::new(Phases::default());
+    let output = PHASES.scope(phases.clone(), request).await;
+    let dns_us = phases.dns_us.load(Ordering::Relaxed);
+    let connect_us = phases.connect_us.load(Ordering::Relaxed).saturating_sub(dns_us);
+    (output, Measured { dns_ms: dns_us / 1000, connect_ms: connect_us / 1000 })
+}
+
+fn current() -> Option<Arc<Phases>> {
+    PHASES.try_with(Arc::clone).ok()
+}
+
+fn add_since(counter: &AtomicU64, started: Instant) {
+    counter.fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+}
+
+/// reqwest's default resolver, `getaddrinfo` on a blocking thread, timed.
+pub struct TimedResolver;
+
+impl reqwest::dns::Resolve for TimedResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let phases = current();
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let started = Instant::now();
+            let addrs = tokio::task::spawn_blocking(move || (host.as_str(), 0).to_socket_addrs()).await??;
+            if let Some(phases) = phases {
+                add_since(&phases.dns_us, started);
+            }
+            Ok(Box::new(addrs) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Times reqwest's connector: resolving, TCP and TLS (and the proxy's
+/// CONNECT, through a proxy).
+#[derive(Clone)]
+pub struct TimedConnectLayer;
+
+impl<S> tower_layer::Layer<S> for TimedConnectLayer {
+    type Service = TimedConnect<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimedConnect { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct TimedConnect<S> {
+    inner: S,
+}
+
+impl<S, R> tower_service::Service<R> for TimedConnect<S>
+where
+    S: tower_service::Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let phases = current();
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let Some(phases) = phases else {
+                return connecting.await;
+            };
+            let started = Instant::now();
+            // hyper may finish the connection on a task of its own; carry the
+            // scope along so the resolver still finds it.
+            let connected = PHASES.scope(phases.clone(), connecting).await;
+            add_since(&phases.connect_us, started);
+            connected
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measure_sums_every_hop_and_takes_dns_out_of_connect() {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        let ((), measured) = runtime.block_on(measure(async {
+            let phases = current().expect("inside measure");
+            for _ in 0..2 {
+                phases.dns_us.fetch_add(2_000, Ordering::Relaxed);
+                phases.connect_us.fetch_add(5_000, Ordering::Relaxed);
+            }
+        }));
+        assert_eq!(measured, Measured { dns_ms: 4, connect_ms: 6 });
+        assert!(current().is_none());
+    }
+}
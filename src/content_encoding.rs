@@ -0,0 +1,202 @@
+//! `Content-Encoding` decoding for response bodies. reqwest can decompress
+//! by itself, but then it drops `Content-Encoding` and `Content-Length` and
+//! never says how many bytes came over the wire, which the status bar's
+//! size reports. So the clients ask for compression themselves
+//! (`ACCEPT_ENCODING`) and `http_client` decodes what arrives through a
+//! `Decoder`, counting the encoded bytes on the way in.
+
+use std::io::{self, Write};
+
+/// Sent on every request that has no `Accept-Encoding` of its own, unless
+/// it asked for minimal headers.
+pub const ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Gzip,
+    Deflate,
+    Brotli,
+}
+
+impl Encoding {
+    /// The coding of a response with these headers. `None` when the body is
+    /// not encoded, or encoded in a way not handled here (it is then shown
+    /// as sent).
+    pub fn of(headers: &[(String, String)]) -> Option<Self> {
+        let (_, value) = headers.iter().find(|(k, _)| k.eq_ignore_ascii_case("content-encoding"))?;
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Self::Gzip),
+            "deflate" => Some(Self::Deflate),
+            "br" => Some(Self::Brotli),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the decoded body into `W` as the encoded one is written to it,
+/// counting the encoded bytes.
+pub struct Decoder<W: Write> {
+    /// Only `None` while `write` swaps a `Pending` for its decoder.
+    inner: Option<Inner<W>>,
+    encoded_len: u64,
+}
+
+enum Inner<W: Write> {
+    Identity(W),
+    /// Encoded, but nothing has arrived yet: an empty body (HEAD, 204, 304)
+    /// is not a truncated stream.
+    Pending(Encoding, W),
+    Gzip(flate2::write::MultiGzDecoder<W>),
+    /// HTTP's "deflate" is zlib-wrapped (RFC 9110 §8.4.1.2).
+    Deflate(flate2::write::ZlibDecoder<W>),
+    Brotli(Box<brotli::DecompressorWriter<W>>),
+}
+
+impl<W: Write> Decoder<W> {
+    pub fn new(encoding: Option<Encoding>, inner: W) -> Self {
+        let inner = match encoding {
+            None => Inner::Identity(inner),
+            Some(encoding) => Inner::Pending(encoding, inner),
+        };
+        Self { inner: Some(inner), encoded_len: 0 }
+    }
+
+    fn inner(&mut self) -> &mut Inner<W> {
+        self.inner.as_mut().expect("decoder in use")
+    }
+
+    /// What has been decoded so far.
+    pub fn get_ref(&self) -> &W {
+        match self.inner.as_ref().expect("decoder in use") {
+            Inner::Identity(w) | Inner::Pending(_, w) => w,
+            Inner::Gzip(d) => d.get_ref(),
+            Inner::Deflate(d) => d.get_ref(),
+            Inner::Brotli(d) => d.get_ref(),
+        }
+    }
+
+    /// Bytes written in, as they came over the wire.
+    pub fn encoded_len(&self) -> u64 {
+        self.encoded_len
+    }
+
+    /// Decode what is left and hand back the writer. A gzip or br body that
+    /// ends mid-stream is an error; flate2 cannot tell for a zlib one, which
+    /// gives what decoded so far.
+    pub fn finish(self) -> io::Result<W> {
+        match self.inner.expect("decoder in use") {
+            Inner::Identity(w) | Inner::Pending(_, w) => Ok(w),
+            Inner::Gzip(d) => d.finish(),
+            Inner::Deflate(d) => d.finish(),
+            Inner::Brotli(d) => d
+                .into_inner()
+                .map_err(|_| io::Error::new(io::ErrorKind::UnexpectedEof, "brotli stream ended early")),
+        }
+    }
+}
+
+impl<W: Write> Write for Decoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !buf.is_empty()
+            && let Some(Inner::Pending(encoding, w)) = self.inner.take_if(|i| matches!(i, Inner::Pending(..)))
+        {
+            self.inner = Some(match encoding {
+                Encoding::Gzip => Inner::Gzip(flate2::write::MultiGzDecoder::new(w)),
+                Encoding::Deflate => Inner::Deflate(flate2::write::ZlibDecoder::new(w)),
+                Encoding::Brotli => Inner::Brotli(Box::new(brotli::DecompressorWriter::new(w, 8192))),
+            });
+        }
+        let written = match self.inner() {
+            Inner::Identity(w) | Inner::Pending(_, w) => w.write(buf),
+            Inner::Gzip(d) => d.write(buf),
+            Inner::Deflate(d) => d.write(buf),
+            Inner::Brotli(d) => d.write(buf),
+        }?;
+        self.encoded_len += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.inner() {
+            Inner::Identity(w) | Inner::Pending(_, w) => w.flush(),
+            Inner::Gzip(d) => d.flush(),
+            Inner::Deflate(d) => d.flush(),
+            Inner::Brotli(d) => d.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &[u8] = b"{\"items\":[1,2,3],\"note\":\"the same words again and again and again\"}";
+
+    fn encode(encoding: Encoding, bytes: &[u8]) -> Vec<u8> {
+        match encoding {
+            Encoding::Gzip => {
+                let mut e = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                e.write_all(bytes).unwrap();
+                e.finish().unwrap()
+            }
+            Encoding::Deflate => {
+                let mut e = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                e.write_all(bytes).unwrap();
+                e.finish().unwrap()
+            }
+            Encoding::Brotli => {
+                let mut out = Vec::new();
+                brotli::BrotliCompress(&mut &bytes[..], &mut out, &Default::default()).unwrap();
+                out
+            }
+        }
+    }
+
+    fn decode(encoding: Option<Encoding>, encoded: &[u8]) -> io::Result<Vec<u8>> {
+        let mut decoder = Decoder::new(encoding, Vec::new());
+        // In small pieces, the way a body stream hands it over.
+        for chunk in encoded.chunks(7) {
+            decoder.write_all(chunk)?;
+        }
+        decoder.finish()
+    }
+
+    #[test]
+    fn reads_the_coding_from_the_headers() {
+        let headers = |value: &str| vec![("Content-Encoding".to_string(), value.to_string())];
+        assert_eq!(Encoding::of(&headers("gzip")), Some(Encoding::Gzip));
+        assert_eq!(Encoding::of(&headers(" BR ")), Some(Encoding::Brotli));
+        assert_eq!(Encoding::of(&headers("deflate")), Some(Encoding::Deflate));
+        assert_eq!(Encoding::of(&headers("zstd")), None);
+        assert_eq!(Encoding::of(&[]), None);
+    }
+
+    #[test]
+    fn every_coding_round_trips_in_pieces() {
+        for encoding in [Encoding::Gzip, Encoding::Deflate, Encoding::Brotli] {
+            let encoded = encode(encoding, TEXT);
+            assert_eq!(decode(Some(encoding), &encoded).unwrap(), TEXT, "{encoding:?}");
+        }
+        assert_eq!(decode(None, TEXT).unwrap(), TEXT);
+    }
+
+    #[test]
+    fn a_body_cut_short_is_an_error_and_an_empty_one_is_not() {
+        for encoding in [Encoding::Gzip, Encoding::Brotli] {
+            let encoded = encode(encoding, TEXT);
+            assert!(decode(Some(encoding), &encoded[..encoded.len() / 2]).is_err(), "{encoding:?}");
+        }
+        for encoding in [Encoding::Gzip, Encoding::Deflate, Encoding::Brotli] {
+            assert_eq!(decode(Some(encoding), b"").unwrap(), b"", "{encoding:?}");
+        }
+    }
+
+    #[test]
+    fn counts_the_encoded_bytes() {
+        let encoded = encode(Encoding::Gzip, TEXT);
+        let mut decoder = Decoder::new(Some(Encoding::Gzip), Vec::new());
+        decoder.write_all(&encoded).unwrap();
+        assert_eq!(decoder.encoded_len(), encoded.len() as u64);
+        assert_eq!(decoder.finish().unwrap().len(), TEXT.len());
+    }
+}
@@ -0,0 +1,160 @@
+//! Precedence rules for the predefined Content-Type header row.
+//!
+//! The row follows the body editor's mode ("auto": `application/json`,
+//! `multipart/form-data; boundary=…`, nothing for a None body) until the user
+//! types a value of their own ("manual"), which then wins over every body
+//! change until it is reset. A custom `Content-Type` row wins over both.
+
+use crate::types::RawSubtype;
+
+/// Shown for form-data bodies; reqwest generates the real boundary at send
+/// time and replaces this header (see `http_client::execute`).
+pub const MULTIPART_AUTO: &str = "multipart/form-data; boundary=<auto>";
+
+/// Whether `value` is one the body editor produces by itself.
+pub fn is_auto_value(value: &str) -> bool {
+    value == MULTIPART_AUTO || RawSubtype::all().iter().any(|s| s.content_type() == value)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentTypeSync {
+    /// What the body editor's current mode implies (`None` for no body).
+    auto: Option<String>,
+    /// A value the user typed; wins over `auto` while set.
+    manual: Option<String>,
+}
+
+impl ContentTypeSync {
+    pub fn new(auto: Option<String>) -> Self {
+        Self { auto, manual: None }
+    }
+
+    /// Restore from a saved request. A saved value the editor could have
+    /// produced itself is treated as stale auto state (and corrected to the
+    /// body's mode), anything else as a manual override.
+    pub fn from_saved(auto: Option<String>, saved: Option<&str>) -> Self {
+        let manual = saved
+            .map(str::trim)
+            .filter(|v| !v.is_empty() && !is_auto_value(v))
+            .map(str::to_string);
+        Self { auto, manual }
+    }
+
+    /// The body editor switched mode.
+    pub fn set_auto(&mut self, auto: Option<String>) {
+        self.auto = auto;
+    }
+
+    /// The row's value field changed. Clearing it, or typing exactly the
+    /// automatic value, hands control back to the body editor.
+    pub fn edited(&mut self, value: &str) {
+        let value = value.trim();
+        self.manual = if value.is_empty() || Some(value) == self.auto.as_deref() {
+            None
+        } else {
+            Some(value.to_string())
+        };
+    }
+
+    pub fn reset(&mut self) {
+        self.manual = None;
+    }
+
+    /// The value the row should show; `None` means the row is switched off.
+    pub fn value(&self) -> Option<&str> {
+        self.manual.as_deref().or(self.auto.as_deref())
+    }
+
+    pub fn is_manual(&self) -> bool {
+        self.manual.is_some()
+    }
+}
+
+/// Keep only the last `Content-Type` in header order. Predefined rows come
+/// before custom ones, so a custom row overrides the predefined one instead of
+/// both going on the wire.
+pub fn last_content_type_wins(headers: Vec<(String, String)>) -> Vec<(String, String)> {
+    let is_ct = |k: &str| k.trim().eq_ignore_ascii_case("content-type");
+    let Some(last) = headers.iter().rposition(|(k, _)| is_ct(k)) else {
+        return headers;
+    };
+    headers
+        .into_iter()
+        .enumerate()
+        .filter(|(i, (k, _))| *i == last || !is_ct(k))
+        .map(|(_, h)| h)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json() -> Option<String> {
+        Some("application/json".to_string())
+    }
+
+    #[test]
+    fn auto_value_follows_the_body_mode() {
+        let mut sync = ContentTypeSync::new(json());
+        assert_eq!(sync.value(), Some("application/json"));
+        sync.set_auto(Some(MULTIPART_AUTO.to_string()));
+        assert_eq!(sync.value(), Some(MULTIPART_AUTO));
+        // No body: the row is switched off.
+        sync.set_auto(None);
+        assert_eq!(sync.value(), None);
+    }
+
+    #[test]
+    fn manual_value_wins_over_body_changes_until_reset() {
+        let mut sync = ContentTypeSync::new(json());
+        sync.edited("application/vnd.api+json");
+        assert!(sync.is_manual());
+        sync.set_auto(Some("text/plain".to_string()));
+        sync.set_auto(None);
+        assert_eq!(sync.value(), Some("application/vnd.api+json"));
+
+        sync.reset();
+        assert_eq!(sync.value(), None);
+    }
+
+    #[test]
+    fn clearing_or_retyping_the_auto_value_is_not_an_override() {
+        let mut sync = ContentTypeSync::new(json());
+        sync.edited("application/json");
+        assert!(!sync.is_manual());
+        sync.edited("text/csv");
+        sync.edited("  ");
+        assert!(!sync.is_manual());
+        assert_eq!(sync.value(), Some("application/json"));
+    }
+
+    #[test]
+    fn saved_values_only_restore_as_overrides_when_not_auto() {
+        // Stale auto value from an older save: corrected to the body's mode.
+        let sync = ContentTypeSync::from_saved(Some(MULTIPART_AUTO.into()), Some("application/json"));
+        assert!(!sync.is_manual());
+        assert_eq!(sync.value(), Some(MULTIPART_AUTO));
+
+        let sync = ContentTypeSync::from_saved(json(), Some("application/hal+json"));
+        assert_eq!(sync.value(), Some("application/hal+json"));
+
+        assert_eq!(ContentTypeSync::from_saved(None, None).value(), None);
+    }
+
+    #[test]
+    fn custom_content_type_row_overrides_the_predefined_one() {
+        let h = |k: &str, v: &str| (k.to_string(), v.to_string());
+        let headers = vec![
+            h("Content-Type", "application/json"),
+            h("Accept", "*/*"),
+            h("content-type", "text/xml"),
+        ];
+        assert_eq!(
+            last_content_type_wins(headers),
+            vec![h("Accept", "*/*"), h("content-type", "text/xml")]
+        );
+        let untouched = vec![h("Accept", "*/*")];
+        assert_eq!(last_content_type_wins(untouched.clone()), untouched);
+    }
+}
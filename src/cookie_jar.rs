@@ -0,0 +1,254 @@
+//! The cookie jar behind the "send cookies automatically" setting: keeps the
+//! cookies responses set and picks the ones a request should carry, by the
+//! RFC 6265 §5 domain, path, expiry and Secure rules. There is no public
+//! suffix list, so a server may set a cookie for a whole registrable domain
+//! like `co.uk`; fine for a developer tool talking to its own APIs.
+//!
+//! Pure: `http_client` owns the shared jar and feeds it each hop.
+
+use chrono::{DateTime, Duration, Utc};
+use url::Url;
+
+use crate::cookies::{parse_set_cookie, SetCookie};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    /// Lowercase, without a leading dot.
+    pub domain: String,
+    /// Set without a `Domain` attribute: sent to exactly `domain`, not its
+    /// subdomains.
+    pub host_only: bool,
+    pub path: String,
+    /// `None` for a session cookie, kept until the app quits.
+    pub expires_at: Option<DateTime<Utc>>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    /// In the order they were first set; a replaced cookie keeps its place.
+    cookies: Vec<StoredCookie>,
+}
+
+impl CookieJar {
+    pub const fn new() -> Self {
+        Self { cookies: Vec::new() }
+    }
+
+    /// Every cookie held, expired ones included until the next store.
+    pub fn cookies(&self) -> &[StoredCookie] {
+        &self.cookies
+    }
+
+    /// Take a `Set-Cookie` header from the response to `url`. A cookie for
+    /// another domain is refused; one that is already expired deletes the
+    /// cookie it names.
+    pub fn store(&mut self, url: &Url, header: &str, now: DateTime<Utc>) {
+        let SetCookie::Parsed(cookie) = parse_set_cookie(header) else {
+            return;
+        };
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+            return;
+        };
+        let (domain, host_only) = match cookie.domain.as_deref().map(|d| d.trim_start_matches('.').to_ascii_lowercase()) {
+            Some(domain) if !domain.is_empty() => {
+                if !domain_matches(&host, &domain) {
+                    return;
+                }
+                (domain, false)
+            }
+            _ => (host, true),
+        };
+        let path = match cookie.path {
+            Some(path) if path.starts_with('/') => path,
+            _ => default_path(url),
+        };
+        let expires_at = match (cookie.max_age, &cookie.expires) {
+            (Some(age), _) => Some(now + Duration::seconds(age.clamp(-1, 400 * 86_400))),
+            (None, Some(date)) => parse_cookie_date(date),
+            (None, None) => None,
+        };
+        let stored = StoredCookie {
+            name: cookie.name,
+            value: cookie.value,
+            domain,
+            host_only,
+            path,
+            expires_at,
+            secure: cookie.secure,
+            http_only: cookie.http_only,
+        };
+        let existing = self
+            .cookies
+            .iter()
+            .position(|c| c.name == stored.name && c.domain == stored.domain && c.path == stored.path);
+        let expired = stored.expires_at.is_some_and(|at| at <= now);
+        match (existing, expired) {
+            (Some(i), true) => {
+                self.cookies.remove(i);
+            }
+            (Some(i), false) => self.cookies[i] = stored,
+            (None, true) => {}
+            (None, false) => self.cookies.push(stored),
+        }
+        self.cookies.retain(|c| c.expires_at.is_none_or(|at| at > now));
+    }
+
+    /// The cookies a request to `url` carries, longest path first.
+    pub fn matching(&self, url: &Url, now: DateTime<Utc>) -> Vec<&StoredCookie> {
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+            return Vec::new();
+        };
+        let secure = url.scheme() == "https";
+        let mut matching: Vec<&StoredCookie> = self
+            .cookies
+            .iter()
+            .filter(|c| c.expires_at.is_none_or(|at| at > now))
+            .filter(|c| if c.host_only { host == c.domain } else { domain_matches(&host, &c.domain) })
+            .filter(|c| path_matches(url.path(), &c.path))
+            .filter(|c| secure || !c.secure)
+            .collect();
+        matching.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+        matching
+    }
+
+    /// The `Cookie` header for a request to `url`, if any cookie applies.
+    pub fn header_for(&self, url: &Url, now: DateTime<Utc>) -> Option<String> {
+        let matching = self.matching(url, now);
+        (!matching.is_empty()).then(|| {
+            matching.iter().map(|c| format!("{}={}", c.name, c.value)).collect::<Vec<_>>().join("; ")
+        })
+    }
+
+    pub fn remove(&mut self, domain: &str, path: &str, name: &str) {
+        self.cookies.retain(|c| !(c.domain == domain && c.path == path && c.name == name));
+    }
+
+    pub fn clear(&mut self) {
+        self.cookies.clear();
+    }
+}
+
+/// `host` is `domain` or one of its subdomains (never for an IP address).
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || (host.ends_with(domain)
+            && host[..host.len() - domain.len()].ends_with('.')
+            && host.parse::<std::net::IpAddr>().is_err())
+}
+
+/// RFC 6265 §5.1.4: `path` is `cookie_path` or below it.
+fn path_matches(path: &str, cookie_path: &str) -> bool {
+    path == cookie_path
+        || (path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || path[cookie_path.len()..].starts_with('/')))
+}
+
+/// RFC 6265 §5.1.4: the request path up to its last `/`, or `/`.
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(i) => url.path()[..i].to_string(),
+    }
+}
+
+/// An `Expires` date: IMF-fixdate, or the old `Wed, 21-Oct-2026` form.
+fn parse_cookie_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(date)
+        .or_else(|_| DateTime::parse_from_rfc2822(&date.replace('-', " ")))
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn header(jar: &CookieJar, to: &str) -> Option<String> {
+        jar.header_for(&url(to), now())
+    }
+
+    #[test]
+    fn login_cookie_is_sent_back_to_the_same_host() {
+        let mut jar = CookieJar::new();
+        jar.store(&url("https://api.test/auth/login"), "sid=abc; Path=/; HttpOnly", now());
+        assert_eq!(header(&jar, "https://api.test/users").as_deref(), Some("sid=abc"));
+        assert_eq!(header(&jar, "https://other.test/users"), None);
+        // No Domain attribute: the exact host only.
+        assert_eq!(header(&jar, "https://www.api.test/"), None);
+    }
+
+    #[test]
+    fn domain_cookies_reach_subdomains_and_foreign_domains_are_refused() {
+        let mut jar = CookieJar::new();
+        jar.store(&url("https://login.example.com/"), "a=1; Domain=.example.com", now());
+        jar.store(&url("https://login.example.com/"), "b=2; Domain=evil.test", now());
+        assert_eq!(jar.cookies().len(), 1);
+        assert_eq!(header(&jar, "https://api.example.com/").as_deref(), Some("a=1"));
+        assert_eq!(header(&jar, "https://example.com/").as_deref(), Some("a=1"));
+        assert_eq!(header(&jar, "https://notexample.com/"), None);
+    }
+
+    #[test]
+    fn paths_and_default_paths() {
+        let mut jar = CookieJar::new();
+        jar.store(&url("http://h.test/app/login"), "implicit=1", now());
+        jar.store(&url("http://h.test/"), "deep=2; Path=/app/admin", now());
+        assert_eq!(jar.cookies()[0].path, "/app");
+        assert_eq!(header(&jar, "http://h.test/app").as_deref(), Some("implicit=1"));
+        assert_eq!(header(&jar, "http://h.test/app/admin/x").as_deref(), Some("deep=2; implicit=1"));
+        assert_eq!(header(&jar, "http://h.test/application"), None);
+    }
+
+    #[test]
+    fn secure_cookies_only_go_over_https() {
+        let mut jar = CookieJar::new();
+        jar.store(&url("https://h.test/"), "s=1; Secure", now());
+        assert_eq!(header(&jar, "http://h.test/"), None);
+        assert_eq!(header(&jar, "https://h.test/").as_deref(), Some("s=1"));
+    }
+
+    #[test]
+    fn replacing_expiring_and_deleting() {
+        let mut jar = CookieJar::new();
+        let login = url("https://h.test/");
+        jar.store(&login, "a=1", now());
+        jar.store(&login, "b=1; Max-Age=60", now());
+        jar.store(&login, "a=2", now());
+        assert_eq!(header(&jar, "https://h.test/").as_deref(), Some("a=2; b=1"));
+
+        let later = now() + Duration::seconds(61);
+        assert_eq!(jar.header_for(&login, later).as_deref(), Some("a=2"));
+
+        jar.store(&login, "a=; Max-Age=0", now());
+        assert_eq!(header(&jar, "https://h.test/").as_deref(), Some("b=1"));
+        jar.store(&login, "b=; Expires=Thu, 01 Jan 1970 00:00:00 GMT", now());
+        assert!(jar.cookies().is_empty());
+
+        jar.store(&login, "c=3; Expires=Wed, 21-Oct-2026 07:28:00 GMT", now());
+        assert!(jar.cookies()[0].expires_at.is_some());
+    }
+
+    #[test]
+    fn remove_and_clear() {
+        let mut jar = CookieJar::new();
+        let login = url("https://h.test/");
+        jar.store(&login, "a=1", now());
+        jar.store(&login, "b=2", now());
+        jar.remove("h.test", "/", "a");
+        assert_eq!(header(&jar, "https://h.test/").as_deref(), Some("b=2"));
+        jar.clear();
+        assert_eq!(header(&jar, "https://h.test/"), None);
+    }
+}
@@ -0,0 +1,172 @@
+//! `Set-Cookie` response headers (RFC 6265 §4.1) split into the fields the
+//! response viewer's Cookies tab shows. Lenient like a browser: attributes in
+//! any order and any case, unknown ones ignored. A header with no usable
+//! `name=value` pair is kept as its raw text rather than dropped.
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResponseCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    /// The `Expires` date as sent.
+    pub expires: Option<String>,
+    /// `Max-Age` in seconds; wins over `Expires` when both are set.
+    pub max_age: Option<i64>,
+    pub http_only: bool,
+    pub secure: bool,
+    pub same_site: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetCookie {
+    Parsed(ResponseCookie),
+    /// A header that is not a cookie, as sent.
+    Malformed(String),
+}
+
+impl ResponseCookie {
+    /// The expiry column: "Session" without one, "Expired" for a deleting
+    /// `Max-Age`, otherwise how long it lives or the date it ends.
+    pub fn expiry(&self) -> String {
+        match (self.max_age, &self.expires) {
+            (Some(age), _) if age <= 0 => "Expired".to_string(),
+            (Some(age), _) => format!("in {}", lifetime(age)),
+            (None, Some(date)) => date.clone(),
+            (None, None) => "Session".to_string(),
+        }
+    }
+}
+
+/// A Max-Age in the largest whole unit: "30 days", "2 hours", "45 s".
+fn lifetime(seconds: i64) -> String {
+    let plural = |n: i64, unit: &str| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+    match seconds {
+        s if s >= 86_400 => plural(s / 86_400, "day"),
+        s if s >= 3_600 => plural(s / 3_600, "hour"),
+        s if s >= 60 => format!("{} min", s / 60),
+        s => format!("{} s", s),
+    }
+}
+
+/// Every `Set-Cookie` header in `headers`, in order.
+pub fn parse_set_cookies(headers: &[(String, String)]) -> Vec<SetCookie> {
+    headers
+        .iter()
+        .filter(|(k, _)| k.eq_ignore_ascii_case("set-cookie"))
+        .map(|(_, v)| parse_set_cookie(v))
+        .collect()
+}
+
+/// One `Set-Cookie` value.
+pub fn parse_set_cookie(header: &str) -> SetCookie {
+    let mut parts = header.split(';');
+    let Some((name, value)) = parts.next().and_then(|pair| pair.split_once('=')) else {
+        return SetCookie::Malformed(header.to_string());
+    };
+    let name = name.trim();
+    if name.is_empty() {
+        return SetCookie::Malformed(header.to_string());
+    }
+    let mut cookie = ResponseCookie {
+        name: name.to_string(),
+        value: value.trim().to_string(),
+        ..Default::default()
+    };
+    for attribute in parts {
+        let (key, value) = match attribute.split_once('=') {
+            Some((key, value)) => (key.trim(), Some(value.trim())),
+            None => (attribute.trim(), None),
+        };
+        let value = value.filter(|v| !v.is_empty()).map(str::to_string);
+        match key.to_ascii_lowercase().as_str() {
+            "domain" => cookie.domain = value,
+            "path" => cookie.path = value,
+            "expires" => cookie.expires = value,
+            // A Max-Age that is not a number is ignored, per the RFC.
+            "max-age" => cookie.max_age = value.and_then(|v| v.parse().ok()).or(cookie.max_age),
+            "httponly" => cookie.http_only = true,
+            "secure" => cookie.secure = true,
+            "samesite" => cookie.same_site = value,
+            _ => {}
+        }
+    }
+    SetCookie::Parsed(cookie)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(header: &str) -> ResponseCookie {
+        match parse_set_cookie(header) {
+            SetCookie::Parsed(cookie) => cookie,
+            SetCookie::Malformed(raw) => panic!("malformed: {raw}"),
+        }
+    }
+
+    #[test]
+    fn reads_every_attribute() {
+        let cookie = parsed(
+            "sid=abc123; Domain=.example.com; Path=/; Expires=Wed, 21 Oct 2026 07:28:00 GMT; \
+             Secure; HttpOnly; SameSite=Lax",
+        );
+        assert_eq!(
+            cookie,
+            ResponseCookie {
+                name: "sid".to_string(),
+                value: "abc123".to_string(),
+                domain: Some(".example.com".to_string()),
+                path: Some("/".to_string()),
+                expires: Some("Wed, 21 Oct 2026 07:28:00 GMT".to_string()),
+                max_age: None,
+                http_only: true,
+                secure: true,
+                same_site: Some("Lax".to_string()),
+            }
+        );
+        assert_eq!(cookie.expiry(), "Wed, 21 Oct 2026 07:28:00 GMT");
+    }
+
+    #[test]
+    fn attributes_in_any_order_and_case() {
+        let cookie = parsed("theme=dark;samesite=Strict;HTTPONLY;max-age=3600;path=/app;secure");
+        assert_eq!(cookie.path.as_deref(), Some("/app"));
+        assert_eq!(cookie.same_site.as_deref(), Some("Strict"));
+        assert!(cookie.http_only && cookie.secure);
+        assert_eq!(cookie.max_age, Some(3600));
+        assert_eq!(cookie.expiry(), "in 1 hour");
+        assert_eq!(parsed("a=1; Max-Age=2592000").expiry(), "in 30 days");
+        assert_eq!(parsed("a=1; Max-Age=90").expiry(), "in 1 min");
+    }
+
+    #[test]
+    fn values_may_contain_equals_and_be_empty() {
+        assert_eq!(parsed("token=a=b==; Path=/").value, "a=b==");
+        assert_eq!(parsed("gone=; Max-Age=0").expiry(), "Expired");
+        assert_eq!(parsed("plain=1").expiry(), "Session");
+    }
+
+    #[test]
+    fn collects_every_set_cookie_header_in_order() {
+        let headers = vec![
+            ("Set-Cookie".to_string(), "a=1".to_string()),
+            ("Content-Type".to_string(), "text/plain".to_string()),
+            ("set-cookie".to_string(), "b=2; Secure".to_string()),
+            ("set-cookie".to_string(), "no pair here".to_string()),
+        ];
+        let cookies = parse_set_cookies(&headers);
+        assert_eq!(cookies.len(), 3);
+        assert!(matches!(&cookies[0], SetCookie::Parsed(c) if c.name == "a" && !c.secure));
+        assert!(matches!(&cookies[1], SetCookie::Parsed(c) if c.name == "b" && c.secure));
+        assert_eq!(cookies[2], SetCookie::Malformed("no pair here".to_string()));
+    }
+
+    #[test]
+    fn malformed_entries_are_kept_raw() {
+        assert_eq!(parse_set_cookie(""), SetCookie::Malformed(String::new()));
+        assert_eq!(parse_set_cookie("=value; Path=/"), SetCookie::Malformed("=value; Path=/".to_string()));
+        // A bad Max-Age is ignored, not fatal.
+        assert_eq!(parsed("a=1; Max-Age=soon").max_age, None);
+    }
+}
@@ -127,6 +127,7 @@ pub fn parse_curl(input: &str) -> Option<RequestData> {
     let mut data_parts: Vec<String> = Vec::new();
     let mut form_rows: Vec<FormDataRow> = Vec::new();
     let mut auth = AuthConfig::default();
+    let mut ignore_tls_errors = false;
 
     let mut i = 1;
     while i < tokens.len() {
@@ -195,6 +196,8 @@ pub fn parse_curl(input: &str) -> Option<RequestData> {
                     ..AuthConfig::default()
                 };
             }
+        } else if tok == "-k" || tok == "--insecure" {
+            ignore_tls_errors = true;
         } else if matches_flag(&tok, "", "--url") {
             if let Some(v) = flag_value(&tokens, &mut i, "", "--url")
                 && url.is_empty()
@@ -240,7 +243,13 @@ pub fn parse_curl(input: &str) -> Option<RequestData> {
         HttpMethod::POST
     });
 
-    Some(RequestData { method, url, headers, body, auth })
+    Some(RequestData {
+        headers,
+        body,
+        auth,
+        ignore_tls_errors,
+        ..RequestData::new(method, url)
+    })
 }
 
 #[cfg(test)]
@@ -291,6 +300,7 @@ mod tests {
     fn attached_and_equals_forms() {
         assert_eq!(parse("curl -XPOST https://example.com").method, HttpMethod::POST);
         assert_eq!(parse("curl --request=PATCH https://example.com").method, HttpMethod::PATCH);
+        assert_eq!(parse("curl -X PURGE https://example.com").method, HttpMethod::Custom("PURGE".into()));
     }
 
     #[test]
@@ -463,6 +473,13 @@ mod tests {
         assert_eq!(r.url, "https://example.com");
     }
 
+    #[test]
+    fn insecure_flag_ignores_tls_errors() {
+        assert!(parse("curl -k https://localhost:8443").ignore_tls_errors);
+        assert!(parse("curl --insecure https://localhost:8443").ignore_tls_errors);
+        assert!(!parse("curl https://localhost:8443").ignore_tls_errors);
+    }
+
     #[test]
     fn unknown_flags_are_skipped() {
         let r = parse("curl -s -L --compressed https://example.com");
@@ -8,20 +8,41 @@
 //! construction and a panic inside one query can't poison a lock for the others.
 
 use anyhow::{anyhow, Result};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, types::ValueRef, Connection, OptionalExtension};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::mpsc::{self, Sender};
 use std::thread;
+use std::time::Instant;
 
-use crate::types::{AuthConfig, BodyType, Environment, EnvVar, HistoryItem, HttpMethod, RequestData};
+use crate::env_overrides::EnvOverrides;
+use crate::history_meta::{BodyKind, HistoryMeta};
+use crate::http_client::ProxyOptions;
+use crate::postman_import::CollectionFolder;
+use crate::types::{
+    BodyType, Collection, Environment, EnvVar, HistoryItem, HostOverride, HttpMethod, NetworkProfile,
+    PredefinedHeader, Preset, RequestData, ResponseData, SavedRequest, SendRoute, TabColor,
+};
+use crate::workspace_bundle::{with_local_presets, ImportPlan, Workspace};
+
+/// Response bodies stored with history are cut to this many bytes unless
+/// the setting says otherwise.
+pub const DEFAULT_HISTORY_BODY_CAP: u64 = 1 << 20;
+
+/// Caps offered in the Edit menu; 0 = store whole bodies.
+pub const HISTORY_BODY_CAP_CHOICES: [u64; 4] = [64 << 10, DEFAULT_HISTORY_BODY_CAP, 10 << 20, 0];
 
 /// A unit of work executed on the database's owning thread.
 type Job = Box<dyn FnOnce(&mut Connection) + Send>;
 
-/// Map a `history` row (id, timestamp, method, url, request_headers,
-/// request_body, request_auth) into a `HistoryItem`. Shared by
-/// `load_recent_history` and `search_history` so the two queries can never
-/// drift in how they decode a row.
+/// The `history` columns `row_to_history_item` decodes, in its index order.
+const HISTORY_COLUMNS: &str = "id, timestamp, method, url, request_headers, request_body, request_auth,
+     request_route, body_kind, body_preview, param_count, header_count, tab_color, minimal_headers,
+     network_profile, chunked, pinned, ignore_tls_errors, status_code, assertions, presets, extract, path_variables,
+     send_body_anyway, run_report";
+
+/// Map a `history` row (`HISTORY_COLUMNS`) into a `HistoryItem`. Shared by
+/// every history query so they can never drift in how they decode a row.
 fn row_to_history_item(row: &rusqlite::Row) -> rusqlite::Result<HistoryItem> {
     let id: i64 = row.get(0)?;
     let timestamp: String = row.get(1)?;
@@ -30,6 +51,8 @@ fn row_to_history_item(row: &rusqlite::Row) -> rusqlite::Result<HistoryItem> {
     let request_headers: String = row.get(4)?;
     let request_body: String = row.get(5)?;
     let request_auth: Option<String> = row.get(6)?;
+    let request_route: Option<String> = row.get(7)?;
+    let body_kind: Option<String> = row.get(8)?;
 
     let headers: Vec<(String, String)> =
         serde_json::from_str(&request_headers).unwrap_or_default();
@@ -38,15 +61,86 @@ fn row_to_history_item(row: &rusqlite::Row) -> rusqlite::Result<HistoryItem> {
         .as_deref()
         .and_then(|s| serde_json::from_str(s).ok())
         .unwrap_or_default();
+    let route: SendRoute = request_route
+        .as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
 
     let request = RequestData {
-        method: HttpMethod::from_str(&method).unwrap_or(HttpMethod::GET),
-        url,
         headers,
         body,
         auth,
+        route,
+        minimal_headers: row.get::<_, Option<bool>>(13)?.unwrap_or(false),
+        network_profile: row
+            .get::<_, Option<String>>(14)?
+            .as_deref()
+            .and_then(|s| serde_json::from_str::<NetworkProfile>(s).ok())
+            .unwrap_or_default(),
+        chunked: row.get::<_, Option<bool>>(15)?.unwrap_or(false),
+        ignore_tls_errors: row.get::<_, Option<bool>>(17)?.unwrap_or(false),
+        assertions: row
+            .get::<_, Option<String>>(19)?
+            .as_deref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default(),
+        presets: row
+            .get::<_, Option<String>>(20)?
+            .as_deref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default(),
+        extract: row
+            .get::<_, Option<String>>(21)?
+            .as_deref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default(),
+        path_variables: row
+            .get::<_, Option<String>>(22)?
+            .as_deref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default(),
+        send_body_anyway: row.get::<_, Option<bool>>(23)?.unwrap_or(false),
+        ..RequestData::new(HttpMethod::from_str(&method).unwrap_or(HttpMethod::GET), url)
     };
-    Ok(HistoryItem::new(id, timestamp, request, None))
+    let pinned = row.get::<_, Option<bool>>(16)?.unwrap_or(false);
+    // Rows written before the meta columns existed get theirs computed here.
+    let meta = match body_kind {
+        Some(kind) => HistoryMeta {
+            body_kind: BodyKind::from_str(&kind),
+            body_preview: row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+            param_count: row.get::<_, Option<i64>>(10)?.unwrap_or(0) as usize,
+            header_count: row.get::<_, Option<i64>>(11)?.unwrap_or(0) as usize,
+        },
+        None => HistoryMeta::of(&request.url, &request.headers, &request.body),
+    };
+    let mut item = HistoryItem::new(id, timestamp, request, None);
+    item.meta = meta;
+    item.color = row.get::<_, Option<String>>(12)?.as_deref().and_then(TabColor::from_str);
+    item.pinned = pinned;
+    item.status = row.get(18)?;
+    item.run_report = row
+        .get::<_, Option<String>>(24)?
+        .as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .map(std::sync::Arc::new);
+    Ok(item)
+}
+
+/// Insert `folder` and everything under it into `collections` and
+/// `saved_requests`, returning its id.
+fn insert_collection_tree(tx: &rusqlite::Transaction, folder: &CollectionFolder, parent_id: Option<i64>) -> Result<i64> {
+    tx.execute("INSERT INTO collections (parent_id, name) VALUES (?1, ?2)", params![parent_id, folder.name])?;
+    let id = tx.last_insert_rowid();
+    for (position, saved) in folder.requests.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO saved_requests (collection_id, name, request, sort_order) VALUES (?1, ?2, ?3, ?4)",
+            params![id, saved.name, serde_json::to_string(&saved.request)?, position as i64],
+        )?;
+    }
+    for sub in &folder.folders {
+        insert_collection_tree(tx, sub, Some(id))?;
+    }
+    Ok(id)
 }
 
 /// Escape a user query so SQLite `LIKE` treats `%`, `_`, and `\` literally.
@@ -65,24 +159,39 @@ pub struct Database {
 }
 
 impl Database {
-    /// Open (or create) the on-disk database and start its owning thread.
-    pub fn new() -> Result<Self> {
-        let db_path = Self::get_db_path()?;
+    /// Open (or create) the on-disk database on its owning thread and return
+    /// right away, so opening and the schema DDL stay off the startup path.
+    /// Calls made meanwhile queue up and run once it is open; if it cannot be
+    /// opened the error is logged and every call fails.
+    pub fn open_in_background() -> Self {
+        Self::spawn_with(|| {
+            let db_path = Self::get_db_path()?;
 
-        // Create directory if it doesn't exist
-        if let Some(parent) = db_path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+            // Create directory if it doesn't exist
+            if let Some(parent) = db_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
 
-        let conn = Connection::open(&db_path)?;
-        Self::init_schema(&conn)?;
-        Ok(Self::spawn(conn))
+            let conn = Connection::open(&db_path)?;
+            Self::init_schema(&conn)?;
+            Ok(conn)
+        })
     }
 
-    /// Move an initialized connection onto its owning thread and return a handle.
-    fn spawn(mut conn: Connection) -> Self {
+    /// Start the owning thread, which runs `open` and then serves jobs.
+    fn spawn_with(open: impl FnOnce() -> Result<Connection> + Send + 'static) -> Self {
         let (tx, rx) = mpsc::channel::<Job>();
         thread::spawn(move || {
+            let started = Instant::now();
+            let mut conn = match open() {
+                Ok(conn) => conn,
+                Err(e) => {
+                    // Dropping `rx` makes every pending and later call fail.
+                    log::error!("Failed to initialize database: {}", e);
+                    return;
+                }
+            };
+            log::info!("Database ready in {}ms", started.elapsed().as_millis());
             // Run jobs until every handle (and thus every Sender) is dropped, at
             // which point recv() errors and the thread exits cleanly.
             while let Ok(job) = rx.recv() {
@@ -98,7 +207,7 @@ impl Database {
     pub(crate) fn new_in_memory() -> Self {
         let conn = Connection::open_in_memory().expect("open in-memory db");
         Self::init_schema(&conn).expect("init schema");
-        Self::spawn(conn)
+        Self::spawn_with(move || Ok(conn))
     }
 
     /// Send `f` to the owning thread and block until it returns a result.
@@ -149,27 +258,197 @@ impl Database {
                  value TEXT NOT NULL,
                  position INTEGER NOT NULL DEFAULT 0
              );
+             CREATE TABLE IF NOT EXISTS global_variables (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 enabled INTEGER NOT NULL DEFAULT 1,
+                 key TEXT NOT NULL,
+                 value TEXT NOT NULL,
+                 secret INTEGER NOT NULL DEFAULT 0,
+                 position INTEGER NOT NULL DEFAULT 0
+             );
+             CREATE TABLE IF NOT EXISTS collections (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 parent_id INTEGER REFERENCES collections(id) ON DELETE CASCADE,
+                 name TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS saved_requests (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 collection_id INTEGER NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+                 name TEXT NOT NULL,
+                 request TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS presets (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 name TEXT NOT NULL,
+                 headers TEXT NOT NULL,
+                 params TEXT NOT NULL
+             );
              CREATE TABLE IF NOT EXISTS app_meta (
                  key TEXT PRIMARY KEY,
                  value TEXT NOT NULL
              );",
         )?;
         Self::migrate_add_request_auth(conn)?;
+        Self::migrate_add_run_report(conn)?;
+        Self::migrate_add_request_route(conn)?;
+        Self::migrate_add_history_meta(conn)?;
+        Self::migrate_add_tab_color(conn)?;
+        Self::migrate_add_minimal_headers(conn)?;
+        Self::migrate_add_network_profile(conn)?;
+        Self::migrate_add_chunked(conn)?;
+        Self::migrate_add_pinned(conn)?;
+        Self::migrate_add_ignore_tls_errors(conn)?;
+        Self::migrate_add_env_secret(conn)?;
+        Self::migrate_add_saved_request_order(conn)?;
+        Self::migrate_add_response_size(conn)?;
+        Self::migrate_add_response_timing(conn)?;
+        Self::migrate_add_response_raw_header_values(conn)?;
+        Self::migrate_add_assertions(conn)?;
+        Self::migrate_add_history_presets(conn)?;
+        Self::migrate_add_extract(conn)?;
+        Self::migrate_add_path_variables(conn)?;
+        Self::migrate_add_send_body_anyway(conn)?;
         Ok(())
     }
 
-    /// Idempotently add the `request_auth` column. SQLite has no
-    /// `ADD COLUMN IF NOT EXISTS`, so check `PRAGMA table_info` first. Old rows
-    /// read back as NULL → `AuthConfig::default()`.
+    /// Idempotently add the `request_auth` column. Old rows read back as
+    /// NULL → `AuthConfig::default()`.
     fn migrate_add_request_auth(conn: &Connection) -> Result<()> {
-        let mut stmt = conn.prepare("PRAGMA table_info(history)")?;
+        Self::add_column_if_missing(conn, "history", "request_auth", "TEXT")
+    }
+
+    /// Idempotently add the `run_report` column: the serialized
+    /// `RepeatReport` of a "Send ×N" batch, NULL for ordinary sends.
+    fn migrate_add_run_report(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "run_report", "TEXT")
+    }
+
+    /// Idempotently add the `request_route` column (serialized `SendRoute`).
+    /// Old rows read back as NULL → `SendRoute::System`, which is how they
+    /// were sent.
+    fn migrate_add_request_route(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "request_route", "TEXT")
+    }
+
+    /// Idempotently add the row-chip columns (`HistoryMeta`). Old rows read
+    /// back as NULL and have their meta computed on load.
+    fn migrate_add_history_meta(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "body_kind", "TEXT")?;
+        Self::add_column_if_missing(conn, "history", "body_preview", "TEXT")?;
+        Self::add_column_if_missing(conn, "history", "param_count", "INTEGER")?;
+        Self::add_column_if_missing(conn, "history", "header_count", "INTEGER")
+    }
+
+    /// Idempotently add the `tab_color` column: the color label of the tab a
+    /// request was sent from, NULL when it had none.
+    fn migrate_add_tab_color(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "tab_color", "TEXT")
+    }
+
+    /// Idempotently add the `minimal_headers` column: 1 when the request was
+    /// sent with only user-added headers, NULL (= off) for older rows.
+    fn migrate_add_minimal_headers(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "minimal_headers", "INTEGER")
+    }
+
+    /// Idempotently add the `network_profile` column: the simulated network
+    /// profile (JSON) a throttled request was sent under, NULL when unthrottled.
+    fn migrate_add_network_profile(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "network_profile", "TEXT")
+    }
+
+    /// Idempotently add the `chunked` column: 1 when the body was sent with
+    /// chunked transfer encoding, NULL (= off) for older rows.
+    fn migrate_add_chunked(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "chunked", "INTEGER")
+    }
+
+    /// Idempotently add the `pinned` column: 1 for rows pinned to the start
+    /// panel of blank tabs, NULL (= not pinned) otherwise.
+    fn migrate_add_pinned(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "pinned", "INTEGER")
+    }
+
+    /// Idempotently add the `ignore_tls_errors` column: 1 when the request
+    /// was sent without certificate verification, NULL (= verified) otherwise.
+    fn migrate_add_ignore_tls_errors(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "ignore_tls_errors", "INTEGER")
+    }
+
+    /// Idempotently add the env_variables `secret` column: 1 for variables
+    /// shown masked, NULL (= plain) for older rows.
+    fn migrate_add_env_secret(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "env_variables", "secret", "INTEGER")
+    }
+
+    /// Idempotently add the saved_requests `sort_order` column: a request's
+    /// place within its collection. Older rows read back as 0 and keep their
+    /// insertion order (ties fall back to id).
+    fn migrate_add_saved_request_order(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "saved_requests", "sort_order", "INTEGER NOT NULL DEFAULT 0")
+    }
+
+    /// Idempotently add the `response_size` column: the full size of a
+    /// stored response body, which `response_body` may hold only the start
+    /// of. NULL when no response was stored.
+    fn migrate_add_response_size(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "response_size", "INTEGER")
+    }
+
+    /// Idempotently add the `response_timing` column: the stored response's
+    /// `ResponseTiming` as JSON. NULL for rows from before it was kept.
+    fn migrate_add_response_timing(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "response_timing", "TEXT")
+    }
+
+    /// Idempotently add the `response_raw_header_values` column: the stored
+    /// response's non-ASCII header bytes (`RawHeaderValue`s) as JSON. NULL
+    /// when every value was ASCII, and for rows from before it was kept.
+    fn migrate_add_response_raw_header_values(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "response_raw_header_values", "TEXT")
+    }
+
+    /// Idempotently add the `assertions` column: the request's Tests (JSON),
+    /// NULL when it had none.
+    fn migrate_add_assertions(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "assertions", "TEXT")
+    }
+
+    /// Idempotently add the history `presets` column: the ids of the presets
+    /// a request was sent with (JSON), NULL when it had none.
+    fn migrate_add_history_presets(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "presets", "TEXT")
+    }
+
+    /// Idempotently add the `extract` column: the request's chaining rules
+    /// (JSON), NULL when it had none.
+    fn migrate_add_extract(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "extract", "TEXT")
+    }
+
+    /// Idempotently add the `path_variables` column: the values of the URL's
+    /// `:name` segments (JSON), NULL when it had none.
+    fn migrate_add_path_variables(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "path_variables", "TEXT")
+    }
+
+    /// Idempotently add the `send_body_anyway` column: 1 when a GET or HEAD
+    /// went out with its body, NULL (= off) for older rows.
+    fn migrate_add_send_body_anyway(conn: &Connection) -> Result<()> {
+        Self::add_column_if_missing(conn, "history", "send_body_anyway", "INTEGER")
+    }
+
+    /// SQLite has no `ADD COLUMN IF NOT EXISTS`, so check `PRAGMA table_info`
+    /// first. `table`/`column`/`decl` are always literals from this file.
+    fn add_column_if_missing(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
         let has_column = stmt
             .query_map([], |row| row.get::<_, String>(1))?
             .filter_map(|r| r.ok())
-            .any(|name| name == "request_auth");
+            .any(|name| name == column);
         drop(stmt);
         if !has_column {
-            conn.execute("ALTER TABLE history ADD COLUMN request_auth TEXT", [])?;
+            conn.execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"), [])?;
         }
         Ok(())
     }
@@ -180,45 +459,196 @@ impl Database {
         Ok(home.join(".poopman").join("history.db"))
     }
 
-    /// Insert a new history item (request only, no response - aligned with Postman)
-    pub fn insert_history(
-        &self,
-        method: &str,
-        url: &str,
-        request_headers: &str,
-        request_body: &BodyType,
-        auth: &AuthConfig,
-    ) -> Result<i64> {
-        let method = method.to_string();
-        let url = url.to_string();
-        let request_headers = request_headers.to_string();
-        // Serialize body type + auth to JSON before crossing the channel.
-        let body_json = serde_json::to_string(request_body).unwrap_or_default();
-        let auth_json = serde_json::to_string(auth).unwrap_or_default();
+    /// Insert a new history item: the request and everything it was sent
+    /// with, in one statement. The response follows (`set_history_response`).
+    pub fn insert_history(&self, request: &RequestData) -> Result<i64> {
+        let method = request.method.as_str().to_string();
+        let url = request.url.clone();
+        // Serialize everything to JSON before crossing the channel; the
+        // optional columns stay NULL when empty or off.
+        let headers_json = serde_json::to_string(&request.headers)?;
+        let body_json = serde_json::to_string(&request.body)?;
+        let auth_json = serde_json::to_string(&request.auth)?;
+        let route_json = serde_json::to_string(&request.route)?;
+        let meta = HistoryMeta::of(&request.url, &request.headers, &request.body);
+        let flag = |on: bool| on.then_some(1);
+        let (minimal_headers, chunked) = (flag(request.minimal_headers), flag(request.chunked));
+        let (ignore_tls_errors, send_body_anyway) = (flag(request.ignore_tls_errors), flag(request.send_body_anyway));
+        let network_profile =
+            request.network_profile.is_throttled().then(|| serde_json::to_string(&request.network_profile)).transpose()?;
+        let assertions = (!request.assertions.is_empty()).then(|| serde_json::to_string(&request.assertions)).transpose()?;
+        let presets = (!request.presets.is_empty()).then(|| serde_json::to_string(&request.presets)).transpose()?;
+        let extract = (!request.extract.is_empty()).then(|| serde_json::to_string(&request.extract)).transpose()?;
+        let path_variables =
+            (!request.path_variables.is_empty()).then(|| serde_json::to_string(&request.path_variables)).transpose()?;
 
         self.call(move |conn| {
             let timestamp = chrono::Utc::now().to_rfc3339();
             conn.execute(
-                "INSERT INTO history (timestamp, method, url, request_headers, request_body, request_auth)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                params![timestamp, method, url, request_headers, body_json, auth_json],
+                "INSERT INTO history (timestamp, method, url, request_headers, request_body, request_auth, request_route,
+                                      body_kind, body_preview, param_count, header_count, minimal_headers, network_profile,
+                                      chunked, ignore_tls_errors, assertions, presets, extract, path_variables,
+                                      send_body_anyway)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+                params![
+                    timestamp,
+                    method,
+                    url,
+                    headers_json,
+                    body_json,
+                    auth_json,
+                    route_json,
+                    meta.body_kind.as_str(),
+                    meta.body_preview,
+                    meta.param_count as i64,
+                    meta.header_count as i64,
+                    minimal_headers,
+                    network_profile,
+                    chunked,
+                    ignore_tls_errors,
+                    assertions,
+                    presets,
+                    extract,
+                    path_variables,
+                    send_body_anyway
+                ],
             )?;
             Ok(conn.last_insert_rowid())
         })
     }
 
+    /// Store the response a send got on its history row, the body cut to
+    /// `body_cap` bytes (0 = whole). Network errors and timeouts are stored
+    /// too, with a NULL status.
+    pub fn set_history_response(&self, id: i64, response: &ResponseData, body_cap: u64) -> Result<()> {
+        let headers = serde_json::to_string(&response.headers)?;
+        let size = response.truncated_after.unwrap_or(response.body.len() as u64);
+        let kept = match body_cap {
+            0 => response.body.len(),
+            cap => response.body.len().min(cap as usize),
+        };
+        let body = response.body[..kept].to_vec();
+        let timing = response.timing.map(|timing| serde_json::to_string(&timing)).transpose()?;
+        let raw_header_values = match response.raw_header_values.as_slice() {
+            [] => None,
+            raw => Some(serde_json::to_string(raw)?),
+        };
+        let (status, duration_ms) = (response.status, response.duration_ms as i64);
+        self.call(move |conn| {
+            conn.execute(
+                "UPDATE history
+                 SET status_code = ?1, duration_ms = ?2, response_headers = ?3, response_body = ?4,
+                     response_size = ?5, response_timing = ?6, response_raw_header_values = ?7
+                 WHERE id = ?8",
+                params![status, duration_ms, headers, body, size as i64, timing, raw_header_values, id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// The response stored on a history row, or None when the row has none
+    /// (sent before responses were kept, or a "Send ×N" batch).
+    pub fn load_history_response(&self, id: i64) -> Result<Option<ResponseData>> {
+        self.call(move |conn| {
+            let response = conn
+                .query_row(
+                    "SELECT status_code, duration_ms, response_headers, response_body, response_size, request_route,
+                            response_timing, response_raw_header_values
+                     FROM history WHERE id = ?1 AND duration_ms IS NOT NULL",
+                    params![id],
+                    |row| {
+                        let headers: Vec<(String, String)> = row
+                            .get::<_, Option<String>>(2)?
+                            .and_then(|json| serde_json::from_str(&json).ok())
+                            .unwrap_or_default();
+                        let body = match row.get_ref(3)? {
+                            ValueRef::Blob(bytes) | ValueRef::Text(bytes) => bytes.to_vec(),
+                            _ => Vec::new(),
+                        };
+                        let size = row.get::<_, Option<i64>>(4)?.unwrap_or(0) as u64;
+                        let route: SendRoute = row
+                            .get::<_, Option<String>>(5)?
+                            .and_then(|json| serde_json::from_str(&json).ok())
+                            .unwrap_or_default();
+                        let timing = row
+                            .get::<_, Option<String>>(6)?
+                            .and_then(|json| serde_json::from_str(&json).ok());
+                        let raw_header_values = row
+                            .get::<_, Option<String>>(7)?
+                            .and_then(|json| serde_json::from_str(&json).ok())
+                            .unwrap_or_default();
+                        Ok(ResponseData {
+                            status: row.get(0)?,
+                            duration_ms: row.get::<_, i64>(1)? as u64,
+                            is_text: crate::types::is_text_response(&headers, &body),
+                            truncated_after: (size > body.len() as u64).then_some(size),
+                            headers,
+                            body,
+                            route,
+                            raw_header_values,
+                            network_profile: Default::default(),
+                            timing,
+                            redirects: vec![],
+                            tls_unverified: false,
+                            attempts: 1,
+                            downloaded: None,
+                            wire_size: None,
+                            version: None,
+                            resolved_by: None,
+                        })
+                    },
+                )
+                .optional()?;
+            Ok(response)
+        })
+    }
+
+    /// Attach a "Send ×N" report (JSON) to a history row.
+    pub fn set_history_run_report(&self, id: i64, report_json: &str) -> Result<()> {
+        let report_json = report_json.to_string();
+        self.call(move |conn| {
+            conn.execute(
+                "UPDATE history SET run_report = ?1 WHERE id = ?2",
+                params![report_json, id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Record the color label of the tab a history row was sent from.
+    pub fn set_history_color(&self, id: i64, color: Option<TabColor>) -> Result<()> {
+        let color = color.map(|c| c.as_str());
+        self.call(move |conn| {
+            conn.execute("UPDATE history SET tab_color = ?1 WHERE id = ?2", params![color, id])?;
+            Ok(())
+        })
+    }
+
+    /// Pin a history row to the start panel, or unpin it.
+    pub fn set_history_pinned(&self, id: i64, pinned: bool) -> Result<()> {
+        let pinned = pinned.then_some(1);
+        self.call(move |conn| {
+            conn.execute("UPDATE history SET pinned = ?1 WHERE id = ?2", params![pinned, id])?;
+            Ok(())
+        })
+    }
+
     /// Load recent history items (request only, no response - aligned with Postman)
-    pub fn load_recent_history(&self, limit: usize) -> Result<Vec<HistoryItem>> {
+    pub fn load_recent_history(&self, limit: usize, offset: usize) -> Result<Vec<HistoryItem>> {
         self.call(move |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, timestamp, method, url, request_headers, request_body, request_auth
+            // Pages count unpinned rows only; the first page also brings every
+            // pinned row, however old.
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {HISTORY_COLUMNS}
                  FROM history
-                 ORDER BY timestamp DESC, id DESC
-                 LIMIT ?1",
-            )?;
+                 WHERE (pinned = 1 AND ?2 = 0)
+                    OR id IN (SELECT id FROM history WHERE pinned IS NOT 1
+                              ORDER BY timestamp DESC, id DESC LIMIT ?1 OFFSET ?2)
+                 ORDER BY timestamp DESC, id DESC"
+            ))?;
 
             // rusqlite 0.40 dropped the `ToSql` impl for `usize`; bind as i64.
-            let items = stmt.query_map([limit as i64], row_to_history_item)?;
+            let items = stmt.query_map([limit as i64, offset as i64], row_to_history_item)?;
 
             let mut result = Vec::new();
             for item in items {
@@ -228,19 +658,39 @@ impl Database {
         })
     }
 
-    /// Search history by URL or method (case-insensitive substring), newest
-    /// first, up to `limit` rows. An empty query matches everything.
-    pub fn search_history(&self, query: &str, limit: usize) -> Result<Vec<HistoryItem>> {
-        let pattern = format!("%{}%", escape_like(query));
+    /// Search history, newest first, `limit` rows from `offset`. The query is split
+    /// on whitespace and every word must match: as a case-insensitive
+    /// substring of the URL or method, or as a prefix of the status code
+    /// ("POST 5 /users" finds failing POSTs to /users). An empty query
+    /// matches everything.
+    pub fn search_history(&self, query: &str, limit: usize, offset: usize) -> Result<Vec<HistoryItem>> {
+        let words: Vec<String> = query.split_whitespace().map(escape_like).collect();
         self.call(move |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, timestamp, method, url, request_headers, request_body, request_auth
+            // One clause per word, each bringing two patterns: substring
+            // and prefix.
+            let clauses: Vec<String> = (0..words.len())
+                .map(|i| {
+                    let (sub, prefix) = (1 + 2 * i, 2 + 2 * i);
+                    format!(
+                        "(url LIKE ?{sub} ESCAPE '\\' OR method LIKE ?{sub} ESCAPE '\\'
+                          OR CAST(status_code AS TEXT) LIKE ?{prefix} ESCAPE '\\')"
+                    )
+                })
+                .collect();
+            let filter = if clauses.is_empty() { "1".to_string() } else { clauses.join(" AND ") };
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {HISTORY_COLUMNS}
                  FROM history
-                 WHERE url LIKE ?1 ESCAPE '\\' OR method LIKE ?1 ESCAPE '\\'
+                 WHERE {filter}
                  ORDER BY timestamp DESC, id DESC
-                 LIMIT ?2",
-            )?;
-            let items = stmt.query_map(params![pattern, limit as i64], row_to_history_item)?;
+                 LIMIT {limit} OFFSET {offset}"
+            ))?;
+            let mut values = Vec::with_capacity(words.len() * 2);
+            for word in &words {
+                values.push(format!("%{word}%"));
+                values.push(format!("{word}%"));
+            }
+            let items = stmt.query_map(rusqlite::params_from_iter(values), row_to_history_item)?;
             let mut result = Vec::new();
             for item in items {
                 result.push(item?);
@@ -249,6 +699,38 @@ impl Database {
         })
     }
 
+    /// The latest send of each of the `limit` most recently used URLs, newest
+    /// first.
+    pub fn load_recent_distinct_urls(&self, limit: usize) -> Result<Vec<HistoryItem>> {
+        self.call(move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {HISTORY_COLUMNS}
+                 FROM history h
+                 WHERE h.id = (SELECT h2.id FROM history h2 WHERE h2.url = h.url
+                               ORDER BY h2.timestamp DESC, h2.id DESC LIMIT 1)
+                 ORDER BY timestamp DESC, id DESC
+                 LIMIT ?1"
+            ))?;
+            let items = stmt.query_map([limit as i64], row_to_history_item)?;
+            Ok(items.collect::<rusqlite::Result<Vec<_>>>()?)
+        })
+    }
+
+    /// Pinned history rows, newest first.
+    pub fn load_pinned_history(&self, limit: usize) -> Result<Vec<HistoryItem>> {
+        self.call(move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {HISTORY_COLUMNS}
+                 FROM history
+                 WHERE pinned = 1
+                 ORDER BY timestamp DESC, id DESC
+                 LIMIT ?1"
+            ))?;
+            let items = stmt.query_map([limit as i64], row_to_history_item)?;
+            Ok(items.collect::<rusqlite::Result<Vec<_>>>()?)
+        })
+    }
+
     /// Delete a history item by ID
     #[allow(dead_code)]
     pub fn delete_history(&self, id: i64) -> Result<()> {
@@ -266,8 +748,15 @@ impl Database {
         })
     }
 
+    /// Clear history except the pinned rows.
+    pub fn clear_unpinned_history(&self) -> Result<()> {
+        self.call(|conn| {
+            conn.execute("DELETE FROM history WHERE pinned IS NOT 1", [])?;
+            Ok(())
+        })
+    }
+
     /// Get total history count
-    #[allow(dead_code)]
     pub fn get_history_count(&self) -> Result<usize> {
         self.call(|conn| {
             let count: i64 = conn.query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))?;
@@ -290,7 +779,7 @@ impl Database {
             let mut result = Vec::with_capacity(env_rows.len());
             for (id, name) in env_rows {
                 let mut vstmt = conn.prepare(
-                    "SELECT enabled, key, value FROM env_variables
+                    "SELECT enabled, key, value, secret FROM env_variables
                      WHERE environment_id = ?1 ORDER BY position, id",
                 )?;
                 let variables = vstmt
@@ -299,6 +788,7 @@ impl Database {
                             enabled: row.get::<_, i64>(0)? != 0,
                             key: row.get(1)?,
                             value: row.get(2)?,
+                            secret: row.get::<_, Option<i64>>(3)?.unwrap_or(0) != 0,
                         })
                     })?
                     .collect::<rusqlite::Result<Vec<_>>>()?;
@@ -321,6 +811,29 @@ impl Database {
         })
     }
 
+    /// Copy an environment and its variables to a new one named `name`,
+    /// placed last. Returns the copy's id.
+    pub fn duplicate_environment(&self, id: i64, name: &str) -> Result<i64> {
+        let name = name.to_string();
+        self.call(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT INTO environments (name, position)
+                 VALUES (?1, (SELECT COALESCE(MAX(position), 0) + 1 FROM environments))",
+                params![name],
+            )?;
+            let copy = tx.last_insert_rowid();
+            tx.execute(
+                "INSERT INTO env_variables (environment_id, enabled, key, value, position, secret)
+                 SELECT ?1, enabled, key, value, position, secret FROM env_variables
+                 WHERE environment_id = ?2 ORDER BY position, id",
+                params![copy, id],
+            )?;
+            tx.commit()?;
+            Ok(copy)
+        })
+    }
+
     pub fn rename_environment(&self, id: i64, name: &str) -> Result<()> {
         let name = name.to_string();
         self.call(move |conn| {
@@ -348,9 +861,195 @@ impl Database {
             )?;
             for (position, v) in vars.iter().enumerate() {
                 tx.execute(
-                    "INSERT INTO env_variables (environment_id, enabled, key, value, position)
+                    "INSERT INTO env_variables (environment_id, enabled, key, value, position, secret)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![environment_id, v.enabled as i64, v.key, v.value, position as i64, v.secret as i64],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// The Globals scope: variables available whatever environment is active.
+    pub fn load_globals(&self) -> Result<Vec<EnvVar>> {
+        self.call(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT enabled, key, value, secret FROM global_variables ORDER BY position, id",
+            )?;
+            let globals = stmt
+                .query_map([], |row| {
+                    Ok(EnvVar {
+                        enabled: row.get::<_, i64>(0)? != 0,
+                        key: row.get(1)?,
+                        value: row.get(2)?,
+                        secret: row.get::<_, i64>(3)? != 0,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(globals)
+        })
+    }
+
+    /// Replace all global variables in a single transaction.
+    pub fn replace_globals(&self, vars: &[EnvVar]) -> Result<()> {
+        let vars = vars.to_vec();
+        self.call(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute("DELETE FROM global_variables", [])?;
+            for (position, v) in vars.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO global_variables (enabled, key, value, secret, position)
                      VALUES (?1, ?2, ?3, ?4, ?5)",
-                    params![environment_id, v.enabled as i64, v.key, v.value, position as i64],
+                    params![v.enabled as i64, v.key, v.value, v.secret as i64, position as i64],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    // ===== Collections =====
+
+    /// All collections and folders, oldest first; `parent_id` gives the tree.
+    pub fn load_collections(&self) -> Result<Vec<Collection>> {
+        self.call(|conn| {
+            let mut stmt = conn.prepare("SELECT id, parent_id, name FROM collections ORDER BY id")?;
+            let collections = stmt
+                .query_map([], |row| Ok(Collection { id: row.get(0)?, parent_id: row.get(1)?, name: row.get(2)? }))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(collections)
+        })
+    }
+
+    /// Create a collection (`parent_id` None) or a folder inside one,
+    /// returning its id.
+    pub fn create_collection(&self, name: &str, parent_id: Option<i64>) -> Result<i64> {
+        let name = name.to_string();
+        self.call(move |conn| {
+            conn.execute("INSERT INTO collections (parent_id, name) VALUES (?1, ?2)", params![parent_id, name])?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    pub fn delete_collection(&self, id: i64) -> Result<()> {
+        self.call(move |conn| {
+            // Sub-folders and saved requests go with it (ON DELETE CASCADE).
+            conn.execute("DELETE FROM collections WHERE id = ?1", params![id])?;
+            Ok(())
+        })
+    }
+
+    /// Every preset, oldest first. Rows whose headers or params no longer
+    /// decode load with none.
+    pub fn load_presets(&self) -> Result<Vec<Preset>> {
+        self.call(|conn| {
+            let mut stmt = conn.prepare("SELECT id, name, headers, params FROM presets ORDER BY id")?;
+            let presets = stmt
+                .query_map([], |row| {
+                    let headers: String = row.get(2)?;
+                    let params: String = row.get(3)?;
+                    Ok(Preset {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        headers: serde_json::from_str(&headers).unwrap_or_default(),
+                        params: serde_json::from_str(&params).unwrap_or_default(),
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(presets)
+        })
+    }
+
+    /// Store `preset` under its id, or as a new preset when the id is 0.
+    /// Returns the id.
+    pub fn save_preset(&self, preset: &Preset) -> Result<i64> {
+        let Preset { id, name, .. } = preset.clone();
+        let headers = serde_json::to_string(&preset.headers)?;
+        let params = serde_json::to_string(&preset.params)?;
+        self.call(move |conn| {
+            if id == 0 {
+                conn.execute(
+                    "INSERT INTO presets (name, headers, params) VALUES (?1, ?2, ?3)",
+                    params![name, headers, params],
+                )?;
+                return Ok(conn.last_insert_rowid());
+            }
+            conn.execute(
+                "UPDATE presets SET name = ?1, headers = ?2, params = ?3 WHERE id = ?4",
+                params![name, headers, params, id],
+            )?;
+            Ok(id)
+        })
+    }
+
+    /// Requests that still reference it simply stop applying it.
+    pub fn delete_preset(&self, id: i64) -> Result<()> {
+        self.call(move |conn| {
+            conn.execute("DELETE FROM presets WHERE id = ?1", params![id])?;
+            Ok(())
+        })
+    }
+
+    /// Every saved request, in collection order. The request is stored as JSON, so
+    /// a row that no longer decodes opens as an empty GET rather than failing
+    /// the whole list.
+    pub fn load_saved_requests(&self) -> Result<Vec<SavedRequest>> {
+        self.call(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, collection_id, name, request FROM saved_requests ORDER BY collection_id, sort_order, id",
+            )?;
+            let saved = stmt
+                .query_map([], |row| {
+                    let json: String = row.get(3)?;
+                    Ok(SavedRequest {
+                        id: row.get(0)?,
+                        collection_id: row.get(1)?,
+                        name: row.get(2)?,
+                        request: serde_json::from_str(&json)
+                            .unwrap_or_else(|_| RequestData::new(HttpMethod::GET, String::new())),
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(saved)
+        })
+    }
+
+    /// Save `request` under `name` as the last request of a collection,
+    /// returning its id.
+    pub fn save_request(&self, collection_id: i64, name: &str, request: &RequestData) -> Result<i64> {
+        let name = name.to_string();
+        let json = serde_json::to_string(request)?;
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO saved_requests (collection_id, name, request, sort_order)
+                 VALUES (?1, ?2, ?3,
+                         (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM saved_requests WHERE collection_id = ?1))",
+                params![collection_id, name, json],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    /// Move a saved request to position `index` of `collection_id` (its own
+    /// collection or another), renumbering that collection in one
+    /// transaction. An `index` past the end appends.
+    pub fn move_saved_request(&self, id: i64, collection_id: i64, index: usize) -> Result<()> {
+        self.call(move |conn| {
+            let tx = conn.transaction()?;
+            let mut stmt = tx.prepare(
+                "SELECT id FROM saved_requests WHERE collection_id = ?1 AND id != ?2 ORDER BY sort_order, id",
+            )?;
+            let mut order = stmt
+                .query_map(params![collection_id, id], |row| row.get::<_, i64>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            drop(stmt);
+            order.insert(index.min(order.len()), id);
+            tx.execute("UPDATE saved_requests SET collection_id = ?1 WHERE id = ?2", params![collection_id, id])?;
+            for (position, row_id) in order.iter().enumerate() {
+                tx.execute(
+                    "UPDATE saved_requests SET sort_order = ?1 WHERE id = ?2",
+                    params![position as i64, row_id],
                 )?;
             }
             tx.commit()?;
@@ -358,6 +1057,35 @@ impl Database {
         })
     }
 
+    /// Overwrite a saved request with the editor's current one (re-save).
+    /// False when the row is gone (deleted since the tab was opened).
+    pub fn update_saved_request(&self, id: i64, request: &RequestData) -> Result<bool> {
+        let json = serde_json::to_string(request)?;
+        self.call(move |conn| {
+            let updated = conn.execute("UPDATE saved_requests SET request = ?1 WHERE id = ?2", params![json, id])?;
+            Ok(updated > 0)
+        })
+    }
+
+    pub fn delete_saved_request(&self, id: i64) -> Result<()> {
+        self.call(move |conn| {
+            conn.execute("DELETE FROM saved_requests WHERE id = ?1", params![id])?;
+            Ok(())
+        })
+    }
+
+    /// Store an imported folder tree as a new collection in one transaction,
+    /// returning the collection's id.
+    pub fn import_collection(&self, root: &CollectionFolder) -> Result<i64> {
+        let root = root.clone();
+        self.call(move |conn| {
+            let tx = conn.transaction()?;
+            let id = insert_collection_tree(&tx, &root, None)?;
+            tx.commit()?;
+            Ok(id)
+        })
+    }
+
     /// Active environment id, or None for "No Environment".
     pub fn get_active_environment_id(&self) -> Result<Option<i64>> {
         self.call(|conn| {
@@ -389,21 +1117,404 @@ impl Database {
             Ok(())
         })
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    use crate::types::{AuthConfig, AuthType};
-
-    fn mem_db() -> Database {
-        Database::new_in_memory()
+    /// Read one `app_meta` setting.
+    fn get_meta(&self, key: &'static str) -> Result<Option<String>> {
+        self.call(move |conn| {
+            Ok(conn
+                .query_row("SELECT value FROM app_meta WHERE key = ?1", params![key], |row| row.get(0))
+                .optional()?)
+        })
     }
 
-    #[test]
-    fn migration_adds_request_auth_and_old_rows_default() {
-        // Simulate a pre-feature database: history table WITHOUT request_auth.
+    /// Write (or with `None`, remove) one `app_meta` setting.
+    fn set_meta(&self, key: &'static str, value: Option<&str>) -> Result<()> {
+        let value = value.map(str::to_string);
+        self.call(move |conn| {
+            match value {
+                Some(value) => {
+                    conn.execute(
+                        "INSERT INTO app_meta (key, value) VALUES (?1, ?2)
+                         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                        params![key, value],
+                    )?;
+                }
+                None => {
+                    conn.execute("DELETE FROM app_meta WHERE key = ?1", params![key])?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// The configured proxy URL offered by the "send through" selector.
+    pub fn get_proxy_url(&self) -> Result<Option<String>> {
+        self.get_meta("proxy_url")
+    }
+
+    /// Set (or with `None`, remove) the configured proxy URL.
+    pub fn set_proxy_url(&self, url: Option<&str>) -> Result<()> {
+        self.set_meta("proxy_url", url)
+    }
+
+    /// Credentials and no-proxy list of the configured proxy; empty fields
+    /// when none were set.
+    pub fn get_proxy_options(&self) -> Result<ProxyOptions> {
+        Ok(ProxyOptions {
+            username: self.get_meta("proxy_username")?.unwrap_or_default(),
+            password: self.get_meta("proxy_password")?.unwrap_or_default(),
+            no_proxy: self.get_meta("proxy_no_proxy")?.unwrap_or_default(),
+        })
+    }
+
+    pub fn set_proxy_options(&self, options: &ProxyOptions) -> Result<()> {
+        let value = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        self.set_meta("proxy_username", value(&options.username).as_deref())?;
+        self.set_meta("proxy_password", value(&options.password).as_deref())?;
+        self.set_meta("proxy_no_proxy", value(options.no_proxy.trim()).as_deref())
+    }
+
+    /// Where completed sends are reported (see `activity_webhook`); `None`
+    /// when the webhook is off.
+    pub fn get_activity_webhook_url(&self) -> Result<Option<String>> {
+        self.get_meta("activity_webhook_url")
+    }
+
+    pub fn set_activity_webhook_url(&self, url: Option<&str>) -> Result<()> {
+        self.set_meta("activity_webhook_url", url)
+    }
+
+    /// PEM file of the extra CA certificate requests trust (see
+    /// `http_client::set_ca_cert_path`); `None` for the system roots only.
+    pub fn get_ca_cert_path(&self) -> Result<Option<String>> {
+        self.get_meta("ca_cert_path")
+    }
+
+    pub fn set_ca_cert_path(&self, path: Option<&str>) -> Result<()> {
+        self.set_meta("ca_cert_path", path)
+    }
+
+    /// Host overrides applied to every request (see
+    /// `http_client::set_host_overrides`); kept as `host_overrides` lines.
+    pub fn get_host_overrides(&self) -> Result<Vec<HostOverride>> {
+        let text = self.get_meta("host_overrides")?.unwrap_or_default();
+        Ok(crate::host_overrides::parse(&text).unwrap_or_default())
+    }
+
+    pub fn set_host_overrides(&self, overrides: &[HostOverride]) -> Result<()> {
+        let text = crate::host_overrides::format(overrides);
+        self.set_meta("host_overrides", (!text.is_empty()).then_some(text.as_str()))
+    }
+
+    /// Response body size limit in bytes (0 = none); `None` until one is
+    /// picked, meaning `http_client::DEFAULT_BODY_LIMIT`.
+    pub fn get_body_limit(&self) -> Result<Option<u64>> {
+        Ok(self.get_meta("body_limit")?.and_then(|v| v.parse().ok()))
+    }
+
+    pub fn set_body_limit(&self, bytes: u64) -> Result<()> {
+        self.set_meta("body_limit", Some(&bytes.to_string()))
+    }
+
+    /// Cap on response bodies stored with history; None until one is
+    /// picked, meaning `DEFAULT_HISTORY_BODY_CAP`.
+    pub fn get_history_body_cap(&self) -> Result<Option<u64>> {
+        Ok(self.get_meta("history_body_cap")?.and_then(|v| v.parse().ok()))
+    }
+
+    pub fn set_history_body_cap(&self, bytes: u64) -> Result<()> {
+        self.set_meta("history_body_cap", Some(&bytes.to_string()))
+    }
+
+    /// The log level chosen in the Edit menu, as `LevelFilter::as_str`;
+    /// `None` until one is picked.
+    pub fn get_log_level(&self) -> Result<Option<String>> {
+        self.get_meta("log_level")
+    }
+
+    pub fn set_log_level(&self, level: &str) -> Result<()> {
+        self.set_meta("log_level", Some(level))
+    }
+
+    /// Temporary variable overrides the user chose to keep across restarts;
+    /// `None` when they are session-only (the default).
+    pub fn get_kept_env_overrides(&self) -> Result<Option<EnvOverrides>> {
+        Ok(self
+            .get_meta("kept_env_overrides")?
+            .map(|json| serde_json::from_str(&json).unwrap_or_default()))
+    }
+
+    /// Keep `overrides` for the next start, or with `None`, stop keeping them.
+    pub fn set_kept_env_overrides(&self, overrides: Option<&EnvOverrides>) -> Result<()> {
+        let json = overrides.map(serde_json::to_string).transpose()?;
+        self.set_meta("kept_env_overrides", json.as_deref())
+    }
+
+    /// The open tabs as `session::to_json` wrote them; `None` on first start.
+    pub fn get_session(&self) -> Result<Option<String>> {
+        self.get_meta("session")
+    }
+
+    pub fn set_session(&self, json: &str) -> Result<()> {
+        self.set_meta("session", Some(json))
+    }
+
+    /// Whether the request editor uses the two-column layout.
+    pub fn get_split_editor(&self) -> Result<bool> {
+        Ok(self.get_meta("split_editor")?.as_deref() == Some("1"))
+    }
+
+    pub fn set_split_editor(&self, split: bool) -> Result<()> {
+        self.set_meta("split_editor", split.then_some("1"))
+    }
+
+    /// The predefined headers new tabs start with; `None` until picked,
+    /// meaning all of them.
+    pub fn get_default_headers(&self) -> Result<Option<Vec<PredefinedHeader>>> {
+        Ok(self.get_meta("default_headers")?.and_then(|v| serde_json::from_str(&v).ok()))
+    }
+
+    pub fn set_default_headers(&self, headers: &[PredefinedHeader]) -> Result<()> {
+        self.set_meta("default_headers", Some(&serde_json::to_string(headers)?))
+    }
+
+    /// The User-Agent new tabs start with; `None` until set, meaning
+    /// `types::DEFAULT_USER_AGENT`.
+    pub fn get_default_user_agent(&self) -> Result<Option<String>> {
+        self.get_meta("default_user_agent")
+    }
+
+    pub fn set_default_user_agent(&self, user_agent: Option<&str>) -> Result<()> {
+        self.set_meta("default_user_agent", user_agent)
+    }
+
+    /// Whether responses' cookies are kept and sent back (off by default).
+    pub fn get_cookie_jar_enabled(&self) -> Result<bool> {
+        Ok(self.get_meta("cookie_jar")?.as_deref() == Some("1"))
+    }
+
+    pub fn set_cookie_jar_enabled(&self, enabled: bool) -> Result<()> {
+        self.set_meta("cookie_jar", enabled.then_some("1"))
+    }
+
+    /// Everything a workspace export carries and an import is planned
+    /// against.
+    pub fn load_workspace(&self) -> Result<Workspace> {
+        Ok(Workspace {
+            environments: self.load_environments()?,
+            active_environment_id: self.get_active_environment_id()?,
+            globals: self.load_globals()?,
+            presets: self.load_presets()?,
+            collections: self.load_collections()?,
+            saved_requests: self.load_saved_requests()?,
+            default_headers: self.get_default_headers()?,
+            default_user_agent: self.get_default_user_agent()?,
+            body_limit: self.get_body_limit()?,
+        })
+    }
+
+    /// Apply a workspace import in ONE transaction: deletions, updated and new
+    /// environments, globals, presets, the active environment and the other
+    /// settings all land, or none of them do.
+    pub fn apply_workspace_import(&self, plan: ImportPlan) -> Result<()> {
+        self.call(move |conn| {
+            let tx = conn.transaction()?;
+            for id in &plan.delete_ids {
+                tx.execute("DELETE FROM environments WHERE id = ?1", params![id])?;
+            }
+            for env in &plan.environments {
+                let id = match env.existing_id {
+                    Some(id) => {
+                        tx.execute("DELETE FROM env_variables WHERE environment_id = ?1", params![id])?;
+                        id
+                    }
+                    None => {
+                        tx.execute(
+                            "INSERT INTO environments (name, position)
+                             VALUES (?1, (SELECT COALESCE(MAX(position), 0) + 1 FROM environments))",
+                            params![env.name],
+                        )?;
+                        tx.last_insert_rowid()
+                    }
+                };
+                for (position, v) in env.variables.iter().enumerate() {
+                    tx.execute(
+                        "INSERT INTO env_variables (environment_id, enabled, key, value, position, secret)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        params![id, v.enabled as i64, v.key, v.value, position as i64, v.secret as i64],
+                    )?;
+                }
+            }
+            tx.execute("DELETE FROM global_variables", [])?;
+            for (position, v) in plan.globals.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO global_variables (enabled, key, value, secret, position)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![v.enabled as i64, v.key, v.value, v.secret as i64, position as i64],
+                )?;
+            }
+            for id in &plan.delete_preset_ids {
+                tx.execute("DELETE FROM presets WHERE id = ?1", params![id])?;
+            }
+            // Bundle preset id → local id, for the collections' requests.
+            let mut preset_ids = HashMap::new();
+            for preset in &plan.presets {
+                let (headers, params) = (serde_json::to_string(&preset.headers)?, serde_json::to_string(&preset.params)?);
+                let id = match preset.existing_id {
+                    Some(id) => {
+                        tx.execute(
+                            "UPDATE presets SET headers = ?1, params = ?2 WHERE id = ?3",
+                            params![headers, params, id],
+                        )?;
+                        id
+                    }
+                    None => {
+                        tx.execute(
+                            "INSERT INTO presets (name, headers, params) VALUES (?1, ?2, ?3)",
+                            params![preset.name, headers, params],
+                        )?;
+                        tx.last_insert_rowid()
+                    }
+                };
+                preset_ids.insert(preset.bundle_id, id);
+            }
+            for id in &plan.delete_collection_ids {
+                // Sub-folders and saved requests go with it (ON DELETE CASCADE).
+                tx.execute("DELETE FROM collections WHERE id = ?1", params![id])?;
+            }
+            for root in &plan.collections {
+                insert_collection_tree(&tx, &with_local_presets(root, &preset_ids), None)?;
+            }
+            let set_meta = |key: &str, value: String| {
+                tx.execute(
+                    "INSERT INTO app_meta (key, value) VALUES (?1, ?2)
+                     ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                    params![key, value],
+                )
+            };
+            if let Some(headers) = &plan.settings.default_headers {
+                set_meta("default_headers", serde_json::to_string(headers)?)?;
+            }
+            if let Some(user_agent) = &plan.settings.default_user_agent {
+                set_meta("default_user_agent", user_agent.clone())?;
+            }
+            if let Some(limit) = plan.settings.body_limit {
+                set_meta("body_limit", limit.to_string())?;
+            }
+            if let Some(name) = &plan.settings.active_environment {
+                let id: Option<i64> = tx
+                    .query_row(
+                        "SELECT id FROM environments WHERE name = ?1 ORDER BY position, id LIMIT 1",
+                        params![name],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                if let Some(id) = id {
+                    set_meta("active_environment_id", id.to_string())?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::assertions::Assertion;
+    use crate::extract::ExtractRule;
+    use crate::types::{AuthConfig, AuthType, RawHeaderValue};
+
+    fn mem_db() -> Database {
+        Database::new_in_memory()
+    }
+
+    fn sent(method: &str, url: &str) -> RequestData {
+        RequestData::new(HttpMethod::from_str(method).unwrap(), url.to_string())
+    }
+
+    fn response(status: Option<u16>, body: &[u8]) -> ResponseData {
+        ResponseData {
+            status,
+            duration_ms: 42,
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: body.to_vec(),
+            is_text: true,
+            route: SendRoute::default(),
+            raw_header_values: vec![],
+            network_profile: Default::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
+        }
+    }
+
+    #[test]
+    fn history_rows_keep_the_response_with_a_capped_body() {
+        let db = mem_db();
+        let send = || {
+            db.insert_history(&sent("GET", "https://x/a"))
+                .unwrap()
+        };
+        let (whole, capped, none) = (send(), send(), send());
+        db.set_history_response(whole, &response(Some(200), b"{\"ok\":true}"), 0).unwrap();
+        db.set_history_response(capped, &response(Some(500), b"0123456789"), 4).unwrap();
+
+        let stored = db.load_history_response(whole).unwrap().expect("stored");
+        assert_eq!((stored.status, stored.duration_ms), (Some(200), 42));
+        assert_eq!(stored.body, b"{\"ok\":true}");
+        assert_eq!(stored.headers, vec![("Content-Type".to_string(), "application/json".to_string())]);
+        assert!(stored.is_text && stored.truncated_after.is_none());
+
+        let stored = db.load_history_response(capped).unwrap().expect("stored");
+        assert_eq!(stored.body, b"0123");
+        assert_eq!(stored.truncated_after, Some(10));
+        assert!(db.load_history_response(none).unwrap().is_none());
+        assert_eq!(stored.timing, None);
+
+        // The list carries only the status, for the row badge.
+        let statuses: Vec<_> = db.load_recent_history(10, 0).unwrap().iter().map(|i| (i.status, i.response.is_none())).collect();
+        assert_eq!(statuses, vec![(None, true), (Some(500), true), (Some(200), true)]);
+
+        let timed = send();
+        let timing = crate::types::ResponseTiming {
+            wait_ms: 80,
+            dns_ms: Some(5),
+            connect_ms: Some(30),
+            download_ms: 7,
+            ..Default::default()
+        };
+        db.set_history_response(timed, &ResponseData { timing: Some(timing), ..response(Some(200), b"") }, 0).unwrap();
+        assert_eq!(db.load_history_response(timed).unwrap().expect("stored").timing, Some(timing));
+    }
+
+    #[test]
+    fn history_keeps_the_raw_bytes_of_non_ascii_header_values() {
+        let db = mem_db();
+        let id = db.insert_history(&sent("GET", "https://x/a")).unwrap();
+        // "café" in Latin-1: shown decoded, the byte 0xE9 kept for the hover.
+        let raw = vec![RawHeaderValue { index: 1, bytes: b"caf\xe9".to_vec() }];
+        let mut latin1 = response(Some(200), b"");
+        latin1.headers.push(("X-Name".into(), "café".into()));
+        latin1.raw_header_values = raw.clone();
+        db.set_history_response(id, &latin1, 0).unwrap();
+
+        let stored = db.load_history_response(id).unwrap().expect("stored");
+        assert_eq!(stored.raw_header_values, raw);
+        assert_eq!(stored.headers[1], ("X-Name".to_string(), "café".to_string()));
+    }
+
+    #[test]
+    fn migration_adds_request_auth_and_old_rows_default() {
+        // Simulate a pre-feature database: history table WITHOUT request_auth.
         let conn = Connection::open_in_memory().unwrap();
         conn.execute_batch(
             "CREATE TABLE history (
@@ -425,11 +1536,228 @@ mod tests {
         // Migration is idempotent and adds the column.
         Database::migrate_add_request_auth(&conn).unwrap();
         Database::migrate_add_request_auth(&conn).unwrap(); // second run is a no-op
+        Database::migrate_add_request_route(&conn).unwrap();
+        Database::migrate_add_history_meta(&conn).unwrap();
+        Database::migrate_add_tab_color(&conn).unwrap();
+        Database::migrate_add_minimal_headers(&conn).unwrap();
+        Database::migrate_add_network_profile(&conn).unwrap();
+        Database::migrate_add_chunked(&conn).unwrap();
+        Database::migrate_add_pinned(&conn).unwrap();
+        Database::migrate_add_ignore_tls_errors(&conn).unwrap();
+        Database::migrate_add_assertions(&conn).unwrap();
+        Database::migrate_add_history_presets(&conn).unwrap();
+        Database::migrate_add_extract(&conn).unwrap();
+        Database::migrate_add_path_variables(&conn).unwrap();
+        Database::migrate_add_send_body_anyway(&conn).unwrap();
+        Database::migrate_add_run_report(&conn).unwrap();
 
-        let db = Database::spawn(conn);
-        let items = db.load_recent_history(10).unwrap();
+        let db = Database::spawn_with(move || Ok(conn));
+        let items = db.load_recent_history(10, 0).unwrap();
         assert_eq!(items.len(), 1);
         assert_eq!(items[0].request.auth.auth_type, AuthType::None);
+        // No stored meta: computed from the request on load.
+        assert_eq!(items[0].meta.body_kind, BodyKind::None);
+    }
+
+    #[test]
+    fn history_rows_keep_the_tab_color() {
+        let db = mem_db();
+        let id = db.insert_history(&sent("GET", "https://x/1")).unwrap();
+        db.insert_history(&sent("GET", "https://x/2")).unwrap();
+        db.set_history_color(id, Some(TabColor::Teal)).unwrap();
+        let items = db.load_recent_history(10, 0).unwrap();
+        assert_eq!(items[0].color, None);
+        assert_eq!(items[1].color, Some(TabColor::Teal));
+    }
+
+    #[test]
+    fn history_rows_keep_the_minimal_headers_flag() {
+        let db = mem_db();
+        db.insert_history(&RequestData { minimal_headers: true, ..sent("GET", "https://x/1") }).unwrap();
+        db.insert_history(&sent("GET", "https://x/2")).unwrap();
+        let items = db.load_recent_history(10, 0).unwrap();
+        assert!(!items[0].request.minimal_headers);
+        assert!(items[1].request.minimal_headers);
+    }
+
+    #[test]
+    fn recent_distinct_urls_keep_the_latest_send_of_each() {
+        let db = mem_db();
+        let send = |method: &str, url: &str| {
+            db.insert_history(&sent(method, url))
+                .unwrap()
+        };
+        send("GET", "https://x/a");
+        send("GET", "https://x/b");
+        let latest_a = send("POST", "https://x/a");
+        send("GET", "https://x/c");
+
+        let items = db.load_recent_distinct_urls(10).unwrap();
+        let urls: Vec<_> = items.iter().map(|i| i.request.url.as_str()).collect();
+        assert_eq!(urls, ["https://x/c", "https://x/a", "https://x/b"]);
+        assert_eq!(items[1].id, latest_a);
+        assert_eq!(items[1].request.method, HttpMethod::POST);
+        assert_eq!(db.load_recent_distinct_urls(2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn pinned_rows_are_listed_until_unpinned() {
+        let db = mem_db();
+        let id = db.insert_history(&sent("GET", "https://x/1")).unwrap();
+        db.insert_history(&sent("GET", "https://x/2")).unwrap();
+        assert!(db.load_pinned_history(10).unwrap().is_empty());
+
+        db.set_history_pinned(id, true).unwrap();
+        let pinned = db.load_pinned_history(10).unwrap();
+        assert_eq!(pinned.iter().map(|i| i.id).collect::<Vec<_>>(), [id]);
+        assert!(pinned[0].pinned);
+        assert!(!db.load_recent_history(10, 0).unwrap()[0].pinned);
+
+        db.set_history_pinned(id, false).unwrap();
+        assert!(db.load_pinned_history(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn pinned_rows_outlive_the_limit_and_a_clear() {
+        let db = mem_db();
+        let send = |url: &str| {
+            db.insert_history(&sent("GET", url))
+                .unwrap()
+        };
+        let old = send("https://x/old");
+        send("https://x/2");
+        send("https://x/3");
+        db.set_history_pinned(old, true).unwrap();
+
+        let urls = |items: Vec<HistoryItem>| items.into_iter().map(|i| i.request.url).collect::<Vec<_>>();
+        assert_eq!(urls(db.load_recent_history(1, 0).unwrap()), ["https://x/3", "https://x/old"]);
+
+        db.clear_unpinned_history().unwrap();
+        assert_eq!(urls(db.load_recent_history(10, 0).unwrap()), ["https://x/old"]);
+        db.clear_all_history().unwrap();
+        assert!(db.load_recent_history(10, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn history_pages_skip_pinned_rows_after_the_first() {
+        let db = mem_db();
+        let send = |url: &str| {
+            db.insert_history(&sent("GET", url))
+                .unwrap()
+        };
+        let pinned = send("https://x/pinned");
+        for n in 1..=4 {
+            send(&format!("https://x/{n}"));
+        }
+        db.set_history_pinned(pinned, true).unwrap();
+
+        let urls = |items: Vec<HistoryItem>| items.into_iter().map(|i| i.request.url).collect::<Vec<_>>();
+        assert_eq!(urls(db.load_recent_history(2, 0).unwrap()), ["https://x/4", "https://x/3", "https://x/pinned"]);
+        assert_eq!(urls(db.load_recent_history(2, 2).unwrap()), ["https://x/2", "https://x/1"]);
+        assert!(db.load_recent_history(2, 4).unwrap().is_empty());
+        assert_eq!(db.get_history_count().unwrap(), 5);
+
+        assert_eq!(urls(db.search_history("x", 2, 1).unwrap()), ["https://x/3", "https://x/2"]);
+    }
+
+    #[test]
+    fn history_rows_keep_the_chunked_flag() {
+        let db = mem_db();
+        db.insert_history(&RequestData { chunked: true, ..sent("POST", "https://x/1") }).unwrap();
+        db.insert_history(&sent("POST", "https://x/2")).unwrap();
+        let items = db.load_recent_history(10, 0).unwrap();
+        assert!(!items[0].request.chunked);
+        assert!(items[1].request.chunked);
+    }
+
+    #[test]
+    fn history_rows_keep_the_ignore_tls_errors_flag() {
+        let db = mem_db();
+        db.insert_history(&sent("GET", "https://localhost:8443")).unwrap();
+        assert!(!db.load_recent_history(10, 0).unwrap()[0].request.ignore_tls_errors);
+        db.insert_history(&RequestData { ignore_tls_errors: true, ..sent("GET", "https://localhost:8443") }).unwrap();
+        assert!(db.load_recent_history(10, 0).unwrap()[0].request.ignore_tls_errors);
+    }
+
+    #[test]
+    fn history_rows_keep_the_network_profile() {
+        let db = mem_db();
+        let profile = NetworkProfile::Custom { kbps: 64, latency_ms: 300 };
+        db.insert_history(&RequestData { network_profile: profile, ..sent("GET", "https://x/1") }).unwrap();
+        db.insert_history(&sent("GET", "https://x/2")).unwrap();
+        let items = db.load_recent_history(10, 0).unwrap();
+        assert_eq!(items[0].request.network_profile, NetworkProfile::Unthrottled);
+        assert_eq!(items[1].request.network_profile, profile);
+    }
+
+    #[test]
+    fn history_rows_keep_their_assertions() {
+        use crate::assertions::Check;
+        let db = mem_db();
+        db.insert_history(&sent("GET", "https://x/1")).unwrap();
+        assert!(db.load_recent_history(10, 0).unwrap()[0].request.assertions.is_empty());
+        let assertions = vec![Assertion::new(Check::StatusInRange, "", "2xx"), Assertion::new(Check::JsonPathEquals, "$.id", "7")];
+        db.insert_history(&RequestData { assertions: assertions.clone(), ..sent("GET", "https://x/1") }).unwrap();
+        assert_eq!(db.load_recent_history(10, 0).unwrap()[0].request.assertions, assertions);
+    }
+
+    #[test]
+    fn presets_are_created_updated_and_deleted() {
+        let db = mem_db();
+        let pair = |k: &str, v: &str| (k.to_string(), v.to_string());
+        let mut preset = Preset {
+            id: 0,
+            name: "Tenant".into(),
+            headers: vec![pair("X-Tenant", "acme"), pair("X-Trace", "{{trace}}")],
+            params: vec![pair("region", "eu")],
+        };
+        preset.id = db.save_preset(&preset).unwrap();
+        assert_eq!(db.load_presets().unwrap(), [preset.clone()]);
+        preset.headers.pop();
+        assert_eq!(db.save_preset(&preset).unwrap(), preset.id);
+        assert_eq!(db.load_presets().unwrap(), [preset.clone()]);
+        db.delete_preset(preset.id).unwrap();
+        assert!(db.load_presets().unwrap().is_empty());
+    }
+
+    #[test]
+    fn history_rows_keep_their_presets() {
+        let db = mem_db();
+        db.insert_history(&RequestData { presets: vec![3, 1], ..sent("GET", "https://x/1") }).unwrap();
+        assert_eq!(db.load_recent_history(10, 0).unwrap()[0].request.presets, [3, 1]);
+    }
+
+    #[test]
+    fn history_rows_keep_their_extract_rules() {
+        use crate::extract::{Scope, Source};
+        let db = mem_db();
+        let rules = vec![ExtractRule { scope: Scope::Session, ..ExtractRule::new(Source::JsonPath, "$.token", "token") }];
+        db.insert_history(&RequestData { extract: rules.clone(), ..sent("POST", "https://x/login") }).unwrap();
+        assert_eq!(db.load_recent_history(10, 0).unwrap()[0].request.extract, rules);
+    }
+
+    #[test]
+    fn history_rows_keep_their_path_variables() {
+        let db = mem_db();
+        let values = vec![("id".to_string(), "42".to_string())];
+        db.insert_history(&RequestData { path_variables: values.clone(), ..sent("GET", "https://x/users/:id") }).unwrap();
+        assert_eq!(db.load_recent_history(10, 0).unwrap()[0].request.path_variables, values);
+    }
+
+    #[test]
+    fn history_stores_row_meta_at_insert() {
+        let db = mem_db();
+        let headers = vec![("Cache-Control".into(), "no-cache".into()), ("Authorization".into(), "Bearer x".into())];
+        let body = BodyType::Raw {
+            content: "{\n  \"id\": 7\n}".into(),
+            subtype: crate::types::RawSubtype::Json,
+        };
+        db.insert_history(&RequestData { headers, body, ..sent("POST", "https://x/a?p=1&q=2") }).unwrap();
+        let meta = &db.load_recent_history(10, 0).unwrap()[0].meta;
+        assert_eq!(meta.body_kind, BodyKind::Json);
+        assert_eq!(meta.body_preview, "{ \"id\": 7 }");
+        assert_eq!(meta.param_count, 2);
+        assert_eq!(meta.header_count, 1);
     }
 
     #[test]
@@ -440,12 +1768,170 @@ mod tests {
             bearer_token: "abc".into(),
             ..Default::default()
         };
-        db.insert_history("GET", "https://x", "[]", &BodyType::None, &auth).unwrap();
-        let items = db.load_recent_history(10).unwrap();
+        db.insert_history(&RequestData { auth, ..sent("GET", "https://x") }).unwrap();
+        let items = db.load_recent_history(10, 0).unwrap();
         assert_eq!(items[0].request.auth.auth_type, AuthType::Bearer);
         assert_eq!(items[0].request.auth.bearer_token, "abc");
     }
 
+    #[test]
+    fn history_records_the_send_route() {
+        let db = mem_db();
+        let proxy = SendRoute::Proxy("http://127.0.0.1:8888".into());
+        db.insert_history(&RequestData { route: proxy.clone(), ..sent("GET", "https://x/1") }).unwrap();
+        db.insert_history(&RequestData { route: SendRoute::Direct, ..sent("GET", "https://x/2") }).unwrap();
+        let items = db.load_recent_history(10, 0).unwrap();
+        assert_eq!(items[0].request.route, SendRoute::Direct);
+        assert_eq!(items[1].request.route, proxy);
+
+        assert_eq!(db.get_proxy_url().unwrap(), None);
+        db.set_proxy_url(Some("http://127.0.0.1:8888")).unwrap();
+        assert_eq!(db.get_proxy_url().unwrap().as_deref(), Some("http://127.0.0.1:8888"));
+        db.set_proxy_url(None).unwrap();
+        assert_eq!(db.get_proxy_url().unwrap(), None);
+    }
+
+    #[test]
+    fn proxy_options_round_trip_and_clear() {
+        let db = mem_db();
+        assert_eq!(db.get_proxy_options().unwrap(), ProxyOptions::default());
+        let options = ProxyOptions {
+            username: "alice".into(),
+            password: "pw".into(),
+            no_proxy: "localhost, .corp.local".into(),
+        };
+        db.set_proxy_options(&options).unwrap();
+        assert_eq!(db.get_proxy_options().unwrap(), options);
+        db.set_proxy_options(&ProxyOptions::default()).unwrap();
+        assert_eq!(db.get_proxy_options().unwrap(), ProxyOptions::default());
+    }
+
+    #[test]
+    fn body_limit_is_unset_until_picked() {
+        let db = mem_db();
+        assert_eq!(db.get_body_limit().unwrap(), None);
+        db.set_body_limit(0).unwrap();
+        assert_eq!(db.get_body_limit().unwrap(), Some(0));
+        db.set_body_limit(1 << 20).unwrap();
+        assert_eq!(db.get_body_limit().unwrap(), Some(1 << 20));
+
+        assert_eq!(db.get_history_body_cap().unwrap(), None);
+        db.set_history_body_cap(64 << 10).unwrap();
+        assert_eq!(db.get_history_body_cap().unwrap(), Some(64 << 10));
+    }
+
+    #[test]
+    fn activity_webhook_is_off_until_configured() {
+        let db = mem_db();
+        assert_eq!(db.get_activity_webhook_url().unwrap(), None);
+        db.set_activity_webhook_url(Some("http://127.0.0.1:9000/hook")).unwrap();
+        assert_eq!(db.get_activity_webhook_url().unwrap().as_deref(), Some("http://127.0.0.1:9000/hook"));
+        db.set_activity_webhook_url(None).unwrap();
+        assert_eq!(db.get_activity_webhook_url().unwrap(), None);
+    }
+
+    #[test]
+    fn host_overrides_round_trip_and_clear() {
+        let db = mem_db();
+        assert_eq!(db.get_host_overrides().unwrap(), vec![]);
+        let overrides = crate::host_overrides::parse("api.example.com 10.0.0.5:8443\ncdn.example.com 10.0.0.6").unwrap();
+        db.set_host_overrides(&overrides).unwrap();
+        assert_eq!(db.get_host_overrides().unwrap(), overrides);
+        db.set_host_overrides(&[]).unwrap();
+        assert_eq!(db.get_meta("host_overrides").unwrap(), None);
+    }
+
+    #[test]
+    fn log_level_is_unset_until_picked() {
+        let db = mem_db();
+        assert_eq!(db.get_log_level().unwrap(), None);
+        db.set_log_level("DEBUG").unwrap();
+        assert_eq!(db.get_log_level().unwrap().as_deref(), Some("DEBUG"));
+    }
+
+    #[test]
+    fn env_overrides_are_only_kept_when_asked() {
+        let db = mem_db();
+        assert_eq!(db.get_kept_env_overrides().unwrap(), None);
+        let mut overrides = EnvOverrides::default();
+        overrides.set("base_url", "http://localhost:3000");
+        db.set_kept_env_overrides(Some(&overrides)).unwrap();
+        assert_eq!(db.get_kept_env_overrides().unwrap(), Some(overrides));
+        db.set_kept_env_overrides(None).unwrap();
+        assert_eq!(db.get_kept_env_overrides().unwrap(), None);
+    }
+
+    #[test]
+    fn handle_is_usable_before_the_database_is_open() {
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let started = Instant::now();
+        let db = Database::spawn_with(move || {
+            release_rx.recv().ok();
+            let conn = Connection::open_in_memory()?;
+            Database::init_schema(&conn)?;
+            Ok(conn)
+        });
+        // The constructor did not wait for the (still blocked) open.
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+
+        // A call made now queues behind the open and completes after it.
+        let db = std::sync::Arc::new(db);
+        let pending = {
+            let db = db.clone();
+            thread::spawn(move || db.get_history_count())
+        };
+        release_tx.send(()).unwrap();
+        assert_eq!(pending.join().unwrap().unwrap(), 0);
+    }
+
+    #[test]
+    fn calls_fail_cleanly_when_the_database_cannot_open() {
+        let db = Database::spawn_with(|| Err(anyhow!("disk full")));
+        assert!(db.load_recent_history(10, 0).is_err());
+    }
+
+    #[test]
+    fn split_editor_setting_roundtrips() {
+        let db = mem_db();
+        assert!(!db.get_split_editor().unwrap());
+        db.set_split_editor(true).unwrap();
+        assert!(db.get_split_editor().unwrap());
+        db.set_split_editor(false).unwrap();
+        assert!(!db.get_split_editor().unwrap());
+    }
+
+    #[test]
+    fn default_headers_are_all_until_picked() {
+        let db = mem_db();
+        assert_eq!(db.get_default_headers().unwrap(), None);
+        db.set_default_headers(&[PredefinedHeader::Accept, PredefinedHeader::ContentType]).unwrap();
+        assert_eq!(
+            db.get_default_headers().unwrap(),
+            Some(vec![PredefinedHeader::Accept, PredefinedHeader::ContentType])
+        );
+        db.set_default_headers(&[]).unwrap();
+        assert_eq!(db.get_default_headers().unwrap(), Some(vec![]));
+    }
+
+    #[test]
+    fn cookie_jar_is_off_until_turned_on() {
+        let db = mem_db();
+        assert!(!db.get_cookie_jar_enabled().unwrap());
+        db.set_cookie_jar_enabled(true).unwrap();
+        assert!(db.get_cookie_jar_enabled().unwrap());
+        db.set_cookie_jar_enabled(false).unwrap();
+        assert!(!db.get_cookie_jar_enabled().unwrap());
+    }
+
+    #[test]
+    fn session_is_overwritten_in_place() {
+        let db = mem_db();
+        assert_eq!(db.get_session().unwrap(), None);
+        db.set_session("{\"tabs\":[1]}").unwrap();
+        db.set_session("{\"tabs\":[2]}").unwrap();
+        assert_eq!(db.get_session().unwrap().as_deref(), Some("{\"tabs\":[2]}"));
+    }
+
     #[test]
     fn crud_and_active() {
         let db = mem_db();
@@ -453,8 +1939,8 @@ mod tests {
         db.replace_variables(
             id,
             &[
-                EnvVar { enabled: true, key: "baseUrl".into(), value: "http://x".into() },
-                EnvVar { enabled: false, key: "token".into(), value: "abc".into() },
+                EnvVar { enabled: true, key: "baseUrl".into(), value: "http://x".into(), secret: false },
+                EnvVar { enabled: false, key: "token".into(), value: "abc".into(), secret: true },
             ],
         )
         .unwrap();
@@ -465,6 +1951,15 @@ mod tests {
         assert_eq!(envs[0].variables.len(), 2);
         assert_eq!(envs[0].variables[0].key, "baseUrl");
         assert!(!envs[0].variables[1].enabled);
+        assert!(envs[0].variables[1].secret && !envs[0].variables[0].secret);
+
+        let copy = db.duplicate_environment(id, "dev copy").unwrap();
+        let envs = db.load_environments().unwrap();
+        assert_eq!(envs[1].id, copy);
+        assert_eq!(envs[1].name, "dev copy");
+        let keys: Vec<_> = envs[1].variables.iter().map(|v| (v.key.as_str(), v.enabled, v.secret)).collect();
+        assert_eq!(keys, vec![("baseUrl", true, false), ("token", false, true)]);
+        db.delete_environment(copy).unwrap();
 
         db.rename_environment(id, "staging").unwrap();
         assert_eq!(db.load_environments().unwrap()[0].name, "staging");
@@ -479,40 +1974,152 @@ mod tests {
         assert!(db.load_environments().unwrap().is_empty());
     }
 
+    #[test]
+    fn globals_are_replaced_as_a_whole() {
+        let db = mem_db();
+        assert!(db.load_globals().unwrap().is_empty());
+        let var = |key: &str, secret: bool| EnvVar { enabled: true, key: key.into(), value: "v".into(), secret };
+        db.replace_globals(&[var("host", false), var("token", true)]).unwrap();
+        db.replace_globals(&[var("token", true), var("host", false)]).unwrap();
+        let globals = db.load_globals().unwrap();
+        let keys: Vec<_> = globals.iter().map(|v| (v.key.as_str(), v.secret)).collect();
+        assert_eq!(keys, vec![("token", true), ("host", false)]);
+    }
+
+    #[test]
+    fn saved_requests_resave_and_cascade_with_their_collection() {
+        let db = mem_db();
+        let api = db.create_collection("API", None).unwrap();
+        let users = db.create_collection("Users", Some(api)).unwrap();
+        let other = db.create_collection("Other", None).unwrap();
+        let mut req = RequestData::new(HttpMethod::GET, "https://x.test/users".into());
+        let list = db.save_request(users, "List users", &req).unwrap();
+        db.save_request(other, "Ping", &req).unwrap();
+        assert!(!db.update_saved_request(list + 100, &req).unwrap());
+
+        req.method = HttpMethod::POST;
+        req.headers = vec![("X-A".into(), "1".into())];
+        assert!(db.update_saved_request(list, &req).unwrap());
+        let saved = db.load_saved_requests().unwrap();
+        assert_eq!(saved[0].name, "List users");
+        assert_eq!(saved[0].collection_id, users);
+        assert_eq!(saved[0].request.method, HttpMethod::POST);
+        assert_eq!(saved[0].request.headers, req.headers);
+
+        db.delete_collection(api).unwrap();
+        let names: Vec<_> = db.load_collections().unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, vec!["Other"]);
+        let saved = db.load_saved_requests().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].name, "Ping");
+    }
+
+    #[test]
+    fn moved_requests_keep_their_place_in_the_target_collection() {
+        let db = mem_db();
+        let a = db.create_collection("A", None).unwrap();
+        let b = db.create_collection("B", None).unwrap();
+        let req = RequestData::new(HttpMethod::GET, "https://x.test".into());
+        let one = db.save_request(a, "one", &req).unwrap();
+        let two = db.save_request(a, "two", &req).unwrap();
+        let three = db.save_request(a, "three", &req).unwrap();
+        db.save_request(b, "four", &req).unwrap();
+        let names = |collection: i64| -> Vec<String> {
+            db.load_saved_requests()
+                .unwrap()
+                .into_iter()
+                .filter(|s| s.collection_id == collection)
+                .map(|s| s.name)
+                .collect()
+        };
+
+        db.move_saved_request(three, a, 0).unwrap();
+        assert_eq!(names(a), vec!["three", "one", "two"]);
+        db.move_saved_request(three, a, 2).unwrap();
+        assert_eq!(names(a), vec!["one", "two", "three"]);
+
+        db.move_saved_request(one, b, 0).unwrap();
+        db.move_saved_request(two, b, usize::MAX).unwrap();
+        assert_eq!(names(a), vec!["three"]);
+        assert_eq!(names(b), vec!["one", "four", "two"]);
+        // A new save still lands last.
+        db.save_request(b, "five", &req).unwrap();
+        assert_eq!(names(b), vec!["one", "four", "two", "five"]);
+    }
+
+    #[test]
+    fn imported_folders_become_nested_collections() {
+        use crate::postman_import::CollectionRequest;
+        let request = |name: &str| CollectionRequest {
+            name: name.into(),
+            request: RequestData::new(HttpMethod::GET, format!("https://x.test/{name}")),
+        };
+        let root = CollectionFolder {
+            name: "Petstore".into(),
+            requests: vec![request("list")],
+            folders: vec![CollectionFolder { name: "Writes".into(), requests: vec![request("create")], folders: vec![] }],
+        };
+        let db = mem_db();
+        let id = db.import_collection(&root).unwrap();
+        let (collections, saved) = (db.load_collections().unwrap(), db.load_saved_requests().unwrap());
+        assert_eq!(collections.len(), 2);
+        assert_eq!((collections[0].id, collections[0].parent_id), (id, None));
+        assert_eq!(collections[1].parent_id, Some(collections[0].id));
+        assert_eq!(collections[1].name, "Writes");
+        let placed: Vec<_> = saved.iter().map(|s| (s.collection_id, s.request.url.as_str())).collect();
+        assert_eq!(
+            placed,
+            vec![(collections[0].id, "https://x.test/list"), (collections[1].id, "https://x.test/create")]
+        );
+    }
+
     #[test]
     fn history_roundtrip() {
         let db = mem_db();
-        db.insert_history("GET", "https://api.test/x", "[]", &crate::types::BodyType::None, &crate::types::AuthConfig::default())
-            .unwrap();
-        let items = db.load_recent_history(10).unwrap();
+        db.insert_history(&sent("GET", "https://api.test/x")).unwrap();
+        let items = db.load_recent_history(10, 0).unwrap();
         assert_eq!(items.len(), 1);
         assert_eq!(items[0].request.url, "https://api.test/x");
         db.clear_all_history().unwrap();
-        assert!(db.load_recent_history(10).unwrap().is_empty());
+        assert!(db.load_recent_history(10, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn run_report_is_stored_on_its_history_row_and_loaded_with_it() {
+        use crate::repeat_runner::{Attempt, RepeatReport};
+
+        let db = mem_db();
+        let single = db.insert_history(&sent("GET", "https://api.test/one")).unwrap();
+        let batch = db.insert_history(&sent("GET", "https://api.test/x")).unwrap();
+        let attempt = Attempt { index: 0, status: Some(200), duration_ms: 12, error: None };
+        let report = RepeatReport::new(10, 5, 40, true, vec![attempt]);
+        db.set_history_run_report(batch, &serde_json::to_string(&report).unwrap()).unwrap();
+
+        let items = db.load_recent_history(10, 0).unwrap();
+        let item = |id: i64| items.iter().find(|i| i.id == id).unwrap();
+        assert_eq!(item(batch).run_report.as_deref(), Some(&report));
+        assert_eq!(item(single).run_report, None);
     }
 
     #[test]
     fn search_history_matches_url_and_method_newest_first() {
         let db = mem_db();
-        db.insert_history("GET", "https://api.test/users", "[]", &crate::types::BodyType::None, &crate::types::AuthConfig::default())
-            .unwrap();
-        db.insert_history("POST", "https://api.test/login", "[]", &crate::types::BodyType::None, &crate::types::AuthConfig::default())
-            .unwrap();
-        db.insert_history("DELETE", "https://api.test/orders/1", "[]", &crate::types::BodyType::None, &crate::types::AuthConfig::default())
-            .unwrap();
+        db.insert_history(&sent("GET", "https://api.test/users")).unwrap();
+        db.insert_history(&sent("POST", "https://api.test/login")).unwrap();
+        db.insert_history(&sent("DELETE", "https://api.test/orders/1")).unwrap();
 
         // URL substring
-        let r = db.search_history("login", 10).unwrap();
+        let r = db.search_history("login", 10, 0).unwrap();
         assert_eq!(r.len(), 1);
         assert_eq!(r[0].request.url, "https://api.test/login");
 
         // method match, case-insensitive
-        let r = db.search_history("post", 10).unwrap();
+        let r = db.search_history("post", 10, 0).unwrap();
         assert_eq!(r.len(), 1);
         assert_eq!(r[0].request.method, HttpMethod::POST);
 
         // shared substring across all three, newest (last inserted) first
-        let r = db.search_history("api.test", 10).unwrap();
+        let r = db.search_history("api.test", 10, 0).unwrap();
         assert_eq!(r.len(), 3);
         assert_eq!(r[0].request.url, "https://api.test/orders/1");
     }
@@ -520,31 +2127,150 @@ mod tests {
     #[test]
     fn search_history_escapes_wildcards() {
         let db = mem_db();
-        db.insert_history("GET", "https://api.test/a%b", "[]", &crate::types::BodyType::None, &crate::types::AuthConfig::default())
-            .unwrap();
-        db.insert_history("GET", "https://api.test/a_b", "[]", &crate::types::BodyType::None, &crate::types::AuthConfig::default())
-            .unwrap();
-        db.insert_history("GET", "https://api.test/axb", "[]", &crate::types::BodyType::None, &crate::types::AuthConfig::default())
-            .unwrap();
+        db.insert_history(&sent("GET", "https://api.test/a%b")).unwrap();
+        db.insert_history(&sent("GET", "https://api.test/a_b")).unwrap();
+        db.insert_history(&sent("GET", "https://api.test/axb")).unwrap();
 
         // '%' must be treated literally: matches only the URL with a literal '%'
-        let r = db.search_history("a%b", 10).unwrap();
+        let r = db.search_history("a%b", 10, 0).unwrap();
         assert_eq!(r.len(), 1);
         assert_eq!(r[0].request.url, "https://api.test/a%b");
 
         // '_' must be treated literally: matches only the URL with a literal '_',
         // not the single-char wildcard that would also match "/axb" and "/a%b".
-        let r = db.search_history("a_b", 10).unwrap();
+        let r = db.search_history("a_b", 10, 0).unwrap();
         assert_eq!(r.len(), 1);
         assert_eq!(r[0].request.url, "https://api.test/a_b");
     }
 
+    #[test]
+    fn workspace_export_then_import_recreates_the_workspace() {
+        use crate::workspace_bundle::{build_bundle, parse_bundle, plan_import, ImportMode, SecretPolicy};
+        let var = |key: &str, value: &str| EnvVar { enabled: true, key: key.into(), value: value.into(), secret: false };
+
+        let exporter = mem_db();
+        let dev = exporter.create_environment("dev").unwrap();
+        exporter.replace_variables(dev, &[var("base_url", "https://dev"), EnvVar { secret: true, ..var("pin", "1") }]).unwrap();
+        let prod = exporter.create_environment("prod").unwrap();
+        exporter.replace_variables(prod, &[var("base_url", "https://prod")]).unwrap();
+        exporter.set_active_environment_id(Some(prod)).unwrap();
+        exporter.replace_globals(&[var("tenant", "acme")]).unwrap();
+        let pair = |k: &str, v: &str| (k.to_string(), v.to_string());
+        let preset = Preset { id: 0, name: "Tenant".into(), headers: vec![pair("X-Tenant", "{{tenant}}")], params: vec![pair("v", "2")] };
+        let preset_id = exporter.save_preset(&preset).unwrap();
+        let users = exporter.create_collection("Users", None).unwrap();
+        let admin = exporter.create_collection("Admin", Some(users)).unwrap();
+        let list = RequestData { presets: vec![preset_id], ..sent("GET", "{{base_url}}/users") };
+        exporter.save_request(users, "List", &list).unwrap();
+        exporter.save_request(admin, "Delete", &sent("DELETE", "{{base_url}}/users/:id")).unwrap();
+        exporter.set_default_headers(&[PredefinedHeader::Accept, PredefinedHeader::UserAgent]).unwrap();
+        exporter.set_default_user_agent(Some("acme-monitor/2")).unwrap();
+        exporter.set_body_limit(2048).unwrap();
+
+        let bundle = build_bundle(&exporter.load_workspace().unwrap(), SecretPolicy::Include, "t".into());
+        let bundle = parse_bundle(&serde_json::to_string_pretty(&bundle).unwrap()).unwrap();
+
+        let importer = mem_db();
+        importer.create_environment("scratch").unwrap();
+        importer.save_preset(&Preset { name: "Scratch".into(), ..preset.clone() }).unwrap();
+        importer.create_collection("Scratch", None).unwrap();
+        let plan = plan_import(&bundle, &importer.load_workspace().unwrap(), ImportMode::Replace);
+        importer.apply_workspace_import(plan).unwrap();
+
+        // The preset got a new id, which the imported request follows...
+        let imported = importer.load_workspace().unwrap();
+        let mut again = build_bundle(&imported, SecretPolicy::Include, "t".into());
+        let tenant = &mut again.presets[0];
+        assert_ne!(tenant.id, preset_id);
+        assert_eq!(again.collections[0].requests[0].request.presets, vec![tenant.id]);
+        // ...and with that set aside, exporting again gives the same bundle.
+        tenant.id = preset_id;
+        again.collections[0].requests[0].request.presets = vec![preset_id];
+        assert_eq!(again, bundle);
+        assert_eq!(imported.environments.iter().find(|e| Some(e.id) == imported.active_environment_id).unwrap().name, "prod");
+        assert!(imported.environments[0].variables[1].secret);
+    }
+
+    #[test]
+    fn workspace_import_applies_everything_in_one_transaction() {
+        use crate::workspace_bundle::{BundleSettings, PlannedEnvironment};
+        let var = |key: &str| EnvVar {
+            enabled: true,
+            key: key.to_string(),
+            value: "v".to_string(),
+            secret: false,
+        };
+
+        let db = mem_db();
+        let dev = db.create_environment("dev").unwrap();
+        db.create_environment("old").unwrap();
+
+        // A plan that fails part-way (unknown environment id → FK violation
+        // on its variables) must leave the earlier steps undone.
+        let broken = ImportPlan {
+            delete_ids: vec![dev],
+            environments: vec![
+                PlannedEnvironment { existing_id: None, name: "new".into(), variables: vec![var("a")] },
+                PlannedEnvironment { existing_id: Some(9999), name: "ghost".into(), variables: vec![var("b")] },
+            ],
+            ..Default::default()
+        };
+        assert!(db.apply_workspace_import(broken).is_err());
+        let names: Vec<String> = db.load_environments().unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["dev", "old"]);
+
+        let plan = ImportPlan {
+            delete_ids: vec![],
+            environments: vec![
+                PlannedEnvironment { existing_id: Some(dev), name: "dev".into(), variables: vec![var("x")] },
+                PlannedEnvironment { existing_id: None, name: "staging".into(), variables: vec![var("y")] },
+            ],
+            settings: BundleSettings { active_environment: Some("staging".into()), ..Default::default() },
+            ..Default::default()
+        };
+        db.apply_workspace_import(plan).unwrap();
+        let envs = db.load_environments().unwrap();
+        assert_eq!(envs.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), vec!["dev", "old", "staging"]);
+        assert_eq!(envs[0].variables[0].key, "x");
+        assert_eq!(db.get_active_environment_id().unwrap(), Some(envs[2].id));
+    }
+
     #[test]
     fn search_history_empty_query_matches_all() {
         let db = mem_db();
-        db.insert_history("GET", "https://api.test/users", "[]", &crate::types::BodyType::None, &crate::types::AuthConfig::default())
-            .unwrap();
-        let r = db.search_history("", 10).unwrap();
+        db.insert_history(&sent("GET", "https://api.test/users")).unwrap();
+        let r = db.search_history("", 10, 0).unwrap();
+        assert_eq!(r.len(), 1);
+        let r = db.search_history("   ", 10, 0).unwrap();
         assert_eq!(r.len(), 1);
     }
+
+    #[test]
+    fn search_history_needs_every_word_and_matches_status_prefixes() {
+        let db = mem_db();
+        let send = |method: &str, url: &str, status: Option<u16>| {
+            let id = db.insert_history(&sent(method, url)).unwrap();
+            if let Some(status) = status {
+                db.set_history_response(id, &response(Some(status), b""), 0).unwrap();
+            }
+        };
+        send("POST", "https://api.test/users", Some(500));
+        send("POST", "https://api.test/users", Some(201));
+        send("GET", "https://api.test/users", Some(500));
+        send("POST", "https://api.test/orders", Some(503));
+        send("POST", "https://api.test/users", None);
+
+        let urls = |query: &str| -> Vec<(String, String)> {
+            db.search_history(query, 10, 0)
+                .unwrap()
+                .into_iter()
+                .map(|item| (item.request.method.as_str().to_string(), item.request.url))
+                .collect()
+        };
+        assert_eq!(urls("POST 500 /users"), vec![("POST".into(), "https://api.test/users".into())]);
+        assert_eq!(urls("post 5").len(), 2);
+        assert_eq!(urls("5 /users").len(), 2);
+        assert_eq!(urls("POST /users").len(), 3);
+        assert!(urls("DELETE /users").is_empty());
+    }
 }
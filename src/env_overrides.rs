@@ -0,0 +1,111 @@
+//! Temporary variable overrides: a layer above the active environment for
+//! one debugging session ("point `{{base_url}}` at localhost for now")
+//! without touching the saved environment.
+//!
+//! Overrides resolve first, before the environment. They live in memory and
+//! are gone when the app closes, unless the user ticks "Keep after closing",
+//! in which case every change is also written to the database and the set
+//! comes back on the next start.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvOverrides {
+    /// Key/value pairs in the order they were added; keys are unique.
+    entries: Vec<(String, String)>,
+}
+
+impl EnvOverrides {
+    /// Add `key`, or change its value in place if it is already overridden.
+    pub fn set(&mut self, key: &str, value: &str) {
+        let key = key.trim();
+        if key.is_empty() {
+            return;
+        }
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value.to_string(),
+            None => self.entries.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn to_map(&self) -> HashMap<String, String> {
+        self.entries.iter().cloned().collect()
+    }
+}
+
+/// The variables requests resolve against: `env` with `overrides` on top.
+pub fn layered(env: &HashMap<String, String>, overrides: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut vars = env.clone();
+    vars.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_replaces_in_place_and_keeps_order() {
+        let mut overrides = EnvOverrides::default();
+        overrides.set("base_url", "http://localhost:3000");
+        overrides.set(" token ", "dev");
+        overrides.set("base_url", "http://localhost:4000");
+        overrides.set("  ", "ignored");
+        assert_eq!(
+            overrides.iter().collect::<Vec<_>>(),
+            vec![("base_url", "http://localhost:4000"), ("token", "dev")]
+        );
+        assert_eq!(overrides.remove("token").as_deref(), Some("dev"));
+        assert_eq!(overrides.remove("token"), None);
+        assert_eq!(overrides.len(), 1);
+    }
+
+    #[test]
+    fn overrides_win_over_the_environment() {
+        let env: HashMap<String, String> =
+            [("base_url", "https://api.prod"), ("token", "t")].map(|(k, v)| (k.into(), v.into())).into();
+        let mut overrides = EnvOverrides::default();
+        overrides.set("base_url", "http://localhost:3000");
+        overrides.set("extra", "1");
+
+        let vars = layered(&env, &overrides.to_map());
+        assert_eq!(vars["base_url"], "http://localhost:3000");
+        assert_eq!(vars["token"], "t");
+        assert_eq!(vars["extra"], "1");
+        assert_eq!(
+            crate::variables::substitute("{{base_url}}/users?t={{token}}", &vars),
+            "http://localhost:3000/users?t=t"
+        );
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut overrides = EnvOverrides::default();
+        overrides.set("a", "1");
+        overrides.set("b", "two");
+        let json = serde_json::to_string(&overrides).unwrap();
+        assert_eq!(serde_json::from_str::<EnvOverrides>(&json).unwrap(), overrides);
+    }
+}
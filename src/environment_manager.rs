@@ -1,7 +1,10 @@
-//! Environment management UI (shown inside a Dialog): create/rename/delete
-//! environments, edit their variables, and choose the active one. All mutations
-//! are written to the DB immediately and an `EnvironmentsChanged` event is emitted
-//! so `PoopmanApp` can reload and refresh the request editor's variable map.
+//! Environment management UI (shown inside a Dialog): create/rename/duplicate/
+//! delete environments, edit their variables and the Globals (variables every
+//! environment sees), and choose the active one. Like the headers table, the
+//! variable list keeps a trailing blank row; values of variables flagged secret
+//! render masked. All mutations are written to the DB immediately and an
+//! `EnvironmentsChanged` event is emitted so `PoopmanApp` can reload and
+//! refresh the request editor's variable map.
 
 use gpui::prelude::FluentBuilder as _;
 use gpui::*;
@@ -21,6 +24,7 @@ pub struct EnvironmentsChanged;
 
 struct VarRow {
     enabled: bool,
+    secret: bool,
     key_input: Entity<InputState>,
     value_input: Entity<InputState>,
 }
@@ -30,6 +34,10 @@ pub struct EnvironmentManager {
     environments: Vec<Environment>,
     active_id: Option<i64>,
     selected_id: Option<i64>,
+    /// Variables available in every environment; edited when `editing_globals`.
+    globals: Vec<EnvVar>,
+    /// The Globals entry is selected instead of an environment.
+    editing_globals: bool,
     name_input: Entity<InputState>,
     var_rows: Vec<VarRow>,
     env_list_scroll_handle: ScrollHandle,
@@ -44,17 +52,17 @@ pub struct EnvironmentManager {
 impl EventEmitter<EnvironmentsChanged> for EnvironmentManager {}
 
 impl EnvironmentManager {
+    /// Starts empty; `set_environments` fills it once the database is open.
     pub fn new(db: Arc<Database>, window: &mut Window, cx: &mut Context<Self>) -> Self {
-        let environments = db.load_environments().unwrap_or_default();
-        let active_id = db.get_active_environment_id().unwrap_or(None);
-        let selected_id = environments.first().map(|e| e.id);
         let name_input = cx.new(|cx| InputState::new(window, cx).placeholder("Environment name"));
 
         let mut this = Self {
             db,
-            environments,
-            active_id,
-            selected_id,
+            environments: vec![],
+            active_id: None,
+            selected_id: None,
+            globals: vec![],
+            editing_globals: false,
             name_input,
             var_rows: vec![],
             env_list_scroll_handle: ScrollHandle::new(),
@@ -67,21 +75,31 @@ impl EnvironmentManager {
     }
 
     /// (Re)subscribe to the name + variable inputs so any edit auto-saves.
+    /// Typing a key into the trailing blank row also appends a new blank row.
     fn wire_inputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let name = self.name_input.clone();
-        let inputs: Vec<Entity<InputState>> = self
-            .var_rows
-            .iter()
-            .flat_map(|r| [r.key_input.clone(), r.value_input.clone()])
-            .collect();
+        let keys: Vec<Entity<InputState>> = self.var_rows.iter().map(|r| r.key_input.clone()).collect();
+        let values: Vec<Entity<InputState>> = self.var_rows.iter().map(|r| r.value_input.clone()).collect();
 
-        let mut subs = Vec::with_capacity(inputs.len() + 1);
+        let mut subs = Vec::with_capacity(keys.len() + values.len() + 1);
         subs.push(cx.subscribe_in(&name, window, |this, _, ev: &InputEvent, _w, cx| {
             if matches!(ev, InputEvent::Change) && !this.suspend_autosave {
                 this.commit(cx);
             }
         }));
-        for input in &inputs {
+        for input in &keys {
+            subs.push(cx.subscribe_in(input, window, |this, input, ev: &InputEvent, window, cx| {
+                if !matches!(ev, InputEvent::Change) || this.suspend_autosave {
+                    return;
+                }
+                this.commit(cx);
+                let is_last = this.var_rows.last().map(|r| r.key_input.entity_id()) == Some(input.entity_id());
+                if is_last && !input.read(cx).value().is_empty() {
+                    this.push_blank_row(window, cx);
+                }
+            }));
+        }
+        for input in &values {
             subs.push(cx.subscribe_in(input, window, |this, _, ev: &InputEvent, _w, cx| {
                 if matches!(ev, InputEvent::Change) && !this.suspend_autosave {
                     this.commit(cx);
@@ -92,57 +110,87 @@ impl EnvironmentManager {
         self._subs = subs;
     }
 
+    /// Show `environments` and `globals` (as loaded at startup), selecting the
+    /// first environment.
+    pub(crate) fn set_environments(
+        &mut self,
+        environments: Vec<Environment>,
+        active_id: Option<i64>,
+        globals: Vec<EnvVar>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.selected_id = environments.first().map(|e| e.id);
+        self.environments = environments;
+        self.active_id = active_id;
+        self.globals = globals;
+        self.load_selected_into_editor(window, cx);
+        cx.notify();
+    }
+
     pub(crate) fn reload(&mut self) {
         self.environments = self.db.load_environments().unwrap_or_default();
         self.active_id = self.db.get_active_environment_id().unwrap_or(None);
+        self.globals = self.db.load_globals().unwrap_or_default();
     }
 
-    /// Populate name_input + var_rows from the currently selected environment.
+    /// Populate name_input + var_rows from the currently selected environment,
+    /// or from the globals.
     fn load_selected_into_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let selected = self
-            .selected_id
-            .and_then(|id| self.environments.iter().find(|e| e.id == id))
-            .cloned();
+        // (name, variables) of what is being edited; globals have no name.
+        let selected = if self.editing_globals {
+            Some((String::new(), self.globals.clone()))
+        } else {
+            self.selected_id
+                .and_then(|id| self.environments.iter().find(|e| e.id == id))
+                .map(|e| (e.name.clone(), e.variables.clone()))
+        };
 
         // Programmatic set_value below would otherwise auto-save the values we're
         // loading; suspend autosave for the duration.
         self.suspend_autosave = true;
 
-        let name = selected.as_ref().map(|e| e.name.clone()).unwrap_or_default();
+        let name = selected.as_ref().map(|(name, _)| name.clone()).unwrap_or_default();
         self.name_input.update(cx, |input, cx| {
             input.set_value(&name, window, cx);
         });
 
         self.var_rows.clear();
-        if let Some(env) = selected {
-            for v in &env.variables {
-                self.var_rows.push(self.make_var_row(v.enabled, &v.key, &v.value, window, cx));
+        if let Some((_, variables)) = selected {
+            for v in &variables {
+                self.var_rows.push(self.make_var_row(v, window, cx));
             }
+            let blank = self.make_var_row(&Self::blank_var(), window, cx);
+            self.var_rows.push(blank);
         }
 
         self.wire_inputs(window, cx);
         self.suspend_autosave = false;
     }
 
-    fn make_var_row(
-        &self,
-        enabled: bool,
-        key: &str,
-        value: &str,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> VarRow {
-        let key = key.to_string();
-        let value = value.to_string();
+    fn blank_var() -> EnvVar {
+        EnvVar {
+            enabled: true,
+            key: String::new(),
+            value: String::new(),
+            secret: false,
+        }
+    }
+
+    fn make_var_row(&self, var: &EnvVar, window: &mut Window, cx: &mut Context<Self>) -> VarRow {
+        let key = var.key.clone();
+        let value = var.value.clone();
+        let secret = var.secret;
         VarRow {
-            enabled,
+            enabled: var.enabled,
+            secret,
             key_input: cx.new(|cx| {
                 let mut i = InputState::new(window, cx).placeholder("Key");
                 i.set_value(&key, window, cx);
                 i
             }),
             value_input: cx.new(|cx| {
-                let mut i = InputState::new(window, cx).placeholder("Value");
+                let mut i = InputState::new(window, cx).placeholder("Value").masked(secret);
                 i.set_value(&value, window, cx);
                 i
             }),
@@ -153,6 +201,14 @@ impl EnvironmentManager {
         // Edits auto-save as they happen, so switching just reloads + reselects.
         self.reload();
         self.selected_id = Some(id);
+        self.editing_globals = false;
+        self.load_selected_into_editor(window, cx);
+        cx.notify();
+    }
+
+    fn select_globals(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.reload();
+        self.editing_globals = true;
         self.load_selected_into_editor(window, cx);
         cx.notify();
     }
@@ -162,6 +218,7 @@ impl EnvironmentManager {
             Ok(id) => {
                 self.reload();
                 self.selected_id = Some(id);
+                self.editing_globals = false;
                 self.load_selected_into_editor(window, cx);
                 cx.emit(EnvironmentsChanged);
                 cx.notify();
@@ -170,6 +227,24 @@ impl EnvironmentManager {
         }
     }
 
+    fn duplicate_environment(&mut self, id: i64, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(env) = self.environments.iter().find(|e| e.id == id) else {
+            return;
+        };
+        let name = format!("{} copy", env.name);
+        match self.db.duplicate_environment(id, &name) {
+            Ok(copy) => {
+                self.reload();
+                self.selected_id = Some(copy);
+                self.editing_globals = false;
+                self.load_selected_into_editor(window, cx);
+                cx.emit(EnvironmentsChanged);
+                cx.notify();
+            }
+            Err(e) => log::error!("Failed to duplicate environment: {}", e),
+        }
+    }
+
     fn delete_environment(&mut self, id: i64, window: &mut Window, cx: &mut Context<Self>) {
         if let Err(e) = self.db.delete_environment(id) {
             log::error!("Failed to delete environment: {}", e);
@@ -178,7 +253,9 @@ impl EnvironmentManager {
         self.reload();
         if self.selected_id == Some(id) {
             self.selected_id = self.environments.first().map(|e| e.id);
-            self.load_selected_into_editor(window, cx);
+            if !self.editing_globals {
+                self.load_selected_into_editor(window, cx);
+            }
         }
         cx.emit(EnvironmentsChanged);
         cx.notify();
@@ -194,15 +271,9 @@ impl EnvironmentManager {
         cx.notify();
     }
 
-    /// Persist the currently selected environment's name + variables.
+    /// Persist the currently selected environment's name + variables, or the
+    /// globals.
     fn save(&mut self, cx: &mut Context<Self>) {
-        let Some(id) = self.selected_id else {
-            return;
-        };
-        let name = self.name_input.read(cx).value().to_string();
-        if !name.is_empty() {
-            let _ = self.db.rename_environment(id, &name);
-        }
         let vars: Vec<EnvVar> = self
             .var_rows
             .iter()
@@ -210,9 +281,21 @@ impl EnvironmentManager {
                 enabled: r.enabled,
                 key: r.key_input.read(cx).value().to_string(),
                 value: r.value_input.read(cx).value().to_string(),
+                secret: r.secret,
             })
             .filter(|v| !v.key.is_empty() || !v.value.is_empty())
             .collect();
+        if self.editing_globals {
+            let _ = self.db.replace_globals(&vars);
+            return;
+        }
+        let Some(id) = self.selected_id else {
+            return;
+        };
+        let name = self.name_input.read(cx).value().to_string();
+        if !name.is_empty() {
+            let _ = self.db.rename_environment(id, &name);
+        }
         let _ = self.db.replace_variables(id, &vars);
     }
 
@@ -226,8 +309,8 @@ impl EnvironmentManager {
         cx.notify();
     }
 
-    fn add_var_row(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let row = self.make_var_row(true, "", "", window, cx);
+    fn push_blank_row(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let row = self.make_var_row(&Self::blank_var(), window, cx);
         self.var_rows.push(row);
         // The new row is empty (not yet persisted), but its inputs need change
         // subscriptions so typing into them auto-saves.
@@ -236,12 +319,22 @@ impl EnvironmentManager {
     }
 
     fn remove_var_row(&mut self, index: usize, cx: &mut Context<Self>) {
-        if index < self.var_rows.len() {
+        // The trailing blank row has no remove button, so it always survives.
+        if index + 1 < self.var_rows.len() {
             self.var_rows.remove(index);
             self.commit(cx);
         }
     }
 
+    fn toggle_secret(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(row) = self.var_rows.get_mut(index) {
+            row.secret = !row.secret;
+            let secret = row.secret;
+            row.value_input.update(cx, |input, cx| input.set_masked(secret, window, cx));
+            self.commit(cx);
+        }
+    }
+
     fn toggle_var(&mut self, index: usize, cx: &mut Context<Self>) {
         if let Some(row) = self.var_rows.get_mut(index) {
             row.enabled = !row.enabled;
@@ -255,6 +348,8 @@ impl Render for EnvironmentManager {
         let theme = cx.theme();
         let selected_id = self.selected_id;
         let active_id = self.active_id;
+        let var_count = self.var_rows.len();
+        let editing_globals = self.editing_globals;
 
         h_flex()
             .w_full()
@@ -311,6 +406,32 @@ impl Render for EnvironmentManager {
                                     .child("New environment"),
                             ),
                     )
+                    .child(
+                        h_flex()
+                            .id("env-globals")
+                            .w_full()
+                            .px_2()
+                            .py_1p5()
+                            .gap_2()
+                            .items_center()
+                            .rounded(theme.radius)
+                            .cursor_pointer()
+                            .when(editing_globals, |s| s.bg(theme.list_active))
+                            .hover(|s| s.bg(theme.list_hover))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.select_globals(window, cx);
+                            }))
+                            .child(div().w(px(16.)).flex_shrink_0())
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .min_w_0()
+                                    .text_sm()
+                                    .text_color(theme.foreground)
+                                    .child("Globals"),
+                            ),
+                    )
+                    .child(div().h(px(1.)).mx_2().my_1().bg(theme.border))
                     .child(
                         div()
                             .flex_1()
@@ -324,7 +445,7 @@ impl Render for EnvironmentManager {
                                     .overflow_scroll()
                                     .children(self.environments.iter().map(|env| {
                                 let id = env.id;
-                                let is_selected = selected_id == Some(id);
+                                let is_selected = !editing_globals && selected_id == Some(id);
                                 let is_active = active_id == Some(id);
                                 h_flex()
                                     .id(("env-row", id as u64))
@@ -400,19 +521,38 @@ impl Render for EnvironmentManager {
                     ),
             )
             // ---- Right: selected environment editor ----
-            .child(if let Some(sel_id) = selected_id {
+            .child(if editing_globals || selected_id.is_some() {
                 v_flex()
                     .flex_1()
                     .h_full()
                     .min_h_0()
                     .min_w_0()
                     .gap_3()
-                    .child(
-                        h_flex()
+                    .child(match selected_id.filter(|_| !editing_globals) {
+                        None => v_flex()
+                            .gap_0p5()
+                            .child(div().text_sm().font_weight(FontWeight::SEMIBOLD).child("Globals"))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .child("Available in every environment; the active environment wins when a key is in both."),
+                            )
+                            .into_any_element(),
+                        Some(sel_id) => h_flex()
                             .w_full()
                             .gap_2()
                             .items_center()
                             .child(div().flex_1().min_w_0().child(Input::new(&self.name_input)))
+                            .child(
+                                Button::new("env-duplicate")
+                                    .small()
+                                    .ghost()
+                                    .label("Duplicate")
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.duplicate_environment(sel_id, window, cx);
+                                    })),
+                            )
                             .child(
                                 Button::new("env-delete")
                                     .small()
@@ -421,8 +561,9 @@ impl Render for EnvironmentManager {
                                     .on_click(cx.listener(move |this, _, window, cx| {
                                         this.delete_environment(sel_id, window, cx);
                                     })),
-                            ),
-                    )
+                            )
+                            .into_any_element(),
+                    })
                     .child(
                         // Inline card (no shadow/bg) — it sits inside the dialog surface, so card_panel's elevation would be wrong here.
                         v_flex()
@@ -446,6 +587,7 @@ impl Render for EnvironmentManager {
                                     .child(div().w(px(20.)).flex_shrink_0())
                                     .child(div().flex_1().child("KEY"))
                                     .child(div().flex_1().child("VALUE"))
+                                    .child(div().w(px(52.)).flex_shrink_0().child("SECRET"))
                                     .child(div().w(px(24.)).flex_shrink_0()),
                             )
                             .child(
@@ -459,6 +601,7 @@ impl Render for EnvironmentManager {
                                             .track_scroll(&self.var_list_scroll_handle)
                                             .overflow_scroll()
                                             .children(self.var_rows.iter().enumerate().map(|(index, row)| {
+                                        let is_trailing = index + 1 == var_count;
                                         h_flex()
                                             .w_full()
                                             .gap_2()
@@ -477,32 +620,39 @@ impl Render for EnvironmentManager {
                                                 ),
                                             )
                                             .child(div().flex_1().min_w_0().child(Input::new(&row.key_input)))
-                                            .child(div().flex_1().min_w_0().child(Input::new(&row.value_input)))
+                                            .child(div().flex_1().min_w_0().child(
+                                                Input::new(&row.value_input).when(row.secret, |i| i.mask_toggle()),
+                                            ))
                                             .child(
-                                                div().w(px(24.)).flex_shrink_0().flex().justify_center().child(
-                                                    Button::new(("var-del", index))
-                                                        .ghost()
-                                                        .xsmall()
-                                                        .label("×")
-                                                        .on_click(cx.listener(move |this, _, _window, cx| {
-                                                            this.remove_var_row(index, cx);
+                                                div().w(px(52.)).flex_shrink_0().flex().justify_center().child(
+                                                    Checkbox::new(("var-secret", index))
+                                                        .checked(row.secret)
+                                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                                            this.toggle_secret(index, window, cx);
                                                         })),
                                                 ),
                                             )
+                                            .child(
+                                                div().w(px(24.)).flex_shrink_0().flex().justify_center().when(
+                                                    !is_trailing,
+                                                    |cell| {
+                                                        cell.child(
+                                                            Button::new(("var-del", index))
+                                                                .ghost()
+                                                                .xsmall()
+                                                                .label("×")
+                                                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                                                    this.remove_var_row(index, cx);
+                                                                })),
+                                                        )
+                                                    },
+                                                ),
+                                            )
                                     })),
                                     )
                                     .vertical_scrollbar(&self.var_list_scroll_handle),
                             ),
                     )
-                    .child(
-                        Button::new("env-add-var")
-                            .small()
-                            .ghost()
-                            .label("+ Add variable")
-                            .on_click(cx.listener(|this, _, window, cx| {
-                                this.add_var_row(window, cx);
-                            })),
-                    )
                     .into_any_element()
             } else {
                 v_flex()
@@ -0,0 +1,195 @@
+//! Request chaining: rules on a request that copy a value out of its
+//! response into a variable (`token` from the login response, say), so the
+//! next request can use it as `{{token}}`. Configured next to the Tests,
+//! run by the app after each send. Pure.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::ResponseData;
+
+/// Where a rule reads its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    #[default]
+    JsonPath,
+    Header,
+}
+
+impl Source {
+    pub const ALL: [Source; 2] = [Source::JsonPath, Source::Header];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::JsonPath => "JSONPath",
+            Source::Header => "Header",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            Source::JsonPath => "$.token",
+            Source::Header => "Header name",
+        }
+    }
+}
+
+/// Where the value is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// The active environment, saved like an edit in the environment
+    /// dialog. Without one, the value goes to the session instead.
+    #[default]
+    Environment,
+    /// The temporary overrides (see `env_overrides`): gone on exit.
+    Session,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 2] = [Scope::Environment, Scope::Session];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Scope::Environment => "Environment",
+            Scope::Session => "Session",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ExtractRule {
+    pub source: Source,
+    /// JSONPath or header name.
+    pub path: String,
+    pub variable: String,
+    #[serde(default)]
+    pub scope: Scope,
+}
+
+impl ExtractRule {
+    #[cfg(test)]
+    pub fn new(source: Source, path: &str, variable: &str) -> Self {
+        Self { source, path: path.to_string(), variable: variable.to_string(), scope: Scope::default() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractResult {
+    pub rule: ExtractRule,
+    /// The value written, or why there was none.
+    pub value: Result<String, String>,
+}
+
+/// Run every rule against `response`, in order. Nothing is extracted from a
+/// response that is not a 2xx: each rule then reports it was skipped.
+pub fn run(rules: &[ExtractRule], response: &ResponseData) -> Vec<ExtractResult> {
+    let skipped = match response.status {
+        Some(status) if (200..300).contains(&status) => None,
+        Some(status) => Some(format!("skipped: status {status}")),
+        None => Some("skipped: no response".to_string()),
+    };
+    rules
+        .iter()
+        .map(|rule| {
+            let value = match &skipped {
+                Some(reason) => Err(reason.clone()),
+                None => extract(rule, response),
+            };
+            ExtractResult { rule: rule.clone(), value }
+        })
+        .collect()
+}
+
+fn extract(rule: &ExtractRule, response: &ResponseData) -> Result<String, String> {
+    let path = rule.path.trim();
+    match rule.source {
+        Source::Header => response
+            .headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(path))
+            .map(|(_, v)| v.trim().to_string())
+            .ok_or_else(|| "header missing".to_string()),
+        Source::JsonPath => {
+            let root = serde_json::from_str::<Value>(&response.body_text()).map_err(|_| "body is not JSON".to_string())?;
+            let matches = crate::json_filter::evaluate(path, &root).map_err(|e| e.to_string())?;
+            match <[Value; 1]>::try_from(matches) {
+                // A string is stored without its quotes; anything else as JSON.
+                Ok([Value::String(s)]) => Ok(s),
+                Ok([single]) => Ok(single.to_string()),
+                Err(matches) if matches.is_empty() => Err("no match".to_string()),
+                Err(matches) => Ok(Value::Array(matches).to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: Option<u16>, headers: &[(&str, &str)], body: &str) -> ResponseData {
+        ResponseData {
+            status,
+            duration_ms: 10,
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: body.as_bytes().to_vec(),
+            is_text: true,
+            route: Default::default(),
+            raw_header_values: vec![],
+            network_profile: Default::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
+        }
+    }
+
+    fn values(rules: &[ExtractRule], response: &ResponseData) -> Vec<Result<String, String>> {
+        run(rules, response).into_iter().map(|r| r.value).collect()
+    }
+
+    #[test]
+    fn json_values_are_extracted_strings_unquoted() {
+        let r = response(Some(200), &[], r#"{"token":"abc","user":{"id":7},"roles":["a","b"]}"#);
+        let rules = [
+            ExtractRule::new(Source::JsonPath, "$.token", "token"),
+            ExtractRule::new(Source::JsonPath, "$.user.id", "user_id"),
+            ExtractRule::new(Source::JsonPath, "$.roles[*]", "roles"),
+            ExtractRule::new(Source::JsonPath, "$.missing", "x"),
+        ];
+        assert_eq!(
+            values(&rules, &r),
+            [Ok("abc".into()), Ok("7".into()), Ok("[\"a\",\"b\"]".into()), Err("no match".into())]
+        );
+        let text = response(Some(200), &[], "plain");
+        assert_eq!(values(&rules[..1], &text), [Err("body is not JSON".into())]);
+    }
+
+    #[test]
+    fn headers_are_matched_ignoring_case() {
+        let r = response(Some(201), &[("Location", " /items/9 ")], "");
+        let rules = [ExtractRule::new(Source::Header, "location", "item"), ExtractRule::new(Source::Header, "ETag", "etag")];
+        assert_eq!(values(&rules, &r), [Ok("/items/9".into()), Err("header missing".into())]);
+    }
+
+    #[test]
+    fn nothing_is_extracted_from_a_failed_send() {
+        let rules = [ExtractRule::new(Source::JsonPath, "$.token", "token")];
+        assert_eq!(values(&rules, &response(Some(401), &[], r#"{"token":"x"}"#)), [Err("skipped: status 401".into())]);
+        assert_eq!(values(&rules, &response(None, &[], "refused")), [Err("skipped: no response".into())]);
+    }
+
+    #[test]
+    fn rules_read_back_without_a_scope() {
+        let parsed: ExtractRule = serde_json::from_str(r#"{"source":"header","path":"X-Id","variable":"id"}"#).unwrap();
+        assert_eq!(parsed, ExtractRule::new(Source::Header, "X-Id", "id"));
+        assert_eq!(parsed.scope, Scope::Environment);
+    }
+}
@@ -1,5 +1,6 @@
 //! Human-readable formatting helpers for UI display (sizes, durations,
-//! relative timestamps). Pure functions so every display rule is unit-tested.
+//! relative timestamps, hex dumps). Pure functions so every display rule is
+//! unit-tested.
 
 use chrono::{DateTime, Utc};
 
@@ -70,6 +71,30 @@ pub fn format_relative_time(timestamp: &str, now: DateTime<Utc>) -> String {
     }
 }
 
+/// `xxd`-style dump of the first `max` bytes: offset, 16 bytes in hex, then
+/// the printable ones as text. A last line counts what was left out.
+pub fn hex_dump(bytes: &[u8], max: usize) -> String {
+    let shown = &bytes[..bytes.len().min(max)];
+    let mut lines: Vec<String> = shown
+        .chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let hex: Vec<String> = (0..16)
+                .map(|i| chunk.get(i).map_or("  ".to_string(), |b| format!("{:02x}", b)))
+                .collect();
+            let text: String = chunk
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                .collect();
+            format!("{:08x}  {}  {}  |{}|", row * 16, hex[..8].join(" "), hex[8..].join(" "), text)
+        })
+        .collect();
+    if bytes.len() > shown.len() {
+        lines.push(format!("… {} more", format_size(bytes.len() - shown.len())));
+    }
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,4 +199,18 @@ mod tests {
         let now = Utc::now();
         assert_eq!(format_relative_time("not-a-date", now), "not-a-date");
     }
+
+    // ===== hex_dump =====
+
+    #[test]
+    fn hex_dump_pads_the_last_row_and_counts_the_rest() {
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\x00\x01";
+        assert_eq!(
+            hex_dump(png, 64),
+            "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|\n\
+             00000010  00 01                                             |..|"
+        );
+        assert_eq!(hex_dump(&[0u8; 40], 16).lines().last(), Some("… 24 B more"));
+        assert_eq!(hex_dump(&[], 16), "");
+    }
 }
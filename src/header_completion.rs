@@ -1,19 +1,20 @@
-//! Typeahead for custom header name fields.
+//! Typeahead for custom header name and value fields.
 //!
-//! Wraps [`crate::header_names::suggest`] in gpui-component's LSP-shaped
+//! Wraps [`crate::header_names::suggest`] and
+//! [`crate::header_names::suggest_values`] in gpui-component's LSP-shaped
 //! [`CompletionProvider`] so the library's completion menu (keyboard navigation,
 //! prefix highlighting, insertion) drives the UI. All matching logic lives in
 //! `header_names`; this file only adapts it.
 
 use anyhow::Result;
-use gpui::{Context, Task, Window};
+use gpui::{Context, Task, WeakEntity, Window};
 use gpui_component::input::{CompletionProvider, InputState, Rope, RopeExt};
 use lsp_types::{
     CompletionContext, CompletionItem, CompletionItemKind, CompletionResponse, CompletionTextEdit,
     TextEdit,
 };
 
-use crate::header_names::suggest;
+use crate::header_names::{suggest, suggest_values};
 
 /// Suggests standard HTTP header names in a single-line header-name input.
 pub struct HeaderCompletionProvider;
@@ -32,26 +33,7 @@ impl CompletionProvider for HeaderCompletionProvider {
         // means the edit replaces what was typed rather than appending to it,
         // which is what puts canonical casing in the field after "au".
         let prefix = rope.to_string();
-        let range = lsp_types::Range {
-            start: rope.offset_to_position(0),
-            end: rope.offset_to_position(rope.len()),
-        };
-
-        let items = suggest(&prefix)
-            .into_iter()
-            .map(|name| CompletionItem {
-                label: name.to_string(),
-                kind: Some(CompletionItemKind::FIELD),
-                filter_text: Some(prefix.clone()),
-                text_edit: Some(CompletionTextEdit::Edit(TextEdit {
-                    range,
-                    new_text: name.to_string(),
-                })),
-                ..Default::default()
-            })
-            .collect::<Vec<_>>();
-
-        Task::ready(Ok(CompletionResponse::Array(items)))
+        Task::ready(Ok(whole_field_items(rope, &prefix, suggest(&prefix), CompletionItemKind::FIELD)))
     }
 
     fn is_completion_trigger(
@@ -65,3 +47,56 @@ impl CompletionProvider for HeaderCompletionProvider {
         true
     }
 }
+
+/// Suggests common values in a custom header row's value input, for the
+/// header named in the same row's `key` input.
+pub struct HeaderValueCompletionProvider {
+    pub key: WeakEntity<InputState>,
+}
+
+impl CompletionProvider for HeaderValueCompletionProvider {
+    fn completions(
+        &self,
+        rope: &Rope,
+        _offset: usize,
+        _trigger: CompletionContext,
+        _window: &mut Window,
+        cx: &mut Context<InputState>,
+    ) -> Task<Result<CompletionResponse>> {
+        let name = self.key.upgrade().map(|key| key.read(cx).value().to_string()).unwrap_or_default();
+        let prefix = rope.to_string();
+        let values = suggest_values(&name, &prefix);
+        Task::ready(Ok(whole_field_items(rope, &prefix, values, CompletionItemKind::VALUE)))
+    }
+
+    fn is_completion_trigger(
+        &self,
+        _offset: usize,
+        _new_text: &str,
+        _cx: &mut Context<InputState>,
+    ) -> bool {
+        true
+    }
+}
+
+/// One item per suggestion, each replacing the whole field.
+fn whole_field_items(rope: &Rope, prefix: &str, suggestions: Vec<&str>, kind: CompletionItemKind) -> CompletionResponse {
+    let range = lsp_types::Range {
+        start: rope.offset_to_position(0),
+        end: rope.offset_to_position(rope.len()),
+    };
+    let items = suggestions
+        .into_iter()
+        .map(|text| CompletionItem {
+            label: text.to_string(),
+            kind: Some(kind),
+            filter_text: Some(prefix.to_string()),
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+                range,
+                new_text: text.to_string(),
+            })),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    CompletionResponse::Array(items)
+}
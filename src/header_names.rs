@@ -1,4 +1,5 @@
-//! Standard HTTP request header names for the header-key typeahead.
+//! Standard HTTP request header names for the header-key typeahead, and
+//! common values for the header-value one.
 //!
 //! Pure functions and a static table, so the whole matching rule is unit-tested
 //! without a GPUI window. The UI layer wraps `suggest` in a
@@ -90,6 +91,46 @@ pub fn suggest(prefix: &str) -> Vec<&'static str> {
         .collect()
 }
 
+/// Common values per header name, offered in a custom row's value field once
+/// its name is known. Sorted by name; every name is one of `HEADER_NAMES`.
+pub const HEADER_VALUES: &[(&str, &[&str])] = &[
+    ("Accept-Charset", &["utf-8", "iso-8859-1", "*"]),
+    ("Accept-Encoding", &["gzip, deflate, br", "gzip", "deflate", "br", "zstd", "identity"]),
+    ("Accept-Language", &["en-US,en;q=0.9", "en-US", "en-GB", "de-DE", "fr-FR", "es-ES", "*"]),
+    ("Access-Control-Request-Headers", &["Content-Type", "Authorization", "Content-Type, Authorization"]),
+    ("Access-Control-Request-Method", &["GET", "POST", "PUT", "PATCH", "DELETE"]),
+    ("Authorization", &["Bearer ", "Basic "]),
+    ("Content-Disposition", &["inline", "attachment", "attachment; filename=\"file.txt\""]),
+    ("Content-Encoding", &["gzip", "deflate", "br", "identity"]),
+    ("Expect", &["100-continue"]),
+    ("If-Match", &["*"]),
+    ("If-None-Match", &["*"]),
+    ("Keep-Alive", &["timeout=5, max=100"]),
+    ("Origin", &["http://localhost:3000", "null"]),
+    ("Pragma", &["no-cache"]),
+    ("Prefer", &["return=minimal", "return=representation", "respond-async", "wait=10"]),
+    ("Range", &["bytes=0-1023", "bytes=0-"]),
+    ("TE", &["trailers", "gzip", "deflate"]),
+    ("Transfer-Encoding", &["chunked", "gzip", "deflate"]),
+    ("Upgrade", &["websocket", "h2c"]),
+    ("X-Forwarded-Proto", &["https", "http"]),
+    ("X-Frame-Options", &["DENY", "SAMEORIGIN"]),
+    ("X-HTTP-Method-Override", &["PUT", "PATCH", "DELETE"]),
+    ("X-Requested-With", &["XMLHttpRequest"]),
+];
+
+/// Common values of the header `name` (any case) that start with `prefix`,
+/// in table order. An empty `prefix` yields them all: unlike the name field,
+/// the value menu only opens on an edit, never on focus alone.
+pub fn suggest_values(name: &str, prefix: &str) -> Vec<&'static str> {
+    let name = name.trim();
+    HEADER_VALUES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(name))
+        .map(|(_, values)| values.iter().copied().filter(|value| starts_with_ignore_ascii_case(value, prefix)).collect())
+        .unwrap_or_default()
+}
+
 fn starts_with_ignore_ascii_case(name: &str, prefix: &str) -> bool {
     name.len() >= prefix.len()
         && name.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
@@ -180,6 +221,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn values_follow_the_header_name_in_any_case() {
+        assert_eq!(suggest_values("accept-encoding", "g"), ["gzip, deflate, br", "gzip"]);
+        assert_eq!(suggest_values(" Upgrade ", "WEB"), ["websocket"]);
+        assert_eq!(suggest_values("Pragma", ""), ["no-cache"]);
+        assert!(suggest_values("X-Request-Id", "").is_empty());
+        assert!(suggest_values("Accept-Encoding", "lz").is_empty());
+    }
+
+    #[test]
+    fn value_table_is_sorted_and_only_names_custom_headers() {
+        for pair in HEADER_VALUES.windows(2) {
+            assert!(pair[0].0 < pair[1].0, "{:?} then {:?}", pair[0].0, pair[1].0);
+        }
+        for (name, values) in HEADER_VALUES {
+            assert!(HEADER_NAMES.contains(name), "{name} is not a custom header name");
+            assert!(!values.is_empty(), "{name} has no values");
+        }
+    }
+
     #[test]
     fn accept_prefix_excludes_the_predefined_accept_but_keeps_its_relatives() {
         let results = suggest("accept");
@@ -0,0 +1,170 @@
+//! Per-request summary shown as chips on History rows: what kind of body was
+//! sent (with a short preview), how many query params and how many headers
+//! beyond the predefined set. Computed once when the row is inserted and
+//! stored next to it, so drawing the list never re-parses bodies.
+
+use crate::types::{BodyType, FormDataValue, PredefinedHeader, RawSubtype};
+
+/// Characters of body kept for the hover preview.
+pub const PREVIEW_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyKind {
+    #[default]
+    None,
+    Json,
+    Text,
+    Form,
+}
+
+impl BodyKind {
+    /// Stable name stored in the `body_kind` history column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BodyKind::None => "none",
+            BodyKind::Json => "json",
+            BodyKind::Text => "text",
+            BodyKind::Form => "form",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "json" => BodyKind::Json,
+            "text" => BodyKind::Text,
+            "form" => BodyKind::Form,
+            _ => BodyKind::None,
+        }
+    }
+
+    /// Glyph drawn in the row chip.
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            BodyKind::None => "",
+            BodyKind::Json => "{}",
+            BodyKind::Text => "¶",
+            BodyKind::Form => "≡",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BodyKind::None => "No body",
+            BodyKind::Json => "JSON body",
+            BodyKind::Text => "Raw body",
+            BodyKind::Form => "Form body",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HistoryMeta {
+    pub body_kind: BodyKind,
+    /// First `PREVIEW_CHARS` characters of the body, whitespace collapsed.
+    pub body_preview: String,
+    pub param_count: usize,
+    /// Headers other than the predefined ones every request carries.
+    pub header_count: usize,
+}
+
+impl HistoryMeta {
+    pub fn of(url: &str, headers: &[(String, String)], body: &BodyType) -> Self {
+        let (body_kind, text) = match body {
+            BodyType::None => (BodyKind::None, String::new()),
+            BodyType::Raw { content, .. } if content.trim().is_empty() => (BodyKind::None, String::new()),
+            BodyType::Raw { content, subtype: RawSubtype::Json } => (BodyKind::Json, content.clone()),
+            BodyType::Raw { content, .. } => (BodyKind::Text, content.clone()),
+            BodyType::FormData(rows) if rows.is_empty() => (BodyKind::None, String::new()),
+            BodyType::FormData(rows) => {
+                let fields: Vec<String> = rows
+                    .iter()
+                    .filter(|r| r.enabled)
+                    .map(|r| match &r.value {
+                        FormDataValue::Text(v) => format!("{}={}", r.key, v),
+                        FormDataValue::File { path } => format!("{}=@{}", r.key, path),
+                    })
+                    .collect();
+                (BodyKind::Form, fields.join("&"))
+            }
+        };
+        let predefined = PredefinedHeader::all();
+        Self {
+            body_kind,
+            body_preview: preview(&text),
+            param_count: crate::url_params::parse_query_params(url).len(),
+            header_count: headers
+                .iter()
+                .filter(|(k, _)| !predefined.iter().any(|p| p.name().eq_ignore_ascii_case(k)))
+                .count(),
+        }
+    }
+}
+
+/// Collapse runs of whitespace to one space and cut at `PREVIEW_CHARS`,
+/// marking the cut with an ellipsis.
+fn preview(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= PREVIEW_CHARS {
+        return collapsed;
+    }
+    let mut cut: String = collapsed.chars().take(PREVIEW_CHARS).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FormDataRow;
+
+    fn headers(names: &[&str]) -> Vec<(String, String)> {
+        names.iter().map(|n| (n.to_string(), "v".to_string())).collect()
+    }
+
+    #[test]
+    fn counts_params_and_only_non_predefined_headers() {
+        let meta = HistoryMeta::of(
+            "https://api.test/x?a=1&b=2&c",
+            &headers(&["Cache-Control", "content-type", "Authorization", "X-Trace"]),
+            &BodyType::None,
+        );
+        assert_eq!(meta.param_count, 3);
+        assert_eq!(meta.header_count, 2);
+        assert_eq!(meta.body_kind, BodyKind::None);
+        assert_eq!(HistoryMeta::of("https://api.test/x", &[], &BodyType::None).param_count, 0);
+    }
+
+    #[test]
+    fn classifies_bodies_and_previews_them() {
+        let json = BodyType::Raw { content: "{\n    \"a\": 1\n}".to_string(), subtype: RawSubtype::Json };
+        let meta = HistoryMeta::of("", &[], &json);
+        assert_eq!(meta.body_kind, BodyKind::Json);
+        assert_eq!(meta.body_preview, "{ \"a\": 1 }");
+
+        let blank = BodyType::Raw { content: "  \n".to_string(), subtype: RawSubtype::Json };
+        assert_eq!(HistoryMeta::of("", &[], &blank).body_kind, BodyKind::None);
+
+        let xml = BodyType::Raw { content: "<a/>".to_string(), subtype: RawSubtype::Xml };
+        assert_eq!(HistoryMeta::of("", &[], &xml).body_kind, BodyKind::Text);
+
+        let form = BodyType::FormData(vec![
+            FormDataRow { enabled: true, key: "name".to_string(), value: FormDataValue::Text("ann".to_string()) },
+            FormDataRow { enabled: false, key: "off".to_string(), value: FormDataValue::Text("x".to_string()) },
+            FormDataRow { enabled: true, key: "doc".to_string(), value: FormDataValue::File { path: "/tmp/a.pdf".to_string() } },
+        ]);
+        let meta = HistoryMeta::of("", &[], &form);
+        assert_eq!(meta.body_kind, BodyKind::Form);
+        assert_eq!(meta.body_preview, "name=ann&doc=@/tmp/a.pdf");
+    }
+
+    #[test]
+    fn long_previews_are_cut_with_an_ellipsis() {
+        let body = BodyType::Raw { content: "é".repeat(500), subtype: RawSubtype::Text };
+        let preview = HistoryMeta::of("", &[], &body).body_preview;
+        assert_eq!(preview.chars().count(), PREVIEW_CHARS + 1);
+        assert!(preview.ends_with('…'));
+        for kind in [BodyKind::None, BodyKind::Json, BodyKind::Text, BodyKind::Form] {
+            assert_eq!(BodyKind::from_str(kind.as_str()), kind);
+        }
+    }
+}
@@ -3,23 +3,89 @@ use gpui::*;
 use gpui_component::{
     button::*, h_flex,
     input::{Input, InputEvent, InputState},
+    menu::{ContextMenuExt as _, PopupMenuItem},
     scroll::ScrollableElement as _,
-    v_flex, ActiveTheme as _, Icon, Sizable as _,
+    spinner::Spinner,
+    tooltip::Tooltip,
+    v_flex, ActiveTheme as _, Disableable as _, Icon, Sizable as _,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::a11y::{self, tab_order};
 use crate::db::Database;
-use crate::types::HistoryItem;
+use crate::history_meta::BodyKind;
+use crate::types::{HistoryItem, Preset, RequestData, ResponseData};
 
-/// Maximum number of history rows loaded/searched at a time.
+/// Number of history rows loaded/searched per page.
 const HISTORY_LIMIT: usize = 100;
 
+/// Scrolling within this distance of the list's end loads the next page.
+const LOAD_MORE_MARGIN: Pixels = px(200.);
+
+/// How long typing must pause before the search runs.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// How long a resent row shows how it went before its button comes back.
+const RESEND_OUTCOME_SHOWN: Duration = Duration::from_secs(4);
+
+/// Below this panel width the row chips are dropped so the URL keeps its room.
+const CHIPS_MIN_WIDTH: f32 = 240.;
+
 /// Event emitted when a history item is clicked
 #[derive(Clone)]
 pub struct HistoryItemClicked {
     pub item: HistoryItem,
 }
 
+/// Event emitted when "Duplicate" is picked from a row's context menu: open
+/// the row in a new tab of its own, even if it is already open.
+#[derive(Clone)]
+pub struct HistoryDuplicateRequested {
+    pub item: HistoryItem,
+}
+
+/// Event emitted when "Diff with current tab" is picked from a row's context
+/// menu.
+#[derive(Clone)]
+pub struct HistoryDiffRequested {
+    pub item: HistoryItem,
+}
+
+/// Event emitted when "Export to File" is picked from a row's context menu.
+#[derive(Clone)]
+pub struct HistoryFileExportRequested {
+    pub item: HistoryItem,
+}
+
+/// Event emitted when a row was sent again from its resend button; the app
+/// records it as a new history row, like any other send.
+#[derive(Clone)]
+pub struct HistoryResent {
+    pub request: Arc<RequestData>,
+    pub response: Arc<ResponseData>,
+}
+
+/// Inline state of a row sent again from the panel.
+#[derive(Debug, Clone, Copy)]
+enum Resend {
+    InFlight,
+    /// Answered with this status; `None` is a network error.
+    Done(Option<u16>),
+}
+
+/// Event emitted after a row was pinned to or unpinned from the start panel.
+#[derive(Clone)]
+pub struct HistoryPinChanged;
+
+/// Event emitted to export rows as a Postman collection: one row from its
+/// context menu, or every row currently listed from the header button.
+#[derive(Clone)]
+pub struct HistoryExportRequested {
+    pub items: Vec<HistoryItem>,
+}
+
 /// History panel component
 pub struct HistoryPanel {
     db: Arc<Database>,
@@ -27,36 +93,137 @@ pub struct HistoryPanel {
     selected_id: Option<i64>,
     search: Entity<InputState>,
     query: String,
+    /// Pending debounced search; replacing it cancels the previous one.
+    search_task: Option<Task<()>>,
+    /// Rows being sent again, or just answered, by row id. Each resend runs
+    /// in its own task, so rows never wait on one another.
+    resends: HashMap<i64, Resend>,
+    /// What a resend builds its request against, kept in step with the
+    /// request editor's: every preset, and the `{{vars}}` in effect.
+    presets: Vec<Preset>,
+    vars: HashMap<String, String>,
     list_scroll_handle: ScrollHandle,
+    /// Rows asked for so far; grows by `HISTORY_LIMIT` with each page loaded,
+    /// so a reload keeps every page the user scrolled through.
+    limit: usize,
+    /// Whether the last page came back full, so another may follow.
+    has_more: bool,
+    /// Rows in the whole history table, shown in the header.
+    total: usize,
+    /// Width the panel was last laid out at; `None` until the first paint.
+    width: Option<Pixels>,
+    /// True until the first list arrives from the database, which opens off
+    /// the startup path; placeholder rows are drawn meanwhile.
+    loading: bool,
 }
 
 impl HistoryPanel {
     pub fn new(db: Arc<Database>, window: &mut Window, cx: &mut Context<Self>) -> Self {
-        // Load initial history from database
-        let history = db.load_recent_history(HISTORY_LIMIT).unwrap_or_default();
-
-        let search = cx.new(|cx| InputState::new(window, cx).placeholder("Search history"));
+        let search = cx.new(|cx| InputState::new(window, cx).placeholder("Search: URL, method, status"));
         cx.subscribe(&search, Self::on_search_change).detach();
 
+        // First load in the background: the query waits for the database to
+        // open, which must not hold up the first frame.
+        let loader = db.clone();
+        cx.spawn(async move |this, cx| {
+            let (history, total) = cx
+                .background_executor()
+                .spawn(async move {
+                    let history = loader.load_recent_history(HISTORY_LIMIT, 0);
+                    (history, loader.get_history_count().unwrap_or_default())
+                })
+                .await;
+            let history = history.unwrap_or_else(|e| {
+                log::error!("Failed to load history: {}", e);
+                vec![]
+            });
+            this.update(cx, |this, cx| {
+                // Typing a search meanwhile already loaded a list; keep it.
+                if this.loading {
+                    this.has_more = this.paged_len(&history) >= HISTORY_LIMIT;
+                    this.history = history;
+                    this.total = total;
+                    this.loading = false;
+                    cx.notify();
+                }
+            })
+        })
+        .detach();
+
         Self {
             db,
-            history,
+            history: vec![],
             selected_id: None,
             search,
             query: String::new(),
+            search_task: None,
+            resends: HashMap::new(),
+            presets: Vec::new(),
+            vars: HashMap::new(),
             list_scroll_handle: ScrollHandle::new(),
+            limit: HISTORY_LIMIT,
+            has_more: false,
+            total: 0,
+            width: None,
+            loading: true,
         }
     }
 
-    /// Re-query the list to honor the current query: recent when empty,
-    /// search otherwise. Shared by typing and by `reload`.
-    fn refresh_list(&mut self) {
+    /// Query one page for the current query: recent when empty, search
+    /// otherwise.
+    fn fetch(&self, limit: usize, offset: usize) -> Vec<HistoryItem> {
         let q = self.query.trim();
-        self.history = if q.is_empty() {
-            self.db.load_recent_history(HISTORY_LIMIT).unwrap_or_default()
+        let rows = if q.is_empty() {
+            self.db.load_recent_history(limit, offset)
         } else {
-            self.db.search_history(q, HISTORY_LIMIT).unwrap_or_default()
+            self.db.search_history(q, limit, offset)
         };
+        rows.unwrap_or_else(|e| {
+            log::error!("Failed to load history: {}", e);
+            vec![]
+        })
+    }
+
+    /// Rows of `page` that count against the page size. The recent list
+    /// brings every pinned row on top of its first page.
+    fn paged_len(&self, page: &[HistoryItem]) -> usize {
+        if self.query.trim().is_empty() {
+            page.iter().filter(|item| !item.pinned).count()
+        } else {
+            page.len()
+        }
+    }
+
+    /// Re-query the list to honor the current query, keeping the pages
+    /// loaded so far. Shared by typing and by `reload`.
+    fn refresh_list(&mut self) {
+        self.loading = false;
+        self.history = self.fetch(self.limit, 0);
+        self.has_more = self.paged_len(&self.history) >= self.limit;
+        self.total = self.db.get_history_count().unwrap_or_default();
+    }
+
+    /// Append the next page to the list.
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if self.loading || !self.has_more {
+            return;
+        }
+        let page = self.fetch(HISTORY_LIMIT, self.limit);
+        self.has_more = self.paged_len(&page) >= HISTORY_LIMIT;
+        self.limit += HISTORY_LIMIT;
+        // Rows sent since the last reload shift the pages; skip the overlap.
+        let seen: std::collections::HashSet<i64> = self.history.iter().map(|item| item.id).collect();
+        self.history.extend(page.into_iter().filter(|item| !seen.contains(&item.id)));
+        cx.notify();
+    }
+
+    /// Load the next page once the list is scrolled close to its end.
+    fn load_more_near_bottom(&mut self, cx: &mut Context<Self>) {
+        let scrolled = -self.list_scroll_handle.offset().y;
+        let max = self.list_scroll_handle.max_offset().height;
+        if max - scrolled <= LOAD_MORE_MARGIN {
+            self.load_more(cx);
+        }
     }
 
     fn on_search_change(
@@ -67,8 +234,15 @@ impl HistoryPanel {
     ) {
         if matches!(event, InputEvent::Change) {
             self.query = self.search.read(cx).value().to_string();
-            self.refresh_list();
-            cx.notify();
+            // A new query starts over from its first page.
+            self.limit = HISTORY_LIMIT;
+            self.search_task = Some(cx.spawn(async move |this, cx| {
+                cx.background_executor().timer(SEARCH_DEBOUNCE).await;
+                let _ = this.update(cx, |this, cx| {
+                    this.refresh_list();
+                    cx.notify();
+                });
+            }));
         }
     }
 
@@ -84,18 +258,28 @@ impl HistoryPanel {
         cx.notify();
     }
 
-    fn clear_history(
-        &mut self,
-        _event: &gpui::ClickEvent,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        if let Err(e) = self.db.clear_all_history() {
+    /// Clear history; pinned rows are kept unless `including_pinned`.
+    fn clear_history(&mut self, including_pinned: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let cleared = if including_pinned {
+            self.db.clear_all_history()
+        } else {
+            self.db.clear_unpinned_history()
+        };
+        if let Err(e) = cleared {
             log::error!("Failed to clear history: {}", e);
             return;
         }
 
-        self.history.clear();
+        if including_pinned {
+            self.history.clear();
+            cx.emit(HistoryPinChanged);
+        } else {
+            self.history.retain(|item| item.pinned);
+        }
+        self.limit = HISTORY_LIMIT;
+        self.has_more = false;
+        self.total = self.history.len();
+        self.loading = false;
         self.selected_id = None;
         self.query = String::new();
         self.search
@@ -103,20 +287,109 @@ impl HistoryPanel {
         cx.notify();
     }
 
+    /// Replace the presets and `{{vars}}` a resend applies (called by
+    /// PoopmanApp whenever it updates the request editor's).
+    pub fn set_send_context(&mut self, presets: Vec<Preset>, vars: HashMap<String, String>) {
+        self.presets = presets;
+        self.vars = vars;
+    }
+
+    /// Send `item`'s request again without opening it, showing progress and
+    /// then the outcome on the row.
+    fn resend(&mut self, item: &HistoryItem, cx: &mut Context<Self>) {
+        if self.resends.contains_key(&item.id) {
+            return;
+        }
+        let (id, request) = (item.id, item.request.clone());
+        let (presets, vars) = (self.presets.clone(), self.vars.clone());
+        self.resends.insert(id, Resend::InFlight);
+        cx.notify();
+        cx.spawn(async move |this, cx| {
+            let response = crate::request_editor::resend_recorded(&request, &presets, &vars).await;
+            this.update(cx, |this, cx| {
+                this.resends.insert(id, Resend::Done(response.status));
+                cx.emit(HistoryResent { request: Arc::new(request), response: Arc::new(response) });
+                cx.notify();
+            })?;
+            cx.background_executor().timer(RESEND_OUTCOME_SHOWN).await;
+            this.update(cx, |this, cx| {
+                this.resends.remove(&id);
+                cx.notify();
+            })
+        })
+        .detach();
+    }
+
+    /// The row's resend button, or the spinner / outcome in its place.
+    fn render_resend(&self, item: &HistoryItem, cx: &Context<Self>) -> AnyElement {
+        let theme = cx.theme();
+        let slot = div().id(("history-resend", item.id as u64)).flex_shrink_0().px_0p5().text_sm();
+        match self.resends.get(&item.id) {
+            None => {
+                let item = item.clone();
+                slot.rounded(theme.radius)
+                    .text_color(theme.muted_foreground)
+                    .hover(|s| s.text_color(theme.foreground))
+                    .child("\u{21bb}")
+                    .tooltip(|window, cx| Tooltip::new("Send again").build(window, cx))
+                    .on_click(cx.listener(move |this, _event: &gpui::ClickEvent, _window, cx| {
+                        cx.stop_propagation();
+                        this.resend(&item, cx);
+                    }))
+                    .into_any_element()
+            }
+            Some(Resend::InFlight) => slot
+                .pt_0p5()
+                .child(Spinner::new().xsmall().color(theme.muted_foreground))
+                .into_any_element(),
+            Some(Resend::Done(status)) => {
+                let ok = status.is_some_and(|s| s < 400);
+                let text = match status {
+                    Some(status) => format!("{} {}", if ok { "\u{2713}" } else { "\u{2717}" }, status),
+                    None => "\u{2717}".to_string(),
+                };
+                slot.text_xs()
+                    .text_color(if ok { theme.success } else { theme.danger })
+                    .font_family(theme.mono_font_family.clone())
+                    .child(text)
+                    .into_any_element()
+            }
+        }
+    }
+
+    fn set_pinned(&mut self, id: i64, pinned: bool, cx: &mut Context<Self>) {
+        if let Err(e) = self.db.set_history_pinned(id, pinned) {
+            log::error!("Failed to pin history item: {}", e);
+            return;
+        }
+        // Re-query: the row moves between the Pinned and Recent sections.
+        self.refresh_list();
+        cx.emit(HistoryPinChanged);
+        cx.notify();
+    }
+
     /// Render one history row. Split out of `render` so the list body stays
     /// shallow enough for rustfmt to format it.
     fn render_item(&self, item: &HistoryItem, cx: &Context<Self>) -> impl IntoElement {
         let theme = cx.theme();
         let item_id = item.id;
         let is_selected = self.selected_id == Some(item_id);
-        let verb = item.request.method.as_str();
-        let verb_color = crate::theme::method_color(item.request.method, theme);
+        let verb = crate::theme::method_badge(&item.request.method);
+        let verb_color = crate::theme::method_color(&item.request.method, theme);
         let url = item.request.url.clone();
         let time = crate::format::format_relative_time(&item.timestamp, chrono::Utc::now());
+        let show_chips = self.width.is_none_or(|w| w >= px(CHIPS_MIN_WIDTH));
+        // Rows sent from a color-labelled tab carry a faint wash of that color.
+        let tint = item
+            .color
+            .map(|c| crate::theme::tab_color(c).opacity(0.12))
+            .unwrap_or(gpui::transparent_black());
         let item_clone = item.clone();
+        let menu_item = item.clone();
+        let panel = cx.entity();
+        let pinned = item.pinned;
 
-        h_flex()
-            .id(("history-item", item_id as u64))
+        a11y::focusable(h_flex().id(("history-item", item_id as u64)), theme)
             .gap_2()
             .items_start()
             .w_full()
@@ -126,14 +399,12 @@ impl HistoryPanel {
             .border_1()
             .border_color(if is_selected {
                 theme.list_active_border
+            } else if pinned {
+                theme.warning.opacity(0.6)
             } else {
                 gpui::transparent_black()
             })
-            .bg(if is_selected {
-                theme.list_active
-            } else {
-                gpui::transparent_black()
-            })
+            .bg(if is_selected { theme.list_active } else { tint })
             .cursor_pointer()
             .hover(|s| {
                 s.bg(if is_selected {
@@ -173,23 +444,210 @@ impl HistoryPanel {
                             .child(url),
                     )
                     .child(
-                        div()
+                        h_flex()
+                            .gap_1()
                             .text_xs()
                             .text_color(theme.muted_foreground)
-                            .child(time),
+                            .child(div().flex_1().child(time))
+                            .when(show_chips, |this| this.children(self.render_chips(item, cx))),
                     ),
             )
+            .child(self.render_resend(item, cx))
+            .child(
+                div()
+                    .id(("history-pin", item_id as u64))
+                    .flex_shrink_0()
+                    .px_0p5()
+                    .rounded(theme.radius)
+                    .text_sm()
+                    .text_color(if pinned { theme.warning } else { theme.muted_foreground })
+                    .hover(|s| s.text_color(theme.warning))
+                    .child(if pinned { "\u{2605}" } else { "\u{2606}" })
+                    .tooltip(move |window, cx| {
+                        Tooltip::new(if pinned {
+                            "Unpin"
+                        } else {
+                            "Pin: kept when clearing, listed on the start page"
+                        })
+                        .build(window, cx)
+                    })
+                    .on_click(cx.listener(move |this, _event: &gpui::ClickEvent, _window, cx| {
+                        cx.stop_propagation();
+                        this.set_pinned(item_id, !pinned, cx);
+                    })),
+            )
+            .context_menu(move |menu, _window, _cx| {
+                let (panel, item) = (panel.clone(), menu_item.clone());
+                let (pin_panel, id) = (panel.clone(), item.id);
+                let (export_panel, export_item) = (panel.clone(), item.clone());
+                let (file_panel, file_item) = (panel.clone(), item.clone());
+                let (duplicate_panel, duplicate_item) = (panel.clone(), item.clone());
+                menu.item(PopupMenuItem::new("Duplicate").on_click(move |_, _window, cx| {
+                    let item = duplicate_item.clone();
+                    duplicate_panel.update(cx, |_, cx| cx.emit(HistoryDuplicateRequested { item }));
+                }))
+                .item(PopupMenuItem::new("Diff with current tab").on_click(move |_, _window, cx| {
+                    panel.update(cx, |_, cx| cx.emit(HistoryDiffRequested { item: item.clone() }));
+                }))
+                .item(PopupMenuItem::new("Export to Postman\u{2026}").on_click(move |_, _window, cx| {
+                    let items = vec![export_item.clone()];
+                    export_panel.update(cx, |_, cx| cx.emit(HistoryExportRequested { items }));
+                }))
+                .item(PopupMenuItem::new("Export to File\u{2026}").on_click(move |_, _window, cx| {
+                    let item = file_item.clone();
+                    file_panel.update(cx, |_, cx| cx.emit(HistoryFileExportRequested { item }));
+                }))
+                .item(
+                    PopupMenuItem::new(if pinned { "Unpin" } else { "Pin" }).on_click(
+                        move |_, _window, cx| {
+                            pin_panel.update(cx, |panel, cx| panel.set_pinned(id, !pinned, cx));
+                        },
+                    ),
+                )
+            })
+    }
+
+    /// Pinned rows in their own section above the chronological list.
+    fn render_sections(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let (pinned, recent): (Vec<&HistoryItem>, Vec<&HistoryItem>) =
+            self.history.iter().partition(|item| item.pinned);
+        let heading = |text: &'static str| {
+            div()
+                .px_2p5()
+                .pt_1()
+                .text_xs()
+                .font_weight(FontWeight::BOLD)
+                .text_color(theme.muted_foreground)
+                .child(text)
+        };
+        let has_pinned = !pinned.is_empty();
+        v_flex()
+            .gap_0p5()
+            .px_2()
+            .py_1()
+            .when(has_pinned, |this| this.child(heading("PINNED")))
+            .children(pinned.into_iter().map(|item| self.render_item(item, cx)))
+            .when(has_pinned && !recent.is_empty(), |this| this.child(heading("RECENT")))
+            .children(recent.into_iter().map(|item| self.render_item(item, cx)))
+    }
+
+    /// Status / body-kind / param-count / header-count chips for one row;
+    /// empty ones are left out.
+    fn render_chips(&self, item: &HistoryItem, cx: &Context<Self>) -> Vec<AnyElement> {
+        let theme = cx.theme();
+        let meta = &item.meta;
+        let chip = |id: &'static str, text: String, tip: String| {
+            div()
+                .id((id, item.id as u64))
+                .flex_shrink_0()
+                .px_1()
+                .rounded(theme.radius)
+                .bg(theme.muted)
+                .font_family(theme.mono_font_family.clone())
+                .child(text)
+                .tooltip(move |window, cx| Tooltip::new(tip.clone()).build(window, cx))
+                .into_any_element()
+        };
+
+        let mut chips = Vec::new();
+        if let Some(status) = item.status {
+            let color = match status {
+                200..=299 => theme.success,
+                400..=599 => theme.danger,
+                _ => theme.accent,
+            };
+            chips.push(
+                div()
+                    .id(("history-status", item.id as u64))
+                    .flex_shrink_0()
+                    .px_1()
+                    .rounded(theme.radius)
+                    .bg(color.opacity(0.12))
+                    .text_color(color)
+                    .font_family(theme.mono_font_family.clone())
+                    .child(status.to_string())
+                    .tooltip(move |window, cx| {
+                        Tooltip::new(format!("{} {}", status, crate::types::status_reason(status))).build(window, cx)
+                    })
+                    .into_any_element(),
+            );
+        }
+        if meta.body_kind != BodyKind::None {
+            let tip = if meta.body_preview.is_empty() {
+                meta.body_kind.label().to_string()
+            } else {
+                format!("{}\n{}", meta.body_kind.label(), meta.body_preview)
+            };
+            chips.push(chip("history-body", meta.body_kind.glyph().to_string(), tip));
+        }
+        if meta.param_count > 0 {
+            let tip = format!("{} query param{}", meta.param_count, plural(meta.param_count));
+            chips.push(chip("history-params", format!("?{}", meta.param_count), tip));
+        }
+        if meta.header_count > 0 {
+            let tip = format!("{} custom header{}", meta.header_count, plural(meta.header_count));
+            chips.push(chip("history-headers", format!("H{}", meta.header_count), tip));
+        }
+        chips
     }
 }
 
+/// Grey bars in the shape of history rows, shown until the list loads.
+fn render_skeleton(cx: &App) -> impl IntoElement {
+    let theme = cx.theme();
+    v_flex().gap_0p5().px_2().py_1().children((0..6).map(|i| {
+        h_flex()
+            .gap_2()
+            .px_2p5()
+            .py_2()
+            .child(div().w(px(34.)).h(px(12.)).rounded(theme.radius).bg(theme.muted))
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_1()
+                    .child(div().w(relative(0.9 - i as f32 * 0.08)).h(px(12.)).rounded(theme.radius).bg(theme.muted))
+                    .child(div().w(px(60.)).h(px(10.)).rounded(theme.radius).bg(theme.muted)),
+            )
+    }))
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 { "" } else { "s" }
+}
+
 impl EventEmitter<HistoryItemClicked> for HistoryPanel {}
+impl EventEmitter<HistoryDuplicateRequested> for HistoryPanel {}
+impl EventEmitter<HistoryDiffRequested> for HistoryPanel {}
+impl EventEmitter<HistoryPinChanged> for HistoryPanel {}
+impl EventEmitter<HistoryExportRequested> for HistoryPanel {}
+impl EventEmitter<HistoryFileExportRequested> for HistoryPanel {}
+impl EventEmitter<HistoryResent> for HistoryPanel {}
 
 impl Render for HistoryPanel {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let theme = cx.theme();
+        let panel = cx.entity();
 
-        v_flex()
+        a11y::tab_group(v_flex(), tab_order::HISTORY)
             .size_full()
+            .relative()
+            .child(
+                // Track the panel width so rows can drop their chips when narrow.
+                canvas(
+                    move |bounds, _, cx| {
+                        panel.update(cx, |this, cx| {
+                            if this.width != Some(bounds.size.width) {
+                                this.width = Some(bounds.size.width);
+                                cx.notify();
+                            }
+                        })
+                    },
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
             .child(
                 // Header
                 h_flex()
@@ -199,17 +657,57 @@ impl Render for HistoryPanel {
                     .border_b_1()
                     .border_color(theme.border)
                     .child(
-                        div()
-                            .font_weight(FontWeight::SEMIBOLD)
-                            .text_color(theme.foreground)
-                            .child("History")
+                        h_flex()
+                            .gap_1p5()
+                            .items_baseline()
+                            .child(
+                                div()
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(theme.foreground)
+                                    .child("History"),
+                            )
+                            .when(!self.loading, |this| {
+                                this.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(theme.muted_foreground)
+                                        .child(self.total.to_string()),
+                                )
+                            }),
                     )
                     .child(
-                        Button::new("clear-btn")
-                            .xsmall()
-                            .ghost()
-                            .label("Clear")
-                            .on_click(cx.listener(Self::clear_history)),
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("export-btn")
+                                    .xsmall()
+                                    .ghost()
+                                    .label("Export")
+                                    .tooltip("Export the listed requests as a Postman collection")
+                                    .disabled(self.history.is_empty())
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        cx.emit(HistoryExportRequested { items: this.history.clone() });
+                                    })),
+                            )
+                            .child(
+                                Button::new("clear-btn")
+                                    .xsmall()
+                                    .ghost()
+                                    .label("Clear")
+                                    .tooltip("Clear history, keeping pinned requests (right-click for all)")
+                                    .on_click(cx.listener(|this, _, window, cx| this.clear_history(false, window, cx)))
+                                    .context_menu({
+                                        let panel = cx.entity();
+                                        move |menu, _window, _cx| {
+                                            let panel = panel.clone();
+                                            menu.item(PopupMenuItem::new("Clear everything, including pinned").on_click(
+                                                move |_, window, cx| {
+                                                    panel.update(cx, |panel, cx| panel.clear_history(true, window, cx));
+                                                },
+                                            ))
+                                        }
+                                    }),
+                            ),
                     ),
             )
             .child(
@@ -226,7 +724,8 @@ impl Render for HistoryPanel {
                             .prefix(Icon::empty().path("icons/search.svg")),
                     ),
             )
-            .when(self.history.is_empty(), |this| {
+            .when(self.loading, |this| this.child(render_skeleton(cx)))
+            .when(!self.loading && self.history.is_empty(), |this| {
                 let msg = if self.query.trim().is_empty() {
                     "No history yet\n\nSend a request to get started".to_string()
                 } else {
@@ -262,9 +761,22 @@ impl Render for HistoryPanel {
                                 .min_h_0()
                                 .track_scroll(&self.list_scroll_handle)
                                 .overflow_scroll()
-                                .child(v_flex().gap_0p5().px_2().py_1().children(
-                                    self.history.iter().map(|item| self.render_item(item, cx)),
-                                )),
+                                .on_scroll_wheel(cx.listener(|this, _: &ScrollWheelEvent, _, cx| {
+                                    this.load_more_near_bottom(cx);
+                                }))
+                                .child(self.render_sections(cx))
+                                .when(self.has_more, |this| {
+                                    this.child(
+                                        div().px_2().pb_2().child(
+                                            Button::new("load-more-btn")
+                                                .xsmall()
+                                                .ghost()
+                                                .w_full()
+                                                .label("Load more")
+                                                .on_click(cx.listener(|this, _, _window, cx| this.load_more(cx))),
+                                        ),
+                                    )
+                                }),
                         )
                         .vertical_scrollbar(&self.list_scroll_handle),
                 )
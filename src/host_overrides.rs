@@ -0,0 +1,127 @@
+//! "Resolve host to": host names sent to a fixed address instead of the one
+//! DNS gives, like curl's `--resolve` or a line in /etc/hosts. Only the
+//! connection goes there; the URL keeps the name, so TLS (SNI and the
+//! certificate check) and the `Host` header still use it. Pure: parsing and
+//! matching the mappings the client applies.
+
+use std::net::{IpAddr, SocketAddr};
+
+use crate::types::HostOverride;
+
+/// Parse one mapping per line, `host address` or `host = address`, where the
+/// address is an IP, optionally with a port (`10.0.0.5`, `10.0.0.5:8443`,
+/// `[::1]:8443`). Blank lines and lines starting with `#` are skipped. The
+/// error names the first line that doesn't parse.
+pub fn parse(text: &str) -> Result<Vec<HostOverride>, String> {
+    let mut overrides = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (host, address) = match line.split_once('=') {
+            Some((host, address)) => (host.trim(), address.trim()),
+            None => line.split_once(char::is_whitespace).map_or((line, ""), |(h, a)| (h, a.trim())),
+        };
+        let problem = if address.is_empty() {
+            Some(format!("no address for {host}"))
+        } else if !is_host_name(host) {
+            Some(format!("\"{host}\" is not a host name"))
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return Err(format!("Line {}: {problem}", index + 1));
+        }
+        let Some(addr) = parse_address(address) else {
+            return Err(format!("Line {}: \"{address}\" is not an IP address or IP:port", index + 1));
+        };
+        overrides.push(HostOverride { host: host.to_ascii_lowercase(), addr });
+    }
+    Ok(overrides)
+}
+
+/// `overrides` as `parse` reads them back, one per line.
+pub fn format(overrides: &[HostOverride]) -> String {
+    overrides.iter().map(|o| format!("{} {}", o.host, o.address_label())).collect::<Vec<_>>().join("\n")
+}
+
+/// The mappings a request is sent with: its own, then the global ones for
+/// hosts it doesn't map itself.
+pub fn merged(global: &[HostOverride], request: &[HostOverride]) -> Vec<HostOverride> {
+    let mut merged = request.to_vec();
+    merged.extend(global.iter().filter(|g| !request.iter().any(|r| r.host == g.host)).cloned());
+    merged
+}
+
+/// The mapping that applies to `url`'s host, if any (the first one for it).
+pub fn for_url<'a>(url: &str, overrides: &'a [HostOverride]) -> Option<&'a HostOverride> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    overrides.iter().find(|o| o.host == host)
+}
+
+/// An IP literal is already an address, and a URL or a port has no place on
+/// the left-hand side.
+fn is_host_name(host: &str) -> bool {
+    !host.is_empty()
+        && host.parse::<IpAddr>().is_err()
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+}
+
+/// Port 0 stands for "the URL's port", as reqwest's `resolve` takes it.
+fn parse_address(address: &str) -> Option<SocketAddr> {
+    if let Ok(addr) = address.parse::<SocketAddr>() {
+        return Some(addr);
+    }
+    let ip = address.trim_start_matches('[').trim_end_matches(']');
+    ip.parse::<IpAddr>().ok().map(|ip| SocketAddr::new(ip, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(host: &str, addr: &str) -> HostOverride {
+        HostOverride { host: host.into(), addr: parse_address(addr).unwrap() }
+    }
+
+    #[test]
+    fn lines_map_hosts_to_addresses_with_an_optional_port() {
+        let text = "# staging LB\nAPI.example.com 10.0.0.5:8443\n\ncdn.example.com = 10.0.0.6\nv6.example.com [::1]:9000\n";
+        let overrides = parse(text).unwrap();
+        assert_eq!(
+            overrides,
+            vec![
+                mapping("api.example.com", "10.0.0.5:8443"),
+                mapping("cdn.example.com", "10.0.0.6"),
+                mapping("v6.example.com", "[::1]:9000"),
+            ]
+        );
+        assert_eq!(overrides[1].addr.port(), 0);
+        assert_eq!(format(&overrides), "api.example.com 10.0.0.5:8443\ncdn.example.com 10.0.0.6\nv6.example.com [::1]:9000");
+        assert_eq!(parse(&format(&overrides)).unwrap(), overrides);
+    }
+
+    #[test]
+    fn bad_lines_are_reported_by_number() {
+        assert_eq!(parse("a.test 10.0.0.1\nb.test").unwrap_err(), "Line 2: no address for b.test");
+        assert_eq!(parse("a.test example.com").unwrap_err(), "Line 1: \"example.com\" is not an IP address or IP:port");
+        assert_eq!(parse("https://a.test 10.0.0.1").unwrap_err(), "Line 1: \"https://a.test\" is not a host name");
+        assert_eq!(parse("10.0.0.1 10.0.0.2").unwrap_err(), "Line 1: \"10.0.0.1\" is not a host name");
+        assert_eq!(parse("  \n# nothing\n").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn a_request_mapping_wins_over_the_global_one_for_its_host() {
+        let global = vec![mapping("api.test", "10.0.0.1"), mapping("auth.test", "10.0.0.2")];
+        let request = vec![mapping("api.test", "127.0.0.1:8080")];
+        let merged = merged(&global, &request);
+        assert_eq!(merged, vec![mapping("api.test", "127.0.0.1:8080"), mapping("auth.test", "10.0.0.2")]);
+
+        assert_eq!(for_url("https://API.test:8443/v1", &merged), Some(&merged[0]));
+        assert_eq!(for_url("http://auth.test/login", &merged), Some(&merged[1]));
+        assert_eq!(for_url("http://other.test/", &merged), None);
+        assert_eq!(for_url("not a url", &merged), None);
+    }
+}
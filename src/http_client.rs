@@ -1,14 +1,194 @@
-use std::sync::OnceLock;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use anyhow::Result;
+use futures::StreamExt as _;
 use tokio::runtime::Runtime;
 
-use crate::types::{BodyType, FormDataValue, HttpMethod};
+use crate::connect_timing::{measure, TimedConnectLayer, TimedResolver};
+use crate::content_encoding::{Decoder, Encoding, ACCEPT_ENCODING};
+use crate::cookie_jar::{CookieJar, StoredCookie};
+use crate::repeat_runner::Attempt;
+use crate::types::{
+    BodyType, FormDataValue, HostOverride, HttpMethod, HttpProtocol, NetworkLimits, NetworkProfile, RawHeaderValue, ResponseTiming, RetryPolicy,
+    SendRoute, WireSize, MAX_RETRIES,
+};
 
 static RUNTIME: OnceLock<Runtime> = OnceLock::new();
 /// Shared reqwest client. A `Client` owns the connection pool and is internally
 /// reference-counted, so one instance is reused across all requests (keep-alive
 /// / pooling / TLS setup are amortized) and cloning is cheap.
 static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+/// Shared client that ignores every proxy setting (`SendRoute::Direct`).
+static DIRECT_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+/// One client per proxy URL (`SendRoute::Proxy`), each with its own pool.
+static PROXY_CLIENTS: OnceLock<Mutex<HashMap<String, reqwest::Client>>> = OnceLock::new();
+/// Clients for "minimal headers", "ignore TLS errors", HTTP-version and
+/// host-override requests, and for every request while a custom CA is set;
+/// one per route and combination.
+static SPECIAL_CLIENTS: OnceLock<Mutex<HashMap<SpecialClient, reqwest::Client>>> = OnceLock::new();
+/// Credentials and exceptions applied to every `SendRoute::Proxy` client.
+static PROXY_OPTIONS: Mutex<ProxyOptions> =
+    Mutex::new(ProxyOptions { username: String::new(), password: String::new(), no_proxy: String::new() });
+/// PEM file of an extra root certificate trusted by every request (the
+/// "CA Certificate" setting); `None` = the system roots only.
+static CA_CERT_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+/// Host overrides applied to every request (the "Resolve Hosts" setting),
+/// beneath each request's own.
+static HOST_OVERRIDES: Mutex<Vec<HostOverride>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SpecialClient {
+    route: SendRoute,
+    minimal_headers: bool,
+    ignore_tls_errors: bool,
+    ca_cert: Option<PathBuf>,
+    protocol: HttpProtocol,
+    host_overrides: Vec<HostOverride>,
+}
+
+/// Trust the root certificate in the PEM file at `path` (on top of the
+/// system roots) for requests from now on; `None` goes back to the system
+/// roots only. The file is read when a client is next needed, so choosing
+/// the same path again picks up a changed file.
+pub fn set_ca_cert_path(path: Option<PathBuf>) {
+    *CA_CERT_PATH.lock().expect("CA path lock poisoned") = path;
+    if let Some(clients) = SPECIAL_CLIENTS.get() {
+        clients.lock().expect("special clients lock poisoned").retain(|key, _| key.ca_cert.is_none());
+    }
+}
+
+fn ca_cert_path() -> Option<PathBuf> {
+    CA_CERT_PATH.lock().expect("CA path lock poisoned").clone()
+}
+
+/// Send requests for these hosts to their fixed addresses from now on (on
+/// top of each request's own overrides).
+pub fn set_host_overrides(overrides: Vec<HostOverride>) {
+    *HOST_OVERRIDES.lock().expect("host overrides lock poisoned") = overrides;
+    if let Some(clients) = SPECIAL_CLIENTS.get() {
+        clients.lock().expect("special clients lock poisoned").retain(|key, _| key.host_overrides.is_empty());
+    }
+}
+
+pub fn host_overrides() -> Vec<HostOverride> {
+    HOST_OVERRIDES.lock().expect("host overrides lock poisoned").clone()
+}
+
+/// The override a request to `url` with its own `overrides` is sent with,
+/// global ones included; for telling the user it was in effect.
+pub fn host_override_for(url: &str, overrides: &[HostOverride]) -> Option<HostOverride> {
+    crate::host_overrides::for_url(url, &crate::host_overrides::merged(&host_overrides(), overrides)).cloned()
+}
+
+/// The proxy settings besides its URL (Edit > Proxy…).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyOptions {
+    /// Proxy credentials (basic auth, or SOCKS5 username/password); none
+    /// are sent while empty.
+    pub username: String,
+    pub password: String,
+    /// Comma-separated hosts reached directly instead: `corp.local` also
+    /// covers its subdomains, `*` covers everything (`NO_PROXY` syntax).
+    pub no_proxy: String,
+}
+
+/// Use `options` for proxy clients from now on. Cached proxy clients are
+/// dropped so the next request builds one with them.
+pub fn set_proxy_options(options: ProxyOptions) {
+    *PROXY_OPTIONS.lock().expect("proxy options lock poisoned") = options;
+    if let Some(clients) = PROXY_CLIENTS.get() {
+        clients.lock().expect("proxy clients lock poisoned").clear();
+    }
+    if let Some(clients) = SPECIAL_CLIENTS.get() {
+        clients
+            .lock()
+            .expect("special clients lock poisoned")
+            .retain(|key, _| !matches!(key.route, SendRoute::Proxy(_)));
+    }
+}
+
+fn proxy_options() -> ProxyOptions {
+    PROXY_OPTIONS.lock().expect("proxy options lock poisoned").clone()
+}
+
+/// `reqwest::Proxy` for `url` with the current `ProxyOptions`.
+fn proxy(url: &str) -> Result<reqwest::Proxy> {
+    let options = proxy_options();
+    let mut proxy = reqwest::Proxy::all(url)?;
+    if !options.username.is_empty() {
+        proxy = proxy.basic_auth(&options.username, &options.password);
+    }
+    if !options.no_proxy.trim().is_empty() {
+        proxy = proxy.no_proxy(reqwest::NoProxy::from_string(&options.no_proxy));
+    }
+    Ok(proxy)
+}
+
+/// Whether `host` is on the `no_proxy` list. Mirrors reqwest's matching for
+/// host names and `*`; subnet entries are not matched.
+pub fn bypasses_proxy(host: &str, no_proxy: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']').to_ascii_lowercase();
+    no_proxy.split(',').map(|entry| entry.trim().trim_start_matches('.').to_ascii_lowercase()).any(|entry| {
+        !entry.is_empty()
+            && (entry == "*" || host == entry || host.strip_suffix(&entry).is_some_and(|rest| rest.ends_with('.')))
+    })
+}
+
+/// Marker error: the request couldn't connect to the proxy it was sent
+/// through, as opposed to the proxy failing to reach the target.
+#[derive(Debug)]
+pub struct ProxyConnectFailed {
+    pub reason: String,
+}
+
+impl std::fmt::Display for ProxyConnectFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Proxy connection failed: {}", self.reason)
+    }
+}
+
+impl std::error::Error for ProxyConnectFailed {}
+
+/// Turn a connect error of a proxied request into `ProxyConnectFailed`.
+fn classify_proxy_error(error: anyhow::Error, proxied: bool) -> anyhow::Error {
+    let connect = error.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_connect());
+    if !(proxied && connect) {
+        return error;
+    }
+    let reason = format!("{:#}", error);
+    anyhow::Error::new(ProxyConnectFailed { reason })
+}
+
+/// The configured CA certificate could not be used. Requests fail with it
+/// rather than silently going out without the certificate.
+#[derive(Debug)]
+pub struct CaCertError {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+impl std::fmt::Display for CaCertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Couldn't load CA certificate {}: {}", self.path.display(), self.reason)
+    }
+}
+
+impl std::error::Error for CaCertError {}
+
+/// Read and parse the PEM file at `path`.
+fn load_ca_cert(path: &Path) -> std::result::Result<reqwest::Certificate, CaCertError> {
+    let error = |reason: String| CaCertError { path: path.to_path_buf(), reason };
+    let pem = std::fs::read(path).map_err(|e| error(e.to_string()))?;
+    // reqwest only checks the PEM when the client is built, and would take a
+    // file without any certificate in it; catch that here.
+    if !String::from_utf8_lossy(&pem).contains("-----BEGIN CERTIFICATE-----") {
+        return Err(error("no PEM certificate in the file".to_string()));
+    }
+    reqwest::Certificate::from_pem(&pem).map_err(|e| error(e.to_string()))
+}
 
 /// A fully-read HTTP response. The body is collected on the tokio runtime
 /// (reqwest's body stream requires its reactor), so callers can use it freely.
@@ -17,6 +197,106 @@ pub struct HttpResponse {
     pub status: u16,
     pub headers: Vec<(String, String)>,
     pub body: Vec<u8>,
+    /// Bytes of the header values that were not visible ASCII.
+    pub raw_header_values: Vec<RawHeaderValue>,
+    /// Where the time went, including the network profile's delays.
+    pub timing: ResponseTiming,
+    /// Redirects followed to get here: each URL requested and its 3xx status.
+    pub redirects: Vec<(String, u16)>,
+    /// Times the request was sent, retries included.
+    pub attempts: u8,
+    /// Set when reading stopped at the body limit: the bytes received by
+    /// then (`body` holds the first `body_limit()` of them).
+    pub truncated_after: Option<u64>,
+    /// What the headers and the still-encoded body took on the wire.
+    pub wire_size: WireSize,
+    /// The HTTP version it came over ("HTTP/1.1", "HTTP/2").
+    pub version: String,
+}
+
+/// Redirects followed before giving up, as reqwest's default policy does.
+pub const MAX_REDIRECTS: usize = 10;
+
+/// Bytes of a response body read into memory unless the setting says
+/// otherwise.
+pub const DEFAULT_BODY_LIMIT: u64 = 10 * 1024 * 1024;
+
+/// Limits offered in the Edit menu; 0 = no limit.
+pub const BODY_LIMIT_CHOICES: [u64; 5] = [1 << 20, DEFAULT_BODY_LIMIT, 100 << 20, 1 << 30, 0];
+
+/// Response bodies stop being read past this many bytes; 0 = no limit.
+static BODY_LIMIT: AtomicU64 = AtomicU64::new(DEFAULT_BODY_LIMIT);
+
+/// Cap the bytes read into memory per response body (Edit > Response Size
+/// Limit); 0 reads whole bodies. Downloads to a file are never capped.
+pub fn set_body_limit(bytes: u64) {
+    BODY_LIMIT.store(bytes, Ordering::Relaxed);
+}
+
+pub fn body_limit() -> u64 {
+    BODY_LIMIT.load(Ordering::Relaxed)
+}
+
+/// Cookies set by responses, kept for the session (never written to disk).
+static COOKIE_JAR: Mutex<CookieJar> = Mutex::new(CookieJar::new());
+/// Whether `COOKIE_JAR` is filled and sent from; read on every hop, so
+/// turning it off stops cookies going out from the next request on.
+static COOKIE_JAR_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Keep the cookies responses set and send them back on later requests
+/// (Edit > Cookies…). Turning it off leaves the jar's contents alone.
+pub fn set_cookie_jar_enabled(enabled: bool) {
+    COOKIE_JAR_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn cookie_jar_enabled() -> bool {
+    COOKIE_JAR_ENABLED.load(Ordering::Relaxed)
+}
+
+/// The shared jar, to list or delete cookies.
+pub fn cookie_jar() -> std::sync::MutexGuard<'static, CookieJar> {
+    COOKIE_JAR.lock().expect("cookie jar lock poisoned")
+}
+
+/// The jar's cookies a request to `url` would carry; none while it is off.
+pub fn jar_cookies_for(url: &str) -> Vec<StoredCookie> {
+    match url::Url::parse(url) {
+        Ok(url) if cookie_jar_enabled() => {
+            cookie_jar().matching(&url, chrono::Utc::now()).into_iter().cloned().collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// `headers` plus the jar's `Cookie` header for `url`. A `Cookie` header
+/// the request sets itself wins: the jar adds nothing then.
+fn with_jar_cookies<'a>(url: &str, headers: &'a [(String, String)]) -> Cow<'a, [(String, String)]> {
+    if !cookie_jar_enabled() || headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("cookie")) {
+        return Cow::Borrowed(headers);
+    }
+    let jar_header = url::Url::parse(url).ok().and_then(|url| cookie_jar().header_for(&url, chrono::Utc::now()));
+    match jar_header {
+        Some(value) => {
+            let mut headers = headers.to_vec();
+            headers.push(("Cookie".to_string(), value));
+            Cow::Owned(headers)
+        }
+        None => Cow::Borrowed(headers),
+    }
+}
+
+/// Put the cookies `response` sets into the jar, while it is on.
+fn keep_jar_cookies(response: &reqwest::Response) {
+    if !cookie_jar_enabled() {
+        return;
+    }
+    let now = chrono::Utc::now();
+    let mut jar = cookie_jar();
+    for value in response.headers().get_all(reqwest::header::SET_COOKIE) {
+        if let Ok(value) = value.to_str() {
+            jar.store(response.url(), value, now);
+        }
+    }
 }
 
 /// Marker error: the in-flight request was aborted by the user.
@@ -32,6 +312,45 @@ impl std::fmt::Display for RequestCanceled {
 
 impl std::error::Error for RequestCanceled {}
 
+/// Marker error: the request ran past its timeout. Displays as
+/// `types::timeout_message`, which is what the response body shows.
+#[derive(Debug)]
+pub struct RequestTimedOut {
+    pub secs: u32,
+}
+
+impl std::fmt::Display for RequestTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", crate::types::timeout_message(self.secs))
+    }
+}
+
+impl std::error::Error for RequestTimedOut {}
+
+/// The last error of a request that was retried (see `RetryPolicy`) and
+/// still failed; displays as that error.
+#[derive(Debug)]
+pub struct GaveUpAfter {
+    pub attempts: u8,
+    pub error: anyhow::Error,
+}
+
+impl std::fmt::Display for GaveUpAfter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for GaveUpAfter {}
+
+/// Errors worth another attempt: transport failures and timeouts, not
+/// e.g. a form-data file that can't be read.
+fn is_network_error(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<reqwest::Error>().is_some()
+        || error.downcast_ref::<RequestTimedOut>().is_some()
+        || error.downcast_ref::<ProxyConnectFailed>().is_some()
+}
+
 /// A request already running on the tokio runtime. `abort_handle()` lets the
 /// UI abort the underlying task — the transfer really stops, the result isn't
 /// merely ignored. Await `wait()` for the outcome.
@@ -55,21 +374,221 @@ impl InFlightRequest {
 
 /// HTTP client that builds reqwest requests natively and manages its own
 /// tokio runtime.
+///
+/// The reqwest clients never follow redirects themselves: `execute` does,
+/// so each hop can be reported and following can be turned off per request.
 pub struct HttpClient {
     client: reqwest::Client,
+    profile: NetworkProfile,
+    /// Whole-request limit in seconds; 0 = none.
+    timeout_secs: u32,
+    follow_redirects: bool,
+    retry: RetryPolicy,
+    /// Set for `SendRoute::Proxy` clients: its no-proxy list, to tell which
+    /// requests actually go through the proxy.
+    no_proxy: Option<String>,
+    /// Decode gzip, deflate and br bodies; off for `minimal_headers`
+    /// clients, which never ask for them.
+    decode_bodies: bool,
 }
 
 impl HttpClient {
     pub fn new() -> Self {
         let client = CLIENT
             .get_or_init(|| {
-                reqwest::Client::builder()
+                compressing_client_builder()
                     .build()
                     .expect("Failed to initialize HTTP client")
             })
             .clone();
 
-        Self { client }
+        Self::from_client(client)
+    }
+
+    fn from_client(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            profile: NetworkProfile::Unthrottled,
+            timeout_secs: 0,
+            follow_redirects: true,
+            retry: RetryPolicy::default(),
+            no_proxy: None,
+            decode_bodies: true,
+        }
+    }
+
+    fn through(mut self, route: &SendRoute) -> Self {
+        if let SendRoute::Proxy(_) = route {
+            self.no_proxy = Some(proxy_options().no_proxy);
+        }
+        self
+    }
+
+    /// Whether a request to `url` goes through this client's proxy, so a
+    /// connect failure is the proxy's (`ProxyConnectFailed`).
+    fn is_proxied(&self, url: &str) -> bool {
+        self.no_proxy.as_deref().is_some_and(|no_proxy| {
+            url::Url::parse(url).ok().and_then(|u| u.host_str().map(|host| !bypasses_proxy(host, no_proxy))) != Some(false)
+        })
+    }
+
+    /// Shape every request sent through this client with `profile`: its
+    /// latency is added before the request goes out and both directions are
+    /// paced to its rates (see `throttle`). Multipart bodies are not paced
+    /// upward — reqwest builds their stream internally.
+    pub fn with_network_profile(mut self, profile: NetworkProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Fail each request with `RequestTimedOut` once `secs` have passed,
+    /// throttling delays included; 0 leaves requests unbounded.
+    pub fn with_timeout(mut self, secs: u32) -> Self {
+        self.timeout_secs = secs;
+        self
+    }
+
+    /// Whether 3xx responses are followed (up to `MAX_REDIRECTS`); when off
+    /// the 3xx itself, `Location` and all, is the response.
+    pub fn with_follow_redirects(mut self, follow: bool) -> Self {
+        self.follow_redirects = follow;
+        self
+    }
+
+    /// Re-send failed requests per `retry`, waiting `RetryPolicy::backoff`
+    /// between attempts; the timeout applies to each attempt. Only
+    /// `start_send` retries — a repeat run reports every attempt as is.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// A client that sends through `route`. `System` is the shared default
+    /// client (reqwest honors `HTTP(S)_PROXY` and the OS settings); fails only
+    /// for a proxy URL reqwest can't use.
+    ///
+    /// Cheap enough to call per send: the reqwest clients behind it are
+    /// built once per route and option set and cached, so consecutive sends
+    /// to a host share its kept-alive connections and TLS sessions. Changing
+    /// a setting they were built with (`set_proxy_options`,
+    /// `set_ca_cert_path`) drops the affected ones.
+    ///
+    /// With `minimal_headers` the client adds as little as reqwest allows:
+    /// compression negotiation is off, so no `Accept-Encoding` is sent (and
+    /// bodies arrive as the server sent them). reqwest has no way to drop its
+    /// `Accept: */*` default, so that one is still added when the request has
+    /// no Accept header of its own.
+    ///
+    /// No client adds a `User-Agent`: a request without one (its row
+    /// disabled or cleared) sends none.
+    ///
+    /// With `ignore_tls_errors` the client accepts any server certificate;
+    /// only requests that ask for it get such a client.
+    ///
+    /// While a CA certificate is set (`set_ca_cert_path`) the client also
+    /// trusts it, and fails with `CaCertError` if the file is unusable.
+    ///
+    /// A `protocol` other than `Auto` gets a client that speaks only that
+    /// HTTP version.
+    ///
+    /// `host_overrides` (merged over the global ones, `set_host_overrides`)
+    /// send their hosts' connections to fixed addresses; the URL, and so the
+    /// TLS server name and `Host` header, keeps the host name. A proxy
+    /// looks up the host itself, so they don't reach past one.
+    ///
+    /// Proxy clients use the current `ProxyOptions`; requests they can't get
+    /// to the proxy fail with `ProxyConnectFailed`.
+    pub fn for_route(
+        route: &SendRoute,
+        minimal_headers: bool,
+        ignore_tls_errors: bool,
+        protocol: HttpProtocol,
+        host_overrides: &[HostOverride],
+    ) -> Result<Self> {
+        let ca_cert = ca_cert_path();
+        let host_overrides = crate::host_overrides::merged(&self::host_overrides(), host_overrides);
+        if minimal_headers
+            || ignore_tls_errors
+            || ca_cert.is_some()
+            || protocol != HttpProtocol::Auto
+            || !host_overrides.is_empty()
+        {
+            let key = SpecialClient {
+                route: route.clone(),
+                minimal_headers,
+                ignore_tls_errors,
+                ca_cert,
+                protocol,
+                host_overrides,
+            };
+            return Ok(Self::special(key)?.through(route));
+        }
+        let client = match route {
+            SendRoute::System => return Ok(Self::new()),
+            SendRoute::Direct => DIRECT_CLIENT
+                .get_or_init(|| {
+                    compressing_client_builder()
+                        .no_proxy()
+                        .build()
+                        .expect("Failed to initialize HTTP client")
+                })
+                .clone(),
+            SendRoute::Proxy(url) => {
+                let mut clients = PROXY_CLIENTS
+                    .get_or_init(Default::default)
+                    .lock()
+                    .expect("proxy clients lock poisoned");
+                match clients.get(url) {
+                    Some(client) => client.clone(),
+                    None => {
+                        let client = compressing_client_builder()
+                            .proxy(proxy(url)?)
+                            .build()?;
+                        clients.insert(url.clone(), client.clone());
+                        client
+                    }
+                }
+            }
+        };
+        Ok(Self::from_client(client).through(route))
+    }
+
+    fn special(key: SpecialClient) -> Result<Self> {
+        let mut clients = SPECIAL_CLIENTS
+            .get_or_init(Default::default)
+            .lock()
+            .expect("special clients lock poisoned");
+        let decode_bodies = !key.minimal_headers;
+        if let Some(client) = clients.get(&key) {
+            return Ok(Self { decode_bodies, ..Self::from_client(client.clone()) });
+        }
+        let mut builder = if key.minimal_headers { client_builder() } else { compressing_client_builder() };
+        if key.ignore_tls_errors {
+            builder = builder.tls_danger_accept_invalid_certs(true);
+        }
+        if let Some(path) = &key.ca_cert {
+            builder = builder.add_root_certificate(load_ca_cert(path)?);
+        }
+        builder = match key.protocol {
+            HttpProtocol::Auto => builder,
+            HttpProtocol::Http1 => builder.http1_only(),
+            HttpProtocol::Http2 => builder.http2_prior_knowledge(),
+        };
+        for host_override in &key.host_overrides {
+            builder = builder.resolve(&host_override.host, host_override.addr);
+        }
+        let builder = match &key.route {
+            SendRoute::System => builder,
+            SendRoute::Direct => builder.no_proxy(),
+            SendRoute::Proxy(url) => builder.proxy(proxy(url)?),
+        };
+        let client = builder.build().map_err(|e| match &key.ca_cert {
+            // The certificate is the only part parsed at build time.
+            Some(path) => anyhow::Error::new(CaCertError { path: path.clone(), reason: e.to_string() }),
+            None => e.into(),
+        })?;
+        clients.insert(key, client.clone());
+        Ok(Self { decode_bodies, ..Self::from_client(client) })
     }
 
     /// Spawn a request built from our own model onto the shared tokio runtime
@@ -88,85 +607,612 @@ impl HttpClient {
         body: BodyType,
     ) -> InFlightRequest {
         let client = self.client.clone();
+        let proxied = self.is_proxied(&url);
+        let (profile, follow_redirects, timeout_secs) = (self.profile, self.follow_redirects, self.timeout_secs);
+        let decode_bodies = self.decode_bodies;
+        let retries = self.retry.retries.min(MAX_RETRIES);
+        let retry = self.retry;
+        let handle = runtime().spawn(async move {
+            let mut attempt: u8 = 1;
+            loop {
+                let request = execute(
+                    client.clone(),
+                    profile,
+                    follow_redirects,
+                    decode_bodies,
+                    method.clone(),
+                    url.clone(),
+                    headers.clone(),
+                    body.clone(),
+                );
+                let result = within_timeout(timeout_secs, request)
+                    .await
+                    .map_err(|e| classify_proxy_error(e, proxied));
+                let again = attempt <= retries
+                    && match &result {
+                        Ok(response) => retry.retries_status(response.status),
+                        Err(e) => is_network_error(e),
+                    };
+                if !again {
+                    return match result {
+                        Ok(response) => Ok(HttpResponse { attempts: attempt, ..response }),
+                        Err(error) if attempt > 1 => Err(anyhow::Error::new(GaveUpAfter { attempts: attempt, error })),
+                        Err(error) => Err(error),
+                    };
+                }
+                let wait = RetryPolicy::backoff(attempt);
+                match &result {
+                    Ok(response) => log::info!("Attempt {} got {}, retrying in {:?}", attempt, response.status, wait),
+                    Err(e) => log::info!("Attempt {} failed ({}), retrying in {:?}", attempt, e, wait),
+                }
+                tokio::time::sleep(wait).await;
+                attempt += 1;
+            }
+        });
+        InFlightRequest { handle }
+    }
 
-        let runtime = RUNTIME.get_or_init(|| {
-            tokio::runtime::Builder::new_multi_thread()
-                .worker_threads(2)
-                .enable_all()
-                .build()
-                .expect("Failed to initialize tokio runtime")
-        });
-
-        let handle = runtime
-            .spawn(async move {
-                let reqwest_method = reqwest::Method::from_bytes(method.as_str().as_bytes())?;
-                let mut req = client.request(reqwest_method, &url);
-
-                let is_form = matches!(body, BodyType::FormData(_));
-                for (key, value) in &headers {
-                    // Never send a manual Content-Length — reqwest computes the correct
-                    // one from the actual body. A stale value (e.g. the predefined "0")
-                    // truncates the request body server-side (multipart boundary then
-                    // can't be found -> 400). reqwest's .header() appends, so we must
-                    // skip it here rather than rely on override.
-                    if key.eq_ignore_ascii_case("content-length") {
-                        continue;
-                    }
-                    // For multipart, let reqwest set Content-Type — it includes the
-                    // boundary. A manually-set one would lack the boundary.
-                    if is_form && key.eq_ignore_ascii_case("content-type") {
-                        continue;
+    /// Send the request and stream the response body into a new file at
+    /// `path` instead of memory, with no size limit. The `HttpResponse` comes
+    /// back with an empty body; the bytes written are the final `progress()`.
+    /// A failed or aborted download removes the partial file. Neither the
+    /// network profile nor the timeout applies — a big file takes what it
+    /// takes, and can be canceled.
+    pub fn start_download(
+        &self,
+        method: HttpMethod,
+        url: String,
+        headers: Vec<(String, String)>,
+        body: BodyType,
+        path: PathBuf,
+    ) -> InFlightDownload {
+        let client = self.client.clone();
+        let (follow_redirects, decode_bodies) = (self.follow_redirects, self.decode_bodies);
+        let proxied = self.is_proxied(&url);
+        let progress = Arc::new(AtomicU64::new(0));
+        let written = progress.clone();
+        let handle = runtime().spawn(async move {
+            let sent_at = std::time::Instant::now();
+            let mut redirects = Vec::new();
+            let (response, measured) =
+                measure(send_following(&client, None, follow_redirects, method, url, headers, body, &mut redirects)).await;
+            let response = response.map_err(|e| classify_proxy_error(e, proxied))?;
+            let mut timing = ResponseTiming {
+                wait_ms: sent_at.elapsed().as_millis() as u64,
+                dns_ms: Some(measured.dns_ms),
+                connect_ms: Some(measured.connect_ms),
+                ..Default::default()
+            };
+            let status = response.status().as_u16();
+            let version = version_label(response.version()).to_string();
+            let header_bytes = head_size(&response);
+            let (headers, raw_header_values) = read_headers(&response);
+            let encoding = if decode_bodies { Encoding::of(&headers) } else { None };
+
+            let download_start = std::time::Instant::now();
+            let mut partial = PartialFile { path: path.clone(), keep: false };
+            let file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+            let mut decoder = Decoder::new(encoding, CountingWriter { inner: file, written });
+            let mut stream = response.bytes_stream();
+            while let Some(chunk) = stream.next().await {
+                std::io::Write::write_all(&mut decoder, &chunk?)?;
+            }
+            let body_bytes = decoder.encoded_len();
+            std::io::Write::flush(&mut decoder.finish()?.inner)?;
+            partial.keep = true;
+            timing.download_ms = download_start.elapsed().as_millis() as u64;
+
+            Ok(HttpResponse {
+                status,
+                headers,
+                body: Vec::new(),
+                raw_header_values,
+                timing,
+                redirects,
+                attempts: 1,
+                truncated_after: None,
+                wire_size: WireSize { header_bytes, body_bytes },
+                version,
+            })
+        });
+        InFlightDownload { request: InFlightRequest { handle }, progress }
+    }
+
+    /// Send the same request `count` times, at most `concurrency` at once, on
+    /// the shared client (so pooled connections are reused between attempts).
+    /// Aborting the returned handle drops every attempt still in flight.
+    pub fn start_repeat(
+        &self,
+        method: HttpMethod,
+        url: String,
+        headers: Vec<(String, String)>,
+        body: BodyType,
+        count: usize,
+        concurrency: usize,
+    ) -> InFlightRepeat {
+        let client = self.client.clone();
+        let profile = self.profile;
+        let timeout_secs = self.timeout_secs;
+        let (follow_redirects, decode_bodies) = (self.follow_redirects, self.decode_bodies);
+        let proxied = self.is_proxied(&url);
+        let attempts = Arc::new(Mutex::new(Vec::with_capacity(count)));
+        let sink = attempts.clone();
+
+        let handle = runtime().spawn(async move {
+            futures::stream::iter(0..count)
+                .map(|index| {
+                    let request = within_timeout(
+                        timeout_secs,
+                        execute(
+                            client.clone(),
+                            profile,
+                            follow_redirects,
+                            decode_bodies,
+                            method.clone(),
+                            url.clone(),
+                            headers.clone(),
+                            body.clone(),
+                        ),
+                    );
+                    async move {
+                        let start = std::time::Instant::now();
+                        let result = request.await.map_err(|e| classify_proxy_error(e, proxied));
+                        let duration_ms = start.elapsed().as_millis() as u64;
+                        match result {
+                            Ok(response) => Attempt {
+                                index,
+                                status: Some(response.status),
+                                duration_ms,
+                                error: None,
+                            },
+                            Err(e) => Attempt {
+                                index,
+                                status: None,
+                                duration_ms,
+                                error: Some(e.to_string()),
+                            },
+                        }
                     }
-                    req = req.header(key.as_str(), value.as_str());
+                })
+                .buffer_unordered(concurrency.max(1))
+                .for_each(|attempt| {
+                    sink.lock().expect("attempts lock poisoned").push(attempt);
+                    futures::future::ready(())
+                })
+                .await;
+        });
+
+        InFlightRepeat { handle, attempts }
+    }
+}
+
+/// A download running on the tokio runtime (`HttpClient::start_download`).
+pub struct InFlightDownload {
+    request: InFlightRequest,
+    progress: Arc<AtomicU64>,
+}
+
+impl InFlightDownload {
+    pub fn abort_handle(&self) -> tokio::task::AbortHandle {
+        self.request.abort_handle()
+    }
+
+    /// Bytes written to the file so far.
+    pub fn progress(&self) -> Arc<AtomicU64> {
+        self.progress.clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.request.handle.is_finished()
+    }
+
+    pub async fn wait(self) -> Result<HttpResponse> {
+        self.request.wait().await
+    }
+}
+
+/// Removes a download's file when dropped, unless `keep` was set: the
+/// error paths and an aborted task both drop it without.
+struct PartialFile {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// A download's file, adding the decoded bytes written to its progress.
+struct CountingWriter<W> {
+    inner: W,
+    written: Arc<AtomicU64>,
+}
+
+impl<W: std::io::Write> std::io::Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A running "Send ×N" batch. Finished attempts are readable at any time, so
+/// a canceled batch still reports what it got through.
+pub struct InFlightRepeat {
+    handle: tokio::task::JoinHandle<()>,
+    attempts: Arc<Mutex<Vec<Attempt>>>,
+}
+
+impl InFlightRepeat {
+    pub fn abort_handle(&self) -> tokio::task::AbortHandle {
+        self.handle.abort_handle()
+    }
+
+    /// Shared view of the finished attempts, in completion order.
+    pub fn attempts(&self) -> Arc<Mutex<Vec<Attempt>>> {
+        self.attempts.clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+/// Proxy URL schemes reqwest can use. `socks5h` resolves host names at the
+/// proxy, `socks5` locally.
+pub const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+/// Why `url` can't be saved as the configured proxy, or `None` if it can: an
+/// absolute URL with a host, one of `PROXY_SCHEMES`, that reqwest accepts.
+pub fn proxy_url_error(url: &str) -> Option<String> {
+    let absolute = "Enter an absolute proxy URL, e.g. http://127.0.0.1:8888 or socks5h://127.0.0.1:1080.";
+    let Ok(parsed) = url::Url::parse(url) else {
+        return Some(absolute.to_string());
+    };
+    if !PROXY_SCHEMES.contains(&parsed.scheme()) {
+        return Some(format!(
+            "Unsupported proxy scheme \"{}\"; use {}.",
+            parsed.scheme(),
+            PROXY_SCHEMES.join(", ")
+        ));
+    }
+    if !parsed.has_host() || reqwest::Proxy::all(url).is_err() {
+        return Some(absolute.to_string());
+    }
+    None
+}
+
+pub fn is_usable_proxy_url(url: &str) -> bool {
+    proxy_url_error(url).is_none()
+}
+
+/// The runtime every send runs on; `ws_client` shares it.
+pub(crate) fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()
+            .expect("Failed to initialize tokio runtime")
+    })
+}
+
+/// The base of every client: redirects are followed by `send_following`,
+/// lookups and connections are timed (see `connect_timing`), and bodies
+/// arrive as sent, for `content_encoding` to decode.
+fn client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .no_gzip()
+        .no_brotli()
+        .no_deflate()
+        .dns_resolver(TimedResolver)
+        .connector_layer(TimedConnectLayer)
+}
+
+/// `client_builder` asking for compressed bodies, the way reqwest's own
+/// decompression would, on requests without an `Accept-Encoding`.
+fn compressing_client_builder() -> reqwest::ClientBuilder {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(reqwest::header::ACCEPT_ENCODING, reqwest::header::HeaderValue::from_static(ACCEPT_ENCODING));
+    client_builder().default_headers(headers)
+}
+
+/// Run `request`, giving up with `RequestTimedOut` after `secs` (0 = never).
+async fn within_timeout(
+    secs: u32,
+    request: impl std::future::Future<Output = Result<HttpResponse>>,
+) -> Result<HttpResponse> {
+    if secs == 0 {
+        return request.await;
+    }
+    tokio::time::timeout(std::time::Duration::from_secs(secs as u64), request)
+        .await
+        .map_err(|_| anyhow::Error::new(RequestTimedOut { secs }))?
+}
+
+/// Build and perform one request, reading the whole body. Runs on the tokio
+/// runtime (reqwest's body stream requires its reactor).
+///
+/// With `follow_redirects`, 3xx answers carrying a `Location` are followed
+/// here, up to `MAX_REDIRECTS`, the way reqwest's default policy would:
+/// 301/302 turn a POST into a GET and 303 turns anything but HEAD into one
+/// (both dropping the body), 307/308 resend as-is, and credentials are not
+/// carried to another origin.
+#[allow(clippy::too_many_arguments)]
+async fn execute(
+    client: reqwest::Client,
+    profile: NetworkProfile,
+    follow_redirects: bool,
+    decode_bodies: bool,
+    method: HttpMethod,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: BodyType,
+) -> Result<HttpResponse> {
+    let limits = profile.limits();
+    let mut timing = ResponseTiming::default();
+    if let Some(limits) = limits
+        && limits.latency_ms > 0
+    {
+        tokio::time::sleep(std::time::Duration::from_millis(limits.latency_ms as u64)).await;
+        timing.latency_ms = limits.latency_ms as u64;
+    }
+
+    let sent_at = std::time::Instant::now();
+    let mut redirects = Vec::new();
+    let (response, measured) =
+        measure(send_following(&client, limits, follow_redirects, method, url, headers, body, &mut redirects)).await;
+    let response = response?;
+    timing.wait_ms = sent_at.elapsed().as_millis() as u64;
+    timing.dns_ms = Some(measured.dns_ms);
+    timing.connect_ms = Some(measured.connect_ms);
+    let status = response.status().as_u16();
+    let version = version_label(response.version()).to_string();
+    let header_bytes = head_size(&response);
+    let (headers, raw_header_values) = read_headers(&response);
+    let encoding = if decode_bodies { Encoding::of(&headers) } else { None };
+    let download_start = std::time::Instant::now();
+    let read = crate::throttle::read_body(
+        response,
+        limits.map_or(0, |l| l.down_kbps),
+        body_limit(),
+        encoding,
+    )
+    .await?;
+    timing.download_ms = download_start.elapsed().as_millis() as u64;
+
+    Ok(HttpResponse {
+        status,
+        headers,
+        body: read.body,
+        raw_header_values,
+        timing,
+        redirects,
+        attempts: 1,
+        truncated_after: read.truncated_after,
+        wire_size: WireSize { header_bytes, body_bytes: read.transferred },
+        version,
+    })
+}
+
+/// How a response's HTTP version is shown: "HTTP/1.1", "HTTP/2", ….
+fn version_label(version: reqwest::Version) -> &'static str {
+    match version {
+        reqwest::Version::HTTP_09 => "HTTP/0.9",
+        reqwest::Version::HTTP_10 => "HTTP/1.0",
+        reqwest::Version::HTTP_11 => "HTTP/1.1",
+        reqwest::Version::HTTP_2 => "HTTP/2",
+        reqwest::Version::HTTP_3 => "HTTP/3",
+        _ => "HTTP",
+    }
+}
+
+/// The status line and headers of `response` as HTTP/1.1 text. Over HTTP/2
+/// they went compressed, so this is what they would have been.
+fn head_size(response: &reqwest::Response) -> u64 {
+    let status = response.status();
+    let status_line = format!("HTTP/1.1 {} {}\r\n", status.as_str(), status.canonical_reason().unwrap_or(""));
+    let headers: usize = response.headers().iter().map(|(k, v)| k.as_str().len() + 2 + v.len() + 2).sum();
+    (status_line.len() + headers + 2) as u64
+}
+
+/// The response headers as strings, plus the raw bytes of the values that
+/// were not visible ASCII.
+fn read_headers(response: &reqwest::Response) -> (Vec<(String, String)>, Vec<RawHeaderValue>) {
+    let mut raw_header_values = Vec::new();
+    let headers = response
+        .headers()
+        .iter()
+        .enumerate()
+        .map(|(index, (k, v))| {
+            let value = match v.to_str() {
+                Ok(s) => s.to_string(),
+                Err(_) => {
+                    raw_header_values.push(RawHeaderValue { index, bytes: v.as_bytes().to_vec() });
+                    decode_header_value(v.as_bytes())
                 }
+            };
+            (k.to_string(), value)
+        })
+        .collect::<Vec<_>>();
+    (headers, raw_header_values)
+}
+
+/// Send the request and, with `follow_redirects`, each redirect after it
+/// (recorded in `redirects`); the final response comes back unread. Each hop
+/// carries the jar's cookies and feeds it the ones it gets back, so a login
+/// that redirects lands already signed in.
+#[allow(clippy::too_many_arguments)]
+async fn send_following(
+    client: &reqwest::Client,
+    limits: Option<NetworkLimits>,
+    follow_redirects: bool,
+    mut method: HttpMethod,
+    mut url: String,
+    mut headers: Vec<(String, String)>,
+    mut body: BodyType,
+    redirects: &mut Vec<(String, u16)>,
+) -> Result<reqwest::Response> {
+    loop {
+        let hop_headers = with_jar_cookies(&url, &headers);
+        let response = build_request(client, limits, &method, &url, &hop_headers, body.clone()).await?.send().await?;
+        keep_jar_cookies(&response);
+        let status = response.status().as_u16();
+        let Some(next) = follow_redirects.then(|| redirect_target(&response)).flatten() else {
+            return Ok(response);
+        };
+        if redirects.len() == MAX_REDIRECTS {
+            anyhow::bail!("Too many redirects (stopped after {MAX_REDIRECTS})");
+        }
+        redirects.push((url, status));
+
+        let to_get = match status {
+            301 | 302 => method == HttpMethod::POST,
+            303 => method != HttpMethod::HEAD,
+            _ => false,
+        };
+        if to_get {
+            method = HttpMethod::GET;
+            body = BodyType::None;
+            headers.retain(|(k, _)| !is_body_header(k));
+        }
+        if !same_origin(response.url(), &next) {
+            headers.retain(|(k, _)| !is_credential_header(k));
+        }
+        url = next.to_string();
+    }
+}
+
+/// One hop of `execute`: the reqwest request for our model, body attached
+/// (paced to the profile's upload rate when there is one).
+async fn build_request(
+    client: &reqwest::Client,
+    limits: Option<NetworkLimits>,
+    method: &HttpMethod,
+    url: &str,
+    headers: &[(String, String)],
+    body: BodyType,
+) -> Result<reqwest::RequestBuilder> {
+    let reqwest_method = reqwest::Method::from_bytes(method.as_str().as_bytes())?;
+    let mut req = client.request(reqwest_method, url);
+
+    let is_form = matches!(body, BodyType::FormData(_));
+    // `Transfer-Encoding: chunked` (the editor's chunked option, or a header
+    // the user added) streams a raw body so no length is known up front.
+    // Multipart bodies keep reqwest's Content-Length: their stream is internal.
+    let chunked = !is_form && crate::types::is_chunked(headers);
+    for (key, value) in headers {
+        // Never send a manual Content-Length, Connection or Accept-Encoding:
+        // reqwest and hyper compute them from the actual body and connection,
+        // and the decoder only reads the codings the client asks for. A stale
+        // value (e.g. the predefined "0") truncates the request body
+        // server-side (multipart boundary then can't be found -> 400).
+        // reqwest's .header() appends, so we must skip them here rather than
+        // rely on override. History rows from before they were filtered at
+        // send time still carry them.
+        if crate::types::is_transport_header(key) {
+            continue;
+        }
+        // For multipart, let reqwest set Content-Type — it includes the
+        // boundary. A manually-set one would lack the boundary.
+        if is_form && key.eq_ignore_ascii_case("content-type") {
+            continue;
+        }
+        // Appends: repeated rows of one name go out as repeated lines.
+        req = req.header(key.as_str(), value.as_str());
+    }
 
-                match body {
-                    BodyType::None => {}
-                    BodyType::Raw { content, .. } => {
-                        req = req.body(content.into_bytes());
+    match body {
+        BodyType::None => {}
+        BodyType::Raw { content, .. } => match limits {
+            Some(limits) if limits.up_kbps > 0 && !content.is_empty() => {
+                // A streamed body would go out chunked; keep the length the
+                // unthrottled request would have had, unless chunked is asked for.
+                if !chunked {
+                    req = req.header(reqwest::header::CONTENT_LENGTH, content.len());
+                }
+                req = req.body(crate::throttle::paced_body(content.into_bytes(), limits.up_kbps));
+            }
+            _ if chunked => req = req.body(chunked_body(content.into_bytes())),
+            _ => req = req.body(content.into_bytes()),
+        },
+        BodyType::FormData(rows) => {
+            let mut form = reqwest::multipart::Form::new();
+            for row in rows {
+                if !row.enabled || row.key.is_empty() {
+                    continue;
+                }
+                match row.value {
+                    FormDataValue::Text(text) => {
+                        form = form.text(row.key, text);
                     }
-                    BodyType::FormData(rows) => {
-                        let mut form = reqwest::multipart::Form::new();
-                        for row in rows {
-                            if !row.enabled || row.key.is_empty() {
-                                continue;
-                            }
-                            match row.value {
-                                FormDataValue::Text(text) => {
-                                    form = form.text(row.key, text);
-                                }
-                                FormDataValue::File { path } => {
-                                    if path.is_empty() {
-                                        continue;
-                                    }
-                                    // Reads the file and guesses MIME from its extension.
-                                    form = form.file(row.key, &path).await.map_err(|e| {
-                                        anyhow::anyhow!("Failed to read file '{}': {}", path, e)
-                                    })?;
-                                }
-                            }
+                    FormDataValue::File { path } => {
+                        if path.is_empty() {
+                            continue;
                         }
-                        req = req.multipart(form);
+                        // Reads the file and guesses MIME from its extension.
+                        form = form.file(row.key, &path).await.map_err(|e| {
+                            anyhow::anyhow!("Failed to read file '{}': {}", path, e)
+                        })?;
                     }
                 }
+            }
+            req = req.multipart(form);
+        }
+    }
 
-                let response = req.send().await?;
-                let status = response.status().as_u16();
-                let headers = response
-                    .headers()
-                    .iter()
-                    .filter_map(|(k, v)| v.to_str().ok().map(|s| (k.to_string(), s.to_string())))
-                    .collect::<Vec<_>>();
-                let body = response.bytes().await?.to_vec();
-
-                Ok::<HttpResponse, anyhow::Error>(HttpResponse {
-                    status,
-                    headers,
-                    body,
-                })
-            });
+    Ok(req)
+}
 
-        InFlightRequest { handle }
+/// Where a followable redirect points: a 301/302/303/307/308 with a
+/// `Location` that resolves to an http(s) URL.
+fn redirect_target(response: &reqwest::Response) -> Option<url::Url> {
+    if !matches!(response.status().as_u16(), 301 | 302 | 303 | 307 | 308) {
+        return None;
+    }
+    let location = response.headers().get(reqwest::header::LOCATION)?.to_str().ok()?;
+    let next = response.url().join(location).ok()?;
+    matches!(next.scheme(), "http" | "https").then_some(next)
+}
+
+fn same_origin(a: &url::Url, b: &url::Url) -> bool {
+    a.scheme() == b.scheme() && a.host_str() == b.host_str() && a.port_or_known_default() == b.port_or_known_default()
+}
+
+/// Headers describing the body, dropped with it when a redirect becomes a GET.
+fn is_body_header(name: &str) -> bool {
+    ["content-type", "content-length", "transfer-encoding"].iter().any(|h| name.eq_ignore_ascii_case(h))
+}
+
+/// Headers not carried to another origin, as reqwest does.
+fn is_credential_header(name: &str) -> bool {
+    ["authorization", "proxy-authorization", "cookie", "www-authenticate"].iter().any(|h| name.eq_ignore_ascii_case(h))
+}
+
+/// A raw body as a stream of `CHUNK_SIZE` pieces: with no length known, hyper
+/// frames it with chunked transfer encoding.
+fn chunked_body(bytes: Vec<u8>) -> reqwest::Body {
+    const CHUNK_SIZE: usize = 16 * 1024;
+    let chunks: Vec<Result<Vec<u8>, std::io::Error>> = bytes.chunks(CHUNK_SIZE).map(|c| Ok(c.to_vec())).collect();
+    reqwest::Body::wrap_stream(futures::stream::iter(chunks))
+}
+
+/// Decode a header value that is not plain visible ASCII: as UTF-8 when it
+/// is valid UTF-8 (what most servers mean today), otherwise as Latin-1, the
+/// historical header charset — which maps every byte, so nothing is lost.
+pub fn decode_header_value(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
     }
 }
 
@@ -204,6 +1250,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hanging_request_fails_with_request_timed_out() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        let client = HttpClient::new().with_timeout(1);
+        let inflight = client.start_send(HttpMethod::GET, url, vec![], BodyType::None);
+
+        let err = block_on(inflight.wait()).expect_err("a silent server must time out");
+        assert!(
+            err.downcast_ref::<RequestTimedOut>().is_some_and(|e| e.secs == 1),
+            "expected RequestTimedOut, got: {err:#}"
+        );
+        assert_eq!(err.to_string(), "Request timed out after 1s");
+    }
+
     #[test]
     fn start_send_completes_normally_without_abort() {
         let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
@@ -227,5 +1289,666 @@ mod tests {
         let response = block_on(inflight.wait()).expect("request should succeed");
         assert_eq!(response.status, 200);
         assert_eq!(response.body, b"hi");
+        assert_eq!(response.version, "HTTP/1.1");
+    }
+
+    /// The first bytes a client with `protocol` sends to a plain-http server.
+    fn opening_bytes(protocol: HttpProtocol) -> Vec<u8> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 24];
+            stream.read_exact(&mut buf).unwrap();
+            buf.to_vec()
+        });
+        let client = HttpClient::for_route(&SendRoute::Direct, false, false, protocol, &[]).unwrap().with_timeout(1);
+        // The server hangs up without answering; only what it read matters.
+        let _ = block_on(client.start_send(HttpMethod::GET, url, vec![], BodyType::None).wait());
+        server.join().unwrap()
+    }
+
+    #[test]
+    fn the_protocol_preference_picks_the_http_version() {
+        assert_eq!(opening_bytes(HttpProtocol::Http2), b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n");
+        assert!(opening_bytes(HttpProtocol::Http1).starts_with(b"GET / HTTP/1.1\r\n"));
+        assert!(opening_bytes(HttpProtocol::Auto).starts_with(b"GET / HTTP/1.1\r\n"));
+    }
+
+    #[test]
+    fn a_fresh_connection_reports_its_dns_and_connect_times() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://localhost:{}/", listener.local_addr().unwrap().port());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            stream.write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n").unwrap();
+        });
+
+        // A client of its own, so there is no pooled connection to reuse.
+        let client = HttpClient::from_client(client_builder().build().unwrap());
+        let response = block_on(client.start_send(HttpMethod::GET, url, vec![], BodyType::None).wait())
+            .expect("request should succeed");
+        let timing = response.timing;
+        let (dns, connect) = (timing.dns_ms.expect("measured"), timing.connect_ms.expect("measured"));
+        assert!(dns + connect <= timing.wait_ms, "{timing:?}");
+    }
+
+    #[test]
+    fn back_to_back_sends_reuse_the_pooled_connection() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let accepted = Arc::new(AtomicU64::new(0));
+        let counter = accepted.clone();
+        // Keep-alive server: every connection answers requests until closed.
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                std::thread::spawn(move || {
+                    let mut buf = [0u8; 4096];
+                    while stream.read(&mut buf).is_ok_and(|n| n > 0) {
+                        if stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nhi").is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        // A client per send, as the editor gets one: they share the pool.
+        let send = || {
+            let client = HttpClient::for_route(&SendRoute::Direct, false, false, HttpProtocol::Auto, &[]).unwrap();
+            block_on(client.start_send(HttpMethod::GET, url.clone(), vec![], BodyType::None).wait())
+                .expect("request should succeed")
+        };
+        let first = send();
+        let second = send();
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+        assert_eq!((second.status, second.body.as_slice()), (200, &b"hi"[..]));
+        // The handshake is paid once: the second send skips DNS and connect.
+        assert!(first.timing.connect_ms.is_some(), "{:?}", first.timing);
+        assert_eq!((second.timing.dns_ms, second.timing.connect_ms), (Some(0), Some(0)), "{:?}", second.timing);
+    }
+
+    #[test]
+    fn gzip_bodies_are_decoded_and_their_wire_size_kept() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &[b'a'; 4000]).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            gzipped.len()
+        );
+        let (sent, sent_head) = (gzipped.clone(), head.clone());
+        std::thread::spawn(move || {
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf);
+                stream.write_all(sent_head.as_bytes()).unwrap();
+                stream.write_all(&sent).unwrap();
+            }
+        });
+
+        let send = |client: HttpClient| {
+            block_on(client.start_send(HttpMethod::GET, url.clone(), vec![], BodyType::None).wait()).unwrap()
+        };
+        let response = send(HttpClient::for_route(&SendRoute::Direct, false, false, HttpProtocol::Auto, &[]).unwrap());
+        assert_eq!(response.body, [b'a'; 4000]);
+        assert_eq!(response.wire_size.body_bytes, gzipped.len() as u64);
+        assert_eq!(response.wire_size.header_bytes, head.len() as u64);
+
+        // Minimal headers: shown as the server sent it.
+        let response = send(HttpClient::for_route(&SendRoute::Direct, true, false, HttpProtocol::Auto, &[]).unwrap());
+        assert_eq!(response.body, gzipped);
+    }
+
+    #[test]
+    fn non_ascii_header_values_are_kept_and_flagged() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nX-Name: caf\xe9\r\nX-Plain: ok\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .unwrap();
+        });
+
+        let client = HttpClient::new();
+        let inflight = client.start_send(HttpMethod::GET, url, vec![], BodyType::None);
+        let response = block_on(inflight.wait()).expect("request should succeed");
+
+        let index = response.headers.iter().position(|(k, _)| k == "x-name").expect("header kept");
+        assert_eq!(response.headers[index].1, "café");
+        assert_eq!(response.raw_header_values, vec![RawHeaderValue { index, bytes: b"caf\xe9".to_vec() }]);
+        assert!(response.headers.iter().any(|(k, v)| k == "x-plain" && v == "ok"));
+    }
+
+    #[test]
+    fn header_values_decode_as_utf8_then_latin1() {
+        assert_eq!(decode_header_value("naïve".as_bytes()), "naïve");
+        assert_eq!(decode_header_value(b"caf\xe9"), "café");
+        assert_eq!(decode_header_value(&[0xff, 0x80]), "\u{ff}\u{80}");
+        let raw = RawHeaderValue { index: 0, bytes: b"a\\b\xe9\x01".to_vec() };
+        assert_eq!(raw.escaped(), "a\\\\b\\xe9\\x01");
+    }
+
+    #[test]
+    fn start_repeat_sends_every_attempt_on_the_shared_client() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        // Answers exactly three requests, closing each connection.
+        std::thread::spawn(move || {
+            for _ in 0..3 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf);
+                stream
+                    .write_all(
+                        b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    )
+                    .unwrap();
+            }
+        });
+
+        let client = HttpClient::new();
+        let inflight = client.start_repeat(HttpMethod::GET, url, vec![], BodyType::None, 3, 2);
+        let attempts = inflight.attempts();
+        while !inflight.is_finished() {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        let mut statuses: Vec<(usize, Option<u16>)> = attempts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|a| (a.index, a.status))
+            .collect();
+        statuses.sort();
+        assert_eq!(statuses, vec![(0, Some(204)), (1, Some(204)), (2, Some(204))]);
+    }
+
+    /// Send a bare GET through `client` and return the request head the
+    /// server received, lowercased.
+    fn received_head(client: HttpClient) -> String {
+        received_head_with(client, vec![])
+    }
+
+    fn received_head_with(client: HttpClient, headers: Vec<(String, String)>) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let head = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase()
+        });
+        let inflight = client.start_send(HttpMethod::GET, url, headers, BodyType::None);
+        block_on(inflight.wait()).expect("request should succeed");
+        head.join().unwrap()
+    }
+
+    #[test]
+    fn a_host_override_connects_elsewhere_but_keeps_the_host_name() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let overrides = vec![HostOverride { host: "api.poopman.test".into(), addr: listener.local_addr().unwrap() }];
+        let head = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase()
+        });
+        // The name doesn't resolve anywhere; the override's port stands in
+        // for the URL's missing one.
+        let url = "http://api.poopman.test/health".to_string();
+        let client = HttpClient::for_route(&SendRoute::Direct, false, false, HttpProtocol::Auto, &overrides).unwrap();
+        let response = block_on(client.start_send(HttpMethod::GET, url.clone(), vec![], BodyType::None).wait()).unwrap();
+        assert_eq!(response.status, 204);
+        let head = head.join().unwrap();
+        assert!(head.starts_with("get /health http/1.1"), "{head}");
+        assert!(head.contains("host: api.poopman.test\r\n"), "{head}");
+        assert_eq!(host_override_for(&url, &overrides), Some(overrides[0].clone()));
+        assert_eq!(host_override_for("http://other.poopman.test/", &overrides), None);
+    }
+
+    #[test]
+    fn only_the_rows_sent_go_out() {
+        // Cache-Control turned off in the editor: the client adds none itself.
+        let headers = vec![("Accept".to_string(), "*/*".to_string())];
+        let head = received_head_with(HttpClient::for_route(&SendRoute::Direct, false, false, HttpProtocol::Auto, &[]).unwrap(), headers);
+        assert!(head.contains("accept: */*"), "{head}");
+        assert!(!head.contains("cache-control:"), "{head}");
+        assert!(!head.contains("connection: keep-alive"), "{head}");
+    }
+
+    #[test]
+    fn transport_headers_from_rows_are_not_sent() {
+        let headers = [("Content-Length", "0"), ("Connection", "close"), ("Accept-Encoding", "zstd"), ("X-Kept", "1")]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .to_vec();
+        let head = received_head_with(HttpClient::for_route(&SendRoute::Direct, false, false, HttpProtocol::Auto, &[]).unwrap(), headers);
+        assert!(head.contains("x-kept: 1"), "{head}");
+        assert!(head.contains(&format!("accept-encoding: {ACCEPT_ENCODING}")), "{head}");
+        for absent in ["content-length: 0", "connection: close", "zstd"] {
+            assert!(!head.contains(absent), "{absent} in {head}");
+        }
+    }
+
+    #[test]
+    fn repeated_headers_go_out_as_repeated_lines() {
+        let headers = [("Accept", "text/html"), ("X-Tag", "a"), ("Accept", "application/json"), ("X-Tag", "b")]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .to_vec();
+        let head = received_head_with(HttpClient::for_route(&SendRoute::Direct, true, false, HttpProtocol::Auto, &[]).unwrap(), headers);
+        let lines: Vec<&str> = head.lines().collect();
+        for line in ["accept: text/html", "accept: application/json", "x-tag: a", "x-tag: b"] {
+            assert!(lines.contains(&line), "{line} missing from {head}");
+        }
+    }
+
+    #[test]
+    fn minimal_headers_client_adds_no_encoding_or_user_agent() {
+        let default_head = received_head(HttpClient::for_route(&SendRoute::Direct, false, false, HttpProtocol::Auto, &[]).unwrap());
+        assert!(default_head.contains("accept-encoding:"), "{default_head}");
+        assert!(!default_head.contains("user-agent:"), "{default_head}");
+
+        let minimal_head = received_head(HttpClient::for_route(&SendRoute::Direct, true, false, HttpProtocol::Auto, &[]).unwrap());
+        assert!(!minimal_head.contains("accept-encoding:"), "{minimal_head}");
+        assert!(!minimal_head.contains("user-agent:"), "{minimal_head}");
+    }
+
+    #[test]
+    fn network_profile_delays_and_paces_both_directions() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let head = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut data = Vec::new();
+            let mut buf = [0u8; 4096];
+            // The paced body arrives in pieces; answer once all 2000 bytes are in.
+            while !data.windows(4).any(|w| w == b"\r\n\r\n") || data.len() < 2000 {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                data.extend_from_slice(&buf[..n]);
+            }
+            let mut response = b"HTTP/1.1 200 OK\r\nContent-Length: 2000\r\nConnection: close\r\n\r\n".to_vec();
+            response.extend_from_slice(&[b'x'; 2000]);
+            stream.write_all(&response).unwrap();
+            String::from_utf8_lossy(&data).to_ascii_lowercase()
+        });
+
+        // 80 kbps = 10 000 bytes/s: 2000 bytes take 200 ms each way.
+        let client = HttpClient::new().with_network_profile(NetworkProfile::Custom { kbps: 80, latency_ms: 50 });
+        let started = std::time::Instant::now();
+        let inflight = client.start_send(
+            HttpMethod::POST,
+            url,
+            vec![],
+            BodyType::Raw { content: "a".repeat(2000), subtype: crate::types::RawSubtype::Text },
+        );
+        let response = block_on(inflight.wait()).expect("request should succeed");
+
+        assert_eq!(response.body.len(), 2000);
+        assert_eq!(response.timing.latency_ms, 50);
+        assert!(response.timing.wait_ms >= 150, "{:?}", response.timing);
+        assert!(response.timing.download_ms >= 150, "{:?}", response.timing);
+        assert!(started.elapsed() >= std::time::Duration::from_millis(400));
+        let head = head.join().unwrap();
+        assert!(head.contains("content-length: 2000"), "{head}");
+        assert!(!head.contains("transfer-encoding"), "{head}");
+    }
+
+    /// Send a raw POST with `headers` and return the raw request (head and
+    /// body bytes) the server received, lowercased.
+    fn received_post(headers: Vec<(String, String)>, body: &str) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let received = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut data = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Either framing ends the same way for this body: after its text,
+            // or after the zero-length last chunk.
+            while !(data.ends_with(b"0\r\n\r\n") || String::from_utf8_lossy(&data).ends_with("hello world")) {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                data.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            String::from_utf8_lossy(&data).to_ascii_lowercase()
+        });
+        let inflight = HttpClient::new().start_send(
+            HttpMethod::POST,
+            url,
+            headers,
+            BodyType::Raw { content: body.to_string(), subtype: crate::types::RawSubtype::Text },
+        );
+        block_on(inflight.wait()).expect("request should succeed");
+        received.join().unwrap()
+    }
+
+    /// A server for `connections` requests: `/start` answers 302 to `/next`,
+    /// anything else 200 "done". Returns the base URL and the request lines
+    /// it saw.
+    fn redirect_server(connections: usize) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let seen = std::thread::spawn(move || {
+            let mut lines = Vec::new();
+            for _ in 0..connections {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut data = Vec::new();
+                let mut buf = [0u8; 4096];
+                while !data.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    data.extend_from_slice(&buf[..n]);
+                }
+                let line = String::from_utf8_lossy(&data).lines().next().unwrap_or_default().to_string();
+                let reply: &[u8] = if line.contains(" /start ") {
+                    b"HTTP/1.1 302 Found\r\nLocation: /next\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                } else {
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\ndone"
+                };
+                stream.write_all(reply).unwrap();
+                lines.push(line);
+            }
+            lines
+        });
+        (base, seen)
+    }
+
+    #[test]
+    fn followed_redirects_are_reported_hop_by_hop() {
+        let (base, seen) = redirect_server(2);
+        let start = format!("{base}/start");
+        let inflight = HttpClient::new().start_send(HttpMethod::POST, start.clone(), vec![], BodyType::None);
+        let response = block_on(inflight.wait()).expect("request should succeed");
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"done");
+        assert_eq!(response.redirects, vec![(start, 302)]);
+        // 302 turns the POST into a GET, as reqwest's own policy does.
+        assert_eq!(seen.join().unwrap(), vec!["POST /start HTTP/1.1", "GET /next HTTP/1.1"]);
+    }
+
+    #[test]
+    fn unfollowed_redirect_is_the_response() {
+        let (base, seen) = redirect_server(1);
+        let client = HttpClient::new().with_follow_redirects(false);
+        let inflight = client.start_send(HttpMethod::GET, format!("{base}/start"), vec![], BodyType::None);
+        let response = block_on(inflight.wait()).expect("request should succeed");
+
+        assert_eq!(response.status, 302);
+        assert!(response.redirects.is_empty());
+        assert!(response.headers.iter().any(|(k, v)| k == "location" && v == "/next"));
+        assert_eq!(seen.join().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn retries_5xx_with_backoff_and_counts_attempts() {
+        // Two 502s, then a 200.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/flaky", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            for reply in [&b"HTTP/1.1 502 Bad Gateway\r\n"[..], b"HTTP/1.1 502 Bad Gateway\r\n", b"HTTP/1.1 200 OK\r\n"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).unwrap();
+                stream.write_all(reply).unwrap();
+                stream.write_all(b"Content-Length: 0\r\nConnection: close\r\n\r\n").unwrap();
+            }
+        });
+
+        let client = HttpClient::new().with_retry(RetryPolicy { retries: 3, on_5xx: true });
+        let start = std::time::Instant::now();
+        let response = block_on(client.start_send(HttpMethod::GET, url, vec![], BodyType::None).wait()).unwrap();
+        assert_eq!((response.status, response.attempts), (200, 3));
+        // Waited 500 ms, then 1 s.
+        assert!(start.elapsed() >= RetryPolicy::backoff(1) + RetryPolicy::backoff(2));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn network_errors_are_retried_then_reported_with_the_attempt_count() {
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let client = HttpClient::new().with_retry(RetryPolicy { retries: 1, on_5xx: false });
+        let inflight = client.start_send(HttpMethod::GET, format!("http://{closed}/"), vec![], BodyType::None);
+        let err = block_on(inflight.wait()).expect_err("nothing listens there");
+        let gave_up = err.downcast_ref::<GaveUpAfter>().expect("retried");
+        assert_eq!(gave_up.attempts, 2);
+        assert!(gave_up.error.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_connect()));
+    }
+
+    #[test]
+    fn bodies_past_the_limit_are_cut_and_downloads_are_not() {
+        // Serves a 5000-byte body to each of two connections.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/big", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).unwrap();
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 5000\r\nConnection: close\r\n\r\n");
+                let _ = stream.write_all(&[b'x'; 5000]);
+            }
+        });
+
+        let client = HttpClient::new();
+        let response = runtime().block_on(async {
+            let response = client.client.get(&url).send().await.unwrap();
+            crate::throttle::read_body(response, 0, 1000, None).await.unwrap()
+        });
+        assert_eq!(response.body.len(), 1000);
+        assert!(response.truncated_after.is_some_and(|n| n > 1000), "{response:?}");
+
+        let path = std::env::temp_dir().join(format!("poopman-download-{}.bin", std::process::id()));
+        let download = client.start_download(HttpMethod::GET, url, vec![], BodyType::None, path.clone());
+        let written = download.progress();
+        let response = block_on(download.wait()).unwrap();
+        assert_eq!((response.status, response.body.len()), (200, 0));
+        assert_eq!(written.load(Ordering::Relaxed), 5000);
+        assert_eq!(std::fs::read(&path).unwrap().len(), 5000);
+        std::fs::remove_file(&path).unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn a_download_cut_off_mid_body_leaves_no_file() {
+        // Promises 5000 bytes, sends 100, hangs up.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/export.csv", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).unwrap();
+            stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 5000\r\n\r\n").unwrap();
+            stream.write_all(&[b'x'; 100]).unwrap();
+        });
+
+        let path = std::env::temp_dir().join(format!("poopman-partial-{}.csv", std::process::id()));
+        let download = HttpClient::new().start_download(HttpMethod::GET, url, vec![], BodyType::None, path.clone());
+        server.join().unwrap();
+        assert!(block_on(download.wait()).is_err());
+        assert!(!path.exists(), "partial file left at {path:?}");
+    }
+
+    #[test]
+    fn chunked_mode_streams_the_body_without_content_length() {
+        let editor_headers = vec![
+            ("Content-Type".to_string(), "text/plain".to_string()),
+            ("Content-Length".to_string(), "11".to_string()),
+        ];
+
+        let chunked = received_post(crate::types::transfer_headers(editor_headers.clone(), true), "hello world");
+        assert!(chunked.contains("transfer-encoding: chunked"), "{chunked}");
+        assert!(!chunked.contains("content-length"), "{chunked}");
+        assert_eq!(chunked.matches("transfer-encoding").count(), 1, "{chunked}");
+        assert!(chunked.contains("\r\n\r\nb\r\nhello world\r\n0\r\n\r\n"), "{chunked}");
+
+        let plain = received_post(crate::types::transfer_headers(editor_headers, false), "hello world");
+        assert!(plain.contains("content-length: 11"), "{plain}");
+        assert!(!plain.contains("transfer-encoding"), "{plain}");
+    }
+
+    #[test]
+    fn unusable_ca_certificates_fail_with_ca_cert_error() {
+        let dir = std::env::temp_dir().join(format!("poopman-ca-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let no_cert = dir.join("empty.pem");
+        std::fs::write(&no_cert, "just some text\n").unwrap();
+        let bad_cert = dir.join("bad.pem");
+        std::fs::write(&bad_cert, "-----BEGIN CERTIFICATE-----\nnot base64!\n-----END CERTIFICATE-----\n").unwrap();
+
+        for path in [dir.join("missing.pem"), no_cert, bad_cert] {
+            let key = SpecialClient {
+                route: SendRoute::System,
+                minimal_headers: false,
+                ignore_tls_errors: false,
+                ca_cert: Some(path.clone()),
+                protocol: HttpProtocol::Auto,
+                host_overrides: vec![],
+            };
+            let err = HttpClient::special(key).err().expect("unusable certificate accepted");
+            let ca_err = err.downcast_ref::<CaCertError>().expect("not a CaCertError");
+            assert_eq!(ca_err.path, path);
+            assert!(err.to_string().starts_with("Couldn't load CA certificate"), "{err}");
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn only_absolute_http_proxy_urls_are_usable() {
+        assert!(is_usable_proxy_url("http://127.0.0.1:8888"));
+        assert!(is_usable_proxy_url("https://user:pw@proxy.corp:3128"));
+        assert!(is_usable_proxy_url("socks5://127.0.0.1:1080"));
+        assert!(is_usable_proxy_url("socks5h://user:pw@127.0.0.1:1080"));
+        assert!(!is_usable_proxy_url("127.0.0.1:8888"));
+        assert!(!is_usable_proxy_url("not a url"));
+        let error = proxy_url_error("socks4://127.0.0.1:1080").unwrap();
+        assert!(error.starts_with("Unsupported proxy scheme \"socks4\""), "{error}");
+    }
+
+    #[test]
+    fn proxy_route_sends_through_the_configured_proxy() {
+        // The "proxy" is a plain listener: a proxied plain-HTTP request arrives
+        // with the absolute URL in its request line.
+        let proxy = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let route = SendRoute::Proxy(format!("http://{}", proxy.local_addr().unwrap()));
+        let request_line = std::thread::spawn(move || {
+            let (mut stream, _) = proxy.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).lines().next().unwrap_or_default().to_string()
+        });
+
+        let client = HttpClient::for_route(&route, false, false, HttpProtocol::Auto, &[]).expect("valid proxy URL");
+        let inflight = client.start_send(
+            HttpMethod::GET,
+            "http://origin.invalid/x".to_string(),
+            vec![],
+            BodyType::None,
+        );
+        let response = block_on(inflight.wait()).expect("proxy answers");
+        assert_eq!(response.status, 204);
+        assert_eq!(request_line.join().unwrap(), "GET http://origin.invalid/x HTTP/1.1");
+    }
+
+    #[test]
+    fn socks5h_route_tunnels_through_the_proxy() {
+        // A one-shot SOCKS5 server that answers the tunneled request itself:
+        // no-auth greeting, CONNECT (recording the target it was asked for),
+        // then a canned HTTP response.
+        let proxy = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let route = SendRoute::Proxy(format!("socks5h://{}", proxy.local_addr().unwrap()));
+        let target = std::thread::spawn(move || {
+            let (mut stream, _) = proxy.accept().unwrap();
+            let mut greeting = [0u8; 2];
+            stream.read_exact(&mut greeting).unwrap();
+            let mut methods = vec![0u8; greeting[1] as usize];
+            stream.read_exact(&mut methods).unwrap();
+            stream.write_all(&[5, 0]).unwrap();
+
+            let mut head = [0u8; 5];
+            stream.read_exact(&mut head).unwrap();
+            assert_eq!(&head[..4], &[5, 1, 0, 3], "CONNECT by domain name");
+            let mut host = vec![0u8; head[4] as usize];
+            stream.read_exact(&mut host).unwrap();
+            let mut port = [0u8; 2];
+            stream.read_exact(&mut port).unwrap();
+            stream.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).unwrap();
+
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .unwrap();
+            format!("{}:{}", String::from_utf8_lossy(&host), u16::from_be_bytes(port))
+        });
+
+        let client = HttpClient::for_route(&route, false, false, HttpProtocol::Auto, &[]).expect("valid SOCKS URL");
+        let inflight = client.start_send(HttpMethod::GET, "http://origin.invalid/x".to_string(), vec![], BodyType::None);
+        let response = block_on(inflight.wait()).expect("tunnel answers");
+        assert_eq!((response.status, response.body.as_slice()), (200, b"ok".as_slice()));
+        assert_eq!(target.join().unwrap(), "origin.invalid:80");
+    }
+
+    #[test]
+    fn unreachable_proxy_fails_as_a_proxy_connection_error() {
+        // Bind and drop to get a port nothing listens on.
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let route = SendRoute::Proxy(format!("http://{closed}"));
+        let client = HttpClient::for_route(&route, false, false, HttpProtocol::Auto, &[]).expect("valid proxy URL");
+        let inflight = client.start_send(HttpMethod::GET, "http://origin.invalid/".to_string(), vec![], BodyType::None);
+        let err = block_on(inflight.wait()).expect_err("nothing listens on the proxy port");
+        assert!(err.downcast_ref::<ProxyConnectFailed>().is_some(), "{err:#}");
+        assert!(err.to_string().starts_with("Proxy connection failed: "), "{err}");
+
+        // The same failure without a proxy is the target's.
+        let direct = HttpClient::for_route(&SendRoute::Direct, false, false, HttpProtocol::Auto, &[]).unwrap();
+        let inflight = direct.start_send(HttpMethod::GET, format!("http://{closed}/"), vec![], BodyType::None);
+        let err = block_on(inflight.wait()).expect_err("nothing listens there");
+        assert!(err.downcast_ref::<ProxyConnectFailed>().is_none(), "{err:#}");
+    }
+
+    #[test]
+    fn no_proxy_entries_cover_hosts_and_their_subdomains() {
+        let list = "localhost, .corp.local,10.0.0.5";
+        assert!(bypasses_proxy("localhost", list));
+        assert!(bypasses_proxy("api.corp.local", list));
+        assert!(bypasses_proxy("CORP.local", list));
+        assert!(bypasses_proxy("10.0.0.5", list));
+        assert!(!bypasses_proxy("notcorp.local", list));
+        assert!(!bypasses_proxy("example.com", list));
+        assert!(bypasses_proxy("example.com", "*"));
+        assert!(!bypasses_proxy("example.com", ""));
     }
 }
@@ -0,0 +1,330 @@
+//! JSONPath filter behind the box above the response body: evaluates an
+//! expression against the parsed response and returns the matches. Pure.
+//!
+//! Supported: `$` (optional), `.key`, `['key']` / `["key"]`, `[3]` and
+//! `[-1]`, `[*]` / `.*`, jq's `[]`, slices `[1:3]` / `[::2]`, unions
+//! `[0,2]` / `['a','b']`, and recursive descent `..key` / `..*` / `..[0]`.
+//! A bare leading key (`items[0]`) reads as `$.items[0]`.
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Key(String),
+    Index(i64),
+    Wildcard,
+    Slice { start: Option<i64>, end: Option<i64>, step: i64 },
+    Union(Vec<Selector>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    /// Applied to each current value.
+    Child(Selector),
+    /// Applied to each current value and everything below it.
+    Descendant(Selector),
+}
+
+/// Every value `expression` selects from `root`, in document order.
+pub fn evaluate(expression: &str, root: &Value) -> Result<Vec<Value>> {
+    let steps = parse(expression)?;
+    let mut current = vec![root];
+    for step in &steps {
+        let mut next = Vec::new();
+        for value in current {
+            match step {
+                Step::Child(selector) => select(value, selector, &mut next),
+                Step::Descendant(selector) => {
+                    let mut all = Vec::new();
+                    descendants(value, &mut all);
+                    for value in all {
+                        select(value, selector, &mut next);
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+    Ok(current.into_iter().cloned().collect())
+}
+
+/// What the body shows for `matches`: a single match on its own, several
+/// (or none) as an array.
+pub fn render(matches: Vec<Value>) -> Result<String> {
+    let value = match <[Value; 1]>::try_from(matches) {
+        Ok([single]) => single,
+        Err(matches) => Value::Array(matches),
+    };
+    crate::code_formatter::pretty_json_4(&value).map_err(anyhow::Error::msg)
+}
+
+fn descendants<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    out.push(value);
+    match value {
+        Value::Object(map) => map.values().for_each(|child| descendants(child, out)),
+        Value::Array(items) => items.iter().for_each(|child| descendants(child, out)),
+        _ => {}
+    }
+}
+
+fn select<'a>(value: &'a Value, selector: &Selector, out: &mut Vec<&'a Value>) {
+    match (selector, value) {
+        (Selector::Key(key), Value::Object(map)) => out.extend(map.get(key)),
+        (Selector::Index(index), Value::Array(items)) => {
+            let index = if *index < 0 { items.len() as i64 + index } else { *index };
+            if index >= 0 {
+                out.extend(items.get(index as usize));
+            }
+        }
+        (Selector::Wildcard, Value::Object(map)) => out.extend(map.values()),
+        (Selector::Wildcard, Value::Array(items)) => out.extend(items.iter()),
+        (Selector::Slice { start, end, step }, Value::Array(items)) => {
+            let len = items.len() as i64;
+            let clamp = |bound: i64| if bound < 0 { (len + bound).max(0) } else { bound.min(len) };
+            if *step > 0 {
+                let (from, to) = (start.map_or(0, clamp), end.map_or(len, clamp));
+                out.extend((from..to).step_by(*step as usize).map(|i| &items[i as usize]));
+            } else {
+                // Negative step walks backwards from `start` down to after `end`.
+                let from = start.map_or(len - 1, |s| clamp(s).min(len - 1));
+                let to = end.map_or(-1, clamp);
+                let mut i = from;
+                while i > to && i >= 0 {
+                    out.push(&items[i as usize]);
+                    i += step;
+                }
+            }
+        }
+        (Selector::Union(selectors), _) => {
+            for selector in selectors {
+                select(value, selector, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse(expression: &str) -> Result<Vec<Step>> {
+    let chars: Vec<char> = expression.trim().chars().collect();
+    let mut pos = 0;
+    let mut steps = Vec::new();
+    if chars.first() == Some(&'$') {
+        pos = 1;
+    } else if chars.first().is_some_and(|c| is_key_char(*c)) {
+        // `items[0]` is short for `$.items[0]`.
+        let key = read_key(&chars, &mut pos);
+        steps.push(Step::Child(Selector::Key(key)));
+    }
+    while pos < chars.len() {
+        match chars[pos] {
+            '.' if chars.get(pos + 1) == Some(&'.') => {
+                pos += 2;
+                let selector = match chars.get(pos) {
+                    Some('[') => read_bracket(&chars, &mut pos)?,
+                    _ => read_dot_member(&chars, &mut pos)?,
+                };
+                steps.push(Step::Descendant(selector));
+            }
+            '.' => {
+                pos += 1;
+                // jq writes `.[0]` / `.[]`; treat the dot as optional there.
+                let selector = match chars.get(pos) {
+                    Some('[') => read_bracket(&chars, &mut pos)?,
+                    _ => read_dot_member(&chars, &mut pos)?,
+                };
+                steps.push(Step::Child(selector));
+            }
+            '[' => steps.push(Step::Child(read_bracket(&chars, &mut pos)?)),
+            c if c.is_whitespace() => pos += 1,
+            c => bail!("unexpected '{c}' at {}", pos + 1),
+        }
+    }
+    Ok(steps)
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '$'
+}
+
+fn read_key(chars: &[char], pos: &mut usize) -> String {
+    let start = *pos;
+    while *pos < chars.len() && is_key_char(chars[*pos]) {
+        *pos += 1;
+    }
+    chars[start..*pos].iter().collect()
+}
+
+/// After a `.`: a key or `*`.
+fn read_dot_member(chars: &[char], pos: &mut usize) -> Result<Selector> {
+    if chars.get(*pos) == Some(&'*') {
+        *pos += 1;
+        return Ok(Selector::Wildcard);
+    }
+    let key = read_key(chars, pos);
+    if key.is_empty() {
+        bail!("expected a key after '.' at {}", *pos + 1);
+    }
+    Ok(Selector::Key(key))
+}
+
+/// `[...]`, with `pos` on the opening bracket.
+fn read_bracket(chars: &[char], pos: &mut usize) -> Result<Selector> {
+    let open = *pos;
+    *pos += 1;
+    let mut parts = Vec::new();
+    loop {
+        skip_spaces(chars, pos);
+        match chars.get(*pos) {
+            None => bail!("unclosed '[' at {}", open + 1),
+            Some(']') if parts.is_empty() => {
+                // jq's `[]`: every element.
+                *pos += 1;
+                return Ok(Selector::Wildcard);
+            }
+            Some('*') => {
+                *pos += 1;
+                parts.push(Selector::Wildcard);
+            }
+            Some(&quote @ ('\'' | '"')) => {
+                *pos += 1;
+                let start = *pos;
+                while chars.get(*pos).is_some_and(|c| *c != quote) {
+                    *pos += 1;
+                }
+                if *pos >= chars.len() {
+                    bail!("unclosed string at {}", start);
+                }
+                parts.push(Selector::Key(chars[start..*pos].iter().collect()));
+                *pos += 1;
+            }
+            Some(_) => parts.push(read_index_or_slice(chars, pos)?),
+        }
+        skip_spaces(chars, pos);
+        match chars.get(*pos) {
+            Some(',') => *pos += 1,
+            Some(']') => {
+                *pos += 1;
+                break;
+            }
+            Some(c) => bail!("unexpected '{c}' at {}", *pos + 1),
+            None => bail!("unclosed '[' at {}", open + 1),
+        }
+    }
+    Ok(if parts.len() == 1 { parts.remove(0) } else { Selector::Union(parts) })
+}
+
+/// `3`, `-1`, or a slice `start:end:step` with any part left out.
+fn read_index_or_slice(chars: &[char], pos: &mut usize) -> Result<Selector> {
+    let mut numbers: Vec<Option<i64>> = vec![read_number(chars, pos)?];
+    while chars.get(*pos) == Some(&':') {
+        *pos += 1;
+        numbers.push(read_number(chars, pos)?);
+    }
+    match numbers.as_slice() {
+        [Some(index)] => Ok(Selector::Index(*index)),
+        [None] => Err(anyhow!("expected an index, key or '*' at {}", *pos + 1)),
+        [start, end] => Ok(Selector::Slice { start: *start, end: *end, step: 1 }),
+        [start, end, step] => match step.unwrap_or(1) {
+            0 => bail!("slice step cannot be 0"),
+            step => Ok(Selector::Slice { start: *start, end: *end, step }),
+        },
+        _ => bail!("too many ':' in slice at {}", *pos + 1),
+    }
+}
+
+fn read_number(chars: &[char], pos: &mut usize) -> Result<Option<i64>> {
+    skip_spaces(chars, pos);
+    let start = *pos;
+    if chars.get(*pos) == Some(&'-') {
+        *pos += 1;
+    }
+    while chars.get(*pos).is_some_and(char::is_ascii_digit) {
+        *pos += 1;
+    }
+    let text: String = chars[start..*pos].iter().collect();
+    skip_spaces(chars, pos);
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse().map(Some).map_err(|_| anyhow!("bad number '{text}' at {}", start + 1))
+}
+
+fn skip_spaces(chars: &[char], pos: &mut usize) {
+    while chars.get(*pos).is_some_and(|c| c.is_whitespace()) {
+        *pos += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!({
+            "items": [
+                {"id": 1, "name": "a", "tags": ["x"]},
+                {"id": 2, "name": "b", "owner": {"id": 9}},
+                {"id": 3, "name": "c"}
+            ],
+            "total": 3,
+            "odd key": true
+        })
+    }
+
+    fn eval(expression: &str) -> Vec<Value> {
+        evaluate(expression, &sample()).unwrap()
+    }
+
+    #[test]
+    fn keys_indexes_and_wildcards() {
+        assert_eq!(eval("$.items[*].id"), vec![json!(1), json!(2), json!(3)]);
+        assert_eq!(eval("$.total"), vec![json!(3)]);
+        assert_eq!(eval("$.items[-1].name"), vec![json!("c")]);
+        assert_eq!(eval("$['odd key']"), vec![json!(true)]);
+        assert_eq!(eval("$.items[1].owner.*"), vec![json!(9)]);
+        assert_eq!(eval("$.missing"), Vec::<Value>::new());
+        assert_eq!(eval("$"), vec![sample()]);
+    }
+
+    #[test]
+    fn jq_style_and_bare_keys() {
+        assert_eq!(eval(".items[].name"), vec![json!("a"), json!("b"), json!("c")]);
+        assert_eq!(eval("items[0].id"), vec![json!(1)]);
+        assert_eq!(eval(".items.[1].id"), vec![json!(2)]);
+    }
+
+    #[test]
+    fn slices_and_unions() {
+        assert_eq!(eval("$.items[0:2].id"), vec![json!(1), json!(2)]);
+        assert_eq!(eval("$.items[1:].id"), vec![json!(2), json!(3)]);
+        assert_eq!(eval("$.items[::2].id"), vec![json!(1), json!(3)]);
+        assert_eq!(eval("$.items[-2:].id"), vec![json!(2), json!(3)]);
+        assert_eq!(eval("$.items[::-1].id"), vec![json!(3), json!(2), json!(1)]);
+        assert_eq!(eval("$.items[0,2].name"), vec![json!("a"), json!("c")]);
+        assert_eq!(eval("$.items[0]['id','name']"), vec![json!(1), json!("a")]);
+    }
+
+    #[test]
+    fn recursive_descent() {
+        assert_eq!(eval("$..id"), vec![json!(1), json!(2), json!(9), json!(3)]);
+        assert_eq!(eval("$..tags[0]"), vec![json!("x")]);
+        assert_eq!(eval("$.items..owner.id"), vec![json!(9)]);
+        assert_eq!(eval("$..[0].id"), vec![json!(1)]);
+    }
+
+    #[test]
+    fn invalid_expressions_are_errors() {
+        for bad in ["$.items[", "$.items[1", "$.", "$.items[a]", "$[::0]", "$.items]", "$['x"] {
+            assert!(evaluate(bad, &sample()).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn one_match_renders_alone_and_several_as_an_array() {
+        assert_eq!(render(vec![json!(1)]).unwrap(), "1");
+        assert_eq!(render(vec![json!(1), json!(2)]).unwrap(), "[\n    1,\n    2\n]");
+        assert_eq!(render(vec![]).unwrap(), "[]");
+    }
+}
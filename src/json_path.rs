@@ -0,0 +1,200 @@
+//! Line → JSON path index for pretty-printed responses, behind the breadcrumb
+//! bar above the response body (`data.items[41].address`).
+//!
+//! Built from the parsed value by walking it in the same layout
+//! `code_formatter::pretty_json_4` prints: one line per scalar member, an
+//! opening and a closing line per non-empty object/array, and `{}` / `[]` on
+//! one line. Each value is stored once with a parent link, so a deep document
+//! costs one node per value rather than one full path per line.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug)]
+struct Node {
+    parent: Option<usize>,
+    /// `None` for the root value.
+    segment: Option<Segment>,
+    /// Line the value starts on (its opening line for objects/arrays).
+    line: usize,
+}
+
+/// One breadcrumb: the segment as written in the path and the line its value
+/// starts on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Crumb {
+    pub text: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct JsonLineIndex {
+    nodes: Vec<Node>,
+    /// Node owning each printed line.
+    line_nodes: Vec<usize>,
+}
+
+impl JsonLineIndex {
+    pub fn build(value: &Value) -> Self {
+        let mut index = Self::default();
+        index.walk(value, None, None);
+        index
+    }
+
+    fn walk(&mut self, value: &Value, parent: Option<usize>, segment: Option<Segment>) {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            parent,
+            segment,
+            line: self.line_nodes.len(),
+        });
+        self.line_nodes.push(id);
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    self.walk(child, Some(id), Some(Segment::Key(key.clone())));
+                }
+                self.line_nodes.push(id); // closing brace
+            }
+            Value::Array(items) if !items.is_empty() => {
+                for (i, child) in items.iter().enumerate() {
+                    self.walk(child, Some(id), Some(Segment::Index(i)));
+                }
+                self.line_nodes.push(id); // closing bracket
+            }
+            _ => {}
+        }
+    }
+
+    /// Path of the value `line` belongs to, outermost first (the root itself
+    /// has no crumb). Lines past the end map to the last line.
+    pub fn crumbs_at(&self, line: usize) -> Vec<Crumb> {
+        let Some(&node) = self.line_nodes.get(line).or(self.line_nodes.last()) else {
+            return vec![];
+        };
+        let mut crumbs = Vec::new();
+        let mut current = Some(node);
+        while let Some(id) = current {
+            let node = &self.nodes[id];
+            if let Some(segment) = &node.segment {
+                let first = node.parent.is_some_and(|p| self.nodes[p].parent.is_none());
+                crumbs.push(Crumb {
+                    text: segment_text(segment, first),
+                    line: node.line,
+                });
+            }
+            current = node.parent;
+        }
+        crumbs.reverse();
+        crumbs
+    }
+}
+
+/// `key` / `.key` for identifier-like keys, `["odd key"]` otherwise, `[3]`
+/// for array items. `first` drops the leading dot of a top-level key.
+fn segment_text(segment: &Segment, first: bool) -> String {
+    match segment {
+        Segment::Index(i) => format!("[{i}]"),
+        Segment::Key(key) => {
+            let plain = key.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+                && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+            if !plain {
+                format!("[{}]", serde_json::to_string(key).unwrap_or_default())
+            } else if first {
+                key.clone()
+            } else {
+                format!(".{key}")
+            }
+        }
+    }
+}
+
+/// JSONPath of member `key` of the value at `parent` (`$` for the root),
+/// e.g. `$.data` or `$["odd key"]`.
+pub fn jsonpath_key(parent: &str, key: &str) -> String {
+    format!("{parent}{}", segment_text(&Segment::Key(key.to_string()), false))
+}
+
+/// JSONPath of item `index` of the array at `parent`, e.g. `$.items[3]`.
+pub fn jsonpath_index(parent: &str, index: usize) -> String {
+    format!("{parent}{}", segment_text(&Segment::Index(index), false))
+}
+
+/// The whole path as one string, e.g. `data.items[41].address`.
+pub fn path_string(crumbs: &[Crumb]) -> String {
+    crumbs.iter().map(|c| c.text.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_formatter::pretty_json_4;
+
+    fn sample() -> Value {
+        serde_json::json!({
+            "data": {
+                "items": [
+                    {"id": 1, "address": {"city": "Oslo", "zip": "0150"}},
+                    {"id": 2, "tags": [], "meta": {}}
+                ]
+            },
+            "odd key": true
+        })
+    }
+
+    #[test]
+    fn index_covers_every_pretty_printed_line() {
+        for value in [sample(), serde_json::json!([]), serde_json::json!("x"), serde_json::json!({"a": [1, [2, {}]]})] {
+            let pretty = pretty_json_4(&value).unwrap();
+            assert_eq!(JsonLineIndex::build(&value).line_nodes.len(), pretty.lines().count(), "{pretty}");
+        }
+    }
+
+    #[test]
+    fn each_line_maps_to_its_path() {
+        let value = sample();
+        let pretty = pretty_json_4(&value).unwrap();
+        let index = JsonLineIndex::build(&value);
+        let path_of = |needle: &str| {
+            let line = pretty.lines().position(|l| l.trim_start().starts_with(needle)).unwrap();
+            path_string(&index.crumbs_at(line))
+        };
+
+        assert_eq!(path_string(&index.crumbs_at(0)), "");
+        assert_eq!(path_of("\"items\""), "data.items");
+        assert_eq!(path_of("\"city\""), "data.items[0].address.city");
+        assert_eq!(path_of("\"tags\""), "data.items[1].tags");
+        assert_eq!(path_of("\"odd key\""), "[\"odd key\"]");
+        // A closing bracket belongs to its container.
+        let closing_items = pretty.lines().position(|l| l == "        ]").unwrap();
+        assert_eq!(path_string(&index.crumbs_at(closing_items)), "data.items");
+    }
+
+    #[test]
+    fn jsonpaths_start_at_the_root() {
+        assert_eq!(jsonpath_key("$", "data"), "$.data");
+        assert_eq!(jsonpath_index(&jsonpath_key("$", "items"), 3), "$.items[3]");
+        assert_eq!(jsonpath_key("$", "odd key"), "$[\"odd key\"]");
+    }
+
+    #[test]
+    fn crumbs_point_at_their_opening_lines() {
+        let value = sample();
+        let pretty = pretty_json_4(&value).unwrap();
+        let lines: Vec<&str> = pretty.lines().collect();
+        let index = JsonLineIndex::build(&value);
+        let zip = lines.iter().position(|l| l.contains("\"zip\"")).unwrap();
+
+        let crumbs = index.crumbs_at(zip);
+        let texts: Vec<&str> = crumbs.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["data", ".items", "[0]", ".address", ".zip"]);
+        assert!(lines[crumbs[1].line].contains("\"items\": ["));
+        assert!(lines[crumbs[3].line].contains("\"address\": {"));
+        assert_eq!(crumbs[4].line, zip);
+    }
+}
@@ -0,0 +1,213 @@
+//! Collapsible tree over a JSON response, behind the response viewer's Tree
+//! tab. The value is flattened into one row per visible node: a container
+//! shows its children only while expanded, and at most `CHUNK` of them until
+//! "show more" asks for the next chunk, so a huge array costs a bounded
+//! number of rows. Rows are rebuilt only when something is toggled.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::json_path::{jsonpath_index, jsonpath_key};
+
+/// Children shown per container before a "show more" row.
+pub const CHUNK: usize = 1000;
+
+/// What a row shows after its key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowKind {
+    /// An object (`array: false`) or array with `len` children.
+    Container { array: bool, len: usize, expanded: bool },
+    /// A scalar as JSON text (strings keep their quotes).
+    Scalar(String),
+    /// More children of the container at `pointer` than are shown.
+    More { hidden: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeRow {
+    pub depth: usize,
+    /// Member key or `[i]`; `None` for the root.
+    pub label: Option<String>,
+    /// JSONPath, e.g. `$.data.items[3]`.
+    pub path: String,
+    /// JSON Pointer for `Value::pointer`, e.g. `/data/items/3`.
+    pub pointer: String,
+    pub kind: RowKind,
+}
+
+pub struct JsonTree {
+    root: Arc<Value>,
+    /// Pointers of the expanded containers.
+    expanded: HashSet<String>,
+    /// Children shown per container pointer, when more than `CHUNK`.
+    shown: HashMap<String, usize>,
+    rows: Vec<TreeRow>,
+}
+
+impl JsonTree {
+    /// A tree with the root and its direct children showing.
+    pub fn new(root: Arc<Value>) -> Self {
+        let mut tree = Self {
+            root,
+            expanded: HashSet::from([String::new()]),
+            shown: HashMap::new(),
+            rows: vec![],
+        };
+        tree.rebuild();
+        tree
+    }
+
+    pub fn rows(&self) -> &[TreeRow] {
+        &self.rows
+    }
+
+    /// Expand or collapse the container at `pointer`.
+    pub fn toggle(&mut self, pointer: &str) {
+        if !self.expanded.remove(pointer) {
+            self.expanded.insert(pointer.to_string());
+        }
+        self.rebuild();
+    }
+
+    /// Show the next `CHUNK` children of the container at `pointer`.
+    pub fn show_more(&mut self, pointer: &str) {
+        let shown = self.shown.entry(pointer.to_string()).or_insert(CHUNK);
+        *shown += CHUNK;
+        self.rebuild();
+    }
+
+    /// The value at `pointer` as copied: strings without their quotes,
+    /// anything else as pretty-printed JSON.
+    pub fn copy_text(&self, pointer: &str) -> Option<String> {
+        match self.root.pointer(pointer)? {
+            Value::String(s) => Some(s.clone()),
+            value => serde_json::to_string_pretty(value).ok(),
+        }
+    }
+
+    fn rebuild(&mut self) {
+        let mut rows = Vec::new();
+        let root = self.root.clone();
+        self.walk(&root, 0, None, "$", "", &mut rows);
+        self.rows = rows;
+    }
+
+    fn walk(
+        &self,
+        value: &Value,
+        depth: usize,
+        label: Option<String>,
+        path: &str,
+        pointer: &str,
+        rows: &mut Vec<TreeRow>,
+    ) {
+        let (array, len) = match value {
+            Value::Object(map) => (false, map.len()),
+            Value::Array(items) => (true, items.len()),
+            scalar => {
+                rows.push(TreeRow {
+                    depth,
+                    label,
+                    path: path.to_string(),
+                    pointer: pointer.to_string(),
+                    kind: RowKind::Scalar(scalar.to_string()),
+                });
+                return;
+            }
+        };
+        let expanded = self.expanded.contains(pointer);
+        rows.push(TreeRow {
+            depth,
+            label,
+            path: path.to_string(),
+            pointer: pointer.to_string(),
+            kind: RowKind::Container { array, len, expanded },
+        });
+        if !expanded {
+            return;
+        }
+
+        let limit = self.shown.get(pointer).copied().unwrap_or(CHUNK);
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map.iter().take(limit) {
+                    let child_pointer = format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"));
+                    self.walk(child, depth + 1, Some(key.clone()), &jsonpath_key(path, key), &child_pointer, rows);
+                }
+            }
+            Value::Array(items) => {
+                for (i, child) in items.iter().enumerate().take(limit) {
+                    let child_pointer = format!("{pointer}/{i}");
+                    self.walk(child, depth + 1, Some(format!("[{i}]")), &jsonpath_index(path, i), &child_pointer, rows);
+                }
+            }
+            _ => {}
+        }
+        if len > limit {
+            rows.push(TreeRow {
+                depth: depth + 1,
+                label: None,
+                path: path.to_string(),
+                pointer: pointer.to_string(),
+                kind: RowKind::More { hidden: len - limit },
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(value: Value) -> JsonTree {
+        JsonTree::new(Arc::new(value))
+    }
+
+    fn labels(tree: &JsonTree) -> Vec<String> {
+        tree.rows()
+            .iter()
+            .map(|row| format!("{}{}", "  ".repeat(row.depth), row.label.as_deref().unwrap_or("$")))
+            .collect()
+    }
+
+    #[test]
+    fn starts_with_the_root_open_and_everything_else_closed() {
+        let mut tree = tree(serde_json::json!({"data": {"items": [1, 2]}, "ok": true}));
+        assert_eq!(labels(&tree), vec!["$", "  data", "  ok"]);
+        assert_eq!(tree.rows()[1].kind, RowKind::Container { array: false, len: 1, expanded: false });
+        assert_eq!(tree.rows()[2].kind, RowKind::Scalar("true".to_string()));
+
+        tree.toggle("/data");
+        tree.toggle("/data/items");
+        assert_eq!(labels(&tree), vec!["$", "  data", "    items", "      [0]", "      [1]", "  ok"]);
+        assert_eq!(tree.rows()[4].path, "$.data.items[1]");
+        assert_eq!(tree.rows()[2].kind, RowKind::Container { array: true, len: 2, expanded: true });
+
+        tree.toggle("/data");
+        assert_eq!(labels(&tree), vec!["$", "  data", "  ok"]);
+    }
+
+    #[test]
+    fn large_arrays_are_shown_a_chunk_at_a_time() {
+        let mut tree = tree(Value::Array((0..2500).map(Value::from).collect()));
+        assert_eq!(tree.rows().len(), 1 + CHUNK + 1);
+        assert_eq!(tree.rows().last().unwrap().kind, RowKind::More { hidden: 1500 });
+        tree.show_more("");
+        assert_eq!(tree.rows().last().unwrap().kind, RowKind::More { hidden: 500 });
+        tree.show_more("");
+        assert_eq!(tree.rows().len(), 1 + 2500);
+        assert_eq!(tree.rows().last().unwrap().label.as_deref(), Some("[2499]"));
+    }
+
+    #[test]
+    fn copies_values_by_pointer_with_escaped_keys() {
+        let tree = tree(serde_json::json!({"a/b": {"name": "ada"}, "n": [1]}));
+        assert_eq!(tree.rows()[1].pointer, "/a~1b");
+        assert_eq!(tree.rows()[1].path, "$[\"a/b\"]");
+        assert_eq!(tree.copy_text("/a~1b/name").as_deref(), Some("ada"));
+        assert_eq!(tree.copy_text("/n").as_deref(), Some("[\n  1\n]"));
+        assert_eq!(tree.copy_text("/missing"), None);
+    }
+}
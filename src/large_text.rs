@@ -0,0 +1,155 @@
+//! The response body view for text too big for the code editor: past
+//! `LARGE_BYTES` or `LARGE_LINES` the viewer shows it as plain rows in a
+//! `uniform_list`, which lays out only the rows on screen. No highlighting
+//! or formatting; `find` stands in for the editor's search.
+//!
+//! Rows are cut from the text, never copied: a line longer than the row
+//! width continues on the next row, so a multi-megabyte minified body is
+//! still many short rows rather than one enormous one.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+pub const LARGE_BYTES: usize = 1024 * 1024;
+pub const LARGE_LINES: usize = 10_000;
+
+/// Row width when wrapping, and the most a row holds when not.
+pub const WRAP_CHARS: usize = 160;
+pub const MAX_ROW_CHARS: usize = 4_000;
+
+/// Whether `text` gets this view rather than the editor.
+pub fn is_large(text: &str) -> bool {
+    text.len() > LARGE_BYTES || text.bytes().filter(|&b| b == b'\n').count() >= LARGE_LINES
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    /// Bytes of the text, line ending left out.
+    pub range: Range<usize>,
+    /// 1-based line the row belongs to.
+    pub line: usize,
+    /// A later piece of a line cut across rows.
+    pub continued: bool,
+}
+
+/// One `find` hit, in bytes of the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub range: Range<usize>,
+    /// The row it starts on.
+    pub row: usize,
+}
+
+pub struct LargeText {
+    text: Arc<str>,
+    rows: Vec<Row>,
+}
+
+impl LargeText {
+    /// Cut `text` into rows of at most `row_chars` characters.
+    pub fn new(text: Arc<str>, row_chars: usize) -> Self {
+        let row_chars = row_chars.max(1);
+        let mut rows = Vec::new();
+        let mut start = 0;
+        for (index, line) in text.split('\n').enumerate() {
+            let end = start + line.strip_suffix('\r').unwrap_or(line).len();
+            let mut row_start = start;
+            let mut chars = 0;
+            for (offset, _) in text[start..end].char_indices() {
+                if chars == row_chars {
+                    rows.push(Row { range: row_start..start + offset, line: index + 1, continued: row_start > start });
+                    row_start = start + offset;
+                    chars = 0;
+                }
+                chars += 1;
+            }
+            rows.push(Row { range: row_start..end, line: index + 1, continued: row_start > start });
+            start += line.len() + 1;
+        }
+        Self { text, rows }
+    }
+
+    pub fn text(&self) -> Arc<str> {
+        self.text.clone()
+    }
+
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    pub fn row_text(&self, row: &Row) -> &str {
+        &self.text[row.range.clone()]
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.rows.last().map_or(0, |row| row.line)
+    }
+
+    /// Every occurrence of `needle`, ignoring ASCII case, in order.
+    pub fn find(&self, needle: &str) -> Vec<Match> {
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        // ASCII lowercasing keeps byte offsets where they were.
+        let haystack = self.text.to_ascii_lowercase();
+        let needle = needle.to_ascii_lowercase();
+        haystack
+            .match_indices(&needle)
+            .map(|(at, _)| Match {
+                range: at..at + needle.len(),
+                row: self.rows.partition_point(|row| row.range.end <= at).min(self.rows.len() - 1),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(text: &str, width: usize) -> Vec<(String, usize, bool)> {
+        let large = LargeText::new(Arc::from(text), width);
+        large.rows().iter().map(|row| (large.row_text(row).to_string(), row.line, row.continued)).collect()
+    }
+
+    #[test]
+    fn lines_become_rows_and_long_ones_continue() {
+        assert_eq!(
+            rows("ab\r\nabcdefg\n\nxyz", 3),
+            [
+                ("ab".to_string(), 1, false),
+                ("abc".to_string(), 2, false),
+                ("def".to_string(), 2, true),
+                ("g".to_string(), 2, true),
+                (String::new(), 3, false),
+                ("xyz".to_string(), 4, false),
+            ]
+        );
+        assert_eq!(LargeText::new(Arc::from("a\nb\n"), 10).line_count(), 3);
+    }
+
+    #[test]
+    fn rows_are_cut_on_character_boundaries() {
+        assert_eq!(
+            rows("héllo wörld", 4),
+            [("héll".to_string(), 1, false), ("o wö".to_string(), 1, true), ("rld".to_string(), 1, true)]
+        );
+    }
+
+    #[test]
+    fn find_ignores_case_and_reports_the_row() {
+        let large = LargeText::new(Arc::from("{\"Name\":1}\n{\"name\":2,\"nickname\":3}"), 12);
+        let found = large.find("NAME");
+        assert_eq!(found.len(), 3);
+        assert_eq!(found.iter().map(|m| m.row).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(&large.text[found[2].range.clone()], "name");
+        assert!(large.find("").is_empty());
+    }
+
+    #[test]
+    fn only_big_bodies_are_large() {
+        assert!(!is_large("{\"small\":true}"));
+        assert!(is_large(&"x".repeat(LARGE_BYTES + 1)));
+        assert!(is_large(&"1\n".repeat(LARGE_LINES)));
+    }
+}
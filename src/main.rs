@@ -1,29 +1,70 @@
 #![windows_subsystem = "windows"]
 
+mod a11y;
+mod activity_webhook;
 mod app;
+mod assertions;
 mod auth_editor;
 mod body_editor;
+mod body_history;
+mod charset;
 mod code_formatter;
 mod code_gen;
 mod code_snippet_panel;
+mod collection_run;
+mod collections_panel;
+mod compare_view;
+mod connect_timing;
+mod content_encoding;
+mod content_type;
+mod cookie_jar;
+mod cookies;
 mod curl_import;
 mod db;
+mod env_overrides;
 mod environment_manager;
+mod extract;
 mod format;
 mod header_completion;
 mod header_names;
+mod history_meta;
 mod history_panel;
+mod host_overrides;
 mod http_client;
+mod json_filter;
+mod json_path;
+mod json_tree;
+mod large_text;
 mod menu_bar;
+mod oauth2;
+mod pagination;
+mod postman_export;
+mod postman_import;
+mod presets;
+mod repeat_runner;
+mod request_builder;
+mod request_diff;
 mod request_editor;
+mod request_export;
+mod request_log;
 mod request_tab;
 mod response_viewer;
+mod retry_after;
+mod runner_panel;
+mod session;
+mod start_panel;
 mod tab_bar;
-mod theme;
+mod text_diff;
+mod theme;
+mod throttle;
 mod types;
 mod ui;
 mod variables;
 mod url_params;
+mod wire_log;
+mod workspace_bundle;
+mod ws_client;
+mod ws_panel;
 
 use gpui::*;
 use gpui_component::Root;
@@ -83,7 +124,9 @@ fn setup_logger() {
     // Clone file handle for the builder
     let file_clone = log_file.try_clone().expect("Failed to clone file handle");
 
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+    // Everything of ours passes the filter; the runtime level
+    // (`request_log::set_level`) decides what is written.
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info,poopman=trace"))
         .format(move |_buf, record| {
             let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
 
@@ -100,6 +143,7 @@ fn setup_logger() {
             Ok(())
         })
         .init();
+    request_log::set_level(request_log::DEFAULT_LOG_LEVEL);
 
     log::info!("Poopman started - logging to: {}", log_file_path.display());
 }
@@ -130,19 +174,36 @@ fn main() {
         cx.bind_keys([
             KeyBinding::new("ctrl-enter", crate::app::SendRequest, None),
             KeyBinding::new("ctrl-enter", crate::app::SendRequest, Some("Input")),
+            KeyBinding::new("cmd-enter", crate::app::SendRequest, None),
+            KeyBinding::new("cmd-enter", crate::app::SendRequest, Some("Input")),
+            KeyBinding::new("ctrl-s", crate::app::SaveRequest, None),
+            KeyBinding::new("ctrl-s", crate::app::SaveRequest, Some("Input")),
             KeyBinding::new("ctrl-t", crate::app::NewTab, None),
             KeyBinding::new("ctrl-w", crate::app::CloseTab, None),
             KeyBinding::new("ctrl-tab", crate::app::NextTab, None),
             KeyBinding::new("ctrl-shift-tab", crate::app::PrevTab, None),
+            KeyBinding::new("ctrl-shift-pageup", crate::app::MoveTabLeft, None),
+            KeyBinding::new("ctrl-shift-pagedown", crate::app::MoveTabRight, None),
             KeyBinding::new("ctrl-l", crate::app::FocusUrl, None),
+            KeyBinding::new("ctrl-shift-m", crate::app::OpenMethodMenu, None),
+            KeyBinding::new("alt-up", crate::app::PrevMethod, None),
+            KeyBinding::new("alt-down", crate::app::NextMethod, None),
+            KeyBinding::new("ctrl-alt-z", crate::app::UndoBodyAction, None),
             KeyBinding::new("cmd-q", crate::app::Quit, None),
         ]);
+        cx.bind_keys((1..=9).map(|n| {
+            KeyBinding::new(&format!("ctrl-{n}"), crate::app::ActivateTab { index: n - 1 }, None)
+        }));
 
         cx.set_menus(vec![Menu {
             name: "Poopman".into(),
             items: vec![MenuItem::action("Quit Poopman", crate::app::Quit)],
         }]);
 
+        // Open the database (file + schema) in parallel with creating the
+        // window; the app only reads from it in the background.
+        let db = std::sync::Arc::new(crate::db::Database::open_in_background());
+
         cx.spawn(async move |cx| {
             let window_options = WindowOptions {
                 titlebar: Some(gpui_component::TitleBar::title_bar_options()),
@@ -150,7 +211,7 @@ fn main() {
                 ..Default::default()
             };
             cx.open_window(window_options, |window, cx| {
-                let view = cx.new(|cx| PoopmanApp::new(window, cx));
+                let view = cx.new(|cx| PoopmanApp::new(db, window, cx));
                 cx.new(|cx| Root::new(view, window, cx))
             })?;
             Ok::<_, anyhow::Error>(())
@@ -1,5 +1,8 @@
 //! The Edit menu shown in the title bar. Houses environment switching (with a
-//! check mark on the active one) and an entry to open the environment dialog.
+//! check mark on the active one), entries for the environment dialog and the
+//! temporary variable overrides, the cookie jar, the activity webhook, CA
+//! certificate, response size limits and log level settings, workspace
+//! export / import, and single-request export / import.
 //! Item handlers call back into `PoopmanApp` via a captured entity handle.
 
 use gpui::*;
@@ -10,19 +13,27 @@ use gpui_component::{
 };
 
 use crate::app::PoopmanApp;
+use crate::db::HISTORY_BODY_CAP_CHOICES;
+use crate::http_client::BODY_LIMIT_CHOICES;
+use crate::request_log::LOG_LEVELS;
 use crate::types::Environment;
+use crate::workspace_bundle::SecretPolicy;
 
 /// Build the "Edit" dropdown button for the title bar.
 pub fn edit_menu(
     app: Entity<PoopmanApp>,
     environments: Vec<Environment>,
     active_id: Option<i64>,
+    override_count: usize,
+    log_level: log::LevelFilter,
+    ca_cert_path: Option<String>,
+    (body_limit, history_body_cap): (u64, u64),
 ) -> impl IntoElement {
     Button::new("edit-menu")
         .ghost()
         .small()
         .label("Edit")
-        .dropdown_menu(move |menu, _window, _cx| {
+        .dropdown_menu(move |menu, window, cx| {
             let mut menu = menu.label("Environment");
 
             for env in &environments {
@@ -68,6 +79,180 @@ pub fn edit_menu(
                 );
             }
 
+            {
+                let app = app.clone();
+                let label = match override_count {
+                    0 => "Temporary Overrides\u{2026}".to_string(),
+                    n => format!("Temporary Overrides ({n} active)\u{2026}"),
+                };
+                menu = menu.item(PopupMenuItem::new(label).on_click(move |_, window, cx| {
+                    app.update(cx, |app, cx| app.open_env_overrides_dialog(window, cx));
+                }));
+            }
+
+            {
+                let app = app.clone();
+                let label = if crate::http_client::cookie_jar_enabled() {
+                    "Cookies (on)\u{2026}"
+                } else {
+                    "Cookies\u{2026}"
+                };
+                menu = menu.item(PopupMenuItem::new(label).on_click(move |_, window, cx| {
+                    app.update(cx, |app, cx| app.open_cookies_dialog(window, cx));
+                }));
+            }
+
+            {
+                let app = app.clone();
+                let label = match crate::http_client::host_overrides().len() {
+                    0 => "Resolve Hosts\u{2026}".to_string(),
+                    n => format!("Resolve Hosts ({n})\u{2026}"),
+                };
+                menu = menu.item(PopupMenuItem::new(label).on_click(move |_, window, cx| {
+                    app.update(cx, |app, cx| app.open_host_overrides_dialog(None, window, cx));
+                }));
+            }
+
+            {
+                let app = app.clone();
+                menu = menu.item(
+                    PopupMenuItem::new("Activity Webhook\u{2026}").on_click(move |_, window, cx| {
+                        app.update(cx, |app, cx| app.open_activity_webhook_dialog(window, cx));
+                    }),
+                );
+            }
+
+            {
+                let app = app.clone();
+                let ca_cert_path = ca_cert_path.clone();
+                menu = menu.submenu("CA Certificate", window, cx, move |mut menu, _window, _cx| {
+                    if let Some(path) = &ca_cert_path {
+                        menu = menu.label(path.clone());
+                    }
+                    let choose = app.clone();
+                    let clear = app.clone();
+                    menu.item(PopupMenuItem::new("Choose PEM File\u{2026}").on_click(move |_, window, cx| {
+                        choose.update(cx, |app, cx| app.choose_ca_cert(window, cx));
+                    }))
+                    .item(
+                        PopupMenuItem::new("System Certificates Only")
+                            .checked(ca_cert_path.is_none())
+                            .on_click(move |_, _window, cx| {
+                                clear.update(cx, |app, cx| app.set_ca_cert_path(None, cx));
+                            }),
+                    )
+                });
+            }
+
+            {
+                let app = app.clone();
+                menu = menu.submenu("Response Size Limit", window, cx, move |mut menu, _window, _cx| {
+                    for bytes in BODY_LIMIT_CHOICES {
+                        let app = app.clone();
+                        let label = match bytes {
+                            0 => "No Limit".to_string(),
+                            n => crate::format::format_size(n as usize),
+                        };
+                        menu = menu.item(PopupMenuItem::new(label).checked(bytes == body_limit).on_click(
+                            move |_, _window, cx| {
+                                app.update(cx, |app, cx| app.set_body_limit(bytes, cx));
+                            },
+                        ));
+                    }
+                    menu
+                });
+            }
+
+            {
+                let app = app.clone();
+                menu = menu.submenu("Response Size Kept in History", window, cx, move |mut menu, _window, _cx| {
+                    for bytes in HISTORY_BODY_CAP_CHOICES {
+                        let app = app.clone();
+                        let label = match bytes {
+                            0 => "Whole Body".to_string(),
+                            n => crate::format::format_size(n as usize),
+                        };
+                        menu = menu.item(PopupMenuItem::new(label).checked(bytes == history_body_cap).on_click(
+                            move |_, _window, cx| {
+                                app.update(cx, |app, cx| app.set_history_body_cap(bytes, cx));
+                            },
+                        ));
+                    }
+                    menu
+                });
+            }
+
+            {
+                let app = app.clone();
+                menu = menu.submenu("Log Level", window, cx, move |mut menu, _window, _cx| {
+                    for level in LOG_LEVELS {
+                        let app = app.clone();
+                        menu = menu.item(
+                            PopupMenuItem::new(level.as_str())
+                                .checked(level == log_level)
+                                .on_click(move |_, _window, cx| {
+                                    app.update(cx, |app, cx| app.set_log_level(level, cx));
+                                }),
+                        );
+                    }
+                    menu
+                });
+            }
+
+            menu = menu.separator();
+
+            {
+                let app = app.clone();
+                menu = menu.submenu("Export Workspace", window, cx, move |menu, _window, _cx| {
+                    let item = |label: &'static str, policy: SecretPolicy| {
+                        let app = app.clone();
+                        PopupMenuItem::new(label).on_click(move |_, window, cx| {
+                            app.update(cx, |app, cx| app.export_workspace(policy, window, cx));
+                        })
+                    };
+                    menu.label("Secrets (tokens, passwords, keys)")
+                        .item(item("Mask Values\u{2026}", SecretPolicy::Mask))
+                        .item(item("Leave Out\u{2026}", SecretPolicy::Exclude))
+                        .item(item("Include Values\u{2026}", SecretPolicy::Include))
+                });
+            }
+
+            {
+                let app = app.clone();
+                menu = menu.item(
+                    PopupMenuItem::new("Import Workspace\u{2026}").on_click(move |_, window, cx| {
+                        app.update(cx, |app, cx| app.import_workspace(window, cx));
+                    }),
+                );
+            }
+
+            {
+                let app = app.clone();
+                menu = menu.item(
+                    PopupMenuItem::new("Export Current Request\u{2026}").on_click(move |_, window, cx| {
+                        app.update(cx, |app, cx| app.export_current_request(window, cx));
+                    }),
+                );
+            }
+
+            {
+                let app = app.clone();
+                menu = menu.item(
+                    PopupMenuItem::new("Import Request\u{2026}").on_click(move |_, window, cx| {
+                        app.update(cx, |app, cx| app.import_request(window, cx));
+                    }),
+                );
+            }
+
+            {
+                let app = app.clone();
+                menu = menu.item(
+                    PopupMenuItem::new("Import Postman Collection\u{2026}").on_click(move |_, window, cx| {
+                        app.update(cx, |app, cx| app.import_postman_collection(window, cx));
+                    }),
+                );
+            }
+
             menu
         })
 }
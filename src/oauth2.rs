@@ -0,0 +1,578 @@
+//! Tokens for OAuth 2.0 auth. A token is fetched from the config's token
+//! endpoint through `HttpClient` (client credentials, a refresh token, or
+//! the code a browser sign-in brought back) and kept in memory for the
+//! session, keyed by endpoint, client, scopes and audience. It is never
+//! saved: sends attach it as `Authorization: Bearer` (`with_token`), and
+//! history keeps that header redacted (`redacted`).
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{Read as _, Write as _};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Local, TimeDelta};
+use futures::channel::oneshot;
+use sha2::{Digest as _, Sha256};
+
+use crate::types::{AuthType, BodyType, HttpMethod, OAuth2ClientAuth, OAuth2Config, OAuth2Grant, RawSubtype, RequestData};
+
+/// A token this close to its expiry counts as expired, so it doesn't lapse
+/// on the way to the server.
+const EXPIRY_SKEW: TimeDelta = TimeDelta::seconds(30);
+
+/// How long the browser sign-in may take before the listener gives up.
+const SIGN_IN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Whole-request limit for token requests, in seconds.
+const TOKEN_TIMEOUT_SECS: u32 = 30;
+
+/// An access token and what came with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub access_token: String,
+    /// `None` when the server gave no `expires_in`; such a token is used
+    /// until a request is refused.
+    pub expires_at: Option<DateTime<Local>>,
+    pub refresh_token: Option<String>,
+}
+
+impl Token {
+    pub fn is_fresh(&self, now: DateTime<Local>) -> bool {
+        self.expires_at.is_none_or(|at| at - EXPIRY_SKEW > now)
+    }
+
+    /// The start and end of the token, for showing without giving it away.
+    pub fn masked(&self) -> String {
+        let chars: Vec<char> = self.access_token.chars().collect();
+        if chars.len() <= 12 {
+            return "•".repeat(chars.len());
+        }
+        let (head, tail): (String, String) = (chars[..4].iter().collect(), chars[chars.len() - 4..].iter().collect());
+        format!("{head}…{tail}")
+    }
+}
+
+/// The session's tokens, by `cache_key`.
+static TOKENS: Mutex<BTreeMap<String, Token>> = Mutex::new(BTreeMap::new());
+
+/// Configs that would ask the same server for the same token share it.
+fn cache_key(config: &OAuth2Config) -> String {
+    let grant = match config.grant {
+        OAuth2Grant::ClientCredentials => "client_credentials",
+        OAuth2Grant::AuthorizationCode => "authorization_code",
+    };
+    [grant, &config.token_url, &config.client_id, &config.scopes, &config.audience].join("\n")
+}
+
+/// The token held for `config`, fresh or not.
+pub fn cached(config: &OAuth2Config) -> Option<Token> {
+    TOKENS.lock().unwrap().get(&cache_key(config)).cloned()
+}
+
+/// The held access token for `config` when it has not expired.
+pub fn usable(config: &OAuth2Config) -> Option<String> {
+    cached(config).filter(|token| token.is_fresh(Local::now())).map(|token| token.access_token)
+}
+
+fn store(config: &OAuth2Config, token: Token) {
+    TOKENS.lock().unwrap().insert(cache_key(config), token);
+}
+
+/// Drop the token held for `config`; the next send fetches a new one.
+pub fn forget(config: &OAuth2Config) {
+    TOKENS.lock().unwrap().remove(&cache_key(config));
+}
+
+/// Why a token can't be fetched for `config`, or `None` when it can.
+pub fn config_problem(config: &OAuth2Config) -> Option<String> {
+    let http_url = |label: &str, url: &str| match url::Url::parse(url.trim()) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => None,
+        _ if url.trim().is_empty() => Some(format!("no {label}")),
+        _ => Some(format!("the {label} is not an http(s) URL")),
+    };
+    if let Some(problem) = http_url("token URL", &config.token_url) {
+        return Some(problem);
+    }
+    if config.client_id.trim().is_empty() {
+        return Some("no client ID".to_string());
+    }
+    if config.grant == OAuth2Grant::AuthorizationCode {
+        if let Some(problem) = http_url("authorization URL", &config.auth_url) {
+            return Some(problem);
+        }
+        if let Err(problem) = loopback(&config.redirect_uri) {
+            return Some(problem);
+        }
+    }
+    None
+}
+
+/// A token for `config` to send with: the held one while it is fresh, else a
+/// new one, which is kept. An authorization-code token can only be renewed
+/// with its refresh token; without one the user has to sign in again.
+pub async fn ensure(config: &OAuth2Config) -> Result<String, String> {
+    if let Some(access_token) = usable(config) {
+        return Ok(access_token);
+    }
+    if let Some(problem) = config_problem(config) {
+        return Err(problem);
+    }
+    let refresh_token = cached(config).and_then(|token| token.refresh_token);
+    let token = match (refresh_token, config.grant) {
+        (Some(refresh_token), grant) => match refresh(config, &refresh_token).await {
+            Ok(token) => token,
+            Err(_) if grant == OAuth2Grant::ClientCredentials => client_credentials(config).await?,
+            Err(e) => return Err(format!("{e}; sign in again with Get token")),
+        },
+        (None, OAuth2Grant::ClientCredentials) => client_credentials(config).await?,
+        (None, OAuth2Grant::AuthorizationCode) => {
+            return Err("not signed in; use Get token in the Auth tab".to_string());
+        }
+    };
+    let access_token = token.access_token.clone();
+    store(config, token);
+    Ok(access_token)
+}
+
+/// Fetch a new client-credentials token for `config` and keep it, whatever
+/// is held already (Get token).
+pub async fn fetch(config: &OAuth2Config) -> Result<Token, String> {
+    if let Some(problem) = config_problem(config) {
+        return Err(problem);
+    }
+    let token = client_credentials(config).await?;
+    store(config, token.clone());
+    Ok(token)
+}
+
+async fn client_credentials(config: &OAuth2Config) -> Result<Token, String> {
+    let mut form = vec![("grant_type", "client_credentials".to_string())];
+    form.extend(scope_and_audience(config));
+    request_token(config, form).await
+}
+
+async fn refresh(config: &OAuth2Config, refresh_token: &str) -> Result<Token, String> {
+    let form = vec![("grant_type", "refresh_token".to_string()), ("refresh_token", refresh_token.to_string())];
+    let token = request_token(config, form).await?;
+    // Servers that don't rotate refresh tokens leave the old one good.
+    Ok(Token { refresh_token: token.refresh_token.or_else(|| Some(refresh_token.to_string())), ..token })
+}
+
+fn scope_and_audience(config: &OAuth2Config) -> Vec<(&'static str, String)> {
+    [("scope", &config.scopes), ("audience", &config.audience)]
+        .into_iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(name, value)| (name, value.trim().to_string()))
+        .collect()
+}
+
+/// The headers and form body of a token request: `form` plus the client's
+/// credentials, placed as `client_auth` says. A client without a secret (a
+/// public one) always names itself in the body.
+fn token_request(config: &OAuth2Config, mut form: Vec<(&'static str, String)>) -> (Vec<(String, String)>, String) {
+    let mut headers = vec![
+        ("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string()),
+        ("Accept".to_string(), "application/json".to_string()),
+    ];
+    let (client_id, secret) = (config.client_id.trim().to_string(), config.client_secret.clone());
+    match config.client_auth {
+        OAuth2ClientAuth::BasicHeader if !secret.is_empty() => {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(format!("{client_id}:{secret}"));
+            headers.push(("Authorization".to_string(), format!("Basic {encoded}")));
+        }
+        OAuth2ClientAuth::BasicHeader => form.push(("client_id", client_id)),
+        OAuth2ClientAuth::Body => {
+            form.push(("client_id", client_id));
+            if !secret.is_empty() {
+                form.push(("client_secret", secret));
+            }
+        }
+    }
+    let body = url::form_urlencoded::Serializer::new(String::new()).extend_pairs(form).finish();
+    (headers, body)
+}
+
+async fn request_token(config: &OAuth2Config, form: Vec<(&'static str, String)>) -> Result<Token, String> {
+    let (headers, body) = token_request(config, form);
+    let body = BodyType::Raw { content: body, subtype: RawSubtype::Text };
+    let client = crate::http_client::HttpClient::new().with_timeout(TOKEN_TIMEOUT_SECS);
+    log::info!("OAuth 2.0 token request: POST {}", config.token_url.trim());
+    let response = client
+        .start_send(HttpMethod::POST, config.token_url.trim().to_string(), headers, body)
+        .wait()
+        .await
+        .map_err(|e| format!("token request failed: {e}"))?;
+    parse_token_response(response.status, &response.body, Local::now())
+}
+
+/// The token in a token endpoint's answer, or what the server said went
+/// wrong (`error` and `error_description`, RFC 6749 §5.2).
+fn parse_token_response(status: u16, body: &[u8], now: DateTime<Local>) -> Result<Token, String> {
+    let json: Option<serde_json::Value> = serde_json::from_slice(body).ok();
+    let field = |name: &str| json.as_ref().and_then(|json| json.get(name));
+    let text = |name: &str| field(name).and_then(serde_json::Value::as_str).filter(|s| !s.is_empty());
+    if let Some(error) = text("error") {
+        return Err(match text("error_description") {
+            Some(description) => format!("{error}: {description} (HTTP {status})"),
+            None => format!("{error} (HTTP {status})"),
+        });
+    }
+    if !(200..300).contains(&status) {
+        let snippet: String = String::from_utf8_lossy(body).chars().take(200).collect();
+        return Err(format!("the token endpoint answered HTTP {status}: {}", snippet.trim()));
+    }
+    let Some(access_token) = text("access_token") else {
+        return Err("the token endpoint's answer has no access_token".to_string());
+    };
+    // Some servers send `expires_in` as a string.
+    let expires_in = field("expires_in").and_then(|value| match value {
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        value => value.as_i64(),
+    });
+    Ok(Token {
+        access_token: access_token.to_string(),
+        expires_at: expires_in.map(|secs| now + TimeDelta::seconds(secs)),
+        refresh_token: text("refresh_token").map(str::to_string),
+    })
+}
+
+/// `sent` with the bearer token in its `Authorization` header, replacing one
+/// typed by hand, as computed auth headers do.
+pub fn with_token(sent: RequestData, access_token: &str) -> RequestData {
+    let mut headers: Vec<(String, String)> =
+        sent.headers.into_iter().filter(|(name, _)| !name.eq_ignore_ascii_case("authorization")).collect();
+    headers.push(("Authorization".to_string(), format!("Bearer {access_token}")));
+    RequestData { headers, ..sent }
+}
+
+/// `sent` as history keeps it: with OAuth 2.0 auth, the token it went out
+/// with is replaced by `REDACTED`. A resend fetches its own.
+pub fn redacted(sent: &RequestData) -> Cow<'_, RequestData> {
+    if sent.auth.auth_type != AuthType::OAuth2 {
+        return Cow::Borrowed(sent);
+    }
+    let headers = sent
+        .headers
+        .iter()
+        .map(|(name, value)| {
+            if name.eq_ignore_ascii_case("authorization") {
+                (name.clone(), format!("Bearer {}", crate::request_log::REDACTED))
+            } else {
+                (name.clone(), value.clone())
+            }
+        })
+        .collect();
+    Cow::Owned(RequestData { headers, ..sent.clone() })
+}
+
+/// Where a `redirect_uri` has the browser come back to: a loopback host and
+/// port to listen on, and the path the redirect arrives at.
+fn loopback(redirect_uri: &str) -> Result<(String, u16, String), String> {
+    let url = url::Url::parse(redirect_uri.trim()).map_err(|e| format!("the redirect URI is not a URL: {e}"))?;
+    let host = match url.host_str() {
+        Some("localhost") => "127.0.0.1".to_string(),
+        Some(host @ ("127.0.0.1" | "[::1]")) => host.trim_matches(['[', ']']).to_string(),
+        _ => return Err("the redirect URI must be on 127.0.0.1, [::1] or localhost".to_string()),
+    };
+    if url.scheme() != "http" {
+        return Err("the redirect URI must be an http:// URL".to_string());
+    }
+    let port = url.port_or_known_default().unwrap_or(80);
+    Ok((host, port, url.path().to_string()))
+}
+
+fn random_text(bytes: usize) -> Result<String, String> {
+    let mut buf = vec![0u8; bytes];
+    getrandom::fill(&mut buf).map_err(|e| format!("no randomness for the sign-in: {e}"))?;
+    Ok(URL_SAFE_NO_PAD.encode(buf))
+}
+
+/// The PKCE S256 challenge for `verifier` (RFC 7636 §4.2).
+fn code_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// The authorization URL the browser opens.
+fn authorize_url(config: &OAuth2Config, state: &str, challenge: &str) -> Result<String, String> {
+    let mut url = url::Url::parse(config.auth_url.trim()).map_err(|e| format!("the authorization URL is not a URL: {e}"))?;
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", config.client_id.trim())
+            .append_pair("redirect_uri", config.redirect_uri.trim());
+        for (name, value) in scope_and_audience(config) {
+            query.append_pair(name, &value);
+        }
+        query
+            .append_pair("state", state)
+            .append_pair("code_challenge", challenge)
+            .append_pair("code_challenge_method", "S256");
+    }
+    Ok(url.into())
+}
+
+/// An authorization-code sign-in under way: the app listens on the redirect
+/// URI's port while the user signs in at `url`. Dropping it stops listening.
+pub struct SignIn {
+    /// For the system browser.
+    pub url: String,
+    verifier: String,
+    code: oneshot::Receiver<Result<String, String>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl SignIn {
+    /// Start listening for the redirect; the caller opens `url`.
+    pub fn start(config: &OAuth2Config) -> Result<Self, String> {
+        if let Some(problem) = config_problem(config) {
+            return Err(problem);
+        }
+        let (host, port, path) = loopback(&config.redirect_uri)?;
+        let listener = TcpListener::bind((host.as_str(), port))
+            .map_err(|e| format!("can't listen on {host}:{port} for the redirect: {e}"))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let (verifier, state) = (random_text(32)?, random_text(16)?);
+        let url = authorize_url(config, &state, &code_challenge(&verifier))?;
+        let (done, code) = oneshot::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        log::info!("OAuth 2.0 sign-in: listening on {host}:{port}{path}");
+        std::thread::spawn(move || {
+            if let Some(result) = wait_for_redirect(&listener, &path, &state, &stopped) {
+                let _ = done.send(result);
+            }
+        });
+        Ok(Self { url, verifier, code, stop })
+    }
+
+    /// Wait for the browser to come back, trade its code for a token and
+    /// keep that.
+    pub async fn finish(mut self, config: &OAuth2Config) -> Result<Token, String> {
+        let code = (&mut self.code).await.map_err(|_| "the sign-in was stopped".to_string())??;
+        let form = vec![
+            ("grant_type", "authorization_code".to_string()),
+            ("code", code),
+            ("redirect_uri", config.redirect_uri.trim().to_string()),
+            ("code_verifier", self.verifier.clone()),
+        ];
+        let token = request_token(config, form).await?;
+        store(config, token.clone());
+        Ok(token)
+    }
+}
+
+impl Drop for SignIn {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Accept connections until one brings the redirect, answering each. `None`
+/// once stopped.
+fn wait_for_redirect(listener: &TcpListener, path: &str, state: &str, stop: &AtomicBool) -> Option<Result<String, String>> {
+    let deadline = Instant::now() + SIGN_IN_TIMEOUT;
+    loop {
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        if Instant::now() > deadline {
+            return Some(Err("no sign-in within 5 minutes".to_string()));
+        }
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Some(result) = answer(stream, path, state) {
+                    return Some(result);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(Duration::from_millis(100)),
+            Err(e) => return Some(Err(format!("listening for the redirect failed: {e}"))),
+        }
+    }
+}
+
+/// Read one request off `stream` and answer it with a page for the browser.
+/// `None` for requests that aren't the redirect (a favicon, say).
+fn answer(mut stream: TcpStream, path: &str, state: &str) -> Option<Result<String, String>> {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 2048];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < 16 * 1024 {
+        match stream.read(&mut chunk) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    }
+    let head = String::from_utf8_lossy(&buf);
+    let result = redirect_code(head.lines().next().unwrap_or_default(), path, state);
+    let (status, title, message) = match &result {
+        None => ("404 Not Found", "Not found", String::new()),
+        Some(Ok(_)) => ("200 OK", "Signed in", "You can close this tab and return to Poopman.".to_string()),
+        Some(Err(e)) => ("400 Bad Request", "Sign-in failed", e.clone()),
+    };
+    let page = format!(
+        "<!doctype html><meta charset=\"utf-8\"><title>{title}</title><h1>{title}</h1><p>{}</p>",
+        html_escape(&message)
+    );
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+        page.len()
+    );
+    result
+}
+
+/// What the redirect in `request_line` (`GET /callback?code=… HTTP/1.1`)
+/// brought: the code, or why there is none. `None` when the request is for
+/// another path.
+fn redirect_code(request_line: &str, path: &str, state: &str) -> Option<Result<String, String>> {
+    let target = request_line.split_whitespace().nth(1)?;
+    let (target_path, query) = target.split_once('?').unwrap_or((target, ""));
+    if target_path != path {
+        return None;
+    }
+    let params: BTreeMap<String, String> = url::form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+    if let Some(error) = params.get("error") {
+        return Some(Err(match params.get("error_description") {
+            Some(description) => format!("{error}: {description}"),
+            None => error.clone(),
+        }));
+    }
+    if params.get("state").map(String::as_str) != Some(state) {
+        return Some(Err("the redirect's state doesn't match this sign-in".to_string()));
+    }
+    Some(params.get("code").cloned().ok_or_else(|| "the redirect has no code".to_string()))
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(token_url: &str) -> OAuth2Config {
+        OAuth2Config {
+            token_url: token_url.to_string(),
+            client_id: "app".to_string(),
+            client_secret: "s3cret".to_string(),
+            scopes: "read write".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn token_responses_are_read_with_their_expiry() {
+        let now = Local::now();
+        let body = br#"{"access_token":"abc","token_type":"Bearer","expires_in":3600,"refresh_token":"r1"}"#;
+        let token = parse_token_response(200, body, now).unwrap();
+        assert_eq!(token.access_token, "abc");
+        assert_eq!(token.expires_at, Some(now + TimeDelta::seconds(3600)));
+        assert_eq!(token.refresh_token.as_deref(), Some("r1"));
+        assert!(token.is_fresh(now));
+        assert!(!token.is_fresh(now + TimeDelta::seconds(3590)));
+
+        let token = parse_token_response(200, br#"{"access_token":"abc","expires_in":"60"}"#, now).unwrap();
+        assert_eq!(token.expires_at, Some(now + TimeDelta::seconds(60)));
+    }
+
+    #[test]
+    fn token_errors_say_what_the_server_said() {
+        let body = br#"{"error":"invalid_client","error_description":"Unknown client"}"#;
+        assert_eq!(parse_token_response(401, body, Local::now()).unwrap_err(), "invalid_client: Unknown client (HTTP 401)");
+        assert!(parse_token_response(502, b"Bad Gateway", Local::now()).unwrap_err().contains("HTTP 502: Bad Gateway"));
+        assert!(parse_token_response(200, b"{}", Local::now()).unwrap_err().contains("no access_token"));
+    }
+
+    #[test]
+    fn client_credentials_go_where_the_config_says() {
+        let form = vec![("grant_type", "client_credentials".to_string())];
+        let (headers, body) = token_request(&config("https://idp.test/token"), form.clone());
+        assert!(headers.contains(&("Authorization".to_string(), "Basic YXBwOnMzY3JldA==".to_string())));
+        assert_eq!(body, "grant_type=client_credentials");
+
+        let in_body = OAuth2Config { client_auth: OAuth2ClientAuth::Body, ..config("https://idp.test/token") };
+        let (headers, body) = token_request(&in_body, form);
+        assert!(!headers.iter().any(|(name, _)| name == "Authorization"));
+        assert_eq!(body, "grant_type=client_credentials&client_id=app&client_secret=s3cret");
+    }
+
+    #[test]
+    fn the_authorize_url_carries_pkce_and_state() {
+        let config = OAuth2Config {
+            grant: OAuth2Grant::AuthorizationCode,
+            auth_url: "https://idp.test/authorize?prompt=login".to_string(),
+            audience: "https://api.test".to_string(),
+            ..config("https://idp.test/token")
+        };
+        let url = url::Url::parse(&authorize_url(&config, "st", "ch").unwrap()).unwrap();
+        let query: BTreeMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(query["prompt"], "login");
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["redirect_uri"], "http://127.0.0.1:8765/callback");
+        assert_eq!(query["scope"], "read write");
+        assert_eq!(query["audience"], "https://api.test");
+        assert_eq!((query["state"].as_str(), query["code_challenge_method"].as_str()), ("st", "S256"));
+        // RFC 7636 appendix B.
+        assert_eq!(code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"), "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+    }
+
+    #[test]
+    fn redirects_are_checked_against_the_sign_in() {
+        assert_eq!(redirect_code("GET /callback?code=c1&state=st HTTP/1.1", "/callback", "st"), Some(Ok("c1".to_string())));
+        assert_eq!(redirect_code("GET /favicon.ico HTTP/1.1", "/callback", "st"), None);
+        assert!(redirect_code("GET /callback?code=c1&state=other HTTP/1.1", "/callback", "st").unwrap().is_err());
+        assert_eq!(
+            redirect_code("GET /callback?error=access_denied&error_description=No%20thanks HTTP/1.1", "/callback", "st"),
+            Some(Err("access_denied: No thanks".to_string()))
+        );
+        assert!(loopback("http://localhost:9000/cb").is_ok_and(|(host, port, path)| (host.as_str(), port, path.as_str()) == ("127.0.0.1", 9000, "/cb")));
+        assert!(loopback("https://example.com/cb").is_err());
+    }
+
+    #[test]
+    fn history_gets_the_token_redacted() {
+        let mut sent = RequestData::new(HttpMethod::GET, "https://api.test".to_string());
+        sent.headers = vec![("Accept".to_string(), "*/*".to_string()), ("Authorization".to_string(), "Bearer old".to_string())];
+        sent.auth.auth_type = AuthType::OAuth2;
+        let sent = with_token(sent, "t0ken");
+        assert_eq!(sent.headers.iter().filter(|(name, _)| name == "Authorization").count(), 1);
+        assert_eq!(sent.headers.last().unwrap().1, "Bearer t0ken");
+        let kept = redacted(&sent);
+        assert_eq!(kept.headers.last().unwrap().1, "Bearer [redacted]");
+        assert!(!serde_json::to_string(&kept.headers).unwrap().contains("t0ken"));
+    }
+
+    #[test]
+    fn client_credentials_tokens_are_fetched_and_reused() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let token_url = format!("http://{}/token", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let body = r#"{"access_token":"fetched","expires_in":3600}"#;
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+            request
+        });
+
+        let config = config(&token_url);
+        let runtime = crate::http_client::runtime();
+        assert_eq!(runtime.block_on(ensure(&config)).unwrap(), "fetched");
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /token "), "{request}");
+        assert!(request.contains("grant_type=client_credentials&scope=read+write"), "{request}");
+        // The server is gone: the second send must use the kept token.
+        assert_eq!(runtime.block_on(ensure(&config)).unwrap(), "fetched");
+        assert_eq!(usable(&config).as_deref(), Some("fetched"));
+        forget(&config);
+        assert_eq!(usable(&config), None);
+    }
+}
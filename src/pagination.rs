@@ -0,0 +1,258 @@
+//! Pagination hints from response headers: RFC 8288 `Link` headers
+//! (`<…>; rel="next"`) and the `X-Next-Page` / `X-Prev-Page` pair some APIs
+//! (GitLab among them) send instead. Pure, so the parsing is testable.
+
+use url::Url;
+
+/// One link-value of a `Link` header: the target between `<` and `>` plus its
+/// parameters, with names lowercased and quoted values unescaped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub target: String,
+    pub params: Vec<(String, String)>,
+}
+
+impl Link {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// `rel` may hold several space-separated relation types (`rel="next last"`).
+    pub fn has_rel(&self, rel: &str) -> bool {
+        self.param("rel")
+            .is_some_and(|rels| rels.split_ascii_whitespace().any(|r| r.eq_ignore_ascii_case(rel)))
+    }
+}
+
+/// Parse a `Link` header value into its link-values.
+///
+/// Commas separate link-values but may also appear inside `<…>` and quoted
+/// parameter values, so this walks the string instead of splitting it.
+/// Malformed entries are skipped rather than failing the whole header.
+pub fn parse_link_header(value: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut chars = value.chars().peekable();
+
+    loop {
+        // Skip separators up to the next link-value.
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        match chars.next() {
+            None => break,
+            Some('<') => {}
+            Some(_) => {
+                // Not a link-value: drop everything up to the next comma.
+                while chars.next_if(|c| *c != ',').is_some() {}
+                continue;
+            }
+        }
+
+        let target: String = chars.by_ref().take_while(|c| *c != '>').collect();
+        let mut params = Vec::new();
+
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.next_if_eq(&';').is_none() {
+                break;
+            }
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+            let mut name = String::new();
+            while let Some(c) = chars.next_if(|c| !matches!(c, '=' | ';' | ',') && !c.is_whitespace()) {
+                name.push(c);
+            }
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+            let mut param_value = String::new();
+            if chars.next_if_eq(&'=').is_some() {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if chars.next_if_eq(&'"').is_some() {
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '"' => break,
+                            '\\' => param_value.extend(chars.next()),
+                            c => param_value.push(c),
+                        }
+                    }
+                } else {
+                    while let Some(c) = chars.next_if(|c| !matches!(c, ';' | ',')) {
+                        param_value.push(c);
+                    }
+                    param_value.truncate(param_value.trim_end().len());
+                }
+            }
+
+            if !name.is_empty() {
+                params.push((name.to_ascii_lowercase(), param_value));
+            }
+        }
+
+        // Anything unexpected after the params belongs to a malformed entry.
+        while chars.next_if(|c| *c != ',').is_some() {}
+
+        links.push(Link {
+            target: target.trim().to_string(),
+            params,
+        });
+    }
+
+    links
+}
+
+/// Where a pagination button leads.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PageTarget {
+    /// A URI reference from a `Link` header (possibly relative).
+    Url(String),
+    /// A bare page number from `X-Next-Page` / `X-Prev-Page`, applied to the
+    /// request's `page` query parameter.
+    Page(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageLinks {
+    pub next: Option<PageTarget>,
+    pub prev: Option<PageTarget>,
+}
+
+impl PageLinks {
+    pub fn is_empty(&self) -> bool {
+        self.next.is_none() && self.prev.is_none()
+    }
+}
+
+/// Find next / previous page targets in a response's headers. `Link` wins
+/// over `X-Next-Page` / `X-Prev-Page`; multiple `Link` headers are combined.
+pub fn page_links(headers: &[(String, String)]) -> PageLinks {
+    let links: Vec<Link> = headers
+        .iter()
+        .filter(|(k, _)| k.eq_ignore_ascii_case("link"))
+        .flat_map(|(_, v)| parse_link_header(v))
+        .collect();
+    let link_for = |rels: &[&str]| {
+        links
+            .iter()
+            .find(|l| !l.target.is_empty() && rels.iter().any(|r| l.has_rel(r)))
+            .map(|l| PageTarget::Url(l.target.clone()))
+    };
+    let header_page = |name: &str| {
+        headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.trim())
+            // GitLab sends an empty value on the first / last page.
+            .filter(|v| !v.is_empty())
+            .map(|v| {
+                if v.chars().all(|c| c.is_ascii_digit()) {
+                    PageTarget::Page(v.to_string())
+                } else {
+                    PageTarget::Url(v.to_string())
+                }
+            })
+    };
+
+    PageLinks {
+        next: link_for(&["next"]).or_else(|| header_page("x-next-page")),
+        prev: link_for(&["prev", "previous"]).or_else(|| header_page("x-prev-page")),
+    }
+}
+
+/// Turn a target into a full URL relative to the URL that was sent.
+/// Returns `None` when `base` can't be parsed and the target isn't absolute.
+pub fn resolve_target(base: &str, target: &PageTarget) -> Option<String> {
+    match target {
+        PageTarget::Url(reference) => match Url::parse(base) {
+            Ok(base) => base.join(reference).ok().map(String::from),
+            Err(_) => Url::parse(reference).ok().map(String::from),
+        },
+        PageTarget::Page(page) => {
+            let mut url = Url::parse(base).ok()?;
+            let pairs: Vec<(String, String)> = url
+                .query_pairs()
+                .filter(|(k, _)| k != "page")
+                .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                .collect();
+            url.query_pairs_mut()
+                .clear()
+                .extend_pairs(pairs)
+                .append_pair("page", page);
+            Some(url.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(k: &str, v: &str) -> (String, String) {
+        (k.to_string(), v.to_string())
+    }
+
+    #[test]
+    fn parses_multiple_links_and_their_parameters() {
+        let links = parse_link_header(
+            r#"<https://api.example.com/items?page=3>; rel="next", <https://api.example.com/items?page=9>; rel=last; title="Last, final""#,
+        );
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].target, "https://api.example.com/items?page=3");
+        assert!(links[0].has_rel("next"));
+        assert_eq!(links[1].param("rel"), Some("last"));
+        // Commas inside a quoted value don't split the header.
+        assert_eq!(links[1].param("title"), Some("Last, final"));
+    }
+
+    #[test]
+    fn rel_can_list_several_relation_types_in_any_case() {
+        let links = parse_link_header(r#"</p/2>;REL="Next Last""#);
+        assert!(links[0].has_rel("next"));
+        assert!(links[0].has_rel("last"));
+        assert!(!links[0].has_rel("prev"));
+    }
+
+    #[test]
+    fn quoted_values_are_unescaped_and_malformed_entries_skipped() {
+        let links = parse_link_header(r#"garbage; rel=next, <a,b>; title="say \"hi\""; rel=prev"#);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target, "a,b");
+        assert_eq!(links[0].param("title"), Some(r#"say "hi""#));
+        assert!(links[0].has_rel("prev"));
+    }
+
+    #[test]
+    fn page_links_prefers_link_and_falls_back_to_x_page_headers() {
+        let headers = vec![
+            header("Link", r#"</items?page=2>; rel="next""#),
+            header("link", r#"</items?page=0>; rel="previous""#),
+            header("X-Next-Page", "7"),
+        ];
+        let links = page_links(&headers);
+        assert_eq!(links.next, Some(PageTarget::Url("/items?page=2".into())));
+        assert_eq!(links.prev, Some(PageTarget::Url("/items?page=0".into())));
+
+        let gitlab = vec![header("X-Next-Page", "3"), header("X-Prev-Page", "")];
+        let links = page_links(&gitlab);
+        assert_eq!(links.next, Some(PageTarget::Page("3".into())));
+        assert_eq!(links.prev, None);
+
+        assert!(page_links(&[header("Content-Type", "text/plain")]).is_empty());
+    }
+
+    #[test]
+    fn resolves_relative_links_and_page_numbers_against_the_sent_url() {
+        let base = "https://api.example.com/v1/items?per_page=20&page=1";
+        assert_eq!(
+            resolve_target(base, &PageTarget::Url("/v1/items?page=2".into())).as_deref(),
+            Some("https://api.example.com/v1/items?page=2")
+        );
+        assert_eq!(
+            resolve_target(base, &PageTarget::Page("2".into())).as_deref(),
+            Some("https://api.example.com/v1/items?per_page=20&page=2")
+        );
+        // Unparseable base: only absolute targets can be followed.
+        assert_eq!(resolve_target("{{host}}/items", &PageTarget::Url("/items".into())), None);
+        assert_eq!(resolve_target("{{host}}/items", &PageTarget::Page("2".into())), None);
+    }
+}
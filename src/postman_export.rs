@@ -0,0 +1,405 @@
+//! "Export to Postman": write requests as a Postman v2.1 collection, the
+//! mirror of `postman_import`. Pure: builds the JSON, the caller writes it.
+
+use serde::Serialize;
+
+use crate::postman_import::CollectionFolder;
+use crate::types::{
+    ApiKeyPlacement, AuthConfig, AuthType, BodyType, FormDataValue, OAuth2ClientAuth, OAuth2Grant, RawSubtype, RequestData,
+};
+
+/// The `info.schema` Postman expects for v2.1 collections.
+pub const SCHEMA_V21: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+#[derive(Debug, Serialize)]
+struct Collection {
+    info: Info,
+    item: Vec<Item>,
+}
+
+#[derive(Debug, Serialize)]
+struct Info {
+    name: String,
+    schema: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum Item {
+    Folder { name: String, item: Vec<Item> },
+    Request { name: String, request: Box<PmRequest> },
+}
+
+#[derive(Debug, Serialize)]
+struct PmRequest {
+    method: String,
+    header: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<Body>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auth: Option<Auth>,
+    url: PmUrl,
+}
+
+#[derive(Debug, Serialize)]
+struct PmUrl {
+    raw: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    protocol: Option<String>,
+    host: Vec<String>,
+    path: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    query: Vec<KeyValue>,
+}
+
+#[derive(Debug, Serialize)]
+struct KeyValue {
+    key: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Body {
+    mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    formdata: Vec<FormParam>,
+}
+
+#[derive(Debug, Serialize)]
+struct FormParam {
+    key: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    src: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    disabled: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Auth {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    bearer: Vec<AuthParam>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    basic: Vec<AuthParam>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    apikey: Vec<AuthParam>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    oauth2: Vec<AuthParam>,
+}
+
+#[derive(Debug, Serialize)]
+struct AuthParam {
+    key: &'static str,
+    value: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+}
+
+fn param(key: &'static str, value: &str) -> AuthParam {
+    AuthParam {
+        key,
+        value: value.to_string(),
+        kind: "string",
+    }
+}
+
+/// Name a request by what it does, for sources (like History) that have no
+/// names: `GET https://api.example.com/users`.
+pub fn default_name(request: &RequestData) -> String {
+    format!("{} {}", request.method.as_str(), request.url)
+}
+
+/// Serialize `folder` (its name becomes the collection name) as pretty
+/// Postman v2.1 JSON.
+pub fn export_collection(folder: &CollectionFolder) -> String {
+    let collection = Collection {
+        info: Info {
+            name: folder.name.clone(),
+            schema: SCHEMA_V21,
+        },
+        item: items(folder),
+    };
+    serde_json::to_string_pretty(&collection).unwrap_or_default()
+}
+
+fn items(folder: &CollectionFolder) -> Vec<Item> {
+    folder
+        .requests
+        .iter()
+        .map(|r| Item::Request {
+            name: r.name.clone(),
+            request: Box::new(convert_request(&r.request)),
+        })
+        .chain(folder.folders.iter().map(|f| Item::Folder {
+            name: f.name.clone(),
+            item: items(f),
+        }))
+        .collect()
+}
+
+fn convert_request(request: &RequestData) -> PmRequest {
+    PmRequest {
+        method: request.method.to_string(),
+        header: request
+            .headers
+            .iter()
+            .filter(|(k, _)| !k.is_empty())
+            .map(|(k, v)| KeyValue { key: k.clone(), value: v.clone() })
+            .collect(),
+        body: convert_body(&request.body),
+        auth: convert_auth(&request.auth),
+        url: convert_url(&request.url),
+    }
+}
+
+/// Postman keeps the URL both raw and split up; the parts are what its UI
+/// shows (the Params table in particular). Query values stay as typed.
+fn convert_url(url: &str) -> PmUrl {
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, query),
+        None => (url, ""),
+    };
+    let (protocol, rest) = match base.split_once("://") {
+        Some((protocol, rest)) => (Some(protocol.to_string()), rest),
+        None => (None, base),
+    };
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    PmUrl {
+        raw: url.to_string(),
+        protocol,
+        host: host.split('.').filter(|s| !s.is_empty()).map(str::to_string).collect(),
+        path: path.split('/').filter(|s| !s.is_empty()).map(str::to_string).collect(),
+        query: query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                KeyValue { key: key.to_string(), value: value.to_string() }
+            })
+            .collect(),
+    }
+}
+
+fn convert_body(body: &BodyType) -> Option<Body> {
+    match body {
+        BodyType::None => None,
+        BodyType::Raw { content, .. } if content.is_empty() => None,
+        BodyType::Raw { content, subtype } => {
+            let language = match subtype {
+                RawSubtype::Json => "json",
+                RawSubtype::Xml => "xml",
+                RawSubtype::JavaScript => "javascript",
+                RawSubtype::Text => "text",
+            };
+            Some(Body {
+                mode: "raw",
+                raw: Some(content.clone()),
+                options: Some(serde_json::json!({ "raw": { "language": language } })),
+                formdata: Vec::new(),
+            })
+        }
+        BodyType::FormData(rows) => Some(Body {
+            mode: "formdata",
+            raw: None,
+            options: None,
+            formdata: rows
+                .iter()
+                .filter(|row| !row.key.is_empty())
+                .map(|row| match &row.value {
+                    FormDataValue::Text(text) => FormParam {
+                        key: row.key.clone(),
+                        kind: "text",
+                        value: Some(text.clone()),
+                        src: None,
+                        disabled: !row.enabled,
+                    },
+                    FormDataValue::File { path } => FormParam {
+                        key: row.key.clone(),
+                        kind: "file",
+                        value: None,
+                        src: Some(path.clone()),
+                        disabled: !row.enabled,
+                    },
+                })
+                .collect(),
+        }),
+    }
+}
+
+fn convert_auth(auth: &AuthConfig) -> Option<Auth> {
+    let make = |kind, bearer, basic, apikey| Some(Auth { kind, bearer, basic, apikey, oauth2: vec![] });
+    match auth.auth_type {
+        AuthType::None => None,
+        AuthType::Bearer => make("bearer", vec![param("token", &auth.bearer_token)], vec![], vec![]),
+        AuthType::Basic => make(
+            "basic",
+            vec![],
+            vec![param("username", &auth.basic_username), param("password", &auth.basic_password)],
+            vec![],
+        ),
+        AuthType::ApiKey => {
+            let placement = match auth.api_key_in {
+                ApiKeyPlacement::Header => "header",
+                ApiKeyPlacement::Query => "query",
+            };
+            make(
+                "apikey",
+                vec![],
+                vec![],
+                vec![param("key", &auth.api_key_name), param("value", &auth.api_key_value), param("in", placement)],
+            )
+        }
+        AuthType::OAuth2 => {
+            let oauth2 = &auth.oauth2;
+            let grant = match oauth2.grant {
+                OAuth2Grant::ClientCredentials => "client_credentials",
+                OAuth2Grant::AuthorizationCode => "authorization_code_with_pkce",
+            };
+            let client_authentication = match oauth2.client_auth {
+                OAuth2ClientAuth::BasicHeader => "header",
+                OAuth2ClientAuth::Body => "body",
+            };
+            Some(Auth {
+                kind: "oauth2",
+                bearer: vec![],
+                basic: vec![],
+                apikey: vec![],
+                oauth2: vec![
+                    param("grant_type", grant),
+                    param("accessTokenUrl", &oauth2.token_url),
+                    param("authUrl", &oauth2.auth_url),
+                    param("clientId", &oauth2.client_id),
+                    param("clientSecret", &oauth2.client_secret),
+                    param("scope", &oauth2.scopes),
+                    param("audience", &oauth2.audience),
+                    param("redirect_uri", &oauth2.redirect_uri),
+                    param("client_authentication", client_authentication),
+                ],
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::postman_import::{parse_collection, CollectionRequest};
+    use crate::types::{FormDataRow, HttpMethod};
+
+    fn named(name: &str, request: RequestData) -> CollectionRequest {
+        CollectionRequest { name: name.to_string(), request }
+    }
+
+    fn sample() -> CollectionFolder {
+        let mut get = RequestData::new(HttpMethod::GET, "{{baseUrl}}/pets?limit=10&q=a%20b".into());
+        get.headers = vec![("Accept".into(), "application/json".into())];
+        get.body = BodyType::None;
+        get.auth = AuthConfig {
+            auth_type: AuthType::ApiKey,
+            api_key_name: "api_key".into(),
+            api_key_value: "s3cret".into(),
+            api_key_in: ApiKeyPlacement::Query,
+            ..Default::default()
+        };
+
+        let mut post = RequestData::new(HttpMethod::POST, "https://petstore.example.com/pets".into());
+        post.headers = vec![("Content-Type".into(), "application/json".into())];
+        post.body = BodyType::Raw { content: "{\"name\": \"rex\"}".into(), subtype: RawSubtype::Json };
+        post.auth = AuthConfig { auth_type: AuthType::Bearer, bearer_token: "{{token}}".into(), ..Default::default() };
+
+        let mut upload = RequestData::new(HttpMethod::PUT, "https://petstore.example.com/pets/1/photo".into());
+        upload.body = BodyType::FormData(vec![
+            FormDataRow { enabled: true, key: "caption".into(), value: FormDataValue::Text("good boy".into()) },
+            FormDataRow { enabled: true, key: "photo".into(), value: FormDataValue::File { path: "/tmp/rex.png".into() } },
+            FormDataRow { enabled: false, key: "draft".into(), value: FormDataValue::Text("1".into()) },
+        ]);
+        upload.auth = AuthConfig {
+            auth_type: AuthType::Basic,
+            basic_username: "ada".into(),
+            basic_password: "pw".into(),
+            ..Default::default()
+        };
+
+        let mut adopt = RequestData::new(HttpMethod::POST, "https://petstore.example.com/pets/1/adopt".into());
+        adopt.body = BodyType::None;
+        adopt.auth = AuthConfig {
+            auth_type: AuthType::OAuth2,
+            oauth2: crate::types::OAuth2Config {
+                grant: OAuth2Grant::AuthorizationCode,
+                token_url: "https://auth.example.com/token".into(),
+                auth_url: "https://auth.example.com/authorize".into(),
+                client_id: "petstore".into(),
+                scopes: "pets:write pets:read".into(),
+                client_auth: OAuth2ClientAuth::Body,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        CollectionFolder {
+            name: "Petstore".into(),
+            requests: vec![named("List pets", get)],
+            folders: vec![CollectionFolder {
+                name: "Writes".into(),
+                requests: vec![named("Create pet", post), named("Upload photo", upload), named("Adopt pet", adopt)],
+                folders: vec![],
+            }],
+        }
+    }
+
+    /// What a round trip must preserve, in a comparable form.
+    fn essentials(r: &RequestData) -> String {
+        format!("{} {} {:?} {:?} {:?}", r.method.as_str(), r.url, r.headers, r.body, r.auth)
+    }
+
+    #[test]
+    fn exported_collections_reimport_unchanged() {
+        let original = sample();
+        let reimported = parse_collection(&export_collection(&original)).expect("export parses");
+        assert_eq!(reimported.name, "Petstore");
+        assert!(reimported.skipped.is_empty(), "{:?}", reimported.skipped);
+
+        let flat = |folder: &CollectionFolder| -> Vec<(String, String, String)> {
+            folder
+                .flatten()
+                .into_iter()
+                .map(|(path, r)| (path.join("/"), r.name.clone(), essentials(&r.request)))
+                .collect()
+        };
+        assert_eq!(flat(&reimported.root), flat(&original));
+    }
+
+    #[test]
+    fn urls_are_split_into_postman_parts() {
+        let json: serde_json::Value = serde_json::from_str(&export_collection(&sample())).unwrap();
+        assert_eq!(json["info"]["schema"], SCHEMA_V21);
+        let url = &json["item"][0]["request"]["url"];
+        assert_eq!(url["raw"], "{{baseUrl}}/pets?limit=10&q=a%20b");
+        assert_eq!(url["host"], serde_json::json!(["{{baseUrl}}"]));
+        assert_eq!(url["path"], serde_json::json!(["pets"]));
+        assert_eq!(
+            url["query"],
+            serde_json::json!([{ "key": "limit", "value": "10" }, { "key": "q", "value": "a%20b" }])
+        );
+        let upload = &json["item"][1]["item"][1]["request"];
+        assert_eq!(upload["url"]["protocol"], "https");
+        assert_eq!(upload["url"]["host"], serde_json::json!(["petstore", "example", "com"]));
+        assert_eq!(upload["body"]["formdata"][1], serde_json::json!({ "key": "photo", "type": "file", "src": "/tmp/rex.png" }));
+        assert_eq!(upload["body"]["formdata"][2]["disabled"], true);
+    }
+
+    #[test]
+    fn history_items_are_named_by_method_and_url() {
+        let request = RequestData::new(HttpMethod::DELETE, "https://x.test/a".into());
+        assert_eq!(default_name(&request), "DELETE https://x.test/a");
+    }
+}
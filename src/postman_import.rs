@@ -0,0 +1,772 @@
+//! "Import Postman Collection": read a Postman v2.1 collection file and turn
+//! its items into requests, keeping the folder tree.
+//!
+//! Pure: parsing and conversion only. Whatever has no equivalent here
+//! (scripts, collection variables, auth types we don't have, GraphQL and
+//! binary bodies) is left out and listed in `ImportedCollection::skipped` so
+//! the import summary can say what was lost.
+
+use anyhow::{bail, Context as _, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{
+    ApiKeyPlacement, AuthConfig, AuthType, BodyType, FormDataRow, FormDataValue, HttpMethod, OAuth2ClientAuth,
+    Collection as SavedCollection, OAuth2Config, OAuth2Grant, RawSubtype, RequestData, SavedRequest,
+};
+
+#[derive(Debug, Deserialize)]
+struct Collection {
+    info: Info,
+    #[serde(default)]
+    item: Vec<Item>,
+    #[serde(default)]
+    event: Vec<Event>,
+    auth: Option<Auth>,
+    #[serde(default)]
+    variable: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Info {
+    name: String,
+    #[serde(default)]
+    schema: String,
+}
+
+/// A folder (has `item`) or a request (has `request`).
+#[derive(Debug, Deserialize)]
+struct Item {
+    #[serde(default)]
+    name: String,
+    item: Option<Vec<Item>>,
+    request: Option<PmRequest>,
+    #[serde(default)]
+    event: Vec<Event>,
+    auth: Option<Auth>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)] // parsed once per item, never stored
+enum PmRequest {
+    /// Shorthand: just a URL, sent as GET.
+    Url(String),
+    Full {
+        #[serde(default = "default_method")]
+        method: String,
+        url: Option<PmUrl>,
+        #[serde(default)]
+        header: Vec<Header>,
+        body: Option<Body>,
+        auth: Option<Auth>,
+    },
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PmUrl {
+    Raw(String),
+    Parts {
+        raw: Option<String>,
+        #[serde(default)]
+        protocol: Option<String>,
+        #[serde(default)]
+        host: Option<StringOrList>,
+        #[serde(default)]
+        path: Option<StringOrList>,
+        #[serde(default)]
+        query: Vec<KeyValue>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StringOrList {
+    One(String),
+    Many(Vec<Value>),
+}
+
+impl StringOrList {
+    fn join(&self, sep: &str) -> String {
+        match self {
+            StringOrList::One(s) => s.clone(),
+            StringOrList::Many(parts) => parts
+                .iter()
+                .map(|p| match p {
+                    Value::String(s) => s.clone(),
+                    // Path variables: { "type": ..., "value": ... }
+                    other => other.get("value").and_then(Value::as_str).unwrap_or_default().to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(sep),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyValue {
+    #[serde(default)]
+    key: String,
+    value: Option<String>,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Header {
+    #[serde(default)]
+    key: String,
+    #[serde(default)]
+    value: String,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Body {
+    #[serde(default)]
+    mode: String,
+    raw: Option<String>,
+    options: Option<Value>,
+    #[serde(default)]
+    formdata: Vec<FormParam>,
+    #[serde(default)]
+    urlencoded: Vec<KeyValue>,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct FormParam {
+    #[serde(default)]
+    key: String,
+    value: Option<String>,
+    /// A path, or a list of paths for multi-file fields.
+    src: Option<Value>,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Auth {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    bearer: Vec<AuthParam>,
+    #[serde(default)]
+    basic: Vec<AuthParam>,
+    #[serde(default)]
+    apikey: Vec<AuthParam>,
+    #[serde(default)]
+    oauth2: Vec<AuthParam>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthParam {
+    key: String,
+    #[serde(default)]
+    value: Value,
+}
+
+impl Auth {
+    fn param(params: &[AuthParam], key: &str) -> String {
+        params
+            .iter()
+            .find(|p| p.key == key)
+            .map(|p| match &p.value {
+                Value::String(s) => s.clone(),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Event {
+    #[serde(default)]
+    listen: String,
+    script: Option<Script>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Script {
+    #[serde(default)]
+    exec: Option<StringOrList>,
+}
+
+impl Event {
+    /// Whether the script has any code (Postman saves empty ones too).
+    fn has_code(&self) -> bool {
+        self.script
+            .as_ref()
+            .and_then(|s| s.exec.as_ref())
+            .is_some_and(|exec| !exec.join("\n").trim().is_empty())
+    }
+}
+
+/// A converted collection: the folder tree plus what was left out.
+#[derive(Debug, Clone)]
+pub struct ImportedCollection {
+    pub name: String,
+    /// The collection itself; its `name` is the collection name.
+    pub root: CollectionFolder,
+    /// One line per feature that was not imported, e.g.
+    /// `"Users / Create: pre-request script"`.
+    pub skipped: Vec<String>,
+}
+
+/// A collection or folder with everything under it. Also the shape the
+/// workspace bundle stores collections in.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CollectionFolder {
+    pub name: String,
+    #[serde(default)]
+    pub folders: Vec<CollectionFolder>,
+    #[serde(default)]
+    pub requests: Vec<CollectionRequest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectionRequest {
+    pub name: String,
+    pub request: RequestData,
+}
+
+impl CollectionFolder {
+    /// `collection`'s subtree as the database holds it: `collections` and
+    /// `saved` are every collection and saved request, in their order.
+    pub fn from_saved(collection: &SavedCollection, collections: &[SavedCollection], saved: &[SavedRequest]) -> Self {
+        CollectionFolder {
+            name: collection.name.clone(),
+            folders: collections
+                .iter()
+                .filter(|c| c.parent_id == Some(collection.id))
+                .map(|c| Self::from_saved(c, collections, saved))
+                .collect(),
+            requests: saved
+                .iter()
+                .filter(|s| s.collection_id == collection.id)
+                .map(|s| CollectionRequest { name: s.name.clone(), request: s.request.clone() })
+                .collect(),
+        }
+    }
+
+    /// Every request in the tree with the folder path it sits under (empty at
+    /// the top level): a folder's own requests first, then each subfolder's.
+    pub fn flatten(&self) -> Vec<(Vec<String>, &CollectionRequest)> {
+        let mut out = Vec::new();
+        self.collect(&mut Vec::new(), &mut out);
+        out
+    }
+
+    fn collect<'a>(&'a self, path: &mut Vec<String>, out: &mut Vec<(Vec<String>, &'a CollectionRequest)>) {
+        for request in &self.requests {
+            out.push((path.clone(), request));
+        }
+        for folder in &self.folders {
+            path.push(folder.name.clone());
+            folder.collect(path, out);
+            path.pop();
+        }
+    }
+}
+
+/// Parse and convert a collection file. Fails only when the file is not a
+/// v2.x collection at all; unsupported parts are reported, not errors.
+pub fn parse_collection(json: &str) -> Result<ImportedCollection> {
+    let collection: Collection = serde_json::from_str(json).context("Not a Postman collection")?;
+    if !collection.info.schema.is_empty() && !collection.info.schema.contains("/collection/v2.") {
+        bail!("Unsupported collection schema {} (expected v2.1)", collection.info.schema);
+    }
+
+    let mut skipped = Vec::new();
+    let name = collection.info.name.clone();
+    note_scripts(&collection.event, &name, &mut skipped);
+    if !collection.variable.is_empty() {
+        skipped.push(format!("{}: {} collection variable(s)", name, collection.variable.len()));
+    }
+    let root = convert_items(&name, &collection.item, collection.auth.as_ref(), &[], &mut skipped);
+    Ok(ImportedCollection { name, root, skipped })
+}
+
+fn convert_items(
+    name: &str,
+    items: &[Item],
+    inherited_auth: Option<&Auth>,
+    path: &[&str],
+    skipped: &mut Vec<String>,
+) -> CollectionFolder {
+    let mut folder = CollectionFolder {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    for item in items {
+        let mut item_path = path.to_vec();
+        item_path.push(&item.name);
+        let label = item_path.join(" / ");
+        note_scripts(&item.event, &label, skipped);
+
+        if let Some(children) = &item.item {
+            let auth = item.auth.as_ref().or(inherited_auth);
+            folder.folders.push(convert_items(&item.name, children, auth, &item_path, skipped));
+        } else if let Some(request) = &item.request {
+            let request = convert_request(request, inherited_auth, &label, skipped);
+            folder.requests.push(CollectionRequest {
+                name: item.name.clone(),
+                request,
+            });
+        }
+    }
+    folder
+}
+
+fn note_scripts(events: &[Event], label: &str, skipped: &mut Vec<String>) {
+    for event in events.iter().filter(|e| e.has_code()) {
+        let what = match event.listen.as_str() {
+            "prerequest" => "pre-request script",
+            "test" => "test script",
+            _ => "script",
+        };
+        skipped.push(format!("{label}: {what}"));
+    }
+}
+
+fn convert_request(request: &PmRequest, inherited_auth: Option<&Auth>, label: &str, skipped: &mut Vec<String>) -> RequestData {
+    let (method, url, headers, body, auth) = match request {
+        PmRequest::Url(url) => return RequestData::new(HttpMethod::GET, url.clone()),
+        PmRequest::Full { method, url, header, body, auth } => (method, url, header, body, auth),
+    };
+
+    let method = HttpMethod::from_str(method).unwrap_or_else(|| {
+        skipped.push(format!("{label}: method {method} (sent as GET)"));
+        HttpMethod::GET
+    });
+    let mut data = RequestData::new(method, url.as_ref().map(convert_url).unwrap_or_default());
+
+    for h in headers {
+        if h.key.is_empty() {
+            continue;
+        }
+        if h.disabled {
+            skipped.push(format!("{label}: disabled header {}", h.key));
+            continue;
+        }
+        data.headers.push((h.key.clone(), h.value.clone()));
+    }
+
+    data.body = match body {
+        Some(body) if !body.disabled => convert_body(body, &mut data.headers, label, skipped),
+        _ => BodyType::None,
+    };
+
+    // A request without its own auth inherits its folder's (or the
+    // collection's); `"type": "inherit"` says the same thing explicitly.
+    let auth = match auth {
+        Some(auth) if auth.kind != "inherit" => Some(auth),
+        _ => inherited_auth,
+    };
+    if let Some(auth) = auth {
+        data.auth = convert_auth(auth, label, skipped);
+    }
+    data
+}
+
+/// The URL as typed in Postman, query string included. Falls back to
+/// rebuilding it from its parts when there is no `raw`.
+fn convert_url(url: &PmUrl) -> String {
+    match url {
+        PmUrl::Raw(raw) => raw.clone(),
+        PmUrl::Parts { raw: Some(raw), .. } => raw.clone(),
+        PmUrl::Parts { raw: None, protocol, host, path, query } => {
+            let mut out = String::new();
+            if let Some(protocol) = protocol {
+                out.push_str(protocol);
+                out.push_str("://");
+            }
+            if let Some(host) = host {
+                out.push_str(&host.join("."));
+            }
+            if let Some(path) = path {
+                let path = path.join("/");
+                if !path.is_empty() {
+                    out.push('/');
+                    out.push_str(&path);
+                }
+            }
+            let pairs: Vec<String> = query
+                .iter()
+                .filter(|q| !q.disabled && !q.key.is_empty())
+                .map(|q| match &q.value {
+                    Some(value) => format!("{}={}", q.key, value),
+                    None => q.key.clone(),
+                })
+                .collect();
+            if !pairs.is_empty() {
+                out.push('?');
+                out.push_str(&pairs.join("&"));
+            }
+            out
+        }
+    }
+}
+
+fn convert_body(body: &Body, headers: &mut Vec<(String, String)>, label: &str, skipped: &mut Vec<String>) -> BodyType {
+    match body.mode.as_str() {
+        "raw" => {
+            let language = body
+                .options
+                .as_ref()
+                .and_then(|o| o.pointer("/raw/language"))
+                .and_then(Value::as_str)
+                .unwrap_or("text");
+            let subtype = match language {
+                "json" => RawSubtype::Json,
+                "xml" => RawSubtype::Xml,
+                "javascript" => RawSubtype::JavaScript,
+                _ => RawSubtype::Text,
+            };
+            BodyType::Raw {
+                content: body.raw.clone().unwrap_or_default(),
+                subtype,
+            }
+        }
+        "formdata" => BodyType::FormData(
+            body.formdata
+                .iter()
+                .filter(|p| !p.key.is_empty())
+                .map(|p| FormDataRow {
+                    enabled: !p.disabled,
+                    key: p.key.clone(),
+                    value: if p.kind == "file" {
+                        FormDataValue::File { path: form_file(p, label, skipped) }
+                    } else {
+                        FormDataValue::Text(p.value.clone().unwrap_or_default())
+                    },
+                })
+                .collect(),
+        ),
+        // No url-encoded body type here: send the encoded pairs as raw text
+        // with the matching Content-Type.
+        "urlencoded" => {
+            let content = body
+                .urlencoded
+                .iter()
+                .filter(|p| !p.disabled && !p.key.is_empty())
+                .map(|p| format!("{}={}", form_encode(&p.key), form_encode(p.value.as_deref().unwrap_or_default())))
+                .collect::<Vec<_>>()
+                .join("&");
+            if !headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("content-type")) {
+                headers.push(("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string()));
+            }
+            BodyType::Raw {
+                content,
+                subtype: RawSubtype::Text,
+            }
+        }
+        "" => BodyType::None,
+        other => {
+            skipped.push(format!("{label}: {other} body"));
+            BodyType::None
+        }
+    }
+}
+
+/// Path of a form-data file field. Postman allows several files per field;
+/// only the first is kept.
+fn form_file(param: &FormParam, label: &str, skipped: &mut Vec<String>) -> String {
+    match &param.src {
+        Some(Value::String(path)) => path.clone(),
+        Some(Value::Array(paths)) => {
+            if paths.len() > 1 {
+                skipped.push(format!("{label}: {} extra file(s) for form field {}", paths.len() - 1, param.key));
+            }
+            paths.first().and_then(Value::as_str).unwrap_or_default().to_string()
+        }
+        _ => String::new(),
+    }
+}
+
+fn form_encode(s: &str) -> String {
+    url::form_urlencoded::byte_serialize(s.as_bytes()).collect()
+}
+
+fn convert_auth(auth: &Auth, label: &str, skipped: &mut Vec<String>) -> AuthConfig {
+    match auth.kind.as_str() {
+        "bearer" => AuthConfig {
+            auth_type: AuthType::Bearer,
+            bearer_token: Auth::param(&auth.bearer, "token"),
+            ..Default::default()
+        },
+        "basic" => AuthConfig {
+            auth_type: AuthType::Basic,
+            basic_username: Auth::param(&auth.basic, "username"),
+            basic_password: Auth::param(&auth.basic, "password"),
+            ..Default::default()
+        },
+        "apikey" => AuthConfig {
+            auth_type: AuthType::ApiKey,
+            api_key_name: Auth::param(&auth.apikey, "key"),
+            api_key_value: Auth::param(&auth.apikey, "value"),
+            api_key_in: if Auth::param(&auth.apikey, "in") == "query" {
+                ApiKeyPlacement::Query
+            } else {
+                ApiKeyPlacement::Header
+            },
+            ..Default::default()
+        },
+        "oauth2" => {
+            let param = |key| Auth::param(&auth.oauth2, key);
+            let grant = match param("grant_type").as_str() {
+                "client_credentials" | "" => OAuth2Grant::ClientCredentials,
+                "authorization_code" | "authorization_code_with_pkce" => OAuth2Grant::AuthorizationCode,
+                other => {
+                    skipped.push(format!("{label}: OAuth 2.0 {other} grant"));
+                    return AuthConfig::default();
+                }
+            };
+            let redirect_uri = param("redirect_uri");
+            AuthConfig {
+                auth_type: AuthType::OAuth2,
+                oauth2: OAuth2Config {
+                    grant,
+                    token_url: param("accessTokenUrl"),
+                    auth_url: param("authUrl"),
+                    client_id: param("clientId"),
+                    client_secret: param("clientSecret"),
+                    scopes: param("scope"),
+                    audience: param("audience"),
+                    redirect_uri: if redirect_uri.is_empty() {
+                        crate::types::DEFAULT_OAUTH2_REDIRECT_URI.to_string()
+                    } else {
+                        redirect_uri
+                    },
+                    client_auth: if param("client_authentication") == "body" {
+                        OAuth2ClientAuth::Body
+                    } else {
+                        OAuth2ClientAuth::BasicHeader
+                    },
+                },
+                ..Default::default()
+            }
+        }
+        "noauth" => AuthConfig::default(),
+        other => {
+            skipped.push(format!("{label}: {other} auth"));
+            AuthConfig::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+      "info": {
+        "name": "Petstore",
+        "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+      },
+      "auth": { "type": "bearer", "bearer": [{ "key": "token", "value": "{{token}}", "type": "string" }] },
+      "variable": [{ "key": "baseUrl", "value": "https://petstore.example.com" }],
+      "event": [{ "listen": "prerequest", "script": { "type": "text/javascript", "exec": [""] } }],
+      "item": [
+        {
+          "name": "Health",
+          "request": { "method": "GET", "url": "{{baseUrl}}/health", "auth": { "type": "noauth" } }
+        },
+        {
+          "name": "Pets",
+          "item": [
+            {
+              "name": "List pets",
+              "event": [{ "listen": "test", "script": { "exec": ["pm.test('ok', () => {});"] } }],
+              "request": {
+                "method": "GET",
+                "header": [
+                  { "key": "Accept", "value": "application/json" },
+                  { "key": "X-Debug", "value": "1", "disabled": true }
+                ],
+                "url": {
+                  "raw": "{{baseUrl}}/pets?limit=10",
+                  "host": ["{{baseUrl}}"],
+                  "path": ["pets"],
+                  "query": [{ "key": "limit", "value": "10" }]
+                }
+              }
+            },
+            {
+              "name": "Create pet",
+              "request": {
+                "method": "POST",
+                "header": [{ "key": "Content-Type", "value": "application/json" }],
+                "body": { "mode": "raw", "raw": "{\"name\": \"rex\"}", "options": { "raw": { "language": "json" } } },
+                "url": "{{baseUrl}}/pets"
+              }
+            },
+            {
+              "name": "Photos",
+              "item": [
+                {
+                  "name": "Upload photo",
+                  "request": {
+                    "method": "PUT",
+                    "auth": { "type": "digest", "digest": [] },
+                    "body": {
+                      "mode": "formdata",
+                      "formdata": [
+                        { "key": "caption", "value": "good boy", "type": "text" },
+                        { "key": "photo", "src": ["/tmp/rex.png", "/tmp/rex2.png"], "type": "file" },
+                        { "key": "draft", "value": "1", "type": "text", "disabled": true }
+                      ]
+                    },
+                    "url": {
+                      "protocol": "https",
+                      "host": ["petstore", "example", "com"],
+                      "path": ["pets", ":id", "photo"],
+                      "query": [{ "key": "replace", "value": "true" }, { "key": "off", "value": "x", "disabled": true }]
+                    }
+                  }
+                }
+              ]
+            }
+          ]
+        },
+        {
+          "name": "Login",
+          "request": {
+            "method": "POST",
+            "auth": { "type": "apikey", "apikey": [{ "key": "key", "value": "api_key" }, { "key": "value", "value": "s3cret" }, { "key": "in", "value": "query" }] },
+            "body": { "mode": "urlencoded", "urlencoded": [{ "key": "user", "value": "ada lovelace" }, { "key": "pw", "value": "a&b" }] },
+            "url": "{{baseUrl}}/login"
+          }
+        },
+        {
+          "name": "Query",
+          "request": { "method": "POST", "body": { "mode": "graphql", "graphql": { "query": "{ pets }" } }, "url": "{{baseUrl}}/graphql" }
+        }
+      ]
+    }"#;
+
+    fn sample() -> ImportedCollection {
+        parse_collection(SAMPLE).expect("sample parses")
+    }
+
+    #[test]
+    fn keeps_the_folder_tree_in_collection_order() {
+        let collection = sample();
+        assert_eq!(collection.name, "Petstore");
+        let tree: Vec<(String, String)> = collection
+            .root
+            .flatten()
+            .into_iter()
+            .map(|(path, r)| (path.join("/"), r.name.clone()))
+            .collect();
+        assert_eq!(
+            tree,
+            vec![
+                ("".into(), "Health".into()),
+                ("".into(), "Login".into()),
+                ("".into(), "Query".into()),
+                ("Pets".into(), "List pets".into()),
+                ("Pets".into(), "Create pet".into()),
+                ("Pets/Photos".into(), "Upload photo".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_method_url_headers_and_bodies() {
+        let collection = sample();
+        let pets = &collection.root.folders[0];
+
+        let list = &pets.requests[0].request;
+        assert_eq!(list.method, HttpMethod::GET);
+        assert_eq!(list.url, "{{baseUrl}}/pets?limit=10");
+        assert_eq!(list.headers, vec![("Accept".to_string(), "application/json".to_string())]);
+        assert!(matches!(list.body, BodyType::None));
+
+        let create = &pets.requests[1].request;
+        assert_eq!(create.method, HttpMethod::POST);
+        match &create.body {
+            BodyType::Raw { content, subtype } => {
+                assert_eq!(content, "{\"name\": \"rex\"}");
+                assert_eq!(*subtype, RawSubtype::Json);
+            }
+            other => panic!("expected raw body, got {other:?}"),
+        }
+
+        let upload = &pets.folders[0].requests[0].request;
+        assert_eq!(upload.method, HttpMethod::PUT);
+        assert_eq!(upload.url, "https://petstore.example.com/pets/:id/photo?replace=true");
+        let BodyType::FormData(rows) = &upload.body else {
+            panic!("expected form-data");
+        };
+        let rows: Vec<_> = rows.iter().map(|r| (r.enabled, r.key.as_str(), r.value.clone())).collect();
+        assert_eq!(
+            rows,
+            vec![
+                (true, "caption", FormDataValue::Text("good boy".into())),
+                (true, "photo", FormDataValue::File { path: "/tmp/rex.png".into() }),
+                (false, "draft", FormDataValue::Text("1".into())),
+            ]
+        );
+
+        let login = &collection.root.requests[1].request;
+        match &login.body {
+            BodyType::Raw { content, .. } => assert_eq!(content, "user=ada+lovelace&pw=a%26b"),
+            other => panic!("expected raw body, got {other:?}"),
+        }
+        assert!(login.headers.contains(&("Content-Type".into(), "application/x-www-form-urlencoded".into())));
+    }
+
+    #[test]
+    fn auth_is_inherited_unless_overridden() {
+        let collection = sample();
+        // Collection-level bearer reaches requests without their own auth…
+        let list = &collection.root.folders[0].requests[0].request;
+        assert_eq!(list.auth.auth_type, AuthType::Bearer);
+        assert_eq!(list.auth.bearer_token, "{{token}}");
+        // …"noauth" turns it off…
+        assert_eq!(collection.root.requests[0].request.auth.auth_type, AuthType::None);
+        // …and API-key auth maps its placement.
+        let login = &collection.root.requests[1].request.auth;
+        assert_eq!(login.auth_type, AuthType::ApiKey);
+        assert_eq!((login.api_key_name.as_str(), login.api_key_value.as_str()), ("api_key", "s3cret"));
+        assert_eq!(login.api_key_in, ApiKeyPlacement::Query);
+        // Unsupported auth falls back to none.
+        let upload = &collection.root.folders[0].folders[0].requests[0].request;
+        assert_eq!(upload.auth.auth_type, AuthType::None);
+    }
+
+    #[test]
+    fn unsupported_parts_are_reported_not_fatal() {
+        assert_eq!(
+            sample().skipped,
+            vec![
+                "Petstore: 1 collection variable(s)",
+                "Pets / List pets: test script",
+                "Pets / List pets: disabled header X-Debug",
+                "Pets / Photos / Upload photo: 1 extra file(s) for form field photo",
+                "Pets / Photos / Upload photo: digest auth",
+                "Query: graphql body",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_v2_collections() {
+        assert!(parse_collection("[]").is_err());
+        assert!(parse_collection(r#"{ "info": { "name": "x", "schema": "https://schema.getpostman.com/json/collection/v1.0.0/collection.json" } }"#).is_err());
+        let shorthand = parse_collection(r#"{ "info": { "name": "x" }, "item": [{ "name": "a", "request": "https://a.test" }] }"#)
+            .unwrap();
+        assert_eq!(shorthand.root.requests[0].request.url, "https://a.test");
+    }
+}
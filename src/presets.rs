@@ -0,0 +1,153 @@
+//! Request presets: named bundles of headers and query params kept in the
+//! database and applied to a request at send time. A request stores only
+//! the ids of the presets it uses, so editing a preset changes every request
+//! that references it. Pure.
+
+use crate::types::{ParamEncoding, Preset};
+use crate::url_params;
+
+/// The presets `ids` refers to, in the order they were applied. Ids of
+/// presets since deleted are skipped.
+pub fn applied(presets: &[Preset], ids: &[i64]) -> Vec<Preset> {
+    ids.iter().filter_map(|id| presets.iter().find(|p| p.id == *id)).cloned().collect()
+}
+
+/// The headers sent: the request's defaults (its predefined rows), then the
+/// presets', then the ones the user added. A preset header replaces a
+/// default of the same name and gives way to one the user added; between
+/// presets, the later one wins.
+pub fn merge_headers(
+    defaults: Vec<(String, String)>,
+    presets: &[Preset],
+    custom: Vec<(String, String)>,
+) -> Vec<(String, String)> {
+    let mut from_presets: Vec<(String, String)> = Vec::new();
+    for (key, value) in presets.iter().flat_map(|p| &p.headers).filter(|(k, _)| !k.trim().is_empty()) {
+        from_presets.retain(|(k, _)| !k.eq_ignore_ascii_case(key));
+        from_presets.push((key.clone(), value.clone()));
+    }
+    from_presets.retain(|(key, _)| !custom.iter().any(|(k, _)| k.eq_ignore_ascii_case(key)));
+    let mut headers: Vec<(String, String)> = defaults
+        .into_iter()
+        .filter(|(key, _)| !from_presets.iter().any(|(k, _)| k.eq_ignore_ascii_case(key)))
+        .collect();
+    headers.extend(from_presets);
+    headers.extend(custom);
+    headers
+}
+
+/// `url` with the presets' query params appended, except those whose key
+/// the URL already has.
+pub fn add_params(url: &str, presets: &[Preset], encoding: ParamEncoding) -> String {
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let mut present: Vec<String> = url_params::parse_query_params(rest).into_iter().map(|(k, _)| k).collect();
+    let mut added = Vec::new();
+    for (key, value) in presets.iter().flat_map(|p| &p.params) {
+        if key.trim().is_empty() || present.contains(key) {
+            continue;
+        }
+        present.push(key.clone());
+        added.push(url_params::QueryParam::new(key.clone(), value.clone(), true));
+    }
+    if added.is_empty() {
+        return url.to_string();
+    }
+    // Build on an empty base to get just the encoded query.
+    let query = url_params::build_url_with_params_encoded("", &added, encoding);
+    let separator = match rest.find('?') {
+        None => "?",
+        Some(_) if rest.ends_with(['?', '&']) => "",
+        Some(_) => "&",
+    };
+    let mut out = format!("{rest}{separator}{}", &query[1..]);
+    if let Some(fragment) = fragment {
+        out.push('#');
+        out.push_str(fragment);
+    }
+    out
+}
+
+/// `Name: value` per line, as the preset dialog edits headers.
+pub fn headers_to_text(headers: &[(String, String)]) -> String {
+    headers.iter().map(|(k, v)| format!("{k}: {v}")).collect::<Vec<_>>().join("\n")
+}
+
+/// `key=value` per line, as the preset dialog edits params.
+pub fn params_to_text(params: &[(String, String)]) -> String {
+    params.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join("\n")
+}
+
+/// Lines of `key<separator>value`, blank lines skipped, both sides trimmed.
+/// A line without the separator is a key with an empty value.
+pub fn parse_lines(text: &str, separator: char) -> Vec<(String, String)> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| match line.split_once(separator) {
+            Some((key, value)) => (key.trim().to_string(), value.trim().to_string()),
+            None => (line.trim().to_string(), String::new()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn preset(id: i64, headers: &[(&str, &str)], params: &[(&str, &str)]) -> Preset {
+        Preset { id, name: format!("p{id}"), headers: pairs(headers), params: pairs(params) }
+    }
+
+    #[test]
+    fn applied_keeps_order_and_skips_deleted_presets() {
+        let all = [preset(1, &[], &[]), preset(2, &[], &[])];
+        let ids: Vec<i64> = applied(&all, &[2, 9, 1]).iter().map(|p| p.id).collect();
+        assert_eq!(ids, [2, 1]);
+    }
+
+    #[test]
+    fn preset_headers_replace_defaults_and_give_way_to_custom_ones() {
+        let tenant = preset(1, &[("X-Tenant", "acme"), ("Accept", "application/xml"), ("X-Trace", "1")], &[]);
+        let later = preset(2, &[("x-tenant", "globex")], &[]);
+        let merged = merge_headers(
+            pairs(&[("Accept", "*/*"), ("User-Agent", "poopman")]),
+            &[tenant, later],
+            pairs(&[("X-Trace", "mine")]),
+        );
+        assert_eq!(
+            merged,
+            pairs(&[
+                ("User-Agent", "poopman"),
+                ("Accept", "application/xml"),
+                ("x-tenant", "globex"),
+                ("X-Trace", "mine"),
+            ])
+        );
+    }
+
+    #[test]
+    fn preset_params_are_appended_unless_the_url_has_them() {
+        let p = preset(1, &[], &[("tenant", "a b"), ("page", "9")]);
+        assert_eq!(
+            add_params("https://x.test/items?page=2", std::slice::from_ref(&p), ParamEncoding::Strict),
+            "https://x.test/items?page=2&tenant=a%20b"
+        );
+        assert_eq!(add_params("https://x.test/#top", &[p], ParamEncoding::Strict), "https://x.test/?tenant=a%20b&page=9#top");
+        assert_eq!(add_params("https://x.test/", &[], ParamEncoding::Strict), "https://x.test/");
+    }
+
+    #[test]
+    fn text_round_trips() {
+        let headers = pairs(&[("X-Tenant", "acme"), ("Authorization", "Bearer a:b")]);
+        assert_eq!(parse_lines(&headers_to_text(&headers), ':'), headers);
+        let params = pairs(&[("q", "a=b"), ("flag", "")]);
+        assert_eq!(parse_lines(&params_to_text(&params), '='), params);
+        assert_eq!(parse_lines("\n  X-A :  1 \n\nX-B\n", ':'), pairs(&[("X-A", "1"), ("X-B", "")]));
+    }
+}
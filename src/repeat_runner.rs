@@ -0,0 +1,197 @@
+//! "Send ×N": the record of a batch of repeated sends of one request, and the
+//! min / avg / p95 summary shown for it. Execution lives in `http_client`
+//! (`HttpClient::start_repeat`); this module is pure so the numbers are testable.
+
+use serde::{Deserialize, Serialize};
+
+/// Batch sizes offered in the Send dropdown; the first is the count
+/// field's default.
+pub const REPEAT_COUNTS: [usize; 3] = [10, 50, 100];
+
+/// Largest batch the count field accepts.
+pub const MAX_REPEAT_COUNT: usize = 1000;
+
+/// Default for the "at a time" field, used when "parallel" is selected.
+pub const PARALLEL_LIMIT: usize = 5;
+
+/// Most attempts the "at a time" field lets run at once.
+pub const MAX_PARALLEL: usize = 50;
+
+/// The count field next to "×N": a whole number from 1 to
+/// `MAX_REPEAT_COUNT`, or what is wrong with it.
+pub fn parse_count(text: &str) -> Result<usize, String> {
+    parse_bounded(text, MAX_REPEAT_COUNT, "Send ×N count")
+}
+
+/// The "at a time" field: a whole number from 1 to `MAX_PARALLEL`, or what
+/// is wrong with it.
+pub fn parse_concurrency(text: &str) -> Result<usize, String> {
+    parse_bounded(text, MAX_PARALLEL, "Attempts at a time")
+}
+
+fn parse_bounded(text: &str, max: usize, what: &str) -> Result<usize, String> {
+    match text.trim().parse::<usize>() {
+        Ok(n) if (1..=max).contains(&n) => Ok(n),
+        _ => Err(format!("{what} must be a whole number from 1 to {max}")),
+    }
+}
+
+/// One send within a batch. `status` is `None` for a network error, whose
+/// message is kept in `error`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attempt {
+    /// 0-based position in the batch (attempts may finish out of order).
+    pub index: usize,
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+impl Attempt {
+    /// Any response below 400 counts as a success for the summary.
+    pub fn is_success(&self) -> bool {
+        self.status.is_some_and(|s| s < 400)
+    }
+}
+
+/// Everything recorded about one batch — also what gets serialized into the
+/// single history row the batch produces.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepeatReport {
+    pub requested: usize,
+    pub concurrency: usize,
+    /// Wall-clock time of the whole batch.
+    pub total_ms: u64,
+    /// True when the user stopped the batch early.
+    pub canceled: bool,
+    /// Completed attempts, ordered by `index`.
+    pub attempts: Vec<Attempt>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RepeatSummary {
+    pub completed: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub min_ms: u64,
+    pub avg_ms: u64,
+    pub p95_ms: u64,
+}
+
+impl RepeatReport {
+    pub fn new(requested: usize, concurrency: usize, total_ms: u64, canceled: bool, mut attempts: Vec<Attempt>) -> Self {
+        attempts.sort_by_key(|a| a.index);
+        Self {
+            requested,
+            concurrency,
+            total_ms,
+            canceled,
+            attempts,
+        }
+    }
+
+    /// Durations cover every completed attempt, failures included — a batch of
+    /// fast connection-refused errors should not look like a fast server.
+    pub fn summary(&self) -> RepeatSummary {
+        let mut durations: Vec<u64> = self.attempts.iter().map(|a| a.duration_ms).collect();
+        if durations.is_empty() {
+            return RepeatSummary::default();
+        }
+        durations.sort_unstable();
+        let succeeded = self.attempts.iter().filter(|a| a.is_success()).count();
+        RepeatSummary {
+            completed: durations.len(),
+            succeeded,
+            failed: durations.len() - succeeded,
+            min_ms: durations[0],
+            avg_ms: durations.iter().sum::<u64>() / durations.len() as u64,
+            p95_ms: percentile(&durations, 95),
+        }
+    }
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice.
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(index: usize, status: Option<u16>, duration_ms: u64) -> Attempt {
+        Attempt {
+            index,
+            status,
+            duration_ms,
+            error: status.is_none().then(|| "connection refused".to_string()),
+        }
+    }
+
+    #[test]
+    fn summary_of_an_empty_batch_is_all_zero() {
+        assert_eq!(RepeatReport::default().summary(), RepeatSummary::default());
+    }
+
+    #[test]
+    fn summary_counts_failures_and_averages_every_attempt() {
+        let report = RepeatReport::new(
+            4,
+            1,
+            100,
+            false,
+            vec![
+                attempt(0, Some(200), 10),
+                attempt(1, Some(503), 30),
+                attempt(2, None, 2),
+                attempt(3, Some(304), 38),
+            ],
+        );
+        let s = report.summary();
+        assert_eq!((s.completed, s.succeeded, s.failed), (4, 2, 2));
+        assert_eq!((s.min_ms, s.avg_ms), (2, 20));
+    }
+
+    #[test]
+    fn p95_uses_nearest_rank() {
+        // 1..=20 ms: rank ceil(0.95 * 20) = 19.
+        let attempts = (0..20).map(|i| attempt(i, Some(200), i as u64 + 1)).collect();
+        assert_eq!(RepeatReport::new(20, 5, 0, false, attempts).summary().p95_ms, 19);
+
+        // A single attempt is its own p95.
+        let one = RepeatReport::new(1, 1, 0, false, vec![attempt(0, Some(200), 7)]);
+        assert_eq!(one.summary().p95_ms, 7);
+    }
+
+    #[test]
+    fn attempts_are_ordered_by_index_regardless_of_completion_order() {
+        let report = RepeatReport::new(
+            3,
+            3,
+            0,
+            false,
+            vec![attempt(2, Some(200), 1), attempt(0, Some(200), 1), attempt(1, Some(200), 1)],
+        );
+        let order: Vec<usize> = report.attempts.iter().map(|a| a.index).collect();
+        assert_eq!(order, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn count_and_concurrency_fields_accept_only_their_range() {
+        assert_eq!(parse_count(" 25 "), Ok(25));
+        assert_eq!(parse_count("1000"), Ok(MAX_REPEAT_COUNT));
+        for bad in ["", "0", "1001", "-3", "2.5", "ten"] {
+            assert!(parse_count(bad).is_err(), "{bad:?}");
+        }
+        assert_eq!(parse_concurrency(&PARALLEL_LIMIT.to_string()), Ok(PARALLEL_LIMIT));
+        assert!(parse_concurrency("51").unwrap_err().contains("1 to 50"));
+    }
+
+    #[test]
+    fn report_round_trips_through_json() {
+        let report = RepeatReport::new(2, 1, 15, true, vec![attempt(0, None, 5)]);
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<RepeatReport>(&json).unwrap(), report);
+    }
+}
@@ -0,0 +1,194 @@
+//! A request as it goes on the wire, built from the request as edited or
+//! saved: `{{vars}}` resolved, presets applied, headers merged and filtered,
+//! path variables filled in, auth folded in, the body left off a GET or
+//! HEAD. The editor's Send and the collection runner both build through
+//! here, so they cannot drift in what they send. Pure.
+
+use std::collections::HashMap;
+
+use crate::types::{Preset, RequestData};
+
+/// What a send resolves a request against, beside the request itself.
+pub struct SendOptions<'a> {
+    /// Headers beneath the request's own (the editor's predefined rows): a
+    /// preset header of the same name replaces them, where the request's
+    /// own headers win over the presets'.
+    pub default_headers: Vec<(String, String)>,
+    /// Every preset; the request's `presets` pick the ones applied.
+    pub presets: &'a [Preset],
+    pub vars: &'a HashMap<String, String>,
+}
+
+/// A built request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedRequest {
+    /// `{{vars}}` resolved, the URL still with its `:name` path segments,
+    /// the presets still only referred to by id and the auth config apart
+    /// from the headers: what history records.
+    pub request: RequestData,
+    /// What goes on the wire: `method`, the final `url` and `headers`, and
+    /// the `body`. A form-data body stays rows here; the client encodes it
+    /// (and reads its files) as it sends.
+    pub sent: RequestData,
+}
+
+/// Build `request` for sending. Fails when the URL is empty or cannot be
+/// sent, or a path variable has no value.
+pub fn build(request: &RequestData, opts: &SendOptions) -> Result<PreparedRequest, String> {
+    let request = resolve(request, opts)?;
+    let sent = wire(&request, opts)?;
+    Ok(PreparedRequest { request, sent })
+}
+
+/// The first half of `build`: `PreparedRequest::request`. The editor keeps
+/// it apart so a request whose path variables are missing can still be
+/// reported as a failed send.
+pub fn resolve(request: &RequestData, opts: &SendOptions) -> Result<RequestData, String> {
+    let url = request.url.trim();
+    if url.is_empty() {
+        return Err("the URL is empty".to_string());
+    }
+    // Vars before the scheme is added, so a value like "https://host"
+    // doesn't get a second one.
+    let url = crate::url_params::with_scheme(&crate::variables::substitute(url, opts.vars));
+    if let Some(problem) = crate::url_params::url_problem(&url) {
+        return Err(format!("{url}: {problem}"));
+    }
+
+    // A default a preset replaces is left out here, so that on the wire
+    // the preset's value goes out instead, where the user's own headers
+    // keep winning over it.
+    let presets = crate::presets::applied(opts.presets, &request.presets);
+    let preset_sets = |key: &str| presets.iter().flat_map(|p| &p.headers).any(|(k, _)| k.eq_ignore_ascii_case(key));
+    let mut headers: Vec<(String, String)> =
+        opts.default_headers.iter().filter(|(key, _)| !preset_sets(key)).cloned().collect();
+    headers.extend(request.headers.iter().cloned());
+    Ok(crate::variables::substitute_request(&RequestData { url, headers, ..request.clone() }, opts.vars))
+}
+
+/// The second half of `build`: `resolved` (from `resolve`, or as history
+/// recorded it) as it goes on the wire. The presets it refers to are
+/// applied here, as they are now, so a preset edited since the request was
+/// recorded sends its current values.
+pub fn wire(resolved: &RequestData, opts: &SendOptions) -> Result<RequestData, String> {
+    let presets: Vec<Preset> = crate::presets::applied(opts.presets, &resolved.presets)
+        .into_iter()
+        .map(|preset| Preset {
+            headers: crate::variables::substitute_pairs(&preset.headers, opts.vars),
+            params: crate::variables::substitute_pairs(&preset.params, opts.vars),
+            ..preset
+        })
+        .collect();
+    let url = crate::presets::add_params(&resolved.url, &presets, resolved.param_encoding);
+    let url = crate::url_params::substitute_path_variables(&url, &resolved.path_variables)?;
+
+    let headers = crate::presets::merge_headers(Vec::new(), &presets, resolved.headers.clone());
+    // A Content-Type the user added overrides the one kept in sync with the body.
+    let headers = crate::content_type::last_content_type_wins(headers);
+    let headers = crate::types::transfer_headers(headers, resolved.chunked);
+    let headers = crate::types::without_transport_headers(headers);
+    Ok(crate::types::body_for_method(RequestData {
+        url: crate::types::effective_wire_url(&url, &resolved.auth, resolved.param_encoding),
+        headers: crate::types::effective_wire_headers(&headers, &resolved.auth),
+        ..resolved.clone()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AuthConfig, AuthType, BodyType, FormDataRow, FormDataValue, HttpMethod, RawSubtype};
+
+    fn request(method: HttpMethod, url: &str) -> RequestData {
+        RequestData::new(method, url.to_string())
+    }
+
+    fn hs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn build_with(request: &RequestData, default_headers: &[(&str, &str)]) -> Result<PreparedRequest, String> {
+        let vars = HashMap::from([("host".to_string(), "api.test".to_string())]);
+        build(request, &SendOptions { default_headers: hs(default_headers), presets: &[], vars: &vars })
+    }
+
+    #[test]
+    fn a_content_type_the_user_added_wins_over_the_body_one() {
+        let mut post = request(HttpMethod::POST, "{{host}}/items");
+        post.headers = hs(&[("content-type", "application/vnd.api+json")]);
+        post.body = BodyType::Raw { content: "{}".into(), subtype: RawSubtype::Json };
+        let built = build_with(&post, &[("Content-Type", "application/json"), ("Accept", "*/*")]).unwrap();
+        assert_eq!(built.sent.url, "https://api.test/items");
+        assert_eq!(built.sent.headers, hs(&[("Accept", "*/*"), ("content-type", "application/vnd.api+json")]));
+
+        // Without one, the body's goes out.
+        post.headers.clear();
+        let built = build_with(&post, &[("Content-Type", "application/json")]).unwrap();
+        assert_eq!(built.sent.headers, hs(&[("Content-Type", "application/json")]));
+    }
+
+    #[test]
+    fn presets_replace_defaults_give_way_to_the_user_and_stay_out_of_the_record() {
+        let preset = Preset {
+            id: 1,
+            name: "p".into(),
+            headers: hs(&[("accept", "text/plain"), ("X-Host", "{{host}}"), ("X-Mine", "preset")]),
+            params: hs(&[("via", "{{host}}")]),
+        };
+        let mut get = request(HttpMethod::GET, "{{host}}/items");
+        get.headers = hs(&[("X-Mine", "user")]);
+        get.presets = vec![1];
+        let vars = HashMap::from([("host".to_string(), "api.test".to_string())]);
+        let opts = SendOptions { default_headers: hs(&[("Accept", "*/*")]), presets: std::slice::from_ref(&preset), vars: &vars };
+        let built = build(&get, &opts).unwrap();
+        assert_eq!(built.request.url, "https://api.test/items");
+        assert_eq!(built.request.headers, hs(&[("X-Mine", "user")]));
+        assert_eq!(built.sent.url, "https://api.test/items?via=api.test");
+        assert_eq!(built.sent.headers, hs(&[("accept", "text/plain"), ("X-Host", "api.test"), ("X-Mine", "user")]));
+    }
+
+    #[test]
+    fn transport_and_chunked_headers_are_settled_here() {
+        let mut post = request(HttpMethod::POST, "https://api.test/upload");
+        post.headers = hs(&[("Content-Length", "0"), ("Connection", "close"), ("X-Trace", "{{host}}")]);
+        let built = build_with(&post, &[]).unwrap();
+        assert_eq!(built.sent.headers, hs(&[("X-Trace", "api.test")]));
+        post.chunked = true;
+        let built = build_with(&post, &[]).unwrap();
+        assert_eq!(built.sent.headers, hs(&[("X-Trace", "api.test"), ("Transfer-Encoding", "chunked")]));
+    }
+
+    #[test]
+    fn raw_and_form_bodies_are_resolved_and_kept_apart_from_auth() {
+        let mut post = request(HttpMethod::POST, "https://api.test/users/:id");
+        post.path_variables = vec![("id".into(), "7".into())];
+        post.auth = AuthConfig { auth_type: AuthType::Bearer, bearer_token: "t".into(), ..Default::default() };
+        post.body = BodyType::Raw { content: r#"{"host":"{{host}}"}"#.into(), subtype: RawSubtype::Json };
+        let built = build_with(&post, &[]).unwrap();
+        assert_eq!(built.sent.body, BodyType::Raw { content: r#"{"host":"api.test"}"#.into(), subtype: RawSubtype::Json });
+        assert_eq!(built.request.url, "https://api.test/users/:id");
+        assert_eq!(built.sent.url, "https://api.test/users/7");
+        assert!(built.request.headers.is_empty());
+        assert_eq!(built.sent.headers, hs(&[("Authorization", "Bearer t")]));
+
+        post.body = BodyType::FormData(vec![
+            FormDataRow { enabled: true, key: "name".into(), value: FormDataValue::Text("{{host}}".into()) },
+            FormDataRow { enabled: true, key: "file".into(), value: FormDataValue::File { path: "{{host}}.txt".into() } },
+        ]);
+        let BodyType::FormData(rows) = build_with(&post, &[]).unwrap().sent.body else { panic!("form data") };
+        assert_eq!(rows[0].value, FormDataValue::Text("api.test".into()));
+        assert_eq!(rows[1].value, FormDataValue::File { path: "{{host}}.txt".into() });
+    }
+
+    #[test]
+    fn unsendable_requests_say_why() {
+        assert_eq!(build_with(&request(HttpMethod::GET, "  "), &[]).unwrap_err(), "the URL is empty");
+        assert!(build_with(&request(HttpMethod::GET, "ftp://x.test"), &[]).unwrap_err().contains("ftp://"));
+        let templated = request(HttpMethod::GET, "{{host}}/users/:id");
+        assert!(build_with(&templated, &[]).unwrap_err().contains(":id has no value"));
+        // `resolve` alone still gets the request, for reporting the failure.
+        let vars = HashMap::new();
+        let opts = SendOptions { default_headers: vec![], presets: &[], vars: &vars };
+        assert_eq!(resolve(&templated, &opts).unwrap().url, "https://{{host}}/users/:id");
+    }
+}
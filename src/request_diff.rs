@@ -0,0 +1,244 @@
+//! Structured comparison of two requests ("Compare with tab…", and a History
+//! entry against the active tab): method, base URL, query params and headers
+//! compared key by key, body compared line by line with the `text_diff` engine.
+
+use crate::text_diff::{diff_lines, DiffLine};
+use crate::types::{BodyType, FormDataValue, RequestData};
+use crate::url_params::{extract_base_url, parse_query_params};
+
+/// One compared item. `None` on a side means the item is missing there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub name: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+impl FieldDiff {
+    fn new(name: &str, left: Option<String>, right: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            left,
+            right,
+        }
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.left != self.right
+    }
+}
+
+/// Headers that differ between sends without the request really changing:
+/// the length follows the body, the agent follows the app version.
+pub const VOLATILE_HEADERS: &[&str] = &["Content-Length", "User-Agent"];
+
+pub fn is_volatile_header(name: &str) -> bool {
+    VOLATILE_HEADERS.iter().any(|h| h.eq_ignore_ascii_case(name))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestDiff {
+    pub method: FieldDiff,
+    /// The URL without its query string; params are compared separately.
+    pub base_url: FieldDiff,
+    pub params: Vec<FieldDiff>,
+    pub headers: Vec<FieldDiff>,
+    /// "none", "raw (application/json)", "form-data" — a changed kind explains a body diff.
+    pub body_kind: FieldDiff,
+    pub body: Vec<DiffLine>,
+}
+
+impl RequestDiff {
+    /// Number of differing items; each changed body line counts once.
+    /// With `ignore_volatile`, changes in `VOLATILE_HEADERS` don't count.
+    pub fn change_count(&self, ignore_volatile: bool) -> usize {
+        [&self.method, &self.base_url, &self.body_kind]
+            .into_iter()
+            .chain(&self.params)
+            .chain(self.headers.iter().filter(|h| !(ignore_volatile && is_volatile_header(&h.name))))
+            .filter(|f| f.is_changed())
+            .count()
+            + self.body.iter().filter(|l| l.is_change()).count()
+    }
+}
+
+/// Compare `left` (the active tab) with `right` (the chosen tab).
+pub fn diff_requests(left: &RequestData, right: &RequestData) -> RequestDiff {
+    RequestDiff {
+        method: FieldDiff::new(
+            "Method",
+            Some(left.method.as_str().to_string()),
+            Some(right.method.as_str().to_string()),
+        ),
+        base_url: FieldDiff::new(
+            "URL",
+            Some(extract_base_url(&left.url)),
+            Some(extract_base_url(&right.url)),
+        ),
+        params: keyed_diff(&parse_query_params(&left.url), &parse_query_params(&right.url), false),
+        headers: keyed_diff(&left.headers, &right.headers, true),
+        body_kind: FieldDiff::new("Body type", Some(body_kind(&left.body)), Some(body_kind(&right.body))),
+        body: diff_lines(&body_text(&left.body), &body_text(&right.body)),
+    }
+}
+
+/// Pair items by key (the n-th `a` on the left with the n-th `a` on the
+/// right, so repeated keys line up). Left order first, then right-only items.
+fn keyed_diff(left: &[(String, String)], right: &[(String, String)], ignore_case: bool) -> Vec<FieldDiff> {
+    let norm = |k: &str| if ignore_case { k.to_ascii_lowercase() } else { k.to_string() };
+    let with_occurrence = |items: &[(String, String)]| -> Vec<(String, usize, String, String)> {
+        let mut seen: Vec<String> = Vec::new();
+        items
+            .iter()
+            .filter(|(k, _)| !k.trim().is_empty())
+            .map(|(k, v)| {
+                let key = norm(k);
+                let n = seen.iter().filter(|s| **s == key).count();
+                seen.push(key.clone());
+                (key, n, k.clone(), v.clone())
+            })
+            .collect()
+    };
+    let left = with_occurrence(left);
+    let right = with_occurrence(right);
+    let find = |items: &[(String, usize, String, String)], key: &str, n: usize| {
+        items.iter().find(|(k, i, _, _)| k == key && *i == n).map(|(_, _, _, v)| v.clone())
+    };
+
+    let mut out: Vec<FieldDiff> = left
+        .iter()
+        .map(|(key, n, name, value)| FieldDiff::new(name, Some(value.clone()), find(&right, key, *n)))
+        .collect();
+    out.extend(
+        right
+            .iter()
+            .filter(|(key, n, _, _)| find(&left, key, *n).is_none())
+            .map(|(_, _, name, value)| FieldDiff::new(name, None, Some(value.clone()))),
+    );
+    out
+}
+
+fn body_kind(body: &BodyType) -> String {
+    match body {
+        BodyType::None => "none".to_string(),
+        BodyType::Raw { subtype, .. } => format!("raw ({})", subtype.content_type()),
+        BodyType::FormData(_) => "form-data".to_string(),
+    }
+}
+
+/// The body as comparable text: raw content as-is, form-data as one
+/// `key: value` line per row (disabled rows commented out).
+pub fn body_text(body: &BodyType) -> String {
+    match body {
+        BodyType::None => String::new(),
+        BodyType::Raw { content, .. } => content.clone(),
+        BodyType::FormData(rows) => rows
+            .iter()
+            .map(|row| {
+                let value = match &row.value {
+                    FormDataValue::Text(text) => text.clone(),
+                    FormDataValue::File { path } => format!("@{path}"),
+                };
+                let prefix = if row.enabled { "" } else { "// " };
+                format!("{prefix}{}: {value}", row.key)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{HttpMethod, RawSubtype};
+
+    fn request(method: HttpMethod, url: &str, headers: &[(&str, &str)], body: &str) -> RequestData {
+        RequestData {
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: BodyType::Raw {
+                content: body.to_string(),
+                subtype: RawSubtype::Json,
+            },
+            ..RequestData::new(method, url.to_string())
+        }
+    }
+
+    #[test]
+    fn identical_requests_have_no_changes() {
+        let req = request(HttpMethod::POST, "https://x.dev/a?q=1", &[("Accept", "*/*")], "{}");
+        let diff = diff_requests(&req, &req.clone());
+        assert_eq!(diff.change_count(false), 0);
+        assert_eq!(diff.params.len(), 1);
+    }
+
+    #[test]
+    fn url_is_compared_param_by_param() {
+        let left = request(HttpMethod::GET, "https://x.dev/a?page=1&q=cat&tag=a&tag=b", &[], "");
+        let right = request(HttpMethod::GET, "https://x.dev/a?q=cat&page=2&tag=a&sort=asc", &[], "");
+        let diff = diff_requests(&left, &right);
+
+        assert!(!diff.base_url.is_changed());
+        let changed: Vec<(&str, Option<&str>, Option<&str>)> = diff
+            .params
+            .iter()
+            .filter(|p| p.is_changed())
+            .map(|p| (p.name.as_str(), p.left.as_deref(), p.right.as_deref()))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                ("page", Some("1"), Some("2")),
+                // The second `tag` has no partner on the right.
+                ("tag", Some("b"), None),
+                ("sort", None, Some("asc")),
+            ]
+        );
+    }
+
+    #[test]
+    fn headers_match_case_insensitively() {
+        let left = request(HttpMethod::GET, "", &[("Content-Type", "a"), ("X-Old", "1")], "");
+        let right = request(HttpMethod::GET, "", &[("content-type", "a"), ("X-New", "2")], "");
+        let diff = diff_requests(&left, &right);
+        let changed: Vec<&str> = diff.headers.iter().filter(|h| h.is_changed()).map(|h| h.name.as_str()).collect();
+        assert_eq!(changed, vec!["X-Old", "X-New"]);
+    }
+
+    #[test]
+    fn volatile_headers_can_be_ignored() {
+        let left = request(HttpMethod::GET, "", &[("User-Agent", "poopman/1"), ("Content-Length", "10")], "");
+        let right = request(HttpMethod::GET, "", &[("user-agent", "poopman/2"), ("X-Trace", "1")], "");
+        let diff = diff_requests(&left, &right);
+        assert_eq!(diff.change_count(false), 3);
+        // Only the X-Trace addition is left.
+        assert_eq!(diff.change_count(true), 1);
+        assert!(is_volatile_header("content-length"));
+        assert!(!is_volatile_header("Content-Type"));
+    }
+
+    #[test]
+    fn method_and_body_lines_are_counted() {
+        let left = request(HttpMethod::POST, "https://x.dev", &[], "{\n  \"a\": 1\n}");
+        let right = request(HttpMethod::PUT, "https://x.dev", &[], "{\n  \"a\": 2\n}");
+        let diff = diff_requests(&left, &right);
+        assert!(diff.method.is_changed());
+        // Method + one removed and one added body line.
+        assert_eq!(diff.change_count(false), 3);
+    }
+
+    #[test]
+    fn form_data_rows_diff_as_lines() {
+        use crate::types::FormDataRow;
+        let row = |key: &str, value: FormDataValue, enabled| FormDataRow {
+            enabled,
+            key: key.to_string(),
+            value,
+        };
+        let body = BodyType::FormData(vec![
+            row("name", FormDataValue::Text("x".into()), true),
+            row("file", FormDataValue::File { path: "/tmp/a.png".into() }, false),
+        ]);
+        assert_eq!(body_text(&body), "name: x\n// file: @/tmp/a.png");
+        assert_eq!(body_kind(&body), "form-data");
+    }
+}
@@ -3,34 +3,138 @@ use gpui::*;
 use gpui::px;
 use gpui_component::{
     button::*, checkbox::Checkbox, input::*,
+    menu::{DropdownMenu as _, PopupMenuItem},
     scroll::ScrollableElement as _,
-    select::*, v_flex, ActiveTheme as _, Disableable as _, Icon, IndexPath, Sizable as _,
+    select::*, tooltip::Tooltip, h_flex, v_flex, ActiveTheme as _, Icon, IndexPath, Disableable as _, Selectable as _, Sizable as _,
 };
 use gpui_component::input::InputEvent;
 
-use crate::auth_editor::AuthEditor;
+use crate::assertions::{Assertion, Check};
+use crate::auth_editor::{AuthEditor, TokenCommand, TokenState};
 use crate::body_editor::{BodyEditor, BodyTypeChanged};
-use crate::header_completion::HeaderCompletionProvider;
-use crate::types::{HeaderType, HttpMethod, PredefinedHeader, RequestData, ResponseData};
-use crate::url_params::{self, QueryParam};
+use crate::content_type::{last_content_type_wins, ContentTypeSync, MULTIPART_AUTO};
+use crate::request_builder;
+use crate::extract::{ExtractRule, Scope, Source};
+use crate::header_completion::{HeaderCompletionProvider, HeaderValueCompletionProvider};
+use crate::repeat_runner::{RepeatReport, MAX_PARALLEL, MAX_REPEAT_COUNT, PARALLEL_LIMIT, REPEAT_COUNTS};
+use crate::types::{
+    AuthType, HeaderType, HostOverride, HttpMethod, HttpProtocol, NetworkProfile, ParamEncoding, PredefinedHeader, Preset, RawSubtype, RequestData, ResponseData,
+    RetryPolicy, SendRoute,
+};
+use crate::url_params::{self, ParamRowSync, QueryParam};
 use crate::theme::METHOD_SELECT_WIDTH;
+use crate::app::{NextMethod, OpenMethodMenu, PrevMethod};
+use crate::a11y::{self, tab_order};
+
+/// Letters typed into the method selector further apart than this start a
+/// new prefix.
+const METHOD_PREFIX_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(800);
+
+/// The method selector's last row, after `HttpMethod::all()`: reveals the
+/// custom method input.
+const CUSTOM_METHOD_ROW: &str = "Custom…";
 
 /// Event emitted when a request is sent and response is received.
 /// The response is `Arc`-shared so subscribers can store it without copying the body.
 #[derive(Clone)]
 pub struct RequestCompleted {
+    /// The request as resolved before the wire (`request_builder::resolve`):
+    /// recorded in History.
     pub request: RequestData,
+    /// Exactly what went on the wire: `request` with the presets applied
+    /// and the auth header merged into its headers. Shown in the viewer's Request tab.
+    pub sent: std::sync::Arc<RequestData>,
     pub response: std::sync::Arc<ResponseData>,
 }
 
+/// Event emitted when a "Send ×N" batch finishes or is canceled.
+#[derive(Clone)]
+pub struct RepeatCompleted {
+    pub request: RequestData,
+    pub report: std::sync::Arc<RepeatReport>,
+}
+
 /// Event emitted when the user asks to view the request as a code snippet.
 #[derive(Clone)]
 pub struct OpenCodeSnippet;
 
+/// Event emitted when the user asks to save the request to a collection.
+#[derive(Clone)]
+pub struct SaveRequested;
+
 /// Event emitted when the user cancels an in-flight request.
 #[derive(Clone)]
 pub struct RequestCancelled;
 
+/// Event emitted when the user switches between the tabbed and the split
+/// layout; PoopmanApp remembers the choice.
+#[derive(Clone)]
+pub struct SplitLayoutChanged {
+    pub split: bool,
+}
+
+/// Event emitted when the user picks which predefined headers new tabs
+/// start with; PoopmanApp remembers the choice.
+#[derive(Clone)]
+pub struct DefaultHeadersChanged {
+    pub headers: Vec<PredefinedHeader>,
+}
+
+/// Event emitted by "Default User-Agent…" in the default headers menu;
+/// PoopmanApp asks for the value new tabs start with.
+#[derive(Clone)]
+pub struct ConfigureUserAgent {
+    pub current: String,
+}
+
+/// Event emitted by "New preset…" (`id` None) or "Edit …" in the presets
+/// menu; PoopmanApp opens the preset dialog.
+#[derive(Clone)]
+pub struct EditPreset {
+    pub id: Option<i64>,
+}
+
+/// Event emitted by "Configure Proxy…" in the send-through selector.
+#[derive(Clone)]
+pub struct ConfigureProxy;
+
+/// Event emitted by "Custom…" in the network profile section of the
+/// send-through selector; PoopmanApp asks for the rate and latency.
+#[derive(Clone)]
+pub struct ConfigureNetworkProfile {
+    pub current: NetworkProfile,
+}
+
+/// Event emitted by "Resolve Hosts…" in the send-through selector;
+/// PoopmanApp asks for the request's host overrides.
+#[derive(Clone)]
+pub struct ConfigureHostOverrides {
+    pub current: Vec<HostOverride>,
+}
+
+/// Event emitted on every edit of the URL bar; PoopmanApp hides the start
+/// panel once the URL is no longer empty.
+#[derive(Clone)]
+pub struct UrlEdited {
+    pub empty: bool,
+}
+
+/// Event emitted when a `{{variable}}` is edited inline from the URL bar.
+/// PoopmanApp writes it through to the active environment.
+#[derive(Clone)]
+pub struct EnvVarEdited {
+    pub key: String,
+    pub value: String,
+}
+
+/// Event emitted when a temporarily overridden `{{variable}}` is edited
+/// inline; PoopmanApp updates the override, not the environment.
+#[derive(Clone)]
+pub struct EnvOverrideEdited {
+    pub key: String,
+    pub value: String,
+}
+
 /// Create a header-name input carrying the standard-header typeahead.
 ///
 /// Custom rows get built in three places — loading a request, restoring saved
@@ -54,6 +158,25 @@ fn custom_header_key_input<T: 'static>(
     })
 }
 
+/// The value input of a custom header row, offering common values for the
+/// name in the row's `key_input` (see `header_names::HEADER_VALUES`).
+fn custom_header_value_input<T: 'static>(
+    key_input: &Entity<InputState>,
+    value: &str,
+    window: &mut Window,
+    cx: &mut Context<T>,
+) -> Entity<InputState> {
+    let (key, value) = (key_input.downgrade(), value.to_string());
+    cx.new(move |cx| {
+        let mut input = InputState::new(window, cx).placeholder("Value");
+        input.lsp.completion_provider = Some(std::rc::Rc::new(HeaderValueCompletionProvider { key }));
+        if !value.is_empty() {
+            input.set_value(&value, window, cx);
+        }
+        input
+    })
+}
+
 /// Header row with key-value inputs and enabled checkbox
 struct HeaderRow {
     enabled: bool,
@@ -61,29 +184,345 @@ struct HeaderRow {
     value_input: Entity<InputState>,
     header_type: HeaderType,
     predefined: Option<PredefinedHeader>,
+    description_input: Entity<InputState>,
     /// Character count of the key field at the previous change, used to tell an
     /// accepted completion (a multi-character replacement) from manual typing (one
     /// character at a time). See `maybe_advance_after_completion`.
     last_key_len: usize,
 }
 
+/// Inline editor for one `{{variable}}`, opened by ctrl-clicking its chip under
+/// the URL bar.
+struct VarEditor {
+    key: String,
+    input: Entity<InputState>,
+    _subscription: Subscription,
+}
+
+/// A "Send ×N" batch in flight. `attempts` is shared with the runtime task,
+/// so cancel can still report the attempts that finished.
+struct RunningRepeat {
+    request: RequestData,
+    requested: usize,
+    concurrency: usize,
+    started: std::time::Instant,
+    attempts: std::sync::Arc<std::sync::Mutex<Vec<crate::repeat_runner::Attempt>>>,
+}
+
+/// Which table a row overflow menu acts on.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RowKind {
+    Header,
+    Param,
+}
+
+impl RowKind {
+    /// Lowercase noun used in control names ("Delete header row 3").
+    fn noun(self) -> &'static str {
+        match self {
+            RowKind::Header => "header",
+            RowKind::Param => "param",
+        }
+    }
+}
+
 /// Query parameter row with key-value inputs and enabled checkbox
 struct ParamRow {
     enabled: bool,
+    /// The value is already percent-encoded: written into the URL as typed.
+    encoded: bool,
     key_input: Entity<InputState>,
     value_input: Entity<InputState>,
+    description_input: Entity<InputState>,
+}
+
+/// One row of the Tests tab.
+struct AssertionRow {
+    check: Check,
+    /// Header name or JSONPath; not shown for checks without one.
+    target_input: Entity<InputState>,
+    expected_input: Entity<InputState>,
+}
+
+/// One extract rule of the Tests tab.
+struct ExtractRow {
+    source: Source,
+    scope: Scope,
+    path_input: Entity<InputState>,
+    variable_input: Entity<InputState>,
+}
+
+/// The value of one `:name` segment of the URL.
+struct PathVariableRow {
+    name: String,
+    value_input: Entity<InputState>,
+}
+
+/// The network-error response (no status) recorded for a request that got
+/// no response, with `message` as its body.
+fn failed_response(request: &RequestData, message: String, duration_ms: u64, attempts: u8) -> ResponseData {
+    ResponseData {
+        status: None,
+        duration_ms,
+        headers: vec![],
+        body: message.into_bytes(),
+        is_text: true,
+        route: request.route.clone(),
+        raw_header_values: vec![],
+        network_profile: request.network_profile,
+        timing: None,
+        redirects: vec![],
+        tls_unverified: request.ignore_tls_errors,
+        attempts,
+        truncated_after: None,
+        downloaded: None,
+        wire_size: None,
+        version: None,
+        resolved_by: resolved_by(request),
+    }
+}
+
+/// The `ResponseData` for a response that arrived.
+fn completed_response(
+    request: &RequestData,
+    response: crate::http_client::HttpResponse,
+    duration_ms: u64,
+) -> ResponseData {
+    let is_text = crate::types::is_text_response(&response.headers, &response.body);
+    ResponseData {
+        status: Some(response.status),
+        duration_ms,
+        headers: response.headers,
+        body: response.body,
+        is_text,
+        route: request.route.clone(),
+        raw_header_values: response.raw_header_values,
+        network_profile: request.network_profile,
+        timing: Some(response.timing),
+        redirects: response.redirects,
+        tls_unverified: request.ignore_tls_errors,
+        attempts: response.attempts,
+        truncated_after: response.truncated_after,
+        downloaded: None,
+        wire_size: Some(response.wire_size),
+        version: Some(response.version),
+        resolved_by: resolved_by(request),
+    }
+}
+
+/// The host override `request` went out with, unless a proxy looked the
+/// host up instead.
+fn resolved_by(request: &RequestData) -> Option<HostOverride> {
+    if let SendRoute::Proxy(_) = request.route {
+        return None;
+    }
+    crate::http_client::host_override_for(&request.url, &request.host_overrides)
+}
+
+/// The client `request` asks for: its route, timeout, redirects, retries and
+/// network profile. Only a proxy URL reqwest rejects or an unusable CA
+/// certificate fails here; the message is reported like a network error so
+/// it lands in the viewer and history.
+fn client_for(request: &RequestData) -> Result<crate::http_client::HttpClient, String> {
+    match crate::http_client::HttpClient::for_route(
+        &request.route,
+        request.minimal_headers,
+        request.ignore_tls_errors,
+        request.protocol,
+        &request.host_overrides,
+    ) {
+        Ok(client) => Ok(client
+            .with_network_profile(request.network_profile)
+            .with_timeout(request.timeout_secs)
+            .with_follow_redirects(request.follow_redirects)
+            .with_retry(request.retry)),
+        Err(e) => {
+            let reason = if e.downcast_ref::<crate::http_client::CaCertError>().is_some() {
+                e.to_string()
+            } else {
+                format!("{}: {}", request.route.label(), e)
+            };
+            Err(format!("Request failed: {}", reason))
+        }
+    }
+}
+
+/// Send a request recorded in history again. History keeps it as resolved
+/// before the wire (`{{vars}}` substituted, the URL still a `:name`
+/// template, the presets it applied referred to by id), so it is built onto
+/// the wire again first, with the presets and `{{vars}}` as they are now.
+/// Used by the history panel's resend button.
+pub async fn resend_recorded(
+    request: &RequestData,
+    presets: &[Preset],
+    vars: &std::collections::HashMap<String, String>,
+) -> ResponseData {
+    let opts = request_builder::SendOptions { default_headers: Vec::new(), presets, vars };
+    match request_builder::wire(request, &opts) {
+        Ok(sent) => resend(sent).await,
+        Err(message) => failed_response(request, format!("Request not sent: {message}"), 0, 1),
+    }
+}
+
+/// Send a wire request (`request_builder::wire`) as it is: its URL and
+/// headers are final (auth folded in), so nothing is substituted or merged.
+/// An OAuth 2.0 token is the exception: it is never part of a request kept
+/// around, so the current one goes out. Used by `resend_recorded` and the
+/// collection runner.
+pub async fn resend(request: RequestData) -> ResponseData {
+    let request = match request.auth.auth_type {
+        AuthType::OAuth2 => match crate::oauth2::ensure(&request.auth.oauth2).await {
+            Ok(token) => crate::oauth2::with_token(request, &token),
+            Err(message) => return failed_response(&request, oauth2_failure(&message), 0, 1),
+        },
+        _ => request,
+    };
+    let missing = request.body.missing_files();
+    if !missing.is_empty() {
+        let message = format!("Request not sent: file not found: {}", missing.join(", "));
+        return failed_response(&request, message, 0, 1);
+    }
+    let client = match client_for(&request) {
+        Ok(client) => client,
+        Err(message) => return failed_response(&request, message, 0, 1),
+    };
+    log::info!("Resend started: {} {}", request.method.as_str(), request.url);
+    let start = std::time::Instant::now();
+    let inflight = client.start_send(request.method.clone(), request.url.clone(), request.headers.clone(), request.body.clone());
+    let result = inflight.wait().await;
+    let duration_ms = start.elapsed().as_millis() as u64;
+    match result {
+        Ok(response) => {
+            log::info!("Resend completed: {} in {}ms", response.status, duration_ms);
+            completed_response(&request, response, duration_ms)
+        }
+        Err(e) => {
+            let (e, attempts) = match e.downcast::<crate::http_client::GaveUpAfter>() {
+                Ok(gave_up) => (gave_up.error, gave_up.attempts),
+                Err(e) => (e, 1),
+            };
+            log::warn!("Resend failed after {}ms: {}", duration_ms, e);
+            failed_response(&request, error_message(&e), duration_ms, attempts)
+        }
+    }
+}
+
+/// Body of `failed_response` when no OAuth 2.0 token could be had.
+fn oauth2_failure(message: &str) -> String {
+    format!("Request not sent: no OAuth 2.0 token: {message}")
+}
+
+/// Body of `failed_response` for a send error: timeouts and proxy failures
+/// speak for themselves, anything else is "Request failed: …".
+fn error_message(error: &anyhow::Error) -> String {
+    if error.downcast_ref::<crate::http_client::RequestTimedOut>().is_some()
+        || error.downcast_ref::<crate::http_client::ProxyConnectFailed>().is_some()
+    {
+        error.to_string()
+    } else {
+        format!("Request failed: {}", error)
+    }
+}
+
+/// The narrow note field of a header or param row.
+fn description_input<T: 'static>(value: &str, window: &mut Window, cx: &mut Context<T>) -> Entity<InputState> {
+    let value = value.to_string();
+    cx.new(move |cx| {
+        let mut input = InputState::new(window, cx).placeholder("Description");
+        if !value.is_empty() {
+            input.set_value(&value, window, cx);
+        }
+        input
+    })
+}
+
+/// A row's description for the saved state; `None` when left blank.
+fn description_of(input: &Entity<InputState>, cx: &App) -> Option<String> {
+    let text = input.read(cx).value().trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// A header or param row being dragged to a new place.
+#[derive(Clone)]
+struct DraggedRow {
+    kind: RowKind,
+    index: usize,
+    label: SharedString,
+}
+
+impl Render for DraggedRow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        div()
+            .px_2()
+            .py_1()
+            .rounded(theme.radius)
+            .bg(theme.background)
+            .border_1()
+            .border_color(theme.border)
+            .shadow_sm()
+            .text_sm()
+            .text_color(theme.foreground)
+            .child(self.label.clone())
+    }
+}
+
+fn param_row_is_empty(row: &ParamRow, cx: &App) -> bool {
+    row.key_input.read(cx).value().is_empty() && row.value_input.read(cx).value().is_empty()
+}
+
+/// A param row's key and value as the URL's query decodes them: the value
+/// of an already-encoded row is decoded first.
+fn param_row_pair(row: &ParamRow, cx: &App) -> (String, String) {
+    let value = row.value_input.read(cx).value();
+    let value = if row.encoded { url_params::decode_component(&value) } else { value.to_string() };
+    (row.key_input.read(cx).value().to_string(), value)
+}
+
+fn header_row_is_empty(row: &HeaderRow, cx: &App) -> bool {
+    row.key_input.read(cx).value().is_empty() && row.value_input.read(cx).value().is_empty()
+}
+
+/// Write `value` into a reused param input only if it changed; if it is the
+/// focused input, put its caret back where it was.
+fn set_value_keeping_caret(
+    input: &Entity<InputState>,
+    value: &str,
+    focused: Option<&(EntityId, gpui_component::input::Position)>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    if input.read(cx).value() == value {
+        return;
+    }
+    let caret = focused.filter(|(id, _)| *id == input.entity_id()).map(|(_, pos)| *pos);
+    input.update(cx, |input, cx| {
+        input.set_value(value.to_string(), window, cx);
+        if let Some(pos) = caret {
+            input.set_cursor_position(pos, window, cx);
+        }
+    });
 }
 
 /// Request editor panel
 pub struct RequestEditor {
     url_input: Entity<InputState>,
-    method_select: Entity<SelectState<Vec<&'static str>>>,
+    method_select: Entity<SelectState<Vec<SharedString>>>,
+    /// The method name while the selector is on `CUSTOM_METHOD_ROW`.
+    custom_method_input: Entity<InputState>,
     body_editor: Entity<BodyEditor>,
     auth_editor: Entity<AuthEditor>,
     headers: Vec<HeaderRow>,
     headers_scroll_handle: ScrollHandle,
     params: Vec<ParamRow>,
     params_scroll_handle: ScrollHandle,
+    /// One row per `:name` segment of the URL, in order.
+    path_variables: Vec<PathVariableRow>,
+    /// The Tests tab (saved with the request).
+    assertions: Vec<AssertionRow>,
+    /// Request chaining rules, under the assertions.
+    extract_rules: Vec<ExtractRow>,
+    assertions_scroll_handle: ScrollHandle,
     active_tab: usize,
     loading: bool,
     /// Abort handle for the in-flight request (Some only while loading).
@@ -92,10 +531,81 @@ pub struct RequestEditor {
     /// generation and bail out if it no longer matches, so a stale task can
     /// never clobber state owned by a newer send.
     send_generation: u64,
+    running_repeat: Option<RunningRepeat>,
+    /// The Get token fetch or browser sign-in under way; dropping it stops it.
+    token_task: Option<Task<()>>,
+    /// Send writes the response body to a chosen file (not saved with the
+    /// request).
+    download_mode: bool,
+    /// Bytes written so far by the running download.
+    download_progress: Option<std::sync::Arc<std::sync::atomic::AtomicU64>>,
+    /// "Send ×N" runs the "at a time" field's count of attempts at once
+    /// instead of one.
+    repeat_parallel: bool,
+    /// How many times "Send ×N" sends; see `repeat_runner::parse_count`.
+    repeat_count_input: Entity<InputState>,
+    /// Attempts at once when `repeat_parallel`; see
+    /// `repeat_runner::parse_concurrency`.
+    repeat_concurrency_input: Entity<InputState>,
+    /// Re-send by itself when a 429's `Retry-After` elapses (per tab, kept by
+    /// PoopmanApp); when off the response viewer only offers the retry.
+    auto_retry: bool,
+    /// Send-through route for this request (saved with it).
+    route: SendRoute,
+    /// Whether `[` / `]` stay literal when the URL is rebuilt from Params.
+    param_encoding: ParamEncoding,
+    /// Whether spaces are written as `+` when the URL is rebuilt from Params.
+    plus_for_space: bool,
+    /// Send only user-added headers; the predefined rows stay but are skipped.
+    minimal_headers: bool,
+    /// Simulated network conditions for this request (saved with it).
+    network_profile: NetworkProfile,
+    /// Send the body chunked; the Content-Length row stays but is skipped.
+    chunked: bool,
+    /// Seconds before a send gives up (0 = never); read at send time.
+    timeout_input: Entity<InputState>,
+    /// Follow 3xx redirects (saved with the request).
+    follow_redirects: bool,
+    /// Accept invalid TLS certificates (saved with the request).
+    ignore_tls_errors: bool,
+    /// Send the body on a GET or HEAD too (saved with the request).
+    send_body_anyway: bool,
+    /// The HTTP version asked for (saved with the request).
+    protocol: HttpProtocol,
+    /// Hosts sent to a fixed address (saved with the request).
+    host_overrides: Vec<HostOverride>,
+    /// Keep a wire transcript for the Console tab (saved with the request).
+    verbose: bool,
+    /// Automatic retries (saved with the request).
+    retry: RetryPolicy,
+    /// The configured proxy, pushed by PoopmanApp; offered by the selector.
+    proxy_url: Option<String>,
+    /// Every preset, pushed by PoopmanApp.
+    presets: Vec<Preset>,
+    /// Ids of the presets this request applies (saved with it).
+    applied_presets: Vec<i64>,
+    /// The predefined headers new tabs (and loaded requests) start with.
+    default_headers: Vec<PredefinedHeader>,
+    /// The User-Agent row's value in new tabs (the "Default User-Agent"
+    /// setting).
+    default_user_agent: String,
+    /// Headers/Auth/Params and Body side by side instead of as tabs.
+    split_layout: bool,
     _subscriptions: Vec<Subscription>,       // Permanent: URL input + body editor subscriptions
     _row_subscriptions: Vec<Subscription>,   // Header/param row subscriptions; rebuilt on load
     /// Active environment variables, pushed by PoopmanApp; used at send time.
     env_vars: std::collections::HashMap<String, String>,
+    /// Globals, pushed by PoopmanApp; `env_vars` wins where keys collide.
+    global_vars: std::collections::HashMap<String, String>,
+    /// Name of the active environment, shown where a variable's value came from.
+    env_name: Option<String>,
+    /// Session overrides, pushed by PoopmanApp; they win over `env_vars`.
+    env_overrides: std::collections::HashMap<String, String>,
+    var_editor: Option<VarEditor>,
+    /// Auto vs manual state of the predefined Content-Type row.
+    content_type_sync: ContentTypeSync,
+    /// Letters typed into the method selector and when the last one came.
+    method_prefix: (String, std::time::Instant),
 }
 
 impl RequestEditor {
@@ -105,37 +615,100 @@ impl RequestEditor {
 
         let method_select = cx.new(|cx| {
             SelectState::new(
-                vec!["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"],
+                HttpMethod::all()
+                    .iter()
+                    .map(|m| SharedString::from(m.to_string()))
+                    .chain([SharedString::from(CUSTOM_METHOD_ROW)])
+                    .collect::<Vec<_>>(),
                 Some(IndexPath::default()), // Default to GET
                 window,
                 cx,
             )
         });
+        let custom_method_input = cx.new(|cx| InputState::new(window, cx).placeholder("PROPFIND"));
 
         let body_editor = cx.new(|cx| BodyEditor::new(window, cx));
         let auth_editor = cx.new(|cx| AuthEditor::new(window, cx));
+        let timeout_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(crate::types::DEFAULT_TIMEOUT_SECS.to_string())
+                .default_value(crate::types::DEFAULT_TIMEOUT_SECS.to_string())
+                .validate(|text, _| text.chars().all(|c| c.is_ascii_digit()))
+        });
+        let repeat_count_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(REPEAT_COUNTS[0].to_string())
+                .validate(|text, _| text.chars().all(|c| c.is_ascii_digit()))
+        });
+        let repeat_concurrency_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(PARALLEL_LIMIT.to_string())
+                .validate(|text, _| text.chars().all(|c| c.is_ascii_digit()))
+        });
 
         // Subscribe to body type changes to auto-update Content-Type header
         let body_sub = cx.subscribe_in(&body_editor, window, |this: &mut RequestEditor, _, event: &BodyTypeChanged, window, cx| {
             this.update_content_type_from_body(&event.content_type, window, cx);
         });
+        let token_sub = cx.subscribe(&auth_editor, |this: &mut RequestEditor, _, command: &TokenCommand, cx| {
+            this.on_token_command(*command, cx);
+        });
 
         let mut editor = Self {
             url_input: url_input.clone(),
-            method_select,
+            method_select: method_select.clone(),
+            custom_method_input: custom_method_input.clone(),
             body_editor,
             auth_editor,
             headers: vec![],
             headers_scroll_handle: ScrollHandle::new(),
             params: vec![],
             params_scroll_handle: ScrollHandle::new(),
+            assertions: vec![],
+            extract_rules: vec![],
+            path_variables: vec![],
+            assertions_scroll_handle: ScrollHandle::new(),
             active_tab: 0,
             loading: false,
             abort_handle: None,
             send_generation: 0,
+            running_repeat: None,
+            token_task: None,
+            download_mode: false,
+            download_progress: None,
+            repeat_parallel: false,
+            repeat_count_input,
+            repeat_concurrency_input,
+            auto_retry: false,
+            route: SendRoute::default(),
+            param_encoding: ParamEncoding::default(),
+            plus_for_space: false,
+            minimal_headers: false,
+            network_profile: NetworkProfile::Unthrottled,
+            chunked: false,
+            timeout_input,
+            follow_redirects: true,
+            ignore_tls_errors: false,
+            send_body_anyway: false,
+            protocol: Default::default(),
+            host_overrides: Default::default(),
+            verbose: false,
+            retry: Default::default(),
+            proxy_url: None,
+            presets: Vec::new(),
+            applied_presets: Vec::new(),
+            default_headers: PredefinedHeader::all(),
+            default_user_agent: crate::types::DEFAULT_USER_AGENT.to_string(),
+            split_layout: false,
             _subscriptions: vec![],
             _row_subscriptions: vec![],
             env_vars: std::collections::HashMap::new(),
+            global_vars: std::collections::HashMap::new(),
+            env_name: None,
+            env_overrides: std::collections::HashMap::new(),
+            var_editor: None,
+            content_type_sync: ContentTypeSync::new(Some(RawSubtype::Json.content_type().to_string())),
+            method_prefix: (String::new(), std::time::Instant::now()),
         };
 
         // Subscribe to URL input changes: a pasted `curl …` command imports the
@@ -143,6 +716,7 @@ impl RequestEditor {
         let url_sub = cx.subscribe_in(&url_input, window, |this, _, event: &InputEvent, window, cx| {
             if matches!(event, InputEvent::Change) {
                 let value = this.url_input.read(cx).value().to_string();
+                cx.emit(UrlEdited { empty: value.trim().is_empty() });
                 if value.trim_start().starts_with("curl ")
                     && let Some(request) = crate::curl_import::parse_curl(&value)
                 {
@@ -154,9 +728,28 @@ impl RequestEditor {
                 }
             }
             this.parse_url_to_params(window, cx);
+            this.sync_path_variables(window, cx);
         });
         editor._subscriptions.push(url_sub);
         editor._subscriptions.push(body_sub);
+        editor._subscriptions.push(token_sub);
+
+        // Picking "Custom…" moves to the method name; any pick may change
+        // what the body panel and Send show.
+        let method_sub = cx.subscribe_in(
+            &method_select,
+            window,
+            |this, _, _event: &SelectEvent<Vec<SharedString>>, window, cx| {
+                if this.is_custom_method(cx) {
+                    this.custom_method_input.update(cx, |input, cx| input.focus(window, cx));
+                }
+                cx.notify();
+            },
+        );
+        let custom_method_sub =
+            cx.subscribe_in(&custom_method_input, window, |_, _, _event: &InputEvent, _window, cx| cx.notify());
+        editor._subscriptions.push(method_sub);
+        editor._subscriptions.push(custom_method_sub);
 
         // Initialize with predefined headers
         editor.init_predefined_headers(window, cx);
@@ -170,32 +763,59 @@ impl RequestEditor {
         editor
     }
 
-    /// Initialize all predefined headers
+    /// Add the rows of the default predefined headers.
     fn init_predefined_headers(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        for predefined in PredefinedHeader::all() {
-            let header_type = predefined.header_type();
+        for predefined in self.default_headers.clone() {
+            self.insert_predefined_header(predefined, window, cx);
+        }
+    }
 
-            let key_input = cx.new(|cx| {
-                let mut input = InputState::new(window, cx);
-                input.set_value(predefined.name(), window, cx);
-                input
-            });
+    /// Add the row of `predefined`, with its default value, in its usual
+    /// place among the predefined rows.
+    fn insert_predefined_header(&mut self, predefined: PredefinedHeader, window: &mut Window, cx: &mut Context<Self>) {
+        let header_type = predefined.header_type();
 
-            let value_input = cx.new(|cx| {
-                let mut input = InputState::new(window, cx);
-                input.set_value(predefined.default_value(), window, cx);
-                input
-            });
+        let key_input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx);
+            input.set_value(predefined.name(), window, cx);
+            input
+        });
 
-            self.headers.push(HeaderRow {
-                enabled: true, // All predefined headers are enabled by default
-                key_input,
-                value_input,
-                header_type,
-                predefined: Some(predefined),
-                last_key_len: predefined.name().chars().count(),
+        let value = match predefined {
+            PredefinedHeader::UserAgent => self.default_user_agent.clone(),
+            _ => predefined.default_value().to_string(),
+        };
+        let value_input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx);
+            input.set_value(value, window, cx);
+            input
+        });
+
+        // Typing into the Content-Type value makes it a manual override;
+        // programmatic syncs re-fire Change with the auto value, which
+        // `edited` recognises, so they don't.
+        if predefined == PredefinedHeader::ContentType {
+            let sub = cx.subscribe_in(&value_input, window, |this, input, event: &InputEvent, _window, cx| {
+                if matches!(event, InputEvent::Change) {
+                    let value = input.read(cx).value().to_string();
+                    this.content_type_sync.edited(&value);
+                    cx.notify();
+                }
             });
+            self._row_subscriptions.push(sub);
         }
+
+        let rows: Vec<Option<PredefinedHeader>> = self.headers.iter().map(|h| h.predefined).collect();
+        let row = HeaderRow {
+            enabled: true, // Predefined headers are enabled by default
+            key_input,
+            value_input,
+            header_type,
+            predefined: Some(predefined),
+            description_input: description_input("", window, cx),
+            last_key_len: predefined.name().chars().count(),
+        };
+        self.headers.insert(predefined.insert_position(&rows), row);
     }
 
     /// Load a request from history
@@ -210,24 +830,41 @@ impl RequestEditor {
             input.set_value(&request.url, window, cx);
         });
 
-        // Set method
-        let method_index = HttpMethod::all()
-            .iter()
-            .position(|m| *m == request.method)
-            .unwrap_or(0);
-        self.method_select.update(cx, |select, cx| {
-            select.set_selected_index(Some(IndexPath::default().row(method_index)), window, cx);
-        });
+        self.select_method(request.method.clone(), window, cx);
 
         // Set body via BodyEditor
         self.body_editor.update(cx, |editor, cx| {
             editor.set_body(&request.body, window, cx);
         });
 
+        self.route = request.route.clone();
+        // A URL that already writes its brackets literally keeps them that way.
+        self.param_encoding = if url_params::uses_literal_brackets(&request.url) {
+            ParamEncoding::KeepBrackets
+        } else {
+            request.param_encoding
+        };
+        self.plus_for_space = url_params::uses_plus_for_spaces(&request.url);
+        self.minimal_headers = request.minimal_headers;
+        self.network_profile = request.network_profile;
+        self.chunked = request.chunked;
+        self.follow_redirects = request.follow_redirects;
+        self.ignore_tls_errors = request.ignore_tls_errors;
+        self.send_body_anyway = request.send_body_anyway;
+        self.protocol = request.protocol;
+        self.host_overrides = request.host_overrides.clone();
+        self.verbose = request.verbose;
+        self.retry = request.retry;
+        self.applied_presets = request.presets.clone();
+        self.timeout_input.update(cx, |input, cx| {
+            input.set_value(request.timeout_secs.to_string(), window, cx);
+        });
+
         // Set auth via AuthEditor
         self.auth_editor.update(cx, |editor, cx| {
             editor.set_auth(&request.auth, window, cx);
         });
+        self.refresh_token_state(cx);
 
         // Set headers - reinitialize with predefined headers
         self.headers.clear();
@@ -239,23 +876,35 @@ impl RequestEditor {
         // Clear params to force rebuild with fresh subscriptions.
         self.params.clear();
 
+        self.assertions.clear();
+        for assertion in &request.assertions {
+            self.add_assertion_row(assertion, window, cx);
+        }
+        self.extract_rules.clear();
+        for rule in &request.extract {
+            self.add_extract_row(rule, window, cx);
+        }
+        self.path_variables.clear();
+        for name in url_params::parse_path_variables(&request.url) {
+            let value = request.path_variables.iter().find(|(k, _)| *k == name).map(|(_, v)| v.as_str());
+            self.add_path_variable_row(name, value.unwrap_or_default(), window, cx);
+        }
+
         // First, add all predefined headers
         self.init_predefined_headers(window, cx);
 
         // Then, update predefined headers or add custom headers from the loaded request
-        for (key, value) in &request.headers {
-            // Check if this matches a predefined header
-            let all_predefined = PredefinedHeader::all();
-            let predefined_match = all_predefined
-                .iter()
-                .find(|p| p.name().eq_ignore_ascii_case(key));
-
-            if let Some(&predefined) = predefined_match {
-                // Update the predefined header's value and enable it
+        for (predefined_match, key, value) in PredefinedHeader::assign(&request.headers) {
+            if let Some(predefined) = predefined_match {
+                // Update the predefined header's value and enable it; one
+                // left out of the default set gets its row back first.
+                if !self.headers.iter().any(|h| h.predefined == Some(predefined)) {
+                    self.insert_predefined_header(predefined, window, cx);
+                }
                 for header in &mut self.headers {
                     if header.predefined == Some(predefined) {
                         header.value_input.update(cx, |input, cx| {
-                            input.set_value(value, window, cx);
+                            input.set_value(value.to_string(), window, cx);
                         });
                         header.enabled = true;
                         break;
@@ -264,11 +913,7 @@ impl RequestEditor {
             } else {
                 // Add as custom header
                 let key_input = custom_header_key_input(key, window, cx);
-                let value_input = cx.new(|cx| {
-                    let mut input = InputState::new(window, cx);
-                    input.set_value(value, window, cx);
-                    input
-                });
+                let value_input = custom_header_value_input(&key_input, value, window, cx);
 
                 self.headers.push(HeaderRow {
                     enabled: true,
@@ -276,6 +921,7 @@ impl RequestEditor {
                     value_input,
                     header_type: HeaderType::Custom,
                     predefined: None,
+                    description_input: description_input("", window, cx),
                     last_key_len: key.chars().count(),
                 });
             }
@@ -289,100 +935,929 @@ impl RequestEditor {
         // parse_url_to_params would otherwise bail and leave Params empty.
         self.rebuild_params_from_url(window, cx);
 
-        // Force sync Content-Type with body type to auto-correct any inconsistencies in history
+        // Content-Type follows the body's mode unless the saved value was a
+        // manual override (stale auto values from history are corrected).
         let content_type = match &request.body {
             crate::types::BodyType::None => None,
             crate::types::BodyType::Raw { subtype, .. } => Some(subtype.content_type().to_string()),
-            crate::types::BodyType::FormData(_) => Some("multipart/form-data; boundary=<auto>".to_string()),
+            crate::types::BodyType::FormData(_) => Some(MULTIPART_AUTO.to_string()),
         };
-        self.update_content_type_from_body(&content_type, window, cx);
+        let saved = request
+            .headers
+            .iter()
+            .rfind(|(k, _)| k.eq_ignore_ascii_case(PredefinedHeader::ContentType.name()))
+            .map(|(_, v)| v.as_str());
+        self.content_type_sync = ContentTypeSync::from_saved(content_type, saved);
+        self.apply_content_type(window, cx);
 
         cx.notify();
     }
 
-    /// Replace the active environment variable map (called by PoopmanApp).
-    pub fn set_env_vars(&mut self, vars: std::collections::HashMap<String, String>) {
+    /// Replace the active environment and global variable maps (called by
+    /// PoopmanApp).
+    pub fn set_env_vars(
+        &mut self,
+        vars: std::collections::HashMap<String, String>,
+        globals: std::collections::HashMap<String, String>,
+        env_name: Option<String>,
+    ) {
         self.env_vars = vars;
+        self.global_vars = globals;
+        self.env_name = env_name;
     }
 
-    /// Extract current request data from the editor
-    pub fn get_current_request_data(&self, cx: &App) -> RequestData {
-        // Get URL
-        let url = self.url_input.read(cx).value().to_string();
+    /// Replace the temporary override map (called by PoopmanApp).
+    pub fn set_env_overrides(&mut self, overrides: std::collections::HashMap<String, String>, cx: &mut Context<Self>) {
+        self.env_overrides = overrides;
+        cx.notify();
+    }
 
-        // Get method
-        let method_index = self
-            .method_select
-            .read(cx)
-            .selected_index(cx).map(|idx| idx.row)
-            .unwrap_or(0);
-        let method = HttpMethod::all().get(method_index).copied().unwrap_or(HttpMethod::GET);
+    /// What `{{vars}}` resolve against: globals, then the active environment,
+    /// then the session overrides on top.
+    pub fn resolved_vars(&self) -> std::collections::HashMap<String, String> {
+        crate::variables::scoped(&self.env_layer(), &self.global_vars)
+    }
 
-        // Get headers (only enabled ones, excluding empty custom headers)
-        let mut headers = Vec::new();
-        for header_row in &self.headers {
-            if header_row.enabled {
-                let key = header_row.key_input.read(cx).value().to_string();
-                let value = header_row.value_input.read(cx).value().to_string();
+    /// The OAuth 2.0 config as it is sent, `{{vars}}` resolved.
+    fn resolved_oauth2(&self, cx: &App) -> crate::types::OAuth2Config {
+        crate::variables::substitute_auth(&self.auth_editor.read(cx).get_auth(cx), &self.resolved_vars()).oauth2
+    }
 
-                // Skip empty custom headers (the placeholder row)
-                if !key.is_empty() || !matches!(header_row.header_type, HeaderType::Custom) {
-                    headers.push((key, value));
-                }
-            }
+    /// Show the token held for the Auth tab's OAuth 2.0 config, unless one
+    /// is being fetched.
+    fn refresh_token_state(&self, cx: &mut Context<Self>) {
+        if self.token_task.is_some() {
+            return;
         }
+        let state = crate::oauth2::cached(&self.resolved_oauth2(cx)).map_or(TokenState::None, TokenState::Held);
+        self.auth_editor.update(cx, |editor, cx| editor.set_token_state(state, cx));
+    }
 
-        // Get body
-        let body = self.body_editor.read(cx).get_body(cx);
+    fn on_token_command(&mut self, command: TokenCommand, cx: &mut Context<Self>) {
+        match command {
+            TokenCommand::Get => self.get_token(cx),
+            TokenCommand::Cancel => {
+                self.token_task = None;
+                self.refresh_token_state(cx);
+            }
+            TokenCommand::Forget => {
+                crate::oauth2::forget(&self.resolved_oauth2(cx));
+                self.refresh_token_state(cx);
+            }
+        }
+    }
 
-        RequestData {
-            method,
-            url,
-            headers,
-            body,
-            auth: self.auth_editor.read(cx).get_auth(cx),
+    /// Get token: fetch a client-credentials token, or open the sign-in in
+    /// the browser and trade the code it brings back for one.
+    fn get_token(&mut self, cx: &mut Context<Self>) {
+        let config = self.resolved_oauth2(cx);
+        let sign_in = match config.grant {
+            crate::types::OAuth2Grant::ClientCredentials => None,
+            crate::types::OAuth2Grant::AuthorizationCode => match crate::oauth2::SignIn::start(&config) {
+                Ok(sign_in) => Some(sign_in),
+                Err(message) => {
+                    self.auth_editor.update(cx, |editor, cx| editor.set_token_state(TokenState::Failed(message), cx));
+                    return;
+                }
+            },
+        };
+        if let Some(sign_in) = &sign_in {
+            cx.open_url(&sign_in.url);
         }
+        self.auth_editor.update(cx, |editor, cx| editor.set_token_state(TokenState::Fetching, cx));
+        self.token_task = Some(cx.spawn(async move |this, cx| {
+            let result = match sign_in {
+                Some(sign_in) => sign_in.finish(&config).await,
+                None => crate::oauth2::fetch(&config).await,
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.token_task = None;
+                match result {
+                    // Shown for whichever request is open by now.
+                    Ok(_) => this.refresh_token_state(cx),
+                    Err(message) => {
+                        log::warn!("OAuth 2.0 token not fetched: {}", message);
+                        this.auth_editor.update(cx, |editor, cx| editor.set_token_state(TokenState::Failed(message), cx));
+                    }
+                }
+            });
+        }));
     }
 
-    /// Current request with `{{vars}}` resolved against the active environment,
-    /// for code generation / previews.
-    pub fn resolved_request_data(&self, cx: &App) -> RequestData {
-        crate::variables::substitute_request(&self.get_current_request_data(cx), &self.env_vars)
+    /// Why the URL as typed cannot be sent, shown under the URL bar. One
+    /// whose `{{vars}}` do not all resolve yet is left alone until send.
+    fn url_problem(&self, cx: &App) -> Option<String> {
+        let url = crate::variables::substitute(self.url_input.read(cx).value().trim(), &self.resolved_vars());
+        if url.contains("{{") {
+            return None;
+        }
+        crate::url_params::url_problem(&url)
     }
 
-    /// Extract complete params state including disabled params
-    pub fn get_params_state(&self, cx: &App) -> Vec<crate::types::ParamState> {
-        self.params
-            .iter()
-            .map(|param_row| {
-                let key = param_row.key_input.read(cx).value().to_string();
-                let value = param_row.value_input.read(cx).value().to_string();
-                crate::types::ParamState {
-                    enabled: param_row.enabled,
-                    key,
-                    value,
-                }
-            })
-            .filter(|state| !state.key.is_empty() || !state.value.is_empty())
-            .collect()
+    /// The active environment with the session overrides on top.
+    fn env_layer(&self) -> std::collections::HashMap<String, String> {
+        crate::env_overrides::layered(&self.env_vars, &self.env_overrides)
     }
 
-    /// Extract complete headers state including disabled headers
-    pub fn get_headers_state(&self, cx: &App) -> Vec<crate::types::HeaderState> {
-        self.headers
-            .iter()
-            .map(|header_row| {
-                let key = header_row.key_input.read(cx).value().to_string();
-                let value = header_row.value_input.read(cx).value().to_string();
-                crate::types::HeaderState {
-                    enabled: header_row.enabled,
-                    key,
-                    value,
-                    header_type: header_row.header_type,
-                    predefined: header_row.predefined,
-                }
-            })
-            .collect()
+    /// Open the inline editor for `key`, prefilled with its current value.
+    /// Needs an active environment to write to, unless `key` is overridden.
+    fn open_var_editor(&mut self, key: String, window: &mut Window, cx: &mut Context<Self>) {
+        if self.env_name.is_none() && !self.env_overrides.contains_key(&key) {
+            return;
+        }
+        let value = self.resolved_vars().get(&key).cloned().unwrap_or_default();
+        let input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx);
+            input.set_value(&value, window, cx);
+            input
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+        let subscription = cx.subscribe_in(&input, window, |this, _, event: &InputEvent, _window, cx| {
+            if matches!(event, InputEvent::PressEnter { .. }) {
+                this.commit_var_editor(cx);
+            }
+        });
+        self.var_editor = Some(VarEditor { key, input, _subscription: subscription });
+        cx.notify();
+    }
+
+    /// Apply the inline edit locally (so the preview updates at once) and hand
+    /// it to PoopmanApp to persist.
+    fn commit_var_editor(&mut self, cx: &mut Context<Self>) {
+        let Some(editor) = self.var_editor.take() else {
+            return;
+        };
+        let value = editor.input.read(cx).value().to_string();
+        if self.env_overrides.contains_key(&editor.key) {
+            self.env_overrides.insert(editor.key.clone(), value.clone());
+            cx.emit(EnvOverrideEdited { key: editor.key, value });
+        } else {
+            self.env_vars.insert(editor.key.clone(), value.clone());
+            cx.emit(EnvVarEdited { key: editor.key, value });
+        }
+        cx.notify();
+    }
+
+    /// Resolved-URL preview plus one chip per `{{variable}}` in the URL. Hovering
+    /// a chip shows its value and source environment; ctrl-click edits it in place.
+    fn render_var_preview(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let url = self.url_input.read(cx).value().to_string();
+        let names = crate::variables::referenced_vars(&url);
+        if names.is_empty() && self.var_editor.is_none() {
+            return None;
+        }
+        let theme = cx.theme();
+        let resolved = crate::variables::resolve(&url, &self.env_layer(), &self.global_vars);
+        let overridden = names.iter().any(|name| self.env_overrides.contains_key(name));
+
+        let chips = names.into_iter().enumerate().map(|(i, name)| {
+            let tooltip = match (self.env_overrides.get(&name), self.env_vars.get(&name), &self.env_name) {
+                (Some(value), Some(hidden), Some(env)) => format!(
+                    "{value}  —  temporary override of \"{env}\" ({hidden}) (ctrl-click to edit)"
+                ),
+                (Some(value), _, _) => format!("{value}  —  temporary override (ctrl-click to edit)"),
+                (None, Some(value), Some(env)) => format!("{value}  —  from \"{env}\" (ctrl-click to edit)"),
+                (None, None, env) if self.global_vars.contains_key(&name) => {
+                    let value = &self.global_vars[&name];
+                    match env {
+                        Some(env) => format!("{value}  —  from Globals (ctrl-click to set in \"{env}\")"),
+                        None => format!("{value}  —  from Globals"),
+                    }
+                }
+                (None, None, Some(env)) => format!("Not defined in \"{env}\" (ctrl-click to add)"),
+                (None, _, None) => "No active environment".to_string(),
+            };
+            let is_override = self.env_overrides.contains_key(&name);
+            let resolved_here =
+                is_override || self.env_vars.contains_key(&name) || self.global_vars.contains_key(&name);
+            Button::new(("env-var-chip", i))
+                .xsmall()
+                .map(|b| {
+                    if is_override {
+                        b.warning()
+                    } else if resolved_here {
+                        b.ghost()
+                    } else {
+                        b.outline()
+                    }
+                })
+                .label(format!("{{{{{name}}}}}"))
+                .tooltip(tooltip)
+                .on_click(cx.listener(move |this, event: &gpui::ClickEvent, window, cx| {
+                    if event.modifiers().secondary() {
+                        this.open_var_editor(name.clone(), window, cx);
+                    }
+                }))
+        });
+
+        let row = v_flex()
+            .gap_1()
+            .w_full()
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_wrap()
+                    .items_center()
+                    .gap_1()
+                    .w_full()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .child(format!("→ {resolved}")),
+                    )
+                    .when(overridden, |this| {
+                        this.child(
+                            div()
+                                .flex_shrink_0()
+                                .text_color(theme.warning)
+                                .child("override in effect"),
+                        )
+                    })
+                    .children(chips),
+            )
+            .when_some(self.var_editor.as_ref(), |this, editor| {
+                this.child(
+                    div()
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap_2()
+                        .w_full()
+                        .child(
+                            div()
+                                .flex_shrink_0()
+                                .text_xs()
+                                .text_color(theme.muted_foreground)
+                                .child(format!("{{{{{}}}}} =", editor.key)),
+                        )
+                        .child(div().flex_1().child(Input::new(&editor.input).small()))
+                        .child(
+                            Button::new("env-var-save")
+                                .primary()
+                                .xsmall()
+                                .label("Save")
+                                .on_click(cx.listener(|this, _, _window, cx| this.commit_var_editor(cx))),
+                        )
+                        .child(
+                            Button::new("env-var-cancel")
+                                .ghost()
+                                .xsmall()
+                                .label("Cancel")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.var_editor = None;
+                                    cx.notify();
+                                })),
+                        ),
+                )
+            });
+
+        Some(row.into_any_element())
+    }
+
+    /// The method shown in the selector. Select rows are `HttpMethod::all()`
+    /// in order, so the row index maps straight onto it; the row after them
+    /// takes the custom method input, GET while that isn't a valid name.
+    fn selected_method(&self, cx: &App) -> HttpMethod {
+        if self.is_custom_method(cx) {
+            return HttpMethod::from_str(self.custom_method_input.read(cx).value().trim()).unwrap_or(HttpMethod::GET);
+        }
+        self.method_select
+            .read(cx)
+            .selected_index(cx)
+            .and_then(|idx| HttpMethod::all().get(idx.row).cloned())
+            .unwrap_or(HttpMethod::GET)
+    }
+
+    fn is_custom_method(&self, cx: &App) -> bool {
+        self.method_select.read(cx).selected_index(cx).is_some_and(|idx| idx.row == HttpMethod::all().len())
+    }
+
+    /// Why the custom method can't be sent, while it can't.
+    fn method_problem(&self, cx: &App) -> Option<String> {
+        if !self.is_custom_method(cx) {
+            return None;
+        }
+        let name = self.custom_method_input.read(cx).value();
+        let name = name.trim();
+        if name.is_empty() {
+            Some("Enter a method name".to_string())
+        } else if !crate::types::is_method_token(name) {
+            Some(format!("\"{name}\" can't be a method: use letters, digits and !#$%&'*+-.^_`|~"))
+        } else {
+            None
+        }
+    }
+
+    fn select_method(&mut self, method: HttpMethod, window: &mut Window, cx: &mut Context<Self>) {
+        let all = HttpMethod::all();
+        let row = all.iter().position(|m| *m == method).unwrap_or(all.len());
+        if let HttpMethod::Custom(name) = &method {
+            self.custom_method_input.update(cx, |input, cx| input.set_value(name, window, cx));
+        }
+        self.method_select.update(cx, |select, cx| {
+            select.set_selected_index(Some(IndexPath::default().row(row)), window, cx);
+        });
+        cx.notify();
+    }
+
+    /// Names of the jar cookies a send to the URL as typed would carry
+    /// (unless the request sets its own Cookie header).
+    fn jar_cookie_names(&self, cx: &App) -> Vec<String> {
+        if !crate::http_client::cookie_jar_enabled() {
+            return Vec::new();
+        }
+        let url = self.url_input.read(cx).value();
+        let url = crate::url_params::with_scheme(&crate::variables::substitute(url.trim(), &self.resolved_vars()));
+        crate::http_client::jar_cookies_for(&url).into_iter().map(|c| c.name).collect()
+    }
+
+    /// "Send through" selector: system proxy settings, no proxy, or the
+    /// configured proxy (plus the request's own proxy if it differs).
+    fn render_route_selector(&self, cx: &Context<Self>) -> impl IntoElement {
+        let editor = cx.entity();
+        let route = self.route.clone();
+        let minimal_headers = self.minimal_headers;
+        let chunked = self.chunked;
+        let follow_redirects = self.follow_redirects;
+        let ignore_tls_errors = self.ignore_tls_errors;
+        let retry = self.retry;
+        let network_profile = self.network_profile;
+        let protocol = self.protocol;
+        let host_overrides = self.host_overrides.clone();
+        let verbose = self.verbose;
+        let jar_cookies = self.jar_cookie_names(cx);
+        let mut label = route.short_label().to_string();
+        if minimal_headers {
+            label.push_str(" · minimal");
+        }
+        if chunked {
+            label.push_str(" · chunked");
+        }
+        if !follow_redirects {
+            label.push_str(" · no redirects");
+        }
+        if ignore_tls_errors {
+            label.push_str(" · insecure");
+        }
+        if verbose {
+            label.push_str(" · verbose");
+        }
+        if protocol != HttpProtocol::Auto {
+            label.push_str(&format!(" · {}", protocol.label()));
+        }
+        if !host_overrides.is_empty() {
+            label.push_str(" · resolved");
+        }
+        if retry.retries > 0 {
+            label.push_str(&format!(" · {}× retry", retry.retries));
+        }
+        if network_profile.is_throttled() {
+            label.push_str(&format!(" · {}", network_profile.label()));
+        }
+        match jar_cookies.len() {
+            0 => {}
+            1 => label.push_str(" · 1 cookie"),
+            n => label.push_str(&format!(" · {n} cookies")),
+        }
+        let mut proxies: Vec<String> = self.proxy_url.iter().cloned().collect();
+        if let SendRoute::Proxy(url) = &route
+            && !proxies.contains(url)
+        {
+            proxies.push(url.clone());
+        }
+
+        Button::new("send-route-btn")
+            .ghost()
+            .label(label)
+            .tooltip(format!(
+                "Send through: {}{}{}{}{}{}{}{}{}{}{}",
+                route.label(),
+                if minimal_headers { ", only headers you added" } else { "" },
+                if chunked { ", body chunked" } else { "" },
+                if follow_redirects { "" } else { ", redirects not followed" },
+                if ignore_tls_errors { ", TLS certificates not verified" } else { "" },
+                if verbose { ", wire log kept (Console tab)" } else { "" },
+                match protocol {
+                    HttpProtocol::Auto => String::new(),
+                    protocol => format!(", {} only", protocol.label()),
+                },
+                if host_overrides.is_empty() {
+                    String::new()
+                } else {
+                    let mappings: Vec<String> =
+                        host_overrides.iter().map(|o| format!("{} → {}", o.host, o.address_label())).collect();
+                    format!(", resolving {}", mappings.join(", "))
+                },
+                match (retry.retries, retry.on_5xx) {
+                    (0, _) => String::new(),
+                    (n, false) => format!(", retried up to {n}× on network errors"),
+                    (n, true) => format!(", retried up to {n}× on network errors and 5xx"),
+                },
+                if network_profile.is_throttled() {
+                    format!(", throttled to {}", network_profile.label())
+                } else {
+                    String::new()
+                },
+                if jar_cookies.is_empty() {
+                    String::new()
+                } else {
+                    format!(", with cookies from the jar: {}", jar_cookies.join(", "))
+                }
+            ))
+            .dropdown_menu(move |menu, _window, _cx| {
+                let item = |label: String, target: SendRoute| {
+                    let editor = editor.clone();
+                    PopupMenuItem::new(label)
+                        .checked(route == target)
+                        .on_click(move |_, _window, cx| {
+                            editor.update(cx, |editor, cx| editor.set_route(target.clone(), cx));
+                        })
+                };
+                let mut menu = menu
+                    .label("Send through")
+                    .item(item("System proxy".to_string(), SendRoute::System))
+                    .item(item("Direct (no proxy)".to_string(), SendRoute::Direct));
+                if proxies.is_empty() {
+                    menu = menu.item(PopupMenuItem::new("No proxy configured").disabled(true));
+                }
+                for url in &proxies {
+                    menu = menu.item(item(format!("Proxy {url}"), SendRoute::Proxy(url.clone())));
+                }
+                let profile_item = |label: &str, target: NetworkProfile| {
+                    let editor = editor.clone();
+                    PopupMenuItem::new(label.to_string())
+                        .checked(network_profile == target)
+                        .on_click(move |_, _window, cx| {
+                            editor.update(cx, |editor, cx| editor.set_network_profile(target, cx));
+                        })
+                };
+                let custom_label = match network_profile {
+                    NetworkProfile::Custom { .. } => format!("Custom ({})…", network_profile.label()),
+                    _ => "Custom…".to_string(),
+                };
+                let custom = editor.clone();
+                let chunk = editor.clone();
+                let redirects = editor.clone();
+                let tls = editor.clone();
+                let wire_log = editor.clone();
+                let configure = editor.clone();
+                let resolve = editor.clone();
+                let resolve_label = match host_overrides.len() {
+                    0 => "Resolve Hosts…".to_string(),
+                    n => format!("Resolve Hosts ({n})…"),
+                };
+                let retry_item = |label: String, retries: u8| {
+                    let editor = editor.clone();
+                    PopupMenuItem::new(label)
+                        .checked(retry.retries == retries)
+                        .on_click(move |_, _window, cx| {
+                            editor.update(cx, |editor, cx| {
+                                editor.retry.retries = retries;
+                                cx.notify();
+                            });
+                        })
+                };
+                let retry_5xx = editor.clone();
+                let protocol_item = |target: HttpProtocol| {
+                    let editor = editor.clone();
+                    let label = match target {
+                        HttpProtocol::Auto => "Auto (negotiated)",
+                        HttpProtocol::Http1 => "HTTP/1.1 only",
+                        HttpProtocol::Http2 => "HTTP/2 (prior knowledge)",
+                    };
+                    PopupMenuItem::new(label).checked(protocol == target).on_click(move |_, _window, cx| {
+                        editor.update(cx, |editor, cx| {
+                            editor.protocol = target;
+                            cx.notify();
+                        });
+                    })
+                };
+                let editor = editor.clone();
+                let mut menu = menu
+                    .separator()
+                    .item(PopupMenuItem::new("Configure Proxy…").on_click(move |_, _window, cx| {
+                        configure.update(cx, |_, cx| cx.emit(ConfigureProxy));
+                    }))
+                    .item(
+                        PopupMenuItem::new(resolve_label)
+                            .checked(!host_overrides.is_empty())
+                            .on_click(move |_, _window, cx| {
+                                let current = resolve.read(cx).host_overrides.clone();
+                                resolve.update(cx, |_, cx| cx.emit(ConfigureHostOverrides { current }));
+                            }),
+                    )
+                    .separator()
+                    .item(
+                        PopupMenuItem::new("Minimal headers (only the ones you added)")
+                            .checked(minimal_headers)
+                            .on_click(move |_, _window, cx| {
+                                editor.update(cx, |editor, cx| editor.set_minimal_headers(!minimal_headers, cx));
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new("Transfer-Encoding: chunked (no Content-Length)")
+                            .checked(chunked)
+                            .on_click(move |_, window, cx| {
+                                chunk.update(cx, |editor, cx| editor.set_chunked(!chunked, window, cx));
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new("Follow redirects")
+                            .checked(follow_redirects)
+                            .on_click(move |_, _window, cx| {
+                                redirects.update(cx, |editor, cx| {
+                                    editor.follow_redirects = !follow_redirects;
+                                    cx.notify();
+                                });
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new("Ignore TLS certificate errors")
+                            .checked(ignore_tls_errors)
+                            .on_click(move |_, _window, cx| {
+                                tls.update(cx, |editor, cx| {
+                                    editor.ignore_tls_errors = !ignore_tls_errors;
+                                    cx.notify();
+                                });
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new("Verbose (wire log in the Console tab)")
+                            .checked(verbose)
+                            .on_click(move |_, _window, cx| {
+                                wire_log.update(cx, |editor, cx| {
+                                    editor.verbose = !verbose;
+                                    cx.notify();
+                                });
+                            }),
+                    )
+                    .separator()
+                    .label("HTTP version");
+                for target in HttpProtocol::ALL {
+                    menu = menu.item(protocol_item(target));
+                }
+                let mut menu = menu
+                    .separator()
+                    .label("Retries (exponential backoff)")
+                    .item(retry_item("Off".to_string(), 0));
+                for retries in 1..=crate::types::MAX_RETRIES {
+                    menu = menu.item(retry_item(format!("Up to {retries}×"), retries));
+                }
+                menu.item(
+                    PopupMenuItem::new("Also retry 5xx responses")
+                        .checked(retry.on_5xx)
+                        .disabled(retry.retries == 0)
+                        .on_click(move |_, _window, cx| {
+                            retry_5xx.update(cx, |editor, cx| {
+                                editor.retry.on_5xx = !retry.on_5xx;
+                                cx.notify();
+                            });
+                        }),
+                )
+                .separator()
+                .label("Network profile")
+                .item(profile_item("Unthrottled", NetworkProfile::Unthrottled))
+                .item(profile_item("3G (750/250 kbps, +100 ms)", NetworkProfile::ThreeG))
+                .item(
+                    PopupMenuItem::new(custom_label)
+                        .checked(matches!(network_profile, NetworkProfile::Custom { .. }))
+                        .on_click(move |_, _window, cx| {
+                            custom.update(cx, |_, cx| cx.emit(ConfigureNetworkProfile { current: network_profile }));
+                        }),
+                )
+            })
+    }
+
+    /// Alt+Up / Alt+Down: step through `HttpMethod::all()` without opening
+    /// the dropdown.
+    fn cycle_method(&mut self, step: isize, window: &mut Window, cx: &mut Context<Self>) {
+        let method = self.selected_method(cx).cycled(step);
+        self.select_method(method, window, cx);
+    }
+
+    /// Ctrl+Shift+M: focus the method selector and open its dropdown.
+    ///
+    /// gpui-component keeps the Select's open/navigate actions crate-private,
+    /// so its registered `ui::SelectDown` action (which opens the menu) is
+    /// built by name and dispatched to the now-focused Select.
+    fn open_method_menu(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.method_select.update(cx, |select, cx| select.focus(window, cx));
+        match cx.build_action("ui::SelectDown", None) {
+            Ok(action) => window.dispatch_action(action, cx),
+            Err(e) => log::warn!("Cannot open method menu: {:?}", e),
+        }
+    }
+
+    /// Type-to-select inside the method selector: letters typed within a
+    /// short window build a prefix ("d", "de" → DELETE).
+    fn on_method_key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        let modifiers = keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(ch) = keystroke.key.chars().next().filter(|c| c.is_ascii_alphabetic() && keystroke.key.len() == 1) else {
+            return;
+        };
+
+        let now = std::time::Instant::now();
+        if now.duration_since(self.method_prefix.1) > METHOD_PREFIX_TIMEOUT {
+            self.method_prefix.0.clear();
+        }
+        self.method_prefix.0.push(ch);
+        self.method_prefix.1 = now;
+
+        // A prefix that matches nothing starts over from this letter.
+        let method = HttpMethod::from_prefix(&self.method_prefix.0).or_else(|| {
+            self.method_prefix.0 = ch.to_string();
+            HttpMethod::from_prefix(&self.method_prefix.0)
+        });
+        if let Some(method) = method {
+            self.select_method(method, window, cx);
+            cx.stop_propagation();
+        }
+    }
+
+    /// The Tests tab: one row per assertion, each a check picked from a menu,
+    /// a target where the check has one, and the expected value.
+    fn render_tests_panel(&self, cx: &Context<Self>) -> Div {
+        let theme = cx.theme();
+        let editor = cx.entity();
+        div()
+            .flex_1()
+            .min_h_0()
+            .flex()
+            .flex_col()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .items_center()
+                    .px_2()
+                    .pt_2()
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child("Checked against every response; results show in the response's Tests tab."),
+                    )
+                    .child(
+                        Button::new("add-assertion")
+                            .ghost()
+                            .xsmall()
+                            .label("+ Add assertion")
+                            .on_click(cx.listener(Self::new_assertion)),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("tests-scroll-container")
+                    .gap_2()
+                    .p_2()
+                    .pb_4()
+                    .size_full()
+                    .track_scroll(&self.assertions_scroll_handle)
+                    .overflow_scroll()
+                    .children(self.assertions.iter().enumerate().map(|(index, row)| {
+                        let current = row.check;
+                        let editor = editor.clone();
+                        h_flex()
+                            .gap_2()
+                            .w_full()
+                            .child(
+                                Button::new(("assertion-check", index))
+                                    .outline()
+                                    .small()
+                                    .w(px(150.))
+                                    .flex_shrink_0()
+                                    .label(current.label())
+                                    .dropdown_menu(move |menu, _window, _cx| {
+                                        Check::ALL.into_iter().fold(menu, |menu, check| {
+                                            let editor = editor.clone();
+                                            menu.item(PopupMenuItem::new(check.label()).checked(check == current).on_click(
+                                                move |_, window, cx| {
+                                                    editor.update(cx, |editor, cx| {
+                                                        editor.set_assertion_check(index, check, window, cx)
+                                                    });
+                                                },
+                                            ))
+                                        })
+                                    }),
+                            )
+                            .when(current.target_hint().is_some(), |this| {
+                                this.child(div().flex_1().child(Input::new(&row.target_input).small()))
+                            })
+                            .child(
+                                div().flex_1().child(
+                                    Input::new(&row.expected_input).small().suffix(
+                                        Button::new(("delete-assertion", index))
+                                            .ghost()
+                                            .xsmall()
+                                            .label("×")
+                                            .tooltip(a11y::delete_row("assertion", index))
+                                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                                this.remove_assertion(index, cx);
+                                            })),
+                                    ),
+                                ),
+                            )
+                    }))
+                    .when(self.assertions.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .py_4()
+                                .text_sm()
+                                .text_color(theme.muted_foreground)
+                                .child("No assertions yet: status, headers, body text, JSONPath values or response time."),
+                        )
+                    })
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .items_center()
+                            .pt_2()
+                            .border_t_1()
+                            .border_color(theme.border)
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .child("Extract into variables after a 2xx response, for the next request's {{var}}."),
+                            )
+                            .child(
+                                Button::new("add-extract-rule")
+                                    .ghost()
+                                    .xsmall()
+                                    .label("+ Add extract")
+                                    .on_click(cx.listener(Self::new_extract_rule)),
+                            ),
+                    )
+                    .children(self.extract_rules.iter().enumerate().map(|(index, row)| {
+                        let (source, scope) = (row.source, row.scope);
+                        let (source_editor, scope_editor) = (editor.clone(), editor.clone());
+                        h_flex()
+                            .gap_2()
+                            .w_full()
+                            .child(
+                                Button::new(("extract-source", index))
+                                    .outline()
+                                    .small()
+                                    .w(px(100.))
+                                    .flex_shrink_0()
+                                    .label(source.label())
+                                    .dropdown_menu(move |menu, _window, _cx| {
+                                        Source::ALL.into_iter().fold(menu, |menu, choice| {
+                                            let editor = source_editor.clone();
+                                            menu.item(PopupMenuItem::new(choice.label()).checked(choice == source).on_click(
+                                                move |_, window, cx| {
+                                                    editor.update(cx, |editor, cx| {
+                                                        editor.set_extract_source(index, choice, window, cx)
+                                                    });
+                                                },
+                                            ))
+                                        })
+                                    }),
+                            )
+                            .child(div().flex_1().child(Input::new(&row.path_input).small()))
+                            .child(div().text_sm().text_color(theme.muted_foreground).child("→"))
+                            .child(div().w(px(160.)).flex_shrink_0().child(Input::new(&row.variable_input).small()))
+                            .child(
+                                Button::new(("extract-scope", index))
+                                    .outline()
+                                    .small()
+                                    .w(px(110.))
+                                    .flex_shrink_0()
+                                    .label(scope.label())
+                                    .dropdown_menu(move |menu, _window, _cx| {
+                                        Scope::ALL.into_iter().fold(menu, |menu, choice| {
+                                            let editor = scope_editor.clone();
+                                            menu.item(PopupMenuItem::new(choice.label()).checked(choice == scope).on_click(
+                                                move |_, _window, cx| {
+                                                    editor.update(cx, |editor, cx| editor.set_extract_scope(index, choice, cx));
+                                                },
+                                            ))
+                                        })
+                                    }),
+                            )
+                            .child(
+                                Button::new(("delete-extract", index))
+                                    .ghost()
+                                    .xsmall()
+                                    .label("×")
+                                    .tooltip(a11y::delete_row("extract rule", index))
+                                    .on_click(cx.listener(move |this, _, _window, cx| {
+                                        this.remove_extract_rule(index, cx);
+                                    })),
+                            )
+                    })),
+            )
+            .vertical_scrollbar(&self.assertions_scroll_handle)
+    }
+
+    /// Extract current request data from the editor
+    pub fn get_current_request_data(&self, cx: &App) -> RequestData {
+        // Get URL
+        let url = self.url_input.read(cx).value().to_string();
+
+        let method = self.selected_method(cx);
+
+        // Get headers (only enabled ones, excluding empty custom headers)
+        let mut headers = Vec::new();
+        for header_row in &self.headers {
+            if header_row.enabled {
+                let key = header_row.key_input.read(cx).value().to_string();
+                let value = header_row.value_input.read(cx).value().to_string();
+
+                // Skip empty custom headers (the placeholder row)
+                if !key.is_empty() || !matches!(header_row.header_type, HeaderType::Custom) {
+                    headers.push((key, value));
+                }
+            }
+        }
+
+        let headers = last_content_type_wins(headers);
+
+        // Get body
+        let body = self.body_editor.read(cx).get_body(cx);
+
+        RequestData {
+            method,
+            url,
+            headers,
+            body,
+            auth: self.auth_editor.read(cx).get_auth(cx),
+            route: self.route.clone(),
+            param_encoding: self.param_encoding,
+            minimal_headers: self.minimal_headers,
+            network_profile: self.network_profile,
+            chunked: self.chunked,
+            timeout_secs: self.timeout_secs(cx),
+            follow_redirects: self.follow_redirects,
+            ignore_tls_errors: self.ignore_tls_errors,
+            retry: self.retry,
+            assertions: self.current_assertions(cx),
+            presets: self.applied_presets.clone(),
+            extract: self.current_extract_rules(cx),
+            path_variables: self.current_path_variables(cx),
+            send_body_anyway: self.send_body_anyway,
+            protocol: self.protocol,
+            host_overrides: self.host_overrides.clone(),
+            verbose: self.verbose,
+        }
+    }
+
+    /// Current request with `{{vars}}` resolved against the active environment,
+    /// for code generation / previews. Path variables with a value are filled in.
+    pub fn resolved_request_data(&self, cx: &App) -> RequestData {
+        let mut request = crate::variables::substitute_request(&self.get_current_request_data(cx), &self.resolved_vars());
+        if let Ok(url) = url_params::substitute_path_variables(&request.url, &request.path_variables) {
+            request.url = url;
+        }
+        request
+    }
+
+    /// Extract complete params state including disabled params
+    pub fn get_params_state(&self, cx: &App) -> Vec<crate::types::ParamState> {
+        self.params
+            .iter()
+            .map(|param_row| {
+                let key = param_row.key_input.read(cx).value().to_string();
+                let value = param_row.value_input.read(cx).value().to_string();
+                crate::types::ParamState {
+                    enabled: param_row.enabled,
+                    key,
+                    value,
+                    description: description_of(&param_row.description_input, cx),
+                    encoded: param_row.encoded,
+                }
+            })
+            .filter(|state| !state.key.is_empty() || !state.value.is_empty() || state.description.is_some())
+            .collect()
+    }
+
+    /// Extract complete headers state including disabled headers
+    pub fn get_headers_state(&self, cx: &App) -> Vec<crate::types::HeaderState> {
+        self.headers
+            .iter()
+            .map(|header_row| {
+                let key = header_row.key_input.read(cx).value().to_string();
+                let value = header_row.value_input.read(cx).value().to_string();
+                crate::types::HeaderState {
+                    enabled: header_row.enabled,
+                    key,
+                    value,
+                    header_type: header_row.header_type,
+                    predefined: header_row.predefined,
+                    description: description_of(&header_row.description_input, cx),
+                }
+            })
+            .collect()
     }
 
     /// Load params state (including disabled params)
@@ -394,6 +1869,7 @@ impl RequestEditor {
         for param_state in state {
             let param_row = ParamRow {
                 enabled: param_state.enabled,
+                encoded: param_state.encoded,
                 key_input: cx.new(|cx| {
                     let mut input = InputState::new(window, cx);
                     input.set_value(&param_state.key, window, cx);
@@ -404,6 +1880,7 @@ impl RequestEditor {
                     input.set_value(&param_state.value, window, cx);
                     input
                 }),
+                description_input: description_input(param_state.description.as_deref().unwrap_or_default(), window, cx),
             };
 
             // Subscribe to changes for syncing back to URL
@@ -434,47 +1911,35 @@ impl RequestEditor {
         for header_state in state {
             // Predefined rows render their key field disabled, so only custom rows
             // get the typeahead.
-            let key_input = if matches!(header_state.header_type, HeaderType::Custom) {
-                custom_header_key_input(&header_state.key, window, cx)
+            let (key_input, value_input) = if matches!(header_state.header_type, HeaderType::Custom) {
+                let key_input = custom_header_key_input(&header_state.key, window, cx);
+                let value_input = custom_header_value_input(&key_input, &header_state.value, window, cx);
+                (key_input, value_input)
             } else {
-                cx.new(|cx| {
-                    let mut input = InputState::new(window, cx);
-                    input.set_value(&header_state.key, window, cx);
-                    input
-                })
+                let input = |text: &str, window: &mut Window, cx: &mut Context<Self>| {
+                    let text = text.to_string();
+                    cx.new(|cx| {
+                        let mut input = InputState::new(window, cx);
+                        input.set_value(&text, window, cx);
+                        input
+                    })
+                };
+                (input(&header_state.key, window, cx), input(&header_state.value, window, cx))
             };
 
             let header_row = HeaderRow {
                 enabled: header_state.enabled,
                 key_input,
-                value_input: cx.new(|cx| {
-                    let mut input = InputState::new(window, cx);
-                    input.set_value(&header_state.value, window, cx);
-                    input
-                }),
+                value_input,
                 header_type: header_state.header_type,
                 predefined: header_state.predefined,
+                description_input: description_input(header_state.description.as_deref().unwrap_or_default(), window, cx),
                 last_key_len: header_state.key.chars().count(),
             };
 
             // Subscribe to key input change if it's a custom header
             if matches!(header_state.header_type, HeaderType::Custom) {
-                let key_input = header_row.key_input.clone();
-                let key_input_for_closure = key_input.clone();
-                let sub = cx.subscribe_in(&key_input, window, move |this, emitter, _event: &InputEvent, window, cx| {
-                    this.maybe_advance_after_completion(emitter, window, cx);
-
-                    if let Some(last) = this.headers.last() {
-                        let has_key = !last.key_input.read(cx).value().is_empty();
-                        if has_key
-                            && matches!(last.header_type, HeaderType::Custom)
-                            && this.headers.last().map(|h| Entity::entity_id(&h.key_input)) == Some(Entity::entity_id(&key_input_for_closure))
-                        {
-                            this.add_custom_header_row(window, cx);
-                        }
-                    }
-                });
-                self._row_subscriptions.push(sub);
+                self.subscribe_custom_header_key(&header_row.key_input, window, cx);
             }
 
             self.headers.push(header_row);
@@ -489,6 +1954,65 @@ impl RequestEditor {
         cx.notify();
     }
 
+    /// Completion-advance and auto-add-row handling for a restored or
+    /// duplicated custom header's key field.
+    fn subscribe_custom_header_key(&mut self, key_input: &Entity<InputState>, window: &mut Window, cx: &mut Context<Self>) {
+        let key_input_for_closure = key_input.clone();
+        let sub = cx.subscribe_in(key_input, window, move |this, emitter, _event: &InputEvent, window, cx| {
+            this.maybe_advance_after_completion(emitter, window, cx);
+
+            if let Some(last) = this.headers.last() {
+                let has_key = !last.key_input.read(cx).value().is_empty();
+                if has_key
+                    && matches!(last.header_type, HeaderType::Custom)
+                    && this.headers.last().map(|h| Entity::entity_id(&h.key_input)) == Some(Entity::entity_id(&key_input_for_closure))
+                {
+                    this.add_custom_header_row(window, cx);
+                }
+            }
+        });
+        self._row_subscriptions.push(sub);
+    }
+
+    /// Clone custom header `index` into a new row right below it (fresh
+    /// inputs, same enabled flag). An empty row is not duplicated.
+    fn duplicate_header_row(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(header) = self.headers.get(index) else {
+            return;
+        };
+        if !matches!(header.header_type, HeaderType::Custom) {
+            return;
+        }
+        let key = header.key_input.read(cx).value().to_string();
+        let value = header.value_input.read(cx).value().to_string();
+        let enabled = header.enabled;
+        let description = header.description_input.read(cx).value().to_string();
+        if key.is_empty() && value.is_empty() {
+            return;
+        }
+
+        let key_input = custom_header_key_input(&key, window, cx);
+        let value_input = custom_header_value_input(&key_input, &value, window, cx);
+        self.subscribe_custom_header_key(&key_input, window, cx);
+        self.headers.insert(
+            index + 1,
+            HeaderRow {
+                enabled,
+                key_input,
+                value_input,
+                header_type: HeaderType::Custom,
+                predefined: None,
+                description_input: description_input(&description, window, cx),
+                last_key_len: key.chars().count(),
+            },
+        );
+        // Duplicating the last filled row leaves no empty row to type into.
+        if self.headers.last().is_some_and(|h| !h.key_input.read(cx).value().is_empty()) {
+            self.add_custom_header_row(window, cx);
+        }
+        cx.notify();
+    }
+
     /// Detect an accepted header-name completion and move focus to the value field.
     ///
     /// The library exposes no "completion accepted" hook, so we infer one: a change
@@ -524,12 +2048,14 @@ impl RequestEditor {
     }
 
     fn add_custom_header_row(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let key_input = custom_header_key_input("", window, cx);
         let new_row = HeaderRow {
             enabled: true,
-            key_input: custom_header_key_input("", window, cx),
-            value_input: cx.new(|cx| InputState::new(window, cx).placeholder("Value")),
+            value_input: custom_header_value_input(&key_input, "", window, cx),
+            key_input,
             header_type: HeaderType::Custom,
             predefined: None,
+            description_input: description_input("", window, cx),
             last_key_len: 0,
         };
 
@@ -591,25 +2117,15 @@ impl RequestEditor {
 
     fn toggle_header(&mut self, index: usize, _checked: &bool, _window: &mut Window, cx: &mut Context<Self>) {
         if let Some(header) = self.headers.get_mut(index) {
-            // Cannot disable mandatory headers (e.g., Cache-Control)
-            if !matches!(header.header_type, HeaderType::Mandatory) {
-                header.enabled = !header.enabled;
-                cx.notify();
-            }
+            header.enabled = !header.enabled;
+            cx.notify();
         }
     }
 
-    fn remove_header_row(
-        &mut self,
-        index: usize,
-        _event: &gpui::ClickEvent,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        // Only allow deletion of custom headers
-        if let Some(header) = self.headers.get(index)
-            && matches!(header.header_type, HeaderType::Custom)
-        {
+    /// Delete a header row; a predefined one comes back with "Restore
+    /// default headers".
+    fn remove_header_row(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if index < self.headers.len() {
             self.headers.remove(index);
 
             // Check if there are any custom headers left
@@ -624,6 +2140,83 @@ impl RequestEditor {
         }
     }
 
+    /// The default predefined headers that have no row.
+    fn missing_default_headers(&self) -> Vec<PredefinedHeader> {
+        self.default_headers
+            .iter()
+            .copied()
+            .filter(|p| !self.headers.iter().any(|h| h.predefined == Some(*p)))
+            .collect()
+    }
+
+    /// Bring back the rows of deleted default headers, with their default
+    /// values (Content-Type and Content-Length then follow the body).
+    fn restore_default_headers(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for predefined in self.missing_default_headers() {
+            self.insert_predefined_header(predefined, window, cx);
+        }
+        self.update_content_length(window, cx);
+        self.apply_content_type(window, cx);
+    }
+
+    /// Replace the set new tabs start with (startup: from the settings).
+    pub fn set_default_headers(&mut self, headers: Vec<PredefinedHeader>, cx: &mut Context<Self>) {
+        self.default_headers = headers;
+        cx.notify();
+    }
+
+    /// Set the User-Agent new tabs (and restored rows) start with.
+    pub fn set_default_user_agent(&mut self, user_agent: String, cx: &mut Context<Self>) {
+        self.default_user_agent = user_agent;
+        cx.notify();
+    }
+
+    fn toggle_default_header(&mut self, predefined: PredefinedHeader, cx: &mut Context<Self>) {
+        let mut headers = self.default_headers.clone();
+        if let Some(at) = headers.iter().position(|p| *p == predefined) {
+            headers.remove(at);
+        } else {
+            headers.push(predefined);
+            headers.sort_by_key(|p| PredefinedHeader::all().iter().position(|q| q == p));
+        }
+        self.set_default_headers(headers.clone(), cx);
+        cx.emit(DefaultHeadersChanged { headers });
+    }
+
+    /// Menu above Headers: restore deleted predefined rows, and pick the
+    /// ones new tabs start with.
+    fn render_default_headers_button(&self, cx: &Context<Self>) -> impl IntoElement {
+        let editor = cx.entity();
+        let defaults = self.default_headers.clone();
+        let user_agent = self.default_user_agent.clone();
+        let nothing_missing = self.missing_default_headers().is_empty();
+        Button::new("default-headers")
+            .ghost()
+            .xsmall()
+            .label("Default headers")
+            .dropdown_menu(move |menu, _window, _cx| {
+                let restore = editor.clone();
+                let mut menu = menu
+                    .item(PopupMenuItem::new("Restore default headers").disabled(nothing_missing).on_click(
+                        move |_, window, cx| restore.update(cx, |editor, cx| editor.restore_default_headers(window, cx)),
+                    ))
+                    .separator()
+                    .label("New tabs start with");
+                for predefined in PredefinedHeader::all() {
+                    let editor = editor.clone();
+                    menu = menu.item(PopupMenuItem::new(predefined.name()).checked(defaults.contains(&predefined)).on_click(
+                        move |_, _window, cx| editor.update(cx, |editor, cx| editor.toggle_default_header(predefined, cx)),
+                    ));
+                }
+                let configure = editor.clone();
+                let current = user_agent.clone();
+                menu.separator().item(PopupMenuItem::new("Default User-Agent…").on_click(move |_, _window, cx| {
+                    let current = current.clone();
+                    configure.update(cx, |_, cx| cx.emit(ConfigureUserAgent { current }));
+                }))
+            })
+    }
+
     /// Update Content-Length header with calculated value from body
     fn update_content_length(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let content_length = self.body_editor.read(cx).calculate_length(cx).to_string();
@@ -643,22 +2236,35 @@ impl RequestEditor {
 
     /// Update Content-Type header to match body type
     fn update_content_type_from_body(&mut self, content_type: &Option<String>, window: &mut Window, cx: &mut Context<Self>) {
-        // Find Content-Type header and update it
-        let new_value = content_type.clone().unwrap_or_default();
-        for header in &mut self.headers {
-            if let Some(predefined) = header.predefined
-                && matches!(predefined, PredefinedHeader::ContentType)
-            {
-                // Update Content-Type value
-                let value_to_set = new_value.clone();
+        self.content_type_sync.set_auto(content_type.clone());
+        self.apply_content_type(window, cx);
+    }
+
+    /// Drop a manual Content-Type and go back to following the body.
+    fn reset_content_type(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.content_type_sync.reset();
+        self.apply_content_type(window, cx);
+    }
+
+    /// Push `content_type_sync` into the predefined Content-Type row: its value,
+    /// and switched off when there is nothing to send (None body, no override).
+    fn apply_content_type(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let value = self.content_type_sync.value().map(str::to_string);
+        if let Some(header) = self
+            .headers
+            .iter_mut()
+            .find(|h| h.predefined == Some(PredefinedHeader::ContentType))
+        {
+            let new_value = value.clone().unwrap_or_default();
+            if header.value_input.read(cx).value() != new_value.as_str() {
                 header.value_input.update(cx, |input, cx| {
-                    input.set_value(&value_to_set, window, cx);
+                    input.set_value(&new_value, window, cx);
                 });
-
-                log::debug!("Auto-updated Content-Type header to: {}", new_value);
-                break;
             }
+            header.enabled = value.is_some();
+            log::debug!("Content-Type header synced to: {:?}", value);
         }
+        cx.notify();
     }
 
     /// Parse URL query parameters into params list.
@@ -690,7 +2296,7 @@ impl RequestEditor {
         // keep existing params instead of wiping them.
         if new_params.is_empty()
             && !url_str.is_empty()
-            && !url_str.contains('?')
+            && !url_params::has_query(&url_str)
             && !self.params.is_empty()
         {
             return;
@@ -701,29 +2307,63 @@ impl RequestEditor {
         let current_params: Vec<(String, String)> = self
             .params
             .iter()
-            .map(|p| {
-                (
-                    p.key_input.read(cx).value().to_string(),
-                    p.value_input.read(cx).value().to_string(),
-                )
-            })
+            .map(|p| param_row_pair(p, cx))
             .filter(|(k, v)| !k.is_empty() || !v.is_empty())
             .collect();
         if url_params::params_equal(&new_params, &current_params) && !self.params.is_empty() {
             return;
         }
 
-        // Rebuild params list from the URL query string.
-        self.params.clear();
-        for (key_str, value_str) in new_params {
-            self.add_param_row_with_values(&key_str, &value_str, true, window, cx);
+        // Reuse rows instead of rebuilding them: rows whose param survives keep
+        // their entities (and with them focus and caret); only added params
+        // get new rows. Disabled rows are not in the URL and drop out, as do
+        // params that disappeared from it.
+        let focused = self.focused_param_input(window, cx);
+        let candidates: Vec<usize> = (0..self.params.len())
+            .filter(|&i| self.params[i].enabled && !param_row_is_empty(&self.params[i], cx))
+            .collect();
+        let current: Vec<(String, String)> = candidates.iter().map(|&i| param_row_pair(&self.params[i], cx)).collect();
+        let spare = self.params.iter().rposition(|p| param_row_is_empty(p, cx));
+        let plan = url_params::plan_param_sync(&current, &new_params);
+
+        let mut old: Vec<Option<ParamRow>> = std::mem::take(&mut self.params).into_iter().map(Some).collect();
+        for op in plan {
+            match op {
+                ParamRowSync::Reuse { index, key, value } => {
+                    let Some(row) = old[candidates[index]].take() else { continue };
+                    // An already-encoded row keeps the text typed into it.
+                    let value = if row.encoded {
+                        url_params::encoded_value(&row.value_input.read(cx).value(), &value, self.encode_options())
+                    } else {
+                        value
+                    };
+                    set_value_keeping_caret(&row.key_input, &key, focused.as_ref(), window, cx);
+                    set_value_keeping_caret(&row.value_input, &value, focused.as_ref(), window, cx);
+                    self.params.push(row);
+                }
+                ParamRowSync::Create { key, value } => {
+                    self.add_param_row_with_values(&key, &value, true, window, cx);
+                }
+            }
         }
         // Always keep one trailing empty row for adding new params.
-        self.add_param_row(window, cx);
+        match spare.and_then(|i| old[i].take()) {
+            Some(row) => self.params.push(row),
+            None => self.add_param_row(window, cx),
+        }
 
         cx.notify();
     }
 
+    /// The param input holding focus, with its caret, if any.
+    fn focused_param_input(&self, window: &Window, cx: &App) -> Option<(EntityId, gpui_component::input::Position)> {
+        self.params
+            .iter()
+            .flat_map(|p| [&p.key_input, &p.value_input])
+            .find(|input| input.read(cx).focus_handle(cx).is_focused(window))
+            .map(|input| (input.entity_id(), input.read(cx).cursor_position()))
+    }
+
     /// Add a param row with specific values (helper for parse_url_to_params)
     fn add_param_row_with_values(
         &mut self,
@@ -739,6 +2379,7 @@ impl RequestEditor {
 
         let param_row = ParamRow {
             enabled,
+            encoded: false,
             key_input: cx.new(|cx| {
                 let mut input = InputState::new(window, cx);
                 input.set_value(&key_string, window, cx);
@@ -749,6 +2390,7 @@ impl RequestEditor {
                 input.set_value(&value_string, window, cx);
                 input
             }),
+            description_input: description_input("", window, cx),
         };
 
         // Subscribe to changes for syncing back to URL
@@ -803,7 +2445,7 @@ impl RequestEditor {
     fn rebuild_url_with_params(&self, url_str: &str, cx: &App) -> String {
         log::debug!("Rebuilding URL from: {}", url_str);
 
-        // Extract base URL using pure function
+        // Extract base URL (fragment included) using pure function
         let base = url_params::extract_base_url(url_str);
 
         // Collect params as QueryParam structs
@@ -813,11 +2455,11 @@ impl RequestEditor {
                 p.key_input.read(cx).value().to_string(),
                 p.value_input.read(cx).value().to_string(),
                 p.enabled,
-            ))
+            ).with_encoded(p.encoded))
             .collect();
 
         // Build URL using pure function
-        let result = url_params::build_url_with_params(base, &params);
+        let result = url_params::build_url_with_options(&base, &params, self.encode_options());
 
         log::debug!("Rebuilt URL to: {}", result);
         result
@@ -827,8 +2469,10 @@ impl RequestEditor {
     fn add_param_row(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let new_row = ParamRow {
             enabled: true,
+            encoded: false,
             key_input: cx.new(|cx| InputState::new(window, cx).placeholder("Parameter")),
             value_input: cx.new(|cx| InputState::new(window, cx).placeholder("Value")),
+            description_input: description_input("", window, cx),
         };
 
         // Subscribe to key input change for auto-add
@@ -898,6 +2542,355 @@ impl RequestEditor {
         }
     }
 
+    /// Switch bracket encoding and rewrite the URL to match.
+    fn set_param_encoding(&mut self, encoding: ParamEncoding, window: &mut Window, cx: &mut Context<Self>) {
+        self.param_encoding = encoding;
+        self.rebuild_url_from_params(window, cx);
+        cx.notify();
+    }
+
+    /// Switch between `+` and `%20` for spaces and rewrite the URL to match.
+    fn set_plus_for_space(&mut self, plus_for_space: bool, window: &mut Window, cx: &mut Context<Self>) {
+        self.plus_for_space = plus_for_space;
+        self.rebuild_url_from_params(window, cx);
+        cx.notify();
+    }
+
+    /// Mark a param's value as already percent-encoded (sent as typed), or
+    /// not, and rewrite the URL to match.
+    fn toggle_param_encoded(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(param) = self.params.get_mut(index) {
+            param.encoded = !param.encoded;
+            self.rebuild_url_from_params(window, cx);
+            cx.notify();
+        }
+    }
+
+    /// How the URL is written from the Params table.
+    fn encode_options(&self) -> url_params::EncodeOptions {
+        url_params::EncodeOptions { brackets: self.param_encoding, plus_for_space: self.plus_for_space }
+    }
+
+    /// "Add array item": insert a row below `index` with the array form of
+    /// its key (`ids` -> `ids[]`, `ids[0]` -> `ids[1]`). A plain key is turned
+    /// into `key[]` as well, so both rows are items of the same array.
+    fn add_array_item(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(param) = self.params.get(index) else {
+            return;
+        };
+        let key = param.key_input.read(cx).value().to_string();
+        if key.is_empty() {
+            return;
+        }
+        let item_key = url_params::array_item_key(&key);
+        if !key.ends_with(']') {
+            param.key_input.update(cx, |input, cx| input.set_value(item_key.clone(), window, cx));
+        }
+        self.add_param_row_with_values(&item_key, "", true, window, cx);
+        if let Some(row) = self.params.pop() {
+            let value_input = row.value_input.clone();
+            self.params.insert(index + 1, row);
+            value_input.update(cx, |input, cx| input.focus(window, cx));
+        }
+        self.rebuild_url_from_params(window, cx);
+        cx.notify();
+    }
+
+    /// Clone param `index` into a new row right below it (fresh inputs, same
+    /// enabled flag) and resync the URL. An empty row is not duplicated.
+    fn duplicate_param(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(param) = self.params.get(index) else {
+            return;
+        };
+        if param_row_is_empty(param, cx) {
+            return;
+        }
+        let key = param.key_input.read(cx).value().to_string();
+        let value = param.value_input.read(cx).value().to_string();
+        let enabled = param.enabled;
+        let encoded = param.encoded;
+        let description = param.description_input.read(cx).value().to_string();
+        self.add_param_row_with_values(&key, &value, enabled, window, cx);
+        if let Some(mut row) = self.params.pop() {
+            row.encoded = encoded;
+            row.description_input = description_input(&description, window, cx);
+            self.params.insert(index + 1, row);
+        }
+        if self.params.last().is_some_and(|p| !param_row_is_empty(p, cx)) {
+            self.add_param_row(window, cx);
+        }
+        self.rebuild_url_from_params(window, cx);
+        cx.notify();
+    }
+
+    /// Whether row `index` can be dragged, or dropped on: a custom header or
+    /// a param, but not the trailing empty row, which stays last.
+    fn is_movable(&self, kind: RowKind, index: usize, cx: &App) -> bool {
+        match kind {
+            RowKind::Header => self.headers.get(index).is_some_and(|h| {
+                matches!(h.header_type, HeaderType::Custom)
+                    && !(index + 1 == self.headers.len() && header_row_is_empty(h, cx))
+            }),
+            RowKind::Param => self
+                .params
+                .get(index)
+                .is_some_and(|p| !(index + 1 == self.params.len() && param_row_is_empty(p, cx))),
+        }
+    }
+
+    /// Drop of row `from` on row `to`: it takes that row's place. Order is
+    /// kept on the wire, which matters to some signing schemes.
+    fn move_row(&mut self, kind: RowKind, from: usize, to: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if from == to || !self.is_movable(kind, from, cx) || !self.is_movable(kind, to, cx) {
+            return;
+        }
+        match kind {
+            RowKind::Header => {
+                let row = self.headers.remove(from);
+                self.headers.insert(to, row);
+            }
+            RowKind::Param => {
+                let row = self.params.remove(from);
+                self.params.insert(to, row);
+                self.rebuild_url_from_params(window, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    /// The grip a row is dragged by; an empty column on rows that stay put.
+    fn render_drag_handle(&self, kind: RowKind, index: usize, cx: &Context<Self>) -> impl IntoElement {
+        let (id, key) = match kind {
+            RowKind::Header => ("header-drag", self.headers.get(index).map(|h| &h.key_input)),
+            RowKind::Param => ("param-drag", self.params.get(index).map(|p| &p.key_input)),
+        };
+        let label: SharedString = key.map(|k| k.read(cx).value().to_string()).unwrap_or_default().into();
+        div()
+            .id((id, index))
+            .flex_shrink_0()
+            .w(px(10.))
+            .text_sm()
+            .text_color(cx.theme().muted_foreground)
+            .when(self.is_movable(kind, index, cx), |this| {
+                this.cursor_grab()
+                    .child("\u{22ee}")
+                    .tooltip(|window, cx| Tooltip::new("Drag to reorder").build(window, cx))
+                    .on_drag(DraggedRow { kind, index, label }, |dragged, _, _, cx| {
+                        cx.stop_propagation();
+                        cx.new(|_| dragged.clone())
+                    })
+            })
+    }
+
+    /// Overflow ("⋯") menu of a custom header or param row: Duplicate / Delete.
+    fn render_row_menu(&self, id: (&'static str, usize), index: usize, kind: RowKind, cx: &Context<Self>) -> impl IntoElement {
+        let editor = cx.entity();
+        let empty = match kind {
+            RowKind::Header => self.headers.get(index).is_none_or(|h| {
+                h.key_input.read(cx).value().is_empty() && h.value_input.read(cx).value().is_empty()
+            }),
+            RowKind::Param => self.params.get(index).is_none_or(|p| param_row_is_empty(p, cx)),
+        };
+        Button::new(id)
+            .ghost()
+            .xsmall()
+            .label("⋯")
+            .tooltip(a11y::row_actions(kind.noun(), index))
+            .dropdown_menu(move |menu, _window, _cx| {
+            let duplicate = editor.clone();
+            let delete = editor.clone();
+            menu.item(PopupMenuItem::new("Duplicate row").disabled(empty).on_click(move |_, window, cx| {
+                duplicate.update(cx, |editor, cx| match kind {
+                    RowKind::Header => editor.duplicate_header_row(index, window, cx),
+                    RowKind::Param => editor.duplicate_param(index, window, cx),
+                });
+            }))
+            .item(PopupMenuItem::new("Delete row").on_click(move |_, window, cx| {
+                delete.update(cx, |editor, cx| match kind {
+                    RowKind::Header => editor.remove_header_row(index, window, cx),
+                    RowKind::Param => editor.remove_param(index, window, cx),
+                });
+            }))
+        })
+    }
+
+    /// Append a Tests row filled from `assertion`. Edits notify, so the tab's
+    /// dirty dot follows them.
+    fn add_assertion_row(&mut self, assertion: &Assertion, window: &mut Window, cx: &mut Context<Self>) {
+        let check = assertion.check;
+        let input = |value: &str, placeholder: &'static str, window: &mut Window, cx: &mut Context<Self>| {
+            let value = value.to_string();
+            cx.new(|cx| {
+                let mut input = InputState::new(window, cx).placeholder(placeholder);
+                input.set_value(&value, window, cx);
+                input
+            })
+        };
+        let row = AssertionRow {
+            check,
+            target_input: input(&assertion.target, check.target_hint().unwrap_or_default(), window, cx),
+            expected_input: input(&assertion.expected, check.expected_hint(), window, cx),
+        };
+        for field in [&row.target_input, &row.expected_input] {
+            self._row_subscriptions
+                .push(cx.subscribe_in(field, window, |_, _, _event: &InputEvent, _window, cx| cx.notify()));
+        }
+        self.assertions.push(row);
+    }
+
+    fn new_assertion(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        self.add_assertion_row(&Assertion::default(), window, cx);
+        if let Some(row) = self.assertions.last() {
+            row.expected_input.update(cx, |input, cx| input.focus(window, cx));
+        }
+        cx.notify();
+    }
+
+    fn set_assertion_check(&mut self, index: usize, check: Check, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(row) = self.assertions.get_mut(index) else {
+            return;
+        };
+        row.check = check;
+        row.target_input
+            .update(cx, |input, cx| input.set_placeholder(check.target_hint().unwrap_or_default(), window, cx));
+        row.expected_input.update(cx, |input, cx| input.set_placeholder(check.expected_hint(), window, cx));
+        cx.notify();
+    }
+
+    fn remove_assertion(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index < self.assertions.len() {
+            self.assertions.remove(index);
+            cx.notify();
+        }
+    }
+
+    /// The Tests rows as saved; rows left blank are skipped. A target typed
+    /// before switching to a check without one is dropped.
+    fn current_assertions(&self, cx: &App) -> Vec<Assertion> {
+        self.assertions
+            .iter()
+            .filter_map(|row| {
+                let target = match row.check.target_hint() {
+                    Some(_) => row.target_input.read(cx).value().to_string(),
+                    None => String::new(),
+                };
+                let expected = row.expected_input.read(cx).value().to_string();
+                (!target.trim().is_empty() || !expected.trim().is_empty())
+                    .then_some(Assertion { check: row.check, target, expected })
+            })
+            .collect()
+    }
+
+    /// Append an extract row filled from `rule`.
+    fn add_extract_row(&mut self, rule: &ExtractRule, window: &mut Window, cx: &mut Context<Self>) {
+        let input = |value: &str, placeholder: &'static str, window: &mut Window, cx: &mut Context<Self>| {
+            let value = value.to_string();
+            cx.new(|cx| {
+                let mut input = InputState::new(window, cx).placeholder(placeholder);
+                input.set_value(&value, window, cx);
+                input
+            })
+        };
+        let row = ExtractRow {
+            source: rule.source,
+            scope: rule.scope,
+            path_input: input(&rule.path, rule.source.hint(), window, cx),
+            variable_input: input(&rule.variable, "Variable", window, cx),
+        };
+        for field in [&row.path_input, &row.variable_input] {
+            self._row_subscriptions
+                .push(cx.subscribe_in(field, window, |_, _, _event: &InputEvent, _window, cx| cx.notify()));
+        }
+        self.extract_rules.push(row);
+    }
+
+    fn new_extract_rule(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        self.add_extract_row(&ExtractRule::default(), window, cx);
+        if let Some(row) = self.extract_rules.last() {
+            row.path_input.update(cx, |input, cx| input.focus(window, cx));
+        }
+        cx.notify();
+    }
+
+    fn set_extract_source(&mut self, index: usize, source: Source, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(row) = self.extract_rules.get_mut(index) else {
+            return;
+        };
+        row.source = source;
+        row.path_input.update(cx, |input, cx| input.set_placeholder(source.hint(), window, cx));
+        cx.notify();
+    }
+
+    fn set_extract_scope(&mut self, index: usize, scope: Scope, cx: &mut Context<Self>) {
+        if let Some(row) = self.extract_rules.get_mut(index) {
+            row.scope = scope;
+            cx.notify();
+        }
+    }
+
+    fn remove_extract_rule(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index < self.extract_rules.len() {
+            self.extract_rules.remove(index);
+            cx.notify();
+        }
+    }
+
+    /// The extract rows as saved; rows without both a path and a variable
+    /// name are skipped.
+    fn current_extract_rules(&self, cx: &App) -> Vec<ExtractRule> {
+        self.extract_rules
+            .iter()
+            .filter_map(|row| {
+                let path = row.path_input.read(cx).value().trim().to_string();
+                let variable = row.variable_input.read(cx).value().trim().to_string();
+                (!path.is_empty() && !variable.is_empty()).then_some(ExtractRule {
+                    source: row.source,
+                    path,
+                    variable,
+                    scope: row.scope,
+                })
+            })
+            .collect()
+    }
+
+    /// Match the Path Variables rows to the URL's `:name` segments: names
+    /// still there keep their row (and value), new ones get an empty row.
+    fn sync_path_variables(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let names = url_params::parse_path_variables(&self.url_input.read(cx).value());
+        if names.iter().eq(self.path_variables.iter().map(|row| &row.name)) {
+            return;
+        }
+        let mut old = std::mem::take(&mut self.path_variables);
+        for name in names {
+            match old.iter().position(|row| row.name == name) {
+                Some(at) => self.path_variables.push(old.remove(at)),
+                None => self.add_path_variable_row(name, "", window, cx),
+            }
+        }
+        cx.notify();
+    }
+
+    fn add_path_variable_row(&mut self, name: String, value: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let value = value.to_string();
+        let value_input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx).placeholder("Value");
+            input.set_value(&value, window, cx);
+            input
+        });
+        self._row_subscriptions
+            .push(cx.subscribe_in(&value_input, window, |_, _, _event: &InputEvent, _window, cx| cx.notify()));
+        self.path_variables.push(PathVariableRow { name, value_input });
+    }
+
+    /// The path variable values as saved; blank ones are left out.
+    fn current_path_variables(&self, cx: &App) -> Vec<(String, String)> {
+        self.path_variables
+            .iter()
+            .map(|row| (row.name.clone(), row.value_input.read(cx).value().to_string()))
+            .filter(|(_, value)| !value.is_empty())
+            .collect()
+    }
+
     /// Remove a param row
     fn remove_param(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
         if index < self.params.len() {
@@ -930,9 +2923,16 @@ impl RequestEditor {
         if let Some(handle) = self.abort_handle.take() {
             handle.abort();
         }
+        log::info!("Send #{} canceled", self.send_generation);
         // Invalidate the spawned task so its completion can't touch state.
         self.send_generation = self.send_generation.wrapping_add(1);
+        if self.running_repeat.is_some() {
+            // A canceled batch still reports the attempts it finished.
+            self.finish_repeat(true, cx);
+            return;
+        }
         self.loading = false;
+        self.download_progress = None;
         cx.emit(RequestCancelled);
         cx.notify();
     }
@@ -943,7 +2943,7 @@ impl RequestEditor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.send(window, cx);
+        self.send_current(window, cx);
     }
 
     /// Focus the URL input and select all of its text. Public so the ctrl-l
@@ -957,160 +2957,356 @@ impl RequestEditor {
         window.dispatch_action(Box::new(gpui_component::input::SelectAll), cx);
     }
 
-    /// Send the current request. Public so the ctrl-enter action can trigger
-    /// it from PoopmanApp; no-op while a request is already in flight (the
-    /// button is swapped to Cancel then, but the keyboard path isn't).
-    pub fn send(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if self.loading {
-            return;
-        }
-        let mut url = self.url_input.read(cx).value().to_string().trim().to_string();
-        if url.is_empty() {
-            log::warn!("Cannot send request: URL is empty");
-            return;
-        }
-
-        // Substitute {{env vars}} BEFORE scheme normalization/validation, so a
-        // value like "https://host" doesn't get an extra "http://" prefix.
-        url = crate::variables::substitute(&url, &self.env_vars);
-
-        // Auto-add scheme if missing (like Postman does) - default to http://
-        if !url.starts_with("http://") && !url.starts_with("https://") {
-            url = format!("http://{}", url);
-            log::debug!("Auto-added http:// scheme to URL: {}", url);
-        }
-
-        // Validate URL format after normalization
-        if url::Url::parse(&url).is_err() {
-            log::error!("Invalid URL format even after normalization: '{}'", url);
-            return;
-        }
-
-        log::debug!("Sending request to: {}", url);
+    /// Replace just the URL, keeping method, headers, body and auth — used to
+    /// follow a pagination link into the current tab.
+    pub fn set_url(&mut self, url: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.url_input.update(cx, |input, cx| {
+            input.set_value(url.to_string(), window, cx);
+        });
+        self.rebuild_params_from_url(window, cx);
+        cx.notify();
+    }
 
+    /// Resolve the editor into the request as it is recorded
+    /// (`request_builder::resolve`: `{{vars}}` substituted, scheme
+    /// normalized, URL validated, presets still referred to by id), or
+    /// `None` when there is nothing valid to send. `request_builder::wire`
+    /// turns it into the wire request.
+    fn prepare_send(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Option<RequestData> {
         // Update Content-Length before sending
         self.update_content_length(window, cx);
 
-        // Get selected method
-        let method_index = self
-            .method_select
-            .read(cx)
-            .selected_index(cx)
-            .map(|idx| idx.row)
-            .unwrap_or(0);
-        let method_str = match method_index {
-            0 => "GET",
-            1 => "POST",
-            2 => "PUT",
-            3 => "DELETE",
-            4 => "PATCH",
-            5 => "HEAD",
-            6 => "OPTIONS",
-            _ => "GET",
-        };
-        let method = HttpMethod::from_str(method_str).unwrap_or(HttpMethod::GET);
-
-        // Get current body from BodyEditor
-        let body = self.body_editor.read(cx).get_body(cx);
-
-        // Build headers from header rows - only include enabled headers
-        let mut headers = vec![];
+        // Build headers from header rows - only include enabled headers. The
+        // predefined rows go beneath the presets' headers, the user's above.
+        let (mut defaults, mut custom) = (vec![], vec![]);
         for header in &self.headers {
-            if header.enabled {
+            if header.enabled && !(self.minimal_headers && header.predefined.is_some()) {
                 let key = header.key_input.read(cx).value().to_string();
                 let value = header.value_input.read(cx).value().to_string();
                 if !key.is_empty() && !value.is_empty() {
-                    headers.push((key, value));
+                    match header.header_type {
+                        HeaderType::Custom => custom.push((key, value)),
+                        _ => defaults.push((key, value)),
+                    }
                 }
             }
         }
 
-        // Note: Content-Type is now automatically synced via BodyTypeChanged event
-        // No need to auto-add here as it's already in the headers list
+        if let Some(problem) = self.method_problem(cx) {
+            log::warn!("Cannot send request: {}", problem);
+            return None;
+        }
+        let draft = RequestData { headers: custom, ..self.get_current_request_data(cx) };
+        let vars = self.resolved_vars();
+        let opts = request_builder::SendOptions { default_headers: defaults, presets: &self.presets, vars: &vars };
+        match request_builder::resolve(&draft, &opts) {
+            Ok(request) => {
+                log::debug!("Sending request to: {}", request.url);
+                Some(request)
+            }
+            Err(message) => {
+                log::warn!("Cannot send request: {}", message);
+                None
+            }
+        }
+    }
 
-        // Substitute {{env vars}} into headers / body at send time. (URL was
-        // already substituted earlier, before scheme normalization.)
-        let env = &self.env_vars;
-        let headers: Vec<(String, String)> = headers
-            .iter()
-            .map(|(k, v)| {
-                (
-                    crate::variables::substitute(k, env),
-                    crate::variables::substitute(v, env),
-                )
-            })
-            .collect();
-        let body = match body {
-            crate::types::BodyType::Raw { content, subtype } => crate::types::BodyType::Raw {
-                content: crate::variables::substitute(&content, env),
-                subtype,
-            },
-            crate::types::BodyType::FormData(rows) => crate::types::BodyType::FormData(
-                rows.into_iter()
-                    .map(|mut row| {
-                        row.key = crate::variables::substitute(&row.key, env);
-                        row.value = match row.value {
-                            crate::types::FormDataValue::Text(t) => {
-                                crate::types::FormDataValue::Text(crate::variables::substitute(&t, env))
-                            }
-                            other => other, // file path left as-is
-                        };
-                        row
-                    })
-                    .collect(),
-            ),
-            crate::types::BodyType::None => crate::types::BodyType::None,
+    /// The timeout field's value in seconds (`types::parse_timeout_secs`).
+    fn timeout_secs(&self, cx: &App) -> u32 {
+        crate::types::parse_timeout_secs(&self.timeout_input.read(cx).value())
+    }
+
+    /// "Send ×N"'s count and attempts at a time from their fields, or what
+    /// is wrong with them.
+    fn repeat_settings(&self, cx: &App) -> Result<(usize, usize), String> {
+        let count = crate::repeat_runner::parse_count(&self.repeat_count_input.read(cx).value())?;
+        let concurrency = if self.repeat_parallel {
+            crate::repeat_runner::parse_concurrency(&self.repeat_concurrency_input.read(cx).value())?
+        } else {
+            1
         };
+        Ok((count, concurrency))
+    }
 
-        // Resolve auth {{vars}} and compute the wire header. The saved request
-        // keeps manual headers + the auth config; only the wire gets the merged
-        // header set (auth wins over a manual same-name header).
-        let resolved_auth = crate::variables::substitute_auth(&self.auth_editor.read(cx).get_auth(cx), env);
+    /// Every preset, as Send applies them.
+    pub fn presets(&self) -> &[Preset] {
+        &self.presets
+    }
 
-        let request = RequestData {
-            method,
-            url: url.clone(),
-            headers: headers.clone(),
-            body: body.clone(),
-            auth: resolved_auth.clone(),
-        };
+    /// Replace the preset list (called by PoopmanApp after any edit).
+    pub fn set_presets(&mut self, presets: Vec<Preset>, cx: &mut Context<Self>) {
+        self.presets = presets;
+        cx.notify();
+    }
 
-        self.send_generation = self.send_generation.wrapping_add(1);
-        let generation = self.send_generation;
-        self.loading = true;
+    fn toggle_preset(&mut self, id: i64, cx: &mut Context<Self>) {
+        if let Some(at) = self.applied_presets.iter().position(|p| *p == id) {
+            self.applied_presets.remove(at);
+        } else {
+            self.applied_presets.push(id);
+        }
+        cx.notify();
+    }
 
-        log::debug!("Starting {} request to: {}", method.as_str(), url);
+    /// Whether a 429 with `Retry-After` re-sends by itself.
+    pub fn auto_retry(&self) -> bool {
+        self.auto_retry
+    }
 
-        // Spawn the HTTP work onto the tokio runtime *now* so we can hold an
-        // abort handle; the gpui task below only awaits the outcome.
-        let start = std::time::Instant::now();
-        let client = crate::http_client::HttpClient::new();
-        let wire_headers = crate::types::effective_wire_headers(&headers, &resolved_auth);
-        let inflight = client.start_send(method, url, wire_headers, body);
-        self.abort_handle = Some(inflight.abort_handle());
+    pub fn set_auto_retry(&mut self, auto_retry: bool, cx: &mut Context<Self>) {
+        self.auto_retry = auto_retry;
         cx.notify();
+    }
 
-        cx.spawn_in(window, async move |this, cx| {
-            let response = match inflight.wait().await {
-                Ok(r) => r,
-                Err(e) => {
-                    if e.downcast_ref::<crate::http_client::RequestCanceled>().is_some() {
-                        // cancel_request() already reset the UI and bumped the
-                        // generation; nothing left to do.
-                        return Ok(());
+    pub fn set_route(&mut self, route: SendRoute, cx: &mut Context<Self>) {
+        self.route = route;
+        cx.notify();
+    }
+
+    fn set_minimal_headers(&mut self, minimal_headers: bool, cx: &mut Context<Self>) {
+        self.minimal_headers = minimal_headers;
+        cx.notify();
+    }
+
+    /// Toggling back refreshes the auto-calculated Content-Length row, which
+    /// goes out again from the next send.
+    fn set_chunked(&mut self, chunked: bool, window: &mut Window, cx: &mut Context<Self>) {
+        self.chunked = chunked;
+        if !chunked {
+            self.update_content_length(window, cx);
+        }
+        cx.notify();
+    }
+
+    pub fn set_network_profile(&mut self, network_profile: NetworkProfile, cx: &mut Context<Self>) {
+        self.network_profile = network_profile;
+        cx.notify();
+    }
+
+    pub fn set_host_overrides(&mut self, host_overrides: Vec<HostOverride>, cx: &mut Context<Self>) {
+        self.host_overrides = host_overrides;
+        cx.notify();
+    }
+
+    pub fn set_proxy_url(&mut self, proxy_url: Option<String>, cx: &mut Context<Self>) {
+        self.proxy_url = proxy_url;
+        cx.notify();
+    }
+
+    pub fn set_split_layout(&mut self, split: bool, cx: &mut Context<Self>) {
+        self.split_layout = split;
+        cx.notify();
+    }
+
+    fn toggle_split_layout(&mut self, _: &gpui::ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        self.set_split_layout(!self.split_layout, cx);
+        cx.emit(SplitLayoutChanged { split: self.split_layout });
+    }
+
+    /// Send the current request (to a file in download mode). Public so the
+    /// ctrl-enter action, pagination and 429 retries can trigger it from
+    /// PoopmanApp; no-op while a request is already in flight (the button is
+    /// swapped to Cancel then, but the keyboard path isn't).
+    pub fn send_current(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.download_mode {
+            self.send_to_file(window, cx);
+        } else {
+            self.send_to(None, window, cx);
+        }
+    }
+
+    /// Download mode: pick where the body goes, then send.
+    fn send_to_file(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.loading {
+            return;
+        }
+        let suggested = url::Url::parse(self.url_input.read(cx).value().trim())
+            .ok()
+            .and_then(|u| u.path_segments()?.next_back().filter(|s| !s.is_empty()).map(str::to_string))
+            .unwrap_or_else(|| "download.bin".to_string());
+        let dir = dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| std::path::PathBuf::from("."));
+        let rx = cx.prompt_for_new_path(&dir, Some(&suggested));
+        cx.spawn_in(window, async move |this, cx| {
+            if let Ok(Ok(Some(path))) = rx.await {
+                this.update_in(cx, |this, window, cx| this.send_to(Some(path), window, cx))?;
+            }
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Send the request; with `download_to`, its body streams into that file
+    /// instead of the viewer.
+    fn send_to(&mut self, download_to: Option<std::path::PathBuf>, window: &mut Window, cx: &mut Context<Self>) {
+        if self.loading {
+            return;
+        }
+        let Some(request) = self.prepare_send(window, cx) else {
+            return;
+        };
+        self.with_oauth2_token(request, window, cx, move |this, request, token, window, cx| {
+            this.send_resolved(request, token, download_to, window, cx);
+        });
+    }
+
+    /// Run `then` with the bearer token `request`'s OAuth 2.0 auth goes out
+    /// with (`None` for any other auth): at once while the held one is
+    /// fresh, else once a new one arrives, Send showing as busy meanwhile. A
+    /// token that can't be had is reported like a failed send.
+    fn with_oauth2_token(
+        &mut self,
+        request: RequestData,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        then: impl FnOnce(&mut Self, RequestData, Option<String>, &mut Window, &mut Context<Self>) + 'static,
+    ) {
+        if request.auth.auth_type != AuthType::OAuth2 {
+            return then(self, request, None, window, cx);
+        }
+        if let Some(token) = crate::oauth2::usable(&request.auth.oauth2) {
+            return then(self, request, Some(token), window, cx);
+        }
+        self.send_generation = self.send_generation.wrapping_add(1);
+        let generation = self.send_generation;
+        self.loading = true;
+        cx.notify();
+        let config = request.auth.oauth2.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let result = crate::oauth2::ensure(&config).await;
+            this.update_in(cx, |this, window, cx| {
+                if this.send_generation != generation {
+                    return; // canceled while the token was fetched
+                }
+                this.loading = false;
+                this.refresh_token_state(cx);
+                match result {
+                    Ok(token) => then(this, request, Some(token), window, cx),
+                    Err(message) => {
+                        log::warn!("Send #{} not sent: {}", generation, message);
+                        let response = failed_response(&request, oauth2_failure(&message), 0, 1);
+                        cx.emit(RequestCompleted {
+                            sent: std::sync::Arc::new(request.clone()),
+                            request,
+                            response: std::sync::Arc::new(response),
+                        });
+                        cx.notify();
+                    }
+                }
+            })
+        })
+        .detach();
+    }
+
+    /// Send `request`, resolved by `prepare_send`, with `token` as its
+    /// OAuth 2.0 bearer token.
+    fn send_resolved(
+        &mut self,
+        request: RequestData,
+        token: Option<String>,
+        download_to: Option<std::path::PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The URL keeps its `:name` template and the presets stay ids; only
+        // the wire gets the values.
+        let vars = self.resolved_vars();
+        let opts = request_builder::SendOptions { default_headers: Vec::new(), presets: &self.presets, vars: &vars };
+        let sent = match request_builder::wire(&request, &opts) {
+            Ok(sent) => std::sync::Arc::new(match &token {
+                Some(token) => crate::oauth2::with_token(sent, token),
+                None => sent,
+            }),
+            Err(message) => {
+                let response = failed_response(&request, format!("Request not sent: {message}"), 0, 1);
+                cx.emit(RequestCompleted {
+                    sent: std::sync::Arc::new(request.clone()),
+                    request,
+                    response: std::sync::Arc::new(response),
+                });
+                return;
+            }
+        };
+        // A form-data file that has gone missing would otherwise only fail
+        // once the send task tries to encode it; say which one up front.
+        let missing = request.body.missing_files();
+        if !missing.is_empty() {
+            let response = failed_response(
+                &request,
+                format!("Request not sent: file not found: {}", missing.join(", ")),
+                0,
+                1,
+            );
+            cx.emit(RequestCompleted {
+                request,
+                sent,
+                response: std::sync::Arc::new(response),
+            });
+            return;
+        }
+        let client = match client_for(&request) {
+            Ok(client) => client,
+            Err(message) => {
+                let response = failed_response(&request, message, 0, 1);
+                cx.emit(RequestCompleted {
+                    request,
+                    sent,
+                    response: std::sync::Arc::new(response),
+                });
+                return;
+            }
+        };
+        if let Some(path) = download_to {
+            self.start_download(request, sent, client, path, window, cx);
+            return;
+        }
+
+        self.send_generation = self.send_generation.wrapping_add(1);
+        let generation = self.send_generation;
+        self.loading = true;
+
+        log::info!(
+            "Send #{} started: {} {} ({} headers, body: {}, route: {})",
+            generation,
+            request.method.as_str(),
+            request.url,
+            sent.headers.len(),
+            crate::request_log::body_summary(&sent.body),
+            request.route.label()
+        );
+        log::debug!("Send #{} headers: {}", generation, crate::request_log::redact_headers(&sent.headers));
+
+        // Spawn the HTTP work onto the tokio runtime *now* so we can hold an
+        // abort handle; the gpui task below only awaits the outcome.
+        let start = std::time::Instant::now();
+        let inflight = client.start_send(
+            sent.method.clone(),
+            sent.url.clone(),
+            sent.headers.clone(),
+            sent.body.clone(),
+        );
+        self.abort_handle = Some(inflight.abort_handle());
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let response = match inflight.wait().await {
+                Ok(r) => r,
+                Err(e) => {
+                    if e.downcast_ref::<crate::http_client::RequestCanceled>().is_some() {
+                        // cancel_request() already reset the UI and bumped the
+                        // generation; nothing left to do.
+                        return Ok(());
                     }
                     // Handle request error (network error, file read error, etc.)
                     let duration = start.elapsed();
-                    let error_message = format!("Request failed: {}", e);
-                    log::error!("{}", error_message);
-
-                    let error_response = ResponseData {
-                        status: None, // Use None to indicate network error
-                        duration_ms: duration.as_millis() as u64,
-                        headers: vec![],
-                        body: error_message.into_bytes(),
-                        is_text: true,
+                    let (e, attempts) = match e.downcast::<crate::http_client::GaveUpAfter>() {
+                        Ok(gave_up) => (gave_up.error, gave_up.attempts),
+                        Err(e) => (e, 1),
                     };
+                    log::warn!("Send #{} failed after {}ms: {}", generation, duration.as_millis(), e);
+                    let error_response =
+                        failed_response(&request, error_message(&e), duration.as_millis() as u64, attempts);
 
                     this.update(cx, |this, cx| {
                         if this.send_generation != generation {
@@ -1120,6 +3316,7 @@ impl RequestEditor {
                         this.abort_handle = None;
                         cx.emit(RequestCompleted {
                             request,
+                            sent,
                             response: std::sync::Arc::new(error_response),
                         });
                         cx.notify();
@@ -1129,20 +3326,15 @@ impl RequestEditor {
             };
 
             let duration = start.elapsed();
-            let status = response.status;
-
-            log::debug!("Request completed with status {} in {}ms", status, duration.as_millis());
-
-            let is_text = crate::types::is_text_response(&response.headers, &response.body);
-            log::debug!("Response body size: {} bytes (text={})", response.body.len(), is_text);
-
-            let response_data = ResponseData {
-                status: Some(status),
-                duration_ms: duration.as_millis() as u64,
-                headers: response.headers,
-                body: response.body,
-                is_text,
-            };
+            log::info!(
+                "Send #{} completed: {} in {}ms ({} bytes)",
+                generation,
+                response.status,
+                duration.as_millis(),
+                response.body.len()
+            );
+            log::debug!("Send #{} response headers: {}", generation, crate::request_log::redact_headers(&response.headers));
+            let response_data = completed_response(&request, response, duration.as_millis() as u64);
 
             this.update(cx, |this, cx| {
                 if this.send_generation != generation {
@@ -1152,6 +3344,7 @@ impl RequestEditor {
                 this.abort_handle = None;
                 cx.emit(RequestCompleted {
                     request,
+                    sent,
                     response: std::sync::Arc::new(response_data),
                 });
                 cx.notify();
@@ -1163,12 +3356,904 @@ impl RequestEditor {
     }
 }
 
+impl RequestEditor {
+    /// Download mode's half of `send_to`: stream the body into `path`,
+    /// keeping the Cancel button's byte count live, and report a response
+    /// without a body but with `downloaded` set.
+    fn start_download(
+        &mut self,
+        request: RequestData,
+        sent: std::sync::Arc<RequestData>,
+        client: crate::http_client::HttpClient,
+        path: std::path::PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.send_generation = self.send_generation.wrapping_add(1);
+        let generation = self.send_generation;
+        self.loading = true;
+        log::info!(
+            "Send #{} started: {} {} (download to {:?})",
+            generation,
+            request.method.as_str(),
+            request.url,
+            path
+        );
+
+        let start = std::time::Instant::now();
+        let inflight =
+            client.start_download(sent.method.clone(), sent.url.clone(), sent.headers.clone(), sent.body.clone(), path.clone());
+        let progress = inflight.progress();
+        self.abort_handle = Some(inflight.abort_handle());
+        self.download_progress = Some(progress.clone());
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            // Polled rather than awaited so the Cancel button's byte count
+            // stays live while the body streams.
+            while !inflight.is_finished() {
+                cx.background_executor()
+                    .timer(std::time::Duration::from_millis(200))
+                    .await;
+                this.update(cx, |_, cx| cx.notify())?;
+            }
+            let result = inflight.wait().await;
+            let duration_ms = start.elapsed().as_millis() as u64;
+            let response = match result {
+                Ok(response) => {
+                    let bytes = progress.load(std::sync::atomic::Ordering::Relaxed);
+                    log::info!(
+                        "Send #{} completed: {} in {}ms ({} bytes to file)",
+                        generation,
+                        response.status,
+                        duration_ms,
+                        bytes
+                    );
+                    ResponseData {
+                        status: Some(response.status),
+                        duration_ms,
+                        headers: response.headers,
+                        body: vec![],
+                        is_text: true,
+                        route: request.route.clone(),
+                        raw_header_values: response.raw_header_values,
+                        network_profile: NetworkProfile::Unthrottled,
+                        timing: Some(response.timing),
+                        redirects: response.redirects,
+                        tls_unverified: request.ignore_tls_errors,
+                        attempts: 1,
+                        truncated_after: None,
+                        downloaded: Some(crate::types::DownloadedBody {
+                            path: path.to_string_lossy().to_string(),
+                            bytes,
+                        }),
+                        wire_size: Some(response.wire_size),
+                        version: Some(response.version),
+                        resolved_by: resolved_by(&request),
+                    }
+                }
+                Err(e) if e.downcast_ref::<crate::http_client::RequestCanceled>().is_some() => return Ok(()),
+                Err(e) => {
+                    log::warn!("Send #{} download failed after {}ms: {}", generation, duration_ms, e);
+                    failed_response(&request, error_message(&e), duration_ms, 1)
+                }
+            };
+            this.update(cx, |this, cx| {
+                if this.send_generation != generation {
+                    return;
+                }
+                this.loading = false;
+                this.abort_handle = None;
+                this.download_progress = None;
+                cx.emit(RequestCompleted { request, sent, response: std::sync::Arc::new(response) });
+                cx.notify();
+            })
+        })
+        .detach();
+    }
+
+    /// "Send ×N": fire the current request `count` times, `concurrency` at a
+    /// time. The batch reports once, via `RepeatCompleted`, when it finishes or
+    /// is canceled.
+    pub fn send_repeated(
+        &mut self,
+        count: usize,
+        concurrency: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.loading {
+            return;
+        }
+        let Some(request) = self.prepare_send(window, cx) else {
+            return;
+        };
+        self.with_oauth2_token(request, window, cx, move |this, request, token, window, cx| {
+            this.repeat_resolved(request, token, count, concurrency, window, cx);
+        });
+    }
+
+    /// `send_repeated` once `request` is resolved and its token in hand.
+    fn repeat_resolved(
+        &mut self,
+        request: RequestData,
+        token: Option<String>,
+        count: usize,
+        concurrency: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let missing = request.body.missing_files();
+        if !missing.is_empty() {
+            log::warn!("Not repeating: file not found: {}", missing.join(", "));
+            return;
+        }
+        let vars = self.resolved_vars();
+        let opts = request_builder::SendOptions { default_headers: Vec::new(), presets: &self.presets, vars: &vars };
+        let sent = match request_builder::wire(&request, &opts) {
+            Ok(sent) => match &token {
+                Some(token) => crate::oauth2::with_token(sent, token),
+                None => sent,
+            },
+            Err(message) => {
+                log::warn!("Not repeating: {message}");
+                return;
+            }
+        };
+
+        self.send_generation = self.send_generation.wrapping_add(1);
+        let generation = self.send_generation;
+        self.loading = true;
+
+        log::debug!(
+            "Starting {}x {} {} ({} at a time)",
+            count,
+            request.method.as_str(),
+            request.url,
+            concurrency
+        );
+
+        let client = match crate::http_client::HttpClient::for_route(
+            &request.route,
+            request.minimal_headers,
+            request.ignore_tls_errors,
+            request.protocol,
+            &request.host_overrides,
+        ) {
+            Ok(client) => client
+                .with_network_profile(request.network_profile)
+                .with_timeout(request.timeout_secs)
+                .with_follow_redirects(request.follow_redirects),
+            Err(e) => {
+                log::error!("Cannot send through {}: {}", request.route.label(), e);
+                return;
+            }
+        };
+        let inflight = client.start_repeat(
+            sent.method.clone(),
+            sent.url,
+            sent.headers,
+            sent.body,
+            count,
+            concurrency,
+        );
+        self.abort_handle = Some(inflight.abort_handle());
+        self.running_repeat = Some(RunningRepeat {
+            request,
+            requested: count,
+            concurrency,
+            started: std::time::Instant::now(),
+            attempts: inflight.attempts(),
+        });
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            // Polled rather than awaited so the Cancel button's progress
+            // count stays live while the batch runs.
+            while !inflight.is_finished() {
+                cx.background_executor()
+                    .timer(std::time::Duration::from_millis(200))
+                    .await;
+                this.update(cx, |_, cx| cx.notify())?;
+            }
+            this.update(cx, |this, cx| {
+                if this.send_generation != generation {
+                    return; // canceled (already reported) or superseded
+                }
+                this.finish_repeat(false, cx);
+            })?;
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Close out the running batch and report whatever it completed.
+    fn finish_repeat(&mut self, canceled: bool, cx: &mut Context<Self>) {
+        let Some(run) = self.running_repeat.take() else {
+            return;
+        };
+        let attempts = run
+            .attempts
+            .lock()
+            .map(|attempts| attempts.clone())
+            .unwrap_or_default();
+        let report = RepeatReport::new(
+            run.requested,
+            run.concurrency,
+            run.started.elapsed().as_millis() as u64,
+            canceled,
+            attempts,
+        );
+        self.loading = false;
+        self.abort_handle = None;
+        cx.emit(RepeatCompleted {
+            request: run.request,
+            report: std::sync::Arc::new(report),
+        });
+        cx.notify();
+    }
+}
+
+impl RequestEditor {
+    /// Pill for the Headers / Auth / Params / Body strip.
+    fn panel_pill(&self, index: usize, id: &'static str, label: &'static str, cx: &Context<Self>) -> Stateful<Div> {
+        let theme = cx.theme();
+        a11y::focusable(crate::ui::segment_pill(theme, self.active_tab == index).id(id), theme)
+            .when(self.active_tab != index, |s| s.hover(|s| s.text_color(theme.foreground)))
+            .on_click(cx.listener(move |this, _event: &gpui::ClickEvent, _window, cx| {
+                this.active_tab = index;
+                cx.notify();
+            }))
+            .child(label)
+    }
+
+    /// Switches between the tab strip and the two-column layout. Only what is
+    /// rendered changes; every row input and subscription is kept as is.
+    fn render_layout_toggle(&self, cx: &Context<Self>) -> Button {
+        let split = self.split_layout;
+        Button::new("editor-layout-toggle")
+            .ghost()
+            .xsmall()
+            .label(if split { "Tabs" } else { "Split" })
+            .tooltip(if split {
+                "Show headers, params and body as tabs"
+            } else {
+                "Show the body next to headers and params"
+            })
+            .on_click(cx.listener(Self::toggle_split_layout))
+    }
+
+    /// Everything below the URL bar as one tab strip (the default layout).
+    fn render_tabbed_panels(&self, cx: &Context<Self>) -> Div {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .w_full()
+            .flex_1()
+            .min_h_0()  // Critical for scrolling to work
+            .child(a11y::tab_group(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        crate::ui::segmented_bar(cx.theme())
+                            .child(self.panel_pill(0, "tab-headers", "Headers", cx))
+                            .child(self.panel_pill(1, "tab-auth", "Auth", cx))
+                            .child(self.panel_pill(2, "tab-params", "Params", cx))
+                            .child(self.panel_pill(3, "tab-body", "Body", cx))
+                            .child(self.panel_pill(4, "tab-tests", "Tests", cx)),
+                    )
+                    .child(self.render_layout_toggle(cx)),
+                tab_order::EDITOR_TABS,
+            ))
+            .child(self.render_panel_group(self.active_tab, cx))
+    }
+
+    /// Headers / Auth / Params tabs on the left, the body always on the right.
+    fn render_split_panels(&self, cx: &Context<Self>) -> Div {
+        let left_tab = if self.active_tab == 3 { 0 } else { self.active_tab };
+        let column = || div().flex().flex_col().gap_2().flex_1().min_w_0().min_h_0();
+        div()
+            .flex()
+            .flex_row()
+            .gap_3()
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .child(
+                column()
+                    .child(a11y::tab_group(
+                        crate::ui::segmented_bar(cx.theme())
+                            .child(self.panel_pill(0, "tab-headers", "Headers", cx))
+                            .child(self.panel_pill(1, "tab-auth", "Auth", cx))
+                            .child(self.panel_pill(2, "tab-params", "Params", cx))
+                            .child(self.panel_pill(4, "tab-tests", "Tests", cx)),
+                        tab_order::EDITOR_TABS,
+                    ))
+                    .child(self.render_panel_group(left_tab, cx)),
+            )
+            .child(
+                column()
+                    .border_l_1()
+                    .border_color(cx.theme().border)
+                    .pl_3()
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .child(div().py_1().text_sm().font_weight(FontWeight::SEMIBOLD).child("Body"))
+                            .child(self.render_layout_toggle(cx)),
+                    )
+                    .child(self.render_panel_group(3, cx)),
+            )
+    }
+
+    /// The open panel as the editor's tab group, so its rows come after the
+    /// pills in the Tab order.
+    fn render_panel_group(&self, index: usize, cx: &Context<Self>) -> Div {
+        a11y::tab_group(div(), tab_order::EDITOR)
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_h_0()
+            .w_full()
+            .child(self.render_panel(index, cx))
+    }
+
+    fn render_panel(&self, index: usize, cx: &Context<Self>) -> AnyElement {
+        match index {
+            0 => self.render_headers_panel(cx).into_any_element(),
+            1 => self.render_auth_panel().into_any_element(),
+            2 => self.render_params_panel(cx).into_any_element(),
+            4 => self.render_tests_panel(cx).into_any_element(),
+            _ => self.render_body_panel(cx).into_any_element(),
+        }
+    }
+
+    fn render_headers_panel(&self, cx: &Context<Self>) -> Div {
+        // Viewport: owns the size constraint so the list can
+        // shrink and actually scroll; also hosts the scrollbar,
+        // which must be the scroller's sibling rather than its
+        // child (an absolute layer inside the scroller scrolls
+        // away with the content).
+        div()
+            .flex_1()
+            .min_h_0()
+            .flex()
+            .flex_col()
+            .child(
+                h_flex()
+                    .justify_end()
+                    .gap_1()
+                    .px_2()
+                    .pt_2()
+                    .child(self.render_default_headers_button(cx))
+                    .child(self.render_presets_button("header-presets", cx)),
+            )
+            .child(
+                // Scrollable headers list
+                v_flex()
+                    .id("headers-scroll-container")
+                    .gap_2()
+                    .p_2()
+                    .pb_4()  // Bottom padding to prevent last row from being obscured
+                    .size_full()
+                    .track_scroll(&self.headers_scroll_handle)
+                    .overflow_scroll()
+                    .children(self.headers.iter().enumerate().map(
+                |(index, header)| {
+                    let enabled = header.enabled;
+                    let is_predefined = !matches!(header.header_type, HeaderType::Custom);
+                    let is_custom = matches!(header.header_type, HeaderType::Custom);
+                    let is_auto_calculated = header.predefined.map(|p| p.is_auto_calculated()).unwrap_or(false);
+                    let is_transport = header.predefined.is_some_and(|p| p.is_transport_managed());
+                    let custom_transport =
+                        is_custom && crate::types::is_transport_header(&header.key_input.read(cx).value());
+                    let is_content_type = header.predefined == Some(PredefinedHeader::ContentType);
+                    let content_type_manual = is_content_type && self.content_type_sync.is_manual();
+
+                    div()
+                        // Not sent while "minimal headers" is on, nor Content-Length
+                        // while the body goes out chunked.
+                        .when(self.minimal_headers && header.predefined.is_some(), |s| s.opacity(0.4))
+                        .when(self.chunked && is_auto_calculated, |s| s.opacity(0.4))
+                        // Never sent from a row: the transport sets these itself.
+                        .when(custom_transport, |s| s.opacity(0.4))
+                        .flex()
+                        .flex_row()
+                        .gap_2()
+                        .items_center() // Vertical center alignment
+                        .w_full()
+                        .when(self.is_movable(RowKind::Header, index, cx), |row| {
+                            row.drag_over::<DraggedRow>(|style, dragged, _, cx| {
+                                if dragged.kind == RowKind::Header { style.bg(cx.theme().drop_target) } else { style }
+                            })
+                            .on_drop(cx.listener(move |this, dragged: &DraggedRow, window, cx| {
+                                if dragged.kind == RowKind::Header {
+                                    this.move_row(RowKind::Header, dragged.index, index, window, cx);
+                                }
+                            }))
+                        })
+                        .child(self.render_drag_handle(RowKind::Header, index, cx))
+                        .child(
+                            div().flex_shrink_0().child(
+                                Checkbox::new(("header-checkbox", index))
+                                    .checked(enabled)
+                                    .on_click(cx.listener(
+                                        move |this, checked, window, cx| {
+                                            this.toggle_header(index, checked, window, cx);
+                                        },
+                                    ))
+                            )
+                        )
+                        .child({
+                            // Key input - disabled for predefined headers.
+                            //
+                            // gpui-component registers the up/down action
+                            // handlers only for multi-line inputs (input.rs
+                            // `.when(is_multi_line)`), so on a single-line
+                            // field the arrow keys never reach the completion
+                            // menu and the highlight cannot move. Enter/Escape
+                            // work because their handlers are unconditional.
+                            // We bridge the two arrow actions to the menu via
+                            // the public `handle_action_for_context_menu`; the
+                            // single-line Input ignores them, so they bubble
+                            // up to this wrapper.
+                            let key_input = header.key_input.clone();
+                            div()
+                                .flex_1()
+                                .when(is_custom, |this| {
+                                    let down_input = key_input.clone();
+                                    let up_input = key_input.clone();
+                                    this.on_action(move |_: &MoveDown, window, cx| {
+                                        down_input.update(cx, |state, cx| {
+                                            state.handle_action_for_context_menu(Box::new(MoveDown), window, cx);
+                                        });
+                                    })
+                                    .on_action(move |_: &MoveUp, window, cx| {
+                                        up_input.update(cx, |state, cx| {
+                                            state.handle_action_for_context_menu(Box::new(MoveUp), window, cx);
+                                        });
+                                    })
+                                })
+                                .child(Input::new(&header.key_input).disabled(is_predefined))
+                        })
+                        .child(
+                            // Value input - disabled for transport-managed headers; Content-Type
+                            // follows the body ("auto") until edited ("manual")
+                            // Delete button embedded as suffix for custom headers
+                            div()
+                                .flex_1()
+                                .child(
+                                    Input::new(&header.value_input)
+                                        .disabled(is_transport)
+                                        .when(is_transport || (is_content_type && !content_type_manual), |input| {
+                                            input.suffix(
+                                                div()
+                                                    .text_xs()
+                                                    .text_color(cx.theme().muted_foreground)
+                                                    .child("auto"),
+                                            )
+                                        })
+                                        .when(is_content_type && content_type_manual, |input| {
+                                            input.suffix(
+                                                Button::new("content-type-reset")
+                                                    .ghost()
+                                                    .xsmall()
+                                                    .label("manual ×")
+                                                    .tooltip("Go back to the body's Content-Type")
+                                                    .on_click(cx.listener(|this, _, window, cx| {
+                                                        this.reset_content_type(window, cx);
+                                                    })),
+                                            )
+                                        })
+                                        .when(is_custom, |input| {
+                                            input.suffix(
+                                                h_flex()
+                                                    .child(
+                                                        Button::new(("delete-header", index))
+                                                            .ghost()
+                                                            .xsmall()
+                                                            .label("×")
+                                                            .tooltip(a11y::delete_row("header", index))
+                                                            .on_click(cx.listener(
+                                                                move |this, _, window, cx| {
+                                                                    this.remove_header_row(index, window, cx);
+                                                                },
+                                                            )),
+                                                    )
+                                                    .child(self.render_row_menu(
+                                                        ("header-row-menu", index),
+                                                        index,
+                                                        RowKind::Header,
+                                                        cx,
+                                                    )),
+                                            )
+                                        })
+                                ),
+                        )
+                        // Custom rows have theirs in the value field; an
+                        // auto-calculated value field is disabled, so
+                        // predefined rows get it beside the field.
+                        .when(is_predefined, |row| {
+                            row.child(
+                                Button::new(("delete-header", index))
+                                    .ghost()
+                                    .xsmall()
+                                    .label("×")
+                                    .tooltip(a11y::delete_row("header", index))
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.remove_header_row(index, window, cx);
+                                    })),
+                            )
+                        })
+                        .child(div().flex_shrink_0().w(px(140.)).child(Input::new(&header.description_input)))
+                },
+            ))
+                    .children(self.render_preset_rows(RowKind::Header, cx))
+            )
+            .vertical_scrollbar(&self.headers_scroll_handle)
+    }
+
+    /// Presets menu above Headers and Params: tick presets to apply them,
+    /// or open one for editing.
+    fn render_presets_button(&self, id: &'static str, cx: &Context<Self>) -> impl IntoElement {
+        let editor = cx.entity();
+        let presets: Vec<(i64, String)> = self.presets.iter().map(|p| (p.id, p.name.clone())).collect();
+        let applied = self.applied_presets.clone();
+        let count = crate::presets::applied(&self.presets, &applied).len();
+        Button::new(id)
+            .ghost()
+            .xsmall()
+            .label(if count == 0 { "Presets".to_string() } else { format!("Presets ({count})") })
+            .tooltip("Add the headers and params of saved presets to this request")
+            .dropdown_menu(move |menu, _window, _cx| {
+                let mut menu = menu.label("Apply");
+                if presets.is_empty() {
+                    menu = menu.item(PopupMenuItem::new("No presets yet").disabled(true));
+                }
+                for (id, name) in &presets {
+                    let (editor, id) = (editor.clone(), *id);
+                    menu = menu.item(PopupMenuItem::new(name.clone()).checked(applied.contains(&id)).on_click(
+                        move |_, _window, cx| editor.update(cx, |editor, cx| editor.toggle_preset(id, cx)),
+                    ));
+                }
+                menu = menu.separator();
+                for (id, name) in &presets {
+                    let (editor, id) = (editor.clone(), *id);
+                    menu = menu.item(PopupMenuItem::new(format!("Edit {name}…")).on_click(move |_, _window, cx| {
+                        editor.update(cx, |_, cx| cx.emit(EditPreset { id: Some(id) }));
+                    }));
+                }
+                let editor = editor.clone();
+                menu.item(PopupMenuItem::new("New preset…").on_click(move |_, _window, cx| {
+                    editor.update(cx, |_, cx| cx.emit(EditPreset { id: None }));
+                }))
+            })
+    }
+
+    /// Read-only rows for what the applied presets add to the Headers or
+    /// Params tab, each with its preset's name. A row the request overrides
+    /// with one of its own is dimmed.
+    fn render_preset_rows(&self, kind: RowKind, cx: &Context<Self>) -> Vec<AnyElement> {
+        let theme = cx.theme();
+        let own: Vec<String> = match kind {
+            RowKind::Header => self
+                .headers
+                .iter()
+                .filter(|h| h.enabled && matches!(h.header_type, HeaderType::Custom))
+                .map(|h| h.key_input.read(cx).value().to_string())
+                .collect(),
+            RowKind::Param => self
+                .params
+                .iter()
+                .filter(|p| p.enabled)
+                .map(|p| p.key_input.read(cx).value().to_string())
+                .collect(),
+        };
+        let cell = |text: String| {
+            div()
+                .flex_1()
+                .min_w_0()
+                .px_2()
+                .py_1()
+                .truncate()
+                .text_sm()
+                .rounded(theme.radius)
+                .border_1()
+                .border_color(theme.border)
+                .bg(theme.muted)
+                .child(text)
+        };
+        crate::presets::applied(&self.presets, &self.applied_presets)
+            .into_iter()
+            .flat_map(|preset| {
+                let rows = match kind {
+                    RowKind::Header => preset.headers,
+                    RowKind::Param => preset.params,
+                };
+                let name = preset.name;
+                rows.into_iter().map(move |(key, value)| (name.clone(), key, value))
+            })
+            .enumerate()
+            .map(|(ix, (name, key, value))| {
+                let overridden = match kind {
+                    RowKind::Header => own.iter().any(|k| k.eq_ignore_ascii_case(&key)),
+                    RowKind::Param => own.contains(&key),
+                };
+                let tooltip = if overridden {
+                    format!("From preset {name}; this request sets its own {key}")
+                } else {
+                    format!("From preset {name}; edit the preset to change it")
+                };
+                h_flex()
+                    .id((if matches!(kind, RowKind::Header) { "preset-header" } else { "preset-param" }, ix))
+                    .gap_2()
+                    .w_full()
+                    .text_color(theme.muted_foreground)
+                    .when(overridden, |s| s.opacity(0.4))
+                    .child(cell(key))
+                    .child(cell(value))
+                    .child(
+                        div()
+                            .flex_shrink_0()
+                            .px_1p5()
+                            .rounded(theme.radius)
+                            .text_xs()
+                            .bg(theme.accent)
+                            .text_color(theme.accent_foreground)
+                            .child(name),
+                    )
+                    .tooltip(move |window, cx| gpui_component::tooltip::Tooltip::new(tooltip.clone()).build(window, cx))
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn render_auth_panel(&self) -> Div {
+        div()
+            .p_2()
+            .w_full()
+            .flex_1()
+            .flex()
+            .flex_col()
+            .min_h_0()
+            .child(self.auth_editor.clone())
+    }
+
+    fn render_params_panel(&self, cx: &Context<Self>) -> Div {
+        // Viewport: owns the size constraint so the list can
+        // shrink and actually scroll; also hosts the scrollbar,
+        // which must be the scroller's sibling rather than its
+        // child (an absolute layer inside the scroller scrolls
+        // away with the content).
+        div()
+            .flex_1()
+            .min_h_0()
+            .flex()
+            .flex_col()
+            .child(
+                h_flex().justify_end().gap_3().px_2().pt_2().child(self.render_presets_button("param-presets", cx)).child(
+                    Checkbox::new("keep-brackets")
+                        .label("Keep [ ] unescaped")
+                        .checked(self.param_encoding == ParamEncoding::KeepBrackets)
+                        .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                            let encoding = if *checked { ParamEncoding::KeepBrackets } else { ParamEncoding::Strict };
+                            this.set_param_encoding(encoding, window, cx);
+                        })),
+                ).child(
+                    Checkbox::new("plus-for-space")
+                        .label("Spaces as +")
+                        .checked(self.plus_for_space)
+                        .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                            this.set_plus_for_space(*checked, window, cx);
+                        })),
+                ),
+            )
+            .child(
+                // Scrollable params list
+                v_flex()
+                    .id("params-scroll-container")
+                    .gap_2()
+                    .p_2()
+                    .pb_4()
+                    .size_full()
+                    .track_scroll(&self.params_scroll_handle)
+                    .overflow_scroll()
+            .children(self.params.iter().enumerate().map(
+                |(index, param)| {
+                    let enabled = param.enabled;
+                    let encoded = param.encoded;
+                    let has_key = !param.key_input.read(cx).value().is_empty();
+
+                    div()
+                        .flex()
+                        .flex_row()
+                        .gap_2()
+                        .items_center()
+                        .w_full()
+                        .when(self.is_movable(RowKind::Param, index, cx), |row| {
+                            row.drag_over::<DraggedRow>(|style, dragged, _, cx| {
+                                if dragged.kind == RowKind::Param { style.bg(cx.theme().drop_target) } else { style }
+                            })
+                            .on_drop(cx.listener(move |this, dragged: &DraggedRow, window, cx| {
+                                if dragged.kind == RowKind::Param {
+                                    this.move_row(RowKind::Param, dragged.index, index, window, cx);
+                                }
+                            }))
+                        })
+                        .child(self.render_drag_handle(RowKind::Param, index, cx))
+                        .child(
+                            // Checkbox
+                            div().flex_shrink_0().child(
+                                Checkbox::new(("param-checkbox", index))
+                                    .checked(enabled)
+                                    .on_click(cx.listener(
+                                        move |this, _, window, cx| {
+                                            this.toggle_param(index, window, cx);
+                                        },
+                                    ))
+                            )
+                        )
+                        .child(
+                            // Key input
+                            div()
+                                .flex_1()
+                                .child(Input::new(&param.key_input)),
+                        )
+                        .child(
+                            // Value input with delete button
+                            div()
+                                .flex_1()
+                                .child(
+                                    Input::new(&param.value_input)
+                                        .suffix(
+                                            h_flex()
+                                                .when(has_key || encoded, |this| {
+                                                    this.child(
+                                                        Button::new(("encoded-param", index))
+                                                            .ghost()
+                                                            .xsmall()
+                                                            .label("%")
+                                                            .selected(encoded)
+                                                            .tooltip(if encoded {
+                                                                "Value is already encoded: sent as typed"
+                                                            } else {
+                                                                "Value is already encoded (don't escape it again)"
+                                                            })
+                                                            .on_click(cx.listener(move |this, _, window, cx| {
+                                                                this.toggle_param_encoded(index, window, cx);
+                                                            })),
+                                                    )
+                                                })
+                                                .when(has_key, |this| {
+                                                    this.child(
+                                                        Button::new(("array-item-param", index))
+                                                            .ghost()
+                                                            .xsmall()
+                                                            .label("[]+")
+                                                            .tooltip("Add array item")
+                                                            .on_click(cx.listener(move |this, _, window, cx| {
+                                                                this.add_array_item(index, window, cx);
+                                                            })),
+                                                    )
+                                                })
+                                                .child(
+                                                    Button::new(("delete-param", index))
+                                                        .ghost()
+                                                        .xsmall()
+                                                        .label("×")
+                                                        .tooltip(a11y::delete_row("param", index))
+                                                        .on_click(cx.listener(
+                                                            move |this, _, window, cx| {
+                                                                this.remove_param(index, window, cx);
+                                                            },
+                                                        )),
+                                                )
+                                                .child(self.render_row_menu(
+                                                    ("param-row-menu", index),
+                                                    index,
+                                                    RowKind::Param,
+                                                    cx,
+                                                )),
+                                        )
+                                ),
+                        )
+                        .child(div().flex_shrink_0().w(px(140.)).child(Input::new(&param.description_input)))
+                },
+            ))
+                    .children(self.render_preset_rows(RowKind::Param, cx))
+                    .when(!self.path_variables.is_empty(), |this| this.child(self.render_path_variables(cx)))
+            )
+            .vertical_scrollbar(&self.params_scroll_handle)
+    }
+
+    /// "Path Variables" under the params: a value for each `:name` segment
+    /// of the URL, put into the path at send time.
+    fn render_path_variables(&self, cx: &Context<Self>) -> Div {
+        let theme = cx.theme();
+        v_flex()
+            .gap_2()
+            .pt_2()
+            .border_t_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(theme.muted_foreground)
+                    .child("Path Variables"),
+            )
+            .children(self.path_variables.iter().map(|row| {
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .w_full()
+                    .child(div().flex_1().pl_6().text_sm().text_color(theme.foreground).child(format!(":{}", row.name)))
+                    .child(div().flex_1().child(Input::new(&row.value_input)))
+            }))
+    }
+
+    /// The body editor, for the Body tab or the right-hand column. On a GET
+    /// or HEAD a notice above it says the body is left off, with the
+    /// override to send it anyway.
+    fn render_body_panel(&self, cx: &Context<Self>) -> Div {
+        let method = self.selected_method(cx);
+        let notice = (method.is_bodyless() && self.body_editor.read(cx).has_body()).then(|| {
+            h_flex()
+                .gap_3()
+                .pb_2()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(if self.send_body_anyway {
+                    format!("The body is sent with this {} request.", method.as_str())
+                } else {
+                    format!("{} requests are sent without this body or its Content-Type.", method.as_str())
+                })
+                .child(
+                    Checkbox::new("send-body-anyway")
+                        .label("Send body anyway")
+                        .checked(self.send_body_anyway)
+                        .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                            this.send_body_anyway = *checked;
+                            cx.notify();
+                        })),
+                )
+        });
+        div()
+            .p_2()
+            .w_full()
+            .flex_1()
+            .flex()
+            .flex_col()
+            .min_h_0()  // Critical for scrolling to work
+            .children(notice)
+            .child(self.body_editor.clone())
+    }
+}
+
 impl EventEmitter<RequestCompleted> for RequestEditor {}
+impl EventEmitter<RepeatCompleted> for RequestEditor {}
 impl EventEmitter<OpenCodeSnippet> for RequestEditor {}
+impl EventEmitter<SaveRequested> for RequestEditor {}
 impl EventEmitter<RequestCancelled> for RequestEditor {}
+impl EventEmitter<EnvVarEdited> for RequestEditor {}
+impl EventEmitter<EnvOverrideEdited> for RequestEditor {}
+impl EventEmitter<ConfigureNetworkProfile> for RequestEditor {}
+impl EventEmitter<ConfigureHostOverrides> for RequestEditor {}
+impl EventEmitter<ConfigureUserAgent> for RequestEditor {}
+impl EventEmitter<UrlEdited> for RequestEditor {}
+impl EventEmitter<ConfigureProxy> for RequestEditor {}
+impl EventEmitter<EditPreset> for RequestEditor {}
+impl EventEmitter<SplitLayoutChanged> for RequestEditor {}
+impl EventEmitter<DefaultHeadersChanged> for RequestEditor {}
 
 impl Render for RequestEditor {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let var_preview = self.render_var_preview(cx);
+        let url_problem = self.url_problem(cx);
+        let method_problem = self.method_problem(cx);
+        let custom_method = self.is_custom_method(cx);
+        let url_empty = self.url_input.read(cx).value().trim().is_empty();
         let theme = cx.theme();
 
         div().id("request-editor-root").flex().flex_col().w_full().h_full().on_click(cx.listener(|_, _, _, cx| cx.stop_propagation())).child(
@@ -1192,322 +4277,258 @@ impl Render for RequestEditor {
                         .w_full()
                         .child(
                             // Method selector - prevent it from growing
-                            div()
+                            a11y::tab_group(div(), tab_order::METHOD)
                                 .flex_shrink_0()
                                 .w(px(METHOD_SELECT_WIDTH))
+                                .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                                    this.on_method_key_down(event, window, cx);
+                                }))
+                                .on_action(cx.listener(|this, _: &NextMethod, window, cx| {
+                                    this.cycle_method(1, window, cx);
+                                }))
+                                .on_action(cx.listener(|this, _: &PrevMethod, window, cx| {
+                                    this.cycle_method(-1, window, cx);
+                                }))
                                 .child(Select::new(&self.method_select)),
                         )
+                        .when(custom_method, |this| {
+                            this.child(
+                                div().flex_shrink_0().w(px(METHOD_SELECT_WIDTH + 18.)).child(
+                                    Input::new(&self.custom_method_input)
+                                        .when(method_problem.is_some(), |input| input.border_color(theme.danger)),
+                                ),
+                            )
+                        })
                         .child(
                             // URL input - takes all remaining space
-                            div()
+                            // Method shortcuts only apply while the URL bar has focus.
+                            a11y::tab_group(div(), tab_order::URL_BAR)
                                 .flex_1()
                                 .overflow_hidden()
-                                .child(Input::new(&self.url_input)),
+                                .on_action(cx.listener(|this, _: &OpenMethodMenu, window, cx| {
+                                    this.open_method_menu(window, cx);
+                                }))
+                                .on_action(cx.listener(|this, _: &NextMethod, window, cx| {
+                                    this.cycle_method(1, window, cx);
+                                }))
+                                .on_action(cx.listener(|this, _: &PrevMethod, window, cx| {
+                                    this.cycle_method(-1, window, cx);
+                                }))
+                                .child(
+                                    Input::new(&self.url_input)
+                                        .when(url_problem.is_some(), |input| input.border_color(theme.danger)),
+                                ),
+                        )
+                        .child(
+                            a11y::tab_group(div(), tab_order::URL_BAR_EXTRAS)
+                                .flex_shrink_0()
+                                .child(self.render_route_selector(cx)),
+                        )
+                        .child(
+                            a11y::tab_group(div(), tab_order::URL_BAR_EXTRAS).flex_shrink_0().child(
+                                Button::new("save-request-btn")
+                                    .ghost()
+                                    .label("Save")
+                                    .tooltip("Save to a collection (Ctrl+S)")
+                                    .on_click(cx.listener(|_this, _ev, _window, cx| {
+                                        cx.emit(SaveRequested);
+                                    })),
+                            ),
                         )
                         .child(
                             // Code snippet button (</>) - opens the code dialog
-                            div().flex_shrink_0().child(
+                            a11y::tab_group(div(), tab_order::URL_BAR_EXTRAS).flex_shrink_0().child(
                                 Button::new("code-snippet-btn")
                                     .ghost()
                                     .icon(Icon::empty().path("icons/code.svg"))
+                                    .tooltip("Show as code snippet")
                                     .on_click(cx.listener(|_this, _ev, _window, cx| {
                                         cx.emit(OpenCodeSnippet);
                                     })),
                             ),
                         )
+                        .child(
+                            a11y::tab_group(div(), tab_order::URL_BAR_EXTRAS)
+                                .id("timeout-field")
+                                .flex_shrink_0()
+                                .w(px(64.))
+                                .tooltip(|window, cx| {
+                                    gpui_component::tooltip::Tooltip::new("Timeout in seconds (0 = none)").build(window, cx)
+                                })
+                                .child(
+                                    Input::new(&self.timeout_input)
+                                        .suffix(div().text_xs().text_color(cx.theme().muted_foreground).child("s")),
+                                ),
+                        )
+                        .child(
+                            a11y::tab_group(div(), tab_order::URL_BAR_EXTRAS).flex_shrink_0().child(
+                                Button::new("download-mode-btn")
+                                    .ghost()
+                                    .label("To file")
+                                    .selected(self.download_mode)
+                                    .tooltip(if self.download_mode {
+                                        "Download mode: Send saves the body to a file you choose"
+                                    } else {
+                                        "Save the response body to a file instead of showing it"
+                                    })
+                                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                                        this.download_mode = !this.download_mode;
+                                        cx.notify();
+                                    })),
+                            ),
+                        )
                         .child(
                             // Send button - prevent it from shrinking.
                             // While loading it becomes a Cancel button.
-                            div().flex_shrink_0().child(if self.loading {
+                            a11y::tab_group(div(), tab_order::SEND).flex_shrink_0().child(if self.loading {
+                                let label = match (&self.running_repeat, &self.download_progress) {
+                                    (Some(run), _) => format!(
+                                        "Cancel ({}/{})",
+                                        run.attempts.lock().map(|a| a.len()).unwrap_or(0),
+                                        run.requested
+                                    ),
+                                    (None, Some(written)) => format!(
+                                        "Cancel ({})",
+                                        crate::format::format_size(
+                                            written.load(std::sync::atomic::Ordering::Relaxed) as usize
+                                        )
+                                    ),
+                                    (None, None) => "Cancel".to_string(),
+                                };
                                 Button::new("cancel-btn")
                                     .danger()
-                                    .label("Cancel")
+                                    .label(label)
                                     .on_click(cx.listener(Self::cancel_request))
                             } else {
                                 Button::new("send-btn")
                                     .primary()
-                                    .label("Send")
+                                    .label(if self.download_mode { "Download…" } else { "Send" })
+                                    .disabled(url_empty || url_problem.is_some() || method_problem.is_some())
                                     .on_click(cx.listener(Self::send_request))
                             }),
-                        ),
-                )
-                .child(
-                    // Tabs for Headers and Body
-                    div()
-                        .flex()
-                        .flex_col()
-                        .gap_2()
-                        .w_full()
-                        .flex_1()
-                        .min_h_0()  // Critical for scrolling to work
-                        .child(
-                            crate::ui::segmented_bar(theme)
-                                .child(
-                                    crate::ui::segment_pill(theme, self.active_tab == 0)
-                                        .id("tab-headers")
-                                        .when(self.active_tab != 0, |s| {
-                                            s.hover(|s| s.text_color(theme.foreground))
-                                        })
-                                        .on_click(cx.listener(
-                                            |this, _event: &gpui::ClickEvent, _window, cx| {
-                                                this.active_tab = 0;
-                                                cx.notify();
-                                            },
-                                        ))
-                                        .child("Headers"),
-                                )
-                                .child(
-                                    crate::ui::segment_pill(theme, self.active_tab == 1)
-                                        .id("tab-auth")
-                                        .when(self.active_tab != 1, |s| {
-                                            s.hover(|s| s.text_color(theme.foreground))
-                                        })
-                                        .on_click(cx.listener(
-                                            |this, _event: &gpui::ClickEvent, _window, cx| {
-                                                this.active_tab = 1;
-                                                cx.notify();
-                                            },
-                                        ))
-                                        .child("Auth"),
-                                )
-                                .child(
-                                    crate::ui::segment_pill(theme, self.active_tab == 2)
-                                        .id("tab-params")
-                                        .when(self.active_tab != 2, |s| {
-                                            s.hover(|s| s.text_color(theme.foreground))
-                                        })
-                                        .on_click(cx.listener(
-                                            |this, _event: &gpui::ClickEvent, _window, cx| {
-                                                this.active_tab = 2;
-                                                cx.notify();
-                                            },
-                                        ))
-                                        .child("Params"),
-                                )
-                                .child(
-                                    crate::ui::segment_pill(theme, self.active_tab == 3)
-                                        .id("tab-body")
-                                        .when(self.active_tab != 3, |s| {
-                                            s.hover(|s| s.text_color(theme.foreground))
-                                        })
-                                        .on_click(cx.listener(
-                                            |this, _event: &gpui::ClickEvent, _window, cx| {
-                                                this.active_tab = 3;
-                                                cx.notify();
-                                            },
-                                        ))
-                                        .child("Body"),
-                                ),
                         )
-                        .when(self.active_tab == 0, |this| {
+                        .when(!self.loading, |this| {
+                            // "Send ×N": count (and, in parallel, attempts at a
+                            // time) fields plus a dropdown to send or pick a count.
+                            let editor = cx.entity();
+                            let parallel = self.repeat_parallel;
+                            let auto_retry = self.auto_retry;
+                            let settings = self.repeat_settings(cx);
+                            let muted = cx.theme().muted_foreground;
                             this.child(
-                                // Viewport: owns the size constraint so the list can
-                                // shrink and actually scroll; also hosts the scrollbar,
-                                // which must be the scroller's sibling rather than its
-                                // child (an absolute layer inside the scroller scrolls
-                                // away with the content).
-                                div()
-                                    .flex_1()
-                                    .min_h_0()
+                                a11y::tab_group(div(), tab_order::URL_BAR_EXTRAS)
+                                    .flex_shrink_0()
+                                    .flex()
+                                    .items_center()
+                                    .gap_1()
                                     .child(
-                                        // Scrollable headers list
-                                        v_flex()
-                                            .id("headers-scroll-container")
-                                            .gap_2()
-                                            .p_2()
-                                            .pb_4()  // Bottom padding to prevent last row from being obscured
-                                            .size_full()
-                                            .track_scroll(&self.headers_scroll_handle)
-                                            .overflow_scroll()
-                                            .children(self.headers.iter().enumerate().map(
-                                        |(index, header)| {
-                                            let enabled = header.enabled;
-                                            let is_mandatory = matches!(header.header_type, HeaderType::Mandatory);
-                                            let is_predefined = !matches!(header.header_type, HeaderType::Custom);
-                                            let is_custom = matches!(header.header_type, HeaderType::Custom);
-                                            let is_auto_calculated = header.predefined.map(|p| p.is_auto_calculated()).unwrap_or(false);
-
+                                        div()
+                                            .id("repeat-count-field")
+                                            .w(px(64.))
+                                            .tooltip(|window, cx| {
+                                                gpui_component::tooltip::Tooltip::new(format!(
+                                                    "Times Send ×N sends (1–{MAX_REPEAT_COUNT})"
+                                                ))
+                                                .build(window, cx)
+                                            })
+                                            .child(
+                                                Input::new(&self.repeat_count_input)
+                                                    .prefix(div().text_xs().text_color(muted).child("×")),
+                                            ),
+                                    )
+                                    .when(parallel, |this| {
+                                        this.child(
                                             div()
-                                                .flex()
-                                                .flex_row()
-                                                .gap_2()
-                                                .items_center() // Vertical center alignment
-                                                .w_full()
-                                                .child(
-                                                    // Checkbox - disabled for mandatory headers
-                                                    div().flex_shrink_0().child(
-                                                        Checkbox::new(("header-checkbox", index))
-                                                            .checked(enabled)
-                                                            .disabled(is_mandatory)
-                                                            .on_click(cx.listener(
-                                                                move |this, checked, window, cx| {
-                                                                    this.toggle_header(index, checked, window, cx);
-                                                                },
-                                                            ))
-                                                    )
-                                                )
-                                                .child({
-                                                    // Key input - disabled for predefined headers.
-                                                    //
-                                                    // gpui-component registers the up/down action
-                                                    // handlers only for multi-line inputs (input.rs
-                                                    // `.when(is_multi_line)`), so on a single-line
-                                                    // field the arrow keys never reach the completion
-                                                    // menu and the highlight cannot move. Enter/Escape
-                                                    // work because their handlers are unconditional.
-                                                    // We bridge the two arrow actions to the menu via
-                                                    // the public `handle_action_for_context_menu`; the
-                                                    // single-line Input ignores them, so they bubble
-                                                    // up to this wrapper.
-                                                    let key_input = header.key_input.clone();
-                                                    div()
-                                                        .flex_1()
-                                                        .when(is_custom, |this| {
-                                                            let down_input = key_input.clone();
-                                                            let up_input = key_input.clone();
-                                                            this.on_action(move |_: &MoveDown, window, cx| {
-                                                                down_input.update(cx, |state, cx| {
-                                                                    state.handle_action_for_context_menu(Box::new(MoveDown), window, cx);
-                                                                });
-                                                            })
-                                                            .on_action(move |_: &MoveUp, window, cx| {
-                                                                up_input.update(cx, |state, cx| {
-                                                                    state.handle_action_for_context_menu(Box::new(MoveUp), window, cx);
-                                                                });
-                                                            })
-                                                        })
-                                                        .child(Input::new(&header.key_input).disabled(is_predefined))
+                                                .id("repeat-concurrency-field")
+                                                .w(px(88.))
+                                                .tooltip(|window, cx| {
+                                                    gpui_component::tooltip::Tooltip::new(format!(
+                                                        "Attempts at a time (1–{MAX_PARALLEL})"
+                                                    ))
+                                                    .build(window, cx)
                                                 })
                                                 .child(
-                                                    // Value input - disabled for auto-calculated headers and Content-Type
-                                                    // Delete button embedded as suffix for custom headers
-                                                    div()
-                                                        .flex_1()
-                                                        .child(
-                                                            Input::new(&header.value_input)
-                                                                .disabled(is_auto_calculated || header.predefined == Some(PredefinedHeader::ContentType))
-                                                                .when(is_custom, |input| {
-                                                                    input.suffix(
-                                                                        Button::new(("delete-header", index))
-                                                                            .ghost()
-                                                                            .xsmall()
-                                                                            .label("×")
-                                                                            .on_click(cx.listener(
-                                                                                move |this, event, window, cx| {
-                                                                                    this.remove_header_row(
-                                                                                        index, event, window, cx,
-                                                                                    );
-                                                                                },
-                                                                            ))
-                                                                    )
-                                                                })
-                                                        ),
-                                                )
-                                        },
-                                    ))
-                                    )
-                                    .vertical_scrollbar(&self.headers_scroll_handle),
-                            )
-                        })
-                        .when(self.active_tab == 1, |this| {
-                            this.child(
-                                div()
-                                    .p_2()
-                                    .w_full()
-                                    .flex_1()
-                                    .flex()
-                                    .flex_col()
-                                    .min_h_0()
-                                    .child(self.auth_editor.clone()),
-                            )
-                        })
-                        .when(self.active_tab == 2, |this| {
-                            this.child(
-                                // Viewport: owns the size constraint so the list can
-                                // shrink and actually scroll; also hosts the scrollbar,
-                                // which must be the scroller's sibling rather than its
-                                // child (an absolute layer inside the scroller scrolls
-                                // away with the content).
-                                div()
-                                    .flex_1()
-                                    .min_h_0()
+                                                    Input::new(&self.repeat_concurrency_input)
+                                                        .suffix(div().text_xs().text_color(muted).child("at once")),
+                                                ),
+                                        )
+                                    })
                                     .child(
-                                        // Scrollable params list
-                                        v_flex()
-                                            .id("params-scroll-container")
-                                            .gap_2()
-                                            .p_2()
-                                            .pb_4()
-                                            .size_full()
-                                            .track_scroll(&self.params_scroll_handle)
-                                            .overflow_scroll()
-                                    .children(self.params.iter().enumerate().map(
-                                        |(index, param)| {
-                                            let enabled = param.enabled;
-
-                                            div()
-                                                .flex()
-                                                .flex_row()
-                                                .gap_2()
-                                                .items_center()
-                                                .w_full()
-                                                .child(
-                                                    // Checkbox
-                                                    div().flex_shrink_0().child(
-                                                        Checkbox::new(("param-checkbox", index))
-                                                            .checked(enabled)
-                                                            .on_click(cx.listener(
-                                                                move |this, _, window, cx| {
-                                                                    this.toggle_param(index, window, cx);
-                                                                },
-                                                            ))
+                                        Button::new("send-repeat-btn")
+                                            .outline()
+                                            .label("×N")
+                                            .dropdown_menu(move |menu, _window, _cx| {
+                                                let send_editor = editor.clone();
+                                                let send_label = match &settings {
+                                                    Ok((count, _)) => format!("Send ×{count}"),
+                                                    Err(problem) => problem.clone(),
+                                                };
+                                                let mut menu = menu.item(
+                                                    PopupMenuItem::new(send_label)
+                                                        .disabled(settings.is_err())
+                                                        .on_click(move |_, window, cx| {
+                                                            send_editor.update(cx, |editor, cx| {
+                                                                match editor.repeat_settings(cx) {
+                                                                    Ok((count, concurrency)) => {
+                                                                        editor.send_repeated(count, concurrency, window, cx)
+                                                                    }
+                                                                    Err(problem) => log::warn!("Not repeating: {problem}"),
+                                                                }
+                                                            });
+                                                        }),
+                                                );
+                                                menu = menu.separator();
+                                                for count in REPEAT_COUNTS {
+                                                    let editor = editor.clone();
+                                                    menu = menu.item(
+                                                        PopupMenuItem::new(format!("×{count}"))
+                                                            .checked(matches!(settings, Ok((n, _)) if n == count))
+                                                            .on_click(move |_, window, cx| {
+                                                                editor.update(cx, |editor, cx| {
+                                                                    editor.repeat_count_input.update(cx, |input, cx| {
+                                                                        input.set_value(count.to_string(), window, cx);
+                                                                    });
+                                                                    cx.notify();
+                                                                });
+                                                            }),
+                                                    );
+                                                }
+                                                let parallel_editor = editor.clone();
+                                                let retry_editor = editor.clone();
+                                                menu.separator()
+                                                    .item(
+                                                        PopupMenuItem::new("Run in parallel")
+                                                            .checked(parallel)
+                                                            .on_click(move |_, _window, cx| {
+                                                                parallel_editor.update(cx, |editor, cx| {
+                                                                    editor.repeat_parallel = !editor.repeat_parallel;
+                                                                    cx.notify();
+                                                                });
+                                                            }),
                                                     )
-                                                )
-                                                .child(
-                                                    // Key input
-                                                    div()
-                                                        .flex_1()
-                                                        .child(Input::new(&param.key_input)),
-                                                )
-                                                .child(
-                                                    // Value input with delete button
-                                                    div()
-                                                        .flex_1()
-                                                        .child(
-                                                            Input::new(&param.value_input)
-                                                                .suffix(
-                                                                    Button::new(("delete-param", index))
-                                                                        .ghost()
-                                                                        .xsmall()
-                                                                        .label("×")
-                                                                        .on_click(cx.listener(
-                                                                            move |this, _, window, cx| {
-                                                                                this.remove_param(index, window, cx);
-                                                                            },
-                                                                        ))
-                                                                )
-                                                        ),
-                                                )
-                                        },
-                                    ))
-                                    )
-                                    .vertical_scrollbar(&self.params_scroll_handle),
-                            )
-                        })
-                        .when(self.active_tab == 3, |this| {
-                            // Body tab - render BodyEditor component
-                            this.child(
-                                div()
-                                    .p_2()
-                                    .w_full()
-                                    .flex_1()
-                                    .flex()
-                                    .flex_col()
-                                    .min_h_0()  // Critical for scrolling to work
-                                    .child(self.body_editor.clone())
+                                                    .item(
+                                                        PopupMenuItem::new("Auto-retry on 429 (Retry-After)")
+                                                            .checked(auto_retry)
+                                                            .on_click(move |_, _window, cx| {
+                                                                retry_editor.update(cx, |editor, cx| {
+                                                                    editor.set_auto_retry(!editor.auto_retry, cx);
+                                                                });
+                                                            }),
+                                                    )
+                                            }),
+                                    ),
                             )
                         }),
-                ),
+                )
+                .when_some(method_problem.or(url_problem), |this, problem| {
+                    this.child(div().text_xs().text_color(theme.danger).child(problem))
+                })
+                .children(var_preview)
+                .child(if self.split_layout {
+                    self.render_split_panels(cx).into_any_element()
+                } else {
+                    self.render_tabbed_panels(cx).into_any_element()
+                }),
         )
     }
 }
@@ -0,0 +1,275 @@
+//! "Export to File" / "Import Request": one request and its response in a
+//! single file. The JSON format goes through the serde derives on
+//! `RequestData` / `ResponseData`, so importing it recreates the tab as it
+//! was; the HTTP text format is for reading (or pasting into a bug report)
+//! and cannot be imported. Pure: builds the text, the caller writes it.
+//!
+//! JSON format (version 1):
+//!
+//! ```json
+//! {
+//!   "format": "poopman-request",
+//!   "version": 1,
+//!   "request": { "method": "GET", "url": "https://example.com/users", ... },
+//!   "response": { "status": 200, "headers": [...], "body": [...], ... }
+//! }
+//! ```
+//!
+//! `response` is `null` for a request that was never sent.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BodyType, FormDataValue, RequestData, ResponseData};
+
+/// Marker so an arbitrary JSON file is not mistaken for an exported request.
+pub const EXPORT_FORMAT: &str = "poopman-request";
+
+/// Bumped on incompatible changes; newer files are refused on import.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// The file formats offered by the export dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    HttpText,
+}
+
+impl ExportFormat {
+    /// All formats, in dialog order.
+    pub const ALL: [Self; 2] = [Self::Json, Self::HttpText];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Json => "JSON (can be imported again)",
+            Self::HttpText => "HTTP text",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::HttpText => "http",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedRequest {
+    pub format: String,
+    pub version: u32,
+    pub request: RequestData,
+    #[serde(default)]
+    pub response: Option<ResponseData>,
+}
+
+/// The file contents for `request` (and `response`, when there is one).
+pub fn export(format: ExportFormat, request: &RequestData, response: Option<&ResponseData>) -> Result<String> {
+    match format {
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(&ExportedRequest {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            request: request.clone(),
+            response: response.cloned(),
+        })?),
+        ExportFormat::HttpText => Ok(http_text(request, response)),
+    }
+}
+
+/// Read a file written by `export` in the JSON format.
+pub fn parse_export(json: &str) -> Result<ExportedRequest> {
+    let exported: ExportedRequest = serde_json::from_str(json)?;
+    if exported.format != EXPORT_FORMAT {
+        bail!("not an exported request (format \"{}\")", exported.format);
+    }
+    if exported.version > EXPORT_VERSION {
+        bail!(
+            "export version {} is newer than this app supports ({})",
+            exported.version,
+            EXPORT_VERSION
+        );
+    }
+    Ok(exported)
+}
+
+/// Suggested file name: method and last path segment, e.g. `get-users.json`.
+pub fn file_name(request: &RequestData, format: ExportFormat) -> String {
+    let url = request.url.split(['?', '#']).next().unwrap_or_default();
+    let after_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = after_scheme.split_once('/').map_or("", |(_, path)| path);
+    let segment = path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|s| !s.is_empty())
+        .unwrap_or("request");
+    let stem: String = format!("{}-{}", request.method.as_str(), segment)
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { '-' })
+        .collect();
+    format!("{stem}.{}", format.extension())
+}
+
+/// The request as it goes on the wire (auth folded in, like the code
+/// snippets), then the response in the same shape.
+fn http_text(request: &RequestData, response: Option<&ResponseData>) -> String {
+    let url = crate::types::effective_wire_url(&request.url, &request.auth, request.param_encoding);
+    let headers = crate::types::effective_wire_headers(&request.headers, &request.auth);
+    // An unparsable URL (an unresolved `{{var}}`, say) is written as is.
+    let (target, host) = match url::Url::parse(&url) {
+        Ok(parsed) => {
+            let host = parsed.host_str().map(|host| match parsed.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host.to_string(),
+            });
+            (parsed[url::Position::BeforePath..url::Position::AfterQuery].to_string(), host)
+        }
+        Err(_) => (url.clone(), None),
+    };
+
+    let mut out = format!("{} {} HTTP/1.1\n", request.method.as_str(), target);
+    if let Some(host) = host
+        && !headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("host"))
+    {
+        out.push_str(&format!("Host: {host}\n"));
+    }
+    for (key, value) in headers.iter().filter(|(k, _)| !k.trim().is_empty()) {
+        out.push_str(&format!("{key}: {value}\n"));
+    }
+    match &request.body {
+        BodyType::Raw { content, .. } if !content.is_empty() => {
+            out.push('\n');
+            out.push_str(content);
+            out.push('\n');
+        }
+        BodyType::FormData(rows) => {
+            out.push('\n');
+            for row in rows.iter().filter(|row| row.enabled && !row.key.trim().is_empty()) {
+                match &row.value {
+                    FormDataValue::Text(value) => out.push_str(&format!("{}={}\n", row.key, value)),
+                    FormDataValue::File { path } => out.push_str(&format!("{}=@{}\n", row.key, path)),
+                }
+            }
+        }
+        _ => {}
+    }
+
+    // No status means no response arrived; there is nothing to write.
+    if let Some(response) = response
+        && let Some(status) = response.status
+    {
+        let version = response.version.as_deref().unwrap_or("HTTP/1.1");
+        out.push_str(&format!("\n{} {} {}\n", version, status, response.status_text()));
+        for (key, value) in &response.headers {
+            out.push_str(&format!("{key}: {value}\n"));
+        }
+        if !response.body.is_empty() {
+            out.push('\n');
+            if response.is_text {
+                out.push_str(&response.body_text());
+                out.push('\n');
+            } else {
+                let size = crate::format::format_size(response.body.len());
+                out.push_str(&format!("[binary body, {size}]\n"));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{HttpMethod, RawSubtype};
+
+    fn post_users() -> RequestData {
+        let mut request = RequestData::new(HttpMethod::POST, "https://api.test:8443/v1/users?page=2".to_string());
+        request.headers = vec![("Content-Type".into(), "application/json".into())];
+        request.body = BodyType::Raw {
+            content: "{\"name\":\"ada\"}".to_string(),
+            subtype: RawSubtype::Json,
+        };
+        request
+    }
+
+    fn created() -> ResponseData {
+        ResponseData {
+            status: Some(201),
+            duration_ms: 12,
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: b"{\"id\":7}".to_vec(),
+            is_text: true,
+            route: Default::default(),
+            raw_header_values: vec![],
+            network_profile: Default::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
+        }
+    }
+
+    #[test]
+    fn json_round_trips_the_request_and_response() {
+        let json = export(ExportFormat::Json, &post_users(), Some(&created())).unwrap();
+        let back = parse_export(&json).unwrap();
+        assert_eq!(back.request.url, "https://api.test:8443/v1/users?page=2");
+        assert_eq!(back.request.method, HttpMethod::POST);
+        assert_eq!(back.request.headers, post_users().headers);
+        assert!(matches!(&back.request.body, BodyType::Raw { content, .. } if content == "{\"name\":\"ada\"}"));
+        let response = back.response.unwrap();
+        assert_eq!(response.status, Some(201));
+        assert_eq!(response.body, b"{\"id\":7}");
+
+        let unsent = export(ExportFormat::Json, &post_users(), None).unwrap();
+        assert!(parse_export(&unsent).unwrap().response.is_none());
+    }
+
+    #[test]
+    fn foreign_and_newer_files_are_refused() {
+        let json = export(ExportFormat::Json, &post_users(), None).unwrap();
+        let foreign = json.replace(EXPORT_FORMAT, "poopman-workspace");
+        assert!(parse_export(&foreign).unwrap_err().to_string().contains("not an exported request"));
+        let newer = json.replace("\"version\": 1", "\"version\": 2");
+        assert!(parse_export(&newer).unwrap_err().to_string().contains("newer"));
+        assert!(parse_export("{}").is_err());
+    }
+
+    #[test]
+    fn http_text_reads_like_the_exchange() {
+        let text = export(ExportFormat::HttpText, &post_users(), Some(&created())).unwrap();
+        assert_eq!(
+            text,
+            "POST /v1/users?page=2 HTTP/1.1\n\
+             Host: api.test:8443\n\
+             Content-Type: application/json\n\
+             \n\
+             {\"name\":\"ada\"}\n\
+             \n\
+             HTTP/1.1 201 Created\n\
+             Content-Type: application/json\n\
+             \n\
+             {\"id\":7}\n"
+        );
+
+        let mut binary = created();
+        binary.is_text = false;
+        binary.version = Some("HTTP/2".to_string());
+        let text = export(ExportFormat::HttpText, &post_users(), Some(&binary)).unwrap();
+        assert!(text.ends_with("[binary body, 8 B]\n"));
+        assert!(text.contains("\nHTTP/2 201 Created\n"), "{text}");
+    }
+
+    #[test]
+    fn file_names_come_from_the_method_and_last_segment() {
+        assert_eq!(file_name(&post_users(), ExportFormat::Json), "post-users.json");
+        let bare = RequestData::new(HttpMethod::GET, "https://api.test/".to_string());
+        assert_eq!(file_name(&bare, ExportFormat::HttpText), "get-request.http");
+    }
+}
@@ -0,0 +1,89 @@
+//! Log lines for the request lifecycle (start, response, failure, cancel,
+//! retry) and the runtime log level behind them.
+//!
+//! The logger is built to pass everything from this crate (see
+//! `setup_logger`); what actually gets written is decided by
+//! `log::set_max_level`, which the Edit menu's "Log Level" changes at
+//! runtime. Other crates stay capped at `info` whatever the setting.
+//!
+//! Header values are written through `redact_headers`, which blanks anything
+//! `workspace_bundle::is_secret_key` would mask in an export.
+
+use log::LevelFilter;
+
+use crate::types::BodyType;
+use crate::workspace_bundle::is_secret_key;
+
+/// Levels offered in the menu, quietest first.
+pub const LOG_LEVELS: [LevelFilter; 5] =
+    [LevelFilter::Error, LevelFilter::Warn, LevelFilter::Info, LevelFilter::Debug, LevelFilter::Trace];
+
+pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// Stands in for a redacted header value.
+pub const REDACTED: &str = "[redacted]";
+
+/// A stored level name (`LevelFilter::as_str`, any case); `None` for
+/// anything else, including "off", which the menu doesn't offer.
+pub fn parse_level(name: &str) -> Option<LevelFilter> {
+    name.trim().parse().ok().filter(|level| LOG_LEVELS.contains(level))
+}
+
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
+/// `Name: value` pairs for a log line, secret values replaced by `REDACTED`.
+pub fn redact_headers(headers: &[(String, String)]) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_secret_key(name) { REDACTED } else { value.as_str() };
+            format!("{name}: {value}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Body size for the "send started" line. Form fields may be files, which
+/// are not read just to log their size.
+pub fn body_summary(body: &BodyType) -> String {
+    match body {
+        BodyType::None => "none".to_string(),
+        BodyType::Raw { content, .. } => format!("{} bytes", content.len()),
+        BodyType::FormData(rows) => format!("form-data, {} fields", rows.iter().filter(|r| r.enabled).count()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn h(name: &str, value: &str) -> (String, String) {
+        (name.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn secret_header_values_are_redacted() {
+        let line = redact_headers(&[
+            h("Authorization", "Bearer abc123"),
+            h("Cookie", "session=s3cr3t"),
+            h("X-API-Key", "k-42"),
+            h("Proxy-Authorization", "Basic dXNlcjpwdw=="),
+            h("Accept", "application/json"),
+        ]);
+        for secret in ["abc123", "s3cr3t", "k-42", "dXNlcjpwdw=="] {
+            assert!(!line.contains(secret), "{secret} leaked: {line}");
+        }
+        assert_eq!(line.matches(REDACTED).count(), 4);
+        assert!(line.contains("Accept: application/json"));
+    }
+
+    #[test]
+    fn levels_parse_case_insensitively_but_not_off() {
+        assert_eq!(parse_level("debug"), Some(LevelFilter::Debug));
+        assert_eq!(parse_level(" WARN "), Some(LevelFilter::Warn));
+        assert_eq!(parse_level("off"), None);
+        assert_eq!(parse_level("loud"), None);
+    }
+}
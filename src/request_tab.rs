@@ -1,6 +1,16 @@
 use std::sync::Arc;
 
-use crate::types::{BodyType, HeaderState, HistoryItem, HttpMethod, ParamState, RequestData, ResponseData};
+use crate::repeat_runner::RepeatReport;
+use crate::response_viewer::BodyView;
+use crate::retry_after::PendingRetry;
+use crate::types::{
+    BodyType, HeaderState, HistoryItem, HttpMethod, ParamState, RequestData, ResponseData, SavedRequest,
+    TabColor,
+};
+use crate::ws_client::WsTabState;
+
+/// How many completed responses a tab remembers (newest last).
+pub const RESPONSE_RING_LEN: usize = 5;
 
 /// Represents a single request tab
 #[derive(Debug, Clone)]
@@ -10,11 +20,47 @@ pub struct RequestTab {
     pub request: RequestData,
     /// Response data for this tab (shared, so tab switches never copy the body)
     pub response: Option<Arc<ResponseData>>,
+    /// The request as sent for `response`, for the viewer's Request tab.
+    pub sent_request: Option<Arc<RequestData>>,
     // UI state (not persisted to database)
     pub params_state: Option<Vec<ParamState>>,
     pub headers_state: Option<Vec<HeaderState>>,
     /// Associated history item ID (if opened from history)
     pub history_id: Option<i64>,
+    /// The saved request this tab was opened from or last saved to; Save
+    /// overwrites it instead of asking for a name.
+    pub saved_request_id: Option<i64>,
+    /// That saved request's name, used as the tab title.
+    pub saved_name: Option<String>,
+    /// Last "Send ×N" result, shown in place of a response.
+    pub repeat_report: Option<Arc<RepeatReport>>,
+    /// The last `RESPONSE_RING_LEN` responses, newest last — a 429 and the
+    /// retry that replaced it are both kept here.
+    pub recent_responses: Vec<Arc<ResponseData>>,
+    /// Retry scheduled (or offered) after a 429 with `Retry-After`.
+    pub retry: Option<PendingRetry>,
+    /// Re-send automatically when a `Retry-After` countdown elapses.
+    pub auto_retry: bool,
+    /// Color label from the tab's context menu; tints its history rows.
+    pub color: Option<TabColor>,
+    /// Made with Duplicate; the title keeps a "(copy)" suffix.
+    pub copy: bool,
+    /// Title typed by the user; shown as is in place of the generated one.
+    pub custom_title: Option<String>,
+    /// Kept at the front of the strip, drawn compact, and closed only after
+    /// a confirmation.
+    pub pinned: bool,
+    /// Pretty / Raw / Preview, as last picked in this tab's response viewer.
+    pub body_view: BodyView,
+    /// The request as it read back from the editor when last loaded from its
+    /// history row or saved request, sent, or saved. `None` for a scratch
+    /// tab, which is never dirty.
+    pub baseline: Option<RequestData>,
+    /// Edited since `baseline`; the tab bar shows a dot.
+    pub dirty: bool,
+    /// Set on a WebSocket tab, which shows a `WsPanel` in place of the
+    /// request editor and response viewer; `request` is unused there.
+    pub ws: Option<WsTabState>,
 }
 
 impl RequestTab {
@@ -23,20 +69,37 @@ impl RequestTab {
         Self {
             id,
             title: "New Request".to_string(),
-            request: RequestData {
-                method: HttpMethod::GET,
-                url: String::new(),
-                headers: vec![],
-                body: BodyType::default(),
-                auth: crate::types::AuthConfig::default(),
-            },
+            request: RequestData::new(HttpMethod::GET, String::new()),
             response: None,
+            sent_request: None,
             params_state: None,
             headers_state: None,
             history_id: None,
+            saved_request_id: None,
+            saved_name: None,
+            repeat_report: None,
+            recent_responses: vec![],
+            retry: None,
+            auto_retry: false,
+            color: None,
+            copy: false,
+            custom_title: None,
+            pinned: false,
+            body_view: BodyView::default(),
+            baseline: None,
+            dirty: false,
+            ws: None,
         }
     }
 
+    /// Create an empty WebSocket tab.
+    pub fn new_websocket(id: usize) -> Self {
+        let mut tab = Self::new_empty(id);
+        tab.ws = Some(WsTabState::default());
+        tab.update_title();
+        tab
+    }
+
     /// Create a request tab from history item
     pub fn from_history(id: usize, item: &HistoryItem) -> Self {
         Self {
@@ -44,9 +107,69 @@ impl RequestTab {
             title: Self::generate_title(&item.request),
             request: item.request.clone(),
             response: item.response.clone(),
+            sent_request: None,
             params_state: None,
             headers_state: None,
             history_id: Some(item.id),
+            saved_request_id: None,
+            saved_name: None,
+            repeat_report: item.run_report.clone(),
+            recent_responses: vec![],
+            retry: None,
+            auto_retry: false,
+            color: item.color,
+            copy: false,
+            custom_title: None,
+            pinned: false,
+            body_view: BodyView::default(),
+            baseline: None,
+            dirty: false,
+            ws: None,
+        }
+    }
+
+    /// Create a request tab from a saved request, titled by its name.
+    pub fn from_saved(id: usize, saved: &SavedRequest) -> Self {
+        let mut tab = Self::new_empty(id);
+        tab.request = saved.request.clone();
+        tab.saved_request_id = Some(saved.id);
+        tab.saved_name = Some(saved.name.clone());
+        tab.update_title();
+        tab
+    }
+
+    /// Create a request tab from a file written by "Export to File", with the
+    /// response that was exported along with it.
+    pub fn from_export(id: usize, exported: crate::request_export::ExportedRequest) -> Self {
+        let mut tab = Self::new_empty(id);
+        tab.request = exported.request;
+        tab.response = exported.response.map(Arc::new);
+        tab.update_title();
+        tab
+    }
+
+    /// A copy of this tab under `id`, tied to no history row or saved request,
+    /// so it is edited and sent on its own.
+    pub fn duplicate(&self, id: usize) -> Self {
+        let mut tab = self.clone();
+        tab.id = id;
+        tab.history_id = None;
+        tab.saved_request_id = None;
+        tab.retry = None;
+        tab.copy = true;
+        tab.custom_title = None;
+        tab.pinned = false;
+        tab.baseline = None;
+        tab.dirty = false;
+        tab.update_title();
+        tab
+    }
+
+    /// What the tab strip shows before the title: the method, or "WS".
+    pub fn badge(&self) -> String {
+        match &self.ws {
+            Some(_) => "WS".to_string(),
+            None => self.request.method.to_string(),
         }
     }
 
@@ -71,9 +194,49 @@ impl RequestTab {
         format!("{} {}", request.method.as_str(), path)
     }
 
+    /// Make `response` the tab's current response and remember it in the ring.
+    pub fn record_response(&mut self, response: Arc<ResponseData>) {
+        if self.recent_responses.len() == RESPONSE_RING_LEN {
+            self.recent_responses.remove(0);
+        }
+        self.recent_responses.push(response.clone());
+        self.response = Some(response);
+    }
+
+    /// How many of the latest responses in a row were 429s.
+    pub fn rate_limit_streak(&self) -> usize {
+        self.recent_responses
+            .iter()
+            .rev()
+            .take_while(|r| r.status == Some(429))
+            .count()
+    }
+
     /// Update title based on current request data
     pub fn update_title(&mut self) {
-        self.title = Self::generate_title(&self.request);
+        if let Some(title) = &self.custom_title {
+            self.title = title.clone();
+            return;
+        }
+        self.title = match (&self.ws, &self.saved_name) {
+            (Some(ws), _) => ws.title(),
+            (None, Some(name)) => name.clone(),
+            (None, None) => Self::generate_title(&self.request),
+        };
+        if self.copy {
+            self.title.push_str(" (copy)");
+        }
+    }
+
+    /// Take `request` (read from the editor) as the unedited state.
+    pub fn set_baseline(&mut self, request: RequestData) {
+        self.baseline = Some(request);
+        self.dirty = false;
+    }
+
+    /// Whether `request` (read from the editor) differs from the baseline.
+    pub fn differs_from_baseline(&self, request: &RequestData) -> bool {
+        self.baseline.as_ref().is_some_and(|baseline| !same_request(baseline, request))
     }
 
     /// A pristine scratch tab — the default tab at startup, or an untouched
@@ -84,10 +247,13 @@ impl RequestTab {
     /// carries the enabled predefined headers (Content-Type, Cache-Control,
     /// ...), so those are not a signal that the user has done anything. What
     /// marks a tab as used is a typed URL, body content, a response, or having
-    /// been opened from history.
+    /// been opened from history. A WebSocket tab is never blank.
     pub fn is_blank(&self) -> bool {
-        self.history_id.is_none()
+        self.ws.is_none()
+            && self.history_id.is_none()
+            && self.saved_request_id.is_none()
             && self.response.is_none()
+            && self.repeat_report.is_none()
             && self.request.url.trim().is_empty()
             && match &self.request.body {
                 BodyType::None => true,
@@ -97,19 +263,32 @@ impl RequestTab {
     }
 }
 
+/// Whether `a` and `b` are the same request. Header and form-data rows with
+/// neither key nor value — the editor's trailing blank rows — do not count.
+fn same_request(a: &RequestData, b: &RequestData) -> bool {
+    fn without_blank_rows(request: &RequestData) -> RequestData {
+        let mut request = request.clone();
+        request
+            .headers
+            .retain(|(k, v)| !(k.trim().is_empty() && v.trim().is_empty()));
+        if let BodyType::FormData(rows) = &mut request.body {
+            rows.retain(|row| {
+                !(row.key.trim().is_empty()
+                    && matches!(&row.value, crate::types::FormDataValue::Text(v) if v.trim().is_empty()))
+            });
+        }
+        request
+    }
+    without_blank_rows(a) == without_blank_rows(b)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::{BodyType, RawSubtype, ResponseData};
 
     fn empty_request() -> RequestData {
-        RequestData {
-            method: HttpMethod::GET,
-            url: String::new(),
-            headers: vec![],
-            body: BodyType::default(),
-            auth: crate::types::AuthConfig::default(),
-        }
+        RequestData::new(HttpMethod::GET, String::new())
     }
 
     #[test]
@@ -131,6 +310,69 @@ mod tests {
         assert!(!RequestTab::from_history(1, &item).is_blank());
     }
 
+    #[test]
+    fn tab_opened_from_a_batch_row_shows_its_report() {
+        let mut item = HistoryItem::new(7, "t".to_string(), empty_request(), None);
+        let report = Arc::new(RepeatReport::new(10, 1, 30, false, vec![]));
+        item.run_report = Some(report.clone());
+        assert_eq!(RequestTab::from_history(1, &item).repeat_report, Some(report));
+    }
+
+    #[test]
+    fn tab_opened_from_a_saved_request_is_titled_by_name() {
+        let saved = SavedRequest { id: 3, collection_id: 1, name: "List users".to_string(), request: empty_request() };
+        let mut tab = RequestTab::from_saved(1, &saved);
+        assert!(!tab.is_blank());
+        tab.request.url = "https://api.test/users".to_string();
+        tab.update_title();
+        assert_eq!(tab.title, "List users");
+    }
+
+    #[test]
+    fn duplicate_is_a_detached_copy() {
+        let request = RequestData::new(HttpMethod::GET, "https://api.test/users".to_string());
+        let item = HistoryItem::new(7, "t".to_string(), request, None);
+        let mut copy = RequestTab::from_history(1, &item).duplicate(2);
+        assert_eq!((copy.id, copy.history_id), (2, None));
+        assert_eq!(copy.title, "GET users (copy)");
+        copy.request.url = "https://api.test/orders".to_string();
+        copy.update_title();
+        assert_eq!(copy.title, "GET orders (copy)");
+    }
+
+    #[test]
+    fn custom_title_survives_url_edits_until_reset() {
+        let mut tab = RequestTab::new_empty(0);
+        tab.request.url = "https://api.test/zen".to_string();
+        tab.custom_title = Some("zen as admin".to_string());
+        tab.update_title();
+        tab.request.url = "https://api.test/zen?x=1".to_string();
+        tab.update_title();
+        assert_eq!(tab.title, "zen as admin");
+        assert_eq!(tab.duplicate(1).title, "GET zen (copy)");
+        tab.custom_title = None;
+        tab.update_title();
+        assert_eq!(tab.title, "GET zen");
+    }
+
+    #[test]
+    fn blank_rows_do_not_make_a_tab_dirty() {
+        let mut tab = RequestTab::new_empty(0);
+        let mut request = RequestData::new(HttpMethod::GET, "https://api.test/users".to_string());
+        request.headers = vec![("Accept".into(), "*/*".into())];
+        assert!(!tab.differs_from_baseline(&request), "a scratch tab is never dirty");
+
+        tab.set_baseline(request.clone());
+        let mut edited = request.clone();
+        edited.headers.push((String::new(), String::new()));
+        assert!(!tab.differs_from_baseline(&edited));
+        edited.headers.push(("X-Trace".into(), String::new()));
+        assert!(tab.differs_from_baseline(&edited));
+        let mut edited = request.clone();
+        edited.url.push_str("?page=2");
+        assert!(tab.differs_from_baseline(&edited));
+    }
+
     #[test]
     fn tab_with_a_response_is_not_blank() {
         let mut tab = RequestTab::new_empty(0);
@@ -140,10 +382,61 @@ mod tests {
             headers: vec![],
             body: vec![],
             is_text: true,
+            route: Default::default(),
+            raw_header_values: vec![],
+            network_profile: Default::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
         }));
         assert!(!tab.is_blank());
     }
 
+    #[test]
+    fn response_ring_keeps_the_latest_and_counts_the_429_streak() {
+        let response = |status| {
+            Arc::new(ResponseData {
+                status: Some(status),
+                duration_ms: 0,
+                headers: vec![],
+                body: vec![],
+                is_text: true,
+                route: Default::default(),
+                raw_header_values: vec![],
+                network_profile: Default::default(),
+                timing: None,
+                redirects: vec![],
+                tls_unverified: false,
+                attempts: 1,
+                truncated_after: None,
+                downloaded: None,
+                wire_size: None,
+                version: None,
+                resolved_by: None,
+            })
+        };
+        let mut tab = RequestTab::new_empty(0);
+        tab.record_response(response(429));
+        assert_eq!(tab.rate_limit_streak(), 1);
+        tab.record_response(response(429));
+        assert_eq!(tab.rate_limit_streak(), 2);
+        tab.record_response(response(200));
+        assert_eq!(tab.rate_limit_streak(), 0);
+        assert_eq!(tab.response.as_ref().and_then(|r| r.status), Some(200));
+
+        for _ in 0..RESPONSE_RING_LEN {
+            tab.record_response(response(429));
+        }
+        assert_eq!(tab.recent_responses.len(), RESPONSE_RING_LEN);
+        assert_eq!(tab.rate_limit_streak(), RESPONSE_RING_LEN);
+    }
+
     #[test]
     fn tab_with_body_content_is_not_blank() {
         let mut tab = RequestTab::new_empty(0);
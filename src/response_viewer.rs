@@ -1,15 +1,29 @@
 use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::{
-    button::*, h_flex, input::*,
+    button::*, h_flex,
+    input::{InputEvent, *},
     menu::{ContextMenuExt as _, PopupMenuItem},
     scroll::ScrollableElement as _,
     text::{TextView, TextViewStyle},
-    v_flex, ActiveTheme as _,
+    tooltip::Tooltip,
+    v_flex, ActiveTheme as _, Disableable as _, Selectable as _, Sizable as _,
 };
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::types::ResponseData;
+use crate::a11y::{self, tab_order};
+use crate::assertions::{self, AssertionResult};
+use crate::extract::ExtractResult;
+use crate::cookies::{parse_set_cookies, SetCookie};
+use crate::json_filter;
+use crate::json_path::{path_string, JsonLineIndex};
+use crate::json_tree::{JsonTree, RowKind, TreeRow};
+use crate::large_text::{self, LargeText};
+use crate::pagination::{page_links, PageLinks, PageTarget};
+use crate::repeat_runner::RepeatReport;
+use crate::retry_after::PendingRetry;
+use crate::types::{BodyType, RawHeaderValue, RequestData, ResponseData, ResponseTiming};
 
 /// Render headers as `key: value` lines — what "Copy all" puts on the clipboard.
 /// No trailing newline, so pasting into a single-line field stays clean.
@@ -21,6 +35,15 @@ fn headers_to_text(headers: &[(String, String)]) -> String {
         .join("\n")
 }
 
+/// What "Copy body" takes: the reformatted (or filtered) text while Pretty
+/// is showing it, the body as it arrived otherwise.
+fn body_to_copy<'a>(view: BodyView, raw: &'a str, reformatted: Option<&'a str>) -> &'a str {
+    match (view, reformatted) {
+        (BodyView::Pretty, Some(text)) => text,
+        _ => raw,
+    }
+}
+
 /// Escape text for embedding in the HTML fed to `TextView`.
 ///
 /// Header values are arbitrary bytes from the network: `&` shows up in every
@@ -40,15 +63,170 @@ fn escape_html(s: &str) -> String {
 }
 
 /// One paragraph per header, key in bold — as HTML so `TextView` can render it
-/// with real text selection.
-fn headers_to_html(headers: &[(String, String)]) -> String {
+/// with real text selection. Values that arrived as non-ASCII bytes get a ⚠
+/// so the decoded text isn't mistaken for what was on the wire.
+fn headers_to_html(headers: &[(String, String)], raw: &[RawHeaderValue]) -> String {
     headers
         .iter()
-        .map(|(k, v)| format!("<p><b>{}:</b> {}</p>", escape_html(k), escape_html(v)))
+        .enumerate()
+        .map(|(i, (k, v))| {
+            let flag = if raw.iter().any(|r| r.index == i) { "⚠ " } else { "" };
+            format!("<p><b>{}:</b> {}{}</p>", escape_html(k), flag, escape_html(v))
+        })
         .collect::<Vec<_>>()
         .join("")
 }
 
+/// Start line and headers of the request as sent, in the same layout as
+/// `headers_to_html`.
+fn sent_request_to_html(request: &RequestData) -> String {
+    format!(
+        "<p><b>{}</b> {}</p>{}",
+        request.method.as_str(),
+        escape_html(&request.url),
+        headers_to_html(&request.headers, &[])
+    )
+}
+
+/// The status bar's size: the body as shown, then what the response took
+/// on the wire once that is known ("1.2 MB (transferred 240 KB)"), with a
+/// tooltip breaking it down.
+fn size_summary(response: &ResponseData) -> (String, Option<String>) {
+    let size = |bytes: u64| crate::format::format_size(bytes as usize);
+    let (label, body) = match &response.downloaded {
+        Some(downloaded) => ("Written", downloaded.bytes),
+        None => ("Size", response.body.len() as u64),
+    };
+    let Some(wire) = response.wire_size else {
+        return (format!("{label}: {}", size(body)), None);
+    };
+    let transferred = wire.header_bytes + wire.body_bytes;
+    let mut lines = vec![format!("Body: {}", size(body))];
+    if wire.body_bytes != body {
+        let coding = response
+            .headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("content-encoding"))
+            .map_or(String::new(), |(_, v)| format!(" ({v})"));
+        lines.push(format!("Body as sent: {}{}", size(wire.body_bytes), coding));
+    }
+    lines.push(format!("Headers: {}", size(wire.header_bytes)));
+    lines.push(format!("Transferred: {}", size(transferred)));
+    (format!("{label}: {} (transferred {})", size(body), size(transferred)), Some(lines.join("\n")))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PhaseKind {
+    Latency,
+    Dns,
+    Connect,
+    Wait,
+    Download,
+}
+
+/// One stretch of a send, in the order they happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TimingPhase {
+    kind: PhaseKind,
+    label: &'static str,
+    ms: u64,
+}
+
+/// The phases of `timing`. DNS and connect are split out of the wait when
+/// they were measured; older history entries have the wait as one piece.
+fn timing_phases(timing: &ResponseTiming) -> Vec<TimingPhase> {
+    let phase = |kind, label, ms| TimingPhase { kind, label, ms };
+    let mut phases = Vec::new();
+    if timing.latency_ms > 0 {
+        phases.push(phase(PhaseKind::Latency, "Added latency", timing.latency_ms));
+    }
+    match (timing.dns_ms, timing.connect_ms) {
+        (Some(dns), Some(connect)) => {
+            phases.push(phase(PhaseKind::Dns, "DNS lookup", dns));
+            phases.push(phase(PhaseKind::Connect, "Connect (TCP + TLS)", connect));
+            phases.push(phase(PhaseKind::Wait, "Waiting (TTFB)", timing.wait_ms.saturating_sub(dns + connect)));
+        }
+        _ => phases.push(phase(PhaseKind::Wait, "Send + wait", timing.wait_ms)),
+    }
+    phases.push(phase(PhaseKind::Download, "Download", timing.download_ms));
+    phases
+}
+
+/// Tooltip for the status bar's "Time": where the time went, with the
+/// network profile's simulated parts marked.
+fn timing_breakdown(response: &ResponseData) -> Option<String> {
+    let timing = response.timing?;
+    let ms = crate::format::format_duration_ms;
+    let limits = response.network_profile.limits();
+    let rate = |kbps: u32| if kbps > 0 { format!(" (throttled to {kbps} kbps)") } else { String::new() };
+    let lines: Vec<String> = timing_phases(&timing)
+        .into_iter()
+        .map(|phase| {
+            let note = match phase.kind {
+                PhaseKind::Latency => " (simulated)".to_string(),
+                PhaseKind::Wait => limits.map_or(String::new(), |l| rate(l.up_kbps)),
+                PhaseKind::Download => limits.map_or(String::new(), |l| rate(l.down_kbps)),
+                PhaseKind::Dns | PhaseKind::Connect => String::new(),
+            };
+            format!("{}: {}{}", phase.label, ms(phase.ms), note)
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+/// What a text body turned out to be; picks the highlighter and is named in
+/// the status bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BodyKind {
+    Json,
+    Xml,
+    Html,
+    Text,
+}
+
+impl BodyKind {
+    /// JSON when the body parsed as JSON (whatever the Content-Type said),
+    /// then HTML by Content-Type, XML as `looks_like_xml` sniffs it, and
+    /// plain text for everything else.
+    fn detect(content_type: Option<&str>, text: &str, is_json: bool) -> Self {
+        if is_json {
+            Self::Json
+        } else if is_html(content_type) {
+            Self::Html
+        } else if crate::code_formatter::looks_like_xml(content_type, text) {
+            Self::Xml
+        } else {
+            Self::Text
+        }
+    }
+
+    /// Language for `InputState::set_highlighter`. There is no XML grammar;
+    /// the HTML one colours its tags well enough.
+    fn highlighter(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Xml | Self::Html => "html",
+            Self::Text => "text",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Xml => "XML",
+            Self::Html => "HTML",
+            Self::Text => "Text",
+        }
+    }
+}
+
+/// Whether a Content-Type is HTML, which is what "Preview" renders.
+fn is_html(content_type: Option<&str>) -> bool {
+    content_type
+        .and_then(|ct| ct.split(';').next())
+        .is_some_and(|mime| mime.trim().to_ascii_lowercase().contains("html"))
+}
+
 /// Map a raw Content-Type header value to a gpui-renderable image format.
 /// Strips `;`-parameters (e.g. charset), trims, and is case-insensitive.
 fn image_format_for_content_type(content_type: &str) -> Option<ImageFormat> {
@@ -92,6 +270,36 @@ fn extension_for_content_type(ct: &str) -> Option<String> {
         .map(|e| e.to_string())
 }
 
+/// Emitted by the Next / Prev page buttons: load the target into the
+/// current tab, sending it right away when `send` is set.
+#[derive(Clone, Debug)]
+pub struct FollowPageLink {
+    pub target: PageTarget,
+    pub send: bool,
+}
+
+/// Emitted by the rate-limit chip in the status bar.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RetryCommand {
+    /// Start the offered countdown.
+    Start,
+    /// Skip the rest of the countdown and re-send now.
+    Now,
+    Cancel,
+}
+
+/// How the body of a text response is shown; each tab keeps its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum BodyView {
+    /// Reformatted (JSON, XML) when that differs from what arrived.
+    #[default]
+    Pretty,
+    /// The body exactly as received.
+    Raw,
+    /// Rendered, for HTML responses.
+    Preview,
+}
+
 /// Response viewer panel
 pub struct ResponseViewer {
     /// Shared with the owning tab, so setting/reading never copies the body.
@@ -102,14 +310,106 @@ pub struct ResponseViewer {
     /// Pre-built preview for image responses (constructed once per response —
     /// `Image::from_bytes` hashes the body for its asset id, too costly per frame).
     preview_image: Option<Arc<gpui::Image>>,
+    /// Start of a binary body that is not an image, as a hex dump.
+    hex_preview: Option<SharedString>,
     body_display: Entity<InputState>,
+    /// Body / Headers / Request / Tree (3, drawn second) / Cookies (4, drawn
+    /// after Headers).
     active_tab: usize,
+    /// The request as sent for `response` (variables resolved, auth header
+    /// merged), shown read-only in the Request tab.
+    sent_request: Option<Arc<RequestData>>,
+    sent_body_display: Entity<InputState>,
+    sent_scroll_handle: ScrollHandle,
+    /// The Console tab's `wire_log` transcript; empty unless the sent
+    /// request was verbose.
+    console_display: Entity<InputState>,
+    /// Show secret header values as `[redacted]` in the Console tab.
+    /// Outlives responses.
+    mask_secrets: bool,
     headers_scroll_handle: ScrollHandle,
+    /// The response's `Set-Cookie` headers, for the Cookies tab.
+    cookies: Vec<SetCookie>,
+    cookies_scroll_handle: ScrollHandle,
+    /// The timing bar in the status bar was clicked open: one row per phase
+    /// under it. Kept from one response to the next.
+    timing_expanded: bool,
+    /// The sent request's assertions, run against this response.
+    test_results: Vec<AssertionResult>,
+    /// What the sent request's extract rules wrote into variables.
+    extracted: Vec<ExtractResult>,
+    /// Result of a "Send ×N" batch; shown instead of a single response.
+    repeat_report: Option<Arc<RepeatReport>>,
+    repeat_scroll_handle: ScrollHandle,
+    /// Next / previous page advertised by the response headers.
+    page_links: PageLinks,
+    /// The shown tab's pending 429 retry (the tab owns it; this is a copy).
+    retry: Option<PendingRetry>,
+    /// Re-renders the countdown once a second; dropping it stops the ticks.
+    _retry_ticker: Option<Task<()>>,
+    /// Line → JSON path of the pretty-printed body, for the breadcrumb bar.
+    json_index: Option<Arc<JsonLineIndex>>,
+    /// The Tree tab's state; `None` unless the body parsed as JSON.
+    json_tree: Option<JsonTree>,
+    json_tree_scroll: UniformListScrollHandle,
+    /// The parsed body, for the filter box; `None` unless it is JSON.
+    json_value: Option<Arc<serde_json::Value>>,
+    /// JSONPath filter over the body. The expression is kept across
+    /// responses, so a resend shows the same slice.
+    filter_input: Entity<InputState>,
+    /// What the filter selected; shown in place of the body while set.
+    filtered_body: Option<String>,
+    /// Why the expression could not be evaluated. The body is left as it was.
+    filter_error: Option<String>,
+    _filter_subscription: Subscription,
+    /// Reformatted body (JSON or XML), when it differs from what arrived.
+    /// `None` while XML is still being formatted, or when it could not be.
+    pretty_body: Option<Arc<str>>,
+    /// The mode picked for the shown tab. Outlives responses; the app sets it
+    /// on tab switches.
+    body_view: BodyView,
+    /// The current response is HTML, so Preview is offered.
+    is_html: bool,
+    /// Detected type of the current text body.
+    body_kind: Option<BodyKind>,
+    /// How the current text body was decoded, when not plain UTF-8.
+    decoding_note: Option<String>,
+    /// Background XML formatting of the current body; dropped (cancelled) by
+    /// the next response.
+    _format_task: Option<Task<()>>,
+    /// Progress of "Save full response to file" for a truncated body.
+    full_save: Option<FullSave>,
+    /// Soft-wrap long lines of the body. Outlives responses.
+    wrap_body: bool,
+    /// The shown body when it is too big for the editor; see `large_text`.
+    large_body: Option<LargeText>,
+    large_scroll: UniformListScrollHandle,
+    /// Search box of the large view, which has no editor search.
+    find_input: Entity<InputState>,
+    find_matches: Vec<large_text::Match>,
+    find_current: usize,
+    _find_subscription: Subscription,
+}
+
+/// Where a re-download of a truncated response stands.
+#[derive(Debug, Clone, PartialEq)]
+enum FullSave {
+    Running,
+    Saved { bytes: u64 },
+    Failed(String),
 }
 
 impl ResponseViewer {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        // The highlighter is picked per response (see `BodyKind`).
         let body_display = cx.new(|cx| {
+            InputState::new(window, cx)
+                .code_editor("text")
+                .line_number(true)
+                .multi_line(true)
+                .tab_size(TabSize { tab_size: 4, hard_tabs: false })
+        });
+        let sent_body_display = cx.new(|cx| {
             InputState::new(window, cx)
                 .code_editor("json")
                 .line_number(true)
@@ -117,24 +417,102 @@ impl ResponseViewer {
                 .tab_size(TabSize { tab_size: 4, hard_tabs: false })
         });
 
+        let console_display = cx.new(|cx| InputState::new(window, cx).code_editor("text").multi_line(true));
+
+        let filter_input = cx.new(|cx| InputState::new(window, cx).placeholder("Filter: $.items[*].id"));
+        let _filter_subscription = cx.subscribe_in(&filter_input, window, |this, _, event: &InputEvent, window, cx| {
+            if matches!(event, InputEvent::Change) {
+                this.apply_filter(window, cx);
+            }
+        });
+        let find_input = cx.new(|cx| InputState::new(window, cx).placeholder("Find"));
+        let _find_subscription = cx.subscribe_in(&find_input, window, |this, _, event: &InputEvent, _window, cx| {
+            match event {
+                InputEvent::Change => {
+                    this.run_find(cx);
+                    this.scroll_to_match();
+                }
+                InputEvent::PressEnter { secondary } => this.step_match(!secondary),
+                _ => return,
+            }
+            cx.notify();
+        });
+
         Self {
             response: None,
             canceled: false,
             preview_image: None,
+            hex_preview: None,
             body_display,
             active_tab: 0,
+            sent_request: None,
+            sent_body_display,
+            sent_scroll_handle: ScrollHandle::new(),
+            console_display,
+            mask_secrets: true,
             headers_scroll_handle: ScrollHandle::new(),
+            cookies: Vec::new(),
+            cookies_scroll_handle: ScrollHandle::new(),
+            timing_expanded: false,
+            test_results: Vec::new(),
+            extracted: Vec::new(),
+            repeat_report: None,
+            repeat_scroll_handle: ScrollHandle::new(),
+            page_links: PageLinks::default(),
+            retry: None,
+            _retry_ticker: None,
+            json_index: None,
+            json_tree: None,
+            json_tree_scroll: UniformListScrollHandle::new(),
+            json_value: None,
+            filter_input,
+            filtered_body: None,
+            filter_error: None,
+            _filter_subscription,
+            pretty_body: None,
+            body_view: BodyView::default(),
+            is_html: false,
+            body_kind: None,
+            decoding_note: None,
+            _format_task: None,
+            full_save: None,
+            wrap_body: true,
+            large_body: None,
+            large_scroll: UniformListScrollHandle::new(),
+            find_input,
+            find_matches: Vec::new(),
+            find_current: 0,
+            _find_subscription,
         }
     }
 
-    /// Set response data
+    /// Show what the extract rules did with the response just set.
+    pub fn set_extracted(&mut self, extracted: Vec<ExtractResult>, cx: &mut Context<Self>) {
+        self.extracted = extracted;
+        cx.notify();
+    }
+
+    /// Set response data, with the request that produced it when known.
     pub fn set_response(
         &mut self,
         response: Arc<ResponseData>,
+        sent: Option<Arc<RequestData>>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.test_results = sent
+            .as_deref()
+            .map(|sent| assertions::evaluate(&sent.assertions, &response))
+            .unwrap_or_default();
+        self.extracted.clear();
+        self.set_sent_request(sent, window, cx);
         self.canceled = false;
+        self.repeat_report = None;
+        self.full_save = None;
+        self.page_links = page_links(&response.headers);
+        self.cookies = parse_set_cookies(&response.headers);
+        self.retry = None;
+        self._retry_ticker = None;
         // Pre-build an inline preview for image responses (binary only).
         self.preview_image = if response.is_text {
             None
@@ -146,25 +524,174 @@ impl ResponseViewer {
                 .and_then(|(_, v)| image_format_for_content_type(v))
                 .map(|format| Arc::new(gpui::Image::from_bytes(format, response.body.clone())))
         };
+        // Anything else binary gets a peek at its first bytes instead.
+        self.hex_preview = (!response.is_text && self.preview_image.is_none() && !response.body.is_empty())
+            .then(|| crate::format::hex_dump(&response.body, 512).into());
         // Only feed the text editor for text responses; binary is shown in a
         // dedicated panel and never decoded to (lossy) text.
-        let display = if response.is_text {
-            let text = response.body_text();
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
-                crate::code_formatter::pretty_json_4(&json).unwrap_or_else(|_| text.to_string())
-            } else {
-                text.to_string()
+        // The stored body is never rewritten: the reformatted text goes to
+        // `pretty_body`, so Raw always has the bytes as they arrived.
+        self.json_index = None;
+        self.json_tree = None;
+        self.json_value = None;
+        self.filtered_body = None;
+        self.pretty_body = None;
+        self._format_task = None;
+        let content_type = response
+            .headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+            .map(|(_, v)| v.as_str());
+        self.is_html = response.is_text && is_html(content_type);
+        self.body_kind = None;
+        self.decoding_note = None;
+        if response.is_text {
+            let (text, decoding) = crate::charset::decode(&response.headers, &response.body);
+            self.decoding_note = decoding.note();
+            let json = serde_json::from_str::<serde_json::Value>(&text).ok();
+            self.body_kind = Some(BodyKind::detect(content_type, &text, json.is_some()));
+            if let Some(json) = json {
+                if let Ok(pretty) = crate::code_formatter::pretty_json_4(&json) {
+                    self.json_index = Some(Arc::new(JsonLineIndex::build(&json)));
+                    self.pretty_body = Some(Arc::from(pretty.as_str()));
+                }
+                let json = Arc::new(json);
+                self.json_tree = Some(JsonTree::new(json.clone()));
+                self.json_value = Some(json);
+            } else if crate::code_formatter::looks_like_xml(content_type, &text) && !large_text::is_large(&text) {
+                self.format_xml_in_background(text.to_string(), window, cx);
             }
+        }
+
+        self.response = Some(response);
+        let language = self.body_kind.map_or("text", BodyKind::highlighter);
+        self.body_display.update(cx, |input, cx| input.set_highlighter(language, cx));
+        self.run_filter(cx);
+        self.show_body_text(window, cx);
+        self.show_console(window, cx);
+        self.active_tab = 0; // Reset to Body tab
+        cx.notify();
+    }
+
+    /// The mode the body is shown in for the active tab.
+    pub fn body_view(&self) -> BodyView {
+        self.body_view
+    }
+
+    /// Take the mode of the tab about to be shown, ahead of its response
+    /// (`set_response` / `clear_response` then fill the editor once).
+    pub fn restore_body_view(&mut self, view: BodyView) {
+        self.body_view = view;
+    }
+
+    /// Switch the body between reformatted, as-received and rendered.
+    pub fn set_body_view(&mut self, view: BodyView, window: &mut Window, cx: &mut Context<Self>) {
+        if self.body_view == view {
+            return;
+        }
+        self.body_view = view;
+        self.show_body_text(window, cx);
+        cx.notify();
+    }
+
+    /// `body_view`, except that Preview falls back to Pretty for a response
+    /// that is not HTML.
+    fn shown_view(&self) -> BodyView {
+        match self.body_view {
+            BodyView::Preview if !self.is_html => BodyView::Pretty,
+            view => view,
+        }
+    }
+
+    /// Put the body into the editor as `shown_view` calls for, or into the
+    /// large view when it is too big for one. Binary bodies are never
+    /// decoded to (lossy) text; they have their own panel.
+    fn show_body_text(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = match (&self.response, &self.pretty_body) {
+            (Some(response), _) if !response.is_text => String::new(),
+            (Some(_), _) if let Some(filtered) = &self.filtered_body => filtered.clone(),
+            (Some(_), Some(pretty)) if self.shown_view() == BodyView::Pretty => pretty.to_string(),
+            (Some(response), _) => response.body_text().to_string(),
+            (None, _) => String::new(),
+        };
+        if large_text::is_large(&text) {
+            self.large_body = Some(LargeText::new(Arc::from(text), self.row_chars()));
+            self.body_display.update(cx, |input, cx| input.set_value("", window, cx));
+            self.run_find(cx);
+        } else {
+            self.large_body = None;
+            self.find_matches.clear();
+            self.body_display.update(cx, |input, cx| input.set_value(&text, window, cx));
+        }
+    }
+
+    fn row_chars(&self) -> usize {
+        if self.wrap_body {
+            large_text::WRAP_CHARS
+        } else {
+            large_text::MAX_ROW_CHARS
+        }
+    }
+
+    fn toggle_wrap(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        self.wrap_body = !self.wrap_body;
+        let wrap = self.wrap_body;
+        self.body_display.update(cx, |input, cx| input.set_soft_wrap(wrap, window, cx));
+        if let Some(large) = self.large_body.take() {
+            // Rows shift with the width; the text itself is shared.
+            self.large_body = Some(LargeText::new(large.text(), self.row_chars()));
+            self.run_find(cx);
+        }
+        cx.notify();
+    }
+
+    /// Search the large view for what is in the find box, from the top.
+    fn run_find(&mut self, cx: &App) {
+        let needle = self.find_input.read(cx).value().to_string();
+        self.find_matches = self.large_body.as_ref().map(|large| large.find(&needle)).unwrap_or_default();
+        self.find_current = 0;
+    }
+
+    fn step_match(&mut self, forward: bool) {
+        let count = self.find_matches.len();
+        if count == 0 {
+            return;
+        }
+        self.find_current = if forward {
+            (self.find_current + 1) % count
         } else {
-            String::new()
+            (self.find_current + count - 1) % count
         };
+        self.scroll_to_match();
+    }
 
-        self.body_display.update(cx, |input, cx| {
-            input.set_value(&display, window, cx);
-        });
+    fn scroll_to_match(&self) {
+        if let Some(found) = self.find_matches.get(self.find_current) {
+            self.large_scroll.scroll_to_item(found.row, ScrollStrategy::Center);
+        }
+    }
 
-        self.response = Some(response);
-        self.active_tab = 0; // Reset to Body tab
+    /// Evaluate the filter box against the body into `filtered_body` /
+    /// `filter_error`. An error leaves `filtered_body` as it was.
+    fn run_filter(&mut self, cx: &App) {
+        let expression = self.filter_input.read(cx).value().trim().to_string();
+        self.filter_error = None;
+        let Some(root) = self.json_value.as_deref().filter(|_| !expression.is_empty()) else {
+            self.filtered_body = None;
+            return;
+        };
+        match json_filter::evaluate(&expression, root).and_then(json_filter::render) {
+            Ok(text) => self.filtered_body = Some(text),
+            Err(e) => self.filter_error = Some(e.to_string()),
+        }
+    }
+
+    /// Re-run the filter after an edit; clearing the box restores the body.
+    fn apply_filter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_filter(cx);
+        if self.filter_error.is_none() {
+            self.show_body_text(window, cx);
+        }
         cx.notify();
     }
 
@@ -173,18 +700,479 @@ impl ResponseViewer {
         self.response.clone()
     }
 
+    /// Show the results table of a "Send ×N" batch in place of a response.
+    pub fn set_repeat_report(
+        &mut self,
+        report: Arc<RepeatReport>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.clear_response(window, cx);
+        self.repeat_report = Some(report);
+        cx.notify();
+    }
+
+    pub fn get_repeat_report(&self) -> Option<Arc<RepeatReport>> {
+        self.repeat_report.clone()
+    }
+
     /// Clear response data
     pub fn clear_response(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.canceled = false;
+        self.repeat_report = None;
+        self.page_links = PageLinks::default();
+        self.cookies.clear();
+        self.test_results.clear();
+        self.extracted.clear();
+        self.retry = None;
+        self._retry_ticker = None;
         self.response = None;
+        self.set_sent_request(None, window, cx);
         self.preview_image = None;
+        self.hex_preview = None;
+        self.json_index = None;
+        self.json_tree = None;
+        self.json_value = None;
+        self.filtered_body = None;
+        self.filter_error = None;
+        self.pretty_body = None;
+        self.is_html = false;
+        self.body_kind = None;
+        self.decoding_note = None;
+        self._format_task = None;
+        self.large_body = None;
+        self.find_matches.clear();
         self.body_display.update(cx, |input, cx| {
             input.set_value("", window, cx);
         });
+        self.show_console(window, cx);
         self.active_tab = 0;
         cx.notify();
     }
 
+    /// The Console tab's transcript, when the sent request was verbose.
+    fn console_text(&self) -> Option<String> {
+        let sent = self.sent_request.as_deref().filter(|sent| sent.verbose)?;
+        let response = self.response.as_deref()?;
+        Some(crate::wire_log::transcript(sent, response, self.mask_secrets))
+    }
+
+    fn show_console(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.console_text().unwrap_or_default();
+        self.console_display.update(cx, |input, cx| input.set_value(text, window, cx));
+    }
+
+    fn toggle_mask_secrets(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        self.mask_secrets = !self.mask_secrets;
+        self.show_console(window, cx);
+        cx.notify();
+    }
+
+    fn copy_console(&mut self, _event: &gpui::ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(text) = self.console_text() {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+        }
+    }
+
+    fn set_sent_request(&mut self, sent: Option<Arc<RequestData>>, window: &mut Window, cx: &mut Context<Self>) {
+        let (text, language) = match sent.as_deref().map(|r| &r.body) {
+            Some(BodyType::Raw { content, subtype }) => (content.clone(), subtype.as_str()),
+            Some(body) => (crate::request_diff::body_text(body), "text"),
+            None => (String::new(), "text"),
+        };
+        self.sent_body_display.update(cx, |input, cx| {
+            input.set_highlighter(language, cx);
+            input.set_value(text, window, cx);
+        });
+        self.sent_request = sent;
+    }
+
+    /// The Request tab: method, resolved URL and wire headers as selectable
+    /// text, then the body in a read-only editor.
+    fn render_sent_request(&self, window: &mut Window, cx: &mut App) -> AnyElement {
+        let Some(request) = self.sent_request.clone() else {
+            return v_flex()
+                .p_2()
+                .text_sm()
+                .child("Not recorded for this response")
+                .into_any_element();
+        };
+        let has_body = !matches!(request.body, BodyType::None);
+        let head = TextView::html("sent-request", sent_request_to_html(&request), window, cx)
+            .selectable(true)
+            .style(TextViewStyle::default().paragraph_gap(rems(0.25)));
+        let theme = cx.theme();
+        v_flex()
+            .id("sent-request-scroll")
+            .flex_1()
+            .w_full()
+            .min_h_0()
+            .gap_2()
+            .track_scroll(&self.sent_scroll_handle)
+            .overflow_scroll()
+            .child(div().p_2().w_full().text_sm().child(head))
+            .child(
+                div()
+                    .px_2()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .child(if has_body { "Body" } else { "No body" }),
+            )
+            .when(has_body, |this| {
+                this.child(
+                    div()
+                        .h(px(280.))
+                        .flex_shrink_0()
+                        .w_full()
+                        .rounded(theme.radius_lg)
+                        .border_1()
+                        .border_color(theme.border)
+                        .bg(theme.popover)
+                        .child(
+                            Input::new(&self.sent_body_display)
+                                .disabled(true)
+                                .rounded(theme.radius_lg)
+                                .w_full()
+                                .h_full(),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+
+    /// Pretty-print an XML body off the UI thread (SOAP envelopes can be
+    /// large). The raw body stays on screen meanwhile, and for good if it is
+    /// not well-formed.
+    fn format_xml_in_background(&mut self, text: String, window: &mut Window, cx: &mut Context<Self>) {
+        self._format_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let formatted = cx
+                .background_executor()
+                .spawn(async move { crate::code_formatter::format_xml(&text) })
+                .await;
+            let Ok(pretty) = formatted else {
+                return;
+            };
+            let _ = this.update_in(cx, |this, window, cx| {
+                this.pretty_body = Some(Arc::from(pretty.as_str()));
+                if this.shown_view() == BodyView::Pretty {
+                    this.show_body_text(window, cx);
+                }
+                cx.notify();
+            });
+        }));
+    }
+
+    /// Copy body / Copy headers / Save body, next to the tab switch.
+    fn render_response_actions(&self, cx: &Context<Self>) -> impl IntoElement {
+        let response = self.response.as_ref();
+        // Nothing to copy or save when no response arrived or the body
+        // already went to a file.
+        let has_body = response.is_some_and(|r| !r.is_network_error() && r.downloaded.is_none());
+        let is_text = response.is_some_and(|r| r.is_text);
+        h_flex()
+            .gap_1()
+            .when(has_body && is_text, |this| {
+                this.child(
+                    Button::new("copy-body")
+                        .ghost()
+                        .xsmall()
+                        .label("Copy body")
+                        .tooltip("Copy the body as shown in Pretty, as received otherwise")
+                        .on_click(cx.listener(Self::copy_body)),
+                )
+            })
+            .when(response.is_some_and(|r| !r.headers.is_empty()), |this| {
+                this.child(
+                    Button::new("copy-headers")
+                        .ghost()
+                        .xsmall()
+                        .label("Copy headers")
+                        .on_click(cx.listener(Self::copy_headers)),
+                )
+            })
+            .when(has_body, |this| {
+                this.child(
+                    Button::new("save-body")
+                        .ghost()
+                        .xsmall()
+                        .label("Save body…")
+                        .tooltip("Save the body exactly as received")
+                        .on_click(cx.listener(Self::save_body)),
+                )
+            })
+    }
+
+    /// Pretty / Raw (/ Preview for HTML) switch for a text response.
+    fn render_body_view_toggle(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let shown = self.shown_view();
+        let pill = |id: &'static str, label: &'static str, view: BodyView| {
+            let active = shown == view;
+            a11y::focusable(crate::ui::segment_pill(theme, active).id(id), theme)
+                .text_xs()
+                .when(!active, |s| s.hover(|s| s.text_color(theme.foreground)))
+                .on_click(cx.listener(move |this, _, window, cx| this.set_body_view(view, window, cx)))
+                .child(label)
+        };
+        crate::ui::segmented_bar(theme)
+            .child(pill("resp-view-pretty", "Pretty", BodyView::Pretty))
+            .child(pill("resp-view-raw", "Raw", BodyView::Raw))
+            .when(self.is_html, |this| this.child(pill("resp-view-preview", "Preview", BodyView::Preview)))
+    }
+
+    /// The Tree tab: one row per visible JSON node, rendered lazily. Clicking
+    /// a container opens or closes it; right-click copies the path or value.
+    fn render_json_tree(&self, cx: &Context<Self>) -> AnyElement {
+        let count = self.json_tree.as_ref().map_or(0, |tree| tree.rows().len());
+        uniform_list(
+            "json-tree",
+            count,
+            cx.processor(|this, range: std::ops::Range<usize>, _window, cx| {
+                let rows = this.json_tree.as_ref().map_or(&[][..], |tree| tree.rows());
+                rows.get(range.clone())
+                    .unwrap_or_default()
+                    .iter()
+                    .zip(range)
+                    .map(|(row, ix)| this.render_tree_row(row, ix, cx))
+                    .collect::<Vec<_>>()
+            }),
+        )
+        .flex_1()
+        .w_full()
+        .min_h_0()
+        .py_1()
+        .track_scroll(self.json_tree_scroll.clone())
+        .into_any_element()
+    }
+
+    /// The body as plain rows when it is too big for the editor, with a
+    /// find bar standing in for the editor's search.
+    fn render_large_body(&self, cx: &Context<Self>) -> AnyElement {
+        let theme = cx.theme();
+        let (rows, lines) = self.large_body.as_ref().map_or((0, 0), |large| (large.rows().len(), large.line_count()));
+        let searching = !self.find_input.read(cx).value().is_empty();
+        let count = match self.find_matches.len() {
+            0 => "No matches".to_string(),
+            n => format!("{} of {n}", self.find_current + 1),
+        };
+        v_flex()
+            .flex_1()
+            .w_full()
+            .min_h_0()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .items_center()
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(div().w(px(220.)).child(Input::new(&self.find_input).small().cleanable(true)))
+                    .when(searching, |this| {
+                        this.child(div().text_xs().text_color(theme.muted_foreground).child(count))
+                            .child(
+                                Button::new("large-find-prev")
+                                    .ghost()
+                                    .xsmall()
+                                    .label("Prev")
+                                    .disabled(self.find_matches.is_empty())
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.step_match(false);
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new("large-find-next")
+                                    .ghost()
+                                    .xsmall()
+                                    .label("Next")
+                                    .disabled(self.find_matches.is_empty())
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.step_match(true);
+                                        cx.notify();
+                                    })),
+                            )
+                    })
+                    .child(
+                        div()
+                            .ml_auto()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(format!("Large body: {lines} lines, shown without highlighting")),
+                    ),
+            )
+            .child(
+                uniform_list(
+                    "large-body",
+                    rows,
+                    cx.processor(|this, range: std::ops::Range<usize>, _window, cx| {
+                        range.map(|ix| this.render_large_row(ix, cx)).collect::<Vec<_>>()
+                    }),
+                )
+                .flex_1()
+                .w_full()
+                .min_h_0()
+                .py_1()
+                .with_horizontal_sizing_behavior(ListHorizontalSizingBehavior::Unconstrained)
+                .track_scroll(self.large_scroll.clone()),
+            )
+            .into_any_element()
+    }
+
+    fn render_large_row(&self, ix: usize, cx: &Context<Self>) -> AnyElement {
+        let theme = cx.theme();
+        let Some(large) = &self.large_body else {
+            return div().into_any_element();
+        };
+        let row = &large.rows()[ix];
+        let text = large.row_text(row).to_string();
+        // Matches on this row, clipped to it; the current one stands out.
+        let highlights = self
+            .find_matches
+            .iter()
+            .enumerate()
+            .filter(|(_, found)| found.range.start < row.range.end && found.range.end > row.range.start)
+            .map(|(i, found)| {
+                let start = found.range.start.max(row.range.start) - row.range.start;
+                let end = found.range.end.min(row.range.end) - row.range.start;
+                let color = if i == self.find_current { theme.warning } else { theme.selection };
+                (start..end, HighlightStyle { background_color: Some(color), ..Default::default() })
+            })
+            .collect::<Vec<_>>();
+        h_flex()
+            .id(("large-body-row", ix))
+            .h(px(18.))
+            .font_family("monospace")
+            .text_xs()
+            .whitespace_nowrap()
+            .child(
+                div()
+                    .w(px(56.))
+                    .flex_shrink_0()
+                    .pr_2()
+                    .text_right()
+                    .text_color(theme.muted_foreground)
+                    .when(!row.continued, |this| this.child(row.line.to_string())),
+            )
+            .child(StyledText::new(text).with_highlights(highlights))
+            .into_any_element()
+    }
+
+    fn render_tree_row(&self, row: &TreeRow, ix: usize, cx: &Context<Self>) -> AnyElement {
+        let theme = cx.theme();
+        let pointer = row.pointer.clone();
+        let (marker, summary) = match &row.kind {
+            RowKind::Container { array, len, expanded } => (
+                if *expanded { "\u{25be}" } else { "\u{25b8}" },
+                match (array, len) {
+                    (true, 1) => "[…] 1 item".to_string(),
+                    (true, n) => format!("[…] {n} items"),
+                    (false, 1) => "{…} 1 key".to_string(),
+                    (false, n) => format!("{{…}} {n} keys"),
+                },
+            ),
+            RowKind::Scalar(text) => ("", text.clone()),
+            RowKind::More { hidden } => {
+                let shown = (*hidden).min(crate::json_tree::CHUNK);
+                return h_flex()
+                    .id(("json-tree-row", ix))
+                    .h(px(22.))
+                    .pl(px(12. + 16. * row.depth as f32))
+                    .text_xs()
+                    .text_color(theme.link)
+                    .cursor_pointer()
+                    .hover(|s| s.bg(theme.list_hover))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        if let Some(tree) = &mut this.json_tree {
+                            tree.show_more(&pointer);
+                            cx.notify();
+                        }
+                    }))
+                    .child(format!("Show {shown} more ({hidden} hidden)"))
+                    .into_any_element();
+            }
+        };
+        let is_container = matches!(row.kind, RowKind::Container { .. });
+        let (path, value_pointer) = (row.path.clone(), row.pointer.clone());
+        let viewer = cx.entity();
+        h_flex()
+            .id(("json-tree-row", ix))
+            .h(px(22.))
+            .pl(px(12. + 16. * row.depth as f32))
+            .pr_2()
+            .gap_1()
+            .items_center()
+            .text_xs()
+            .font_family(theme.mono_font_family.clone())
+            .whitespace_nowrap()
+            .hover(|s| s.bg(theme.list_hover))
+            .when(is_container, |this| {
+                this.cursor_pointer().on_click(cx.listener(move |this, _, _, cx| {
+                    if let Some(tree) = &mut this.json_tree {
+                        tree.toggle(&pointer);
+                        cx.notify();
+                    }
+                }))
+            })
+            .child(div().w(px(10.)).text_color(theme.muted_foreground).child(marker))
+            .when_some(row.label.clone(), |this, label| {
+                this.child(div().text_color(theme.foreground).font_weight(FontWeight::SEMIBOLD).child(label))
+                    .child(div().text_color(theme.muted_foreground).child(":"))
+            })
+            .child(
+                div()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .text_color(if is_container { theme.muted_foreground } else { theme.foreground })
+                    .child(summary),
+            )
+            .context_menu(move |menu, _window, _cx| {
+                let (path, viewer, value_pointer) = (path.clone(), viewer.clone(), value_pointer.clone());
+                menu.item(PopupMenuItem::new("Copy JSONPath").on_click(move |_, _, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(path.clone()));
+                }))
+                .item(PopupMenuItem::new("Copy value").on_click(move |_, _, cx| {
+                    let text = viewer.read(cx).json_tree.as_ref().and_then(|tree| tree.copy_text(&value_pointer));
+                    if let Some(text) = text {
+                        cx.write_to_clipboard(ClipboardItem::new_string(text));
+                    }
+                }))
+            })
+            .into_any_element()
+    }
+
+    /// The Preview pane: the HTML body rendered as selectable rich text.
+    fn render_body_preview(&self, window: &mut Window, cx: &mut App) -> AnyElement {
+        let html = self.response.as_ref().map(|r| r.body_text().to_string()).unwrap_or_default();
+        let view = TextView::html("resp-preview", html, window, cx).selectable(true);
+        div()
+            .id("resp-preview-scroll")
+            .flex_1()
+            .w_full()
+            .min_h_0()
+            .p_3()
+            .text_sm()
+            .overflow_y_scroll()
+            .child(view)
+            .into_any_element()
+    }
+
+    /// Show (or hide) the rate-limit chip for the current response.
+    pub fn set_retry(&mut self, retry: Option<PendingRetry>, cx: &mut Context<Self>) {
+        self.retry = retry;
+        self._retry_ticker = retry.filter(|r| r.deadline.is_some()).map(|_| {
+            cx.spawn(async move |this, cx| {
+                loop {
+                    cx.background_executor().timer(Duration::from_secs(1)).await;
+                    if this.update(cx, |_, cx| cx.notify()).is_err() {
+                        break;
+                    }
+                }
+            })
+        });
+        cx.notify();
+    }
+
     /// Clear the panel and show a "Request canceled" notice.
     pub fn show_canceled(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.clear_response(window, cx);
@@ -192,8 +1180,27 @@ impl ResponseViewer {
         cx.notify();
     }
 
-    /// Save the (binary) response body to a file chosen via the OS dialog.
-    fn save_binary(&mut self, _event: &gpui::ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+    /// Put the body on the clipboard (see `body_to_copy`). Text bodies only.
+    fn copy_body(&mut self, _event: &gpui::ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(response) = self.response.as_ref().filter(|r| r.is_text) else {
+            return;
+        };
+        let raw = response.body_text();
+        let reformatted = self.filtered_body.as_deref().or(self.pretty_body.as_deref());
+        let text = body_to_copy(self.shown_view(), &raw, reformatted).to_string();
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+    }
+
+    /// Put the headers on the clipboard as `Key: Value` lines.
+    fn copy_headers(&mut self, _event: &gpui::ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(response) = &self.response {
+            cx.write_to_clipboard(ClipboardItem::new_string(headers_to_text(&response.headers)));
+        }
+    }
+
+    /// Save the response body, byte for byte as it arrived, to a file chosen
+    /// via the OS dialog.
+    fn save_body(&mut self, _event: &gpui::ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         let Some(response) = self.response.clone() else {
             return;
         };
@@ -220,67 +1227,798 @@ impl ResponseViewer {
         .detach();
     }
 
-    fn render_status_bar(&self, cx: &App) -> impl IntoElement {
-        if let Some(response) = &self.response {
-            let status_color = if response.is_network_error() {
-                cx.theme().danger // Special color for network errors
-            } else if response.is_success() {
-                cx.theme().success
-            } else if response.is_error() {
-                cx.theme().danger
-            } else {
-                cx.theme().accent
-            };
+    /// Send the request behind a truncated response again and stream the
+    /// whole body to a file chosen via the OS dialog.
+    fn save_full_response(&mut self, _event: &gpui::ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(sent) = self.sent_request.clone() else {
+            return;
+        };
+        let client = match crate::http_client::HttpClient::for_route(
+            &sent.route,
+            sent.minimal_headers,
+            sent.ignore_tls_errors,
+            sent.protocol,
+            &sent.host_overrides,
+        ) {
+            Ok(client) => client.with_follow_redirects(sent.follow_redirects),
+            Err(e) => {
+                self.full_save = Some(FullSave::Failed(e.to_string()));
+                cx.notify();
+                return;
+            }
+        };
+        let dir = dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| std::path::PathBuf::from("."));
+        let rx = cx.prompt_for_new_path(&dir, Some("response.bin"));
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(path))) = rx.await else {
+                return Ok(());
+            };
+            this.update(cx, |this, cx| {
+                this.full_save = Some(FullSave::Running);
+                cx.notify();
+            })?;
+            let download = client.start_download(
+                sent.method.clone(),
+                sent.url.clone(),
+                sent.headers.clone(),
+                sent.body.clone(),
+                path.clone(),
+            );
+            let written = download.progress();
+            let outcome = match download.wait().await {
+                Ok(_) => {
+                    let bytes = written.load(std::sync::atomic::Ordering::Relaxed);
+                    log::info!("Saved full response ({} bytes) to {:?}", bytes, path);
+                    FullSave::Saved { bytes }
+                }
+                Err(e) => {
+                    log::error!("Failed to save full response to {:?}: {}", path, e);
+                    FullSave::Failed(e.to_string())
+                }
+            };
+            this.update(cx, |this, cx| {
+                this.full_save = Some(outcome);
+                cx.notify();
+            })
+        })
+        .detach();
+    }
+
+    /// Notice under the status bar for a body cut at the size limit, with
+    /// the way to get all of it.
+    fn render_truncation(&self, response: &ResponseData, received: u64, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let size = crate::format::format_size;
+        let status = match &self.full_save {
+            None => None,
+            Some(FullSave::Running) => Some("Downloading…".to_string()),
+            Some(FullSave::Saved { bytes }) => Some(format!("Saved {}", size(*bytes as usize))),
+            Some(FullSave::Failed(e)) => Some(format!("Save failed: {e}")),
+        };
+        h_flex()
+            .gap_3()
+            .px_4()
+            .py_1p5()
+            .border_b_1()
+            .border_color(theme.border)
+            .bg(theme.warning.opacity(0.1))
+            .text_xs()
+            .child(
+                div()
+                    .id("body-truncated")
+                    .text_color(theme.warning)
+                    .child(format!("Body truncated at {}", size(response.body.len())))
+                    .tooltip(move |window, cx| {
+                        Tooltip::new(format!(
+                            "Reading stopped after {} (Edit > Response Size Limit).",
+                            size(received as usize)
+                        ))
+                        .build(window, cx)
+                    }),
+            )
+            .child(
+                Button::new("save-full-response")
+                    .xsmall()
+                    .ghost()
+                    .label("Save full response to file…")
+                    .tooltip("Sends the request again and writes the whole body to a file")
+                    .disabled(self.sent_request.is_none() || self.full_save == Some(FullSave::Running))
+                    .on_click(cx.listener(Self::save_full_response)),
+            )
+            .when_some(status, |this, status| {
+                this.child(div().text_color(theme.muted_foreground).child(status))
+            })
+    }
+
+    /// Prev / Next page buttons for the status bar. Click loads and sends;
+    /// ctrl/cmd-click only loads the URL so it can be tweaked first.
+    fn render_page_buttons(&self, cx: &Context<Self>) -> impl IntoElement {
+        let button = |id: &'static str, label: &'static str, target: PageTarget| {
+            Button::new(id)
+                .ghost()
+                .xsmall()
+                .label(label)
+                .tooltip("Load and send (ctrl-click to load only)")
+                .on_click(cx.listener(move |_, event: &ClickEvent, _, cx| {
+                    cx.emit(FollowPageLink {
+                        target: target.clone(),
+                        send: !event.modifiers().secondary(),
+                    });
+                }))
+        };
+
+        h_flex()
+            .ml_auto()
+            .gap_1()
+            .children(self.page_links.prev.clone().map(|t| button("page-prev", "← Prev page", t)))
+            .children(self.page_links.next.clone().map(|t| button("page-next", "Next page →", t)))
+    }
+
+    /// "Retrying in 4s" with Retry now / Cancel while counting down, or the
+    /// wait the server asked for with a button to start it.
+    fn render_retry_chip(&self, retry: PendingRetry, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let button = |id: &'static str, label: &'static str, command: RetryCommand| {
+            Button::new(id)
+                .ghost()
+                .xsmall()
+                .label(label)
+                .on_click(cx.listener(move |_, _, _, cx| cx.emit(command)))
+        };
+        let chip = |text: String| {
+            div()
+                .px_2()
+                .py_0p5()
+                .rounded(theme.radius)
+                .text_xs()
+                .bg(theme.warning.opacity(0.12))
+                .text_color(theme.warning)
+                .child(text)
+        };
+
+        match retry.remaining(Instant::now()) {
+            Some(left) => h_flex()
+                .gap_1()
+                .items_center()
+                .child(chip(format!("Retrying in {}s", left.as_secs_f64().ceil() as u64)))
+                .child(button("retry-now", "Retry now", RetryCommand::Now))
+                .child(button("retry-cancel", "Cancel", RetryCommand::Cancel)),
+            None => h_flex()
+                .gap_1()
+                .items_center()
+                .child(chip(format!("Retry-After {}s", retry.delay.as_secs())))
+                .child(button("retry-start", "Retry when allowed", RetryCommand::Start)),
+        }
+    }
+
+    /// The filter box above a JSON body, with the parse error under it.
+    fn render_filter_bar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        div()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(theme.border)
+            .child(Input::new(&self.filter_input).small().cleanable(true))
+            .when_some(self.filter_error.clone(), |this, error| {
+                this.child(div().id("filter-error").pt_1().text_xs().text_color(theme.danger).child(error))
+            })
+    }
+
+    /// Path of the JSON value under the body's caret. Each segment moves the
+    /// caret (and so the scroll position) to the line its value opens on.
+    fn render_json_breadcrumbs(&self, index: &JsonLineIndex, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let line = self.body_display.read(cx).cursor_position().line as usize;
+        let crumbs = index.crumbs_at(line);
+        let full_path = path_string(&crumbs);
+
+        h_flex()
+            .id("json-breadcrumbs")
+            .px_2()
+            .py_0p5()
+            .border_b_1()
+            .border_color(theme.border)
+            .overflow_x_hidden()
+            .text_xs()
+            .font_family("monospace")
+            .text_color(theme.muted_foreground)
+            .when(crumbs.is_empty(), |this| this.child("(root)"))
+            .children(crumbs.into_iter().enumerate().map(|(i, crumb)| {
+                let target = crumb.line as u32;
+                div()
+                    .id(("json-crumb", i))
+                    .flex_shrink_0()
+                    .cursor_pointer()
+                    .hover(|s| s.text_color(theme.foreground))
+                    .child(crumb.text)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.body_display.update(cx, |input, cx| {
+                            input.set_cursor_position(gpui_component::input::Position::new(target, 0), window, cx);
+                        });
+                    }))
+            }))
+            .when(!full_path.is_empty(), |this| {
+                this.tooltip(move |window, cx| Tooltip::new(full_path.clone()).build(window, cx))
+            })
+    }
+
+    fn render_status_bar(&self, cx: &Context<Self>) -> impl IntoElement {
+        if let Some(response) = &self.response {
+            let status_color = if response.is_network_error() {
+                cx.theme().danger // Special color for network errors
+            } else if response.is_success() {
+                cx.theme().success
+            } else if response.is_error() {
+                cx.theme().danger
+            } else {
+                cx.theme().accent
+            };
+
+            let status_text = if response.is_timeout() {
+                "TIMEOUT".to_string()
+            } else if response.is_network_error() {
+                format!("ERROR - {}", response.status_text())
+            } else {
+                format!(
+                    "{} {}",
+                    response.status.unwrap_or(0),
+                    response.status_text()
+                )
+            };
+
+            h_flex()
+                .gap_3()
+                .items_center()
+                .px_4()
+                .py_2p5()
+                .border_b_1()
+                .border_color(cx.theme().border)
+                .child(
+                    div()
+                        .px_2p5()
+                        .py_0p5()
+                        .rounded(cx.theme().radius)
+                        .text_xs()
+                        .font_weight(FontWeight::BOLD)
+                        .bg(status_color.opacity(0.12))
+                        .text_color(status_color)
+                        .child(status_text),
+                )
+                .when_some(response.version.clone(), |this, version| {
+                    this.child(div().text_xs().text_color(cx.theme().muted_foreground).child(version))
+                })
+                .child(
+                    div()
+                        .id("response-time")
+                        .text_sm()
+                        .child(format!("Time: {}", crate::format::format_duration_ms(response.duration_ms)))
+                        .when_some(timing_breakdown(response), |this, breakdown| {
+                            this.tooltip(move |window, cx| Tooltip::new(breakdown.clone()).build(window, cx))
+                        }),
+                )
+                .when_some(response.timing, |this, timing| this.child(self.render_timing_bar(&timing, cx)))
+                .when(response.attempts > 1, |this| {
+                    this.child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("({} attempts)", response.attempts)),
+                    )
+                })
+                .when(response.tls_unverified, |this| {
+                    this.child(
+                        div()
+                            .id("tls-unverified")
+                            .px_2()
+                            .py_0p5()
+                            .rounded(cx.theme().radius)
+                            .text_xs()
+                            .bg(cx.theme().warning.opacity(0.15))
+                            .text_color(cx.theme().warning)
+                            .child("TLS not verified")
+                            .tooltip(|window, cx| {
+                                Tooltip::new("Sent with certificate verification off; the server's identity was not checked.")
+                                    .build(window, cx)
+                            }),
+                    )
+                })
+                .when_some(response.resolved_by.clone(), |this, resolved_by| {
+                    let tooltip = format!(
+                        "Connected to {} instead of the address DNS gives for {}; TLS and the Host header still used {}.",
+                        resolved_by.address_label(),
+                        resolved_by.host,
+                        resolved_by.host
+                    );
+                    this.child(
+                        div()
+                            .id("resolved-by")
+                            .px_2()
+                            .py_0p5()
+                            .rounded(cx.theme().radius)
+                            .text_xs()
+                            .bg(cx.theme().info.opacity(0.15))
+                            .text_color(cx.theme().info)
+                            .child(format!("Resolved → {}", resolved_by.address_label()))
+                            .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx)),
+                    )
+                })
+                .when(response.network_profile.is_throttled(), |this| {
+                    this.child(
+                        div()
+                            .px_2()
+                            .py_0p5()
+                            .rounded(cx.theme().radius)
+                            .text_xs()
+                            .bg(cx.theme().warning.opacity(0.15))
+                            .text_color(cx.theme().warning)
+                            .child(format!("Throttled · {}", response.network_profile.label())),
+                    )
+                })
+                .when(!response.is_network_error(), |this| {
+                    let (size, breakdown) = size_summary(response);
+                    this.child(
+                        div()
+                            .id("response-size")
+                            .text_sm()
+                            .child(size)
+                            .when_some(breakdown, |this, breakdown| {
+                                this.tooltip(move |window, cx| Tooltip::new(breakdown.clone()).build(window, cx))
+                            }),
+                    )
+                })
+                .when_some(self.body_kind.filter(|_| !response.is_network_error()), |this, kind| {
+                    this.child(
+                        div()
+                            .id("body-kind")
+                            .px_1p5()
+                            .rounded(cx.theme().radius)
+                            .border_1()
+                            .border_color(cx.theme().border)
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(kind.label())
+                            .tooltip(|window, cx| Tooltip::new("Detected body type").build(window, cx)),
+                    )
+                })
+                .when_some(self.decoding_note.clone().filter(|_| response.is_text), |this, note| {
+                    this.child(
+                        div()
+                            .id("body-decoding")
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(note)
+                            .tooltip(|window, cx| {
+                                Tooltip::new("Save body… writes the bytes as received").build(window, cx)
+                            }),
+                    )
+                })
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(format!("via {}", response.route.label())),
+                )
+                .when_some(self.retry, |this, retry| this.child(self.render_retry_chip(retry, cx)))
+                .when(!self.page_links.is_empty(), |this| this.child(self.render_page_buttons(cx)))
+        } else {
+            h_flex()
+                .px_4()
+                .py_2p5()
+                .border_b_1()
+                .border_color(cx.theme().border)
+                .text_color(cx.theme().muted_foreground)
+                .child(if self.canceled { "Request canceled" } else { "No response yet" })
+        }
+    }
+
+    /// Theme color of a timing phase, shared by the bar and its breakdown.
+    fn phase_color(kind: PhaseKind, cx: &App) -> Hsla {
+        let theme = cx.theme();
+        match kind {
+            PhaseKind::Latency => theme.warning,
+            PhaseKind::Dns => theme.chart_1,
+            PhaseKind::Connect => theme.chart_2,
+            PhaseKind::Wait => theme.chart_3,
+            PhaseKind::Download => theme.chart_4,
+        }
+    }
+
+    /// The phases as one small stacked bar; a click opens `render_timing_details`.
+    fn render_timing_bar(&self, timing: &ResponseTiming, cx: &Context<Self>) -> impl IntoElement {
+        let phases = timing_phases(timing);
+        let total = phases.iter().map(|p| p.ms).sum::<u64>().max(1) as f32;
+        let expanded = self.timing_expanded;
+        h_flex()
+            .id("timing-bar")
+            .w(px(96.))
+            .h(px(6.))
+            .rounded(px(3.))
+            .overflow_hidden()
+            .bg(cx.theme().muted)
+            .cursor_pointer()
+            .children(
+                phases
+                    .iter()
+                    .filter(|p| p.ms > 0)
+                    .map(|p| div().h_full().w(relative(p.ms as f32 / total)).bg(Self::phase_color(p.kind, cx))),
+            )
+            .on_click(cx.listener(|this, _, _, cx| {
+                this.timing_expanded = !this.timing_expanded;
+                cx.notify();
+            }))
+            .tooltip(move |window, cx| {
+                Tooltip::new(if expanded { "Hide timing breakdown" } else { "Show timing breakdown" }).build(window, cx)
+            })
+    }
 
-            let status_text = if response.is_network_error() {
-                format!("ERROR - {}", response.status_text())
-            } else {
-                format!(
-                    "{} {}",
-                    response.status.unwrap_or(0),
-                    response.status_text()
-                )
-            };
+    /// One row per phase, each bar starting where the previous one ended.
+    fn render_timing_details(&self, timing: &ResponseTiming, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let phases = timing_phases(timing);
+        let total = phases.iter().map(|p| p.ms).sum::<u64>().max(1) as f32;
+        let mut start = 0;
+        v_flex()
+            .gap_1()
+            .px_4()
+            .py_1p5()
+            .border_b_1()
+            .border_color(theme.border)
+            .children(phases.into_iter().map(|phase| {
+                let offset = start as f32 / total;
+                start += phase.ms;
+                h_flex()
+                    .gap_2()
+                    .text_xs()
+                    .child(div().flex_shrink_0().w(px(132.)).text_color(theme.muted_foreground).child(phase.label))
+                    .child(
+                        h_flex()
+                            .flex_1()
+                            .h(px(6.))
+                            .child(div().h_full().w(relative(offset)))
+                            .child(
+                                div()
+                                    .h_full()
+                                    .w(relative(phase.ms as f32 / total))
+                                    .min_w(px(1.))
+                                    .rounded(px(2.))
+                                    .bg(Self::phase_color(phase.kind, cx)),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .flex_shrink_0()
+                            .w(px(56.))
+                            .text_right()
+                            .text_color(theme.foreground)
+                            .child(crate::format::format_duration_ms(phase.ms)),
+                    )
+            }))
+    }
+
+    /// The redirects followed to reach the response, one hop per line.
+    fn render_redirects(&self, redirects: &[(String, u16)], cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        v_flex()
+            .gap_0p5()
+            .px_4()
+            .py_1p5()
+            .border_b_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(theme.muted_foreground)
+                    .child(format!("Redirects ({})", redirects.len())),
+            )
+            .children(redirects.iter().map(|(url, status)| {
+                h_flex()
+                    .gap_2()
+                    .text_xs()
+                    .child(div().flex_shrink_0().w(px(28.)).text_color(theme.accent).child(status.to_string()))
+                    .child(
+                        div()
+                            .min_w_0()
+                            .overflow_x_hidden()
+                            .whitespace_nowrap()
+                            .text_ellipsis()
+                            .text_color(theme.foreground)
+                            .child(url.clone()),
+                    )
+            }))
+    }
+
+    /// Summary line + per-attempt table for a "Send ×N" batch.
+    fn render_repeat_report(&self, report: &RepeatReport, cx: &App) -> AnyElement {
+        let theme = cx.theme();
+        let summary = report.summary();
+        let outcome_color = if summary.failed == 0 { theme.success } else { theme.danger };
+        let outcome = if report.canceled {
+            format!("CANCELED {}/{}", summary.completed, report.requested)
+        } else {
+            format!("{}/{} OK", summary.succeeded, summary.completed)
+        };
+        let ms = crate::format::format_duration_ms;
+
+        v_flex()
+            .flex_1()
+            .min_h_0()
+            .w_full()
+            .child(
+                h_flex()
+                    .gap_3()
+                    .items_center()
+                    .px_4()
+                    .py_2p5()
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .px_2p5()
+                            .py_0p5()
+                            .rounded(theme.radius)
+                            .text_xs()
+                            .font_weight(FontWeight::BOLD)
+                            .bg(outcome_color.opacity(0.12))
+                            .text_color(outcome_color)
+                            .child(outcome),
+                    )
+                    .child(div().text_sm().child(format!(
+                        "min {} · avg {} · p95 {}",
+                        ms(summary.min_ms),
+                        ms(summary.avg_ms),
+                        ms(summary.p95_ms)
+                    )))
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.muted_foreground)
+                            .child(format!(
+                                "Total: {} · {} at a time",
+                                ms(report.total_ms),
+                                report.concurrency
+                            )),
+                    ),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .child(
+                        v_flex()
+                            .id("repeat-attempts-scroll")
+                            .size_full()
+                            .p_4()
+                            .gap_1()
+                            .track_scroll(&self.repeat_scroll_handle)
+                            .overflow_scroll()
+                            .children(report.attempts.iter().map(|attempt| {
+                                let color = if attempt.is_success() { theme.success } else { theme.danger };
+                                let status = match attempt.status {
+                                    Some(code) => code.to_string(),
+                                    None => "ERROR".to_string(),
+                                };
+                                h_flex()
+                                    .gap_3()
+                                    .text_sm()
+                                    .child(
+                                        div()
+                                            .w(px(40.))
+                                            .text_color(theme.muted_foreground)
+                                            .child(format!("#{}", attempt.index + 1)),
+                                    )
+                                    .child(div().w(px(56.)).text_color(color).child(status))
+                                    .child(div().w(px(72.)).child(ms(attempt.duration_ms)))
+                                    .when_some(attempt.error.clone(), |this, error| {
+                                        this.child(
+                                            div()
+                                                .flex_1()
+                                                .min_w_0()
+                                                .truncate()
+                                                .text_color(theme.muted_foreground)
+                                                .child(error),
+                                        )
+                                    })
+                            })),
+                    )
+                    .vertical_scrollbar(&self.repeat_scroll_handle),
+            )
+            .into_any_element()
+    }
 
+    /// The Tests tab: each assertion with a pass / fail mark and what the
+    /// response had.
+    fn render_test_results(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let rows = self.test_results.iter().enumerate().map(|(ix, result)| {
+            let (mark, color) = if result.passed { ("✓", theme.success) } else { ("✗", theme.danger) };
+            let tooltip = format!("Expected: {}\nActual: {}", result.assertion.describe(), result.actual);
             h_flex()
+                .id(("test-result", ix))
                 .gap_3()
-                .items_center()
-                .px_4()
-                .py_2p5()
+                .px_2()
+                .py_1()
+                .text_sm()
                 .border_b_1()
-                .border_color(cx.theme().border)
+                .border_color(theme.border.opacity(0.5))
+                .child(div().w(px(16.)).flex_shrink_0().text_color(color).child(mark))
+                .child(div().flex_1().min_w_0().truncate().child(result.assertion.describe()))
                 .child(
                     div()
-                        .px_2p5()
-                        .py_0p5()
-                        .rounded(cx.theme().radius)
+                        .max_w(px(320.))
+                        .truncate()
+                        .font_family(theme.mono_font_family.clone())
                         .text_xs()
-                        .font_weight(FontWeight::BOLD)
-                        .bg(status_color.opacity(0.12))
-                        .text_color(status_color)
-                        .child(status_text),
+                        .text_color(if result.passed { theme.muted_foreground } else { color })
+                        .child(result.actual.clone()),
                 )
+                .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+        });
+        let extracted = self.extracted.iter().enumerate().map(|(ix, result)| {
+            let (mark, color, value) = match &result.value {
+                Ok(value) => ("✓", theme.success, value.clone()),
+                Err(reason) => ("✗", theme.danger, reason.clone()),
+            };
+            let rule = &result.rule;
+            h_flex()
+                .id(("extract-result", ix))
+                .gap_3()
+                .px_2()
+                .py_1()
+                .text_sm()
+                .border_b_1()
+                .border_color(theme.border.opacity(0.5))
+                .child(div().w(px(16.)).flex_shrink_0().text_color(color).child(mark))
                 .child(
                     div()
-                        .text_sm()
-                        .child(format!("Time: {}", crate::format::format_duration_ms(response.duration_ms))),
+                        .flex_1()
+                        .min_w_0()
+                        .truncate()
+                        .child(format!("{{{{{}}}}} ← {} {}", rule.variable, rule.source.label(), rule.path)),
                 )
-                .when(!response.is_network_error(), |this| {
-                    this.child(
-                        div()
-                            .text_sm()
-                            .child(format!("Size: {}", crate::format::format_size(response.body.len()))),
-                    )
+                .child(
+                    div()
+                        .max_w(px(320.))
+                        .truncate()
+                        .font_family(theme.mono_font_family.clone())
+                        .text_xs()
+                        .text_color(if result.value.is_ok() { theme.muted_foreground } else { color })
+                        .child(value.clone()),
+                )
+                .when(result.value.is_ok(), |this| {
+                    this.child(div().text_xs().text_color(theme.muted_foreground).child(rule.scope.label()))
                 })
-        } else {
-            h_flex()
-                .px_4()
-                .py_2p5()
+                .tooltip(move |window, cx| Tooltip::new(value.clone()).build(window, cx))
+        });
+        v_flex()
+            .id("test-results")
+            .flex_1()
+            .min_h_0()
+            .w_full()
+            .overflow_y_scroll()
+            .rounded(theme.radius_lg)
+            .border_1()
+            .border_color(theme.border)
+            .children(rows)
+            .when(!self.extracted.is_empty(), |this| {
+                this.child(
+                    div()
+                        .px_2()
+                        .py_1()
+                        .text_xs()
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .text_color(theme.muted_foreground)
+                        .border_b_1()
+                        .border_color(theme.border)
+                        .child("Extracted"),
+                )
+                .children(extracted)
+            })
+    }
+
+    /// One row per `Set-Cookie` header; malformed ones as their raw text.
+    fn render_cookies(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let cell = |width: f32| div().w(px(width)).flex_shrink_0().truncate();
+        let flag = |on: bool| if on { "✓" } else { "" };
+        let heading = h_flex()
+            .gap_3()
+            .px_2()
+            .py_1()
+            .text_xs()
+            .font_weight(FontWeight::SEMIBOLD)
+            .text_color(theme.muted_foreground)
+            .border_b_1()
+            .border_color(theme.border)
+            .child(cell(140.).child("Name"))
+            .child(div().flex_1().min_w_0().child("Value"))
+            .child(cell(120.).child("Domain"))
+            .child(cell(80.).child("Path"))
+            .child(cell(150.).child("Expires"))
+            .child(cell(64.).child("HttpOnly"))
+            .child(cell(48.).child("Secure"))
+            .child(cell(64.).child("SameSite"))
+            .child(cell(48.));
+        let rows = self.cookies.iter().enumerate().map(|(ix, cookie)| {
+            let row = h_flex()
+                .id(("cookie-row", ix))
+                .gap_3()
+                .px_2()
+                .py_1()
+                .text_sm()
                 .border_b_1()
-                .border_color(cx.theme().border)
-                .text_color(cx.theme().muted_foreground)
-                .child(if self.canceled { "Request canceled" } else { "No response yet" })
-        }
+                .border_color(theme.border.opacity(0.5));
+            let (value, row) = match cookie {
+                SetCookie::Parsed(cookie) => (
+                    cookie.value.clone(),
+                    row.child(cell(140.).font_weight(FontWeight::MEDIUM).child(cookie.name.clone()))
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .truncate()
+                                .font_family(theme.mono_font_family.clone())
+                                .child(cookie.value.clone()),
+                        )
+                        .child(cell(120.).child(cookie.domain.clone().unwrap_or_default()))
+                        .child(cell(80.).child(cookie.path.clone().unwrap_or_default()))
+                        .child(cell(150.).child(cookie.expiry()))
+                        .child(cell(64.).child(flag(cookie.http_only)))
+                        .child(cell(48.).child(flag(cookie.secure)))
+                        .child(cell(64.).child(cookie.same_site.clone().unwrap_or_default())),
+                ),
+                SetCookie::Malformed(raw) => (
+                    raw.clone(),
+                    row.child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .truncate()
+                            .text_color(theme.muted_foreground)
+                            .font_family(theme.mono_font_family.clone())
+                            .child(raw.clone()),
+                    ),
+                ),
+            };
+            row.child(
+                cell(48.).child(
+                    Button::new(("copy-cookie", ix))
+                        .ghost()
+                        .xsmall()
+                        .label("Copy")
+                        .tooltip("Copy value")
+                        .on_click(move |_, _, cx| cx.write_to_clipboard(ClipboardItem::new_string(value.clone()))),
+                ),
+            )
+        });
+        div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_h_0()
+            .w_full()
+            .rounded(theme.radius_lg)
+            .border_1()
+            .border_color(theme.border)
+            .bg(theme.popover)
+            .child(heading)
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .child(
+                        v_flex()
+                            .id("cookies-scroll")
+                            .size_full()
+                            .track_scroll(&self.cookies_scroll_handle)
+                            .overflow_scroll()
+                            .children(rows),
+                    )
+                    .vertical_scrollbar(&self.cookies_scroll_handle),
+            )
     }
 
     fn render_headers(&self, window: &mut Window, cx: &mut App) -> AnyElement {
@@ -305,13 +2043,16 @@ impl ResponseViewer {
                         .child(
                             TextView::html(
                                 "response-headers",
-                                headers_to_html(&response.headers),
+                                headers_to_html(&response.headers, &response.raw_header_values),
                                 window,
                                 cx,
                             )
                             .selectable(true)
                             .style(TextViewStyle::default().paragraph_gap(rems(0.25))),
                         )
+                        .when(!response.raw_header_values.is_empty(), |this| {
+                            this.child(self.render_raw_header_values(response, cx))
+                        })
                         .context_menu(move |menu, _window, _cx| {
                             // Only "Copy all headers" -- a "Copy selection" item cannot
                             // work here: it would have to dispatch TextView's Copy
@@ -338,12 +2079,43 @@ impl ResponseViewer {
     }
 }
 
+impl ResponseViewer {
+    /// Footnote under the header list: one line per value that was not plain
+    /// ASCII, with the exact bytes on hover.
+    fn render_raw_header_values(&self, response: &ResponseData, cx: &App) -> impl IntoElement {
+        v_flex()
+            .mt_2()
+            .gap_1()
+            .text_xs()
+            .text_color(cx.theme().warning)
+            .children(response.raw_header_values.iter().filter_map(|raw| {
+                let (name, _) = response.headers.get(raw.index)?;
+                let escaped = raw.escaped();
+                Some(
+                    div()
+                        .id(("raw-header", raw.index))
+                        .child(format!("⚠ {name}: value is not ASCII, shown decoded"))
+                        .tooltip(move |window, cx| {
+                            Tooltip::new(format!("Raw bytes: {escaped}")).build(window, cx)
+                        }),
+                )
+            }))
+    }
+}
+
+impl EventEmitter<FollowPageLink> for ResponseViewer {}
+impl EventEmitter<RetryCommand> for ResponseViewer {}
+
 impl Render for ResponseViewer {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         // Built before `theme` borrows cx immutably -- TextView needs &mut App.
         // Only while the tab is showing, so the HTML is not parsed for nothing.
         let headers_el = (self.active_tab == 1 && self.response.is_some())
             .then(|| self.render_headers(window, cx));
+        let sent_el = (self.active_tab == 2 && self.response.is_some())
+            .then(|| self.render_sent_request(window, cx));
+        let mut preview_el = (self.active_tab == 0 && self.shown_view() == BodyView::Preview)
+            .then(|| self.render_body_preview(window, cx));
         let theme = cx.theme();
 
         div()
@@ -354,17 +2126,34 @@ impl Render for ResponseViewer {
             .h_full()
             .overflow_hidden() // Prevent content overflow
             .on_click(cx.listener(|_, _, _, cx| cx.stop_propagation())) // Prevent click events from propagating
-            .child(
-                // Response status bar (self-styled with its own padding + bottom border)
-                div()
-                    .flex()
-                    .flex_col()
-                    .w_full()
-                    .child(self.render_status_bar(cx)),
-            )
-            .when_some(self.response.as_ref(), |this, _| {
+            .when_some(self.repeat_report.clone(), |this, report| {
+                this.child(self.render_repeat_report(&report, cx))
+            })
+            .when(self.repeat_report.is_none(), |this| {
                 this.child(
+                    // Response status bar (self-styled with its own padding + bottom border)
                     div()
+                        .flex()
+                        .flex_col()
+                        .w_full()
+                        .child(self.render_status_bar(cx))
+                        .when_some(
+                            self.response.as_ref().and_then(|r| r.timing).filter(|_| self.timing_expanded),
+                            |this, timing| this.child(self.render_timing_details(&timing, cx)),
+                        )
+                        .when_some(
+                            self.response.as_ref().filter(|r| !r.redirects.is_empty()),
+                            |this, response| this.child(self.render_redirects(&response.redirects, cx)),
+                        )
+                        .when_some(
+                            self.response.as_ref().and_then(|r| Some((r, r.truncated_after?))),
+                            |this, (response, received)| this.child(self.render_truncation(response, received, cx)),
+                        ),
+                )
+            })
+            .when_some(self.response.as_ref(), |this, _| {
+                this.child(
+                    a11y::tab_group(div(), tab_order::RESPONSE)
                         .flex()
                         .flex_col()
                         .gap_2()
@@ -378,39 +2167,234 @@ impl Render for ResponseViewer {
                         .p_4()
                         .w_full()
                         .child(
-                            crate::ui::segmented_bar(theme)
+                            h_flex()
+                                .justify_between()
+                                .items_center()
                                 .child(
-                                    crate::ui::segment_pill(theme, self.active_tab == 0)
-                                        .id("resp-tab-body")
-                                        .when(self.active_tab != 0, |s| {
-                                            s.hover(|s| s.text_color(theme.foreground))
+                                    crate::ui::segmented_bar(theme)
+                                        .child(
+                                            a11y::focusable(
+                                                crate::ui::segment_pill(theme, self.active_tab == 0)
+                                                    .id("resp-tab-body"),
+                                                theme,
+                                            )
+                                                .when(self.active_tab != 0, |s| {
+                                                    s.hover(|s| s.text_color(theme.foreground))
+                                                })
+                                                .on_click(cx.listener(
+                                                    |this, _event: &gpui::ClickEvent, _window, cx| {
+                                                        this.active_tab = 0;
+                                                        cx.notify();
+                                                    },
+                                                ))
+                                                .child("Body"),
+                                        )
+                                        .child({
+                                            // Only for JSON bodies; dimmed otherwise.
+                                            let has_tree = self.json_tree.is_some();
+                                            a11y::focusable(
+                                                crate::ui::segment_pill(theme, self.active_tab == 3)
+                                                    .id("resp-tab-tree"),
+                                                theme,
+                                            )
+                                                .when(!has_tree, |s| s.opacity(0.5))
+                                                .when(has_tree && self.active_tab != 3, |s| {
+                                                    s.hover(|s| s.text_color(theme.foreground))
+                                                })
+                                                .when(has_tree, |s| {
+                                                    s.on_click(cx.listener(
+                                                        |this, _event: &gpui::ClickEvent, _window, cx| {
+                                                            this.active_tab = 3;
+                                                            cx.notify();
+                                                        },
+                                                    ))
+                                                })
+                                                .child("Tree")
                                         })
-                                        .on_click(cx.listener(
-                                            |this, _event: &gpui::ClickEvent, _window, cx| {
-                                                this.active_tab = 0;
-                                                cx.notify();
-                                            },
-                                        ))
-                                        .child("Body"),
+                                        .child(
+                                            a11y::focusable(
+                                                crate::ui::segment_pill(theme, self.active_tab == 1)
+                                                    .id("resp-tab-headers"),
+                                                theme,
+                                            )
+                                                .when(self.active_tab != 1, |s| {
+                                                    s.hover(|s| s.text_color(theme.foreground))
+                                                })
+                                                .on_click(cx.listener(
+                                                    |this, _event: &gpui::ClickEvent, _window, cx| {
+                                                        this.active_tab = 1;
+                                                        cx.notify();
+                                                    },
+                                                ))
+                                                .child("Headers"),
+                                        )
+                                        .child({
+                                            // Only when the response set cookies; dimmed otherwise.
+                                            let has_cookies = !self.cookies.is_empty();
+                                            a11y::focusable(
+                                                crate::ui::segment_pill(theme, self.active_tab == 4)
+                                                    .id("resp-tab-cookies"),
+                                                theme,
+                                            )
+                                                .when(!has_cookies, |s| s.opacity(0.5))
+                                                .when(has_cookies && self.active_tab != 4, |s| {
+                                                    s.hover(|s| s.text_color(theme.foreground))
+                                                })
+                                                .when(has_cookies, |s| {
+                                                    s.on_click(cx.listener(
+                                                        |this, _event: &gpui::ClickEvent, _window, cx| {
+                                                            this.active_tab = 4;
+                                                            cx.notify();
+                                                        },
+                                                    ))
+                                                })
+                                                .child(if has_cookies {
+                                                    format!("Cookies ({})", self.cookies.len())
+                                                } else {
+                                                    "Cookies".to_string()
+                                                })
+                                        })
+                                        .child({
+                                            // Only when the request has assertions or extract
+                                            // rules; dimmed otherwise.
+                                            let has_tests = !self.test_results.is_empty() || !self.extracted.is_empty();
+                                            let failed = self.test_results.iter().filter(|r| !r.passed).count();
+                                            let extract_failed = self.extracted.iter().any(|r| r.value.is_err());
+                                            a11y::focusable(
+                                                crate::ui::segment_pill(theme, self.active_tab == 5)
+                                                    .id("resp-tab-tests"),
+                                                theme,
+                                            )
+                                                .when(!has_tests, |s| s.opacity(0.5))
+                                                .when(has_tests && self.active_tab != 5, |s| {
+                                                    s.hover(|s| s.text_color(theme.foreground))
+                                                })
+                                                .when(has_tests, |s| {
+                                                    s.on_click(cx.listener(
+                                                        |this, _event: &gpui::ClickEvent, _window, cx| {
+                                                            this.active_tab = 5;
+                                                            cx.notify();
+                                                        },
+                                                    ))
+                                                })
+                                                .when(failed > 0 || extract_failed, |s| s.text_color(theme.danger))
+                                                .child(if !self.test_results.is_empty() {
+                                                    let passed = self.test_results.len() - failed;
+                                                    format!("Tests ({passed}/{})", self.test_results.len())
+                                                } else {
+                                                    "Tests".to_string()
+                                                })
+                                        })
+                                        .child(
+                                            a11y::focusable(
+                                                crate::ui::segment_pill(theme, self.active_tab == 2)
+                                                    .id("resp-tab-request"),
+                                                theme,
+                                            )
+                                                .when(self.active_tab != 2, |s| {
+                                                    s.hover(|s| s.text_color(theme.foreground))
+                                                })
+                                                .on_click(cx.listener(
+                                                    |this, _event: &gpui::ClickEvent, _window, cx| {
+                                                        this.active_tab = 2;
+                                                        cx.notify();
+                                                    },
+                                                ))
+                                                .child("Request"),
+                                        )
+                                        .child({
+                                            // Only when the request was sent verbose; dimmed otherwise.
+                                            let has_console = self.sent_request.as_ref().is_some_and(|r| r.verbose);
+                                            a11y::focusable(
+                                                crate::ui::segment_pill(theme, self.active_tab == 6)
+                                                    .id("resp-tab-console"),
+                                                theme,
+                                            )
+                                                .when(!has_console, |s| s.opacity(0.5))
+                                                .when(has_console && self.active_tab != 6, |s| {
+                                                    s.hover(|s| s.text_color(theme.foreground))
+                                                })
+                                                .when(has_console, |s| {
+                                                    s.on_click(cx.listener(
+                                                        |this, _event: &gpui::ClickEvent, _window, cx| {
+                                                            this.active_tab = 6;
+                                                            cx.notify();
+                                                        },
+                                                    ))
+                                                })
+                                                .when(!has_console, |s| {
+                                                    s.tooltip(|window, cx| {
+                                                        Tooltip::new("Turn on Verbose in the send menu to keep a wire log")
+                                                            .build(window, cx)
+                                                    })
+                                                })
+                                                .child("Console")
+                                        }),
                                 )
                                 .child(
-                                    crate::ui::segment_pill(theme, self.active_tab == 1)
-                                        .id("resp-tab-headers")
-                                        .when(self.active_tab != 1, |s| {
-                                            s.hover(|s| s.text_color(theme.foreground))
-                                        })
-                                        .on_click(cx.listener(
-                                            |this, _event: &gpui::ClickEvent, _window, cx| {
-                                                this.active_tab = 1;
-                                                cx.notify();
+                                    h_flex()
+                                        .gap_1()
+                                        .items_center()
+                                        .when(
+                                            self.active_tab == 0 && self.response.as_ref().is_some_and(|r| r.is_text),
+                                            |this| {
+                                                this.child(self.render_body_view_toggle(cx)).child(
+                                                    Button::new("resp-wrap")
+                                                        .ghost()
+                                                        .xsmall()
+                                                        .label("Wrap")
+                                                        .selected(self.wrap_body)
+                                                        .tooltip("Wrap long lines of the body")
+                                                        .on_click(cx.listener(Self::toggle_wrap)),
+                                                )
                                             },
-                                        ))
-                                        .child("Headers"),
+                                        )
+                                        .when(self.active_tab == 6, |this| {
+                                            this.child(
+                                                Button::new("console-mask")
+                                                    .ghost()
+                                                    .xsmall()
+                                                    .label("Mask secrets")
+                                                    .selected(self.mask_secrets)
+                                                    .tooltip("Hide Authorization, Cookie and other secret header values")
+                                                    .on_click(cx.listener(Self::toggle_mask_secrets)),
+                                            )
+                                            .child(
+                                                Button::new("copy-console")
+                                                    .ghost()
+                                                    .xsmall()
+                                                    .label("Copy")
+                                                    .tooltip("Copy the transcript as shown")
+                                                    .on_click(cx.listener(Self::copy_console)),
+                                            )
+                                        })
+                                        .when(self.active_tab != 6, |this| this.child(self.render_response_actions(cx))),
                                 ),
                         )
                         .when(self.active_tab == 0, |this| {
                             let resp_is_text = self.response.as_ref().is_none_or(|r| r.is_text);
-                            if resp_is_text {
+                            if let Some(downloaded) = self.response.as_ref().and_then(|r| r.downloaded.clone()) {
+                                // Download mode: the body went to a file.
+                                this.child(
+                                    v_flex()
+                                        .flex_1()
+                                        .w_full()
+                                        .min_h_0()
+                                        .items_center()
+                                        .justify_center()
+                                        .gap_2()
+                                        .child(
+                                            div()
+                                                .text_sm()
+                                                .text_color(theme.foreground)
+                                                .child(format!(
+                                                    "Saved {} to file",
+                                                    crate::format::format_size(downloaded.bytes as usize)
+                                                )),
+                                        )
+                                        .child(div().text_xs().text_color(theme.muted_foreground).child(downloaded.path)),
+                                )
+                            } else if resp_is_text {
                                 let is_error = self
                                     .response
                                     .as_ref()
@@ -425,13 +2409,30 @@ impl Render for ResponseViewer {
                                         .border_1()
                                         .border_color(theme.border)
                                         .bg(theme.popover)
-                                        .child(
-                                            Input::new(&self.body_display)
-                                                .disabled(is_error)
-                                                .rounded(theme.radius_lg)
-                                                .w_full()
-                                                .h_full(),
-                                        ),
+                                        .when(self.json_value.is_some(), |this| {
+                                            this.child(self.render_filter_bar(cx))
+                                        })
+                                        .when_some(
+                                            self.json_index
+                                                .clone()
+                                                .filter(|_| {
+                                                    self.shown_view() == BodyView::Pretty
+                                                        && self.filtered_body.is_none()
+                                                        && self.large_body.is_none()
+                                                }),
+                                            |this, index| this.child(self.render_json_breadcrumbs(&index, cx)),
+                                        )
+                                        .map(|this| match preview_el.take() {
+                                            Some(preview) => this.child(preview),
+                                            None if self.large_body.is_some() => this.child(self.render_large_body(cx)),
+                                            None => this.child(
+                                                Input::new(&self.body_display)
+                                                    .disabled(is_error)
+                                                    .rounded(theme.radius_lg)
+                                                    .w_full()
+                                                    .h_full(),
+                                            ),
+                                        }),
                                 )
                             } else {
                                 // Binary response: don't decode to lossy text — show info + Save.
@@ -489,15 +2490,48 @@ impl Render for ResponseViewer {
                                                     crate::format::format_size(len)
                                                 )),
                                         )
+                                        .when_some(self.hex_preview.clone(), |this, dump| {
+                                            this.child(
+                                                div()
+                                                    .id("hex-preview")
+                                                    .max_h(px(280.))
+                                                    .overflow_y_scroll()
+                                                    .p_2()
+                                                    .rounded(theme.radius)
+                                                    .border_1()
+                                                    .border_color(theme.border)
+                                                    .bg(theme.popover)
+                                                    .font_family(theme.mono_font_family.clone())
+                                                    .text_xs()
+                                                    .text_color(theme.muted_foreground)
+                                                    .whitespace_nowrap()
+                                                    .children(dump.lines().map(|line| div().child(line.to_string()))),
+                                            )
+                                        })
                                         .child(
                                             Button::new("save-binary")
                                                 .primary()
                                                 .label("Save to file…")
-                                                .on_click(cx.listener(Self::save_binary)),
+                                                .on_click(cx.listener(Self::save_body)),
                                         ),
                                 )
                             }
                         })
+                        .when(self.active_tab == 3 && self.json_tree.is_some(), |this| {
+                            this.child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .flex_1()
+                                    .min_h_0()
+                                    .w_full()
+                                    .rounded(theme.radius_lg)
+                                    .border_1()
+                                    .border_color(theme.border)
+                                    .bg(theme.popover)
+                                    .child(self.render_json_tree(cx)),
+                            )
+                        })
                         .when(self.active_tab == 1, |this| {
                             this.child(
                                 div()
@@ -510,10 +2544,49 @@ impl Render for ResponseViewer {
                                     .children(headers_el)
                                     .vertical_scrollbar(&self.headers_scroll_handle),
                             )
+                        })
+                        .when(self.active_tab == 4 && !self.cookies.is_empty(), |this| {
+                            this.child(self.render_cookies(cx))
+                        })
+                        .when(
+                            self.active_tab == 5 && (!self.test_results.is_empty() || !self.extracted.is_empty()),
+                            |this| this.child(self.render_test_results(cx)),
+                        )
+                        .when(self.active_tab == 2, |this| {
+                            this.child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .flex_1()
+                                    .min_h_0()
+                                    .w_full()
+                                    .overflow_hidden()
+                                    .children(sent_el)
+                                    .vertical_scrollbar(&self.sent_scroll_handle),
+                            )
+                        })
+                        .when(self.active_tab == 6, |this| {
+                            this.child(
+                                div()
+                                    .flex_1()
+                                    .min_h_0()
+                                    .w_full()
+                                    .rounded(theme.radius_lg)
+                                    .border_1()
+                                    .border_color(theme.border)
+                                    .bg(theme.popover)
+                                    .child(
+                                        Input::new(&self.console_display)
+                                            .disabled(true)
+                                            .rounded(theme.radius_lg)
+                                            .w_full()
+                                            .h_full(),
+                                    ),
+                            )
                         }),
                 )
             })
-            .when(self.response.is_none(), |this| {
+            .when(self.response.is_none() && self.repeat_report.is_none(), |this| {
                 this.child(
                     div()
                         .flex_1()
@@ -535,11 +2608,44 @@ impl Render for ResponseViewer {
 mod tests {
     // NOT `use super::*`: that would pull in `gpui::*`, whose `test` attribute
     // macro shadows the standard `#[test]`.
-    use super::headers_to_html;
+    use super::{headers_to_html, sent_request_to_html};
+    use crate::types::{BodyType, RawHeaderValue, RequestData};
     use super::headers_to_text;
     use super::image_format_for_content_type;
+    use super::{size_summary, timing_breakdown, timing_phases};
+    use super::{body_to_copy, is_html, BodyKind, BodyView};
     use gpui::ImageFormat;
 
+    #[test]
+    fn body_kind_follows_the_body_and_content_type() {
+        // JSON served as text/plain is still JSON.
+        assert_eq!(BodyKind::detect(Some("text/plain"), "{}", true), BodyKind::Json);
+        assert_eq!(BodyKind::detect(Some("text/html; charset=utf-8"), "<p>hi</p>", false), BodyKind::Html);
+        assert_eq!(BodyKind::detect(Some("application/soap+xml"), "<a/>", false), BodyKind::Xml);
+        assert_eq!(BodyKind::detect(None, "<?xml version=\"1.0\"?><a/>", false), BodyKind::Xml);
+        assert_eq!(BodyKind::detect(Some("text/csv"), "a,b", false), BodyKind::Text);
+        assert_eq!(BodyKind::Xml.highlighter(), "html");
+        assert_eq!(BodyKind::Text.highlighter(), "text");
+    }
+
+    #[test]
+    fn copy_takes_the_reformatted_body_only_in_pretty() {
+        let raw = "{\"a\":1}";
+        let pretty = "{\n    \"a\": 1\n}";
+        assert_eq!(body_to_copy(BodyView::Pretty, raw, Some(pretty)), pretty);
+        assert_eq!(body_to_copy(BodyView::Raw, raw, Some(pretty)), raw);
+        assert_eq!(body_to_copy(BodyView::Preview, raw, Some(pretty)), raw);
+        assert_eq!(body_to_copy(BodyView::Pretty, raw, None), raw);
+    }
+
+    #[test]
+    fn preview_is_for_html_only() {
+        assert!(is_html(Some("text/html")));
+        assert!(is_html(Some("application/xhtml+xml; charset=utf-8")));
+        assert!(!is_html(Some("application/json")));
+        assert!(!is_html(None));
+    }
+
     #[test]
     fn maps_supported_image_content_types() {
         assert_eq!(image_format_for_content_type("image/png"), Some(ImageFormat::Png));
@@ -618,7 +2724,7 @@ mod tests {
     #[test]
     fn one_bold_key_paragraph_per_header() {
         assert_eq!(
-            headers_to_html(&hs(&[("content-type", "text/html"), ("server", "nginx")])),
+            headers_to_html(&hs(&[("content-type", "text/html"), ("server", "nginx")]), &[]),
             "<p><b>content-type:</b> text/html</p><p><b>server:</b> nginx</p>"
         );
     }
@@ -627,7 +2733,7 @@ mod tests {
     fn escapes_ampersands_in_values() {
         // Every URL-bearing header carries these; unescaped they vanish as markup.
         assert_eq!(
-            headers_to_html(&hs(&[("location", "/a?x=1&y=2")])),
+            headers_to_html(&hs(&[("location", "/a?x=1&y=2")]), &[]),
             "<p><b>location:</b> /a?x=1&amp;y=2</p>"
         );
     }
@@ -636,7 +2742,7 @@ mod tests {
     fn escapes_angle_brackets_in_values() {
         // Link and Report-To headers really do contain these.
         assert_eq!(
-            headers_to_html(&hs(&[("link", "<https://a/b>; rel=preload")])),
+            headers_to_html(&hs(&[("link", "<https://a/b>; rel=preload")]), &[]),
             "<p><b>link:</b> &lt;https://a/b&gt;; rel=preload</p>"
         );
     }
@@ -644,13 +2750,126 @@ mod tests {
     #[test]
     fn escapes_keys_too() {
         assert_eq!(
-            headers_to_html(&hs(&[("x<evil>", "v")])),
+            headers_to_html(&hs(&[("x<evil>", "v")]), &[]),
             "<p><b>x&lt;evil&gt;:</b> v</p>"
         );
     }
 
+    #[test]
+    fn flags_values_that_were_not_ascii() {
+        let raw = [RawHeaderValue { index: 1, bytes: b"caf\xe9".to_vec() }];
+        assert_eq!(
+            headers_to_html(&hs(&[("server", "nginx"), ("x-name", "café")]), &raw),
+            "<p><b>server:</b> nginx</p><p><b>x-name:</b> ⚠ café</p>"
+        );
+    }
+
+    #[test]
+    fn sent_request_leads_with_method_and_resolved_url() {
+        let request = RequestData {
+            headers: hs(&[("Authorization", "Bearer t")]),
+            body: BodyType::None,
+            ..RequestData::new(crate::types::HttpMethod::POST, "https://api.test/a?x=1&y=2".to_string())
+        };
+        assert_eq!(
+            sent_request_to_html(&request),
+            "<p><b>POST</b> https://api.test/a?x=1&amp;y=2</p><p><b>Authorization:</b> Bearer t</p>"
+        );
+    }
+
+    #[test]
+    fn timing_breakdown_marks_the_simulated_parts() {
+        let mut response = crate::types::ResponseData {
+            status: Some(200),
+            duration_ms: 1400,
+            headers: vec![],
+            body: vec![],
+            is_text: true,
+            route: Default::default(),
+            raw_header_values: vec![],
+            network_profile: crate::types::NetworkProfile::ThreeG,
+            timing: Some(crate::types::ResponseTiming { latency_ms: 100, wait_ms: 300, download_ms: 1000, ..Default::default() }),
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
+        };
+        assert_eq!(
+            timing_breakdown(&response).unwrap(),
+            "Added latency: 100 ms (simulated)\nSend + wait: 300 ms (throttled to 250 kbps)\n\
+             Download: 1 s (throttled to 750 kbps)"
+        );
+
+        response.network_profile = Default::default();
+        response.timing = Some(crate::types::ResponseTiming { latency_ms: 0, wait_ms: 20, download_ms: 5, ..Default::default() });
+        assert_eq!(timing_breakdown(&response).unwrap(), "Send + wait: 20 ms\nDownload: 5 ms");
+
+        response.timing = None;
+        assert_eq!(timing_breakdown(&response), None);
+    }
+
+    #[test]
+    fn size_shows_what_came_over_the_wire() {
+        let mut response = crate::types::ResponseData {
+            status: Some(200),
+            duration_ms: 10,
+            headers: vec![("Content-Encoding".to_string(), "gzip".to_string())],
+            body: vec![b'x'; 5000],
+            is_text: true,
+            route: Default::default(),
+            raw_header_values: vec![],
+            network_profile: Default::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
+        };
+        assert_eq!(size_summary(&response), ("Size: 4.88 KB".to_string(), None));
+
+        response.wire_size = Some(crate::types::WireSize { header_bytes: 120, body_bytes: 904 });
+        let (label, tooltip) = size_summary(&response);
+        assert_eq!(label, "Size: 4.88 KB (transferred 1 KB)");
+        assert_eq!(
+            tooltip.unwrap(),
+            "Body: 4.88 KB\nBody as sent: 904 B (gzip)\nHeaders: 120 B\nTransferred: 1 KB"
+        );
+
+        // Not compressed: the body line already says it.
+        response.wire_size = Some(crate::types::WireSize { header_bytes: 120, body_bytes: 5000 });
+        assert_eq!(size_summary(&response).1.unwrap(), "Body: 4.88 KB\nHeaders: 120 B\nTransferred: 5 KB");
+    }
+
+    #[test]
+    fn measured_dns_and_connect_come_out_of_the_wait() {
+        let timing = crate::types::ResponseTiming {
+            wait_ms: 120,
+            dns_ms: Some(15),
+            connect_ms: Some(40),
+            download_ms: 8,
+            ..Default::default()
+        };
+        let phases: Vec<(&str, u64)> = timing_phases(&timing).iter().map(|p| (p.label, p.ms)).collect();
+        assert_eq!(
+            phases,
+            [("DNS lookup", 15), ("Connect (TCP + TLS)", 40), ("Waiting (TTFB)", 65), ("Download", 8)]
+        );
+
+        // A reused connection: nothing to look up or open.
+        let reused = crate::types::ResponseTiming { wait_ms: 30, dns_ms: Some(0), connect_ms: Some(0), ..timing };
+        assert_eq!(timing_phases(&reused)[2].ms, 30);
+    }
+
     #[test]
     fn empty_headers_give_empty_html() {
-        assert_eq!(headers_to_html(&[]), "");
+        assert_eq!(headers_to_html(&[], &[]), "");
     }
 }
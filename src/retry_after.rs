@@ -0,0 +1,132 @@
+//! Delayed retry of rate-limited (429) requests: parsing `Retry-After`
+//! (delta-seconds or an HTTP-date, RFC 9110 §10.2.3) and the per-tab retry
+//! state the countdown chip and timers work from.
+
+use chrono::{DateTime, Utc};
+use std::time::{Duration, Instant};
+
+use crate::types::ResponseData;
+
+/// Consecutive 429s after which automatic retries stop (the chip still
+/// offers a manual retry).
+pub const MAX_AUTO_RETRIES: u32 = 3;
+
+/// Longer waits than this are not counted down — the server is telling us to
+/// come back much later, not to wait at the keyboard.
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(10 * 60);
+
+/// Parse a `Retry-After` value relative to `now`. Dates in the past mean
+/// "retry now" (zero delay).
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    // IMF-fixdate ("Sun, 06 Nov 1994 08:49:37 GMT") is RFC 2822 compatible.
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// How long to wait before retrying `response`: only for a 429 carrying a
+/// usable `Retry-After` within `MAX_RETRY_DELAY`.
+pub fn retry_delay(response: &ResponseData, now: DateTime<Utc>) -> Option<Duration> {
+    if response.status != Some(429) {
+        return None;
+    }
+    response
+        .headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("retry-after"))
+        .and_then(|(_, v)| parse_retry_after(v, now))
+        .filter(|d| *d <= MAX_RETRY_DELAY)
+}
+
+/// A retry a tab is waiting on. Lives in the tab's state so the countdown
+/// keeps running while another tab is shown.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PendingRetry {
+    /// What `Retry-After` asked for.
+    pub delay: Duration,
+    /// When the re-send is due; `None` while it is only offered (auto-retry
+    /// off, or the auto-retry budget is spent).
+    pub deadline: Option<Instant>,
+}
+
+impl PendingRetry {
+    /// Time left on the countdown, or `None` when it isn't running.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.deadline.map(|d| d.saturating_duration_since(now))
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.deadline.is_some_and(|d| d <= now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn response(status: u16, retry_after: Option<&str>) -> ResponseData {
+        ResponseData {
+            status: Some(status),
+            duration_ms: 1,
+            headers: retry_after
+                .map(|v| vec![("Retry-After".to_string(), v.to_string())])
+                .unwrap_or_default(),
+            body: vec![],
+            is_text: true,
+            route: Default::default(),
+            raw_header_values: vec![],
+            network_profile: Default::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
+        }
+    }
+
+    #[test]
+    fn parses_delta_seconds_and_http_dates() {
+        assert_eq!(parse_retry_after(" 120 ", now()), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Sun, 01 Mar 2026 12:00:30 GMT", now()),
+            Some(Duration::from_secs(30))
+        );
+        // A date in the past means "now".
+        assert_eq!(parse_retry_after("Sun, 01 Mar 2026 11:00:00 GMT", now()), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now()), None);
+        assert_eq!(parse_retry_after("-5", now()), None);
+    }
+
+    #[test]
+    fn only_429_with_a_reasonable_retry_after_schedules_a_retry() {
+        assert_eq!(retry_delay(&response(429, Some("3")), now()), Some(Duration::from_secs(3)));
+        assert_eq!(retry_delay(&response(503, Some("3")), now()), None);
+        assert_eq!(retry_delay(&response(429, None), now()), None);
+        assert_eq!(retry_delay(&response(429, Some("86400")), now()), None);
+    }
+
+    #[test]
+    fn pending_retry_counts_down_to_due() {
+        let start = Instant::now();
+        let offered = PendingRetry { delay: Duration::from_secs(5), deadline: None };
+        assert_eq!(offered.remaining(start), None);
+        assert!(!offered.is_due(start));
+
+        let running = PendingRetry { deadline: Some(start + Duration::from_secs(5)), ..offered };
+        assert_eq!(running.remaining(start), Some(Duration::from_secs(5)));
+        assert!(!running.is_due(start));
+        assert!(running.is_due(start + Duration::from_secs(6)));
+        assert_eq!(running.remaining(start + Duration::from_secs(6)), Some(Duration::ZERO));
+    }
+}
@@ -0,0 +1,453 @@
+//! The "Run collection" dialog body: pick an environment, an iteration count
+//! and a delay, then send every request of the collection in order. Results
+//! fill a table as they come in; a row opens into the request's details.
+//! Owned by the dialog `PoopmanApp` opens from the collections tree, so
+//! closing the dialog also stops the run.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{
+    button::*, checkbox::Checkbox, input::*,
+    menu::{DropdownMenu as _, PopupMenuItem},
+    h_flex, v_flex, ActiveTheme as _, Disableable as _, Sizable as _,
+};
+
+use crate::collection_run::{self, RunEntry, RunReport, RunStep, MAX_ITERATIONS};
+use crate::postman_import::CollectionFolder;
+use crate::types::{Environment, Preset};
+
+/// Height of the results table (dialog height is content-driven).
+const RESULTS_HEIGHT: f32 = 400.;
+
+pub struct RunnerPanel {
+    collection: String,
+    steps: Vec<RunStep>,
+    environments: Vec<Environment>,
+    environment_id: Option<i64>,
+    globals: HashMap<String, String>,
+    presets: Vec<Preset>,
+    iterations_input: Entity<InputState>,
+    delay_input: Entity<InputState>,
+    stop_on_failure: bool,
+    report: Option<RunReport>,
+    /// When the current run started; `None` once it is over.
+    running_since: Option<Instant>,
+    /// Results opened into their details, by index.
+    expanded: HashSet<usize>,
+    results_scroll_handle: ScrollHandle,
+    /// The run; dropping it stops sending.
+    _run: Option<Task<anyhow::Result<()>>>,
+}
+
+impl RunnerPanel {
+    pub fn new(
+        folder: &CollectionFolder,
+        environments: Vec<Environment>,
+        environment_id: Option<i64>,
+        globals: HashMap<String, String>,
+        presets: Vec<Preset>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let iterations_input = cx.new(|cx| InputState::new(window, cx).default_value("1"));
+        let delay_input = cx.new(|cx| InputState::new(window, cx).default_value("0"));
+        Self {
+            collection: folder.name.clone(),
+            steps: collection_run::steps(folder),
+            environments,
+            environment_id,
+            globals,
+            presets,
+            iterations_input,
+            delay_input,
+            stop_on_failure: false,
+            report: None,
+            running_since: None,
+            expanded: HashSet::new(),
+            results_scroll_handle: ScrollHandle::new(),
+            _run: None,
+        }
+    }
+
+    fn iterations(&self, cx: &App) -> usize {
+        self.iterations_input.read(cx).value().trim().parse().unwrap_or(1).clamp(1, MAX_ITERATIONS)
+    }
+
+    /// Send every request, `iterations` times over, one after the other.
+    /// Values the requests extract (see `extract`) feed the ones after them
+    /// for the rest of the run; the environment itself is not changed.
+    fn start(&mut self, cx: &mut Context<Self>) {
+        if self.running_since.is_some() || self.steps.is_empty() {
+            return;
+        }
+        let iterations = self.iterations(cx);
+        let delay_ms: u64 = self.delay_input.read(cx).value().trim().parse().unwrap_or(0);
+        let environment = self.environment_id.and_then(|id| self.environments.iter().find(|e| e.id == id));
+        let env_vars = environment.map(|e| crate::variables::enabled_map(&e.variables)).unwrap_or_default();
+        let mut vars = crate::variables::scoped(&env_vars, &self.globals);
+        self.report = Some(RunReport {
+            collection: self.collection.clone(),
+            environment: environment.map(|e| e.name.clone()),
+            iterations,
+            delay_ms,
+            ..Default::default()
+        });
+        self.expanded.clear();
+        self.running_since = Some(Instant::now());
+        let (steps, presets, stop_on_failure) = (self.steps.clone(), self.presets.clone(), self.stop_on_failure);
+        log::info!("Collection run started: {} ({} requests × {})", self.collection, steps.len(), iterations);
+
+        self._run = Some(cx.spawn(async move |this, cx| {
+            let mut stopped = false;
+            'run: for iteration in 1..=iterations {
+                for (index, step) in steps.iter().enumerate() {
+                    if delay_ms > 0 && (iteration, index) != (1, 0) {
+                        cx.background_executor().timer(Duration::from_millis(delay_ms)).await;
+                    }
+                    let entry = match collection_run::prepare(&step.request, &presets, &vars) {
+                        Ok(sent) => {
+                            let response = crate::request_editor::resend(sent.clone()).await;
+                            for result in crate::extract::run(&sent.extract, &response) {
+                                if let Ok(value) = result.value {
+                                    vars.insert(result.rule.variable, value);
+                                }
+                            }
+                            RunEntry::new(iteration, step, &sent, &response)
+                        }
+                        Err(error) => RunEntry::not_sent(iteration, step, error),
+                    };
+                    let failed = !entry.passed();
+                    this.update(cx, |this, cx| {
+                        if let Some(report) = &mut this.report {
+                            report.entries.push(entry);
+                        }
+                        cx.notify();
+                    })?;
+                    if failed && stop_on_failure {
+                        stopped = true;
+                        break 'run;
+                    }
+                }
+            }
+            this.update(cx, |this, cx| this.finish(false, stopped, cx))
+        }));
+        cx.notify();
+    }
+
+    fn cancel(&mut self, cx: &mut Context<Self>) {
+        if self.running_since.is_some() {
+            self._run = None;
+            self.finish(true, false, cx);
+        }
+    }
+
+    fn finish(&mut self, canceled: bool, stopped_on_failure: bool, cx: &mut Context<Self>) {
+        let Some(since) = self.running_since.take() else {
+            return;
+        };
+        if let Some(report) = &mut self.report {
+            report.total_ms = since.elapsed().as_millis() as u64;
+            report.canceled = canceled;
+            report.stopped_on_failure = stopped_on_failure;
+            let summary = report.summary();
+            log::info!(
+                "Collection run finished: {} passed, {} failed in {}ms{}",
+                summary.passed,
+                summary.failed,
+                report.total_ms,
+                if canceled { " (canceled)" } else { "" }
+            );
+        }
+        cx.notify();
+    }
+
+    fn toggle_entry(&mut self, index: usize, cx: &mut Context<Self>) {
+        if !self.expanded.remove(&index) {
+            self.expanded.insert(index);
+        }
+        cx.notify();
+    }
+
+    /// "Export JSON": the whole report, results and details, to a file.
+    fn export(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(report) = &self.report else {
+            return;
+        };
+        let json = report.to_json();
+        let dir = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| std::path::PathBuf::from("."));
+        let stem: String = self
+            .collection
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '-' })
+            .collect();
+        let rx = cx.prompt_for_new_path(&dir, Some(&format!("{stem}.run.json")));
+        cx.spawn_in(window, async move |_this, _cx| {
+            if let Ok(Ok(Some(path))) = rx.await
+                && let Err(e) = std::fs::write(&path, json)
+            {
+                log::error!("Failed to export run results to {:?}: {}", path, e);
+            }
+        })
+        .detach();
+    }
+
+    fn render_settings(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let running = self.running_since.is_some();
+        let panel = cx.entity();
+        let current = self.environment_id;
+        let environments: Vec<(i64, String)> = self.environments.iter().map(|e| (e.id, e.name.clone())).collect();
+        let environment_label = current
+            .and_then(|id| environments.iter().find(|(e, _)| *e == id))
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| "No environment".to_string());
+        let label = |text: &'static str| div().text_sm().text_color(theme.muted_foreground).child(text);
+
+        h_flex()
+            .gap_3()
+            .items_center()
+            .flex_wrap()
+            .child(
+                Button::new("runner-environment")
+                    .outline()
+                    .small()
+                    .label(environment_label)
+                    .disabled(running)
+                    .dropdown_menu(move |menu, _window, _cx| {
+                        let choices = std::iter::once((None, "No environment".to_string()))
+                            .chain(environments.iter().map(|(id, name)| (Some(*id), name.clone())));
+                        choices.fold(menu, |menu, (id, name)| {
+                            let panel = panel.clone();
+                            menu.item(PopupMenuItem::new(name).checked(id == current).on_click(move |_, _window, cx| {
+                                panel.update(cx, |panel, cx| {
+                                    panel.environment_id = id;
+                                    cx.notify();
+                                });
+                            }))
+                        })
+                    }),
+            )
+            .child(label("Iterations"))
+            .child(div().w(px(64.)).child(Input::new(&self.iterations_input).small().disabled(running)))
+            .child(label("Delay (ms)"))
+            .child(div().w(px(80.)).child(Input::new(&self.delay_input).small().disabled(running)))
+            .child(
+                Checkbox::new("runner-stop-on-failure")
+                    .label("Stop on failure")
+                    .checked(self.stop_on_failure)
+                    .disabled(running)
+                    .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                        this.stop_on_failure = *checked;
+                        cx.notify();
+                    })),
+            )
+            .child(div().flex_1())
+            .child(if running {
+                Button::new("runner-cancel")
+                    .small()
+                    .danger()
+                    .label("Cancel")
+                    .on_click(cx.listener(|this, _, _window, cx| this.cancel(cx)))
+            } else {
+                Button::new("runner-run")
+                    .small()
+                    .primary()
+                    .label("Run")
+                    .disabled(self.steps.is_empty())
+                    .on_click(cx.listener(|this, _, _window, cx| this.start(cx)))
+            })
+    }
+
+    fn render_summary(&self, report: &RunReport, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let summary = report.summary();
+        let ms = crate::format::format_duration_ms;
+        let state = if self.running_since.is_some() {
+            format!("Running {}/{}", report.entries.len(), self.steps.len() * report.iterations)
+        } else if report.canceled {
+            "Canceled".to_string()
+        } else if report.stopped_on_failure {
+            "Stopped on failure".to_string()
+        } else {
+            format!("Done in {}", ms(report.total_ms))
+        };
+        let color = if summary.failed == 0 { theme.success } else { theme.danger };
+        let tests = summary.tests_passed + summary.tests_failed;
+
+        h_flex()
+            .gap_3()
+            .items_center()
+            .text_sm()
+            .child(
+                div()
+                    .px_2p5()
+                    .py_0p5()
+                    .rounded(theme.radius)
+                    .text_xs()
+                    .font_weight(FontWeight::BOLD)
+                    .bg(color.opacity(0.12))
+                    .text_color(color)
+                    .child(format!("{} passed · {} failed", summary.passed, summary.failed)),
+            )
+            .when(tests > 0, |this| this.child(format!("Tests {}/{}", summary.tests_passed, tests)))
+            .child(div().text_color(theme.muted_foreground).child(state))
+            .child(div().flex_1())
+            .child(
+                Button::new("runner-export")
+                    .ghost()
+                    .xsmall()
+                    .label("Export JSON…")
+                    .disabled(self.running_since.is_some() || report.entries.is_empty())
+                    .on_click(cx.listener(|this, _, window, cx| this.export(window, cx))),
+            )
+    }
+
+    fn render_entry(&self, index: usize, entry: &RunEntry, iterations: usize, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let passed = entry.passed();
+        let color = if passed { theme.success } else { theme.danger };
+        let open = self.expanded.contains(&index);
+        let status = entry.status.map(|s| s.to_string()).unwrap_or_else(|| "ERROR".to_string());
+        let tests_passed = entry.tests.iter().filter(|t| t.passed).count();
+        let mono = theme.mono_font_family.clone();
+
+        v_flex()
+            .border_b_1()
+            .border_color(theme.border.opacity(0.5))
+            .child(
+                h_flex()
+                    .id(("run-entry", index))
+                    .gap_3()
+                    .px_2()
+                    .py_1()
+                    .text_sm()
+                    .cursor_pointer()
+                    .hover(|s| s.bg(theme.list_hover))
+                    .on_click(cx.listener(move |this, _, _window, cx| this.toggle_entry(index, cx)))
+                    .child(div().w(px(16.)).flex_shrink_0().text_color(color).child(if passed { "✓" } else { "✗" }))
+                    .when(iterations > 1, |this| {
+                        this.child(
+                            div()
+                                .w(px(40.))
+                                .flex_shrink_0()
+                                .text_color(theme.muted_foreground)
+                                .child(format!("#{}", entry.iteration)),
+                        )
+                    })
+                    .child(div().w(px(56.)).flex_shrink_0().text_xs().font_weight(FontWeight::SEMIBOLD).child(entry.method.clone()))
+                    .child(
+                        h_flex()
+                            .flex_1()
+                            .min_w_0()
+                            .gap_1()
+                            .when(!entry.folder.is_empty(), |this| {
+                                this.child(
+                                    div()
+                                        .flex_shrink_0()
+                                        .text_color(theme.muted_foreground)
+                                        .child(format!("{} /", entry.folder)),
+                                )
+                            })
+                            .child(div().min_w_0().truncate().child(entry.name.clone())),
+                    )
+                    .child(div().w(px(56.)).flex_shrink_0().text_color(color).child(status))
+                    .child(div().w(px(72.)).flex_shrink_0().child(crate::format::format_duration_ms(entry.duration_ms)))
+                    .child(
+                        div()
+                            .w(px(72.))
+                            .flex_shrink_0()
+                            .text_color(theme.muted_foreground)
+                            .child(if entry.tests.is_empty() {
+                                String::new()
+                            } else {
+                                format!("Tests {}/{}", tests_passed, entry.tests.len())
+                            }),
+                    ),
+            )
+            .when(open, |this| {
+                this.child(
+                    v_flex()
+                        .gap_1()
+                        .pl(px(40.))
+                        .pr_2()
+                        .pb_2()
+                        .text_xs()
+                        .child(div().font_family(mono.clone()).truncate().child(entry.url.clone()))
+                        .when_some(entry.error.clone(), |this, error| this.child(div().text_color(theme.danger).child(error)))
+                        .children(entry.tests.iter().map(|test| {
+                            let color = if test.passed { theme.success } else { theme.danger };
+                            h_flex()
+                                .gap_2()
+                                .child(div().text_color(color).child(if test.passed { "✓" } else { "✗" }))
+                                .child(test.assertion.describe())
+                                .child(div().text_color(theme.muted_foreground).child(format!("({})", test.actual)))
+                        }))
+                        .when(!entry.response_headers.is_empty(), |this| {
+                            this.child(
+                                v_flex().font_family(mono.clone()).text_color(theme.muted_foreground).children(
+                                    entry.response_headers.iter().map(|(k, v)| div().truncate().child(format!("{k}: {v}"))),
+                                ),
+                            )
+                        })
+                        .when(!entry.body.is_empty(), |this| {
+                            this.child(
+                                div()
+                                    .max_h(px(160.))
+                                    .overflow_hidden()
+                                    .p_1()
+                                    .rounded(theme.radius)
+                                    .bg(theme.muted)
+                                    .font_family(mono.clone())
+                                    .whitespace_normal()
+                                    .child(entry.body.clone()),
+                            )
+                        }),
+                )
+            })
+    }
+}
+
+impl Render for RunnerPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let iterations = self.report.as_ref().map_or(1, |r| r.iterations);
+        v_flex()
+            .gap_3()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(theme.muted_foreground)
+                    .child(match self.steps.len() {
+                        0 => "This collection has no requests.".to_string(),
+                        1 => "1 request, sent in collection order.".to_string(),
+                        n => format!("{n} requests, sent in collection order."),
+                    }),
+            )
+            .child(self.render_settings(cx))
+            .when_some(self.report.as_ref(), |this, report| this.child(self.render_summary(report, cx)))
+            .child(
+                div()
+                    .id("runner-results")
+                    .h(px(RESULTS_HEIGHT))
+                    .w_full()
+                    .overflow_y_scroll()
+                    .track_scroll(&self.results_scroll_handle)
+                    .rounded(theme.radius_lg)
+                    .border_1()
+                    .border_color(theme.border)
+                    .children(self.report.iter().flat_map(|report| {
+                        report
+                            .entries
+                            .iter()
+                            .enumerate()
+                            .map(|(index, entry)| self.render_entry(index, entry, iterations, cx).into_any_element())
+                    })),
+            )
+    }
+}
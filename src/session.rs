@@ -0,0 +1,204 @@
+//! The open tabs, kept across restarts. Written as one JSON blob (the
+//! `session` app setting) whenever the active tab's state is saved and when
+//! the window closes; read back at startup. Responses are not kept — a
+//! restored tab shows its request only (a WebSocket tab, its URL, draft and
+//! the end of its log). Pure: the caller stores the text.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::request_tab::RequestTab;
+use crate::response_viewer::BodyView;
+use crate::types::{HeaderState, ParamState, RequestData, TabColor};
+use crate::ws_client::WsTabState;
+
+/// Bumped on incompatible changes; a session from a newer version is dropped.
+const SESSION_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Session {
+    version: u32,
+    active: usize,
+    tabs: Vec<SessionTab>,
+}
+
+/// What a tab keeps across a restart.
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionTab {
+    request: RequestData,
+    #[serde(default)]
+    params_state: Option<Vec<ParamState>>,
+    #[serde(default)]
+    headers_state: Option<Vec<HeaderState>>,
+    #[serde(default)]
+    history_id: Option<i64>,
+    #[serde(default)]
+    saved_request_id: Option<i64>,
+    #[serde(default)]
+    saved_name: Option<String>,
+    #[serde(default)]
+    color: Option<TabColor>,
+    #[serde(default)]
+    copy: bool,
+    #[serde(default)]
+    custom_title: Option<String>,
+    #[serde(default)]
+    pinned: bool,
+    #[serde(default)]
+    body_view: BodyView,
+    #[serde(default)]
+    auto_retry: bool,
+    #[serde(default)]
+    baseline: Option<RequestData>,
+    #[serde(default)]
+    dirty: bool,
+    #[serde(default)]
+    ws: Option<WsTabState>,
+}
+
+/// The session text for `tabs`, `active` being the selected one.
+pub fn to_json(tabs: &[RequestTab], active: usize) -> Result<String> {
+    let session = Session {
+        version: SESSION_VERSION,
+        active,
+        tabs: tabs
+            .iter()
+            .map(|tab| SessionTab {
+                request: tab.request.clone(),
+                params_state: tab.params_state.clone(),
+                headers_state: tab.headers_state.clone(),
+                history_id: tab.history_id,
+                saved_request_id: tab.saved_request_id,
+                saved_name: tab.saved_name.clone(),
+                color: tab.color,
+                copy: tab.copy,
+                custom_title: tab.custom_title.clone(),
+                pinned: tab.pinned,
+                body_view: tab.body_view,
+                auto_retry: tab.auto_retry,
+                baseline: tab.baseline.clone(),
+                dirty: tab.dirty,
+                ws: tab.ws.clone(),
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_string(&session)?)
+}
+
+/// The tabs in `json`, with ids from 0, and the index of the active one
+/// (clamped into range). An empty session is an error like a corrupt one:
+/// either way the caller starts with a single empty tab.
+pub fn restore(json: &str) -> Result<(Vec<RequestTab>, usize)> {
+    let session: Session = serde_json::from_str(json)?;
+    if session.version > SESSION_VERSION {
+        bail!(
+            "session version {} is newer than this app supports ({})",
+            session.version,
+            SESSION_VERSION
+        );
+    }
+    if session.tabs.is_empty() {
+        bail!("session has no tabs");
+    }
+    let active = session.active.min(session.tabs.len() - 1);
+    let tabs = session
+        .tabs
+        .into_iter()
+        .enumerate()
+        .map(|(id, saved)| {
+            let mut tab = RequestTab::new_empty(id);
+            tab.request = saved.request;
+            tab.params_state = saved.params_state;
+            tab.headers_state = saved.headers_state;
+            tab.history_id = saved.history_id;
+            tab.saved_request_id = saved.saved_request_id;
+            tab.saved_name = saved.saved_name;
+            tab.color = saved.color;
+            tab.copy = saved.copy;
+            tab.custom_title = saved.custom_title;
+            tab.pinned = saved.pinned;
+            tab.body_view = saved.body_view;
+            tab.auto_retry = saved.auto_retry;
+            tab.baseline = saved.baseline;
+            tab.dirty = saved.dirty;
+            tab.ws = saved.ws;
+            tab.update_title();
+            tab
+        })
+        .collect();
+    Ok((tabs, active))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{HeaderType, HttpMethod};
+
+    #[test]
+    fn tabs_round_trip_through_the_session() {
+        let mut users = RequestTab::new_empty(4);
+        users.request = RequestData::new(HttpMethod::GET, "https://api.test/users".to_string());
+        users.history_id = Some(12);
+        users.color = Some(TabColor::Teal);
+        users.pinned = true;
+        users.body_view = BodyView::Raw;
+        users.headers_state = Some(vec![HeaderState {
+            enabled: false,
+            key: "X-Trace".to_string(),
+            value: "1".to_string(),
+            header_type: HeaderType::Custom,
+            predefined: None,
+            description: Some("Request tracing".to_string()),
+        }]);
+        users.set_baseline(users.request.clone());
+        let mut saved = RequestTab::new_empty(9);
+        saved.request = RequestData::new(HttpMethod::POST, "https://api.test/orders".to_string());
+        saved.saved_request_id = Some(3);
+        saved.saved_name = Some("Create order".to_string());
+        saved.dirty = true;
+        saved.custom_title = Some("Order as guest".to_string());
+        let mut socket = RequestTab::new_websocket(2);
+        if let Some(ws) = &mut socket.ws {
+            ws.url = "wss://feed.test/live".to_string();
+            ws.draft = "{\"subscribe\": \"prices\"}".to_string();
+            ws.json = true;
+        }
+
+        let json = to_json(&[users, saved, socket], 1).unwrap();
+        let (tabs, active) = restore(&json).unwrap();
+        assert_eq!(active, 1);
+        assert_eq!(tabs.iter().map(|t| t.id).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(tabs[0].title, "GET users");
+        assert_eq!((tabs[0].history_id, tabs[0].color), (Some(12), Some(TabColor::Teal)));
+        assert!(tabs[0].baseline.is_some());
+        assert!(tabs[0].pinned && !tabs[1].pinned);
+        assert_eq!((tabs[0].body_view, tabs[1].body_view), (BodyView::Raw, BodyView::Pretty));
+        let headers = tabs[0].headers_state.as_ref().unwrap();
+        assert_eq!((headers[0].key.as_str(), headers[0].enabled), ("X-Trace", false));
+        assert_eq!(headers[0].description.as_deref(), Some("Request tracing"));
+        assert_eq!(tabs[1].title, "Order as guest");
+        assert!(tabs[1].dirty);
+        assert!(tabs[0].ws.is_none());
+        let ws = tabs[2].ws.as_ref().unwrap();
+        assert_eq!((ws.url.as_str(), ws.json), ("wss://feed.test/live", true));
+        assert_eq!(tabs[2].title, "feed.test/live");
+    }
+
+    #[test]
+    fn corrupt_or_empty_sessions_are_refused() {
+        assert!(restore("").is_err());
+        assert!(restore("{\"tabs\": 3}").is_err());
+        let empty = to_json(&[], 0).unwrap();
+        assert!(restore(&empty).is_err());
+        let newer = to_json(&[RequestTab::new_empty(0)], 0)
+            .unwrap()
+            .replace("\"version\":1", "\"version\":2");
+        assert!(restore(&newer).unwrap_err().to_string().contains("newer"));
+    }
+
+    #[test]
+    fn active_index_is_clamped() {
+        let json = to_json(&[RequestTab::new_empty(0), RequestTab::new_empty(1)], 7).unwrap();
+        assert_eq!(restore(&json).unwrap().1, 1);
+    }
+}
@@ -0,0 +1,242 @@
+//! The start panel a blank tab shows where the response would be: the most
+//! recent distinct URLs, pinned history rows and recently closed tabs, each
+//! one click away from the editor.
+//!
+//! The lists are queried on the database thread and filled in when they
+//! arrive, so opening a tab never waits on the database. PoopmanApp decides
+//! when the panel is visible (no URL typed, no response) and keeps the
+//! recently-closed stack, which is in memory only.
+
+use gpui::*;
+use gpui_component::{h_flex, scroll::ScrollableElement as _, v_flex, ActiveTheme as _};
+use std::sync::Arc;
+
+use crate::a11y::{self, tab_order};
+use crate::db::Database;
+use crate::request_tab::RequestTab;
+use crate::types::{HistoryItem, HttpMethod};
+
+/// How many distinct recent URLs (and pinned rows) the panel lists.
+pub const RECENT_URLS: usize = 10;
+/// How many closed tabs are remembered for reopening.
+pub const CLOSED_TABS_KEPT: usize = 10;
+
+/// Remember a closed tab for the start panel, newest first. Blank tabs are
+/// not worth reopening and are dropped.
+pub fn remember_closed(stack: &mut Vec<RequestTab>, tab: RequestTab) {
+    if tab.is_blank() {
+        return;
+    }
+    stack.insert(0, tab);
+    stack.truncate(CLOSED_TABS_KEPT);
+}
+
+/// What was clicked on the start panel.
+#[derive(Clone)]
+pub enum StartPick {
+    History(Box<HistoryItem>),
+    /// Index into PoopmanApp's recently-closed stack.
+    Closed(usize),
+}
+
+/// Event emitted when an entry of the start panel is clicked.
+#[derive(Clone)]
+pub struct StartItemPicked {
+    pub pick: StartPick,
+}
+
+pub struct StartPanel {
+    recent: Vec<HistoryItem>,
+    pinned: Vec<HistoryItem>,
+    /// Method and title of each recently closed tab, newest first.
+    closed: Vec<(HttpMethod, String)>,
+    /// True until the first lists arrive from the database.
+    loading: bool,
+    scroll_handle: ScrollHandle,
+}
+
+impl StartPanel {
+    pub fn new() -> Self {
+        Self { recent: vec![], pinned: vec![], closed: vec![], loading: true, scroll_handle: ScrollHandle::new() }
+    }
+
+    /// Re-query the recent and pinned lists in the background; the old
+    /// lists stay up until the new ones arrive.
+    pub fn refresh(&mut self, db: Arc<Database>, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            let (recent, pinned) = cx
+                .background_executor()
+                .spawn(async move {
+                    let recent = db.load_recent_distinct_urls(RECENT_URLS).unwrap_or_else(|e| {
+                        log::error!("Failed to load recent URLs: {}", e);
+                        vec![]
+                    });
+                    let pinned = db.load_pinned_history(RECENT_URLS).unwrap_or_else(|e| {
+                        log::error!("Failed to load pinned history: {}", e);
+                        vec![]
+                    });
+                    (recent, pinned)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.recent = recent;
+                this.pinned = pinned;
+                this.loading = false;
+                cx.notify();
+            })
+        })
+        .detach();
+    }
+
+    pub fn set_closed(&mut self, closed: &[RequestTab], cx: &mut Context<Self>) {
+        self.closed = closed.iter().map(|tab| (tab.request.method.clone(), tab.title.clone())).collect();
+        cx.notify();
+    }
+
+    fn render_section(
+        &self,
+        title: &'static str,
+        rows: Vec<(SharedString, HttpMethod, String, StartPick)>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let theme = cx.theme();
+        v_flex()
+            .gap_0p5()
+            .child(
+                div()
+                    .px_2()
+                    .pb_1()
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(theme.muted_foreground)
+                    .child(title),
+            )
+            .children(rows.into_iter().map(|(id, method, label, pick)| {
+                a11y::focusable(h_flex().id(id), theme)
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .rounded(theme.radius)
+                    .cursor_pointer()
+                    .hover(|s| s.bg(theme.list_hover))
+                    .on_click(cx.listener(move |_, _, _window, cx| {
+                        cx.emit(StartItemPicked { pick: pick.clone() });
+                    }))
+                    .child(
+                        div()
+                            .flex_shrink_0()
+                            .w(px(52.))
+                            .text_xs()
+                            .font_weight(FontWeight::BOLD)
+                            .text_color(crate::theme::method_color(&method, theme))
+                            .child(crate::theme::method_badge(&method)),
+                    )
+                    .child(
+                        div()
+                            .min_w_0()
+                            .text_sm()
+                            .text_color(theme.foreground)
+                            .overflow_x_hidden()
+                            .whitespace_nowrap()
+                            .text_ellipsis()
+                            .child(label),
+                    )
+            }))
+    }
+}
+
+impl EventEmitter<StartItemPicked> for StartPanel {}
+
+impl Render for StartPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let history_rows = |prefix: &'static str, items: &[HistoryItem]| {
+            items
+                .iter()
+                .map(|item| {
+                    (
+                        SharedString::from(format!("start-{prefix}-{}", item.id)),
+                        item.request.method.clone(),
+                        item.request.url.clone(),
+                        StartPick::History(Box::new(item.clone())),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let recent = history_rows("recent", &self.recent);
+        let pinned = history_rows("pinned", &self.pinned);
+        let closed: Vec<_> = self
+            .closed
+            .iter()
+            .enumerate()
+            .map(|(index, (method, title))| {
+                (SharedString::from(format!("start-closed-{index}")), method.clone(), title.clone(), StartPick::Closed(index))
+            })
+            .collect();
+        let empty = recent.is_empty() && pinned.is_empty() && closed.is_empty();
+
+        let body = v_flex()
+            .id("start-panel")
+            .flex_1()
+            .min_h_0()
+            .gap_4()
+            .p_4()
+            .track_scroll(&self.scroll_handle)
+            .overflow_y_scroll();
+        let body = if empty {
+            body.child(
+                div()
+                    .text_sm()
+                    .text_color(theme.muted_foreground)
+                    .child(if self.loading {
+                        "Loading recent requests…"
+                    } else {
+                        "Type a URL to start. Recent and pinned requests will show up here."
+                    }),
+            )
+        } else {
+            let mut body = body;
+            if !pinned.is_empty() {
+                body = body.child(self.render_section("Pinned", pinned, cx));
+            }
+            if !recent.is_empty() {
+                body = body.child(self.render_section("Recent URLs", recent, cx));
+            }
+            if !closed.is_empty() {
+                body = body.child(self.render_section("Recently closed", closed, cx));
+            }
+            body
+        };
+        a11y::tab_group(div().flex().flex_col().size_full().overflow_hidden(), tab_order::RESPONSE)
+            .child(body)
+            .vertical_scrollbar(&self.scroll_handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // NOT `use super::*`: that would pull in `gpui::*`, whose `test` attribute
+    // macro shadows the standard `#[test]`.
+    use super::{remember_closed, CLOSED_TABS_KEPT};
+    use crate::request_tab::RequestTab;
+
+    fn tab(id: usize, url: &str) -> RequestTab {
+        let mut tab = RequestTab::new_empty(id);
+        tab.request.url = url.to_string();
+        tab
+    }
+
+    #[test]
+    fn closed_stack_is_newest_first_capped_and_skips_blank_tabs() {
+        let mut stack = Vec::new();
+        remember_closed(&mut stack, RequestTab::new_empty(0));
+        assert!(stack.is_empty());
+
+        for id in 1..=CLOSED_TABS_KEPT + 2 {
+            remember_closed(&mut stack, tab(id, &format!("https://x/{id}")));
+        }
+        assert_eq!(stack.len(), CLOSED_TABS_KEPT);
+        assert_eq!(stack[0].id, CLOSED_TABS_KEPT + 2);
+        assert_eq!(stack.last().unwrap().id, 3);
+    }
+}
@@ -1,10 +1,20 @@
 use gpui::*;
 use gpui::px;
 use gpui::prelude::FluentBuilder as _;
-use gpui_component::{h_flex, scroll::ScrollableElement as _, ActiveTheme as _};
+use gpui_component::{
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    menu::{ContextMenuExt as _, DropdownMenu as _, PopupMenuItem},
+    scroll::ScrollableElement as _,
+    tooltip::Tooltip,
+    ActiveTheme as _, IconName, Sizable as _,
+};
 
+use crate::a11y::{self, tab_order};
 use crate::request_tab::RequestTab;
-use crate::theme::method_color;
+use crate::theme::{method_color, tab_color};
+use crate::types::TabColor;
 
 /// Event emitted when a tab is clicked
 #[derive(Clone)]
@@ -16,17 +26,115 @@ pub struct TabClicked {
 #[derive(Clone)]
 pub struct NewTabClicked;
 
+/// Event emitted when "New WebSocket tab" is picked from the tab strip's
+/// context menu.
+#[derive(Clone)]
+pub struct NewWebSocketTabClicked;
+
 /// Event emitted when a tab close button is clicked
 #[derive(Clone)]
 pub struct TabCloseClicked {
     pub tab_index: usize,
 }
 
+/// Event emitted when "Compare with …" is picked from the tab strip's context
+/// menu: diff the active tab against `tab_index`.
+#[derive(Clone)]
+pub struct TabCompareClicked {
+    pub tab_index: usize,
+}
+
+/// Event emitted when a color label is picked (or cleared) from a tab's
+/// context menu.
+#[derive(Clone)]
+pub struct TabColorPicked {
+    pub tab_index: usize,
+    pub color: Option<TabColor>,
+}
+
+/// Event emitted when "Duplicate tab" is picked from a tab's context menu.
+#[derive(Clone)]
+pub struct TabDuplicateClicked {
+    pub tab_index: usize,
+}
+
+/// Event emitted when "Close others" is picked from a tab's context menu.
+#[derive(Clone)]
+pub struct TabsCloseOthers {
+    pub keep_index: usize,
+}
+
+/// Event emitted when "Close tabs to the right" is picked from a tab's
+/// context menu: close every tab after `from_index`.
+#[derive(Clone)]
+pub struct TabsCloseRight {
+    pub from_index: usize,
+}
+
+/// Event emitted when "Pin tab" / "Unpin tab" is picked from a tab's context
+/// menu.
+#[derive(Clone)]
+pub struct TabPinToggled {
+    pub tab_index: usize,
+    pub pinned: bool,
+}
+
+/// Event emitted when a tab's title is edited in place (`Some`) or reset to
+/// the generated one (`None`).
+#[derive(Clone)]
+pub struct TabRenamed {
+    pub tab_index: usize,
+    pub title: Option<String>,
+}
+
+/// Event emitted when a tab is dragged onto another: the tab at `from` is to
+/// take the place of the one at `to`.
+#[derive(Clone)]
+pub struct TabMoved {
+    pub from: usize,
+    pub to: usize,
+}
+
+/// A tab being dragged along the strip; renders as the drag preview.
+#[derive(Clone)]
+struct DraggedTab {
+    index: usize,
+    label: SharedString,
+}
+
+impl Render for DraggedTab {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        div()
+            .px_3()
+            .py_1()
+            .rounded(theme.radius)
+            .bg(theme.background)
+            .border_1()
+            .border_color(theme.border)
+            .shadow_sm()
+            .text_sm()
+            .text_color(theme.foreground)
+            .child(self.label.clone())
+    }
+}
+
+/// The title input shown in place of a tab's title while it is renamed.
+struct TitleEditor {
+    tab_index: usize,
+    input: Entity<InputState>,
+    _subscription: Subscription,
+}
+
 /// Tab bar component for managing multiple request tabs
 pub struct TabBar {
     tabs: Vec<RequestTab>,
     active_tab_index: usize,
     scroll_handle: ScrollHandle,
+    /// Tab under the last right-click, read when the context menu is built
+    /// (`None` for a click on the empty part of the strip).
+    menu_tab: Option<usize>,
+    title_editor: Option<TitleEditor>,
 }
 
 impl TabBar {
@@ -35,6 +143,8 @@ impl TabBar {
             tabs: vec![],
             active_tab_index: 0,
             scroll_handle: ScrollHandle::new(),
+            menu_tab: None,
+            title_editor: None,
         }
     }
 
@@ -52,8 +162,43 @@ impl TabBar {
         }
     }
 
-    fn on_tab_click(&mut self, tab_index: usize, _event: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+    fn on_tab_click(&mut self, tab_index: usize, event: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         cx.emit(TabClicked { tab_index });
+        if event.click_count() == 2 {
+            self.start_rename(tab_index, window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Swap the tab's title for an input. Enter or clicking away keeps the
+    /// new title, Escape drops it.
+    fn start_rename(&mut self, tab_index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tab) = self.tabs.get(tab_index) else {
+            return;
+        };
+        let title = tab.custom_title.clone().unwrap_or_else(|| tab.title.clone());
+        let input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx);
+            input.set_value(&title, window, cx);
+            input
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+        let subscription = cx.subscribe_in(&input, window, |this, _, event: &InputEvent, _window, cx| {
+            if matches!(event, InputEvent::PressEnter { .. } | InputEvent::Blur) {
+                this.commit_rename(cx);
+            }
+        });
+        self.title_editor = Some(TitleEditor { tab_index, input, _subscription: subscription });
+        cx.notify();
+    }
+
+    /// A blank title goes back to the generated one.
+    fn commit_rename(&mut self, cx: &mut Context<Self>) {
+        let Some(editor) = self.title_editor.take() else {
+            return;
+        };
+        let title = editor.input.read(cx).value().trim().to_string();
+        cx.emit(TabRenamed { tab_index: editor.tab_index, title: (!title.is_empty()).then_some(title) });
         cx.notify();
     }
 
@@ -70,12 +215,36 @@ impl TabBar {
 
 impl EventEmitter<TabClicked> for TabBar {}
 impl EventEmitter<NewTabClicked> for TabBar {}
+impl EventEmitter<NewWebSocketTabClicked> for TabBar {}
 impl EventEmitter<TabCloseClicked> for TabBar {}
+impl EventEmitter<TabCompareClicked> for TabBar {}
+impl EventEmitter<TabColorPicked> for TabBar {}
+impl EventEmitter<TabDuplicateClicked> for TabBar {}
+impl EventEmitter<TabMoved> for TabBar {}
+impl EventEmitter<TabRenamed> for TabBar {}
+impl EventEmitter<TabPinToggled> for TabBar {}
+impl EventEmitter<TabsCloseOthers> for TabBar {}
+impl EventEmitter<TabsCloseRight> for TabBar {}
 
 impl Render for TabBar {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let theme = cx.theme();
         let active_index = self.active_tab_index;
+        let bar = cx.entity();
+        let others: Vec<(usize, String)> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .filter(|(index, tab)| *index != active_index && tab.ws.is_none())
+            .map(|(index, tab)| (index, format!("{} {}", tab.badge(), tab.title)))
+            .collect();
+        let all_tabs: Vec<String> = self
+            .tabs
+            .iter()
+            .map(|tab| format!("{} {}", tab.badge(), tab.title))
+            .collect();
+        let tab_list = cx.entity();
+        let title_editor = self.title_editor.as_ref().map(|editor| (editor.tab_index, editor.input.clone()));
 
         h_flex()
             .gap_1()
@@ -93,7 +262,7 @@ impl Render for TabBar {
                 // content width once the row fills, at which point the strip overflows
                 // and scrolls internally and "+" ends up pinned at the right edge.
                 // flex_1 here would hold "+" against the right edge even with two tabs.
-                div()
+                a11y::tab_group(div(), tab_order::TAB_BAR)
                     .min_w_0()
                     .child(
                         h_flex()
@@ -106,24 +275,53 @@ impl Render for TabBar {
                             .children(self.tabs.iter().enumerate().map(|(index, tab)| {
                                 let is_active = index == active_index;
                                 let tab_index = index;
-                                let method = tab.request.method.as_str();
+                                let method = tab.badge();
 
-                                let verb_color = method_color(tab.request.method, theme);
+                                let verb_color = match tab.ws {
+                                    Some(_) => theme.info,
+                                    None => method_color(&tab.request.method, theme),
+                                };
+                                // Always draw the underline so labelled and plain tabs
+                                // keep the same height.
+                                let underline = tab.color.map(tab_color).unwrap_or(gpui::transparent_black());
+                                let close_name = a11y::close_tab(&method, &tab.title);
+                                let label: SharedString = format!("{} {}", method, tab.title).into();
+                                let dragged = DraggedTab { index, label: label.clone() };
 
-                                h_flex()
-                                    .id(("tab", tab.id))
+                                a11y::focusable(h_flex().id(("tab", tab.id)), theme)
                                     .gap_1p5()
                                     .items_center()
                                     .flex_shrink_0()
-                                    .px_3()
+                                    .map(|this| if tab.pinned { this.px_2() } else { this.px_3() })
+                                    // Pinned tabs show only the method; the title moves here.
+                                    .when(tab.pinned, |this| {
+                                        this.tooltip(move |window, cx| Tooltip::new(label.clone()).build(window, cx))
+                                    })
                                     .py_1()
                                     .rounded(theme.radius)
+                                    .border_b_2()
+                                    .border_color(underline)
                                     .bg(if is_active { theme.muted } else { gpui::transparent_black() })
                                     .when(!is_active, |s| s.hover(|s| s.bg(theme.list_hover)))
                                     .cursor_pointer()
                                     .on_click(cx.listener(move |this, event, window, cx| {
                                         this.on_tab_click(tab_index, event, window, cx);
                                     }))
+                                    .on_mouse_down(
+                                        MouseButton::Right,
+                                        cx.listener(move |this, _, _, _| this.menu_tab = Some(tab_index)),
+                                    )
+                                    .on_drag(dragged, |dragged, _, _, cx| {
+                                        cx.stop_propagation();
+                                        cx.new(|_| dragged.clone())
+                                    })
+                                    .drag_over::<DraggedTab>(|style, _, _, cx| style.bg(cx.theme().drop_target))
+                                    .on_drop(cx.listener(move |_, dragged: &DraggedTab, _, cx| {
+                                        // Dropped back onto itself: nothing moves.
+                                        if dragged.index != tab_index {
+                                            cx.emit(TabMoved { from: dragged.index, to: tab_index });
+                                        }
+                                    }))
                                     .child(
                                         div()
                                             .text_xs()
@@ -131,21 +329,46 @@ impl Render for TabBar {
                                             .text_color(verb_color)
                                             .child(method)
                                     )
-                                    .child(
-                                        // Tab title
-                                        div()
-                                            .text_sm()
-                                            .text_color(if is_active { theme.foreground } else { theme.muted_foreground })
-                                            .max_w(px(150.))
-                                            .overflow_hidden()
-                                            .whitespace_nowrap()
-                                            .text_ellipsis()
-                                            .child(tab.title.clone())
-                                    )
-                                    .child(
+                                    .map(|this| match &title_editor {
+                                        Some((editing, input)) if *editing == tab_index => this.child(
+                                            div()
+                                                .w(px(150.))
+                                                .on_key_down(cx.listener(|this, event: &KeyDownEvent, _, cx| {
+                                                    if event.keystroke.key == "escape" {
+                                                        this.title_editor = None;
+                                                        cx.notify();
+                                                    }
+                                                }))
+                                                .child(Input::new(input).xsmall()),
+                                        ),
+                                        _ if tab.pinned => this,
+                                        _ => this.child(
+                                            // Tab title
+                                            div()
+                                                .text_sm()
+                                                .text_color(if is_active { theme.foreground } else { theme.muted_foreground })
+                                                .max_w(px(150.))
+                                                .overflow_hidden()
+                                                .whitespace_nowrap()
+                                                .text_ellipsis()
+                                                .child(tab.title.clone()),
+                                        ),
+                                    })
+                                    .when(tab.dirty, |this| {
+                                        // Edited since it was loaded, sent or saved
+                                        this.child(
+                                            div()
+                                                .id(("tab-dirty", tab.id))
+                                                .text_xs()
+                                                .text_color(theme.foreground)
+                                                .tooltip(|window, cx| Tooltip::new("Edited").build(window, cx))
+                                                .child("\u{25cf}"),
+                                        )
+                                    })
+                                    .when(!tab.pinned, |this| this.child(
                                         // Close button
-                                        div()
-                                            .id(("close-tab", tab.id))
+                                        a11y::focusable(div().id(("close-tab", tab.id)), theme)
+                                            .tooltip(move |window, cx| Tooltip::new(close_name.clone()).build(window, cx))
                                             .text_xs()
                                             .text_color(theme.muted_foreground)
                                             .cursor_pointer()
@@ -155,15 +378,104 @@ impl Render for TabBar {
                                                 this.on_close_tab_click(tab_index, event, window, cx);
                                             }))
                                             .child("×")
-                                    )
+                                    ))
                             })),
                     )
-                    .horizontal_scrollbar(&self.scroll_handle),
+                    .horizontal_scrollbar(&self.scroll_handle)
+                    // Runs before the tabs' own right-click handlers, which then
+                    // record the tab that was hit.
+                    .capture_any_mouse_down(cx.listener(|this, _, _, _| this.menu_tab = None))
+                    .context_menu(move |menu, _window, cx| {
+                        let new_ws = bar.clone();
+                        let mut menu = menu
+                            .item(PopupMenuItem::new("New WebSocket tab").on_click(move |_, _, cx| {
+                                new_ws.update(cx, |_, cx| cx.emit(NewWebSocketTabClicked));
+                            }))
+                            .separator();
+                        let target = bar.read(cx).menu_tab;
+                        if let Some(tab_index) = target {
+                            let count = bar.read(cx).tabs.len();
+                            let (close, others, right) = (bar.clone(), bar.clone(), bar.clone());
+                            let duplicate = bar.clone();
+                            let (rename, reset) = (bar.clone(), bar.clone());
+                            let renamed = bar.read(cx).tabs.get(tab_index).is_some_and(|t| t.custom_title.is_some());
+                            let pin = bar.clone();
+                            let pinned = bar.read(cx).tabs.get(tab_index).is_some_and(|t| t.pinned);
+                            menu = menu
+                                .item(PopupMenuItem::new("Close").on_click(move |_, _, cx| {
+                                    close.update(cx, |_, cx| cx.emit(TabCloseClicked { tab_index }));
+                                }))
+                                .item(PopupMenuItem::new("Close others").disabled(count <= 1).on_click(
+                                    move |_, _, cx| {
+                                        others.update(cx, |_, cx| cx.emit(TabsCloseOthers { keep_index: tab_index }));
+                                    },
+                                ))
+                                .item(
+                                    PopupMenuItem::new("Close tabs to the right")
+                                        .disabled(tab_index + 1 >= count)
+                                        .on_click(move |_, _, cx| {
+                                            right.update(cx, |_, cx| cx.emit(TabsCloseRight { from_index: tab_index }));
+                                        }),
+                                )
+                                .separator()
+                                .item(PopupMenuItem::new(if pinned { "Unpin tab" } else { "Pin tab" }).on_click(
+                                    move |_, _, cx| {
+                                        pin.update(cx, |_, cx| cx.emit(TabPinToggled { tab_index, pinned: !pinned }));
+                                    },
+                                ))
+                                .item(PopupMenuItem::new("Rename").on_click(move |_, window, cx| {
+                                    rename.update(cx, |bar, cx| bar.start_rename(tab_index, window, cx));
+                                }))
+                                .item(PopupMenuItem::new("Reset title").disabled(!renamed).on_click(
+                                    move |_, _, cx| {
+                                        reset.update(cx, |_, cx| cx.emit(TabRenamed { tab_index, title: None }));
+                                    },
+                                ))
+                                .item(PopupMenuItem::new("Duplicate tab").on_click(move |_, _, cx| {
+                                    duplicate.update(cx, |_, cx| cx.emit(TabDuplicateClicked { tab_index }));
+                                }))
+                                .separator();
+                            let current = bar.read(cx).tabs.get(tab_index).and_then(|t| t.color);
+                            menu = menu.label("Color label");
+                            for color in TabColor::all() {
+                                let bar = bar.clone();
+                                menu = menu.item(
+                                    PopupMenuItem::new(color.label())
+                                        .checked(current == Some(color))
+                                        .on_click(move |_, _, cx| {
+                                            bar.update(cx, |_, cx| {
+                                                cx.emit(TabColorPicked { tab_index, color: Some(color) })
+                                            });
+                                        }),
+                                );
+                            }
+                            let bar = bar.clone();
+                            menu = menu
+                                .item(PopupMenuItem::new("No color").disabled(current.is_none()).on_click(
+                                    move |_, _, cx| {
+                                        bar.update(cx, |_, cx| cx.emit(TabColorPicked { tab_index, color: None }));
+                                    },
+                                ))
+                                .separator();
+                        }
+                        menu = menu.label("Compare current tab with");
+                        if others.is_empty() {
+                            return menu.item(PopupMenuItem::new("No other tabs open").disabled(true));
+                        }
+                        for (tab_index, label) in &others {
+                            let tab_index = *tab_index;
+                            let bar = bar.clone();
+                            menu = menu.item(PopupMenuItem::new(label.clone()).on_click(move |_, _, cx| {
+                                bar.update(cx, |_, cx| cx.emit(TabCompareClicked { tab_index }));
+                            }));
+                        }
+                        menu
+                    }),
             )
             .child(
                 // New tab button
-                div()
-                    .id("new-tab-button")
+                a11y::focusable(div().id("new-tab-button"), theme)
+                    .tooltip(|window, cx| Tooltip::new("New tab").build(window, cx))
                     .flex_shrink_0()
                     .px_2()
                     .py_1()
@@ -181,5 +493,28 @@ impl Render for TabBar {
                             .child("+")
                     )
             )
+            .child(
+                // Every open tab by title, including the ones scrolled out of view
+                Button::new("tab-list")
+                    .ghost()
+                    .xsmall()
+                    .icon(IconName::ChevronDown)
+                    .tooltip("All tabs")
+                    .flex_shrink_0()
+                    .dropdown_menu(move |menu, _window, _cx| {
+                        let mut menu = menu;
+                        for (tab_index, label) in all_tabs.iter().enumerate() {
+                            let bar = tab_list.clone();
+                            menu = menu.item(
+                                PopupMenuItem::new(label.clone())
+                                    .checked(tab_index == active_index)
+                                    .on_click(move |_, _, cx| {
+                                        bar.update(cx, |_, cx| cx.emit(TabClicked { tab_index }));
+                                    }),
+                            );
+                        }
+                        menu
+                    }),
+            )
     }
 }
@@ -0,0 +1,121 @@
+//! Line diff engine: the longest common subsequence of two texts' lines,
+//! reported as kept / removed / added lines. Shared by anything that compares
+//! bodies (request comparison today).
+
+/// One line of a diff, in display order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    /// Only in the left / old text.
+    Removed(String),
+    /// Only in the right / new text.
+    Added(String),
+}
+
+impl DiffLine {
+    pub fn is_change(&self) -> bool {
+        !matches!(self, DiffLine::Same(_))
+    }
+}
+
+/// Above this many lines (after trimming the common prefix / suffix) on both
+/// sides, the quadratic LCS table is skipped and the middle is reported as
+/// removed-then-added — still correct, just not minimal.
+const MAX_LCS_LINES: usize = 2000;
+
+/// Diff `old` against `new` line by line. Equal texts yield only `Same` lines.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut out: Vec<DiffLine> = a[..prefix].iter().map(|l| DiffLine::Same(l.to_string())).collect();
+    if a_mid.len() > MAX_LCS_LINES && b_mid.len() > MAX_LCS_LINES {
+        out.extend(a_mid.iter().map(|l| DiffLine::Removed(l.to_string())));
+        out.extend(b_mid.iter().map(|l| DiffLine::Added(l.to_string())));
+    } else {
+        lcs_diff(a_mid, b_mid, &mut out);
+    }
+    out.extend(a[a.len() - suffix..].iter().map(|l| DiffLine::Same(l.to_string())));
+    out
+}
+
+fn lcs_diff(a: &[&str], b: &[&str], out: &mut Vec<DiffLine>) {
+    // lcs[i][j] = LCS length of a[i..] and b[j..].
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            out.push(DiffLine::Same(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(DiffLine::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            out.push(DiffLine::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    out.extend(b[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn same(s: &str) -> DiffLine {
+        DiffLine::Same(s.into())
+    }
+    fn removed(s: &str) -> DiffLine {
+        DiffLine::Removed(s.into())
+    }
+    fn added(s: &str) -> DiffLine {
+        DiffLine::Added(s.into())
+    }
+
+    #[test]
+    fn equal_texts_have_no_changes() {
+        let diff = diff_lines("a\nb", "a\nb");
+        assert_eq!(diff, vec![same("a"), same("b")]);
+        assert!(!diff.iter().any(DiffLine::is_change));
+        assert!(diff_lines("", "").is_empty());
+    }
+
+    #[test]
+    fn changed_middle_line_is_removed_then_added() {
+        assert_eq!(
+            diff_lines("{\n  \"a\": 1\n}", "{\n  \"a\": 2\n}"),
+            vec![same("{"), removed("  \"a\": 1"), added("  \"a\": 2"), same("}")]
+        );
+    }
+
+    #[test]
+    fn insertions_and_deletions_keep_the_common_lines() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "a\nc\nd\ne"),
+            vec![same("a"), removed("b"), same("c"), same("d"), added("e")]
+        );
+        assert_eq!(diff_lines("", "x"), vec![added("x")]);
+        assert_eq!(diff_lines("x", ""), vec![removed("x")]);
+    }
+}
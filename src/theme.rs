@@ -4,7 +4,7 @@
 use gpui::{px, App, Hsla};
 use gpui_component::{scroll::ScrollbarShow, Theme, ThemeMode};
 
-use crate::types::HttpMethod;
+use crate::types::{HttpMethod, TabColor};
 
 // ===== Palette (warm paper light), as 0xRRGGBB =====
 const BACKGROUND: u32 = 0xFAF9F5; // main canvas
@@ -47,15 +47,41 @@ fn c(hex: u32) -> Hsla {
 }
 
 /// Semantic color for an HTTP method label (used by tab bar + history).
-pub fn method_color(method: HttpMethod, theme: &Theme) -> Hsla {
+/// Custom methods get the neutral one.
+pub fn method_color(method: &HttpMethod, theme: &Theme) -> Hsla {
     match method {
         HttpMethod::GET => theme.success,
         HttpMethod::POST | HttpMethod::PUT | HttpMethod::PATCH => theme.warning,
         HttpMethod::DELETE => theme.danger,
-        HttpMethod::HEAD | HttpMethod::OPTIONS => theme.muted_foreground,
+        HttpMethod::HEAD | HttpMethod::OPTIONS | HttpMethod::Custom(_) => theme.muted_foreground,
     }
 }
 
+/// A method as it fits the narrow badge column of the history, collections
+/// and start lists: a custom one longer than DELETE is cut short.
+pub fn method_badge(method: &HttpMethod) -> String {
+    let name = method.as_str();
+    if name.chars().count() <= 6 {
+        return name.to_string();
+    }
+    format!("{}…", name.chars().take(5).collect::<String>())
+}
+
+/// Underline / tint color for a tab color label. Muted to sit on the warm
+/// paper background.
+pub fn tab_color(color: TabColor) -> Hsla {
+    c(match color {
+        TabColor::Red => 0xC0503F,
+        TabColor::Orange => 0xD9773A,
+        TabColor::Yellow => 0xD1A83B,
+        TabColor::Green => 0x4F8A5B,
+        TabColor::Teal => 0x3E8C87,
+        TabColor::Blue => 0x4A74B5,
+        TabColor::Purple => 0x8A5FB0,
+        TabColor::Pink => 0xC65D8F,
+    })
+}
+
 /// Apply the warm-light theme to the global Theme. Call once after
 /// `gpui_component::init(cx)`.
 pub fn apply_theme(cx: &mut App) {
@@ -0,0 +1,156 @@
+//! Bandwidth shaping for simulated network profiles (`NetworkProfile`).
+//!
+//! The real connection is as fast as it is; throttling only holds bytes back.
+//! Uploads are fed to reqwest in small chunks with a pause before each one,
+//! downloads are read from the body stream and paused after each chunk, so
+//! the average rate settles at the profile's limit. The added latency is a
+//! single sleep before the request goes out.
+
+use std::io::Write as _;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt as _;
+
+use crate::content_encoding::{Decoder, Encoding};
+use crate::types::NetworkProfile;
+
+/// The custom profile typed into the "Network Profile" dialog: whole kbps
+/// (empty or 0 = no rate limit) and whole milliseconds of added latency
+/// (empty = none). `None` when either field is not a number; both unset is
+/// simply unthrottled.
+pub fn parse_custom(kbps: &str, latency_ms: &str) -> Option<NetworkProfile> {
+    let number = |s: &str| match s.trim() {
+        "" => Some(0),
+        s => s.parse::<u32>().ok(),
+    };
+    match (number(kbps)?, number(latency_ms)?) {
+        (0, 0) => Some(NetworkProfile::Unthrottled),
+        (kbps, latency_ms) => Some(NetworkProfile::Custom { kbps, latency_ms }),
+    }
+}
+
+/// Bytes per second for a rate in kilobits per second.
+pub fn bytes_per_sec(kbps: u32) -> u64 {
+    kbps as u64 * 1000 / 8
+}
+
+/// Keeps a transfer on schedule: after `sent` bytes, no earlier than
+/// `sent / rate` seconds past the start.
+#[derive(Debug, Clone, Copy)]
+pub struct Pacer {
+    bytes_per_sec: u64,
+    start: Instant,
+    sent: u64,
+}
+
+impl Pacer {
+    pub fn new(kbps: u32, start: Instant) -> Self {
+        Self { bytes_per_sec: bytes_per_sec(kbps).max(1), start, sent: 0 }
+    }
+
+    /// Record `n` more bytes and return how long to wait, as of `now`, before
+    /// the transfer is allowed to continue.
+    pub fn delay_after(&mut self, n: usize, now: Instant) -> Duration {
+        self.sent += n as u64;
+        let due = self.start + Duration::from_secs_f64(self.sent as f64 / self.bytes_per_sec as f64);
+        due.saturating_duration_since(now)
+    }
+
+    /// Chunk size that makes one chunk take about 100 ms at this rate.
+    pub fn chunk_size(&self) -> usize {
+        (self.bytes_per_sec / 10).clamp(64, 64 * 1024) as usize
+    }
+}
+
+/// A request body that trickles `bytes` out at `kbps`. The caller sets the
+/// Content-Length itself; a streamed body is otherwise sent chunked.
+pub fn paced_body(bytes: Vec<u8>, kbps: u32) -> reqwest::Body {
+    let pacer = Pacer::new(kbps, Instant::now());
+    let chunk = pacer.chunk_size();
+    let chunks: Vec<Vec<u8>> = bytes.chunks(chunk).map(<[u8]>::to_vec).collect();
+    let stream = futures::stream::iter(chunks).scan(pacer, |pacer, chunk| {
+        // Wait for the chunk's slot before handing it over, so the first
+        // bytes don't all leave at once.
+        let delay = pacer.delay_after(chunk.len(), Instant::now());
+        async move {
+            tokio::time::sleep(delay).await;
+            Some(Ok::<_, std::io::Error>(chunk))
+        }
+    });
+    reqwest::Body::wrap_stream(stream)
+}
+
+/// A response body as `read_body` got it.
+#[derive(Debug)]
+pub struct ReadBody {
+    /// Decoded, and cut to the limit.
+    pub body: Vec<u8>,
+    /// Set when reading stopped early: the decoded bytes received by then.
+    pub truncated_after: Option<u64>,
+    /// Bytes that came over the wire, before decoding.
+    pub transferred: u64,
+}
+
+/// Read the response body chunk by chunk, decoding `encoding`, at no more
+/// than `kbps` when it is non-zero (counted on the wire), and stop once the
+/// decoded body is past `limit` bytes (0 = no limit).
+pub async fn read_body(
+    response: reqwest::Response,
+    kbps: u32,
+    limit: u64,
+    encoding: Option<Encoding>,
+) -> anyhow::Result<ReadBody> {
+    let mut pacer = (kbps > 0).then(|| Pacer::new(kbps, Instant::now()));
+    let mut decoder = Decoder::new(encoding, Vec::new());
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        decoder.write_all(&chunk)?;
+        if limit > 0 && decoder.get_ref().len() as u64 > limit {
+            // Dropping the stream closes the connection mid-body.
+            let mut body = decoder.get_ref().clone();
+            let received = body.len() as u64;
+            body.truncate(limit as usize);
+            return Ok(ReadBody { body, truncated_after: Some(received), transferred: decoder.encoded_len() });
+        }
+        if let Some(pacer) = pacer.as_mut() {
+            tokio::time::sleep(pacer.delay_after(chunk.len(), Instant::now())).await;
+        }
+    }
+    let transferred = decoder.encoded_len();
+    Ok(ReadBody { body: decoder.finish()?, truncated_after: None, transferred })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pacer_spreads_bytes_over_the_rate() {
+        let start = Instant::now();
+        // 80 kbps = 10 000 bytes/s.
+        let mut pacer = Pacer::new(80, start);
+        assert_eq!(pacer.chunk_size(), 1000);
+        assert_eq!(pacer.delay_after(1000, start), Duration::from_millis(100));
+        assert_eq!(pacer.delay_after(1000, start + Duration::from_millis(150)), Duration::from_millis(50));
+        // Behind schedule: no wait.
+        assert_eq!(pacer.delay_after(1000, start + Duration::from_secs(1)), Duration::ZERO);
+    }
+
+    #[test]
+    fn custom_profile_parses_blank_as_zero() {
+        assert_eq!(parse_custom("64", " 300 "), Some(NetworkProfile::Custom { kbps: 64, latency_ms: 300 }));
+        assert_eq!(parse_custom("", "2000"), Some(NetworkProfile::Custom { kbps: 0, latency_ms: 2000 }));
+        assert_eq!(parse_custom("0", ""), Some(NetworkProfile::Unthrottled));
+        assert_eq!(parse_custom("fast", "1"), None);
+        assert_eq!(parse_custom("-5", "1"), None);
+    }
+
+    #[test]
+    fn chunk_size_stays_within_bounds() {
+        let now = Instant::now();
+        assert_eq!(Pacer::new(1, now).chunk_size(), 64);
+        assert_eq!(Pacer::new(100_000, now).chunk_size(), 64 * 1024);
+        assert_eq!(bytes_per_sec(750), 93_750);
+    }
+}
@@ -2,19 +2,26 @@ use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::assertions::Assertion;
+use crate::extract::ExtractRule;
+
 /// Header type for distinguishing predefined vs custom headers
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HeaderType {
-    /// Mandatory header that cannot be disabled or deleted (e.g., Cache-Control)
-    Mandatory,
-    /// Predefined header that can be toggled but not deleted
+    /// One of the `PredefinedHeader` rows: toggled or deleted like any row,
+    /// brought back by "Restore default headers". Sessions saved while
+    /// Cache-Control could not be turned off call it `Mandatory`.
+    #[serde(alias = "Mandatory")]
     Predefined,
     /// Custom user-defined header that can be toggled and deleted
     Custom,
 }
 
+/// The User-Agent row's value in new tabs until the setting says otherwise.
+pub const DEFAULT_USER_AGENT: &str = concat!("Poopman/", env!("CARGO_PKG_VERSION"));
+
 /// Predefined header names
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PredefinedHeader {
     CacheControl,
     ContentType,
@@ -41,7 +48,7 @@ impl PredefinedHeader {
             PredefinedHeader::CacheControl => "no-cache",
             PredefinedHeader::ContentType => "application/json",
             PredefinedHeader::Accept => "*/*",
-            PredefinedHeader::UserAgent => "Poopman/1.0",
+            PredefinedHeader::UserAgent => DEFAULT_USER_AGENT,
             PredefinedHeader::Connection => "keep-alive",
             PredefinedHeader::ContentLength => "0",
         }
@@ -51,11 +58,14 @@ impl PredefinedHeader {
         matches!(self, PredefinedHeader::ContentLength)
     }
 
+    /// Shown for information only: the transport sets the header itself
+    /// (see `is_transport_header`).
+    pub fn is_transport_managed(&self) -> bool {
+        is_transport_header(self.name())
+    }
+
     pub fn header_type(&self) -> HeaderType {
-        match self {
-            PredefinedHeader::CacheControl => HeaderType::Mandatory,
-            _ => HeaderType::Predefined,
-        }
+        HeaderType::Predefined
     }
 
     pub fn all() -> Vec<Self> {
@@ -68,15 +78,44 @@ impl PredefinedHeader {
             PredefinedHeader::ContentLength,
         ]
     }
+
+    /// Where a `self` row is inserted among `rows` (the predefined header of
+    /// each, `None` for a custom row): after the predefined rows that come
+    /// before it in `all()`, ahead of the rest.
+    pub fn insert_position(&self, rows: &[Option<Self>]) -> usize {
+        let order = |p: &Self| Self::all().iter().position(|q| q == p);
+        rows.iter()
+            .position(|row| row.as_ref().is_none_or(|p| order(p) > order(self)))
+            .unwrap_or(rows.len())
+    }
+
+    /// The row each of a loaded request's headers goes to, in order: the
+    /// first header of a predefined name fills that row; other names, and
+    /// repeats of a predefined one, get custom rows (`None`). Repeated
+    /// headers are legal HTTP and go out as repeated lines.
+    pub fn assign(headers: &[(String, String)]) -> Vec<(Option<Self>, &str, &str)> {
+        let mut filled = Vec::new();
+        headers
+            .iter()
+            .map(|(key, value)| {
+                let predefined = Self::all()
+                    .into_iter()
+                    .find(|p| p.name().eq_ignore_ascii_case(key) && !filled.contains(p));
+                filled.extend(predefined);
+                (predefined, key.as_str(), value.as_str())
+            })
+            .collect()
+    }
 }
 
 /// HTTP methods supported by the API client.
 ///
 /// Variant names are all-caps on purpose: they match the wire format and are
 /// serialized by name into the history database, so renaming them would break
-/// previously saved requests.
+/// previously saved requests. A `Custom` method is serialized as its name
+/// too, so it reads back as one.
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HttpMethod {
     GET,
     POST,
@@ -85,10 +124,13 @@ pub enum HttpMethod {
     PATCH,
     HEAD,
     OPTIONS,
+    /// Any other method (PROPFIND, PURGE, LINK, …): a valid token (see
+    /// `is_method_token`), as typed, never one of the names above.
+    Custom(String),
 }
 
 impl HttpMethod {
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             HttpMethod::GET => "GET",
             HttpMethod::POST => "POST",
@@ -97,9 +139,16 @@ impl HttpMethod {
             HttpMethod::PATCH => "PATCH",
             HttpMethod::HEAD => "HEAD",
             HttpMethod::OPTIONS => "OPTIONS",
+            HttpMethod::Custom(name) => name,
         }
     }
 
+    /// GET and HEAD: a body on them is left off unless the request asks to
+    /// send it anyway (`RequestData::send_body_anyway`).
+    pub fn is_bodyless(&self) -> bool {
+        matches!(self, HttpMethod::GET | HttpMethod::HEAD)
+    }
+
     pub fn all() -> Vec<Self> {
         vec![
             HttpMethod::GET,
@@ -112,6 +161,8 @@ impl HttpMethod {
         ]
     }
 
+    /// The listed methods case-insensitively; any other valid token as a
+    /// `Custom` one, as written. `None` for an empty or invalid name.
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_uppercase().as_str() {
             "GET" => Some(HttpMethod::GET),
@@ -121,9 +172,28 @@ impl HttpMethod {
             "PATCH" => Some(HttpMethod::PATCH),
             "HEAD" => Some(HttpMethod::HEAD),
             "OPTIONS" => Some(HttpMethod::OPTIONS),
+            _ if is_method_token(s) => Some(HttpMethod::Custom(s.to_string())),
             _ => None,
         }
     }
+
+    /// The method `step` places away in `all()`, wrapping at both ends
+    /// (Alt+Up / Alt+Down in the URL bar). A custom method steps from GET.
+    pub fn cycled(&self, step: isize) -> Self {
+        let all = Self::all();
+        let index = all.iter().position(|m| m == self).unwrap_or(0) as isize;
+        all[(index + step).rem_euclid(all.len() as isize) as usize].clone()
+    }
+
+    /// First method in `all()` starting with `prefix`, case-insensitively
+    /// ("de" → DELETE, "p" → POST). Used for type-to-select in the dropdown.
+    pub fn from_prefix(prefix: &str) -> Option<Self> {
+        if prefix.is_empty() {
+            return None;
+        }
+        let prefix = prefix.to_ascii_uppercase();
+        Self::all().into_iter().find(|m| m.as_str().starts_with(&prefix))
+    }
 }
 
 impl fmt::Display for HttpMethod {
@@ -132,6 +202,25 @@ impl fmt::Display for HttpMethod {
     }
 }
 
+impl Serialize for HttpMethod {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for HttpMethod {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        HttpMethod::from_str(&name).ok_or_else(|| serde::de::Error::custom(format!("invalid HTTP method {name:?}")))
+    }
+}
+
+/// Whether `name` can be a method: one or more RFC 7230 token characters
+/// (letters, digits and ``!#$%&'*+-.^_`|~``).
+pub fn is_method_token(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
 /// Raw body subtype for syntax highlighting
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RawSubtype {
@@ -182,7 +271,7 @@ pub enum FormDataValue {
 }
 
 /// Form-data row
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FormDataRow {
     pub enabled: bool,
     pub key: String,
@@ -190,7 +279,7 @@ pub struct FormDataRow {
 }
 
 /// Request body type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BodyType {
     None,
     Raw {
@@ -209,6 +298,26 @@ impl Default for BodyType {
     }
 }
 
+impl BodyType {
+    /// Paths of enabled form-data file rows that don't point at a readable
+    /// file, in row order. Rows the sender skips (disabled, no key, empty
+    /// path) are ignored, so an empty result means the form can be encoded.
+    pub fn missing_files(&self) -> Vec<&str> {
+        let BodyType::FormData(rows) = self else {
+            return Vec::new();
+        };
+        rows.iter()
+            .filter(|row| row.enabled && !row.key.is_empty())
+            .filter_map(|row| match &row.value {
+                FormDataValue::File { path } if !path.is_empty() && !std::path::Path::new(path).is_file() => {
+                    Some(path.as_str())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
 /// Authentication scheme selected in the Auth sub-tab.
 ///
 /// Variant names are serialized by name into the history database, so renaming
@@ -220,6 +329,77 @@ pub enum AuthType {
     Bearer,
     Basic,
     ApiKey,
+    /// A token fetched from an OAuth 2.0 token endpoint (see `oauth2`).
+    OAuth2,
+}
+
+/// How OAuth 2.0 auth gets its token. Serialized by name, like `AuthType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum OAuth2Grant {
+    /// The client's own id and secret buy the token; no user involved.
+    #[default]
+    ClientCredentials,
+    /// The user signs in in the browser, which comes back to a loopback
+    /// `redirect_uri` with a code to trade for the token.
+    AuthorizationCode,
+}
+
+/// Where the client id and secret go in a token request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum OAuth2ClientAuth {
+    /// An `Authorization: Basic` header (`client_secret_basic`).
+    #[default]
+    BasicHeader,
+    /// `client_id` and `client_secret` form fields (`client_secret_post`).
+    Body,
+}
+
+/// Where the browser is sent back to unless the config says otherwise.
+pub const DEFAULT_OAUTH2_REDIRECT_URI: &str = "http://127.0.0.1:8765/callback";
+
+/// OAuth 2.0 settings. The token itself is never part of the config: it is
+/// fetched at send time (or with Get token) and kept in memory by `oauth2`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OAuth2Config {
+    pub grant: OAuth2Grant,
+    pub token_url: String,
+    /// The authorization endpoint; authorization code only.
+    pub auth_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Space-separated, as sent.
+    pub scopes: String,
+    /// Sent as `audience` when set, as Auth0 and others want.
+    pub audience: String,
+    /// Authorization code only; a loopback `http://` URL the app listens on.
+    pub redirect_uri: String,
+    pub client_auth: OAuth2ClientAuth,
+}
+
+impl Default for OAuth2Config {
+    fn default() -> Self {
+        Self {
+            grant: OAuth2Grant::default(),
+            token_url: String::new(),
+            auth_url: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            scopes: String::new(),
+            audience: String::new(),
+            redirect_uri: DEFAULT_OAUTH2_REDIRECT_URI.to_string(),
+            client_auth: OAuth2ClientAuth::default(),
+        }
+    }
+}
+
+/// Where API-Key auth puts the key. Serialized by name, like `AuthType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ApiKeyPlacement {
+    #[default]
+    Header,
+    /// Appended to the URL's query string at send time; never a Params row.
+    Query,
 }
 
 /// Config-based auth: a flat struct (all fields always present) so switching
@@ -232,9 +412,13 @@ pub struct AuthConfig {
     pub bearer_token: String,
     pub basic_username: String,
     pub basic_password: String,
-    /// Header name for API-Key auth, e.g. "X-API-Key".
+    /// Header (or query parameter) name for API-Key auth, e.g. "X-API-Key".
     pub api_key_name: String,
     pub api_key_value: String,
+    #[serde(default)]
+    pub api_key_in: ApiKeyPlacement,
+    #[serde(default)]
+    pub oauth2: OAuth2Config,
 }
 
 impl AuthConfig {
@@ -243,9 +427,13 @@ impl AuthConfig {
     /// Emitted only when the relevant field(s) are non-empty, so an in-progress
     /// edit never sends a placeholder header (e.g. a dangling `Bearer `). This
     /// differs slightly from Postman, which emits once a type is selected.
+    ///
+    /// OAuth 2.0 has none here: its token is not in the config, and the
+    /// sender adds it (`oauth2::with_token`, in
+    /// `request_editor::send_resolved` and `resend`).
     pub fn compute_header(&self) -> Option<(String, String)> {
         match self.auth_type {
-            AuthType::None => None,
+            AuthType::None | AuthType::OAuth2 => None,
             AuthType::Bearer => {
                 if self.bearer_token.is_empty() {
                     None
@@ -262,7 +450,7 @@ impl AuthConfig {
                 }
             }
             AuthType::ApiKey => {
-                if self.api_key_name.is_empty() {
+                if self.api_key_name.is_empty() || self.api_key_in != ApiKeyPlacement::Header {
                     None
                 } else {
                     Some((self.api_key_name.clone(), self.api_key_value.clone()))
@@ -270,6 +458,15 @@ impl AuthConfig {
             }
         }
     }
+
+    /// The query parameter this auth adds to the URL, or `None`. Only API-Key
+    /// auth placed in the query has one.
+    pub fn query_param(&self) -> Option<(String, String)> {
+        (self.auth_type == AuthType::ApiKey
+            && self.api_key_in == ApiKeyPlacement::Query
+            && !self.api_key_name.is_empty())
+        .then(|| (self.api_key_name.clone(), self.api_key_value.clone()))
+    }
 }
 
 /// Manual headers with the computed auth header merged in.
@@ -295,8 +492,317 @@ pub fn effective_wire_headers(
     }
 }
 
+/// The URL with the auth's query parameter appended (see
+/// `AuthConfig::query_param`); unchanged for every other auth.
+///
+/// The pair is added only on the wire, never to the saved URL or the Params
+/// rows, so switching the placement back to Header leaves nothing behind.
+pub fn effective_wire_url(url: &str, auth: &AuthConfig, encoding: ParamEncoding) -> String {
+    let Some((key, value)) = auth.query_param() else {
+        return url.to_string();
+    };
+    let (base, fragment) = match url.find('#') {
+        Some(i) => url.split_at(i),
+        None => (url, ""),
+    };
+    let param = crate::url_params::QueryParam::new(key, value, true);
+    // "?key=value" on an empty base; reuse its encoding, drop the "?".
+    let pair = crate::url_params::build_url_with_params_encoded("", &[param], encoding);
+    let separator = match base.find('?') {
+        None => "?",
+        Some(_) if base.ends_with('?') || base.ends_with('&') => "",
+        Some(_) => "&",
+    };
+    format!("{base}{separator}{}{fragment}", &pair[1..])
+}
+
+/// Headers for a chunked send: every Content-Length is dropped and a single
+/// `Transfer-Encoding: chunked` takes its place. Unchanged when `chunked` is
+/// off (Content-Length is then the auto-calculated row, or reqwest's own).
+pub fn transfer_headers(headers: Vec<(String, String)>, chunked: bool) -> Vec<(String, String)> {
+    if !chunked {
+        return headers;
+    }
+    let mut out: Vec<(String, String)> = headers
+        .into_iter()
+        .filter(|(k, _)| !k.eq_ignore_ascii_case("content-length") && !k.eq_ignore_ascii_case("transfer-encoding"))
+        .collect();
+    out.push(("Transfer-Encoding".to_string(), "chunked".to_string()));
+    out
+}
+
+/// Headers the transport works out from the body and the connection. A
+/// value from a header row would contradict it: a stale Content-Length cuts
+/// the body short, and an Accept-Encoding naming a coding the response
+/// decoder does not handle leaves the body encoded.
+pub const TRANSPORT_HEADERS: [&str; 3] = ["Content-Length", "Connection", "Accept-Encoding"];
+
+pub fn is_transport_header(name: &str) -> bool {
+    TRANSPORT_HEADERS.iter().any(|h| h.eq_ignore_ascii_case(name.trim()))
+}
+
+/// `headers` without the `TRANSPORT_HEADERS`: what the header rows put on
+/// the wire.
+pub fn without_transport_headers(headers: Vec<(String, String)>) -> Vec<(String, String)> {
+    headers.into_iter().filter(|(k, _)| !is_transport_header(k)).collect()
+}
+
+/// Whether a send of `request` leaves its body off: a GET or HEAD, which
+/// some servers reject with a body, unless `send_body_anyway` is set (APIs
+/// like Elasticsearch read GET bodies).
+pub fn skips_body(request: &RequestData) -> bool {
+    request.method.is_bodyless() && !request.send_body_anyway && !matches!(request.body, BodyType::None)
+}
+
+/// `request` as it goes on the wire body-wise: with `skips_body`, no body
+/// and none of the headers describing one (Content-Type, Transfer-Encoding).
+pub fn body_for_method(request: RequestData) -> RequestData {
+    if !skips_body(&request) {
+        return request;
+    }
+    let headers = request
+        .headers
+        .into_iter()
+        .filter(|(k, _)| !k.eq_ignore_ascii_case("content-type") && !k.eq_ignore_ascii_case("transfer-encoding"))
+        .collect();
+    RequestData { headers, body: BodyType::None, ..request }
+}
+
+/// Whether `headers` ask for a chunked body.
+pub fn is_chunked(headers: &[(String, String)]) -> bool {
+    headers.iter().any(|(k, v)| {
+        k.eq_ignore_ascii_case("transfer-encoding")
+            && v.split(',').any(|coding| coding.trim().eq_ignore_ascii_case("chunked"))
+    })
+}
+
+/// The network path a request takes (the "send through" selector).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum SendRoute {
+    /// Proxy settings from the environment (`HTTPS_PROXY`, …) and OS —
+    /// reqwest's default, and what every request used before the selector.
+    #[default]
+    System,
+    /// Never through a proxy.
+    Direct,
+    /// Through this proxy URL (the configured proxy when the route was chosen).
+    Proxy(String),
+}
+
+impl SendRoute {
+    /// Short name for the selector button.
+    pub fn short_label(&self) -> &'static str {
+        match self {
+            SendRoute::System => "System",
+            SendRoute::Direct => "Direct",
+            SendRoute::Proxy(_) => "Proxy",
+        }
+    }
+
+    /// Full description, as echoed next to the response.
+    pub fn label(&self) -> String {
+        match self {
+            SendRoute::System => "System proxy".to_string(),
+            SendRoute::Direct => "Direct".to_string(),
+            SendRoute::Proxy(url) => format!("Proxy {url}"),
+        }
+    }
+}
+
+/// How param keys/values are percent-encoded when the URL is rebuilt from
+/// the Params table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ParamEncoding {
+    /// Everything outside the unreserved set is escaped (`ids%5B%5D=1`).
+    #[default]
+    Strict,
+    /// Same, but `[` and `]` stay literal for PHP/Rails-style array params
+    /// (`ids[]=1`, `filter[name]=x`).
+    KeepBrackets,
+}
+
+/// The HTTP version a request asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum HttpProtocol {
+    /// Whatever the server negotiates: HTTP/2 over TLS when it offers it
+    /// (ALPN), HTTP/1.1 otherwise.
+    #[default]
+    Auto,
+    /// HTTP/1.1 only, even where HTTP/2 is offered.
+    Http1,
+    /// HTTP/2 from the first byte ("prior knowledge"), also over plain
+    /// http; fails against a server that only speaks HTTP/1.
+    Http2,
+}
+
+impl HttpProtocol {
+    pub const ALL: [HttpProtocol; 3] = [HttpProtocol::Auto, HttpProtocol::Http1, HttpProtocol::Http2];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HttpProtocol::Auto => "Auto",
+            HttpProtocol::Http1 => "HTTP/1.1",
+            HttpProtocol::Http2 => "HTTP/2",
+        }
+    }
+}
+
+/// A host name connected to at a fixed address instead of the one DNS
+/// gives (see `host_overrides`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HostOverride {
+    /// Lowercase.
+    pub host: String,
+    /// Port 0 = the URL's port.
+    pub addr: std::net::SocketAddr,
+}
+
+impl HostOverride {
+    /// The address as written: the IP alone when the port is the URL's.
+    pub fn address_label(&self) -> String {
+        match self.addr {
+            addr if addr.port() != 0 => addr.to_string(),
+            std::net::SocketAddr::V6(addr) => format!("[{}]", addr.ip()),
+            addr => addr.ip().to_string(),
+        }
+    }
+}
+
+/// Simulated network conditions a request is sent under (the "network
+/// profile"). Recorded in history with the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NetworkProfile {
+    #[default]
+    Unthrottled,
+    /// Chrome DevTools' "Regular 3G": 750 kbps down, 250 kbps up, 100 ms.
+    ThreeG,
+    /// `kbps` in both directions (0 = no limit) plus `latency_ms` up front.
+    Custom { kbps: u32, latency_ms: u32 },
+}
+
+impl NetworkProfile {
+    pub fn label(&self) -> String {
+        match self {
+            NetworkProfile::Unthrottled => "Unthrottled".to_string(),
+            NetworkProfile::ThreeG => "3G".to_string(),
+            NetworkProfile::Custom { kbps, latency_ms } => format!("{kbps} kbps, +{latency_ms} ms"),
+        }
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        *self != NetworkProfile::Unthrottled
+    }
+
+    /// Download and upload rate in kbps (0 = no limit) and the added
+    /// latency; `None` when unthrottled.
+    pub fn limits(&self) -> Option<NetworkLimits> {
+        match *self {
+            NetworkProfile::Unthrottled => None,
+            NetworkProfile::ThreeG => Some(NetworkLimits { down_kbps: 750, up_kbps: 250, latency_ms: 100 }),
+            NetworkProfile::Custom { kbps, latency_ms } => {
+                Some(NetworkLimits { down_kbps: kbps, up_kbps: kbps, latency_ms })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkLimits {
+    pub down_kbps: u32,
+    pub up_kbps: u32,
+    pub latency_ms: u32,
+}
+
+/// Where the time of a send went, simulated network delays included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ResponseTiming {
+    /// Latency added by the network profile before the request went out.
+    pub latency_ms: u64,
+    /// Sending the request and waiting for the response headers, the DNS
+    /// and connect phases below included.
+    pub wait_ms: u64,
+    /// Looking up the host. `None` on responses from before it was
+    /// measured; 0 when a kept-alive connection was reused.
+    #[serde(default)]
+    pub dns_ms: Option<u64>,
+    /// Opening the connection: TCP, plus TLS for https. Same `None` and 0
+    /// cases as `dns_ms`.
+    #[serde(default)]
+    pub connect_ms: Option<u64>,
+    /// Reading the response body.
+    pub download_ms: u64,
+}
+
+/// What a response took on the wire, next to its decoded body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct WireSize {
+    /// Status line and headers, counted as HTTP/1.1 text.
+    pub header_bytes: u64,
+    /// The body as it arrived: still compressed, chunk framing left out.
+    pub body_bytes: u64,
+}
+
+/// Color label a tab can carry (tab context menu). Stored by `as_str` name
+/// on the history rows sent from a labelled tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TabColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Teal,
+    Blue,
+    Purple,
+    Pink,
+}
+
+impl TabColor {
+    pub fn all() -> [Self; 8] {
+        [
+            TabColor::Red,
+            TabColor::Orange,
+            TabColor::Yellow,
+            TabColor::Green,
+            TabColor::Teal,
+            TabColor::Blue,
+            TabColor::Purple,
+            TabColor::Pink,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TabColor::Red => "red",
+            TabColor::Orange => "orange",
+            TabColor::Yellow => "yellow",
+            TabColor::Green => "green",
+            TabColor::Teal => "teal",
+            TabColor::Blue => "blue",
+            TabColor::Purple => "purple",
+            TabColor::Pink => "pink",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::all().into_iter().find(|c| c.as_str() == s)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TabColor::Red => "Red",
+            TabColor::Orange => "Orange",
+            TabColor::Yellow => "Yellow",
+            TabColor::Green => "Green",
+            TabColor::Teal => "Teal",
+            TabColor::Blue => "Blue",
+            TabColor::Purple => "Purple",
+            TabColor::Pink => "Pink",
+        }
+    }
+}
+
 /// Request data structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RequestData {
     pub method: HttpMethod,
     pub url: String,
@@ -306,10 +812,114 @@ pub struct RequestData {
     /// feature (history rows / saved tabs) still deserialize — missing → `None`.
     #[serde(default)]
     pub auth: AuthConfig,
+    /// How the request is sent; recorded in history with the request.
+    #[serde(default)]
+    pub route: SendRoute,
+    /// Bracket handling when the URL is rebuilt from the Params table.
+    #[serde(default)]
+    pub param_encoding: ParamEncoding,
+    /// Simulated network conditions the request is sent under.
+    #[serde(default)]
+    pub network_profile: NetworkProfile,
+    /// Send only the headers the user added: no predefined rows, and no
+    /// defaults from the HTTP client either (see `HttpClient::for_route`).
+    #[serde(default)]
+    pub minimal_headers: bool,
+    /// Send the body with `Transfer-Encoding: chunked` and no Content-Length.
+    #[serde(default)]
+    pub chunked: bool,
+    /// Give up on the request after this many seconds; 0 waits forever.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u32,
+    /// Follow 3xx responses to their `Location`; off shows the 3xx itself.
+    #[serde(default = "default_follow_redirects")]
+    pub follow_redirects: bool,
+    /// Accept any TLS certificate (self-signed, expired, wrong host).
+    #[serde(default)]
+    pub ignore_tls_errors: bool,
+    /// Re-send automatically after a network error (or a 5xx, if asked).
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// Checks run against each response (the Tests tab).
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+    /// Ids of the presets whose headers and params are added at send time.
+    #[serde(default)]
+    pub presets: Vec<i64>,
+    /// Values copied from the response into variables after a send.
+    #[serde(default)]
+    pub extract: Vec<ExtractRule>,
+    /// Values of the URL's `:name` path segments, filled in at send time.
+    #[serde(default)]
+    pub path_variables: Vec<(String, String)>,
+    /// Send the body on a GET or HEAD too (see `skips_body`).
+    #[serde(default)]
+    pub send_body_anyway: bool,
+    /// The HTTP version asked for.
+    #[serde(default)]
+    pub protocol: HttpProtocol,
+    /// Hosts connected to at a fixed address; over the global ones for the
+    /// same host (see `host_overrides::merged`).
+    #[serde(default)]
+    pub host_overrides: Vec<HostOverride>,
+    /// Keep a `curl -v` style transcript of the exchange (the Console tab).
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+fn default_follow_redirects() -> bool {
+    true
+}
+
+/// Most retries a request can ask for.
+pub const MAX_RETRIES: u8 = 5;
+
+/// Wait before the first retry; each later one waits twice as long as the
+/// one before.
+pub const RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Automatic re-sends of a request that failed. Network errors (timeouts
+/// included) are retried; 5xx responses only with `on_5xx`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Attempts after the first, at most `MAX_RETRIES`; 0 is off.
+    pub retries: u8,
+    pub on_5xx: bool,
+}
+
+impl RetryPolicy {
+    /// Whether a response with `status` is sent again.
+    pub fn retries_status(&self, status: u16) -> bool {
+        self.on_5xx && (500..600).contains(&status)
+    }
+
+    /// Exponential backoff: how long to wait before retry number `retry`
+    /// (1-based).
+    pub fn backoff(retry: u8) -> std::time::Duration {
+        RETRY_BASE_DELAY * 2u32.pow(retry.saturating_sub(1) as u32)
+    }
+}
+
+/// Timeout for new requests (and ones saved before timeouts existed).
+pub const DEFAULT_TIMEOUT_SECS: u32 = 30;
+
+fn default_timeout_secs() -> u32 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+/// The timeout field next to Send: whole seconds, blank (or not a number)
+/// meaning the default.
+pub fn parse_timeout_secs(text: &str) -> u32 {
+    text.trim().parse().unwrap_or(DEFAULT_TIMEOUT_SECS)
+}
+
+/// Body of the `ResponseData` for a request that ran out of time; the
+/// viewer recognizes it by this wording (see `ResponseData::is_timeout`).
+pub fn timeout_message(secs: u32) -> String {
+    format!("Request timed out after {secs}s")
 }
 
 impl RequestData {
-    #[allow(dead_code)]
     pub fn new(method: HttpMethod, url: String) -> Self {
         Self {
             method,
@@ -317,6 +927,23 @@ impl RequestData {
             headers: vec![],
             body: BodyType::default(),
             auth: AuthConfig::default(),
+            route: SendRoute::default(),
+            param_encoding: Default::default(),
+            minimal_headers: false,
+            network_profile: Default::default(),
+            chunked: false,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            follow_redirects: true,
+            ignore_tls_errors: false,
+            retry: RetryPolicy::default(),
+            assertions: Vec::new(),
+            presets: Vec::new(),
+            extract: Vec::new(),
+            path_variables: Vec::new(),
+            send_body_anyway: false,
+            protocol: Default::default(),
+            host_overrides: Default::default(),
+            verbose: false,
         }
     }
 }
@@ -331,12 +958,90 @@ pub struct ResponseData {
     pub body: Vec<u8>,
     /// Whether the body should be shown as text (vs treated as binary).
     pub is_text: bool,
+    /// The path the request took, echoed in the status bar.
+    #[serde(default)]
+    pub route: SendRoute,
+    /// Headers whose value was not plain ASCII; `headers` holds a lossy
+    /// decoding of these, flagged in the Headers tab.
+    #[serde(default)]
+    pub raw_header_values: Vec<RawHeaderValue>,
+    /// The network profile the request was shaped with.
+    #[serde(default)]
+    pub network_profile: NetworkProfile,
+    /// Breakdown of `duration_ms`; `None` when no response arrived.
+    #[serde(default)]
+    pub timing: Option<ResponseTiming>,
+    /// Redirects followed on the way here, in order: the URL requested and
+    /// the 3xx status it answered with.
+    #[serde(default)]
+    pub redirects: Vec<(String, u16)>,
+    /// Obtained with certificate verification off (`ignore_tls_errors`).
+    #[serde(default)]
+    pub tls_unverified: bool,
+    /// How many times the request was sent to get this (see `RetryPolicy`).
+    #[serde(default = "default_attempts")]
+    pub attempts: u8,
+    /// Set when the body stopped being read at the size limit: the bytes
+    /// received by then. `body` holds only the first part.
+    #[serde(default)]
+    pub truncated_after: Option<u64>,
+    /// Set when the body went to a file (download mode) rather than `body`.
+    #[serde(default)]
+    pub downloaded: Option<DownloadedBody>,
+    /// Bytes received for it; `None` for a response that never arrived, or
+    /// one reopened from history.
+    #[serde(default)]
+    pub wire_size: Option<WireSize>,
+    /// The HTTP version the response came over ("HTTP/1.1", "HTTP/2"); `None`
+    /// for a response that never arrived, or one recorded before it was kept.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// The host override the request was sent with, if its host had one.
+    #[serde(default)]
+    pub resolved_by: Option<HostOverride>,
+}
+
+/// Where a downloaded response body was written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadedBody {
+    pub path: String,
+    pub bytes: u64,
+}
+
+fn default_attempts() -> u8 {
+    1
+}
+
+/// The bytes of a response header value that did not decode as visible
+/// ASCII. The `headers` entry at `index` shows it decoded as UTF-8 when the
+/// bytes are valid UTF-8, as Latin-1 otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RawHeaderValue {
+    pub index: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl RawHeaderValue {
+    /// The bytes as an ASCII string, anything outside printable ASCII
+    /// written as `\xNN`.
+    pub fn escaped(&self) -> String {
+        self.bytes
+            .iter()
+            .map(|&b| match b {
+                b'\\' => "\\\\".to_string(),
+                0x20..=0x7e => (b as char).to_string(),
+                _ => format!("\\x{b:02x}"),
+            })
+            .collect()
+    }
 }
 
 /// Decide whether a response body should be shown as text.
 ///
 /// Uses the `Content-Type` header first (clear text vs clear binary families),
-/// falling back to a UTF-8 validity sniff when the type is missing/ambiguous.
+/// falling back to a sniff when the type is missing/ambiguous: a declared
+/// charset, or valid UTF-8 with no NUL bytes (protobuf and friends are often
+/// valid UTF-8).
 pub fn is_text_response(headers: &[(String, String)], body: &[u8]) -> bool {
     let content_type = headers
         .iter()
@@ -370,31 +1075,22 @@ pub fn is_text_response(headers: &[(String, String)], body: &[u8]) -> bool {
         }
         // else: unknown application/* — fall through to UTF-8 sniff
     }
+    if crate::charset::declared(headers).is_some_and(|charset| !charset.eq_ignore_ascii_case("binary")) {
+        return true;
+    }
 
-    std::str::from_utf8(body).is_ok()
+    std::str::from_utf8(body).is_ok() && !body.contains(&0)
 }
 
 impl ResponseData {
-    /// Lossy text view of the body (for display when `is_text`).
+    /// Text view of the body (for display when `is_text`), in the declared
+    /// charset; see `charset::decode`.
     pub fn body_text(&self) -> std::borrow::Cow<'_, str> {
-        String::from_utf8_lossy(&self.body)
+        crate::charset::decode(&self.headers, &self.body).0
     }
 
     pub fn status_text(&self) -> &'static str {
-        match self.status {
-            Some(200) => "OK",
-            Some(201) => "Created",
-            Some(204) => "No Content",
-            Some(400) => "Bad Request",
-            Some(401) => "Unauthorized",
-            Some(403) => "Forbidden",
-            Some(404) => "Not Found",
-            Some(500) => "Internal Server Error",
-            Some(502) => "Bad Gateway",
-            Some(503) => "Service Unavailable",
-            Some(_) => "Unknown",
-            None => "Network Error",
-        }
+        self.status.map_or("Network Error", status_reason)
     }
 
     pub fn is_success(&self) -> bool {
@@ -416,6 +1112,93 @@ impl ResponseData {
     pub fn is_network_error(&self) -> bool {
         self.status.is_none()
     }
+
+    /// A network error caused by the request's own timeout.
+    pub fn is_timeout(&self) -> bool {
+        self.status.is_none() && self.body.starts_with(b"Request timed out after ")
+    }
+}
+
+/// The reason phrase for a status code: the IANA registry's name, or the
+/// code's class ("Client Error") for one that is not registered.
+pub fn status_reason(status: u16) -> &'static str {
+    registered_reason(status).unwrap_or(match status {
+        100..=199 => "Informational",
+        200..=299 => "Success",
+        300..=399 => "Redirection",
+        400..=499 => "Client Error",
+        500..=599 => "Server Error",
+        _ => "Unknown",
+    })
+}
+
+fn registered_reason(status: u16) -> Option<&'static str> {
+    Some(match status {
+        100 => "Continue",
+        101 => "Switching Protocols",
+        102 => "Processing",
+        103 => "Early Hints",
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        203 => "Non-Authoritative Information",
+        204 => "No Content",
+        205 => "Reset Content",
+        206 => "Partial Content",
+        207 => "Multi-Status",
+        208 => "Already Reported",
+        226 => "IM Used",
+        300 => "Multiple Choices",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        305 => "Use Proxy",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        402 => "Payment Required",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        407 => "Proxy Authentication Required",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        410 => "Gone",
+        411 => "Length Required",
+        412 => "Precondition Failed",
+        413 => "Content Too Large",
+        414 => "URI Too Long",
+        415 => "Unsupported Media Type",
+        416 => "Range Not Satisfiable",
+        417 => "Expectation Failed",
+        // Reserved by RFC 9110 §15.5.19, but everyone knows it by this name.
+        418 => "I'm a teapot",
+        421 => "Misdirected Request",
+        422 => "Unprocessable Content",
+        423 => "Locked",
+        424 => "Failed Dependency",
+        425 => "Too Early",
+        426 => "Upgrade Required",
+        428 => "Precondition Required",
+        429 => "Too Many Requests",
+        431 => "Request Header Fields Too Large",
+        451 => "Unavailable For Legal Reasons",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        505 => "HTTP Version Not Supported",
+        506 => "Variant Also Negotiates",
+        507 => "Insufficient Storage",
+        508 => "Loop Detected",
+        510 => "Not Extended",
+        511 => "Network Authentication Required",
+        _ => return None,
+    })
 }
 
 /// History item stored in database
@@ -428,6 +1211,18 @@ pub struct HistoryItem {
     pub timestamp: String,
     pub request: RequestData,
     pub response: Option<std::sync::Arc<ResponseData>>,
+    /// Row chips (body kind, param/header counts); filled in by the DB.
+    pub meta: crate::history_meta::HistoryMeta,
+    /// Label of the tab the request was sent from.
+    pub color: Option<TabColor>,
+    /// Listed on the start panel of blank tabs.
+    pub pinned: bool,
+    /// Status code of the stored response. List queries fill only this; the
+    /// full response is loaded when the row is opened.
+    pub status: Option<u16>,
+    /// The attempts and summary of a "Send ×N" batch; `None` for a single
+    /// send.
+    pub run_report: Option<std::sync::Arc<crate::repeat_runner::RepeatReport>>,
 }
 
 impl HistoryItem {
@@ -441,27 +1236,41 @@ impl HistoryItem {
             id,
             timestamp,
             request,
+            meta: Default::default(),
+            color: None,
+            pinned: false,
+            status: response.as_ref().and_then(|r| r.status),
             response,
+            run_report: None,
         }
     }
 }
 
 /// Query parameter state for UI (including enabled/disabled state)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParamState {
     pub enabled: bool,
     pub key: String,
     pub value: String,
+    /// A note for the reader; never sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The value is already percent-encoded and goes into the URL as typed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encoded: bool,
 }
 
 /// Header state for UI (including enabled/disabled state and header type)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HeaderState {
     pub enabled: bool,
     pub key: String,
     pub value: String,
     pub header_type: HeaderType,
     pub predefined: Option<PredefinedHeader>,
+    /// A note for the reader; never sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
 /// A named environment holding a set of variables.
@@ -478,12 +1287,87 @@ pub struct EnvVar {
     pub enabled: bool,
     pub key: String,
     pub value: String,
+    /// Shown masked in the environment manager and treated as a credential
+    /// on export, whatever the key is called.
+    pub secret: bool,
+}
+
+/// A collection of saved requests, or — when it has a parent — a folder
+/// inside one.
+#[derive(Debug, Clone)]
+pub struct Collection {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub name: String,
+}
+
+/// A named bundle of headers and query params that requests can apply
+/// (see `presets`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preset {
+    pub id: i64,
+    pub name: String,
+    pub headers: Vec<(String, String)>,
+    pub params: Vec<(String, String)>,
+}
+
+/// A request saved by name into a collection (or folder).
+#[derive(Debug, Clone)]
+pub struct SavedRequest {
+    pub id: i64,
+    pub collection_id: i64,
+    pub name: String,
+    pub request: RequestData,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn transport_headers_never_come_from_rows() {
+        let headers: Vec<(String, String)> =
+            [("content-length", "12"), ("Accept", "*/*"), ("Connection", "keep-alive"), ("Accept-Encoding ", "br")]
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .into();
+        assert_eq!(without_transport_headers(headers), [("Accept".to_string(), "*/*".to_string())]);
+        assert!(PredefinedHeader::Connection.is_transport_managed());
+        assert!(!PredefinedHeader::Accept.is_transport_managed());
+    }
+
+    #[test]
+    fn every_predefined_header_can_be_turned_off() {
+        assert!(PredefinedHeader::all().iter().all(|p| p.header_type() == HeaderType::Predefined));
+        let old: HeaderType = serde_json::from_str("\"Mandatory\"").unwrap();
+        assert_eq!(old, HeaderType::Predefined);
+    }
+
+    #[test]
+    fn restored_predefined_rows_go_back_in_order() {
+        use PredefinedHeader::*;
+        let rows = [Some(ContentType), Some(Connection), None, None];
+        assert_eq!(CacheControl.insert_position(&rows), 0);
+        assert_eq!(Accept.insert_position(&rows), 1);
+        assert_eq!(ContentLength.insert_position(&rows), 2);
+        assert_eq!(Accept.insert_position(&[]), 0);
+    }
+
+    #[test]
+    fn repeated_predefined_headers_keep_their_extra_rows() {
+        let headers: Vec<(String, String)> = [("accept", "text/html"), ("X-A", "1"), ("Accept", "application/json"), ("X-A", "2")]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .into();
+        assert_eq!(
+            PredefinedHeader::assign(&headers),
+            [
+                (Some(PredefinedHeader::Accept), "accept", "text/html"),
+                (None, "X-A", "1"),
+                (None, "Accept", "application/json"),
+                (None, "X-A", "2"),
+            ]
+        );
+    }
+
     fn h(ct: &str) -> Vec<(String, String)> {
         vec![("Content-Type".to_string(), ct.to_string())]
     }
@@ -512,6 +1396,69 @@ mod tests {
         // unknown application/* defers to sniff
         assert!(is_text_response(&h("application/weird"), b"readable"));
         assert!(!is_text_response(&h("application/weird"), &[0xff, 0x00]));
+        // valid UTF-8, but the NUL gives it away
+        assert!(!is_text_response(&h("application/x-protobuf"), b"\x08\x96\x01\x12\x00ab"));
+        // a declared charset says text, whatever the bytes
+        assert!(is_text_response(&h("application/weird; charset=shift_jis"), b"\x82\xb1"));
+    }
+
+    #[test]
+    fn status_reasons_come_from_the_registry_then_the_class() {
+        let cases = [
+            (101, "Switching Protocols"),
+            (206, "Partial Content"),
+            (301, "Moved Permanently"),
+            (308, "Permanent Redirect"),
+            (418, "I'm a teapot"),
+            (429, "Too Many Requests"),
+            (451, "Unavailable For Legal Reasons"),
+            (507, "Insufficient Storage"),
+            (299, "Success"),
+            (499, "Client Error"),
+            (599, "Server Error"),
+            (999, "Unknown"),
+        ];
+        for (status, reason) in cases {
+            assert_eq!(status_reason(status), reason, "{status}");
+        }
+    }
+
+    #[test]
+    fn method_cycling_wraps_around_all() {
+        assert_eq!(HttpMethod::GET.cycled(1), HttpMethod::POST);
+        assert_eq!(HttpMethod::GET.cycled(-1), HttpMethod::OPTIONS);
+        assert_eq!(HttpMethod::OPTIONS.cycled(1), HttpMethod::GET);
+        assert_eq!(HttpMethod::PATCH.cycled(0), HttpMethod::PATCH);
+    }
+
+    #[test]
+    fn custom_methods_are_any_other_token() {
+        assert_eq!(HttpMethod::from_str("purge"), Some(HttpMethod::Custom("purge".into())));
+        assert_eq!(HttpMethod::from_str("delete"), Some(HttpMethod::DELETE));
+        assert_eq!(HttpMethod::from_str("PROP FIND"), None);
+        assert_eq!(HttpMethod::from_str("LINK/1"), None);
+        assert_eq!(HttpMethod::from_str(""), None);
+        assert!(is_method_token("M-SEARCH") && is_method_token("X_!#$%&'*+.^`|~1"));
+        // Cycling from a custom method starts over at GET.
+        assert_eq!(HttpMethod::Custom("PURGE".into()).cycled(1), HttpMethod::POST);
+    }
+
+    #[test]
+    fn methods_serialize_as_their_names() {
+        let methods = [HttpMethod::PATCH, HttpMethod::Custom("PROPFIND".into())];
+        let json = serde_json::to_string(&methods).unwrap();
+        assert_eq!(json, r#"["PATCH","PROPFIND"]"#);
+        assert_eq!(serde_json::from_str::<Vec<HttpMethod>>(&json).unwrap(), methods);
+        assert!(serde_json::from_str::<HttpMethod>(r#""NOT A METHOD""#).is_err());
+    }
+
+    #[test]
+    fn method_prefix_matches_first_in_order() {
+        assert_eq!(HttpMethod::from_prefix("de"), Some(HttpMethod::DELETE));
+        assert_eq!(HttpMethod::from_prefix("P"), Some(HttpMethod::POST));
+        assert_eq!(HttpMethod::from_prefix("pa"), Some(HttpMethod::PATCH));
+        assert_eq!(HttpMethod::from_prefix("x"), None);
+        assert_eq!(HttpMethod::from_prefix(""), None);
     }
 
     #[test]
@@ -526,6 +1473,9 @@ mod tests {
         // ApiKey with empty name → nothing
         let a = AuthConfig { auth_type: AuthType::ApiKey, api_key_value: "v".into(), ..Default::default() };
         assert_eq!(a.compute_header(), None);
+        // OAuth 2.0 → nothing; the sender adds the fetched token
+        let a = AuthConfig { auth_type: AuthType::OAuth2, ..Default::default() };
+        assert_eq!(a.compute_header(), None);
     }
 
     #[test]
@@ -564,6 +1514,39 @@ mod tests {
         assert_eq!(a.compute_header(), Some(("X-API-Key".into(), "secret".into())));
     }
 
+    #[test]
+    fn api_key_in_query_goes_on_the_wire_url_only() {
+        let mut a = AuthConfig {
+            auth_type: AuthType::ApiKey,
+            api_key_name: "api_key".into(),
+            api_key_value: "s e/cret".into(),
+            api_key_in: ApiKeyPlacement::Query,
+            ..Default::default()
+        };
+        assert_eq!(a.compute_header(), None);
+        let strict = ParamEncoding::Strict;
+        assert_eq!(effective_wire_url("https://x/a", &a, strict), "https://x/a?api_key=s%20e%2Fcret");
+        assert_eq!(effective_wire_url("https://x/a?q=1#top", &a, strict), "https://x/a?q=1&api_key=s%20e%2Fcret#top");
+        assert_eq!(effective_wire_url("https://x/a?", &a, strict), "https://x/a?api_key=s%20e%2Fcret");
+
+        // Back to Header: the saved URL never had the pair, so nothing is left.
+        a.api_key_in = ApiKeyPlacement::Header;
+        assert_eq!(effective_wire_url("https://x/a", &a, strict), "https://x/a");
+        assert_eq!(a.compute_header(), Some(("api_key".into(), "s e/cret".into())));
+    }
+
+    #[test]
+    fn auth_saved_before_placement_existed_uses_a_header() {
+        let old = r#"{"auth_type":"ApiKey","bearer_token":"","basic_username":"","basic_password":"","api_key_name":"K","api_key_value":"v"}"#;
+        let auth: AuthConfig = serde_json::from_str(old).unwrap();
+        assert_eq!(auth.api_key_in, ApiKeyPlacement::Header);
+        assert_eq!(auth.oauth2.redirect_uri, DEFAULT_OAUTH2_REDIRECT_URI);
+        let round_trip: AuthConfig =
+            serde_json::from_str(&serde_json::to_string(&AuthConfig { api_key_in: ApiKeyPlacement::Query, ..auth }).unwrap())
+                .unwrap();
+        assert_eq!(round_trip.api_key_in, ApiKeyPlacement::Query);
+    }
+
     #[test]
     fn effective_headers_none_leaves_manual_untouched() {
         let manual = vec![("Accept".to_string(), "*/*".to_string())];
@@ -615,4 +1598,124 @@ mod tests {
         let out = effective_wire_headers(&manual, &auth);
         assert_eq!(out, vec![("X-API-Key".to_string(), "new".to_string())]);
     }
+
+    fn hs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn chunked_mode_swaps_content_length_for_transfer_encoding() {
+        let headers = hs(&[("Content-Type", "application/json"), ("Content-Length", "17"), ("content-length", "3")]);
+        let chunked = transfer_headers(headers.clone(), true);
+        assert_eq!(chunked, hs(&[("Content-Type", "application/json"), ("Transfer-Encoding", "chunked")]));
+        assert!(is_chunked(&chunked));
+
+        // Off: the auto-calculated Content-Length goes out untouched.
+        let plain = transfer_headers(headers.clone(), false);
+        assert_eq!(plain, headers);
+        assert!(!is_chunked(&plain));
+    }
+
+    #[test]
+    fn get_and_head_go_out_without_their_body_unless_asked() {
+        let mut request = RequestData::new(HttpMethod::GET, "https://x.test/_search".to_string());
+        request.headers = hs(&[("Content-Type", "application/json"), ("Transfer-Encoding", "chunked"), ("X-Id", "1")]);
+        request.body = BodyType::Raw { content: "{\"query\":{}}".to_string(), subtype: RawSubtype::Json };
+
+        let sent = body_for_method(request.clone());
+        assert!(skips_body(&request));
+        assert_eq!(sent.body, BodyType::None);
+        assert_eq!(sent.headers, hs(&[("X-Id", "1")]));
+
+        request.method = HttpMethod::HEAD;
+        assert_eq!(body_for_method(request.clone()).body, BodyType::None);
+
+        // The override, and methods that carry a body, send it as it is.
+        request.send_body_anyway = true;
+        assert!(!skips_body(&request));
+        assert_eq!(body_for_method(request.clone()), request);
+        request.send_body_anyway = false;
+        request.method = HttpMethod::POST;
+        assert_eq!(body_for_method(request.clone()), request);
+
+        // Nothing to skip: a GET without a body keeps its headers.
+        request.method = HttpMethod::GET;
+        request.body = BodyType::None;
+        assert!(!skips_body(&request));
+        assert_eq!(body_for_method(request.clone()).headers.len(), 3);
+    }
+
+    #[test]
+    fn timeouts_default_when_blank_and_are_recognised_by_message() {
+        assert_eq!(parse_timeout_secs(""), DEFAULT_TIMEOUT_SECS);
+        assert_eq!(parse_timeout_secs(" 5 "), 5);
+        assert_eq!(parse_timeout_secs("0"), 0);
+
+        let failed = |body: String| ResponseData {
+            status: None,
+            duration_ms: 5000,
+            headers: vec![],
+            body: body.into_bytes(),
+            is_text: true,
+            route: SendRoute::default(),
+            raw_header_values: vec![],
+            network_profile: NetworkProfile::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: None,
+            resolved_by: None,
+        };
+        assert!(failed(timeout_message(5)).is_timeout());
+        assert!(!failed("Request failed: connection refused".into()).is_timeout());
+
+        // Saved before timeouts existed: the default applies.
+        let old: RequestData = serde_json::from_str(r#"{"method":"GET","url":"https://x","headers":[],"body":"None"}"#).unwrap();
+        assert_eq!(old.timeout_secs, DEFAULT_TIMEOUT_SECS);
+    }
+
+    #[test]
+    fn retries_back_off_exponentially_and_only_retry_5xx_when_asked() {
+        let ms = |retry| RetryPolicy::backoff(retry).as_millis();
+        assert_eq!((ms(1), ms(2), ms(3), ms(5)), (500, 1000, 2000, 8000));
+
+        let errors_only = RetryPolicy { retries: 3, on_5xx: false };
+        assert!(!errors_only.retries_status(502));
+        let with_5xx = RetryPolicy { retries: 3, on_5xx: true };
+        assert!(with_5xx.retries_status(502));
+        assert!(!with_5xx.retries_status(429));
+        assert!(!with_5xx.retries_status(404));
+    }
+
+    #[test]
+    fn missing_files_lists_only_rows_that_would_be_sent() {
+        let present = std::env::temp_dir().join("poopman-missing-files-test.txt");
+        std::fs::write(&present, b"x").unwrap();
+        let row = |enabled: bool, key: &str, path: &str| FormDataRow {
+            enabled,
+            key: key.to_string(),
+            value: FormDataValue::File { path: path.to_string() },
+        };
+        let body = BodyType::FormData(vec![
+            row(true, "a", present.to_str().unwrap()),
+            row(true, "b", "/no/such/file.bin"),
+            row(false, "c", "/no/such/disabled.bin"),
+            row(true, "", "/no/such/keyless.bin"),
+            row(true, "d", ""),
+            FormDataRow { enabled: true, key: "e".into(), value: FormDataValue::Text("hi".into()) },
+        ]);
+        assert_eq!(body.missing_files(), vec!["/no/such/file.bin"]);
+        assert!(BodyType::default().missing_files().is_empty());
+        let _ = std::fs::remove_file(present);
+    }
+
+    #[test]
+    fn chunked_is_read_from_any_transfer_coding_list() {
+        assert!(is_chunked(&hs(&[("transfer-encoding", "gzip, Chunked")])));
+        assert!(!is_chunked(&hs(&[("Transfer-Encoding", "gzip")])));
+    }
 }
@@ -2,7 +2,8 @@
 
 use std::collections::HashMap;
 
-use crate::types::{AuthConfig, BodyType, FormDataRow, FormDataValue, RequestData};
+use crate::assertions::Assertion;
+use crate::types::{AuthConfig, BodyType, EnvVar, FormDataRow, FormDataValue, OAuth2Config, RequestData};
 
 /// Replace `{{key}}` / `{{ key }}` (key trimmed) with values from `vars`.
 ///
@@ -38,6 +39,71 @@ pub fn substitute(input: &str, vars: &HashMap<String, String>) -> String {
     out
 }
 
+/// Names of the `{{key}}` tokens in `input` (trimmed, first occurrence order,
+/// deduplicated). Unclosed and empty tokens are ignored, matching `substitute`.
+pub fn referenced_vars(input: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = input;
+    while let Some(open) = rest.find("{{") {
+        let after = &rest[open + 2..];
+        let Some(close) = after.find("}}") else {
+            break;
+        };
+        let key = after[..close].trim();
+        if !key.is_empty() && !names.iter().any(|n| n == key) {
+            names.push(key.to_string());
+        }
+        rest = &after[close + 2..];
+    }
+    names
+}
+
+/// The enabled, keyed variables of an environment (or the Globals scope) as a
+/// flat map. A later duplicate key wins.
+pub fn enabled_map(vars: &[EnvVar]) -> HashMap<String, String> {
+    vars.iter()
+        .filter(|v| v.enabled && !v.key.is_empty())
+        .map(|v| (v.key.clone(), v.value.clone()))
+        .collect()
+}
+
+/// The variables in scope for a request: `globals`, with the active
+/// environment's `env` winning where keys collide.
+pub fn scoped(env: &HashMap<String, String>, globals: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut vars = globals.clone();
+    vars.extend(env.iter().map(|(k, v)| (k.clone(), v.clone())));
+    vars
+}
+
+/// Resolve `template` against the environment, falling back to globals
+/// (environment > globals). Unknown keys stay literal, as in `substitute`.
+pub fn resolve(template: &str, env: &HashMap<String, String>, globals: &HashMap<String, String>) -> String {
+    substitute(template, &scoped(env, globals))
+}
+
+/// Set `key` to `value` in an environment's variable list: the row
+/// `enabled_map` resolves (the last enabled one with that key) is updated;
+/// failing that the last disabled one, re-enabled since the caller just
+/// resolved it; failing that a new enabled row is appended.
+pub fn upsert_var(vars: &mut Vec<EnvVar>, key: &str, value: &str) {
+    let index = vars
+        .iter()
+        .rposition(|v| v.key == key && v.enabled)
+        .or_else(|| vars.iter().rposition(|v| v.key == key));
+    match index.map(|i| &mut vars[i]) {
+        Some(var) => {
+            var.value = value.to_string();
+            var.enabled = true;
+        }
+        None => vars.push(EnvVar {
+            enabled: true,
+            key: key.to_string(),
+            value: value.to_string(),
+            secret: false,
+        }),
+    }
+}
+
 /// Substitute `{{vars}}` in every auth field. `auth_type` is preserved as-is.
 pub fn substitute_auth(auth: &AuthConfig, vars: &HashMap<String, String>) -> AuthConfig {
     AuthConfig {
@@ -47,18 +113,38 @@ pub fn substitute_auth(auth: &AuthConfig, vars: &HashMap<String, String>) -> Aut
         basic_password: substitute(&auth.basic_password, vars),
         api_key_name: substitute(&auth.api_key_name, vars),
         api_key_value: substitute(&auth.api_key_value, vars),
+        api_key_in: auth.api_key_in,
+        oauth2: OAuth2Config {
+            token_url: substitute(&auth.oauth2.token_url, vars),
+            auth_url: substitute(&auth.oauth2.auth_url, vars),
+            client_id: substitute(&auth.oauth2.client_id, vars),
+            client_secret: substitute(&auth.oauth2.client_secret, vars),
+            scopes: substitute(&auth.oauth2.scopes, vars),
+            audience: substitute(&auth.oauth2.audience, vars),
+            redirect_uri: substitute(&auth.oauth2.redirect_uri, vars),
+            ..auth.oauth2.clone()
+        },
     }
 }
 
+/// Substitute `{{vars}}` in the target and expected value of each assertion.
+pub fn substitute_assertions(assertions: &[Assertion], vars: &HashMap<String, String>) -> Vec<Assertion> {
+    assertions
+        .iter()
+        .map(|a| Assertion { check: a.check, target: substitute(&a.target, vars), expected: substitute(&a.expected, vars) })
+        .collect()
+}
+
+/// Substitute `{{vars}}` in both sides of each header or param.
+pub fn substitute_pairs(pairs: &[(String, String)], vars: &HashMap<String, String>) -> Vec<(String, String)> {
+    pairs.iter().map(|(k, v)| (substitute(k, vars), substitute(v, vars))).collect()
+}
+
 /// Substitute `{{vars}}` throughout a request — URL, header keys+values, and
 /// raw/form body text — so generated code & previews use resolved values.
 /// File form-data paths are left untouched.
 pub fn substitute_request(req: &RequestData, vars: &HashMap<String, String>) -> RequestData {
-    let headers = req
-        .headers
-        .iter()
-        .map(|(k, v)| (substitute(k, vars), substitute(v, vars)))
-        .collect();
+    let headers = substitute_pairs(&req.headers, vars);
 
     let body = match &req.body {
         BodyType::None => BodyType::None,
@@ -81,11 +167,13 @@ pub fn substitute_request(req: &RequestData, vars: &HashMap<String, String>) ->
     };
 
     RequestData {
-        method: req.method,
         url: substitute(&req.url, vars),
         headers,
         body,
         auth: substitute_auth(&req.auth, vars),
+        assertions: substitute_assertions(&req.assertions, vars),
+        path_variables: req.path_variables.iter().map(|(k, v)| (k.clone(), substitute(v, vars))).collect(),
+        ..req.clone()
     }
 }
 
@@ -97,6 +185,30 @@ mod tests {
         pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
     }
 
+    #[test]
+    fn resolve_prefers_the_environment_over_globals() {
+        let env = vars(&[("host", "https://dev.example.com")]);
+        let globals = vars(&[("host", "https://global.example.com"), ("path", "v1/users")]);
+        assert_eq!(resolve("{{host}}/{{path}}", &env, &globals), "https://dev.example.com/v1/users");
+        assert_eq!(resolve("{{host}}/{{path}}", &HashMap::new(), &globals), "https://global.example.com/v1/users");
+        assert_eq!(resolve("{{host}}/{{ missing }}", &env, &globals), "https://dev.example.com/{{ missing }}");
+        // Values are not re-scanned, whichever scope they came from.
+        let globals = vars(&[("path", "{{host}}")]);
+        assert_eq!(resolve("{{path}}", &env, &globals), "{{host}}");
+    }
+
+    #[test]
+    fn enabled_map_skips_disabled_and_keyless_rows() {
+        let var = |enabled: bool, key: &str, value: &str| EnvVar {
+            enabled,
+            key: key.into(),
+            value: value.into(),
+            secret: false,
+        };
+        let map = enabled_map(&[var(true, "a", "1"), var(false, "b", "2"), var(true, "", "3"), var(true, "a", "4")]);
+        assert_eq!(map, vars(&[("a", "4")]));
+    }
+
     #[test]
     fn replaces_known_var() {
         assert_eq!(substitute("{{a}}", &vars(&[("a", "1")])), "1");
@@ -143,14 +255,12 @@ mod tests {
     fn substitute_request_resolves_url_headers_and_raw_body() {
         use crate::types::{BodyType, HttpMethod, RawSubtype, RequestData};
         let req = RequestData {
-            method: HttpMethod::POST,
-            url: "{{base_url}}/users".to_string(),
             headers: vec![("Authorization".to_string(), "Bearer {{token}}".to_string())],
             body: BodyType::Raw {
                 content: "{\"env\": \"{{env}}\"}".to_string(),
                 subtype: RawSubtype::Json,
             },
-            auth: crate::types::AuthConfig::default(),
+            ..RequestData::new(HttpMethod::POST, "{{base_url}}/users".to_string())
         };
         let v = vars(&[("base_url", "https://api.test"), ("token", "abc"), ("env", "prod")]);
         let out = super::substitute_request(&req, &v);
@@ -172,10 +282,17 @@ mod tests {
             basic_password: "{{pass}}".into(),
             api_key_name: "{{keyname}}".into(),
             api_key_value: "{{keyval}}".into(),
+            oauth2: crate::types::OAuth2Config {
+                token_url: "{{idp}}/token".into(),
+                client_secret: "{{secret}}".into(),
+                ..Default::default()
+            },
+            ..Default::default()
         };
         let v = vars(&[
             ("token", "abc"), ("user", "u"), ("pass", "p"),
             ("keyname", "X-Key"), ("keyval", "kv"),
+            ("idp", "https://idp.test"), ("secret", "s3"),
         ]);
         let out = super::substitute_auth(&auth, &v);
         assert_eq!(out.auth_type, AuthType::Bearer);
@@ -184,19 +301,62 @@ mod tests {
         assert_eq!(out.basic_password, "p");
         assert_eq!(out.api_key_name, "X-Key");
         assert_eq!(out.api_key_value, "kv");
+        assert_eq!(out.oauth2.token_url, "https://idp.test/token");
+        assert_eq!(out.oauth2.client_secret, "s3");
     }
 
     #[test]
     fn substitute_request_resolves_auth() {
         use crate::types::{AuthConfig, AuthType, BodyType, HttpMethod, RequestData};
         let req = RequestData {
-            method: HttpMethod::GET,
-            url: "https://api.test".into(),
-            headers: vec![],
             body: BodyType::None,
             auth: AuthConfig { auth_type: AuthType::Bearer, bearer_token: "{{token}}".into(), ..Default::default() },
+            ..RequestData::new(HttpMethod::GET, "https://api.test".into())
         };
         let out = super::substitute_request(&req, &vars(&[("token", "abc")]));
         assert_eq!(out.auth.bearer_token, "abc");
     }
+
+    #[test]
+    fn referenced_vars_in_order_deduplicated_and_trimmed() {
+        assert_eq!(
+            referenced_vars("{{ base_url }}/users/{{id}}?q={{base_url}}"),
+            vec!["base_url".to_string(), "id".to_string()]
+        );
+    }
+
+    #[test]
+    fn referenced_vars_ignores_unclosed_and_empty_tokens() {
+        assert!(referenced_vars("{{}} and {{ unclosed").is_empty());
+        assert!(referenced_vars("no vars").is_empty());
+    }
+
+    #[test]
+    fn upsert_var_updates_the_row_that_resolves_or_appends() {
+        let mut v = vec![
+            EnvVar { enabled: false, key: "host".into(), value: "old".into(), secret: false },
+            EnvVar { enabled: true, key: "host".into(), value: "a".into(), secret: false },
+            EnvVar { enabled: true, key: "host".into(), value: "b".into(), secret: false },
+        ];
+        upsert_var(&mut v, "host", "new");
+        assert_eq!((v[0].value.as_str(), v[1].value.as_str(), v[2].value.as_str()), ("old", "a", "new"));
+        assert_eq!(enabled_map(&v)["host"], "new");
+
+        let mut v = vec![EnvVar { enabled: false, key: "host".into(), value: "old".into(), secret: false }];
+        upsert_var(&mut v, "host", "new");
+        assert!(v[0].enabled, "an edited variable is re-enabled");
+        assert_eq!(enabled_map(&v)["host"], "new");
+
+        upsert_var(&mut v, "token", "abc");
+        assert_eq!(v.len(), 2);
+        assert_eq!((v[1].key.as_str(), v[1].value.as_str()), ("token", "abc"));
+    }
+
+    #[test]
+    fn assertions_resolve_their_target_and_expected_value() {
+        use crate::assertions::Check;
+        let env = vars(&[("field", "id"), ("user", "42")]);
+        let resolved = substitute_assertions(&[Assertion::new(Check::JsonPathEquals, "$.{{field}}", "{{user}}")], &env);
+        assert_eq!(resolved, [Assertion::new(Check::JsonPathEquals, "$.id", "42")]);
+    }
 }
@@ -0,0 +1,163 @@
+//! The exchange of a verbose request as `curl -v` shows it: the request
+//! line and headers as sent (`>`), the body, then the response's status
+//! line and headers (`<`). Built from what `request_builder` put on the
+//! wire, so auth and the inferred Content-Type are in, plus the headers
+//! the client adds on its own (`Host`, `Accept-Encoding`,
+//! `Content-Length`). Shown in the viewer's Console tab. Pure.
+
+use crate::types::{BodyType, FormDataValue, RequestData, ResponseData};
+use crate::workspace_bundle::is_secret_key;
+
+/// The transcript of `sent` and what came back. With `mask`, values of
+/// secret headers (Authorization, Cookie, ...; see `is_secret_key`) are
+/// replaced by `request_log::REDACTED`. A network error ends it with
+/// `* ` and the message.
+pub fn transcript(sent: &RequestData, response: &ResponseData, mask: bool) -> String {
+    let version = response.version.as_deref().unwrap_or("HTTP/1.1");
+    let value = |name: &str, value: &str| -> String {
+        if mask && is_secret_key(name) {
+            crate::request_log::REDACTED.to_string()
+        } else {
+            value.to_string()
+        }
+    };
+
+    let (target, host) = match url::Url::parse(&sent.url) {
+        Ok(parsed) => {
+            let host = parsed.host_str().map(|host| match parsed.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host.to_string(),
+            });
+            (parsed[url::Position::BeforePath..url::Position::AfterQuery].to_string(), host)
+        }
+        Err(_) => (sent.url.clone(), None),
+    };
+    let has = |name: &str| sent.headers.iter().any(|(k, _)| k.eq_ignore_ascii_case(name));
+
+    let mut out = format!("> {} {} {}\n", sent.method.as_str(), target, version);
+    if let Some(host) = host.filter(|_| !has("host")) {
+        out.push_str(&format!("> Host: {host}\n"));
+    }
+    for (name, v) in sent.headers.iter().filter(|(k, _)| !k.trim().is_empty()) {
+        out.push_str(&format!("> {name}: {}\n", value(name, v)));
+    }
+    if !sent.minimal_headers && !has("accept-encoding") {
+        out.push_str(&format!("> Accept-Encoding: {}\n", crate::content_encoding::ACCEPT_ENCODING));
+    }
+    if let BodyType::Raw { content, .. } = &sent.body
+        && !sent.chunked
+        && !content.is_empty()
+    {
+        out.push_str(&format!("> Content-Length: {}\n", content.len()));
+    }
+    out.push_str(">\n");
+    match &sent.body {
+        BodyType::Raw { content, .. } if !content.is_empty() => {
+            out.push_str(content);
+            if !content.ends_with('\n') {
+                out.push('\n');
+            }
+        }
+        BodyType::FormData(rows) => {
+            for row in rows.iter().filter(|row| row.enabled && !row.key.trim().is_empty()) {
+                match &row.value {
+                    FormDataValue::Text(text) => out.push_str(&format!("{}={}\n", row.key, text)),
+                    FormDataValue::File { path } => out.push_str(&format!("{}=@{}\n", row.key, path)),
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let Some(status) = response.status else {
+        out.push_str(&format!("* {}\n", response.body_text()));
+        return out;
+    };
+    out.push_str(&format!("< {} {} {}\n", version, status, response.status_text()));
+    for (name, v) in &response.headers {
+        out.push_str(&format!("< {name}: {}\n", value(name, v)));
+    }
+    out.push_str("<\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{HttpMethod, RawSubtype};
+
+    fn h(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn sent() -> RequestData {
+        let mut request = RequestData::new(HttpMethod::POST, "https://api.test:8443/v1/users?page=2".to_string());
+        request.headers = h(&[("Content-Type", "application/json"), ("Authorization", "Bearer s3cr3t")]);
+        request.body = BodyType::Raw { content: "{\"name\":\"ada\"}".to_string(), subtype: RawSubtype::Json };
+        request
+    }
+
+    fn response(status: Option<u16>, headers: &[(&str, &str)], body: &[u8]) -> ResponseData {
+        ResponseData {
+            status,
+            duration_ms: 12,
+            headers: h(headers),
+            body: body.to_vec(),
+            is_text: true,
+            route: Default::default(),
+            raw_header_values: vec![],
+            network_profile: Default::default(),
+            timing: None,
+            redirects: vec![],
+            tls_unverified: false,
+            attempts: 1,
+            truncated_after: None,
+            downloaded: None,
+            wire_size: None,
+            version: status.map(|_| "HTTP/2".to_string()),
+            resolved_by: None,
+        }
+    }
+
+    fn created() -> ResponseData {
+        response(Some(201), &[("Content-Type", "application/json"), ("Set-Cookie", "session=abc")], b"{\"id\":7}")
+    }
+
+    #[test]
+    fn reads_like_curl_verbose() {
+        assert_eq!(
+            transcript(&sent(), &created(), false),
+            "> POST /v1/users?page=2 HTTP/2\n\
+             > Host: api.test:8443\n\
+             > Content-Type: application/json\n\
+             > Authorization: Bearer s3cr3t\n\
+             > Accept-Encoding: gzip, deflate, br\n\
+             > Content-Length: 14\n\
+             >\n\
+             {\"name\":\"ada\"}\n\
+             < HTTP/2 201 Created\n\
+             < Content-Type: application/json\n\
+             < Set-Cookie: session=abc\n\
+             <\n"
+        );
+    }
+
+    #[test]
+    fn masking_hides_secret_header_values_both_ways() {
+        let text = transcript(&sent(), &created(), true);
+        assert!(!text.contains("s3cr3t") && !text.contains("session=abc"), "{text}");
+        assert!(text.contains("> Authorization: [redacted]"), "{text}");
+        assert!(text.contains("< Set-Cookie: [redacted]"), "{text}");
+        assert!(text.contains("> Content-Type: application/json"), "{text}");
+    }
+
+    #[test]
+    fn minimal_chunked_and_failed_sends_leave_out_what_was_not_sent() {
+        let request = RequestData { minimal_headers: true, chunked: true, ..sent() };
+        let failed = response(None, &[], b"connection refused");
+        let text = transcript(&request, &failed, false);
+        assert!(!text.contains("Accept-Encoding") && !text.contains("Content-Length"), "{text}");
+        assert!(text.starts_with("> POST /v1/users?page=2 HTTP/1.1\n"), "{text}");
+        assert!(text.ends_with("* connection refused\n"), "{text}");
+    }
+}
@@ -0,0 +1,760 @@
+//! "Export / Import Workspace": the single-file JSON bundle used to hand a
+//! workspace to a teammate, and the pure planning of how an imported bundle
+//! lands on the existing data. Applying a plan is
+//! `Database::apply_workspace_import` (one transaction).
+//!
+//! Bundle format (version 1):
+//!
+//! ```json
+//! {
+//!   "format": "poopman-workspace",
+//!   "version": 1,
+//!   "exported_at": "2026-01-01T00:00:00+00:00",
+//!   "environments": [
+//!     { "name": "dev", "variables": [
+//!       { "key": "base_url", "value": "https://dev.example.com", "enabled": true },
+//!       { "key": "token", "value": "", "enabled": true, "masked": true, "secret": true }
+//!     ] }
+//!   ],
+//!   "globals": [{ "key": "tenant", "value": "acme", "enabled": true }],
+//!   "presets": [
+//!     { "id": 3, "name": "Tenant", "headers": [["X-Tenant", "{{tenant}}"]], "params": [] }
+//!   ],
+//!   "collections": [
+//!     { "name": "Users", "requests": [{ "name": "List", "request": { "method": "GET", ... } }],
+//!       "folders": [{ "name": "Admin", "requests": [], "folders": [] }] }
+//!   ],
+//!   "settings": {
+//!     "active_environment": "dev",
+//!     "default_headers": ["Accept", "UserAgent"],
+//!     "default_user_agent": "acme-monitor/2",
+//!     "body_limit": 10485760
+//!   }
+//! }
+//! ```
+//!
+//! Environments, presets and top-level collections are matched by name, never by id — ids are local to a database. The preset
+//! ids saved requests refer to are remapped on import.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::postman_import::{CollectionFolder, CollectionRequest};
+use crate::types::{AuthConfig, Collection, EnvVar, Environment, PredefinedHeader, Preset, RequestData, SavedRequest};
+
+/// Marker so an arbitrary JSON file is not mistaken for a bundle.
+pub const BUNDLE_FORMAT: &str = "poopman-workspace";
+
+/// Bumped on incompatible changes; newer bundles are refused on import.
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceBundle {
+    pub format: String,
+    pub version: u32,
+    /// RFC 3339 timestamp, informational only.
+    pub exported_at: String,
+    pub environments: Vec<BundleEnvironment>,
+    pub globals: Vec<BundleVar>,
+    pub presets: Vec<BundlePreset>,
+    /// Top-level collections with their folders and saved requests.
+    pub collections: Vec<CollectionFolder>,
+    #[serde(default)]
+    pub settings: BundleSettings,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleEnvironment {
+    pub name: String,
+    pub variables: Vec<BundleVar>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleVar {
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+    /// The value was withheld at export (`value` is empty). On a merge the
+    /// receiving side keeps its own value for this key.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub masked: bool,
+    /// The variable was flagged secret in the exporting workspace.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub secret: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundlePreset {
+    /// The preset's id in the exporting workspace, which requests in the
+    /// bundle refer to it by. Not kept on import.
+    pub id: i64,
+    pub name: String,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub params: Vec<(String, String)>,
+    /// Headers whose value was withheld at export, as `BundleVar::masked`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub masked_headers: Vec<String>,
+    /// Params whose value was withheld at export.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub masked_params: Vec<String>,
+}
+
+/// The portable subset of settings. Machine-local state (window layout,
+/// history, proxy, CA certificate) is deliberately not exported. A setting
+/// the exporting side never changed is `None` and left alone on import.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BundleSettings {
+    /// Name of the environment that was active when exporting.
+    #[serde(default)]
+    pub active_environment: Option<String>,
+    /// The predefined headers new tabs start with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_headers: Option<Vec<PredefinedHeader>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_user_agent: Option<String>,
+    /// Response body size limit in bytes (0 = none).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_limit: Option<u64>,
+}
+
+/// The workspace as the database holds it: what an export reads and an
+/// import is planned against (`Database::load_workspace`).
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    pub environments: Vec<Environment>,
+    pub active_environment_id: Option<i64>,
+    pub globals: Vec<EnvVar>,
+    pub presets: Vec<Preset>,
+    /// Every collection and folder, and every saved request.
+    pub collections: Vec<Collection>,
+    pub saved_requests: Vec<SavedRequest>,
+    pub default_headers: Option<Vec<PredefinedHeader>>,
+    pub default_user_agent: Option<String>,
+    pub body_limit: Option<u64>,
+}
+
+/// What to do with variables that look like credentials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretPolicy {
+    /// Export the value as-is.
+    Include,
+    /// Keep the key, blank the value (marked `masked`).
+    Mask,
+    /// Leave the variable out entirely.
+    Exclude,
+}
+
+/// Heuristic for "this variable holds a credential", by key name, for
+/// variables not explicitly flagged secret. Also decides which header values
+/// `request_log` redacts.
+pub fn is_secret_key(key: &str) -> bool {
+    const MARKERS: [&str; 10] = [
+        "secret", "token", "password", "passwd", "apikey", "api_key", "api-key", "credential",
+        "authorization", "cookie",
+    ];
+    let key = key.to_ascii_lowercase();
+    MARKERS.iter().any(|m| key.contains(m)) || key.contains("private") || key == "auth" || key.ends_with("_auth")
+}
+
+/// `request` as a bundle carries it: unless `policy` includes secrets, its
+/// credential-looking headers and auth secrets are blanked (or, the headers,
+/// left out). A value that only refers to a `{{var}}` is kept; the secret
+/// is in the variable.
+fn export_request(request: &RequestData, policy: SecretPolicy) -> RequestData {
+    if policy == SecretPolicy::Include {
+        return request.clone();
+    }
+    let withheld = |value: &str| if value.trim_start().starts_with("{{") { value.to_string() } else { String::new() };
+    let headers = request
+        .headers
+        .iter()
+        .filter(|(key, _)| policy != SecretPolicy::Exclude || !is_secret_key(key))
+        .map(|(key, value)| if is_secret_key(key) { (key.clone(), withheld(value)) } else { (key.clone(), value.clone()) })
+        .collect();
+    let auth = &request.auth;
+    let auth = AuthConfig {
+        bearer_token: withheld(&auth.bearer_token),
+        basic_password: withheld(&auth.basic_password),
+        api_key_value: withheld(&auth.api_key_value),
+        oauth2: crate::types::OAuth2Config { client_secret: withheld(&auth.oauth2.client_secret), ..auth.oauth2.clone() },
+        ..auth.clone()
+    };
+    RequestData { headers, auth, ..request.clone() }
+}
+
+fn export_folder(folder: CollectionFolder, policy: SecretPolicy) -> CollectionFolder {
+    CollectionFolder {
+        folders: folder.folders.into_iter().map(|sub| export_folder(sub, policy)).collect(),
+        requests: folder
+            .requests
+            .into_iter()
+            .map(|saved| CollectionRequest { request: export_request(&saved.request, policy), ..saved })
+            .collect(),
+        ..folder
+    }
+}
+
+/// Build a bundle from the workspace. `policy` applies to environment and
+/// global variables, preset headers and params, and saved requests' headers
+/// and auth alike.
+pub fn build_bundle(workspace: &Workspace, policy: SecretPolicy, exported_at: String) -> WorkspaceBundle {
+    let export_var = |v: &EnvVar| {
+        let secret = v.secret || is_secret_key(&v.key);
+        match policy {
+            SecretPolicy::Exclude if secret => None,
+            SecretPolicy::Mask if secret => Some(BundleVar {
+                key: v.key.clone(),
+                value: String::new(),
+                enabled: v.enabled,
+                masked: true,
+                secret: v.secret,
+            }),
+            _ => Some(BundleVar {
+                key: v.key.clone(),
+                value: v.value.clone(),
+                enabled: v.enabled,
+                masked: false,
+                secret: v.secret,
+            }),
+        }
+    };
+    // A preset's pairs, and the keys of those whose value was withheld.
+    let export_pairs = |pairs: &[(String, String)]| {
+        let mut masked = Vec::new();
+        let pairs = pairs
+            .iter()
+            .filter_map(|(key, value)| match policy {
+                SecretPolicy::Exclude if is_secret_key(key) => None,
+                SecretPolicy::Mask if is_secret_key(key) => {
+                    masked.push(key.clone());
+                    Some((key.clone(), String::new()))
+                }
+                _ => Some((key.clone(), value.clone())),
+            })
+            .collect();
+        (pairs, masked)
+    };
+
+    WorkspaceBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at,
+        environments: workspace
+            .environments
+            .iter()
+            .map(|env| BundleEnvironment {
+                name: env.name.clone(),
+                variables: env.variables.iter().filter_map(export_var).collect(),
+            })
+            .collect(),
+        globals: workspace.globals.iter().filter_map(export_var).collect(),
+        presets: workspace
+            .presets
+            .iter()
+            .map(|preset| {
+                let (headers, masked_headers) = export_pairs(&preset.headers);
+                let (params, masked_params) = export_pairs(&preset.params);
+                BundlePreset { id: preset.id, name: preset.name.clone(), headers, params, masked_headers, masked_params }
+            })
+            .collect(),
+        collections: workspace
+            .collections
+            .iter()
+            .filter(|c| c.parent_id.is_none())
+            .map(|c| {
+                let tree = CollectionFolder::from_saved(c, &workspace.collections, &workspace.saved_requests);
+                export_folder(tree, policy)
+            })
+            .collect(),
+        settings: BundleSettings {
+            active_environment: workspace
+                .environments
+                .iter()
+                .find(|env| Some(env.id) == workspace.active_environment_id)
+                .map(|env| env.name.clone()),
+            default_headers: workspace.default_headers.clone(),
+            default_user_agent: workspace.default_user_agent.clone(),
+            body_limit: workspace.body_limit,
+        },
+    }
+}
+
+/// Parse and validate a bundle file's contents.
+pub fn parse_bundle(json: &str) -> Result<WorkspaceBundle> {
+    let bundle: WorkspaceBundle = serde_json::from_str(json)?;
+    if bundle.format != BUNDLE_FORMAT {
+        bail!("not a workspace bundle (format \"{}\")", bundle.format);
+    }
+    if bundle.version > BUNDLE_VERSION {
+        bail!(
+            "bundle version {} is newer than this app supports ({})",
+            bundle.version,
+            BUNDLE_VERSION
+        );
+    }
+    Ok(bundle)
+}
+
+/// How an import treats what is already there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Keep existing environments, globals and presets; same-named ones get
+    /// the bundle's variables (or headers and params) merged in (bundle wins
+    /// per key, masked values never overwrite). A same-named collection is
+    /// replaced by the bundle's; the others stay.
+    Merge,
+    /// Delete every existing environment, global, preset and collection and
+    /// recreate the bundle's.
+    Replace,
+}
+
+/// Names in a bundle against the local ones of the same kind.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NameDiff {
+    /// In the bundle, with no same-named local one.
+    pub added: Vec<String>,
+    /// In the bundle, sharing a name with a local one.
+    pub conflicts: Vec<String>,
+    /// Local, absent from the bundle (deleted by Replace only).
+    pub local_only: Vec<String>,
+}
+
+impl NameDiff {
+    fn of<'a>(incoming: impl Iterator<Item = &'a str> + Clone, local: impl Iterator<Item = &'a str> + Clone) -> Self {
+        let (conflicts, added): (Vec<String>, Vec<String>) =
+            incoming.clone().map(str::to_string).partition(|name| local.clone().any(|l| l == name));
+        NameDiff {
+            added,
+            conflicts,
+            local_only: local.filter(|name| !incoming.clone().any(|i| i == *name)).map(str::to_string).collect(),
+        }
+    }
+}
+
+/// What an import would do, shown before the user picks a mode.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportPreview {
+    pub environments: NameDiff,
+    pub presets: NameDiff,
+    /// Top-level collections.
+    pub collections: NameDiff,
+}
+
+pub fn preview_import(bundle: &WorkspaceBundle, existing: &Workspace) -> ImportPreview {
+    ImportPreview {
+        environments: NameDiff::of(
+            bundle.environments.iter().map(|e| e.name.as_str()),
+            existing.environments.iter().map(|e| e.name.as_str()),
+        ),
+        presets: NameDiff::of(
+            bundle.presets.iter().map(|p| p.name.as_str()),
+            existing.presets.iter().map(|p| p.name.as_str()),
+        ),
+        collections: NameDiff::of(
+            bundle.collections.iter().map(|c| c.name.as_str()),
+            existing.collections.iter().filter(|c| c.parent_id.is_none()).map(|c| c.name.as_str()),
+        ),
+    }
+}
+
+/// One environment to write: update `existing_id`'s variables, or create it.
+#[derive(Debug, Clone)]
+pub struct PlannedEnvironment {
+    pub existing_id: Option<i64>,
+    pub name: String,
+    pub variables: Vec<EnvVar>,
+}
+
+/// One preset to write: overwrite `existing_id`, or create it.
+#[derive(Debug, Clone)]
+pub struct PlannedPreset {
+    pub existing_id: Option<i64>,
+    /// `BundlePreset::id`, which the planned collections' requests refer to
+    /// it by until it has a local id.
+    pub bundle_id: i64,
+    pub name: String,
+    pub headers: Vec<(String, String)>,
+    pub params: Vec<(String, String)>,
+}
+
+/// Everything an import writes, applied in a single transaction.
+#[derive(Debug, Clone, Default)]
+pub struct ImportPlan {
+    pub delete_ids: Vec<i64>,
+    pub environments: Vec<PlannedEnvironment>,
+    /// The globals afterwards.
+    pub globals: Vec<EnvVar>,
+    pub delete_preset_ids: Vec<i64>,
+    pub presets: Vec<PlannedPreset>,
+    /// Top-level collections deleted, with everything under them.
+    pub delete_collection_ids: Vec<i64>,
+    /// Collection trees to create; their requests' presets are bundle ids.
+    pub collections: Vec<CollectionFolder>,
+    /// Settings to write, the active environment by name; `None` fields are
+    /// left as they are.
+    pub settings: BundleSettings,
+}
+
+fn to_var(v: &BundleVar) -> EnvVar {
+    EnvVar {
+        enabled: v.enabled,
+        key: v.key.clone(),
+        value: v.value.clone(),
+        secret: v.secret,
+    }
+}
+
+/// `local`'s variables with `incoming` merged in: the bundle wins per key,
+/// except where its value was masked; new keys are appended.
+fn merge_vars(local: &[EnvVar], incoming: &[BundleVar]) -> Vec<EnvVar> {
+    let mut variables = local.to_vec();
+    for v in incoming {
+        match variables.iter_mut().find(|l| l.key == v.key) {
+            Some(_) if v.masked => {}
+            Some(slot) => *slot = to_var(v),
+            None => variables.push(to_var(v)),
+        }
+    }
+    variables
+}
+
+/// The bundle's pairs, a masked value taken from `local`'s same-keyed pair
+/// where there is one.
+fn unmask_pairs(pairs: &[(String, String)], masked: &[String], local: &[(String, String)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(key, value)| match local.iter().find(|(k, _)| k == key) {
+            Some((_, own)) if masked.contains(key) => (key.clone(), own.clone()),
+            _ => (key.clone(), value.clone()),
+        })
+        .collect()
+}
+
+pub fn plan_import(bundle: &WorkspaceBundle, existing: &Workspace, mode: ImportMode) -> ImportPlan {
+    let environments = bundle
+        .environments
+        .iter()
+        .map(|incoming| {
+            let local = existing.environments.iter().find(|e| e.name == incoming.name);
+            match (mode, local) {
+                (ImportMode::Merge, Some(local)) => PlannedEnvironment {
+                    existing_id: Some(local.id),
+                    name: local.name.clone(),
+                    variables: merge_vars(&local.variables, &incoming.variables),
+                },
+                _ => PlannedEnvironment {
+                    existing_id: None,
+                    name: incoming.name.clone(),
+                    variables: incoming.variables.iter().map(to_var).collect(),
+                },
+            }
+        })
+        .collect();
+
+    let globals = match mode {
+        ImportMode::Merge => merge_vars(&existing.globals, &bundle.globals),
+        ImportMode::Replace => bundle.globals.iter().map(to_var).collect(),
+    };
+
+    let presets = bundle
+        .presets
+        .iter()
+        .map(|incoming| {
+            let local = existing.presets.iter().find(|p| p.name == incoming.name);
+            match (mode, local) {
+                (ImportMode::Merge, Some(local)) => PlannedPreset {
+                    existing_id: Some(local.id),
+                    bundle_id: incoming.id,
+                    name: local.name.clone(),
+                    headers: unmask_pairs(&incoming.headers, &incoming.masked_headers, &local.headers),
+                    params: unmask_pairs(&incoming.params, &incoming.masked_params, &local.params),
+                },
+                _ => PlannedPreset {
+                    existing_id: None,
+                    bundle_id: incoming.id,
+                    name: incoming.name.clone(),
+                    headers: incoming.headers.clone(),
+                    params: incoming.params.clone(),
+                },
+            }
+        })
+        .collect();
+
+    let replaced = |ids: Vec<i64>| match mode {
+        ImportMode::Merge => Vec::new(),
+        ImportMode::Replace => ids,
+    };
+    ImportPlan {
+        delete_ids: replaced(existing.environments.iter().map(|e| e.id).collect()),
+        environments,
+        globals,
+        delete_preset_ids: replaced(existing.presets.iter().map(|p| p.id).collect()),
+        presets,
+        delete_collection_ids: existing
+            .collections
+            .iter()
+            .filter(|c| c.parent_id.is_none())
+            .filter(|c| mode == ImportMode::Replace || bundle.collections.iter().any(|i| i.name == c.name))
+            .map(|c| c.id)
+            .collect(),
+        collections: bundle.collections.clone(),
+        settings: bundle.settings.clone(),
+    }
+}
+
+/// `folder` with its requests' preset ids (bundle ids) swapped for the
+/// local ids `ids` maps them to, once the planned presets are written. A
+/// preset the bundle did not carry is dropped.
+pub fn with_local_presets(folder: &CollectionFolder, ids: &HashMap<i64, i64>) -> CollectionFolder {
+    CollectionFolder {
+        name: folder.name.clone(),
+        folders: folder.folders.iter().map(|sub| with_local_presets(sub, ids)).collect(),
+        requests: folder
+            .requests
+            .iter()
+            .map(|saved| {
+                let presets = saved.request.presets.iter().filter_map(|id| ids.get(id).copied()).collect();
+                CollectionRequest {
+                    name: saved.name.clone(),
+                    request: RequestData { presets, ..saved.request.clone() },
+                }
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(key: &str, value: &str) -> EnvVar {
+        EnvVar {
+            enabled: true,
+            key: key.to_string(),
+            value: value.to_string(),
+            secret: false,
+        }
+    }
+
+    fn env(id: i64, name: &str, variables: Vec<EnvVar>) -> Environment {
+        Environment {
+            id,
+            name: name.to_string(),
+            variables,
+        }
+    }
+
+    fn workspace(environments: &[Environment], active_environment_id: Option<i64>) -> Workspace {
+        Workspace { environments: environments.to_vec(), active_environment_id, ..Default::default() }
+    }
+
+    fn sample() -> Vec<Environment> {
+        vec![
+            env(1, "dev", vec![var("base_url", "https://dev"), var("api_token", "t0k")]),
+            env(2, "prod", vec![var("base_url", "https://prod")]),
+        ]
+    }
+
+    #[test]
+    fn secret_keys_are_recognised_by_name() {
+        for key in [
+            "token", "API_KEY", "client_secret", "db_password", "privateKey", "basic_auth", "Authorization", "Set-Cookie",
+        ] {
+            assert!(is_secret_key(key), "{key}");
+        }
+        for key in ["base_url", "user_id", "author", "page_size"] {
+            assert!(!is_secret_key(key), "{key}");
+        }
+    }
+
+    #[test]
+    fn secret_policy_masks_excludes_or_keeps_values() {
+        let keys = |b: &WorkspaceBundle| -> Vec<(String, String, bool)> {
+            b.environments[0]
+                .variables
+                .iter()
+                .map(|v| (v.key.clone(), v.value.clone(), v.masked))
+                .collect()
+        };
+        let masked = build_bundle(&workspace(&sample(), Some(2)), SecretPolicy::Mask, "t".into());
+        assert_eq!(
+            keys(&masked),
+            vec![("base_url".into(), "https://dev".into(), false), ("api_token".into(), String::new(), true)]
+        );
+        assert_eq!(masked.settings.active_environment.as_deref(), Some("prod"));
+
+        let excluded = build_bundle(&workspace(&sample(), None), SecretPolicy::Exclude, "t".into());
+        assert_eq!(keys(&excluded).len(), 1);
+        assert_eq!(excluded.settings.active_environment, None);
+
+        let included = build_bundle(&workspace(&sample(), None), SecretPolicy::Include, "t".into());
+        assert_eq!(keys(&included)[1], ("api_token".into(), "t0k".into(), false));
+    }
+
+    #[test]
+    fn flagged_secrets_are_masked_whatever_their_name_and_stay_flagged() {
+        let envs = vec![env(1, "dev", vec![EnvVar { secret: true, ..var("pin", "1234") }])];
+        let bundle = build_bundle(&workspace(&envs, None), SecretPolicy::Mask, "t".into());
+        let exported = &bundle.environments[0].variables[0];
+        assert!(exported.masked && exported.secret && exported.value.is_empty());
+
+        let included = build_bundle(&workspace(&envs, None), SecretPolicy::Include, "t".into());
+        let plan = plan_import(&included, &Workspace::default(), ImportMode::Replace);
+        let imported = &plan.environments[0].variables[0];
+        assert!(imported.secret);
+        assert_eq!(imported.value, "1234");
+    }
+
+    #[test]
+    fn bundle_round_trips_through_json() {
+        let bundle = build_bundle(&workspace(&sample(), Some(1)), SecretPolicy::Mask, "2026-01-01T00:00:00+00:00".into());
+        let json = serde_json::to_string_pretty(&bundle).unwrap();
+        assert_eq!(parse_bundle(&json).unwrap(), bundle);
+        // Unmasked variables don't carry the flag at all.
+        assert_eq!(json.matches("\"masked\"").count(), 1);
+    }
+
+    #[test]
+    fn parse_rejects_foreign_and_newer_files() {
+        assert!(parse_bundle("{\"name\": 1}").is_err());
+        let mut bundle = build_bundle(&workspace(&[], None), SecretPolicy::Include, "t".into());
+        bundle.format = "something-else".into();
+        assert!(parse_bundle(&serde_json::to_string(&bundle).unwrap()).is_err());
+        bundle.format = BUNDLE_FORMAT.into();
+        bundle.version = BUNDLE_VERSION + 1;
+        assert!(parse_bundle(&serde_json::to_string(&bundle).unwrap()).is_err());
+    }
+
+    #[test]
+    fn preview_lists_new_conflicting_and_local_only_environments() {
+        let mut bundle = build_bundle(&workspace(&sample()[..1], None), SecretPolicy::Include, "t".into());
+        bundle.environments.push(BundleEnvironment {
+            name: "staging".into(),
+            variables: vec![],
+        });
+        let preview = preview_import(&bundle, &workspace(&sample(), None));
+        assert_eq!(preview.environments.added, vec!["staging"]);
+        assert_eq!(preview.environments.conflicts, vec!["dev"]);
+        assert_eq!(preview.environments.local_only, vec!["prod"]);
+    }
+
+    #[test]
+    fn merge_updates_by_key_and_never_applies_masked_values() {
+        let bundle = WorkspaceBundle {
+            format: BUNDLE_FORMAT.into(),
+            version: BUNDLE_VERSION,
+            exported_at: "t".into(),
+            environments: vec![BundleEnvironment {
+                name: "dev".into(),
+                variables: vec![
+                    BundleVar { key: "base_url".into(), value: "https://new".into(), enabled: true, masked: false, secret: false },
+                    BundleVar { key: "api_token".into(), value: String::new(), enabled: true, masked: true, secret: false },
+                    BundleVar { key: "extra".into(), value: "1".into(), enabled: false, masked: false, secret: false },
+                ],
+            }],
+            globals: vec![],
+            presets: vec![],
+            collections: vec![],
+            settings: BundleSettings::default(),
+        };
+
+        let plan = plan_import(&bundle, &workspace(&sample(), None), ImportMode::Merge);
+        assert!(plan.delete_ids.is_empty());
+        let dev = &plan.environments[0];
+        assert_eq!(dev.existing_id, Some(1));
+        let values: Vec<(&str, &str)> = dev.variables.iter().map(|v| (v.key.as_str(), v.value.as_str())).collect();
+        assert_eq!(values, vec![("base_url", "https://new"), ("api_token", "t0k"), ("extra", "1")]);
+
+        let plan = plan_import(&bundle, &workspace(&sample(), None), ImportMode::Replace);
+        assert_eq!(plan.delete_ids, vec![1, 2]);
+        assert_eq!(plan.environments[0].existing_id, None);
+        assert_eq!(plan.environments[0].variables[1].value, "");
+    }
+
+    #[test]
+    fn presets_and_globals_merge_by_name_and_key_and_keep_masked_values() {
+        let pair = |k: &str, v: &str| (k.to_string(), v.to_string());
+        let local = Workspace {
+            globals: vec![var("tenant", "local"), var("region", "eu")],
+            presets: vec![Preset {
+                id: 5,
+                name: "Auth".into(),
+                headers: vec![pair("Authorization", "Bearer mine"), pair("X-Old", "1")],
+                params: vec![],
+            }],
+            ..Default::default()
+        };
+        let exporter = Workspace {
+            globals: vec![var("tenant", "acme"), var("api_token", "t0k")],
+            presets: vec![Preset {
+                id: 1,
+                name: "Auth".into(),
+                headers: vec![pair("Authorization", "Bearer theirs"), pair("X-New", "2")],
+                params: vec![pair("api_key", "k")],
+            }],
+            ..Default::default()
+        };
+        let bundle = build_bundle(&exporter, SecretPolicy::Mask, "t".into());
+        let exported = &bundle.presets[0];
+        assert_eq!(exported.headers, vec![pair("Authorization", ""), pair("X-New", "2")]);
+        assert_eq!(exported.masked_headers, vec!["Authorization"]);
+        assert_eq!(exported.masked_params, vec!["api_key"]);
+
+        let plan = plan_import(&bundle, &local, ImportMode::Merge);
+        let globals: Vec<(&str, &str)> = plan.globals.iter().map(|v| (v.key.as_str(), v.value.as_str())).collect();
+        assert_eq!(globals, vec![("tenant", "acme"), ("region", "eu"), ("api_token", "")]);
+        assert!(plan.delete_preset_ids.is_empty());
+        assert_eq!(plan.presets[0].existing_id, Some(5));
+        assert_eq!(plan.presets[0].headers, vec![pair("Authorization", "Bearer mine"), pair("X-New", "2")]);
+        // A masked value with nothing local to keep stays blank.
+        assert_eq!(plan.presets[0].params, vec![pair("api_key", "")]);
+
+        let plan = plan_import(&bundle, &local, ImportMode::Replace);
+        assert_eq!(plan.globals.len(), 2);
+        assert_eq!(plan.delete_preset_ids, vec![5]);
+        assert_eq!(plan.presets[0].existing_id, None);
+    }
+
+    #[test]
+    fn collections_carry_masked_requests_and_replace_by_top_level_name() {
+        let collection = |id: i64, parent_id: Option<i64>, name: &str| Collection { id, parent_id, name: name.into() };
+        let mut request = RequestData::new(crate::types::HttpMethod::GET, "{{base_url}}/users".into());
+        request.headers = vec![("Authorization".into(), "Bearer abc".into()), ("Accept".into(), "*/*".into())];
+        request.auth.bearer_token = "{{token}}".into();
+        request.auth.basic_password = "hunter2".into();
+        request.presets = vec![1, 9];
+        let exporter = Workspace {
+            collections: vec![collection(1, None, "Users"), collection(2, Some(1), "Admin")],
+            saved_requests: vec![SavedRequest { id: 1, collection_id: 2, name: "List".into(), request }],
+            ..Default::default()
+        };
+
+        let bundle = build_bundle(&exporter, SecretPolicy::Mask, "t".into());
+        let folders = &bundle.collections;
+        assert_eq!(folders.len(), 1);
+        assert_eq!(folders[0].folders[0].name, "Admin");
+        let exported = &folders[0].folders[0].requests[0].request;
+        assert_eq!(exported.headers[0], ("Authorization".to_string(), String::new()));
+        assert_eq!(exported.auth.bearer_token, "{{token}}");
+        assert_eq!(exported.auth.basic_password, "");
+        let excluded = build_bundle(&exporter, SecretPolicy::Exclude, "t".into());
+        assert_eq!(excluded.collections[0].folders[0].requests[0].request.headers.len(), 1);
+
+        let local = Workspace {
+            collections: vec![collection(4, None, "Users"), collection(5, Some(4), "Old"), collection(6, None, "Billing")],
+            ..Default::default()
+        };
+        assert_eq!(preview_import(&bundle, &local).collections.conflicts, vec!["Users"]);
+        assert_eq!(plan_import(&bundle, &local, ImportMode::Merge).delete_collection_ids, vec![4]);
+        assert_eq!(plan_import(&bundle, &local, ImportMode::Replace).delete_collection_ids, vec![4, 6]);
+
+        // Bundle preset 1 became local 7; 9 was not in the bundle.
+        let local_ids = HashMap::from([(1, 7)]);
+        let remapped = with_local_presets(&folders[0], &local_ids);
+        assert_eq!(remapped.folders[0].requests[0].request.presets, vec![7]);
+    }
+}
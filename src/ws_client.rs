@@ -0,0 +1,301 @@
+//! WebSocket connections for WebSocket tabs. A connection runs on the send
+//! runtime (`http_client::runtime`) and talks to the UI through channels:
+//! `WsConnection::send_text` and `ping` queue a frame, `events` yields what
+//! happened, every frame either way included, until the socket closes.
+//! Dropping the connection closes the socket.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Local};
+use futures::channel::mpsc;
+use futures::{SinkExt as _, StreamExt as _};
+use serde::{Deserialize, Serialize};
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Frames a tab keeps in its log; the oldest go first.
+pub const LOG_LIMIT: usize = 1000;
+
+/// Frames of the log kept with the tab across restarts.
+pub const SAVED_LOG_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FrameKind {
+    Text,
+    Binary,
+    Ping,
+    Pong,
+    Close,
+}
+
+/// One frame in a tab's log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WsFrame {
+    pub at: DateTime<Local>,
+    pub direction: Direction,
+    pub kind: FrameKind,
+    /// The text of a text frame, a hex dump of a binary one, the payload of
+    /// a ping or pong, and the code and reason of a close.
+    pub text: String,
+}
+
+impl WsFrame {
+    fn of(direction: Direction, message: &Message) -> Option<Self> {
+        let (kind, text) = match message {
+            Message::Text(text) => (FrameKind::Text, text.to_string()),
+            Message::Binary(bytes) => (FrameKind::Binary, binary_text(bytes)),
+            Message::Ping(bytes) => (FrameKind::Ping, String::from_utf8_lossy(bytes).into_owned()),
+            Message::Pong(bytes) => (FrameKind::Pong, String::from_utf8_lossy(bytes).into_owned()),
+            Message::Close(frame) => (FrameKind::Close, close_text(frame.as_ref())),
+            // Only seen when reading raw frames, which this never does.
+            Message::Frame(_) => return None,
+        };
+        Some(Self { at: Local::now(), direction, kind, text })
+    }
+}
+
+fn binary_text(bytes: &[u8]) -> String {
+    format!("[binary, {}]\n{}", crate::format::format_size(bytes.len()), crate::format::hex_dump(bytes, 256))
+}
+
+fn close_text(frame: Option<&CloseFrame>) -> String {
+    match frame {
+        Some(frame) if frame.reason.is_empty() => u16::from(frame.code).to_string(),
+        Some(frame) => format!("{} {}", u16::from(frame.code), frame.reason),
+        None => String::new(),
+    }
+}
+
+/// What a connection reports, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WsEvent {
+    /// The handshake succeeded.
+    Connected,
+    Frame(WsFrame),
+    /// The socket closed; nothing follows.
+    Closed,
+    /// Connecting failed or the connection broke; nothing follows.
+    Failed(String),
+}
+
+/// What a WebSocket tab keeps: saved with the tab and across restarts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WsTabState {
+    pub url: String,
+    /// The message being written.
+    #[serde(default)]
+    pub draft: String,
+    /// The draft is JSON: highlighted, and formatted on request.
+    #[serde(default)]
+    pub json: bool,
+    /// The end of the log, at most `SAVED_LOG_LIMIT` frames.
+    #[serde(default)]
+    pub log: Vec<WsFrame>,
+}
+
+impl WsTabState {
+    /// The tab's title: the URL without its scheme.
+    pub fn title(&self) -> String {
+        let url = self.url.trim();
+        if url.is_empty() {
+            return "New WebSocket".to_string();
+        }
+        url.split_once("://").map_or(url, |(_, rest)| rest).to_string()
+    }
+}
+
+/// Why `url` can't be connected to, or `None` when it can.
+pub fn url_problem(url: &str) -> Option<String> {
+    match url::Url::parse(url.trim()) {
+        Ok(parsed) if matches!(parsed.scheme(), "ws" | "wss") && parsed.has_host() => None,
+        Ok(parsed) if matches!(parsed.scheme(), "ws" | "wss") => Some("the URL has no host".to_string()),
+        Ok(parsed) => Some(format!("\"{}\" is not a WebSocket scheme; use ws:// or wss://", parsed.scheme())),
+        Err(e) => Some(format!("not a URL: {e}")),
+    }
+}
+
+/// What the UI asks of a connection.
+enum Command {
+    Send(Message),
+    Close,
+}
+
+/// A live (or connecting) WebSocket.
+pub struct WsConnection {
+    commands: mpsc::UnboundedSender<Command>,
+    /// Taken by the UI to read the connection's events.
+    pub events: Option<mpsc::UnboundedReceiver<WsEvent>>,
+}
+
+impl WsConnection {
+    /// Start connecting to `url` (checked with `url_problem` first). The
+    /// outcome arrives as the first event.
+    pub fn connect(url: &str) -> Self {
+        let (commands, command_rx) = mpsc::unbounded();
+        let (event_tx, events) = mpsc::unbounded();
+        let url = url.trim().to_string();
+        crate::http_client::runtime().spawn(run(url, command_rx, event_tx));
+        Self { commands, events: Some(events) }
+    }
+
+    /// Queue a text frame.
+    pub fn send_text(&self, text: String) {
+        let _ = self.commands.unbounded_send(Command::Send(Message::text(text)));
+    }
+
+    pub fn ping(&self) {
+        let _ = self.commands.unbounded_send(Command::Send(Message::Ping(Vec::new().into())));
+    }
+
+    /// Start the closing handshake; `Closed` follows once it is done.
+    pub fn close(&self) {
+        let _ = self.commands.unbounded_send(Command::Close);
+    }
+}
+
+/// rustls is built with both of its crypto providers (reqwest asks for one,
+/// this crate for the other), so none is picked by default: the config
+/// names aws-lc-rs, the one reqwest uses too.
+fn tls_connector() -> Result<tokio_tungstenite::Connector, String> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::aws_lc_rs::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(tokio_tungstenite::Connector::Rustls(Arc::new(config)))
+}
+
+async fn run(url: String, commands: mpsc::UnboundedReceiver<Command>, events: mpsc::UnboundedSender<WsEvent>) {
+    let report = |event: WsEvent| {
+        let _ = events.unbounded_send(event);
+    };
+    let connector = match url.starts_with("wss:").then(tls_connector).transpose() {
+        Ok(connector) => connector,
+        Err(e) => return report(WsEvent::Failed(format!("TLS setup failed: {e}"))),
+    };
+    let stream = match tokio_tungstenite::connect_async_tls_with_config(url.as_str(), None, false, connector).await {
+        Ok((stream, _response)) => stream,
+        Err(e) => return report(WsEvent::Failed(e.to_string())),
+    };
+    report(WsEvent::Connected);
+
+    enum Step {
+        Command(Command),
+        /// The UI dropped the connection.
+        Dropped,
+        Read(Result<Message, tokio_tungstenite::tungstenite::Error>),
+        /// The socket is closed.
+        Ended,
+    }
+    let (mut write, read) = stream.split();
+    let commands = commands.map(Step::Command).chain(futures::stream::once(futures::future::ready(Step::Dropped)));
+    let read = read.map(Step::Read).chain(futures::stream::once(futures::future::ready(Step::Ended)));
+    let mut steps = futures::stream::select(commands, read);
+    while let Some(step) = steps.next().await {
+        let outgoing = match step {
+            Step::Command(Command::Send(message)) => message,
+            Step::Command(Command::Close) => Message::Close(None),
+            Step::Dropped => {
+                let _ = write.close().await;
+                return;
+            }
+            Step::Read(Ok(message)) => {
+                if let Some(frame) = WsFrame::of(Direction::Received, &message) {
+                    report(WsEvent::Frame(frame));
+                }
+                continue;
+            }
+            Step::Read(Err(e)) => return report(closed_or_failed(e)),
+            Step::Ended => break,
+        };
+        let frame = WsFrame::of(Direction::Sent, &outgoing);
+        match write.send(outgoing).await {
+            Ok(()) => frame.into_iter().for_each(|frame| report(WsEvent::Frame(frame))),
+            Err(e) => return report(closed_or_failed(e)),
+        }
+    }
+    report(WsEvent::Closed);
+}
+
+fn closed_or_failed(e: tokio_tungstenite::tungstenite::Error) -> WsEvent {
+    use tokio_tungstenite::tungstenite::Error;
+    match e {
+        Error::ConnectionClosed | Error::AlreadyClosed => WsEvent::Closed,
+        e => WsEvent::Failed(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_ws_urls_with_a_host_can_be_connected_to() {
+        assert_eq!(url_problem("ws://localhost:8080/chat"), None);
+        assert_eq!(url_problem(" wss://echo.test "), None);
+        assert!(url_problem("https://echo.test").unwrap().contains("ws://"));
+        assert!(url_problem("echo.test").unwrap().starts_with("not a URL"));
+    }
+
+    #[test]
+    fn titles_drop_the_scheme() {
+        let state = WsTabState { url: "wss://echo.test/feed".to_string(), ..Default::default() };
+        assert_eq!(state.title(), "echo.test/feed");
+        assert_eq!(WsTabState::default().title(), "New WebSocket");
+    }
+
+    #[test]
+    fn control_frames_say_what_they_carry() {
+        let close = Message::Close(Some(CloseFrame { code: 1000.into(), reason: "bye".into() }));
+        let frame = WsFrame::of(Direction::Received, &close).unwrap();
+        assert_eq!((frame.kind, frame.text.as_str()), (FrameKind::Close, "1000 bye"));
+        let ping = WsFrame::of(Direction::Sent, &Message::Ping(b"hi".to_vec().into())).unwrap();
+        assert_eq!((ping.kind, ping.text.as_str()), (FrameKind::Ping, "hi"));
+        let binary = WsFrame::of(Direction::Received, &Message::Binary(vec![0, 1, 2].into())).unwrap();
+        assert!(binary.text.starts_with("[binary, 3 B]"), "{}", binary.text);
+    }
+
+    #[test]
+    fn echoes_round_trip_and_the_close_is_logged() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            loop {
+                match socket.read() {
+                    Ok(message @ Message::Text(_)) => socket.send(message).unwrap(),
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+        });
+
+        let mut connection = WsConnection::connect(&url);
+        let mut events = connection.events.take().unwrap();
+        let next = |events: &mut mpsc::UnboundedReceiver<WsEvent>| futures::executor::block_on(events.next()).unwrap();
+        assert_eq!(next(&mut events), WsEvent::Connected);
+        connection.send_text("hello".to_string());
+        let sent = next(&mut events);
+        let echoed = next(&mut events);
+        assert!(matches!(sent, WsEvent::Frame(WsFrame { direction: Direction::Sent, kind: FrameKind::Text, ref text, .. }) if text == "hello"));
+        assert!(matches!(echoed, WsEvent::Frame(WsFrame { direction: Direction::Received, ref text, .. }) if text == "hello"));
+
+        connection.close();
+        let mut rest = Vec::new();
+        while let Some(event) = futures::executor::block_on(events.next()) {
+            rest.push(event);
+        }
+        assert!(rest.iter().any(|e| matches!(e, WsEvent::Frame(f) if f.kind == FrameKind::Close)), "{rest:?}");
+        assert_eq!(rest.last(), Some(&WsEvent::Closed));
+        server.join().unwrap();
+    }
+}
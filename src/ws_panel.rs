@@ -0,0 +1,419 @@
+//! The body of a WebSocket tab: the URL with Connect / Disconnect, a
+//! composer for text or JSON messages, and the log of frames both ways.
+//! Shown by `PoopmanApp` in place of the request editor and response viewer
+//! while a WebSocket tab is active; one panel per tab, so a connection stays
+//! up while other tabs are in front. Errors show in a banner above the
+//! composer, not in the log file.
+
+use futures::StreamExt as _;
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{
+    button::*, input::*, tooltip::Tooltip, h_flex, v_flex, ActiveTheme as _, Disableable as _, Sizable as _,
+};
+
+use crate::a11y;
+use crate::ws_client::{self, Direction, FrameKind, WsConnection, WsEvent, WsFrame, WsTabState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Disconnected,
+    Connecting,
+    Connected,
+}
+
+pub struct WsPanel {
+    url_input: Entity<InputState>,
+    composer: Entity<InputState>,
+    json: bool,
+    log: Vec<WsFrame>,
+    status: Status,
+    /// The last connection error or refused send; cleared on the next try.
+    problem: Option<String>,
+    /// Dropping it closes the socket.
+    connection: Option<WsConnection>,
+    log_scroll_handle: ScrollHandle,
+    /// Feeds the connection's events into the panel.
+    _events: Option<Task<()>>,
+}
+
+impl WsPanel {
+    pub fn new(state: &WsTabState, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let url_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("ws://localhost:8080/socket")
+                .default_value(state.url.clone())
+        });
+        let composer = cx.new(|cx| {
+            InputState::new(window, cx)
+                .code_editor(if state.json { "json" } else { "text" })
+                .line_number(false)
+                .placeholder("Message")
+                .default_value(state.draft.clone())
+        });
+        Self {
+            url_input,
+            composer,
+            json: state.json,
+            log: state.log.clone(),
+            status: Status::Disconnected,
+            problem: None,
+            connection: None,
+            log_scroll_handle: ScrollHandle::new(),
+            _events: None,
+        }
+    }
+
+    /// What the tab keeps: the URL, the draft and the end of the log.
+    pub fn state(&self, cx: &App) -> WsTabState {
+        let skip = self.log.len().saturating_sub(ws_client::SAVED_LOG_LIMIT);
+        WsTabState {
+            url: self.url_input.read(cx).value().trim().to_string(),
+            draft: self.composer.read(cx).value().to_string(),
+            json: self.json,
+            log: self.log[skip..].to_vec(),
+        }
+    }
+
+    fn connect(&mut self, cx: &mut Context<Self>) {
+        let url = self.url_input.read(cx).value().trim().to_string();
+        if let Some(problem) = ws_client::url_problem(&url) {
+            self.problem = Some(problem);
+            cx.notify();
+            return;
+        }
+        let mut connection = WsConnection::connect(&url);
+        let mut events = connection.events.take().expect("events are taken once, here");
+        self._events = Some(cx.spawn(async move |this, cx| {
+            while let Some(event) = events.next().await {
+                if this.update(cx, |this, cx| this.on_event(event, cx)).is_err() {
+                    break;
+                }
+            }
+        }));
+        self.connection = Some(connection);
+        self.status = Status::Connecting;
+        self.problem = None;
+        cx.notify();
+    }
+
+    /// Close the socket; while still connecting, give up on it.
+    fn disconnect(&mut self, cx: &mut Context<Self>) {
+        match (&self.connection, self.status) {
+            (Some(connection), Status::Connected) => connection.close(),
+            _ => {
+                self.connection = None;
+                self._events = None;
+                self.status = Status::Disconnected;
+            }
+        }
+        cx.notify();
+    }
+
+    fn on_event(&mut self, event: WsEvent, cx: &mut Context<Self>) {
+        match event {
+            WsEvent::Connected => self.status = Status::Connected,
+            WsEvent::Frame(frame) => {
+                if self.log.len() == ws_client::LOG_LIMIT {
+                    self.log.remove(0);
+                }
+                self.log.push(frame);
+                self.log_scroll_handle.scroll_to_bottom();
+            }
+            WsEvent::Closed => {
+                self.connection = None;
+                self.status = Status::Disconnected;
+            }
+            WsEvent::Failed(error) => {
+                self.connection = None;
+                self.status = Status::Disconnected;
+                self.problem = Some(error);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Send the composer's text; in JSON mode only when it parses.
+    pub fn send_message(&mut self, cx: &mut Context<Self>) {
+        let text = self.composer.read(cx).value().to_string();
+        let Some(connection) = self.connection.as_ref().filter(|_| self.status == Status::Connected) else {
+            self.problem = Some("Not connected".to_string());
+            cx.notify();
+            return;
+        };
+        if self.json
+            && let Err(e) = serde_json::from_str::<serde_json::Value>(&text)
+        {
+            self.problem = Some(format!("Not valid JSON: {e}"));
+            cx.notify();
+            return;
+        }
+        connection.send_text(text);
+        self.problem = None;
+        cx.notify();
+    }
+
+    fn ping(&mut self, cx: &mut Context<Self>) {
+        if let Some(connection) = &self.connection {
+            connection.ping();
+        }
+        cx.notify();
+    }
+
+    fn set_json(&mut self, json: bool, cx: &mut Context<Self>) {
+        self.json = json;
+        self.composer.update(cx, |input, cx| input.set_highlighter(if json { "json" } else { "text" }, cx));
+        cx.notify();
+    }
+
+    fn format_draft(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.composer.read(cx).value().to_string();
+        match crate::code_formatter::format_json(&text) {
+            Ok(formatted) => {
+                self.composer.update(cx, |input, cx| input.set_value(formatted, window, cx));
+                self.problem = None;
+            }
+            Err(e) => self.problem = Some(e),
+        }
+        cx.notify();
+    }
+
+    fn clear_log(&mut self, cx: &mut Context<Self>) {
+        self.log.clear();
+        cx.notify();
+    }
+
+    fn render_connection_bar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let (color, label) = match self.status {
+            Status::Disconnected => (theme.muted_foreground, "Disconnected"),
+            Status::Connecting => (theme.warning, "Connecting…"),
+            Status::Connected => (theme.success, "Connected"),
+        };
+        h_flex()
+            .gap_2()
+            .items_center()
+            .child(div().flex_1().child(Input::new(&self.url_input).disabled(self.status != Status::Disconnected)))
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .items_center()
+                    .text_sm()
+                    .text_color(color)
+                    .child(div().size_2().rounded_full().bg(color))
+                    .child(label),
+            )
+            .child(match self.status {
+                Status::Disconnected => Button::new("ws-connect")
+                    .primary()
+                    .label("Connect")
+                    .on_click(cx.listener(|this, _, _window, cx| this.connect(cx))),
+                Status::Connecting | Status::Connected => Button::new("ws-disconnect")
+                    .outline()
+                    .label("Disconnect")
+                    .on_click(cx.listener(|this, _, _window, cx| this.disconnect(cx))),
+            })
+    }
+
+    fn render_composer(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let connected = self.status == Status::Connected;
+        let pill = |id: &'static str, label: &'static str, json: bool| {
+            let active = self.json == json;
+            a11y::focusable(crate::ui::segment_pill(theme, active).id(id), theme)
+                .text_xs()
+                .when(!active, |s| s.hover(|s| s.text_color(theme.foreground)))
+                .on_click(cx.listener(move |this, _, _window, cx| this.set_json(json, cx)))
+                .child(label)
+        };
+        v_flex()
+            .gap_2()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        crate::ui::segmented_bar(theme)
+                            .child(pill("ws-mode-text", "Text", false))
+                            .child(pill("ws-mode-json", "JSON", true)),
+                    )
+                    .when(self.json, |this| {
+                        this.child(
+                            Button::new("ws-format")
+                                .ghost()
+                                .xsmall()
+                                .label("Format")
+                                .on_click(cx.listener(|this, _, window, cx| this.format_draft(window, cx))),
+                        )
+                    })
+                    .child(div().flex_1())
+                    .child(
+                        Button::new("ws-ping")
+                            .outline()
+                            .small()
+                            .label("Ping")
+                            .disabled(!connected)
+                            .on_click(cx.listener(|this, _, _window, cx| this.ping(cx))),
+                    )
+                    .child(
+                        Button::new("ws-send")
+                            .primary()
+                            .small()
+                            .label("Send")
+                            .disabled(!connected)
+                            .on_click(cx.listener(|this, _, _window, cx| this.send_message(cx))),
+                    ),
+            )
+            .child(
+                div()
+                    .h(px(120.))
+                    .w_full()
+                    .rounded(theme.radius_lg)
+                    .border_1()
+                    .border_color(theme.border)
+                    .bg(theme.popover)
+                    .child(Input::new(&self.composer).rounded(theme.radius_lg).w_full().h_full()),
+            )
+    }
+
+    fn render_frame(&self, index: usize, frame: &WsFrame, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let (arrow, arrow_color, direction) = match frame.direction {
+            Direction::Sent => ("↑", theme.warning, "Sent"),
+            Direction::Received => ("↓", theme.success, "Received"),
+        };
+        let control = match frame.kind {
+            FrameKind::Text => None,
+            FrameKind::Binary => Some("BINARY"),
+            FrameKind::Ping => Some("PING"),
+            FrameKind::Pong => Some("PONG"),
+            FrameKind::Close => Some("CLOSE"),
+        };
+        let ping_pong = matches!(frame.kind, FrameKind::Ping | FrameKind::Pong);
+
+        h_flex()
+            .id(("ws-frame", index))
+            .gap_2()
+            .items_start()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(theme.border.opacity(0.5))
+            .text_sm()
+            .when(ping_pong, |this| this.bg(theme.muted.opacity(0.5)))
+            .when(frame.kind == FrameKind::Close, |this| this.bg(theme.warning.opacity(0.08)))
+            .child(
+                div()
+                    .flex_shrink_0()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .font_family(theme.mono_font_family.clone())
+                    .child(frame.at.format("%H:%M:%S%.3f").to_string()),
+            )
+            .child(
+                div()
+                    .id(("ws-frame-direction", index))
+                    .w(px(12.))
+                    .flex_shrink_0()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(arrow_color)
+                    .tooltip(move |window, cx| Tooltip::new(direction).build(window, cx))
+                    .child(arrow),
+            )
+            .when_some(control, |this, label| {
+                this.child(
+                    div()
+                        .flex_shrink_0()
+                        .px_1p5()
+                        .rounded(theme.radius)
+                        .text_xs()
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .bg(theme.muted)
+                        .text_color(theme.muted_foreground)
+                        .child(label),
+                )
+            })
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .font_family(theme.mono_font_family.clone())
+                    .whitespace_normal()
+                    .when(control.is_some(), |this| this.text_color(theme.muted_foreground))
+                    .child(frame.text.clone()),
+            )
+    }
+}
+
+impl Render for WsPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        v_flex()
+            .size_full()
+            .p_3()
+            .gap_3()
+            .child(self.render_connection_bar(cx))
+            .when_some(self.problem.clone(), |this, problem| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .px_3()
+                        .py_2()
+                        .rounded(theme.radius)
+                        .bg(theme.danger.opacity(0.1))
+                        .text_sm()
+                        .text_color(theme.danger)
+                        .child(div().flex_1().min_w_0().child(problem))
+                        .child(
+                            Button::new("ws-dismiss-problem")
+                                .ghost()
+                                .xsmall()
+                                .label("Dismiss")
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.problem = None;
+                                    cx.notify();
+                                })),
+                        ),
+                )
+            })
+            .child(self.render_composer(cx))
+            .child(
+                h_flex()
+                    .items_center()
+                    .text_sm()
+                    .child(
+                        div()
+                            .text_color(theme.muted_foreground)
+                            .child(format!("Messages ({})", self.log.len())),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        Button::new("ws-clear-log")
+                            .ghost()
+                            .xsmall()
+                            .label("Clear")
+                            .disabled(self.log.is_empty())
+                            .on_click(cx.listener(|this, _, _window, cx| this.clear_log(cx))),
+                    ),
+            )
+            .child(
+                div()
+                    .id("ws-log")
+                    .flex_1()
+                    .min_h_0()
+                    .w_full()
+                    .overflow_y_scroll()
+                    .track_scroll(&self.log_scroll_handle)
+                    .rounded(theme.radius_lg)
+                    .border_1()
+                    .border_color(theme.border)
+                    .children(
+                        self.log
+                            .iter()
+                            .enumerate()
+                            .map(|(index, frame)| self.render_frame(index, frame, cx).into_any_element()),
+                    ),
+            )
+    }
+}